@@ -0,0 +1,71 @@
+//! Layout benchmarks for deep and wide widget trees
+//!
+//! Run with `cargo bench --bench layout`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use glam::Vec2;
+use glassui::widgets::{Column, Label, Row, Widget};
+
+/// Column nested `depth` levels deep, each level holding a label
+fn deep_tree(depth: usize) -> Box<dyn Widget> {
+    let mut node: Box<dyn Widget> = Box::new(Label::new("leaf"));
+    for i in 0..depth {
+        node = Box::new(
+            Column::new()
+                .add_child(Box::new(Label::new(&format!("level {}", i))))
+                .add_child(node),
+        );
+    }
+    node
+}
+
+/// Column of `rows` rows, each with `columns` labels
+fn wide_tree(rows: usize, columns: usize) -> Box<dyn Widget> {
+    let mut column = Column::new();
+    for r in 0..rows {
+        let mut row = Row::new();
+        for c in 0..columns {
+            row = row.add_child(Box::new(Label::new(&format!("{}:{}", r, c))));
+        }
+        column = column.add_child(Box::new(row));
+    }
+    Box::new(column)
+}
+
+fn bench_layout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("layout");
+    
+    for depth in [16, 64, 256] {
+        let mut tree = deep_tree(depth);
+        let mut width = 800.0;
+        group.bench_with_input(BenchmarkId::new("deep", depth), &depth, |b, _| {
+            b.iter(|| {
+                // Alternate the width so the layout cache can't short-circuit
+                width = if width == 800.0 { 801.0 } else { 800.0 };
+                black_box(tree.layout(Vec2::ZERO, Vec2::new(width, 10_000.0)))
+            })
+        });
+    }
+    
+    for rows in [10, 100, 1000] {
+        let mut tree = wide_tree(rows, 10);
+        let mut width = 1920.0;
+        group.bench_with_input(BenchmarkId::new("wide", rows * 10), &rows, |b, _| {
+            b.iter(|| {
+                width = if width == 1920.0 { 1921.0 } else { 1920.0 };
+                black_box(tree.layout(Vec2::ZERO, Vec2::new(width, 100_000.0)))
+            })
+        });
+    }
+    
+    // Unchanged tree, same constraints: measures the layout cache fast path
+    let mut tree = wide_tree(1000, 10);
+    group.bench_function("wide_cached/10000", |b| {
+        b.iter(|| black_box(tree.layout(Vec2::ZERO, Vec2::new(1920.0, 100_000.0))))
+    });
+    
+    group.finish();
+}
+
+criterion_group!(benches, bench_layout);
+criterion_main!(benches);
@@ -0,0 +1,102 @@
+//! CPU-side render encoding benchmarks
+//!
+//! Covers the work done before anything reaches the GPU: building glass
+//! instances, measuring text and scrolling a large `DataTable`.
+//! Run with `cargo bench --bench render_encode`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use glam::{Vec2, Vec4};
+use glassui::renderer::GlassInstance;
+use glassui::text::{measure_text_with, TextRenderer};
+use glassui::widgets::{CellValue, DataTable, GridColumn, GridRow, Widget};
+use winit::event::{DeviceId, Event, MouseScrollDelta, TouchPhase, WindowEvent};
+use winit::window::WindowId;
+
+fn bench_instances(c: &mut Criterion) {
+    let mut instances: Vec<GlassInstance> = Vec::new();
+    c.bench_function("instances/10000_rects", |b| {
+        b.iter(|| {
+            instances.clear();
+            for i in 0..10_000 {
+                let x = (i % 100) as f32 * 12.0;
+                let y = (i / 100) as f32 * 12.0;
+                instances.push(GlassInstance::new(
+                    Vec2::new(x, y),
+                    Vec2::new(10.0, 10.0),
+                    Vec4::new(1.0, 1.0, 1.0, 0.1),
+                    4.0,
+                ));
+            }
+            black_box(bytemuck::cast_slice::<GlassInstance, u8>(&instances).len())
+        })
+    });
+}
+
+fn bench_text(c: &mut Criterion) {
+    let font = TextRenderer::load_system_font();
+    let mut group = c.benchmark_group("text_measure");
+    for len in [100, 1_000, 10_000] {
+        let text: String = "The quick brown fox jumps over the lazy dog. ".chars().cycle().take(len).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(len), &text, |b, text| {
+            b.iter(|| black_box(measure_text_with(&font, text, 14.0).width))
+        });
+    }
+    group.finish();
+}
+
+fn large_table(rows: usize) -> DataTable {
+    let mut table = DataTable::new().with_columns(vec![
+        GridColumn::new("name", "Name", 200.0),
+        GridColumn::new("value", "Value", 100.0),
+        GridColumn::new("status", "Status", 150.0),
+    ]);
+    for i in 0..rows {
+        table.add_row(GridRow::new(&format!("row{}", i), vec![
+            CellValue::text(&format!("Item {}", i)),
+            CellValue::number(i as f64 * 1.5),
+            CellValue::text(if i % 3 == 0 { "Active" } else { "Idle" }),
+        ]));
+    }
+    table
+}
+
+fn wheel(lines: f32) -> Event<()> {
+    Event::WindowEvent {
+        window_id: unsafe { WindowId::dummy() },
+        event: WindowEvent::MouseWheel {
+            device_id: unsafe { DeviceId::dummy() },
+            delta: MouseScrollDelta::LineDelta(0.0, lines),
+            phase: TouchPhase::Moved,
+        },
+    }
+}
+
+fn bench_table_scroll(c: &mut Criterion) {
+    let mut table = large_table(100_000);
+    table.layout(Vec2::ZERO, Vec2::new(800.0, 600.0));
+    let down = wheel(-3.0);
+    let up = wheel(3.0);
+    let mouse = Vec2::new(400.0, 300.0);
+    let mut step = 0usize;
+    
+    c.bench_function("data_table/scroll_100k_rows", |b| {
+        b.iter(|| {
+            // Scroll down for a while, then back up
+            step += 1;
+            let event = if (step / 500).is_multiple_of(2) { &down } else { &up };
+            table.handle_event(event, mouse);
+            
+            // Format the cells that would be drawn this frame
+            let mut chars = 0;
+            for row in &table.rows[table.visible_rows()] {
+                for cell in &row.cells {
+                    chars += cell.display().len();
+                }
+            }
+            black_box(chars)
+        })
+    });
+}
+
+criterion_group!(benches, bench_instances, bench_text, bench_table_scroll);
+criterion_main!(benches);
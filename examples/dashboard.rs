@@ -139,7 +139,7 @@ pub fn quick_start_guide() -> &'static str {
 ## 1. Create a window
 ```rust
 let (window, event_loop) = create_window();
-let mut ctx = GlassContext::new(&window).await;
+let mut ctx = GlassContext::new(&window, AntiAliasing::default()).await;
 ```
 
 ## 2. Create widgets
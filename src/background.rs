@@ -0,0 +1,124 @@
+//! GlassUI Window Background
+//!
+//! What is drawn behind the widgets (and blurred by glass panels):
+//! - `Background` - Stock grid, solid color, image, custom shader or nothing
+//! - `BACKGROUND_PRELUDE` - Bindings and vertex stage shared by background shaders
+//!
+//! Set with `GlassRenderer::set_background`. Translucent windows (see
+//! `set_background_opacity`) skip the background whatever it is.
+
+use glam::Vec4;
+
+use crate::renderer::TextureId;
+
+/// Declarations prepended to a `Background::Shader`'s WGSL
+///
+/// In scope for `fs_main(in: BackgroundInput) -> @location(0) vec4<f32>`:
+/// - `uniforms.time`, `uniforms.resolution` (logical pixels), `uniforms.scale`
+/// - `in.uv` - 0..1 across the window, top-left origin
+pub const BACKGROUND_PRELUDE: &str = r#"
+struct Uniforms {
+    time: f32,
+    quality: u32,
+    resolution: vec2<f32>,
+    scale: f32,
+};
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+struct BackgroundInput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) v_idx: u32) -> BackgroundInput {
+    // Fullscreen triangle
+    let pos = vec2<f32>(f32((v_idx << 1u) & 2u), f32(v_idx & 2u));
+    var out: BackgroundInput;
+    out.position = vec4<f32>(pos * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(pos.x, 1.0 - pos.y);
+    return out;
+}
+"#;
+
+/// Fragment stage of `Background::Image`: scales the texture to cover the
+/// window, cropping the overflow evenly
+const IMAGE_FRAGMENT: &str = r#"
+@group(1) @binding(0) var background_texture: texture_2d<f32>;
+@group(1) @binding(1) var background_sampler: sampler;
+
+@fragment
+fn fs_main(in: BackgroundInput) -> @location(0) vec4<f32> {
+    let texture_size = vec2<f32>(textureDimensions(background_texture));
+    let window = max(uniforms.resolution, vec2<f32>(1.0));
+    let cover = max(window.x / texture_size.x, window.y / texture_size.y);
+    let visible = window / (texture_size * cover);
+    let uv = (in.uv - 0.5) * visible + 0.5;
+    return vec4<f32>(textureSample(background_texture, background_sampler, uv).rgb, 1.0);
+}
+"#;
+
+// =============================================================================
+// BACKGROUND
+// =============================================================================
+
+/// Window background, see `GlassRenderer::set_background`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Background {
+    /// The stock animated grid
+    #[default]
+    Procedural,
+    /// One flat color
+    Solid(Vec4),
+    /// A loaded texture covering the window (cropped to keep its aspect)
+    Image(TextureId),
+    /// WGSL defining `fs_main` (appended to `BACKGROUND_PRELUDE`)
+    Shader(String),
+    /// Nothing: transparent black, e.g. to draw over an existing scene
+    None,
+}
+
+impl Background {
+    /// Full WGSL module drawing this background, if it needs a shader
+    /// (solid colors and `None` are a plain clear)
+    pub fn shader_source(&self) -> Option<String> {
+        match self {
+            Background::Procedural => Some(include_str!("shaders/bg.wgsl").to_string()),
+            Background::Image(_) => Some(format!("{}\n{}", BACKGROUND_PRELUDE, IMAGE_FRAGMENT)),
+            Background::Shader(source) => Some(format!("{}\n{}", BACKGROUND_PRELUDE, source)),
+            Background::Solid(_) | Background::None => None,
+        }
+    }
+
+    /// Color the frame is cleared to before the background is drawn
+    pub fn clear_color(&self) -> Vec4 {
+        match self {
+            Background::Solid(color) => *color,
+            Background::None => Vec4::ZERO,
+            _ => Vec4::W,
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_sources() {
+        assert_eq!(Background::default(), Background::Procedural);
+        assert!(Background::Procedural.shader_source().unwrap().contains("fn fs_main"));
+
+        let custom = Background::Shader("@fragment fn fs_main(in: BackgroundInput) -> @location(0) vec4<f32> { return vec4<f32>(in.uv, 0.0, 1.0); }".into());
+        assert!(custom.shader_source().unwrap().starts_with(BACKGROUND_PRELUDE));
+
+        let solid = Background::Solid(Vec4::new(0.1, 0.2, 0.3, 1.0));
+        assert!(solid.shader_source().is_none());
+        assert_eq!(solid.clear_color(), Vec4::new(0.1, 0.2, 0.3, 1.0));
+        assert_eq!(Background::None.clear_color(), Vec4::ZERO);
+    }
+}
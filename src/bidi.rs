@@ -0,0 +1,283 @@
+//! GlassUI Bidirectional Text
+//!
+//! Right-to-left (Hebrew, Arabic) support for the text renderer:
+//! - `visual_runs` - A line split into same-direction runs, in display order
+//! - `BidiText` - A line reordered for display, mapped back to the source
+//! - `caret_positions` - Caret x of every character boundary of a line
+//! - `move_visually` - Caret movement by arrow keys in mixed-direction text
+//!
+//! Reordering is the Unicode Bidirectional Algorithm (UAX #9) from
+//! `unicode-bidi`, explicit embeddings and isolates included. Letter forms,
+//! kerning and mark placement come from shaping each run with `rustybuzz`
+//! in the text renderer (see `TextRenderer::draw_text_with_font`).
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use unicode_bidi::{bidi_class, BidiClass, Level, ParagraphBidiInfo};
+
+// =============================================================================
+// DIRECTION
+// =============================================================================
+
+/// Writing direction
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+/// True if `text` has any right-to-left characters or controls (nothing
+/// to reorder otherwise)
+pub fn has_rtl(text: &str) -> bool {
+    text.chars().any(|c| matches!(bidi_class(c), BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI))
+}
+
+/// Direction of the first strong character (outside isolates),
+/// left-to-right if there is none
+pub fn paragraph_direction(text: &str) -> Direction {
+    if !has_rtl(text) {
+        return Direction::Ltr;
+    }
+    match ParagraphBidiInfo::new(text, None).paragraph_level.is_rtl() {
+        true => Direction::Rtl,
+        false => Direction::Ltr,
+    }
+}
+
+/// A stretch of one line drawn in one direction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BidiRun {
+    /// Byte range in the source (logical order inside the run)
+    pub range: Range<usize>,
+    pub rtl: bool,
+}
+
+/// Runs of `text` (one line: newlines aren't treated specially) in display
+/// order, left to right, in the direction of its first strong character
+pub fn visual_runs(text: &str) -> Vec<BidiRun> {
+    runs_with(text, None)
+}
+
+fn runs_with(text: &str, direction: Option<Direction>) -> Vec<BidiRun> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    if !has_rtl(text) && direction != Some(Direction::Rtl) {
+        return vec![BidiRun { range: 0..text.len(), rtl: false }];
+    }
+    let level = direction.map(|d| if d == Direction::Rtl { Level::rtl() } else { Level::ltr() });
+    let info = ParagraphBidiInfo::new(text, level);
+    let (levels, runs) = info.visual_runs(0..text.len());
+    runs.into_iter().map(|range| BidiRun { rtl: levels[range.start].is_rtl(), range }).collect()
+}
+
+// =============================================================================
+// BIDI TEXT
+// =============================================================================
+
+/// One line reordered for left-to-right drawing, character by character
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidiText {
+    /// Characters in display order, left to right
+    pub visual: String,
+    /// Byte range in the source of each character of `visual`
+    pub source: Vec<Range<usize>>,
+    /// Whether each character of `visual` is in a right-to-left run
+    pub rtl: Vec<bool>,
+    pub direction: Direction,
+}
+
+impl BidiText {
+    /// Reorder `text` (one line: newlines aren't treated specially) for
+    /// display, in the direction of its first strong character
+    pub fn new(text: &str) -> Self {
+        Self::with_direction(text, paragraph_direction(text))
+    }
+
+    pub fn with_direction(text: &str, direction: Direction) -> Self {
+        let mut bidi = Self { visual: String::with_capacity(text.len()), source: Vec::new(), rtl: Vec::new(), direction };
+        for run in runs_with(text, Some(direction)) {
+            let chars = text[run.range.clone()].char_indices().map(|(i, c)| (run.range.start + i, c));
+            let mut push = |(i, c): (usize, char)| {
+                bidi.visual.push(if run.rtl { mirror(c) } else { c });
+                bidi.source.push(i..i + c.len_utf8());
+                bidi.rtl.push(run.rtl);
+            };
+            if run.rtl {
+                chars.rev().for_each(&mut push);
+            } else {
+                chars.for_each(&mut push);
+            }
+        }
+        bidi
+    }
+
+    /// Number of visual characters left of the caret at byte `cursor` of
+    /// the source
+    ///
+    /// The caret sits after the character logically before it: on that
+    /// character's right in left-to-right runs, on its left in
+    /// right-to-left ones.
+    pub fn caret_index(&self, cursor: usize) -> usize {
+        let before = self.source.iter().position(|r| r.end == cursor && r.start < cursor);
+        if let Some(i) = before {
+            return if self.rtl[i] { i } else { i + 1 };
+        }
+        // At the start of the text (or inside a ligature): before the
+        // character there
+        match self.source.iter().position(|r| r.contains(&cursor)) {
+            Some(i) if self.rtl[i] => i + 1,
+            Some(i) => i,
+            None if self.direction == Direction::Rtl => 0,
+            None => self.source.len(),
+        }
+    }
+
+    /// The first `count` visual characters (measure this for a caret x)
+    pub fn visual_prefix(&self, count: usize) -> &str {
+        let end = self.visual.char_indices().nth(count).map_or(self.visual.len(), |(i, _)| i);
+        &self.visual[..end]
+    }
+}
+
+/// `text` in display order, borrowed unchanged if it has no right-to-left
+/// characters
+pub fn visual_text(text: &str) -> Cow<'_, str> {
+    if has_rtl(text) {
+        Cow::Owned(BidiText::new(text).visual)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Caret x of every character boundary of `text` (one line), in logical
+/// order, with `measure` giving the width of a logical substring
+///
+/// The caret sits after the character logically before it: on that
+/// character's right in left-to-right runs, on its left in right-to-left
+/// ones. Runs are measured whole, so shaped widths (joined letters,
+/// kerning) line up with what's drawn.
+pub fn caret_positions(text: &str, measure: impl Fn(&str) -> f32) -> Vec<(usize, f32)> {
+    let runs = visual_runs(text);
+    let mut starts = Vec::with_capacity(runs.len());
+    let mut x = 0.0;
+    for run in &runs {
+        starts.push(x);
+        x += measure(&text[run.range.clone()]);
+    }
+    let boundaries = text.char_indices().map(|(i, _)| i).chain(std::iter::once(text.len()));
+    boundaries.map(|i| {
+        // Run of the character before the caret (the first one at the start)
+        let before = text[..i].chars().next_back().map(|c| i - c.len_utf8());
+        let found = runs.iter().position(|run| run.range.contains(&before.unwrap_or(i)));
+        let Some(r) = found else { return (i, 0.0) };
+        let run = &runs[r];
+        let x = match (run.rtl, before.is_some_and(|b| run.range.contains(&b))) {
+            (false, true) => measure(&text[run.range.start..i]),
+            (true, true) => measure(&text[i..run.range.end]),
+            // Nothing before the caret in this run: its logical start
+            (false, false) => 0.0,
+            (true, false) => measure(&text[run.range.clone()]),
+        };
+        (i, starts[r] + x)
+    }).collect()
+}
+
+/// Caret position after pressing Left (`right = false`) or Right from byte
+/// `cursor` of `text`: the neighbouring caret position on screen
+pub fn move_visually(text: &str, cursor: usize, right: bool) -> usize {
+    if !has_rtl(text) {
+        // Plain left-to-right: one character back or forward
+        return if right {
+            text[cursor..].chars().next().map_or(cursor, |c| cursor + c.len_utf8())
+        } else {
+            text[..cursor].chars().next_back().map_or(cursor, |c| cursor - c.len_utf8())
+        };
+    }
+    // One unit per character, so every caret stop has its own x
+    let stops = caret_positions(text, |run| run.chars().count() as f32);
+    let Some(&(_, current)) = stops.iter().find(|&&(i, _)| i == cursor) else { return cursor };
+    let candidates = stops.iter().filter(|&&(i, _)| i != cursor);
+    let best = if right {
+        candidates.filter(|&&(_, x)| x > current).min_by(|a, b| a.1.total_cmp(&b.1))
+    } else {
+        candidates.filter(|&&(_, x)| x < current).max_by(|a, b| a.1.total_cmp(&b.1))
+    };
+    best.map_or(cursor, |&(i, _)| i)
+}
+
+/// Mirrored bracket for right-to-left runs
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        _ => c,
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reorders_rtl_runs() {
+        assert_eq!(visual_text("hello"), "hello");
+        assert!(matches!(visual_text("hello"), Cow::Borrowed(_)));
+
+        // Hebrew reads right to left; numbers and embedded Latin stay in order
+        assert_eq!(BidiText::new("שלום").visual, "םולש");
+        assert_eq!(BidiText::new("abc שלום def").visual, "abc םולש def");
+        assert_eq!(BidiText::new("שלום abc 123").visual, "abc 123 םולש");
+        assert_eq!(BidiText::new("מחיר 42 (ש\"ח)").visual, "(ח\"ש) 42 ריחמ");
+        assert_eq!(paragraph_direction("123 שלום"), Direction::Rtl);
+    }
+
+    #[test]
+    fn test_explicit_isolates_and_runs() {
+        // A right-to-left isolate inside Latin text keeps the Latin order
+        let text = "ab \u{2067}אב\u{2069} cd";
+        assert_eq!(paragraph_direction(text), Direction::Ltr);
+        let runs = visual_runs(text);
+        let rtl: Vec<&str> = runs.iter().filter(|run| run.rtl).map(|run| &text[run.range.clone()]).collect();
+        assert_eq!(rtl, ["אב"]);
+        assert!(text[runs[0].range.clone()].starts_with("ab"));
+        assert!(text[runs[runs.len() - 1].range.clone()].ends_with("cd"));
+        
+        // An RLO override reverses Latin letters too
+        assert_eq!(BidiText::new("\u{202E}abc\u{202C}").visual.replace(['\u{202E}', '\u{202C}'], ""), "cba");
+    }
+
+    #[test]
+    fn test_caret_moves_visually() {
+        // Right-to-left: the end of the text is on the left
+        let text = "אבג";
+        let bidi = BidiText::new(text);
+        assert_eq!(bidi.caret_index(0), 3);
+        assert_eq!(bidi.caret_index(text.len()), 0);
+
+        // Right arrow moves towards the start of the text
+        let end = text.len();
+        let moved = move_visually(text, end, true);
+        assert_eq!(moved, end - 'ג'.len_utf8());
+        assert_eq!(move_visually(text, moved, false), end);
+        assert_eq!(move_visually(text, end, false), end);
+
+        // Plain text moves by characters
+        assert_eq!(move_visually("añb", 1, true), 3);
+        assert_eq!(move_visually("añb", 3, false), 1);
+    }
+}
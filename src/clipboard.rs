@@ -3,6 +3,7 @@
 //! Cross-platform clipboard integration using arboard.
 
 use std::sync::Mutex;
+use winit::keyboard::{Key, ModifiersState};
 
 // Global clipboard instance (lazy initialized)
 static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
@@ -56,10 +57,47 @@ pub fn clear_clipboard() -> Result<(), String> {
     }
 }
 
+/// Clipboard command asked for by a key press
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipboardAction {
+    Copy,
+    Cut,
+    Paste,
+}
+
+impl ClipboardAction {
+    /// The command for `key` pressed with `modifiers`: C, X or V with Ctrl
+    /// (or Cmd) held
+    pub fn from_key(key: &Key, modifiers: ModifiersState) -> Option<Self> {
+        if !(modifiers.control_key() || modifiers.super_key()) {
+            return None;
+        }
+        let Key::Character(c) = key else {
+            return None;
+        };
+        match c.to_lowercase().as_str() {
+            "c" => Some(Self::Copy),
+            "x" => Some(Self::Cut),
+            "v" => Some(Self::Paste),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_clipboard_action_from_key() {
+        let key = |c: &str| Key::Character(c.into());
+        assert_eq!(ClipboardAction::from_key(&key("c"), ModifiersState::CONTROL), Some(ClipboardAction::Copy));
+        assert_eq!(ClipboardAction::from_key(&key("X"), ModifiersState::CONTROL | ModifiersState::SHIFT), Some(ClipboardAction::Cut));
+        assert_eq!(ClipboardAction::from_key(&key("v"), ModifiersState::SUPER), Some(ClipboardAction::Paste));
+        assert_eq!(ClipboardAction::from_key(&key("v"), ModifiersState::empty()), None);
+        assert_eq!(ClipboardAction::from_key(&key("a"), ModifiersState::CONTROL), None);
+    }
+    
     #[test]
     fn test_clipboard_roundtrip() {
         let test_text = "Hello, GlassUI!";
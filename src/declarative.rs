@@ -0,0 +1,1076 @@
+//! GlassUI Declarative UI
+//!
+//! Widget trees described in JSON, rebuilt live when the file changes:
+//! - UiNode - serializable description of a widget subtree
+//! - Keyed - wrapper whose user-editable state survives rebuilds
+//! - KeyedList - column or row regenerated from app data, reconciled by key
+//! - HotReloadView - widget that owns a built tree and reloads it in place
+//!
+//! # Example
+//! ```json
+//! { "type": "column", "spacing": 12, "children": [
+//!     { "type": "label", "text": "Settings", "size": 24 },
+//!     { "type": "checkbox", "key": "sound", "label": "Sound effects" },
+//!     { "type": "button", "text": "Apply", "action": "apply_settings" }
+//! ] }
+//! ```
+//!
+//! Keyed widgets (slider value, checkbox, text input) keep what the user
+//! entered when the file is edited; button `action`s are queued for the
+//! app to handle via `HotReloadView::take_actions`.
+
+use glam::{Vec2, Vec4};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::layout::{BoxConstraints, EdgeInsets, Offset, Size, Rect, LayoutCache};
+use crate::redraw::RedrawWaker;
+use crate::renderer::GlassRenderer;
+use crate::widgets::{
+    Widget, WidgetState, UpdateState, get_theme, mount, unmount, LinearLayout, place_children,
+    Column, Row, Stack, Panel, Label, Button, Slider, Checkbox, TextInput, Spacer, FlexDirection,
+};
+
+// =============================================================================
+// UI NODES
+// =============================================================================
+
+/// Serializable description of a widget subtree
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UiNode {
+    Column {
+        #[serde(default)]
+        spacing: Option<f32>,
+        #[serde(default)]
+        padding: Option<f32>,
+        #[serde(default)]
+        children: Vec<UiNode>,
+    },
+    Row {
+        #[serde(default)]
+        spacing: Option<f32>,
+        #[serde(default)]
+        padding: Option<f32>,
+        #[serde(default)]
+        children: Vec<UiNode>,
+    },
+    Stack {
+        #[serde(default)]
+        children: Vec<UiNode>,
+    },
+    Panel {
+        #[serde(default)]
+        padding: Option<f32>,
+        #[serde(default)]
+        child: Option<Box<UiNode>>,
+    },
+    Label {
+        text: String,
+        #[serde(default)]
+        size: Option<f32>,
+    },
+    Button {
+        text: String,
+        /// Queued on click, see `HotReloadView::take_actions`
+        #[serde(default)]
+        action: Option<String>,
+    },
+    Slider {
+        #[serde(default)]
+        key: Option<String>,
+        #[serde(default)]
+        value: f32,
+    },
+    Checkbox {
+        #[serde(default)]
+        key: Option<String>,
+        label: String,
+        #[serde(default)]
+        checked: bool,
+    },
+    TextInput {
+        #[serde(default)]
+        key: Option<String>,
+        #[serde(default)]
+        placeholder: String,
+        #[serde(default)]
+        text: String,
+    },
+    Spacer {
+        #[serde(default)]
+        width: f32,
+        #[serde(default)]
+        height: f32,
+    },
+}
+
+impl UiNode {
+    pub fn from_json(json: &str) -> Result<Self, DeclarativeError> {
+        serde_json::from_str(json).map_err(|e| DeclarativeError::ParseError(e.to_string()))
+    }
+
+    /// Build the widget tree, seeding keyed widgets from `ctx.state`
+    pub fn build(&self, ctx: &BuildContext) -> Box<dyn Widget> {
+        match self {
+            UiNode::Column { spacing, padding, children } => {
+                let mut column = Column::new();
+                if let Some(spacing) = spacing { column.spacing = *spacing; }
+                if let Some(padding) = padding { column.padding = EdgeInsets::all(*padding); }
+                column.children = children.iter().map(|c| (c.build(ctx), 0.0)).collect();
+                Box::new(column)
+            }
+            UiNode::Row { spacing, padding, children } => {
+                let mut row = Row::new();
+                if let Some(spacing) = spacing { row.spacing = *spacing; }
+                if let Some(padding) = padding { row.padding = EdgeInsets::all(*padding); }
+                row.children = children.iter().map(|c| (c.build(ctx), 0.0)).collect();
+                Box::new(row)
+            }
+            UiNode::Stack { children } => {
+                let stack = children.iter().fold(Stack::new(), |stack, c| stack.add_child(c.build(ctx)));
+                Box::new(stack)
+            }
+            UiNode::Panel { padding, child } => {
+                let mut panel = match child {
+                    Some(child) => Panel::new(child.build(ctx)),
+                    None => Panel::new_empty(),
+                };
+                if let Some(padding) = padding { panel.padding = *padding; }
+                Box::new(panel)
+            }
+            UiNode::Label { text, size } => {
+                let label = Label::new(text);
+                Box::new(match size {
+                    Some(size) => label.with_size(*size),
+                    None => label,
+                })
+            }
+            UiNode::Button { text, action } => {
+                let button = Button::new(text);
+                Box::new(match action {
+                    Some(action) => {
+                        let (queue, action) = (ctx.actions.clone(), action.clone());
+                        button.with_callback(move || queue.borrow_mut().push(action.clone()))
+                    }
+                    None => button,
+                })
+            }
+            UiNode::Slider { key, value } => {
+                let value = match ctx.saved(key) {
+                    Some(WidgetState::Number(v)) => *v,
+                    _ => *value,
+                };
+                keyed(key, Box::new(Slider::new(value)))
+            }
+            UiNode::Checkbox { key, label, checked } => {
+                let checked = match ctx.saved(key) {
+                    Some(WidgetState::Bool(b)) => *b,
+                    _ => *checked,
+                };
+                keyed(key, Box::new(Checkbox::new(label, checked)))
+            }
+            UiNode::TextInput { key, placeholder, text } => {
+                let text = match ctx.saved(key) {
+                    Some(WidgetState::Text(t)) => t.as_str(),
+                    _ => text.as_str(),
+                };
+                keyed(key, Box::new(TextInput::new(placeholder).with_text(text)))
+            }
+            UiNode::Spacer { width, height } => Box::new(Spacer::new(Vec2::new(*width, *height))),
+        }
+    }
+}
+
+fn keyed(key: &Option<String>, child: Box<dyn Widget>) -> Box<dyn Widget> {
+    match key {
+        Some(key) => Box::new(Keyed::new(key, child)),
+        None => child,
+    }
+}
+
+/// Inputs for `UiNode::build`
+#[derive(Default)]
+pub struct BuildContext {
+    /// State harvested from the previous tree, by key
+    pub state: HashMap<String, WidgetState>,
+    /// Queue button actions are pushed to
+    pub actions: Rc<RefCell<Vec<String>>>,
+}
+
+impl BuildContext {
+    fn saved(&self, key: &Option<String>) -> Option<&WidgetState> {
+        key.as_ref().and_then(|k| self.state.get(k))
+    }
+}
+
+/// Collect the state of every keyed widget under `root`
+pub fn collect_keyed_state(root: &dyn Widget) -> HashMap<String, WidgetState> {
+    fn walk(widget: &dyn Widget, out: &mut HashMap<String, WidgetState>) {
+        if let (Some(key), Some(state)) = (widget.state_key(), widget.saved_state()) {
+            out.insert(key.to_string(), state);
+        }
+        widget.visit_children(&mut |child| walk(child, out));
+    }
+    let mut state = HashMap::new();
+    walk(root, &mut state);
+    state
+}
+
+// =============================================================================
+// KEYED
+// =============================================================================
+
+/// Gives a widget a stable key so its state is carried across rebuilds
+pub struct Keyed {
+    pub key: String,
+    pub child: Box<dyn Widget>,
+}
+
+impl Keyed {
+    pub fn new(key: &str, child: Box<dyn Widget>) -> Self {
+        Self { key: key.to_string(), child }
+    }
+}
+
+impl Widget for Keyed {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.child.layout_child(origin, max_size)
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout_child_with_constraints(constraints)
+    }
+
+    fn set_position(&mut self, position: Offset) {
+        self.child.set_position(position);
+    }
+
+    fn get_position(&self) -> Offset {
+        self.child.get_position()
+    }
+
+    fn get_size(&self) -> Size {
+        self.child.get_size()
+    }
+
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("key", self.key.clone()));
+    }
+
+    fn state_key(&self) -> Option<&str> {
+        Some(&self.key)
+    }
+
+    fn saved_state(&self) -> Option<WidgetState> {
+        self.child.saved_state()
+    }
+
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn positioned(&self) -> Option<&crate::widgets::Positioned> {
+        self.child.positioned()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
+}
+
+// =============================================================================
+// KEYED LIST
+// =============================================================================
+
+/// What `KeyedList::set_items` did to reconcile the children
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListDiff {
+    /// Items whose key was new; their widgets were built from the view function
+    pub inserted: usize,
+    /// Items that changed under an existing key
+    pub updated: usize,
+    /// Widgets dropped because their key disappeared
+    pub removed: usize,
+    /// Widgets kept as-is
+    pub kept: usize,
+}
+
+type ItemView<T> = Box<dyn Fn(&T) -> Box<dyn Widget>>;
+type ItemUpdate<T> = Box<dyn Fn(&T, &mut (dyn Widget + 'static))>;
+
+struct ListChild<T> {
+    key: String,
+    item: T,
+    widget: Box<dyn Widget>,
+}
+
+/// List whose children are regenerated from data by a view function
+///
+/// Children are laid out like a Column's (or a Row's, with
+/// `with_direction`).
+///
+/// Each item maps to a key; on `set_items` children are matched by key, so a
+/// widget keeps its state (scroll offset, text, hover animation...) while its
+/// item stays in the list, wherever it moves. Only new keys are built. An
+/// item that changed under an existing key is patched in place by the
+/// `with_update` hook if set, otherwise its widget is rebuilt.
+///
+/// ```ignore
+/// let mut cards = KeyedList::new(
+///     |agent: &AgentRow| agent.id.to_string(),
+///     |agent| Box::new(Label::new(&agent.name)),
+/// );
+/// cards.set_items(agents.iter().cloned());
+/// ```
+pub struct KeyedList<T> {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub direction: FlexDirection,
+    pub spacing: f32,
+    /// Space between the edges and the children
+    pub padding: EdgeInsets,
+    children: Vec<ListChild<T>>,
+    /// Child positions relative to ours, from the last constraint layout
+    offsets: Vec<Vec2>,
+    key_fn: Box<dyn Fn(&T) -> String>,
+    view: ItemView<T>,
+    update: Option<ItemUpdate<T>>,
+    layout_cache: LayoutCache,
+    /// Between `on_mount` and `on_unmount`: built and dropped children are
+    /// mounted and unmounted as `set_items` reconciles
+    mounted: bool,
+}
+
+impl<T: PartialEq + 'static> KeyedList<T> {
+    pub fn new(
+        key_fn: impl Fn(&T) -> String + 'static,
+        view: impl Fn(&T) -> Box<dyn Widget> + 'static,
+    ) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            direction: FlexDirection::Column,
+            spacing: 10.0,
+            padding: EdgeInsets::ZERO,
+            children: Vec::new(),
+            offsets: Vec::new(),
+            key_fn: Box::new(key_fn),
+            view: Box::new(view),
+            update: None,
+            layout_cache: LayoutCache::new(),
+            mounted: false,
+        }
+    }
+
+    /// Patch an existing widget when its item changes instead of rebuilding it
+    pub fn with_update(mut self, update: impl Fn(&T, &mut (dyn Widget + 'static)) + 'static) -> Self {
+        self.update = Some(Box::new(update));
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Same padding on all sides
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = EdgeInsets::all(padding);
+        self
+    }
+
+    pub fn with_insets(mut self, insets: EdgeInsets) -> Self {
+        self.padding = insets;
+        self
+    }
+
+    /// Lay the children out in a row instead of a column
+    pub fn with_direction(mut self, direction: FlexDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Builder form of `set_items`
+    pub fn with_items(mut self, items: impl IntoIterator<Item = T>) -> Self {
+        self.set_items(items);
+        self
+    }
+
+    /// Replace the list contents, reusing widgets whose key is still present
+    ///
+    /// If two items share a key only the first reuses the old widget.
+    pub fn set_items(&mut self, items: impl IntoIterator<Item = T>) -> ListDiff {
+        let mut old: HashMap<String, ListChild<T>> = self.children
+            .drain(..)
+            .map(|child| (child.key.clone(), child))
+            .collect();
+        let mut diff = ListDiff::default();
+        
+        for item in items {
+            let key = (self.key_fn)(&item);
+            let child = match old.remove(&key) {
+                Some(child) if child.item == item => {
+                    diff.kept += 1;
+                    child
+                }
+                Some(mut child) => {
+                    diff.updated += 1;
+                    match &self.update {
+                        Some(update) => update(&item, child.widget.as_mut()),
+                        None => {
+                            if self.mounted {
+                                unmount(child.widget.as_mut());
+                            }
+                            child.widget = self.build(&item);
+                        }
+                    }
+                    ListChild { key, item, widget: child.widget }
+                }
+                None => {
+                    diff.inserted += 1;
+                    let widget = self.build(&item);
+                    ListChild { key, item, widget }
+                }
+            };
+            self.children.push(child);
+        }
+        
+        if self.mounted {
+            for child in old.values_mut() {
+                unmount(child.widget.as_mut());
+            }
+        }
+        diff.removed = old.len();
+        self.layout_cache.invalidate();
+        diff
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.children.iter().map(|child| child.key.as_str())
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.children.iter().map(|child| &child.item)
+    }
+
+    /// Widget currently shown for `key`
+    pub fn widget(&self, key: &str) -> Option<&(dyn Widget + 'static)> {
+        self.children.iter().find(|child| child.key == key).map(|child| child.widget.as_ref())
+    }
+
+    pub fn widget_mut(&mut self, key: &str) -> Option<&mut (dyn Widget + 'static)> {
+        match self.children.iter_mut().find(|child| child.key == key) {
+            Some(child) => Some(child.widget.as_mut()),
+            None => None,
+        }
+    }
+
+    /// Run the view function, mounting the result if the list is mounted
+    fn build(&self, item: &T) -> Box<dyn Widget> {
+        let mut widget = (self.view)(item);
+        if self.mounted {
+            mount(widget.as_mut());
+        }
+        widget
+    }
+    
+    fn linear(&self) -> LinearLayout {
+        LinearLayout { direction: self.direction, spacing: self.spacing, padding: self.padding }
+    }
+}
+
+impl<T: PartialEq + 'static> Widget for KeyedList<T> {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
+        self.position = origin;
+        let linear = self.linear();
+        let mut children: Vec<_> = self.children.iter_mut().map(|c| (c.widget.as_mut() as &mut dyn Widget, 0.0)).collect();
+        self.size = linear.layout(&mut children, origin, max_size);
+        self.layout_cache.store(origin, max_size, self.size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let linear = self.linear();
+        let mut children: Vec<_> = self.children.iter_mut().map(|c| (c.widget.as_mut() as &mut dyn Widget, 0.0)).collect();
+        let size = linear.layout_with_constraints(&mut children, constraints, &mut self.offsets);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        place_children(self.children.iter_mut().map(|c| &mut c.widget), &self.offsets, position);
+    }
+
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        for child in &self.children {
+            visitor(child.widget.as_ref());
+        }
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        for child in &mut self.children {
+            visitor(child.widget.as_mut());
+        }
+    }
+
+    fn on_mount(&mut self) {
+        self.mounted = true;
+    }
+
+    fn on_unmount(&mut self) {
+        self.mounted = false;
+    }
+
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("children", self.children.len().to_string()));
+    }
+
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.children.iter().any(|c| c.widget.needs_layout())
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let mut handled = false;
+        for child in &mut self.children {
+            if child.widget.handle_event(event, mouse_pos) {
+                handled = true;
+            }
+        }
+        handled
+    }
+
+    fn update(&mut self, dt: f32) {
+        for child in &mut self.children {
+            child.widget.update(dt);
+        }
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        for child in &mut self.children {
+            child.widget.split_update(dt, jobs);
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|c| c.widget.is_animating())
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        for child in &self.children {
+            child.widget.render(renderer);
+        }
+    }
+}
+
+// =============================================================================
+// HOT RELOAD VIEW
+// =============================================================================
+
+/// Owns a tree built from a UI definition and rebuilds it when the source changes
+///
+/// Call `poll()` once per frame (it only stats the file). With `with_waker`
+/// a background thread also wakes a sleeping event loop when the file is
+/// saved, so edits show up without any input.
+pub struct HotReloadView {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    root: Box<dyn Widget>,
+    actions: Rc<RefCell<Vec<String>>>,
+    error: Option<String>,
+    reloads: usize,
+    watcher_stop: Option<Arc<AtomicBool>>,
+    /// Between `on_mount` and `on_unmount`: a reload unmounts the old tree
+    /// and mounts the new one
+    mounted: bool,
+}
+
+impl HotReloadView {
+    /// Build from a JSON string (no file watching)
+    pub fn from_json(json: &str) -> Result<Self, DeclarativeError> {
+        let mut view = Self {
+            path: None,
+            modified: None,
+            root: Box::new(Column::new()),
+            actions: Rc::new(RefCell::new(Vec::new())),
+            error: None,
+            reloads: 0,
+            watcher_stop: None,
+            mounted: false,
+        };
+        view.reload_str(json)?;
+        view.reloads = 0;
+        Ok(view)
+    }
+
+    /// Build from a file and watch it for changes
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, DeclarativeError> {
+        let path = path.as_ref().to_path_buf();
+        let json = fs::read_to_string(&path).map_err(|e| DeclarativeError::IoError(e.to_string()))?;
+        let mut view = Self::from_json(&json)?;
+        view.modified = modified_time(&path);
+        view.path = Some(path);
+        Ok(view)
+    }
+
+    /// Wake `waker` whenever the watched file changes on disk
+    pub fn with_waker(mut self, waker: RedrawWaker) -> Self {
+        let Some(path) = self.path.clone() else { return self };
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let mut last = self.modified;
+        std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Acquire) {
+                std::thread::sleep(Duration::from_millis(250));
+                let modified = modified_time(&path);
+                if modified != last {
+                    last = modified;
+                    waker.wake();
+                }
+            }
+        });
+        self.watcher_stop = Some(stop);
+        self
+    }
+
+    /// Rebuild from `json`, carrying keyed state over from the current tree
+    ///
+    /// On error the current tree is kept.
+    pub fn reload_str(&mut self, json: &str) -> Result<(), DeclarativeError> {
+        let node = UiNode::from_json(json)?;
+        let ctx = BuildContext {
+            state: collect_keyed_state(self.root.as_ref()),
+            actions: self.actions.clone(),
+        };
+        let root = node.build(&ctx);
+        if self.mounted {
+            unmount(self.root.as_mut());
+        }
+        self.root = root;
+        if self.mounted {
+            mount(self.root.as_mut());
+        }
+        self.reloads += 1;
+        Ok(())
+    }
+
+    /// Reload if the watched file changed; returns true when the tree was rebuilt
+    pub fn poll(&mut self) -> bool {
+        let Some(path) = &self.path else { return false };
+        let modified = modified_time(path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        let result = fs::read_to_string(path)
+            .map_err(|e| DeclarativeError::IoError(e.to_string()))
+            .and_then(|json| self.reload_str(&json));
+        match result {
+            Ok(()) => {
+                self.error = None;
+                true
+            }
+            Err(e) => {
+                log::warn!("UI reload failed: {}", e);
+                self.error = Some(e.to_string());
+                false
+            }
+        }
+    }
+
+    /// Button actions clicked since the last call
+    pub fn take_actions(&mut self) -> Vec<String> {
+        std::mem::take(&mut *self.actions.borrow_mut())
+    }
+
+    /// Error from the last failed reload, if the file is currently broken
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Number of successful rebuilds since creation
+    pub fn reloads(&self) -> usize {
+        self.reloads
+    }
+
+    pub fn root(&self) -> &dyn Widget {
+        self.root.as_ref()
+    }
+}
+
+impl Drop for HotReloadView {
+    fn drop(&mut self) {
+        if let Some(stop) = &self.watcher_stop {
+            stop.store(true, Ordering::Release);
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Widget for HotReloadView {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.root.layout_child(origin, max_size)
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.root.layout_child_with_constraints(constraints)
+    }
+
+    fn set_position(&mut self, position: Offset) {
+        self.root.set_position(position);
+    }
+
+    fn get_position(&self) -> Offset {
+        self.root.get_position()
+    }
+
+    fn get_size(&self) -> Size {
+        self.root.get_size()
+    }
+
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.root.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.root.as_mut());
+    }
+
+    fn on_mount(&mut self) {
+        self.mounted = true;
+    }
+
+    fn on_unmount(&mut self) {
+        self.mounted = false;
+    }
+
+    fn render_bounds(&self) -> Option<Rect> {
+        self.root.render_bounds()
+    }
+
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.root.opaque_rect()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.root.needs_layout()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.root.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.root.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.root.split_update(dt, jobs);
+    }
+
+    fn is_animating(&self) -> bool {
+        self.root.is_animating()
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.root.render(renderer);
+
+        if let Some(error) = &self.error {
+            let theme = get_theme();
+            let Offset { x, y } = self.root.get_position();
+            let pos = Vec2::new(x, y) + Vec2::splat(8.0);
+            renderer.draw_overlay_rect(pos, Vec2::new(520.0, 44.0), Vec4::new(0.25, 0.02, 0.04, 0.92), 6.0);
+            renderer.draw_overlay_text("UI reload failed (keeping previous tree)", pos + Vec2::new(10.0, 6.0), 13.0, theme.error);
+            renderer.draw_overlay_text(error, pos + Vec2::new(10.0, 24.0), 11.0, theme.text_secondary);
+        }
+    }
+}
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// UI definition load errors
+#[derive(Clone, Debug)]
+pub enum DeclarativeError {
+    IoError(String),
+    ParseError(String),
+}
+
+impl std::fmt::Display for DeclarativeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeclarativeError::IoError(e) => write!(f, "IO error: {}", e),
+            DeclarativeError::ParseError(e) => write!(f, "Parse error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DeclarativeError {}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::CheckState;
+    use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+
+    const SETTINGS: &str = r#"{ "type": "column", "children": [
+        { "type": "label", "text": "Settings" },
+        { "type": "checkbox", "key": "sound", "label": "Sound" },
+        { "type": "button", "text": "Apply", "action": "apply" }
+    ] }"#;
+
+    fn press(view: &mut HotReloadView, pos: Vec2) {
+        for state in [ElementState::Pressed, ElementState::Released] {
+            view.handle_event(&Event::WindowEvent {
+                window_id: unsafe { winit::window::WindowId::dummy() },
+                event: WindowEvent::MouseInput {
+                    device_id: unsafe { winit::event::DeviceId::dummy() },
+                    state,
+                    button: MouseButton::Left,
+                },
+            }, pos);
+        }
+    }
+
+    /// Center of the `index`th child of the root column
+    fn child_center(view: &HotReloadView, index: usize) -> Vec2 {
+        let mut center = Vec2::ZERO;
+        let mut i = 0;
+        view.root().visit_children(&mut |child| {
+            if i == index {
+                let Offset { x, y } = child.get_position();
+                let Size { width, height } = child.get_size();
+                center = Vec2::new(x + width.min(20.0) * 0.5, y + height * 0.5);
+            }
+            i += 1;
+        });
+        center
+    }
+
+    #[test]
+    fn test_build_from_json() {
+        let view = HotReloadView::from_json(SETTINGS).unwrap();
+        let mut count = 0;
+        view.root().visit_children(&mut |_| count += 1);
+        assert_eq!(count, 3);
+        assert!(HotReloadView::from_json(r#"{ "type": "nope" }"#).is_err());
+    }
+
+    #[test]
+    fn test_reload_preserves_keyed_state() {
+        let mut view = HotReloadView::from_json(SETTINGS).unwrap();
+        view.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        let checkbox = child_center(&view, 1);
+        press(&mut view, checkbox);
+        assert_eq!(collect_keyed_state(&view).get("sound"), Some(&WidgetState::Bool(true)));
+
+        // Insert a widget above and rename the checkbox; the key carries the state
+        let edited = SETTINGS
+            .replace(r#"{ "type": "label", "text": "Settings" },"#,
+                r#"{ "type": "label", "text": "Settings" }, { "type": "slider", "key": "volume", "value": 0.3 },"#)
+            .replace(r#""label": "Sound""#, r#""label": "Sound effects""#);
+        view.reload_str(&edited).unwrap();
+        assert_eq!(view.reloads(), 1);
+        let state = collect_keyed_state(&view);
+        assert_eq!(state.get("sound"), Some(&WidgetState::Bool(true)));
+        assert_eq!(state.get("volume"), Some(&WidgetState::Number(0.3)));
+    }
+
+    #[test]
+    fn test_reload_unmounts_old_tree() {
+        use crate::hero::{HeroId, HeroScope, mounted_hero_bounds};
+
+        let id = HeroId::new("reload-hero");
+        let mut view = HotReloadView::from_json(SETTINGS).unwrap();
+        view.root = Box::new(HeroScope::new(id.clone(), Box::new(Column::new())));
+        mount(&mut view);
+        assert!(mounted_hero_bounds(&id).is_some());
+
+        view.reload_str(SETTINGS).unwrap();
+        assert!(mounted_hero_bounds(&id).is_none(), "the replaced tree was unmounted");
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_tree() {
+        let mut view = HotReloadView::from_json(SETTINGS).unwrap();
+        assert!(view.reload_str("{ not json").is_err());
+        assert_eq!(view.reloads(), 0);
+        assert_eq!(collect_keyed_state(&view).len(), 1);
+    }
+
+    #[test]
+    fn test_button_actions_are_queued() {
+        let mut view = HotReloadView::from_json(SETTINGS).unwrap();
+        view.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        let button = child_center(&view, 2);
+        view.handle_event(&Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::CursorMoved {
+                device_id: unsafe { winit::event::DeviceId::dummy() },
+                position: winit::dpi::PhysicalPosition::new(button.x as f64, button.y as f64),
+            },
+        }, button);
+        press(&mut view, button);
+        assert_eq!(view.take_actions(), vec!["apply".to_string()]);
+        assert!(view.take_actions().is_empty());
+    }
+
+    #[test]
+    fn test_poll_reloads_changed_file() {
+        let path = std::env::temp_dir().join(format!("glassui-hot-reload-{}.json", std::process::id()));
+        fs::write(&path, SETTINGS).unwrap();
+        let mut view = HotReloadView::from_file(&path).unwrap();
+        assert!(!view.poll());
+
+        // Force a different mtime regardless of filesystem timestamp resolution
+        view.modified = None;
+        fs::write(&path, r#"{ "type": "label", "text": "Replaced" }"#).unwrap();
+        assert!(view.poll());
+        assert_eq!(view.root().type_name(), "Label");
+
+        view.modified = None;
+        fs::write(&path, "{").unwrap();
+        assert!(!view.poll());
+        assert!(view.error().is_some());
+        assert_eq!(view.root().type_name(), "Label");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_keyed_list_reconciles_by_key() {
+        let agents = |rows: &[(u32, &str)]| -> Vec<(u32, String)> {
+            rows.iter().map(|(id, name)| (*id, name.to_string())).collect()
+        };
+        let mut list = KeyedList::new(
+            |agent: &(u32, String)| agent.0.to_string(),
+            |agent| Box::new(Checkbox::new(&agent.1, false)),
+        ).with_update(|agent, widget| {
+            widget.downcast_mut::<Checkbox>().unwrap().label = agent.1.clone();
+        });
+        
+        let diff = list.set_items(agents(&[(1, "alpha"), (2, "beta")]));
+        assert_eq!(diff, ListDiff { inserted: 2, ..Default::default() });
+        list.widget_mut("2").unwrap().downcast_mut::<Checkbox>().unwrap().set_state(CheckState::Checked);
+        
+        // Reorder, rename, add and drop; the checked box follows its key
+        let diff = list.set_items(agents(&[(3, "gamma"), (2, "beta v2")]));
+        assert_eq!(diff, ListDiff { inserted: 1, updated: 1, removed: 1, kept: 0 });
+        assert_eq!(list.keys().collect::<Vec<_>>(), vec!["3", "2"]);
+        let beta = list.widget("2").unwrap().downcast_ref::<Checkbox>().unwrap();
+        assert!(beta.is_checked());
+        assert_eq!(beta.label, "beta v2");
+        assert_eq!(list.widget("3").unwrap().saved_state(), Some(WidgetState::Bool(false)));
+        
+        let size = list.layout(Vec2::ZERO, Vec2::new(300.0, 400.0));
+        assert!(size.y > 0.0);
+        assert!(list.widget("2").unwrap().get_position().y > 0.0);
+    }
+    
+    #[test]
+    fn test_keyed_list_lays_out_like_column_or_row() {
+        let build = |direction| {
+            let mut list = KeyedList::new(|n: &u32| n.to_string(), |n| Box::new(Label::new(&format!("item {n}"))))
+                .with_spacing(5.0)
+                .with_padding(4.0)
+                .with_direction(direction);
+            list.set_items(vec![1, 2]);
+            list
+        };
+        let first = |list: &KeyedList<u32>| {
+            let widget = list.widget("1").unwrap();
+            (widget.get_position(), widget.get_size())
+        };
+        
+        let mut column = build(FlexDirection::Column);
+        column.layout(Vec2::ZERO, Vec2::new(300.0, 400.0));
+        let (pos, size) = first(&column);
+        assert_eq!(pos, Offset::new(4.0, 4.0));
+        assert_eq!(column.widget("2").unwrap().get_position(), Offset::new(4.0, 4.0 + size.height + 5.0));
+        
+        let mut row = build(FlexDirection::Row);
+        row.layout(Vec2::ZERO, Vec2::new(300.0, 400.0));
+        let (pos, size) = first(&row);
+        assert_eq!(pos, Offset::new(4.0, 4.0));
+        assert_eq!(row.widget("2").unwrap().get_position(), Offset::new(4.0 + size.width + 5.0, 4.0));
+        
+        // The constraint pass places children the same way
+        row.layout_with_constraints(BoxConstraints::loose(Size::new(300.0, 400.0)));
+        row.set_position(Offset::new(10.0, 10.0));
+        let (_, size) = first(&row);
+        assert_eq!(row.widget("2").unwrap().get_position(), Offset::new(14.0 + size.width + 5.0, 14.0));
+    }
+}
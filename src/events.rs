@@ -0,0 +1,885 @@
+//! GlassUI Event Routing
+//!
+//! Delivers pointer events to the widget under the pointer instead of to
+//! every widget in the tree:
+//! - Hit testing: popups first, then the topmost widget whose `hit_test`
+//!   contains the pointer (later siblings and children are on top)
+//! - Capture phase: ancestors, root first, may take the event
+//!   (`capture_routed_event`)
+//! - Bubbling: the widget under the pointer, then each ancestor, until one
+//!   handles it (`handle_routed_event`)
+//! - Pointer grab: the widget that handled a press gets every move and the
+//!   release, wherever the pointer goes (drags)
+//! - Keyboard focus: a press focuses the deepest focusable widget under the
+//!   pointer (or clears focus), Tab/Shift+Tab move it, and keys go to the
+//!   focused widget, then its ancestors
+//! - Click counting: quick presses at one spot are counted, and the second
+//!   and later ones also bubble as `handle_multi_click` (double-click)
+//! - Hover tracking: widgets entering and leaving the hit path as the
+//!   pointer moves, or leaves the window, get `on_hover_enter` and
+//!   `on_hover_leave` (only while some widget in the tree `wants_hover`)
+//!
+//! Pointer moves and other events, and keys nothing focused handled
+//! (shortcuts), are still broadcast with `handle_event`. When the pointer
+//! leaves the window, `CursorLeft` is broadcast at a point no widget
+//! contains, so widgets tracking `inside` themselves drop their hover.
+
+use std::time::Instant;
+use glam::Vec2;
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+use crate::focus::{FocusId, FocusManager, FocusNode, Focusable};
+use crate::widgets::{Widget, get_theme, tree_generation};
+
+/// Position no widget contains, used to tell widgets off the hit path about
+/// a press elsewhere
+const OUTSIDE: Vec2 = Vec2::splat(f32::NEG_INFINITY);
+
+// =============================================================================
+// EVENT DISPATCHER
+// =============================================================================
+
+/// Routes pointer events through a widget tree
+///
+/// Widgets are addressed by their child-index path from the root (see
+/// `Widget::visit_children_mut`).
+#[derive(Debug, Default)]
+pub struct EventDispatcher {
+    /// Path of the widget holding the pointer grab
+    capture: Option<Vec<usize>>,
+    /// Focusable widgets in the tree and which one has focus
+    focus: FocusManager,
+    /// `tree_generation` the focus manager was last synced at
+    focus_generation: Option<u64>,
+    /// Held modifiers, for Shift+Tab
+    modifiers: ModifiersState,
+    /// Hit path under the pointer (`None` while it's outside the window)
+    hover: Option<Vec<usize>>,
+    /// `tree_generation` and whether a widget in the tree `wants_hover`
+    hover_listeners: Option<(u64, bool)>,
+    /// When and where the last press was, to count multi-clicks
+    last_press: Option<(Instant, Vec2)>,
+    /// Presses in the current run, 1 for a single click
+    clicks: u32,
+}
+
+impl EventDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Path of the widget that handled the last press, until the release
+    pub fn captured(&self) -> Option<&[usize]> {
+        self.capture.as_deref()
+    }
+
+    /// Drop the pointer grab (e.g. when the tree is rebuilt)
+    pub fn release_capture(&mut self) {
+        self.capture = None;
+    }
+
+    /// Presses in the latest run of quick clicks at one spot (2 right after
+    /// a double-click, 0 before any press)
+    pub fn click_count(&self) -> u32 {
+        self.clicks
+    }
+
+    /// Count a press at `pos` into the current run or start a new one
+    /// (other buttons than the left one always start over)
+    fn count_click(&mut self, button: MouseButton, pos: Vec2) -> u32 {
+        let theme = get_theme();
+        let now = Instant::now();
+        let repeat = button == MouseButton::Left && self.last_press.is_some_and(|(time, last)| {
+            now.duration_since(time).as_secs_f32() <= theme.double_click_time
+                && last.distance(pos) <= theme.double_click_distance
+        });
+        self.clicks = if repeat { self.clicks + 1 } else { 1 };
+        self.last_press = (button == MouseButton::Left).then_some((now, pos));
+        self.clicks
+    }
+
+    /// Focus state of the tree, synced on each dispatch
+    pub fn focus(&self) -> &FocusManager {
+        &self.focus
+    }
+
+    /// Path of the topmost widget under the pointer; it and its ancestors
+    /// count as hovered
+    /// 
+    /// Always `None` while no widget in the tree `wants_hover`.
+    pub fn hovered(&self) -> Option<&[usize]> {
+        self.hover.as_deref()
+    }
+
+    /// Whether a widget in the tree `wants_hover`, looked up again only
+    /// after widgets were mounted or unmounted
+    fn tracks_hover(&mut self, root: &dyn Widget) -> bool {
+        let generation = tree_generation();
+        match self.hover_listeners {
+            Some((cached, listening)) if cached == generation => listening,
+            _ => {
+                let listening = any_wants_hover(root);
+                self.hover_listeners = Some((generation, listening));
+                listening
+            }
+        }
+    }
+
+    /// Move the hover to `path`, telling widgets that left the old path
+    /// (deepest first) and then widgets that joined the new one
+    fn set_hover(&mut self, root: &mut dyn Widget, path: Option<Vec<usize>>) {
+        let old = std::mem::replace(&mut self.hover, path);
+        // Widgets above this depth are on both paths
+        let shared = match (&old, &self.hover) {
+            (Some(old), Some(new)) => old.iter().zip(new).take_while(|(a, b)| a == b).count() + 1,
+            _ => 0,
+        };
+        if let Some(old) = &old {
+            for depth in (shared..=old.len()).rev() {
+                if let Some(widget) = root.widget_at_mut(&old[..depth]) {
+                    widget.on_hover_leave();
+                }
+            }
+        }
+        if let Some(new) = &self.hover {
+            for depth in shared..=new.len() {
+                if let Some(widget) = root.widget_at_mut(&new[..depth]) {
+                    widget.on_hover_enter();
+                }
+            }
+        }
+    }
+
+    /// Widget that currently has keyboard focus
+    pub fn focused(&self) -> Option<FocusId> {
+        self.focus.focused()
+    }
+
+    /// Focus the widget with `id` (`None` clears focus); returns false if
+    /// no focusable widget in the tree has that id
+    pub fn set_focus(&mut self, root: &mut dyn Widget, id: Option<FocusId>) -> bool {
+        self.sync_focus(root);
+        let old = self.focus.focused();
+        match id {
+            // Added without `mount`, or enabled since the last sync
+            Some(id) if !self.focus.request_focus(id) => {
+                self.resync_focus(root);
+                if !self.focus.request_focus(id) {
+                    self.notify_focus(root, old);
+                    return false;
+                }
+            }
+            Some(_) => {}
+            None => self.focus.clear_focus(),
+        }
+        self.notify_focus(root, old);
+        true
+    }
+
+    /// Move focus along the tab order; returns false if nothing can take it
+    pub fn focus_next(&mut self, root: &mut dyn Widget, backwards: bool) -> bool {
+        self.sync_focus(root);
+        let old = self.focus.focused();
+        let mut moved = if backwards { self.focus.focus_previous() } else { self.focus.focus_next() };
+        // The cached order may hold a widget disabled since it was built
+        let stale = self.focus.focused().is_some_and(|id| !focusable_mut(root, id).is_some_and(|f| f.can_focus()));
+        if moved && stale {
+            self.resync_focus(root);
+            match old {
+                Some(old) if self.focus.request_focus(old) => {}
+                _ => self.focus.clear_focus(),
+            }
+            moved = if backwards { self.focus.focus_previous() } else { self.focus.focus_next() };
+        }
+        self.notify_focus(root, old);
+        moved
+    }
+
+    /// Register the tree's focusable widgets with the focus manager if any
+    /// were mounted or unmounted since the last sync (focus is dropped if
+    /// the focused widget left the tree)
+    fn sync_focus(&mut self, root: &dyn Widget) {
+        if self.focus_generation != Some(tree_generation()) {
+            self.resync_focus(root);
+        }
+    }
+
+    /// Register the tree's current focusable widgets with the focus manager
+    fn resync_focus(&mut self, root: &dyn Widget) {
+        let mut nodes = Vec::new();
+        collect_focus_nodes(root, &mut nodes);
+        self.focus.sync(nodes);
+        self.focus_generation = Some(tree_generation());
+    }
+
+    /// Tell the widgets that lost and gained focus since `old`
+    fn notify_focus(&mut self, root: &mut dyn Widget, old: Option<FocusId>) {
+        let new = self.focus.focused();
+        if old == new {
+            return;
+        }
+        if let Some(widget) = old.and_then(|id| focusable_mut(root, id)) {
+            widget.on_blur();
+        }
+        if let Some(widget) = new.and_then(|id| focusable_mut(root, id)) {
+            widget.on_focus();
+        }
+    }
+
+    /// Send a key or IME event to the focused widget and its ancestors,
+    /// falling back to the whole tree, then to Tab navigation
+    fn dispatch_key(&mut self, root: &mut dyn Widget, event: &Event<()>, mouse_pos: Vec2) -> bool {
+        self.sync_focus(root);
+        let path = self.focus.focused().and_then(|id| focus_path(root, id));
+        // No capture phase: containers' shortcuts must not eat typed text
+        if path.is_some_and(|path| bubble(root, &path, event, mouse_pos).is_some()) {
+            return true;
+        }
+        if root.handle_event(event, mouse_pos) {
+            return true;
+        }
+        match event {
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. }
+                if key_event.state.is_pressed() && key_event.logical_key == Key::Named(NamedKey::Tab) =>
+            {
+                self.focus_next(root, self.modifiers.shift_key())
+            }
+            _ => false,
+        }
+    }
+
+    /// Deliver `event` to `root`'s tree; returns whether a widget handled it
+    pub fn dispatch(&mut self, root: &mut dyn Widget, event: &Event<()>, mouse_pos: Vec2) -> bool {
+        let Event::WindowEvent { event: window_event, .. } = event else {
+            return root.handle_event(event, mouse_pos);
+        };
+        match window_event {
+            WindowEvent::MouseInput { state: ElementState::Pressed, button, .. } => {
+                let clicks = self.count_click(*button, mouse_pos);
+                let mut path = hit_path(root, mouse_pos);
+                let focus_target = (0..=path.len()).rev().find_map(|depth| {
+                    root.widget_at(&path[..depth])
+                        .and_then(|w| w.as_focusable())
+                        .filter(|f| f.can_focus())
+                        .map(|f| f.focus_id())
+                });
+                // Widgets elsewhere see the press at a point they don't
+                // contain, so popups close and fields drop focus
+                notify_outside(root, &mut Vec::new(), &path, event);
+                let routed = route(root, &path, event, mouse_pos);
+                self.set_focus(root, focus_target);
+                let multi = clicks > 1 && (0..=path.len()).rev().any(|depth| {
+                    root.widget_at_mut(&path[..depth]).is_some_and(|w| w.handle_multi_click(clicks, mouse_pos))
+                });
+                let Some(depth) = routed else {
+                    return multi;
+                };
+                path.truncate(depth);
+                raise_path(root, &mut path);
+                self.capture = Some(path);
+                true
+            }
+            WindowEvent::MouseInput { state: ElementState::Released, .. } => {
+                let path = self.capture.take().unwrap_or_else(|| hit_path(root, mouse_pos));
+                route(root, &path, event, mouse_pos).is_some()
+            }
+            WindowEvent::CursorMoved { .. } => {
+                // The hit path walk is only needed to tell hover listeners
+                if self.tracks_hover(root) {
+                    let path = hit_path(root, mouse_pos);
+                    self.set_hover(root, Some(path));
+                } else if self.hover.is_some() {
+                    self.set_hover(root, None);
+                }
+                match &self.capture {
+                    Some(path) => bubble(root, path, event, mouse_pos).is_some(),
+                    None => root.handle_event(event, mouse_pos),
+                }
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.set_hover(root, None);
+                root.handle_event(event, OUTSIDE)
+            }
+            WindowEvent::MouseWheel { .. } => {
+                let path = hit_path(root, mouse_pos);
+                route(root, &path, event, mouse_pos).is_some()
+            }
+            WindowEvent::KeyboardInput { .. } | WindowEvent::Ime(_) => self.dispatch_key(root, event, mouse_pos),
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+                root.handle_event(event, mouse_pos)
+            }
+            _ => root.handle_event(event, mouse_pos),
+        }
+    }
+}
+
+// =============================================================================
+// HIT TESTING
+// =============================================================================
+
+/// Path to the widget a pointer event at `point` goes to: inside the
+/// topmost open popup if there is one, else the topmost widget hit
+pub fn hit_path(root: &dyn Widget, point: Vec2) -> Vec<usize> {
+    let mut path = Vec::new();
+    if find_path(root, &mut path, &|w: &dyn Widget| w.hit_test_popup(point)) {
+        if let Some(popup) = root.widget_at(&path) {
+            let mut inner = Vec::new();
+            children_path(popup, &mut inner, &|w: &dyn Widget| w.hit_test(point));
+            path.extend(inner);
+        }
+        return path;
+    }
+    find_path(root, &mut path, &|w: &dyn Widget| w.hit_test(point));
+    path
+}
+
+/// Whether `widget` or a widget below it `wants_hover`
+fn any_wants_hover(widget: &dyn Widget) -> bool {
+    let mut found = widget.wants_hover();
+    widget.visit_children(&mut |child| found = found || any_wants_hover(child));
+    found
+}
+
+/// Depth-first search for the topmost widget matching `hit`, pruning
+/// subtrees hit testing can't see and those of clipping containers that
+/// don't match
+fn find_path(widget: &dyn Widget, path: &mut Vec<usize>, hit: &dyn Fn(&dyn Widget) -> bool) -> bool {
+    if !widget.hit_test_visible() || (widget.clips_content() && !hit(widget)) {
+        return false;
+    }
+    children_path(widget, path, hit) || hit(widget)
+}
+
+/// `find_path` over `widget`'s children, last (topmost) first
+fn children_path(widget: &dyn Widget, path: &mut Vec<usize>, hit: &dyn Fn(&dyn Widget) -> bool) -> bool {
+    let mut children = Vec::new();
+    widget.visit_children(&mut |child| children.push(child));
+    for (index, child) in children.into_iter().enumerate().rev() {
+        path.push(index);
+        if find_path(child, path, hit) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+// =============================================================================
+// DELIVERY
+// =============================================================================
+
+/// Capture phase down `path`, then bubbling back up; returns the depth of
+/// the widget that handled the event
+fn route(root: &mut dyn Widget, path: &[usize], event: &Event<()>, mouse_pos: Vec2) -> Option<usize> {
+    for depth in 0..path.len() {
+        let captured = root.widget_at_mut(&path[..depth]).map(|w| w.capture_routed_event(event, mouse_pos));
+        if captured == Some(true) {
+            return Some(depth);
+        }
+    }
+    bubble(root, path, event, mouse_pos)
+}
+
+/// Offer `event` to the widget at `path`, then to each ancestor
+fn bubble(root: &mut dyn Widget, path: &[usize], event: &Event<()>, mouse_pos: Vec2) -> Option<usize> {
+    (0..=path.len()).rev().find(|&depth| {
+        root.widget_at_mut(&path[..depth]).map(|w| w.handle_routed_event(event, mouse_pos)) == Some(true)
+    })
+}
+
+/// Let each ancestor react to a press inside `path` (e.g. bring it to the
+/// front), deepest first so the indices above stay valid
+fn raise_path(root: &mut dyn Widget, path: &mut [usize]) {
+    for depth in (0..path.len()).rev() {
+        let index = path[depth];
+        if let Some(raised) = root.widget_at_mut(&path[..depth]).map(|w| w.child_pressed(index)) {
+            path[depth] = raised;
+        }
+    }
+}
+
+/// Every focusable widget under `widget`, in tree order
+fn collect_focus_nodes(widget: &dyn Widget, nodes: &mut Vec<FocusNode>) {
+    if let Some(focusable) = widget.as_focusable() {
+        let mut node = FocusNode::new(focusable.focus_id()).with_tab_index(focusable.tab_index());
+        node.can_focus = focusable.can_focus();
+        nodes.push(node);
+    }
+    widget.visit_children(&mut |child| collect_focus_nodes(child, nodes));
+}
+
+/// Path to the focusable widget with `id`
+fn focus_path(root: &dyn Widget, id: FocusId) -> Option<Vec<usize>> {
+    fn search(widget: &dyn Widget, id: FocusId, path: &mut Vec<usize>) -> bool {
+        if widget.as_focusable().is_some_and(|f| f.focus_id() == id) {
+            return true;
+        }
+        let mut children = Vec::new();
+        widget.visit_children(&mut |child| children.push(child));
+        for (index, child) in children.into_iter().enumerate() {
+            path.push(index);
+            if search(child, id, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+    let mut path = Vec::new();
+    search(root, id, &mut path).then_some(path)
+}
+
+fn focusable_mut(root: &mut dyn Widget, id: FocusId) -> Option<&mut dyn Focusable> {
+    let path = focus_path(root, id)?;
+    root.widget_at_mut(&path)?.as_focusable_mut()
+}
+
+/// Send `event` at `OUTSIDE` to every widget not on `target`'s path
+fn notify_outside(widget: &mut dyn Widget, path: &mut Vec<usize>, target: &[usize], event: &Event<()>) {
+    if !target.starts_with(path) {
+        widget.handle_routed_event(event, OUTSIDE);
+    }
+    let mut index = 0;
+    widget.visit_children_mut(&mut |child| {
+        path.push(index);
+        notify_outside(child, path, target, event);
+        path.pop();
+        index += 1;
+    });
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use crate::layout::{Offset, Size};
+    use crate::recorder::{RecordedButton, RecordedEvent};
+    use crate::renderer::GlassRenderer;
+    use crate::widgets::{Align, Alignment, Button, Column, DialogManager, Draggable, Dropdown, FileNode, FileTree, PassThrough, Row, Stack, TextInput, Tooltip};
+
+    /// Fixed-rect leaf that counts the presses it takes
+    struct Pad {
+        rect: (Vec2, Vec2),
+        takes_press: bool,
+        presses: Rc<Cell<u32>>,
+    }
+
+    impl Pad {
+        fn new(position: Vec2, size: Vec2, takes_press: bool) -> (Self, Rc<Cell<u32>>) {
+            let presses = Rc::new(Cell::new(0));
+            (Self { rect: (position, size), takes_press, presses: presses.clone() }, presses)
+        }
+    }
+
+    impl Widget for Pad {
+        fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
+            self.rect.1
+        }
+
+        fn get_position(&self) -> Offset {
+            Offset::new(self.rect.0.x, self.rect.0.y)
+        }
+
+        fn get_size(&self) -> Size {
+            Size::new(self.rect.1.x, self.rect.1.y)
+        }
+
+        fn handle_event(&mut self, event: &Event<()>, mouse_pos: Vec2) -> bool {
+            let pressed = matches!(event, Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, .. }, .. });
+            if pressed && self.takes_press && self.hit_test(mouse_pos) {
+                self.presses.set(self.presses.get() + 1);
+                return true;
+            }
+            false
+        }
+
+        fn update(&mut self, _dt: f32) {}
+
+        fn render(&self, _renderer: &mut GlassRenderer) {}
+    }
+
+    /// Focusable leaf counting the IME commits it receives
+    struct Field {
+        pad: Pad,
+        focus_id: FocusId,
+        commits: Rc<Cell<u32>>,
+    }
+
+    impl Focusable for Field {
+        fn focus_id(&self) -> FocusId {
+            self.focus_id
+        }
+    }
+
+    impl Widget for Field {
+        fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+            self.pad.layout(origin, max_size)
+        }
+
+        fn get_position(&self) -> Offset {
+            self.pad.get_position()
+        }
+
+        fn get_size(&self) -> Size {
+            self.pad.get_size()
+        }
+
+        fn as_focusable(&self) -> Option<&dyn Focusable> {
+            Some(self)
+        }
+
+        fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+            Some(self)
+        }
+
+        fn handle_event(&mut self, event: &Event<()>, _mouse_pos: Vec2) -> bool {
+            let commit = matches!(event, Event::WindowEvent { event: WindowEvent::Ime(_), .. });
+            if commit {
+                self.commits.set(self.commits.get() + 1);
+            }
+            commit
+        }
+
+        fn update(&mut self, _dt: f32) {}
+
+        fn render(&self, _renderer: &mut GlassRenderer) {}
+    }
+
+    /// Leaf logging its hover enters and leaves
+    struct HoverPad {
+        pad: Pad,
+        name: &'static str,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Widget for HoverPad {
+        fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+            self.pad.layout(origin, max_size)
+        }
+
+        fn get_position(&self) -> Offset {
+            self.pad.get_position()
+        }
+
+        fn get_size(&self) -> Size {
+            self.pad.get_size()
+        }
+
+        fn on_hover_enter(&mut self) {
+            self.log.borrow_mut().push(format!("enter {}", self.name));
+        }
+
+        fn on_hover_leave(&mut self) {
+            self.log.borrow_mut().push(format!("leave {}", self.name));
+        }
+
+        fn wants_hover(&self) -> bool {
+            true
+        }
+
+        fn handle_event(&mut self, _event: &Event<()>, _mouse_pos: Vec2) -> bool {
+            false
+        }
+
+        fn update(&mut self, _dt: f32) {}
+
+        fn render(&self, _renderer: &mut GlassRenderer) {}
+    }
+
+    fn click(pressed: bool) -> Event<()> {
+        RecordedEvent::MouseInput { button: RecordedButton::Left, pressed }.to_event().unwrap()
+    }
+
+    fn move_to(point: Vec2) -> Event<()> {
+        RecordedEvent::CursorMoved { x: point.x as f64, y: point.y as f64 }.to_event().unwrap()
+    }
+
+    #[test]
+    fn test_topmost_widget_takes_press() {
+        let (below, below_presses) = Pad::new(Vec2::ZERO, Vec2::splat(100.0), true);
+        let (above, above_presses) = Pad::new(Vec2::splat(50.0), Vec2::splat(100.0), true);
+        let mut root = Stack::new().add_child(Box::new(below)).add_child(Box::new(above));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+
+        // Overlap goes to the later sibling only
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(75.0)));
+        assert_eq!((below_presses.get(), above_presses.get()), (0, 1));
+        assert_eq!(dispatcher.captured(), Some(&[1][..]));
+        dispatcher.dispatch(&mut root, &click(false), Vec2::splat(75.0));
+        assert_eq!(dispatcher.captured(), None);
+
+        // Pressing the lower one brings it to the front
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(10.0)));
+        assert_eq!((below_presses.get(), above_presses.get()), (1, 1));
+        assert_eq!(dispatcher.captured(), Some(&[1][..]));
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(75.0)));
+        assert_eq!((below_presses.get(), above_presses.get()), (2, 1));
+    }
+
+    #[test]
+    fn test_pass_through_overlay_is_skipped() {
+        let (below, below_presses) = Pad::new(Vec2::ZERO, Vec2::splat(100.0), true);
+        let (glow, glow_presses) = Pad::new(Vec2::ZERO, Vec2::splat(100.0), true);
+        let mut root = Stack::new().add_child(Box::new(below)).add_child(Box::new(PassThrough::new(Box::new(glow))));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        assert_eq!(hit_path(&root, Vec2::splat(50.0)), vec![0]);
+        root.child_mut(1).unwrap().downcast_mut::<PassThrough>().unwrap().hit_test = true;
+        assert_eq!(hit_path(&root, Vec2::splat(50.0)), vec![1, 0]);
+        root.child_mut(1).unwrap().downcast_mut::<PassThrough>().unwrap().hit_test = false;
+
+        let mut dispatcher = EventDispatcher::new();
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(50.0)));
+        assert_eq!((below_presses.get(), glow_presses.get()), (1, 0));
+        // Broadcast presses don't reach it either
+        let overlay = (&mut root as &mut dyn Widget).first_of_type_mut::<PassThrough>().unwrap();
+        assert!(!overlay.handle_event(&click(true), Vec2::splat(50.0)));
+        assert_eq!(glow_presses.get(), 0);
+    }
+
+    #[test]
+    fn test_layout_containers_let_presses_through() {
+        let (below, below_presses) = Pad::new(Vec2::ZERO, Vec2::splat(100.0), true);
+        let (above, above_presses) = Pad::new(Vec2::new(200.0, 0.0), Vec2::splat(100.0), true);
+        // Both wrappers fill the stack; only their children are hit
+        let mut root = Stack::new()
+            .add_child(Box::new(Align::new(Alignment::TopLeft, Box::new(below))))
+            .add_child(Box::new(Align::new(Alignment::TopLeft, Box::new(above))));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(10.0)));
+        assert_eq!((below_presses.get(), above_presses.get()), (1, 0));
+        assert!(!dispatcher.dispatch(&mut root, &click(true), Vec2::splat(150.0)));
+    }
+
+    #[test]
+    fn test_press_between_children_reaches_widget_beneath() {
+        let (background, background_presses) = Pad::new(Vec2::ZERO, Vec2::splat(400.0), true);
+        let (left, left_presses) = Pad::new(Vec2::ZERO, Vec2::splat(100.0), true);
+        let (right, right_presses) = Pad::new(Vec2::new(200.0, 0.0), Vec2::splat(100.0), true);
+        let row = Row::new().with_spacing(100.0).add_child(Box::new(left)).add_child(Box::new(right));
+        let mut root = Stack::new().add_child(Box::new(background)).add_child(Box::new(row));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::new(250.0, 50.0)));
+        assert_eq!(right_presses.get(), 1);
+
+        // The gap is inside the row's bounds but belongs to no child
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::new(150.0, 50.0)));
+        assert_eq!((background_presses.get(), left_presses.get(), right_presses.get()), (1, 0, 1));
+    }
+
+    #[test]
+    fn test_press_bubbles_and_captures_drag() {
+        let (content, presses) = Pad::new(Vec2::ZERO, Vec2::splat(100.0), false);
+        let mut root = Draggable::new(Box::new(content)).at(Vec2::ZERO);
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+
+        // The content ignores the press, so it bubbles up to the draggable
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(10.0)));
+        assert_eq!(presses.get(), 0);
+        assert_eq!(dispatcher.captured(), Some(&[][..]));
+
+        // The grab keeps the drag going with the pointer outside
+        assert!(dispatcher.dispatch(&mut root, &move_to(Vec2::splat(300.0)), Vec2::splat(300.0)));
+        assert_eq!(root.position, Vec2::splat(290.0));
+        dispatcher.dispatch(&mut root, &click(false), Vec2::splat(300.0));
+        assert!(!root.dragging);
+        assert_eq!(dispatcher.captured(), None);
+    }
+
+    #[test]
+    fn test_open_popup_is_hit_first() {
+        let mut dropdown = Dropdown::new(vec!["One".into(), "Two".into(), "Three".into()]);
+        dropdown.open = true;
+        // Later sibling covering the open list
+        let (cover, cover_presses) = Pad::new(Vec2::new(0.0, 40.0), Vec2::splat(180.0), true);
+        let mut root = Stack::new().add_child(Box::new(dropdown)).add_child(Box::new(cover));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::new(10.0, 80.0)));
+        assert_eq!(cover_presses.get(), 0);
+        // The dropdown came to the front, but its list closed on selection
+        assert_eq!(hit_path(&root, Vec2::new(10.0, 80.0)), vec![0]);
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::new(10.0, 80.0)));
+        assert_eq!(cover_presses.get(), 1);
+    }
+
+    #[test]
+    fn test_press_focuses_and_keys_go_to_focus() {
+        let fields: Vec<_> = (0..2).map(|i| {
+            let (pad, _) = Pad::new(Vec2::new(300.0, i as f32 * 50.0), Vec2::splat(40.0), false);
+            let commits = Rc::new(Cell::new(0));
+            (Field { pad, focus_id: FocusId::new(), commits: commits.clone() }, commits)
+        }).collect();
+        let ids: Vec<_> = fields.iter().map(|(field, _)| field.focus_id).collect();
+        let mut root = Column::new().add_child(Box::new(TextInput::new("a"))).add_child(Box::new(TextInput::new("b")));
+        let mut commits = Vec::new();
+        for (field, count) in fields {
+            root = root.add_child(Box::new(field));
+            commits.push(count);
+        }
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+        let input_focused = |root: &Column, i: usize| root.children[i].0.downcast_ref::<TextInput>().unwrap().focused;
+
+        // Clicking a field focuses it and only it
+        let a = root.children[0].0.get_position();
+        dispatcher.dispatch(&mut root, &click(true), Vec2::new(a.x + 5.0, a.y + 5.0));
+        let b = root.children[1].0.get_position();
+        dispatcher.dispatch(&mut root, &click(true), Vec2::new(b.x + 5.0, b.y + 5.0));
+        assert!(!input_focused(&root, 0) && input_focused(&root, 1));
+
+        // Tab order follows the tree; the text input is told it lost focus
+        assert!(dispatcher.focus_next(&mut root, false));
+        assert_eq!(dispatcher.focused(), Some(ids[0]));
+        assert!(!input_focused(&root, 1));
+
+        let ime = Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::Ime(winit::event::Ime::Commit("x".into())),
+        };
+        assert!(dispatcher.dispatch(&mut root, &ime, Vec2::ZERO));
+        assert_eq!((commits[0].get(), commits[1].get()), (1, 0));
+
+        // A press on nothing focusable clears focus
+        dispatcher.dispatch(&mut root, &click(true), Vec2::new(390.0, 390.0));
+        assert_eq!(dispatcher.focused(), None);
+    }
+
+    #[test]
+    fn test_tab_order_follows_mounts_and_skips_disabled() {
+        use crate::widgets::mount;
+        fn input(root: &mut Column, i: usize) -> &mut TextInput {
+            root.children[i].0.downcast_mut::<TextInput>().unwrap()
+        }
+        let mut root = Column::new().add_child(Box::new(TextInput::new("a"))).add_child(Box::new(TextInput::new("b")));
+        mount(&mut root);
+        let ids: Vec<_> = (0..2).map(|i| input(&mut root, i).focus_id).collect();
+        let mut dispatcher = EventDispatcher::new();
+        assert!(dispatcher.focus_next(&mut root, false));
+        assert_eq!(dispatcher.focused(), Some(ids[0]));
+
+        // Disabled after the order was cached: Tab goes past it
+        input(&mut root, 1).disabled = true;
+        assert!(dispatcher.focus_next(&mut root, false));
+        assert_eq!(dispatcher.focused(), Some(ids[0]));
+
+        // Mounting a new field puts it in the order
+        let mut added: Box<dyn Widget> = Box::new(TextInput::new("c"));
+        mount(added.as_mut());
+        root.children.push((added, 0.0));
+        assert!(dispatcher.focus_next(&mut root, false));
+        assert_eq!(dispatcher.focused(), Some(input(&mut root, 2).focus_id));
+    }
+
+    #[test]
+    fn test_hover_enter_and_leave() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let pad = |name, x: f32| {
+            let (pad, _) = Pad::new(Vec2::new(x, 0.0), Vec2::splat(100.0), false);
+            Box::new(HoverPad { pad, name, log: log.clone() })
+        };
+        let mut root = Stack::new()
+            .add_child(pad("a", 0.0))
+            .add_child(pad("b", 200.0))
+            .add_child(Box::new(Align::new(Alignment::BottomLeft, Box::new(Button::new("Go")))));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+
+        dispatcher.dispatch(&mut root, &move_to(Vec2::splat(10.0)), Vec2::splat(10.0));
+        dispatcher.dispatch(&mut root, &move_to(Vec2::splat(20.0)), Vec2::splat(20.0));
+        dispatcher.dispatch(&mut root, &move_to(Vec2::new(210.0, 10.0)), Vec2::new(210.0, 10.0));
+        assert_eq!(dispatcher.hovered(), Some(&[1][..]));
+        assert_eq!(*log.borrow(), vec!["enter a", "leave a", "enter b"]);
+
+        // Leaving the window ends the hover, including self-tracked ones
+        dispatcher.dispatch(&mut root, &move_to(Vec2::new(10.0, 390.0)), Vec2::new(10.0, 390.0));
+        let button = |root: &Stack| root.child(2).unwrap().widget_at(&[0]).unwrap().downcast_ref::<Button>().unwrap().hovered;
+        assert!(button(&root));
+        let left = RecordedEvent::CursorLeft.to_event().unwrap();
+        dispatcher.dispatch(&mut root, &left, Vec2::new(10.0, 390.0));
+        assert_eq!(dispatcher.hovered(), None);
+        assert!(!button(&root));
+        assert_eq!(log.borrow().last().map(String::as_str), Some("leave b"));
+    }
+
+    #[test]
+    fn test_hover_skipped_without_listeners_and_tooltip_respects_cover() {
+        let pad = |position| Box::new(Pad::new(position, Vec2::splat(100.0), false).0);
+        let mut root = Stack::new().add_child(pad(Vec2::ZERO));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.dispatch(&mut root, &move_to(Vec2::splat(10.0)), Vec2::splat(10.0));
+        assert_eq!(dispatcher.hovered(), None);
+
+        // A covered part of the tooltip's child doesn't count as hovering it
+        let mut root = Stack::new()
+            .add_child(Box::new(Tooltip::new(pad(Vec2::ZERO), "tip")))
+            .add_child(pad(Vec2::splat(50.0)));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+        let tooltip = |root: &Stack| root.child(0).unwrap().downcast_ref::<Tooltip>().unwrap().hovered;
+        dispatcher.dispatch(&mut root, &move_to(Vec2::splat(10.0)), Vec2::splat(10.0));
+        assert!(tooltip(&root));
+        dispatcher.dispatch(&mut root, &move_to(Vec2::splat(75.0)), Vec2::splat(75.0));
+        assert!(!tooltip(&root));
+        dispatcher.dispatch(&mut root, &move_to(Vec2::splat(20.0)), Vec2::splat(20.0));
+        let left = RecordedEvent::CursorLeft.to_event().unwrap();
+        dispatcher.dispatch(&mut root, &left, Vec2::splat(20.0));
+        assert!(!tooltip(&root));
+    }
+
+    #[test]
+    fn test_double_click_counts_presses() {
+        let opened = Rc::new(RefCell::new(Vec::new()));
+        let sink = opened.clone();
+        let mut tree = FileTree::new().on_activate(move |id| sink.borrow_mut().push(id.to_string()));
+        tree.add_node(FileNode::file("notes", "notes.md"));
+        let mut root = Stack::new().add_child(Box::new(tree));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+        let row = Vec2::new(20.0, 10.0);
+
+        dispatcher.dispatch(&mut root, &click(true), row);
+        assert_eq!(dispatcher.click_count(), 1);
+        assert!(opened.borrow().is_empty());
+        dispatcher.dispatch(&mut root, &click(true), row + Vec2::splat(1.0));
+        assert_eq!(dispatcher.click_count(), 2);
+        assert_eq!(*opened.borrow(), vec!["notes"]);
+
+        // A third press is a triple-click, a press elsewhere starts over
+        dispatcher.dispatch(&mut root, &click(true), row);
+        assert_eq!((dispatcher.click_count(), opened.borrow().len()), (3, 1));
+        dispatcher.dispatch(&mut root, &click(true), row + Vec2::new(100.0, 0.0));
+        assert_eq!(dispatcher.click_count(), 1);
+    }
+
+    #[test]
+    fn test_open_dialog_blocks_presses_below() {
+        let (below, below_presses) = Pad::new(Vec2::ZERO, Vec2::splat(100.0), true);
+        let mut dialogs = DialogManager::new();
+        dialogs.alert("Saved", "Workspace saved");
+        let mut root = Stack::new().add_child(Box::new(below)).add_child(Box::new(dialogs));
+        root.layout(Vec2::ZERO, Vec2::splat(400.0));
+        let mut dispatcher = EventDispatcher::new();
+
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(50.0)));
+        assert_eq!(below_presses.get(), 0);
+
+        root.child_mut(1).unwrap().downcast_mut::<DialogManager>().unwrap().accept();
+        dispatcher.dispatch(&mut root, &click(false), Vec2::splat(50.0));
+        assert!(dispatcher.dispatch(&mut root, &click(true), Vec2::splat(50.0)));
+        assert_eq!(below_presses.get(), 1);
+    }
+}
@@ -4,6 +4,9 @@
 //! - Tab/Shift+Tab traversal
 //! - Focus scoping for modals/dialogs
 //! - Programmatic focus control
+//!
+//! `EventDispatcher` keeps a `FocusManager` in sync with the widgets that
+//! return themselves from `Widget::as_focusable`.
 
 use std::collections::HashMap;
 
@@ -116,6 +119,18 @@ impl FocusManager {
         self.rebuild_tab_order();
     }
     
+    /// Replace the registered widgets with `nodes` (e.g., after the tree
+    /// changed), dropping focus if the focused widget is gone
+    pub fn sync(&mut self, nodes: impl IntoIterator<Item = FocusNode>) {
+        self.nodes = nodes.into_iter().map(|node| (node.id, node)).collect();
+        if let Some(id) = self.focused {
+            if !self.nodes.get(&id).is_some_and(|n| n.can_focus) {
+                self.focused = None;
+            }
+        }
+        self.rebuild_tab_order();
+    }
+    
     /// Unregister a widget (e.g., when removed from tree)
     pub fn unregister(&mut self, id: FocusId) {
         self.nodes.remove(&id);
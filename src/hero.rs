@@ -14,12 +14,14 @@
 //! hero_controller.start_transition("avatar-1");
 //! ```
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::time::Duration;
 use glam::{Vec2, Vec4};
 use crate::animation::{AnimationController, Curve, Lerp};
 use crate::renderer::GlassRenderer;
 use crate::layout::{BoxConstraints, Size, Offset};
+use crate::widget_id::WidgetId;
 use crate::widgets::Widget;
 
 // =============================================================================
@@ -198,6 +200,43 @@ impl HeroFlight {
     }
 }
 
+// =============================================================================
+// MOUNTED HEROES
+// =============================================================================
+
+thread_local! {
+    /// Bounds of every mounted `HeroScope`, in mount order
+    static MOUNTED_HEROES: RefCell<HashMap<HeroId, Vec<(WidgetId, HeroRect)>>> = RefCell::new(HashMap::new());
+}
+
+/// Last laid-out bounds of the most recently mounted `HeroScope` with `id`
+pub fn mounted_hero_bounds(id: &HeroId) -> Option<HeroRect> {
+    MOUNTED_HEROES.with(|heroes| heroes.borrow().get(id).and_then(|scopes| scopes.last()).map(|(_, rect)| *rect))
+}
+
+fn set_mounted_hero(id: &HeroId, token: WidgetId, bounds: HeroRect) {
+    MOUNTED_HEROES.with(|heroes| {
+        let mut heroes = heroes.borrow_mut();
+        let scopes = heroes.entry(id.clone()).or_default();
+        match scopes.iter_mut().find(|(t, _)| *t == token) {
+            Some((_, rect)) => *rect = bounds,
+            None => scopes.push((token, bounds)),
+        }
+    });
+}
+
+fn remove_mounted_hero(id: &HeroId, token: WidgetId) {
+    MOUNTED_HEROES.with(|heroes| {
+        let mut heroes = heroes.borrow_mut();
+        if let Some(scopes) = heroes.get_mut(id) {
+            scopes.retain(|(t, _)| *t != token);
+            if scopes.is_empty() {
+                heroes.remove(id);
+            }
+        }
+    });
+}
+
 // =============================================================================
 // HERO CONTROLLER
 // =============================================================================
@@ -276,9 +315,11 @@ impl HeroController {
     }
 
     /// Start a transition using registered bounds
-    /// Returns true if both source and destination were found
+    /// 
+    /// Falls back to the bounds of a mounted `HeroScope` with the same id
+    /// when none were registered by hand. Returns true if a source was found.
     pub fn start_registered_flight(&mut self, hero_id: HeroId, dest_bounds: HeroRect) -> bool {
-        if let Some(source) = self.registry.get(&hero_id).cloned() {
+        if let Some(source) = self.registry.get(&hero_id).cloned().or_else(|| mounted_hero_bounds(&hero_id)) {
             self.start_flight(hero_id, source, dest_bounds);
             true
         } else {
@@ -354,7 +395,8 @@ impl Default for HeroController {
 
 /// Wrapper widget that marks a child as a hero element
 /// 
-/// During layout, automatically registers bounds with the global HeroController.
+/// While mounted, its laid-out bounds are published for
+/// `HeroController::start_registered_flight` (see `mounted_hero_bounds`).
 /// During transitions, the child is hidden and a placeholder is shown while
 /// the HeroController renders the animated version as an overlay.
 pub struct HeroScope {
@@ -372,6 +414,8 @@ pub struct HeroScope {
     pub corner_radius: f32,
     /// Tint color for transitions
     pub tint: Option<Vec4>,
+    /// Registry token while mounted
+    mounted: Option<WidgetId>,
 }
 
 impl HeroScope {
@@ -384,6 +428,7 @@ impl HeroScope {
             in_flight: false,
             corner_radius: 0.0,
             tint: None,
+            mounted: None,
         }
     }
 
@@ -406,17 +451,24 @@ impl HeroScope {
     pub fn set_in_flight(&mut self, in_flight: bool) {
         self.in_flight = in_flight;
     }
+
+    fn publish_bounds(&self) {
+        if let Some(token) = self.mounted {
+            set_mounted_hero(&self.hero_id, token, self.bounds());
+        }
+    }
 }
 
 impl Widget for HeroScope {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = self.child.layout(origin, max_size);
+        self.size = self.child.layout_child(origin, max_size);
+        self.publish_bounds();
         self.size
     }
 
     fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
-        let child_size = self.child.layout_with_constraints(constraints);
+        let child_size = self.child.layout_child_with_constraints(constraints);
         self.size = Vec2::new(child_size.width, child_size.height);
         child_size
     }
@@ -424,6 +476,7 @@ impl Widget for HeroScope {
     fn set_position(&mut self, position: Offset) {
         self.position = Vec2::new(position.x, position.y);
         self.child.set_position(position);
+        self.publish_bounds();
     }
 
     fn get_position(&self) -> Offset {
@@ -434,6 +487,10 @@ impl Widget for HeroScope {
         Size::new(self.size.x, self.size.y)
     }
 
+    fn positioned(&self) -> Option<&crate::widgets::Positioned> {
+        self.child.positioned()
+    }
+
     fn intrinsic_width(&self, height: f32) -> Option<f32> {
         self.child.intrinsic_width(height)
     }
@@ -442,6 +499,25 @@ impl Widget for HeroScope {
         self.child.intrinsic_height(width)
     }
 
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+
+    fn on_mount(&mut self) {
+        self.mounted = Some(WidgetId::new());
+        self.publish_bounds();
+    }
+
+    fn on_unmount(&mut self) {
+        if let Some(token) = self.mounted.take() {
+            remove_mounted_hero(&self.hero_id, token);
+        }
+    }
+
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Don't handle events during flight
         if self.in_flight {
@@ -621,4 +697,26 @@ mod tests {
         
         assert!(!controller.has_active_flights());
     }
+
+    #[test]
+    fn test_mounted_scopes_publish_bounds() {
+        use crate::widgets::{mount, unmount, Spacer};
+
+        let id = HeroId::new("mounted-avatar");
+        let mut scope = HeroScope::new(id.clone(), Box::new(Spacer::new(Vec2::splat(40.0))));
+        scope.layout(Vec2::new(10.0, 20.0), Vec2::new(100.0, 100.0));
+        assert!(mounted_hero_bounds(&id).is_none(), "built but never mounted");
+
+        mount(&mut scope);
+        assert_eq!(mounted_hero_bounds(&id).map(|r| r.position()), Some(Vec2::new(10.0, 20.0)));
+        scope.layout(Vec2::new(30.0, 40.0), Vec2::new(100.0, 100.0));
+        assert_eq!(mounted_hero_bounds(&id).map(|r| r.position()), Some(Vec2::new(30.0, 40.0)));
+
+        let mut controller = HeroController::new();
+        assert!(controller.start_registered_flight(id.clone(), HeroRect::new(0.0, 0.0, 200.0, 200.0)));
+
+        unmount(&mut scope);
+        assert!(mounted_hero_bounds(&id).is_none());
+        assert!(!controller.start_registered_flight(id, HeroRect::default()));
+    }
 }
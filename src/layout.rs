@@ -319,6 +319,15 @@ impl BoxConstraints {
         )
     }
     
+    /// The biggest size that satisfies the constraints, using `fallback`
+    /// (clamped to the minimums) on unbounded sides
+    pub fn biggest_or(&self, fallback: Size) -> Size {
+        Size::new(
+            if self.max_width.is_finite() { self.max_width } else { self.constrain_width(fallback.width) },
+            if self.max_height.is_finite() { self.max_height } else { self.constrain_height(fallback.height) },
+        )
+    }
+    
     /// The smallest size that satisfies the constraints
     pub fn smallest(&self) -> Size {
         Size::new(self.min_width, self.min_height)
@@ -417,6 +426,179 @@ impl From<Size> for LayoutResult {
     }
 }
 
+// =============================================================================
+// RECT
+// =============================================================================
+
+/// Axis-aligned screen rectangle
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Rect {
+    /// Rectangle from a top-left position and size
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self { min: position, max: position + size }
+    }
+    
+    pub fn size(&self) -> Vec2 {
+        (self.max - self.min).max(Vec2::ZERO)
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.max.x <= self.min.x || self.max.y <= self.min.y
+    }
+    
+    /// Shrink (or grow, for negative amounts) on every side
+    pub fn inset(&self, amount: f32) -> Self {
+        Self { min: self.min + Vec2::splat(amount), max: self.max - Vec2::splat(amount) }
+    }
+    
+    /// Shrink by per-side insets
+    pub fn deflate(&self, insets: &EdgeInsets) -> Self {
+        Self {
+            min: self.min + Vec2::new(insets.left, insets.top),
+            max: self.max - Vec2::new(insets.right, insets.bottom),
+        }
+    }
+    
+    /// Whether `point` lies inside this rectangle
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.min.x && point.y >= self.min.y
+            && point.x < self.max.x && point.y < self.max.y
+    }
+    
+    /// Whether `other` lies entirely inside this rectangle
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        !self.is_empty()
+            && other.min.x >= self.min.x && other.min.y >= self.min.y
+            && other.max.x <= self.max.x && other.max.y <= self.max.y
+    }
+    
+    /// Whether the two rectangles overlap (touching edges don't count)
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min.x < other.max.x && other.min.x < self.max.x
+            && self.min.y < other.max.y && other.min.y < self.max.y
+    }
+    
+    /// Smallest rectangle containing both
+    pub fn union(&self, other: &Rect) -> Self {
+        Self { min: self.min.min(other.min), max: self.max.max(other.max) }
+    }
+    
+    /// Overlap of the two rectangles (empty when they don't intersect)
+    pub fn intersection(&self, other: &Rect) -> Self {
+        let min = self.min.max(other.min);
+        Self { min, max: self.max.min(other.max).max(min) }
+    }
+}
+
+// =============================================================================
+// LAYOUT CACHE
+// =============================================================================
+
+thread_local! {
+    /// Layout passes stored on this thread since the last `take_layout_count`
+    static LAYOUT_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    /// Bumped to make every `LayoutCache` miss once
+    static LAYOUT_GENERATION: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+    /// Constraints each widget was last laid out with, by address, while
+    /// `record_constraints` is on
+    static RECORDED_CONSTRAINTS: std::cell::RefCell<Option<std::collections::HashMap<usize, BoxConstraints>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Number of cached layouts (subtrees actually laid out rather than
+/// reused) since the last call, e.g. once per frame for the stats overlay
+pub fn take_layout_count() -> usize {
+    LAYOUT_COUNT.with(|count| count.replace(0))
+}
+
+/// Start or stop noting the constraints parents lay each widget out with
+/// (see `Widget::layout_child`), for the `Inspector`
+/// 
+/// Starting drops every cached layout, so the next pass lays out (and
+/// notes) the whole tree.
+pub fn record_constraints(on: bool) {
+    RECORDED_CONSTRAINTS.with(|recorded| *recorded.borrow_mut() = on.then(Default::default));
+    if on {
+        LAYOUT_GENERATION.with(|generation| generation.set(generation.get() + 1));
+    }
+}
+
+/// Constraints `widget` was last laid out with while recording
+pub fn recorded_constraints<W: ?Sized>(widget: &W) -> Option<BoxConstraints> {
+    let key = (widget as *const W).cast::<()>() as usize;
+    RECORDED_CONSTRAINTS.with(|recorded| recorded.borrow().as_ref()?.get(&key).copied())
+}
+
+/// Note the constraints `widget` is being laid out with, if recording
+pub(crate) fn note_constraints<W: ?Sized>(widget: &W, constraints: BoxConstraints) {
+    RECORDED_CONSTRAINTS.with(|recorded| {
+        if let Some(recorded) = recorded.borrow_mut().as_mut() {
+            recorded.insert((widget as *const W).cast::<()>() as usize, constraints);
+        }
+    });
+}
+
+/// Remembers the inputs and result of a widget's last layout pass
+///
+/// Containers use this to skip re-laying out a subtree when it is asked
+/// for the same origin and max size again and nothing inside it changed
+/// (see `Widget::needs_layout`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LayoutCache {
+    origin: Vec2,
+    max_size: Vec2,
+    size: Vec2,
+    valid: bool,
+    /// `LAYOUT_GENERATION` when stored
+    generation: u64,
+}
+
+impl LayoutCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Cached size if the last layout used the same origin and max size
+    pub fn get(&self, origin: Vec2, max_size: Vec2) -> Option<Vec2> {
+        if self.is_valid() && self.origin == origin && self.max_size == max_size {
+            Some(self.size)
+        } else {
+            None
+        }
+    }
+    
+    /// Record the result of a layout pass
+    pub fn store(&mut self, origin: Vec2, max_size: Vec2, size: Vec2) {
+        LAYOUT_COUNT.with(|count| count.set(count.get() + 1));
+        self.origin = origin;
+        self.max_size = max_size;
+        self.size = size;
+        self.valid = true;
+        self.generation = LAYOUT_GENERATION.with(|generation| generation.get());
+    }
+    
+    /// Force the next layout pass to run
+    pub fn invalidate(&mut self) {
+        self.valid = false;
+    }
+    
+    /// Max size passed to the last stored layout pass
+    pub fn max_size(&self) -> Option<Vec2> {
+        self.is_valid().then_some(self.max_size)
+    }
+    
+    /// Whether a layout result has been stored since the last invalidation
+    /// (or `record_constraints` started)
+    pub fn is_valid(&self) -> bool {
+        self.valid && self.generation == LAYOUT_GENERATION.with(|generation| generation.get())
+    }
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -425,6 +607,21 @@ impl From<Size> for LayoutResult {
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_rect_contains() {
+        let outer = Rect::new(Vec2::ZERO, Vec2::new(100.0, 100.0));
+        let inner = Rect::new(Vec2::new(10.0, 10.0), Vec2::new(50.0, 50.0));
+        assert!(outer.contains_rect(&inner));
+        assert!(!inner.contains_rect(&outer));
+        assert!(!outer.inset(20.0).contains_rect(&inner));
+        assert!(!Rect::default().contains_rect(&Rect::default()));
+        assert_eq!(inner.union(&outer), outer);
+        assert!(inner.intersects(&outer));
+        assert!(!inner.intersects(&Rect::new(Vec2::new(60.0, 0.0), Vec2::splat(10.0))));
+        assert_eq!(inner.intersection(&outer), inner);
+        assert!(inner.intersection(&Rect::new(Vec2::new(70.0, 0.0), Vec2::splat(10.0))).is_empty());
+    }
+    
     #[test]
     fn test_tight_constraints() {
         let c = BoxConstraints::tight(Size::new(100.0, 50.0));
@@ -450,6 +647,14 @@ mod tests {
         assert_eq!(c.constrain(Size::new(200.0, 200.0)), Size::new(150.0, 80.0));
     }
     
+    #[test]
+    fn test_biggest_or() {
+        let c = BoxConstraints::new(50.0, 150.0, 30.0, f32::INFINITY);
+        
+        assert_eq!(c.biggest_or(Size::new(10.0, 10.0)), Size::new(150.0, 30.0));
+        assert_eq!(c.biggest_or(Size::new(10.0, 90.0)), Size::new(150.0, 90.0));
+    }
+    
     #[test]
     fn test_deflate() {
         let c = BoxConstraints::tight(Size::new(100.0, 100.0));
@@ -466,4 +671,19 @@ mod tests {
         assert_eq!(insets.vertical(), 40.0);
         assert_eq!(insets.top_left(), Offset::new(10.0, 20.0));
     }
+    
+    #[test]
+    fn test_layout_cache() {
+        let mut cache = LayoutCache::new();
+        assert!(!cache.is_valid());
+        assert_eq!(cache.get(Vec2::ZERO, Vec2::splat(100.0)), None);
+        
+        cache.store(Vec2::ZERO, Vec2::splat(100.0), Vec2::new(40.0, 20.0));
+        assert_eq!(cache.get(Vec2::ZERO, Vec2::splat(100.0)), Some(Vec2::new(40.0, 20.0)));
+        assert_eq!(cache.get(Vec2::new(1.0, 0.0), Vec2::splat(100.0)), None);
+        assert_eq!(cache.get(Vec2::ZERO, Vec2::splat(90.0)), None);
+        
+        cache.invalidate();
+        assert_eq!(cache.get(Vec2::ZERO, Vec2::splat(100.0)), None);
+    }
 }
@@ -6,10 +6,12 @@ pub mod renderer;
 pub mod widget;       // Legacy - will be deprecated
 pub mod widgets;      // New modular widget system
 pub mod text;
+pub mod bidi;         // Right-to-left reordering and Arabic shaping
 pub mod state;
 pub mod property;
 pub mod layout;       // New constraint-based layout primitives
 pub mod focus;        // Focus management and keyboard navigation
+pub mod events;       // Hit-tested pointer event routing
 pub mod clipboard;    // Clipboard copy/paste support
 pub mod accessibility; // Screen reader and assistive technology support
 pub mod animation;    // Animation system with curves and springs
@@ -23,6 +25,9 @@ pub mod video;        // Video playback abstraction
 pub mod widget_id;    // Widget identity and context system
 pub mod reactive;     // Reactive data binding system
 pub mod panel_style;  // Panel presets and shapes
+pub mod path;         // Vector path tessellation
+pub mod plugin;       // Post-processing renderer plugins
+pub mod background;   // Pluggable window background
 pub mod dashboard;    // Dashboard framework
 pub mod ai;           // AI backend integration
 pub mod task;         // Task system with notifications
@@ -32,19 +37,28 @@ pub mod persistence;  // Save/load workspace state
 pub mod shortcuts;    // Keyboard shortcut management
 pub mod hover;        // Hover effects and animations
 pub mod effects;      // GPU shader effects (glow, blur, glass)
+pub mod redraw;       // Event-driven redraw scheduling
+pub mod profiler;     // CPU/GPU frame timings
+pub mod recorder;     // Event recording and deterministic replay
+pub mod declarative;  // JSON UI definitions with hot reload
+#[cfg(feature = "tray")]
+pub mod tray;         // System tray icon and minimize-to-tray
 
 use winit::window::Window;
 // use winit::event::Event;
 pub use glam::{Vec2, Vec4, Mat4};
 
 // Re-export layout primitives for convenience
-pub use layout::{Size, Offset, BoxConstraints, EdgeInsets, LayoutResult};
+pub use layout::{Size, Offset, BoxConstraints, EdgeInsets, LayoutResult, LayoutCache, Rect, take_layout_count};
 
 // Re-export focus primitives
 pub use focus::{FocusId, FocusManager, FocusNode, Focusable};
 
+// Re-export event routing
+pub use events::EventDispatcher;
+
 // Re-export clipboard functions
-pub use clipboard::{copy_to_clipboard, paste_from_clipboard};
+pub use clipboard::{copy_to_clipboard, paste_from_clipboard, ClipboardAction};
 
 // Re-export animation types
 pub use animation::{
@@ -53,7 +67,7 @@ pub use animation::{
 };
 
 // Re-export style types
-pub use style::{WidgetStyle, ButtonVariant, StyleSheet, SizeVariant};
+pub use style::{WidgetStyle, TextStyle, ButtonVariant, StyleSheet, SizeVariant};
 
 // Re-export command types
 pub use commands::{Command, CommandHistory};
@@ -75,6 +89,10 @@ pub use reactive::{Reactive, Computed, Property, ColorSource, AnimationTrigger};
 
 // Re-export panel style types (v2)
 pub use panel_style::{PanelPreset, PanelShape, PanelStyle, PathCommand};
+pub use path::{PathMesh, PolylineJoin};
+pub use plugin::{RendererPlugin, Vignette};
+pub use background::Background;
+pub use text::{FontError, FontId, TextLayout, TextLine, TextOverflow};
 
 // Re-export dashboard types (v2)
 pub use dashboard::{Dashboard, DashboardPanel, DashboardLayout, DashboardTemplate, SizeHint, PositionHint, Edge};
@@ -85,19 +103,37 @@ pub use ai::{AiBackend, NpuBackend, OllamaClient, LocalAiAgent, AgentId, AgentSt
 // Re-export task types (v2)
 pub use task::{Task, TaskId, TaskStatus, TaskPanel, TaskManager, NotificationSound};
 
+// Re-export redraw scheduling types
+pub use redraw::{RedrawMode, RedrawScheduler, RedrawWaker};
+
+// Re-export renderer settings
+pub use renderer::{AntiAliasing, BlurDownsample, BlurSettings, GlassQuality, TextureId, TextureError, ImageInstance, Gradient, RenderCache, release_texture};
+
+// Re-export profiling types
+pub use profiler::{FrameTimings, GlyphCacheStats, PerfStage, RenderStats};
+
+// Re-export event recording types
+pub use recorder::{EventRecorder, EventReplayer, Recording, RecordedEvent, RecordedFrame, RecorderError};
+
+// Re-export declarative UI types
+pub use declarative::{UiNode, Keyed, KeyedList, ListDiff, HotReloadView, DeclarativeError};
+
 // Re-export workspace types (v2)
 pub use workspace::{Workspace, WorkspacePanel, WorkspaceLayout, WorkspaceManager, SnapTarget, SnapEdge, TileMode};
 
 pub struct GlassContext {
     pub renderer: renderer::GlassRenderer,
+    /// Surface size in physical pixels (see `logical_size` for layout)
     pub width: u32,
     pub height: u32,
 }
 
 impl GlassContext {
-    pub async fn new(window: &Window) -> Self {
+    /// Create a context whose renderer uses the given anti-aliasing
+    /// (`AntiAliasing::default()` for shader edge smoothing only)
+    pub async fn new(window: &Window, antialiasing: AntiAliasing) -> Self {
         let size = window.inner_size();
-        let renderer = renderer::GlassRenderer::new(window).await;
+        let renderer = renderer::GlassRenderer::with_antialiasing(window, antialiasing).await;
         
         Self {
             renderer,
@@ -116,7 +152,55 @@ impl GlassContext {
         self.renderer.update(dt);
     }
     
+    /// Physical pixels per logical pixel
+    pub fn scale_factor(&self) -> f32 {
+        self.renderer.scale_factor()
+    }
+    
+    /// Forward `WindowEvent::ScaleFactorChanged`
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.renderer.set_scale_factor(scale_factor);
+    }
+    
+    /// Override the window's scale factor (`None` follows the window)
+    pub fn set_ui_scale(&mut self, scale: Option<f32>) {
+        self.renderer.set_ui_scale(scale);
+    }
+    
+    /// Window size in logical pixels, to lay widgets out in
+    pub fn logical_size(&self) -> Vec2 {
+        self.renderer.logical_size()
+    }
+    
+    /// Convert a physical cursor position from winit to logical pixels
+    pub fn to_logical(&self, position: winit::dpi::PhysicalPosition<f64>) -> Vec2 {
+        self.renderer.to_logical(position)
+    }
+    
     pub fn render(&mut self, root_widget: &mut dyn widget::Widget) {
         self.renderer.render(root_widget);
     }
+    
+    /// Render several root widgets back to front (see `GlassRenderer::render_layers`)
+    pub fn render_layers(&mut self, layers: &mut [&mut dyn widget::Widget]) {
+        self.renderer.render_layers(layers);
+    }
+    
+    /// Run `f` and record how long it took as a CPU stage timing
+    pub fn time<R>(&mut self, stage: PerfStage, f: impl FnOnce() -> R) -> R {
+        let start = std::time::Instant::now();
+        let result = f();
+        self.renderer.record_timing(stage, start.elapsed().as_secs_f32() * 1000.0);
+        result
+    }
+    
+    /// Most recent per-stage frame timings
+    pub fn frame_timings(&self) -> FrameTimings {
+        self.renderer.frame_timings()
+    }
+    
+    /// Timings and primitive counts of the last frame
+    pub fn frame_stats(&self) -> RenderStats {
+        self.renderer.frame_stats()
+    }
 }
@@ -1,23 +1,35 @@
 //! GlassUI Declarative Macros
 //!
-//! Provides ergonomic macros for building widget hierarchies:
-//! - `ui!` - Main declarative macro for widget trees
-//! - `column!` / `row!` - Quick layout helpers
+//! Provides ergonomic macros for building widget hierarchies without the
+//! `Box::new(...)` nesting:
+//! - `column!` / `row!` / `stack!` / `grid!` - Layout containers
+//! - `panel!` - Glass panel around a column of children
+//! - `label!` / `button!` / `spacer!` - Common leaves
+//! - `align!` / `center!` / `scroll!` / `tabs!` / `tooltip!` - Wrappers
 //! - `style!` - Style builder macro
 //!
+//! Each macro expands to the same builder calls you'd write by hand, so
+//! the result is an ordinary widget (not a box) and can be chained further.
+//! `WidgetExt` adds the wrappers as methods (`.draggable()`, `.aligned(..)`).
+//!
 //! # Example
 //! ```rust
-//! use glassui::macros::*;
+//! use glassui::{column, row, label, button, spacer};
 //!
 //! let ui = column![
-//!     Label::new("Hello, GlassUI!"),
+//!     label!("Hello, GlassUI!", size: 24.0),
+//!     spacer!(0.0, 8.0),
 //!     row![
-//!         Button::new("OK").with_callback(|| {}),
-//!         Button::new("Cancel"),
+//!         button!("OK", on_click: || println!("OK")),
+//!         button!("Cancel"),
 //!     ],
 //! ];
 //! ```
 
+// =============================================================================
+// LAYOUT MACROS
+// =============================================================================
+
 /// Create a Column layout with children
 /// 
 /// # Example
@@ -27,6 +39,7 @@
 ///     Label::new("Second"),
 ///     Button::new("Click"),
 /// ];
+/// let spaced = column![spacing: 8.0; Label::new("A"), Label::new("B")];
 /// ```
 #[macro_export]
 macro_rules! column {
@@ -37,18 +50,18 @@ macro_rules! column {
     
     // Column with spacing
     (spacing: $spacing:expr; $($child:expr),* $(,)?) => {{
-        let mut col = $crate::widgets::Column::new().with_spacing($spacing);
+        let col = $crate::widgets::Column::new().with_spacing($spacing);
         $(
-            col = col.add_child(Box::new($child));
+            let col = col.add_child(Box::new($child));
         )*
         col
     }};
     
     // Column with children
     ($($child:expr),* $(,)?) => {{
-        let mut col = $crate::widgets::Column::new();
+        let col = $crate::widgets::Column::new();
         $(
-            col = col.add_child(Box::new($child));
+            let col = col.add_child(Box::new($child));
         )*
         col
     }};
@@ -60,7 +73,7 @@ macro_rules! column {
 /// ```rust
 /// let row = row![
 ///     Button::new("Left"),
-///     Spacer::new(),
+///     spacer!(8.0, 0.0),
 ///     Button::new("Right"),
 /// ];
 /// ```
@@ -73,24 +86,24 @@ macro_rules! row {
     
     // Row with spacing
     (spacing: $spacing:expr; $($child:expr),* $(,)?) => {{
-        let mut r = $crate::widgets::Row::new().with_spacing($spacing);
+        let r = $crate::widgets::Row::new().with_spacing($spacing);
         $(
-            r = r.add_child(Box::new($child));
+            let r = r.add_child(Box::new($child));
         )*
         r
     }};
     
     // Row with children
     ($($child:expr),* $(,)?) => {{
-        let mut r = $crate::widgets::Row::new();
+        let r = $crate::widgets::Row::new();
         $(
-            r = r.add_child(Box::new($child));
+            let r = r.add_child(Box::new($child));
         )*
         r
     }};
 }
 
-/// Create a Stack layout with children
+/// Create a Stack layout with children (last on top)
 /// 
 /// # Example
 /// ```rust
@@ -102,19 +115,19 @@ macro_rules! row {
 #[macro_export]
 macro_rules! stack {
     ($($child:expr),* $(,)?) => {{
-        let mut s = $crate::widgets::Stack::new();
+        let s = $crate::widgets::Stack::new();
         $(
-            s = s.add_child(Box::new($child));
+            let s = s.add_child(Box::new($child));
         )*
         s
     }};
 }
 
-/// Create a Grid layout
+/// Create a Grid layout with a fixed number of columns
 /// 
 /// # Example
 /// ```rust
-/// let grid = grid!(2, 2;
+/// let grid = grid!(2;
 ///     Button::new("1"),
 ///     Button::new("2"),
 ///     Button::new("3"),
@@ -123,15 +136,56 @@ macro_rules! stack {
 /// ```
 #[macro_export]
 macro_rules! grid {
-    ($cols:expr, $rows:expr; $($child:expr),* $(,)?) => {{
-        let mut g = $crate::widgets::Grid::new($cols, $rows);
+    ($cols:expr; $($child:expr),* $(,)?) => {{
+        let g = $crate::widgets::Grid::new($cols);
         $(
-            g = g.add_child(Box::new($child));
+            let g = g.add_child(Box::new($child));
         )*
         g
     }};
 }
 
+/// Create a glass panel; several children are stacked in a Column
+/// 
+/// # Example
+/// ```rust
+/// let panel = panel![
+///     Label::new("Inside Panel"),
+///     Button::new("Action"),
+/// ];
+/// let tinted = panel!(color: Vec4::new(0.06, 0.06, 0.1, 0.92);
+///     Label::new("Tinted"),
+/// );
+/// ```
+#[macro_export]
+macro_rules! panel {
+    (color: $color:expr; $($child:expr),* $(,)?) => {
+        $crate::panel![$($child),*].with_color($color)
+    };
+    
+    ($($child:expr),* $(,)?) => {
+        // Panel takes a single child, so wrap in Column
+        $crate::widgets::Panel::new(Box::new($crate::column![$($child),*]))
+    };
+}
+
+// =============================================================================
+// WRAPPER MACROS
+// =============================================================================
+
+/// Align a widget within the space its parent gives it
+/// 
+/// # Example
+/// ```rust
+/// let corner = align!(BottomRight; Button::new("Help"));
+/// ```
+#[macro_export]
+macro_rules! align {
+    ($alignment:ident; $child:expr) => {
+        $crate::widgets::Align::new($crate::widgets::Alignment::$alignment, Box::new($child))
+    };
+}
+
 /// Create a centered widget
 /// 
 /// # Example
@@ -141,10 +195,7 @@ macro_rules! grid {
 #[macro_export]
 macro_rules! center {
     ($child:expr) => {
-        $crate::widgets::Align::new(
-            Box::new($child),
-            $crate::widgets::Alignment::Center
-        )
+        $crate::widgets::Align::center(Box::new($child))
     };
 }
 
@@ -158,32 +209,95 @@ macro_rules! center {
 #[macro_export]
 macro_rules! padding {
     // All sides
-    ($all:expr; $child:expr) => {{
-        // TODO: Implement Padding widget wrapper
-        // For now, we'll return the child as-is
-        // In a full implementation, this would wrap with EdgeInsets
-        $child
-    }};
+    ($all:expr; $child:expr) => {
+        $crate::widgets::Padding::new($crate::layout::EdgeInsets::all($all), Box::new($child))
+    };
     
     // Horizontal, Vertical
-    ($h:expr, $v:expr; $child:expr) => {{
-        $child
+    ($h:expr, $v:expr; $child:expr) => {
+        $crate::widgets::Padding::new($crate::layout::EdgeInsets::symmetric($h, $v), Box::new($child))
+    };
+}
+
+/// Create a scrollable area
+/// 
+/// # Example
+/// ```rust
+/// let scroll = scroll!(column![
+///     Label::new("Item 1"),
+///     Label::new("Item 2"),
+///     // ... many items
+/// ]);
+/// ```
+#[macro_export]
+macro_rules! scroll {
+    ($child:expr) => {
+        $crate::widgets::ScrollArea::new(Box::new($child))
+    };
+}
+
+/// Create a TabBar with tabs
+/// 
+/// # Example
+/// ```rust
+/// let tabs = tabs![
+///     "Home" => column![Label::new("Home content")],
+///     "Settings" => column![Label::new("Settings content")],
+/// ];
+/// ```
+#[macro_export]
+macro_rules! tabs {
+    ($($name:expr => $content:expr),* $(,)?) => {{
+        let tb = $crate::widgets::TabBar::new();
+        $(
+            let tb = tb.add_tab($name, Box::new($content));
+        )*
+        tb
     }};
 }
 
+/// Create a tooltip wrapper
+/// 
+/// # Example  
+/// ```rust
+/// let btn = tooltip!("Click to submit"; Button::new("Submit"));
+/// ```
+#[macro_export]
+macro_rules! tooltip {
+    ($text:expr; $child:expr) => {
+        $crate::widgets::Tooltip::new(Box::new($child), $text)
+    };
+}
+
+// =============================================================================
+// LEAF MACROS
+// =============================================================================
+
 /// Quick label creation
 /// 
 /// # Example
 /// ```rust
 /// let label = label!("Hello");
 /// let styled = label!("Big", 24.0);
+/// let colored = label!("Warning", size: 14.0, color: Vec4::new(1.0, 0.8, 0.2, 1.0));
 /// ```
 #[macro_export]
 macro_rules! label {
+    (@set $l:expr, size, $value:expr) => { $l.with_size($value) };
+    (@set $l:expr, color, $value:expr) => { $l.with_color($value) };
+    
     ($text:expr) => {
         $crate::widgets::Label::new($text)
     };
     
+    ($text:expr, $($key:ident : $value:expr),+ $(,)?) => {{
+        let l = $crate::widgets::Label::new($text);
+        $(
+            let l = $crate::label!(@set l, $key, $value);
+        )+
+        l
+    }};
+    
     ($text:expr, $size:expr) => {
         $crate::widgets::Label::new($text).with_size($size)
     };
@@ -193,6 +307,7 @@ macro_rules! label {
 /// 
 /// # Example
 /// ```rust
+/// let btn = button!("Click Me", on_click: || println!("Clicked!"));
 /// let btn = button!("Click Me" => || println!("Clicked!"));
 /// let btn = button!("Plain");
 /// ```
@@ -202,82 +317,42 @@ macro_rules! button {
         $crate::widgets::Button::new($text)
     };
     
+    ($text:expr, on_click: $callback:expr $(,)?) => {
+        $crate::widgets::Button::new($text).with_callback($callback)
+    };
+    
     ($text:expr => $callback:expr) => {
         $crate::widgets::Button::new($text).with_callback($callback)
     };
 }
 
-/// Create a panel with children
+/// Fixed-size gap
 /// 
 /// # Example
 /// ```rust
-/// let panel = panel![
-///     Label::new("Inside Panel"),
-///     Button::new("Action"),
-/// ];
+/// let gap = spacer!(8.0);        // 8 x 8
+/// let line = spacer!(0.0, 12.0); // 12 tall, for columns
 /// ```
 #[macro_export]
-macro_rules! panel {
-    ($($child:expr),* $(,)?) => {{
-        // Panel takes a single child, so wrap in Column if multiple
-        let content = column![$($child),*];
-        $crate::widgets::Panel::new(Box::new(content))
-    }};
+macro_rules! spacer {
+    () => {
+        $crate::widgets::Spacer::new($crate::Vec2::ZERO)
+    };
     
-    (size: $w:expr, $h:expr; $($child:expr),* $(,)?) => {{
-        let content = column![$($child),*];
-        $crate::widgets::Panel::new(Box::new(content))
-            .with_size($w, $h)
-    }};
-}
-
-/// Create a scrollable area
-/// 
-/// # Example
-/// ```rust
-/// let scroll = scroll!(height: 300.0;
-///     column![
-///         Label::new("Item 1"),
-///         Label::new("Item 2"),
-///         // ... many items
-///     ]
-/// );
-/// ```
-#[macro_export]
-macro_rules! scroll {
-    (height: $h:expr; $child:expr) => {
-        $crate::widgets::ScrollArea::new(Box::new($child), $h)
+    ($size:expr) => {
+        $crate::widgets::Spacer::new($crate::Vec2::splat($size))
+    };
+    
+    ($width:expr, $height:expr) => {
+        $crate::widgets::Spacer::new($crate::Vec2::new($width, $height))
     };
-}
-
-/// Create a TabBar with tabs
-/// 
-/// # Example
-/// ```rust
-/// let tabs = tabs![
-///     "Home" => column![Label::new("Home content")],
-///     "Settings" => column![Label::new("Settings content")],
-/// ];
-/// ```
-#[macro_export]
-macro_rules! tabs {
-    ($($name:expr => $content:expr),* $(,)?) => {{
-        let mut tb = $crate::widgets::TabBar::new();
-        $(
-            tb = tb.add_tab($name, Box::new($content));
-        )*
-        tb
-    }};
 }
 
 /// Conditional widget inclusion
 /// 
 /// # Example
 /// ```rust
-/// let ui = column![
-///     Label::new("Always shown"),
-///     when!(show_button; Button::new("Conditional")),
-/// ];
+/// let extra = when!(show_button; Button::new("Conditional"));
 /// ```
 #[macro_export]
 macro_rules! when {
@@ -290,36 +365,18 @@ macro_rules! when {
     };
 }
 
-/// Create a tooltip wrapper
-/// 
-/// # Example  
-/// ```rust
-/// let btn = tooltip!("Click to submit"; Button::new("Submit"));
-/// ```
-#[macro_export]
-macro_rules! tooltip {
-    ($text:expr; $child:expr) => {
-        $crate::widgets::Tooltip::new(Box::new($child), $text)
-    };
-}
-
-/// Quick spacing
-#[macro_export]
-macro_rules! spacer {
-    () => {
-        $crate::widgets::Spacer::new()
-    };
-    
-    ($size:expr) => {
-        $crate::widgets::Spacer::fixed($size)
-    };
-}
-
 // =============================================================================
 // FLUENT BUILDER EXTENSIONS
 // =============================================================================
 
 /// Trait extension for fluent widget building
+/// 
+/// ```rust
+/// let panel = panel![label!("Charts")]
+///     .resizable(Vec2::new(560.0, 220.0))
+///     .draggable()
+///     .aligned(Alignment::TopLeft);
+/// ```
 pub trait WidgetExt: Sized {
     /// Wrap this widget in a Tooltip
     fn with_tooltip(self, text: &str) -> crate::widgets::Tooltip;
@@ -327,16 +384,95 @@ pub trait WidgetExt: Sized {
     /// Make this widget draggable
     fn draggable(self) -> crate::widgets::Draggable;
     
-    /// Make this widget resizable
-    fn resizable(self) -> crate::widgets::Resizable;
+    /// Make this widget resizable, starting at `size`
+    fn resizable(self, size: crate::Vec2) -> crate::widgets::Resizable;
+    
+    /// Align this widget in its parent's space
+    fn aligned(self, alignment: crate::widgets::Alignment) -> crate::widgets::Align;
     
     /// Center this widget
     fn centered(self) -> crate::widgets::Align;
+    
+    /// Keep this widget at a fixed width/height ratio
+    fn aspect_ratio(self, ratio: f32) -> crate::widgets::AspectRatio;
+    
+    /// Size this widget relative to its parent (`Length::Fill`,
+    /// `Length::Percent(0.5)`, or pixels)
+    fn sized(self, width: impl Into<crate::widgets::Length>, height: impl Into<crate::widgets::Length>) -> crate::widgets::SizedBox;
+    
+    /// Place this widget by edge distances inside a `Stack` (chain
+    /// `with_left`, `with_top`, ...)
+    fn positioned(self) -> crate::widgets::Positioned;
+    
+    /// Surround this widget with empty space (see `EdgeInsets`)
+    fn padded(self, insets: crate::layout::EdgeInsets) -> crate::widgets::Padding;
+    
+    /// Tag this widget so it can be found later (see `Widget::find_mut`)
+    fn identified(self, id: crate::WidgetId) -> crate::widgets::Identified;
+    
+    /// Show this widget only while `state` holds true
+    fn visible_when(self, state: &crate::state::State<bool>) -> crate::widgets::Visible;
+    
+    /// Let pointer events through this widget to whatever is beneath
+    fn pass_through(self) -> crate::widgets::PassThrough;
+    
+    /// Box this widget for a container's child list
+    fn boxed(self) -> Box<dyn crate::widgets::Widget>;
 }
 
-// Note: Implementation would require `where Self: Widget + 'static`
-// which needs the Widget trait to be object-safe in specific ways.
-// This serves as a design document for the API.
+impl<W: crate::widgets::Widget + 'static> WidgetExt for W {
+    fn with_tooltip(self, text: &str) -> crate::widgets::Tooltip {
+        crate::widgets::Tooltip::new(Box::new(self), text)
+    }
+    
+    fn draggable(self) -> crate::widgets::Draggable {
+        crate::widgets::Draggable::new(Box::new(self))
+    }
+    
+    fn resizable(self, size: crate::Vec2) -> crate::widgets::Resizable {
+        crate::widgets::Resizable::new(Box::new(self), size)
+    }
+    
+    fn aligned(self, alignment: crate::widgets::Alignment) -> crate::widgets::Align {
+        crate::widgets::Align::new(alignment, Box::new(self))
+    }
+    
+    fn centered(self) -> crate::widgets::Align {
+        crate::widgets::Align::center(Box::new(self))
+    }
+    
+    fn aspect_ratio(self, ratio: f32) -> crate::widgets::AspectRatio {
+        crate::widgets::AspectRatio::new(ratio, Box::new(self))
+    }
+    
+    fn sized(self, width: impl Into<crate::widgets::Length>, height: impl Into<crate::widgets::Length>) -> crate::widgets::SizedBox {
+        crate::widgets::SizedBox::new(width, height, Box::new(self))
+    }
+    
+    fn positioned(self) -> crate::widgets::Positioned {
+        crate::widgets::Positioned::new(Box::new(self))
+    }
+    
+    fn padded(self, insets: crate::layout::EdgeInsets) -> crate::widgets::Padding {
+        crate::widgets::Padding::new(insets, Box::new(self))
+    }
+    
+    fn identified(self, id: crate::WidgetId) -> crate::widgets::Identified {
+        crate::widgets::Identified::new(id, Box::new(self))
+    }
+    
+    fn visible_when(self, state: &crate::state::State<bool>) -> crate::widgets::Visible {
+        crate::widgets::Visible::bound(state, Box::new(self))
+    }
+    
+    fn pass_through(self) -> crate::widgets::PassThrough {
+        crate::widgets::PassThrough::new(Box::new(self))
+    }
+    
+    fn boxed(self) -> Box<dyn crate::widgets::Widget> {
+        Box::new(self)
+    }
+}
 
 // =============================================================================
 // STYLE MACRO
@@ -414,6 +550,45 @@ pub mod prelude {
     pub use crate::animation::*;
     pub use crate::style::*;
     pub use crate::focus::*;
+    pub use super::WidgetExt;
     
     // Re-export macros (they're already at crate root due to #[macro_export])
 }
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::WidgetExt;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use crate::widgets::{Alignment, Label, Panel, Widget, count_widgets};
+    use crate::{Vec2, Vec4, WidgetId};
+
+    #[test]
+    fn test_builder_macros_match_hand_built_tree() {
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        let title = WidgetId::new();
+        let panel: Panel = panel!(color: Vec4::ONE;
+            label!("Title", size: 18.0, color: Vec4::ZERO).identified(title),
+            spacer!(0.0, 8.0),
+            row![spacing: 4.0; button!("OK", on_click: move || counter.set(counter.get() + 1)), button!("Cancel")],
+            grid!(2; label!("a"), label!("b"), label!("c")),
+        );
+        assert_eq!(panel.color, Vec4::ONE);
+
+        let mut root: Box<dyn Widget> = panel.draggable().aligned(Alignment::TopLeft).boxed();
+        // Align > Draggable > Panel > Column > (Identified > Label, Spacer, Row > 2, Grid > 3)
+        assert_eq!(count_widgets(root.as_ref()), 14);
+        let label = root.find::<Label>(title).unwrap();
+        assert_eq!((label.text.as_str(), label.font_size, label.color), ("Title", 18.0, Some(Vec4::ZERO)));
+
+        let ok = root.first_of_type_mut::<crate::widgets::Button>().unwrap();
+        (ok.on_click.as_mut().unwrap())();
+        assert_eq!(clicks.get(), 1);
+        assert_eq!(spacer!(8.0).size, Vec2::splat(8.0));
+    }
+}
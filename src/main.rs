@@ -8,40 +8,66 @@
 //! - Charts, Controls, and more
 
 use winit::event::{Event, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop};
+use winit::event_loop::EventLoop;
 use winit::window::WindowBuilder;
-use glassui::GlassContext;
+use glassui::{AntiAliasing, GlassContext};
+use glassui::{stack, row, panel, label, button, spacer};
+use glassui::macros::WidgetExt;
 use glassui::widgets::{
-    Widget, Panel, Button, Label, Slider, Checkbox, Column, Row, Stack, 
-    Align, Alignment, Spacer, Draggable, Resizable,
-    TextInput, Dropdown, Toggle, ProgressBar, NumberInput, RadioGroup,
+    Widget, Panel, Slider, Checkbox, Alignment, DockPanel, Dock,
+    TextInput, Dropdown, Combobox, Toggle, ProgressBar, NumberInput, RadioGroup,
     ScrollArea, TabBar, Tooltip,
     Table, TableColumn, TableRow, ListView, ListItem,
     LineChart, BarChart, PieChart, Sparkline,
     RichText, RichTextEditor,
     // V2 Widgets
-    StatusBar, ToastContainer, AgentCard, Toast, ToastType,
+    StatusBar, ToastContainer, ToastAnchor, AgentCard, Toast, ToastType,
     CommandPalette, Command,
     Timeline, TimelineEntry, TimelineEntryType,
     AnimatedProgressBar, CircularGauge, MetricDisplay, MetricTrend,
     Tab, TabView,
     FileNode, FileTree,
     GridColumn, GridRow, DataTable, CellValue,
-    ControllablePanel,
+    ControllablePanel, PerfOverlay, FrameStats, Inspector, DialogManager, TitleBar, borderless_window_builder, update_parallel, count_widgets, mount, set_debug_layout, debug_layout,
 };
-use glassui::ai::AgentState;
+use glassui::ai::{AgentState, OllamaClient};
+use glassui::persistence::PersistenceManager;
 use glassui::shortcuts::{ShortcutManager, Shortcut, ShortcutKey};
-use glassui::{Vec2, Vec4};
+use glassui::{Vec2, Vec4, RedrawMode, RedrawScheduler, PerfStage, EventRecorder, EventReplayer, EventDispatcher, Vignette, take_layout_count};
+use std::time::Instant;
 
 fn main() {
     env_logger::init();
     let event_loop = EventLoop::new().unwrap();
-    let window = WindowBuilder::new()
-        .with_title("GlassUI v2 Dashboard Demo")
+    // Redraw only on input/animation; pass --continuous to poll every frame
+    let redraw_mode = if std::env::args().any(|a| a == "--continuous") {
+        RedrawMode::Continuous
+    } else {
+        RedrawMode::OnDemand
+    };
+    let mut scheduler = RedrawScheduler::new(redraw_mode).with_proxy(event_loop.create_proxy());
+    // --borderless draws our own title bar over a translucent window
+    let borderless = std::env::args().any(|a| a == "--borderless");
+    // --msaa steadies edges of dragged panels and paths with 4x multisampling
+    let antialiasing = if std::env::args().any(|a| a == "--msaa") { AntiAliasing::Msaa4x } else { AntiAliasing::Off };
+    let title = "GlassUI v2 Dashboard Demo";
+    let builder = if borderless { borderless_window_builder(title) } else { WindowBuilder::new().with_title(title) };
+    let window = builder
         .with_inner_size(winit::dpi::LogicalSize::new(1600.0, 900.0))
         .build(&event_loop).unwrap();
     
-    let mut context = pollster::block_on(GlassContext::new(&window));
+    let mut context = pollster::block_on(GlassContext::new(&window, antialiasing));
+    let mut title_bar = TitleBar::new(title).with_icon("◆");
+    if borderless && !context.renderer.set_background_opacity(0.75) {
+        log::info!("Surface doesn't support alpha; window stays opaque");
+    }
+    let chrome_height = if borderless { title_bar.height } else { 0.0 };
+    // --vignette runs the built-in post-processing plugin
+    if std::env::args().any(|a| a == "--vignette") {
+        if let Err(e) = context.renderer.add_plugin(Vignette::default()) {
+            log::warn!("Vignette unavailable: {}", e);
+        }
+    }
     
     // =========================================================================
     // KEYBOARD SHORTCUTS
@@ -51,7 +77,7 @@ fn main() {
     shortcuts.register_dashboard_shortcuts();
     
     // =========================================================================
-    // STATUS BAR (TOP)
+    // STATUS BAR (BOTTOM)
     // =========================================================================
     
     let mut status_bar = StatusBar::dashboard_default();
@@ -60,16 +86,51 @@ fn main() {
     // TOAST CONTAINER (TOP RIGHT)
     // =========================================================================
     
-    let mut toasts = ToastContainer::new().position_top_right(1600.0, 16.0);
+    let mut toasts = ToastContainer::new().with_anchor(ToastAnchor::TopRight);
     // Add initial toast
     toasts.info("Welcome", "GlassUI v2 Dashboard loaded");
     
+    // =========================================================================
+    // SYSTEM TRAY (--features tray)
+    // =========================================================================
+    
+    // GlassUI ships no platform tray, so the demo uses the headless backend
+    // (notifications are logged); apps plug in their tray library's backend.
+    // With no icon to click the window couldn't come back, so closing or
+    // minimizing to the tray is opt-in with --close-to-tray.
+    #[cfg(feature = "tray")]
+    let tray_notifications = std::rc::Rc::new(std::cell::Cell::new(true));
+    #[cfg(feature = "tray")]
+    let mut tray = {
+        use glassui::tray::{HeadlessTray, Tray, TrayIconImage};
+        let to_tray = std::env::args().any(|a| a == "--close-to-tray");
+        let enabled = tray_notifications.clone();
+        Tray::new(Box::new(HeadlessTray::new()), TrayIconImage::solid(32, [0, 200, 255, 255]))
+            .with_tooltip(title)
+            .with_menu(vec![
+                glassui::widgets::MenuItem::new("Notifications")
+                    .checkable(true)
+                    .with_callback(move || enabled.set(!enabled.get())),
+            ])
+            .with_close_to_tray(to_tray)
+            .with_minimize_to_tray(to_tray)
+    };
+    // Toasts newer than this are forwarded while the window is hidden
+    #[cfg(feature = "tray")]
+    let mut last_toast_id = 0;
+    
     // =========================================================================
     // COMMAND PALETTE (OVERLAY)
     // =========================================================================
     
+    // Recently used commands are kept in the app state between runs
+    let mut persistence = PersistenceManager::new().with_path("glassui-state.json");
+    if let Err(e) = persistence.load() {
+        log::error!("Failed to load app state: {}", e);
+    }
     let mut command_palette = CommandPalette::new()
         .with_dashboard_commands()
+        .with_recents(persistence.state().command_recents.clone())
         .center_on_screen(Vec2::new(1600.0, 900.0));
     
     // =========================================================================
@@ -79,6 +140,12 @@ fn main() {
     let mut agent_card = AgentCard::new("Assistant", "phi3");
     agent_card.set_state(AgentState::Idle);
     
+    // Known models to pick from, or type any other tag
+    let model_selector = Combobox::new("Model")
+        .with_options(OllamaClient::new("phi3").list_models())
+        .with_value("phi3")
+        .on_change(|model| log::info!("Model selected: {}", model));
+    
     // =========================================================================
     // TAB VIEW (NEW V2)
     // =========================================================================
@@ -136,17 +203,13 @@ fn main() {
         .with_data("Sales", &[80.0, 120.0, 90.0, 140.0, 100.0])
         .with_size(260.0, 150.0);
     
-    let charts_content = Column::new()
-        .add_child(Box::new(Label::new("📊 Charts")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(Row::new()
-            .add_child(Box::new(line_chart))
-            .add_child(Box::new(Spacer::new(Vec2::new(8.0, 0.0))))
-            .add_child(Box::new(bar_chart))
-        ));
+    let panel_color = Vec4::new(0.06, 0.06, 0.1, 0.92);
     
-    let charts_panel = Panel::new(Box::new(charts_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
+    let charts_panel = panel!(color: panel_color;
+        label!("📊 Charts"),
+        spacer!(0.0, 8.0),
+        row![line_chart, spacer!(8.0, 0.0), bar_chart],
+    );
     
     // =========================================================================
     // ORIGINAL CONTROLS PANEL
@@ -159,19 +222,17 @@ fn main() {
         "Low".to_string(), "Medium".to_string(), "High".to_string()
     ]).with_selected(1);
     
-    let controls_content = Column::new()
-        .add_child(Box::new(Label::new("🎛 Controls")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(orig_progress))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 6.0))))
-        .add_child(Box::new(toggle))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 6.0))))
-        .add_child(Box::new(number))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 6.0))))
-        .add_child(Box::new(radio));
-    
-    let controls_panel = Panel::new(Box::new(controls_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
+    let controls_panel = panel!(color: panel_color;
+        label!("🎛 Controls"),
+        spacer!(0.0, 8.0),
+        orig_progress,
+        spacer!(0.0, 6.0),
+        toggle,
+        spacer!(0.0, 6.0),
+        number,
+        spacer!(0.0, 6.0),
+        radio,
+    );
     
     // =========================================================================
     // INPUT PANEL
@@ -184,125 +245,106 @@ fn main() {
         "Option A".to_string(), "Option B".to_string(), "Option C".to_string()
     ]);
     
-    let inputs_content = Column::new()
-        .add_child(Box::new(Label::new("📝 Inputs")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(text_input))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 6.0))))
-        .add_child(Box::new(slider))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 6.0))))
-        .add_child(Box::new(checkbox))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 6.0))))
-        .add_child(Box::new(dropdown))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 10.0))))
-        .add_child(Box::new(Row::new()
-            .add_child(Box::new(Button::new("OK")))
-            .add_child(Box::new(Spacer::new(Vec2::new(8.0, 0.0))))
-            .add_child(Box::new(Button::new("Cancel")))
-        ));
-    
-    let inputs_panel = Panel::new(Box::new(inputs_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
+    let inputs_panel = panel!(color: panel_color;
+        label!("📝 Inputs"),
+        spacer!(0.0, 8.0),
+        text_input,
+        spacer!(0.0, 6.0),
+        slider,
+        spacer!(0.0, 6.0),
+        checkbox,
+        spacer!(0.0, 6.0),
+        dropdown,
+        spacer!(0.0, 10.0),
+        row![button!("OK"), spacer!(8.0, 0.0), button!("Cancel")],
+    );
     
     // =========================================================================
     // V2 PANELS - Using new widgets
     // =========================================================================
     
-    // Gauges panel
-    let gauges_content = Column::new()
-        .add_child(Box::new(Label::new("📊 System Metrics")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(cpu_metric));
-    
-    let gauges_panel = Panel::new(Box::new(gauges_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
-    
-    // Agent panel
-    let agent_content = Column::new()
-        .add_child(Box::new(Label::new("🤖 AI Agent")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(agent_card));
-    
-    let agent_panel = Panel::new(Box::new(agent_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
-    
-    // Timeline panel
-    let timeline_content = Column::new()
-        .add_child(Box::new(Label::new("📅 Activity")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(timeline));
-    
-    let timeline_panel = Panel::new(Box::new(timeline_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
-    
-    // File tree panel
-    let tree_content = Column::new()
-        .add_child(Box::new(Label::new("📁 Files")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(file_tree));
-    
-    let tree_panel = Panel::new(Box::new(tree_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
-    
-    // Data table panel
-    let table_content = Column::new()
-        .add_child(Box::new(Label::new("📋 Services")))
-        .add_child(Box::new(Spacer::new(Vec2::new(0.0, 8.0))))
-        .add_child(Box::new(data_table));
-    
-    let table_panel = Panel::new(Box::new(table_content))
-        .with_color(Vec4::new(0.06, 0.06, 0.1, 0.92));
+    let gauges_panel = panel!(color: panel_color; label!("📊 System Metrics"), spacer!(0.0, 8.0), cpu_metric);
+    let agent_panel = panel!(color: panel_color; label!("🤖 AI Agent"), spacer!(0.0, 8.0), agent_card, spacer!(0.0, 8.0), model_selector);
+    let timeline_panel = panel!(color: panel_color; label!("📅 Activity"), spacer!(0.0, 8.0), timeline);
+    let tree_panel = panel!(color: panel_color; label!("📁 Files"), spacer!(0.0, 8.0), file_tree);
+    let table_panel = panel!(color: panel_color; label!("📋 Services"), spacer!(0.0, 8.0), data_table);
     
     // =========================================================================
     // HEADER
     // =========================================================================
     
-    let header = Panel::new(Box::new(Row::new()
-        .add_child(Box::new(Label::new("GlassUI v2 Dashboard")))
-        .add_child(Box::new(Spacer::new(Vec2::new(40.0, 0.0))))
-        .add_child(Box::new(Label::new("Ctrl+K for commands | R=Resize M=Move C=Color")))
-    ))
+    let header = Panel::new(Box::new(row![
+        label!("GlassUI v2 Dashboard"),
+        spacer!(40.0, 0.0),
+        label!("Ctrl+K for commands | R=Resize M=Move C=Color"),
+    ]))
     .with_color(Vec4::new(0.04, 0.04, 0.08, 0.95));
     
     // =========================================================================
-    // ROOT LAYOUT - All panels draggable
+    // ROOT LAYOUT - Header, file tree and timeline docked, the rest draggable
     // =========================================================================
     
-    let mut root = Stack::new()
-        // Header at top
-        .add_child(Box::new(Align::new(Alignment::TopLeft, Box::new(header))))
+    let workspace = stack![
         // Charts - top left
-        .add_child(Box::new(Align::new(Alignment::TopLeft,
-            Box::new(Draggable::new(Box::new(Resizable::new(
-                Box::new(charts_panel), Vec2::new(560.0, 220.0))))))))
-        // Controls - bottom left  
-        .add_child(Box::new(Align::new(Alignment::BottomLeft,
-            Box::new(Draggable::new(Box::new(controls_panel))))))
+        charts_panel.resizable(Vec2::new(560.0, 220.0)).draggable().aligned(Alignment::TopLeft),
+        // Controls - bottom left
+        controls_panel.draggable().aligned(Alignment::BottomLeft),
         // Inputs - center
-        .add_child(Box::new(Align::new(Alignment::Center,
-            Box::new(Draggable::new(Box::new(inputs_panel))))))
+        inputs_panel.draggable().aligned(Alignment::Center),
         // Gauges - top right (offset from agent)
-        .add_child(Box::new(Align::new(Alignment::TopRight,
-            Box::new(Draggable::new(Box::new(gauges_panel))))))
+        gauges_panel.draggable().aligned(Alignment::TopRight),
         // Agent - top right
-        .add_child(Box::new(Align::new(Alignment::TopRight,
-            Box::new(Draggable::new(Box::new(agent_panel))))))
-        // Timeline - center right area
-        .add_child(Box::new(Align::new(Alignment::Center,
-            Box::new(Draggable::new(Box::new(timeline_panel))))))
-        // File tree - bottom left (offset from controls)
-        .add_child(Box::new(Align::new(Alignment::BottomLeft,
-            Box::new(Draggable::new(Box::new(tree_panel))))))
+        agent_panel.draggable().aligned(Alignment::TopRight),
         // Data table - bottom right
-        .add_child(Box::new(Align::new(Alignment::BottomRight,
-            Box::new(Draggable::new(Box::new(Resizable::new(
-                Box::new(table_panel), Vec2::new(480.0, 200.0))))))));
+        table_panel.resizable(Vec2::new(480.0, 200.0)).draggable().aligned(Alignment::BottomRight),
+    ];
+    
+    let mut root = DockPanel::new()
+        .add_docked(Dock::Top, Box::new(header))
+        // Room for the status bar, which is laid out on its own
+        .add_docked(Dock::Bottom, Box::new(spacer!(0.0, 28.0)))
+        .add_docked(Dock::Left, Box::new(tree_panel))
+        .add_docked(Dock::Right, Box::new(timeline_panel))
+        .add_child(Box::new(workspace));
+    mount(&mut root);
+    
+    // =========================================================================
+    // PERFORMANCE OVERLAY (F3) / FPS COUNTER (F2)
+    // =========================================================================
+    
+    let mut perf_overlay = PerfOverlay::new().at(Vec2::new(16.0, 80.0));
+    let mut frame_stats = FrameStats::new().at(Vec2::new(16.0, 296.0));
+    
+    // =========================================================================
+    // WIDGET INSPECTOR (F12)
+    // =========================================================================
+    
+    let mut inspector = Inspector::new();
+    let mut dialogs = DialogManager::new();
+    
+    // Pointer events go to the topmost widget under the cursor
+    let mut dispatcher = EventDispatcher::new();
+    // The title bar sits outside the widget tree but still counts clicks
+    let mut title_dispatcher = EventDispatcher::new();
+    
+    // =========================================================================
+    // EVENT RECORDING (Ctrl+Shift+R) / REPLAY (GLASSUI_REPLAY=<file>)
+    // =========================================================================
+    
+    let mut recorder = EventRecorder::new();
+    let mut replayer = std::env::var("GLASSUI_REPLAY").ok().and_then(|path| {
+        EventReplayer::load(&path)
+            .map_err(|e| log::error!("Failed to load recording {}: {}", path, e))
+            .ok()
+    });
     
     let mut cursor_pos = Vec2::ZERO;
     let mut command_palette_visible = false;
+    let mut elapsed = 0.0f32;
 
     event_loop.run(move |event, target| {
-        target.set_control_flow(ControlFlow::Poll);
+        scheduler.handle_event(&event, Instant::now());
+        recorder.record(&event, cursor_pos);
 
         // Handle keyboard shortcuts
         if let Some(action) = shortcuts.handle_event(&event) {
@@ -321,6 +363,41 @@ fn main() {
                 "new_panel" => {
                     toasts.info("Panel", "New panel created");
                 },
+                "close_panel" => {
+                    dialogs.confirm("Close Panel", "Close the selected panel?")
+                        .connect_forever(|ok| log::info!("Close panel confirmed: {}", ok));
+                },
+                "toggle_perf_overlay" => {
+                    perf_overlay.toggle();
+                    context.renderer.set_profiling(perf_overlay.visible || frame_stats.visible);
+                },
+                "toggle_frame_stats" => {
+                    frame_stats.toggle();
+                    context.renderer.set_profiling(frame_stats.visible || perf_overlay.visible);
+                },
+                "toggle_inspector" => {
+                    inspector.toggle();
+                },
+                "toggle_layout_debug" => {
+                    set_debug_layout(!debug_layout());
+                },
+                "toggle_event_recording" => {
+                    if recorder.is_recording() {
+                        let recording = recorder.stop();
+                        match recording.save("glassui-recording.json") {
+                            Ok(()) => toasts.success("Recording", &format!("Saved {} events", recording.events.len())),
+                            Err(e) => toasts.error("Recording", &e.to_string()),
+                        }
+                    } else {
+                        recorder.start();
+                        toasts.info("Recording", "Recording input events");
+                    }
+                },
+                "cycle_glass_quality" => {
+                    let quality = context.renderer.quality().next();
+                    context.renderer.set_quality(quality);
+                    toasts.info("Glass Quality", quality.label());
+                },
                 "deselect" => {
                     command_palette_visible = false;
                     command_palette.hide();
@@ -329,6 +406,13 @@ fn main() {
             }
         }
 
+        #[cfg(feature = "tray")]
+        if let Event::WindowEvent { event: window_event, .. } = &event {
+            if tray.handle_window_event(window_event) && matches!(window_event, WindowEvent::CloseRequested) {
+                return;
+            }
+        }
+
         match event {
             Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
                 target.exit();
@@ -336,9 +420,21 @@ fn main() {
             Event::WindowEvent { event: WindowEvent::Resized(size), .. } => {
                 context.resize(size.width, size.height);
             }
+            Event::WindowEvent { event: WindowEvent::ScaleFactorChanged { scale_factor, .. }, .. } => {
+                context.set_scale_factor(scale_factor);
+            }
             Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
-                cursor_pos = Vec2::new(position.x as f32, position.y as f32);
-                root.handle_event(&Event::WindowEvent { 
+                cursor_pos = context.to_logical(position);
+                if borderless {
+                    title_dispatcher.dispatch(&mut title_bar, &event, cursor_pos);
+                    title_bar.apply(&window);
+                }
+                inspector.handle_event(&event, cursor_pos);
+                if dialogs.handle_event(&event, cursor_pos) {
+                    return;
+                }
+                status_bar.handle_event(&event, cursor_pos);
+                dispatcher.dispatch(&mut root, &Event::WindowEvent { 
                     window_id: unsafe { winit::window::WindowId::dummy() }, 
                     event: WindowEvent::CursorMoved { 
                         device_id: unsafe { winit::event::DeviceId::dummy() }, 
@@ -348,34 +444,106 @@ fn main() {
             }
             Event::WindowEvent { event: WindowEvent::RedrawRequested, .. } => {
                 // Update
-                context.update(0.016);
-                root.update(0.016);
-                toasts.update(0.016);
-                status_bar.update(0.016);
+                let animating = root.is_animating() || toasts.is_animating() || perf_overlay.is_animating() || frame_stats.is_animating() || inspector.is_animating() || dialogs.is_animating() || status_bar.is_animating();
+                let dt = scheduler.frame_drawn(Instant::now(), animating);
+                elapsed += dt;
+                context.update(dt);
+                if let Some(replay) = &mut replayer {
+                    replay.replay_into(&mut root, dt, |_| {});
+                    cursor_pos = replay.cursor();
+                    if replay.is_finished() {
+                        replayer = None;
+                    }
+                }
+                context.time(PerfStage::Update, || {
+                    update_parallel(&mut root, dt);
+                    toasts.update(dt);
+                    dialogs.update(dt);
+                    status_bar.update(dt);
+                    title_bar.update(dt);
+                });
                 
                 // Update status bar metrics (simulated from time)
-                static mut FRAME: u32 = 0;
-                unsafe { FRAME += 1; }
-                let elapsed = unsafe { FRAME } as f32 * 0.016;
                 let cpu = (40.0 + 30.0 * (elapsed * 0.5).sin()) as i32;
                 let mem = (50.0 + 20.0 * (elapsed * 0.3).cos()) as i32;
                 status_bar.update_item("cpu", &format!("{}%", cpu));
                 status_bar.update_item("mem", &format!("{}%", mem));
                 
                 // Layout
-                root.layout(Vec2::ZERO, Vec2::new(context.width as f32, context.height as f32));
+                let screen = context.logical_size();
+                context.time(PerfStage::Layout, || root.layout_child(Vec2::new(0.0, chrome_height), screen - Vec2::new(0.0, chrome_height)));
+                if borderless {
+                    title_bar.layout(Vec2::ZERO, screen);
+                }
+                inspector.layout(Vec2::ZERO, screen);
+                dialogs.layout(Vec2::ZERO, screen);
+                toasts.layout(Vec2::ZERO, screen);
+                status_bar.layout(Vec2::new(0.0, screen.y - 28.0), screen);
+                let layouts = take_layout_count();
+                inspector.inspect_with(&root, Some(dispatcher.focus()), dispatcher.hovered());
                 
                 // Render
-                context.render(&mut root);
+                perf_overlay.push(context.frame_timings());
+                frame_stats.record_frame(dt);
+                if frame_stats.visible {
+                    frame_stats.set_counts(count_widgets(&root), context.renderer.instance_count());
+                    frame_stats.set_layout_count(layouts);
+                    frame_stats.set_render_stats(context.frame_stats());
+                }
+                let mut layers: Vec<&mut dyn Widget> = vec![&mut root, &mut status_bar];
+                if borderless {
+                    layers.push(&mut title_bar);
+                }
+                layers.extend([&mut perf_overlay as &mut dyn Widget, &mut frame_stats, &mut toasts, &mut dialogs, &mut inspector]);
+                context.render_layers(&mut layers);
                 
-                // Render overlays (status bar, toasts, command palette)
+                // Render overlays (command palette)
                 // Note: In a real app, these would be rendered as part of the context
             }
             Event::AboutToWait => {
-                window.request_redraw();
+                #[cfg(feature = "tray")]
+                {
+                    // No redraws while hidden, so toasts become OS notifications
+                    for toast in toasts.toasts.iter().filter(|toast| toast.id > last_toast_id) {
+                        if tray_notifications.get() {
+                            tray.notify(&toast.title, &toast.message);
+                        }
+                    }
+                    last_toast_id = toasts.toasts.iter().map(|toast| toast.id).max().unwrap_or(last_toast_id);
+                    if tray.apply(&window) {
+                        target.exit();
+                    }
+                }
+                let now = Instant::now();
+                if scheduler.should_redraw(now) {
+                    window.request_redraw();
+                }
+                target.set_control_flow(scheduler.control_flow(now));
+            }
+            Event::LoopExiting => {
+                persistence.state_mut().command_recents = command_palette.recents().to_vec();
+                if let Err(e) = persistence.save() {
+                    log::error!("Failed to save app state: {}", e);
+                }
             }
             _ => {
-                root.handle_event(&event, cursor_pos);
+                if borderless {
+                    let handled = title_dispatcher.dispatch(&mut title_bar, &event, cursor_pos);
+                    if title_bar.apply(&window) {
+                        target.exit();
+                    }
+                    if handled {
+                        return;
+                    }
+                }
+                if dialogs.handle_event(&event, cursor_pos)
+                    || inspector.handle_event(&event, cursor_pos)
+                    || toasts.handle_event(&event, cursor_pos)
+                    || status_bar.handle_event(&event, cursor_pos)
+                {
+                    return;
+                }
+                dispatcher.dispatch(&mut root, &event, cursor_pos);
                 
                 // Handle command palette events
                 if command_palette_visible {
@@ -5,7 +5,7 @@
 //! - Custom panel shapes (rect, circle, hex, SVG paths)
 //! - Quick styling methods
 
-use glam::Vec4;
+use glam::{Vec2, Vec4};
 
 // =============================================================================
 // PANEL PRESET
@@ -100,6 +100,63 @@ impl Default for PanelShape {
     }
 }
 
+impl PanelShape {
+    /// Outline of the shape for a panel of `size`, relative to its top-left
+    /// corner (custom paths are returned as-is)
+    pub fn to_path(&self, size: Vec2) -> Vec<PathCommand> {
+        match self {
+            PanelShape::Rectangle { corner_radius } => rounded_rect_path(Vec2::ZERO, size, [*corner_radius; 4]),
+            PanelShape::RoundedRect { radii } => rounded_rect_path(Vec2::ZERO, size, *radii),
+            PanelShape::Circle => {
+                let d = size.x.min(size.y);
+                rounded_rect_path((size - Vec2::splat(d)) * 0.5, Vec2::splat(d), [d * 0.5; 4])
+            }
+            PanelShape::Hexagon => {
+                // Flat-topped, corners on the left and right edges
+                let (w, h) = (size.x, size.y);
+                let inset = w * 0.25;
+                vec![
+                    PathCommand::MoveTo(inset, 0.0),
+                    PathCommand::LineTo(w - inset, 0.0),
+                    PathCommand::LineTo(w, h * 0.5),
+                    PathCommand::LineTo(w - inset, h),
+                    PathCommand::LineTo(inset, h),
+                    PathCommand::LineTo(0.0, h * 0.5),
+                    PathCommand::Close,
+                ]
+            }
+            PanelShape::Custom { path } => path.clone(),
+        }
+    }
+}
+
+/// Rect with per-corner radii [top-left, top-right, bottom-right, bottom-left],
+/// corners drawn as cubic quarter circles
+fn rounded_rect_path(pos: Vec2, size: Vec2, radii: [f32; 4]) -> Vec<PathCommand> {
+    // Control point distance for a cubic approximating a quarter circle
+    const KAPPA: f32 = 0.552_284_8;
+    let max_r = size.x.min(size.y) * 0.5;
+    let [tl, tr, br, bl] = radii.map(|r| r.clamp(0.0, max_r));
+    let (x0, y0, x1, y1) = (pos.x, pos.y, pos.x + size.x, pos.y + size.y);
+    let corner = |from: (f32, f32), to: (f32, f32), c: (f32, f32)| PathCommand::CubicTo {
+        control1: (from.0 + (c.0 - from.0) * KAPPA, from.1 + (c.1 - from.1) * KAPPA),
+        control2: (to.0 + (c.0 - to.0) * KAPPA, to.1 + (c.1 - to.1) * KAPPA),
+        end: to,
+    };
+    vec![
+        PathCommand::MoveTo(x0 + tl, y0),
+        PathCommand::LineTo(x1 - tr, y0),
+        corner((x1 - tr, y0), (x1, y0 + tr), (x1, y0)),
+        PathCommand::LineTo(x1, y1 - br),
+        corner((x1, y1 - br), (x1 - br, y1), (x1, y1)),
+        PathCommand::LineTo(x0 + bl, y1),
+        corner((x0 + bl, y1), (x0, y1 - bl), (x0, y1)),
+        PathCommand::LineTo(x0, y0 + tl),
+        corner((x0, y0 + tl), (x0 + tl, y0), (x0, y0)),
+        PathCommand::Close,
+    ]
+}
+
 /// SVG-like path commands for custom shapes
 #[derive(Clone, Debug)]
 pub enum PathCommand {
@@ -212,4 +269,18 @@ mod tests {
         assert_eq!(style.corner_radius, 20.0);
         assert!(style.title_bar);
     }
+    
+    #[test]
+    fn test_shape_paths() {
+        let size = Vec2::new(100.0, 60.0);
+        let bounds = |shape: PanelShape| crate::path::fill_path(&shape.to_path(size)).bounds().unwrap();
+        
+        assert_eq!(bounds(PanelShape::Hexagon), crate::layout::Rect::new(Vec2::ZERO, size));
+        // Circles are centered in the panel
+        let circle = bounds(PanelShape::Circle);
+        assert!(circle.min.abs_diff_eq(Vec2::new(20.0, 0.0), 0.01) && circle.max.abs_diff_eq(Vec2::new(80.0, 60.0), 0.01));
+        // Radii larger than the panel are clamped
+        let path = PanelShape::Rectangle { corner_radius: 500.0 }.to_path(size);
+        assert!(matches!(path[0], PathCommand::MoveTo(x, y) if x == 30.0 && y == 0.0));
+    }
 }
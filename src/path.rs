@@ -0,0 +1,268 @@
+//! GlassUI Vector Paths
+//!
+//! Turns `PathCommand` lists into triangle meshes for the GPU:
+//! - `fill_path` - Interior of the path (non-zero rule, like SVG)
+//! - `stroke_path` - Outline of the path with round joins and caps
+//! - `stroke_polyline` - Outline through a list of points (charts, connectors)
+//! - `PathMesh` - Vertices and triangle indices, ready for `GlassRenderer::draw_mesh`
+//!
+//! Curves are flattened to within `PATH_TOLERANCE` pixels. Coordinates are
+//! whatever the commands use; the renderer offsets them by an origin.
+
+use glam::Vec2;
+use lyon_tessellation::math::point;
+use lyon_tessellation::path::Path;
+use lyon_tessellation::{
+    BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex, LineCap, LineJoin,
+    StrokeOptions, StrokeTessellator, StrokeVertex, VertexBuffers,
+};
+
+use crate::layout::Rect;
+use crate::panel_style::PathCommand;
+
+/// Maximum distance (in pixels) between a curve and its flattened polyline
+pub const PATH_TOLERANCE: f32 = 0.25;
+
+// =============================================================================
+// PATH MESH
+// =============================================================================
+
+/// Triangle list produced by tessellating a path
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathMesh {
+    pub vertices: Vec<Vec2>,
+    /// Three indices into `vertices` per triangle
+    pub indices: Vec<u32>,
+}
+
+impl PathMesh {
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Bounding box of the vertices
+    pub fn bounds(&self) -> Option<Rect> {
+        let first = *self.vertices.first()?;
+        let (min, max) = self.vertices.iter().fold((first, first), |(min, max), &v| (min.min(v), max.max(v)));
+        Some(Rect::new(min, max - min))
+    }
+
+    fn from_buffers(buffers: VertexBuffers<Vec2, u32>) -> Self {
+        Self { vertices: buffers.vertices, indices: buffers.indices }
+    }
+}
+
+// =============================================================================
+// TESSELLATION
+// =============================================================================
+
+/// Build a lyon path, closing or ending sub-paths the way SVG does
+/// (drawing commands before any `MoveTo` start at the origin)
+fn build_path(commands: &[PathCommand]) -> Path {
+    let mut builder = Path::builder();
+    let mut open = false;
+    let mut current = point(0.0, 0.0);
+    let mut start = current;
+
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo(x, y) => {
+                if open {
+                    builder.end(false);
+                }
+                current = point(x, y);
+                start = current;
+                builder.begin(current);
+                open = true;
+                continue;
+            }
+            PathCommand::Close => {
+                if open {
+                    builder.end(true);
+                    open = false;
+                    current = start;
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        if !open {
+            builder.begin(current);
+            start = current;
+            open = true;
+        }
+        current = match *command {
+            PathCommand::LineTo(x, y) => {
+                builder.line_to(point(x, y));
+                point(x, y)
+            }
+            PathCommand::QuadraticTo { control, end } => {
+                builder.quadratic_bezier_to(point(control.0, control.1), point(end.0, end.1));
+                point(end.0, end.1)
+            }
+            PathCommand::CubicTo { control1, control2, end } => {
+                builder.cubic_bezier_to(point(control1.0, control1.1), point(control2.0, control2.1), point(end.0, end.1));
+                point(end.0, end.1)
+            }
+            PathCommand::MoveTo(..) | PathCommand::Close => unreachable!(),
+        };
+    }
+    if open {
+        builder.end(false);
+    }
+    builder.build()
+}
+
+/// Triangulate the interior of `commands` (open sub-paths are closed
+/// implicitly, overlapping sub-paths use the non-zero rule)
+pub fn fill_path(commands: &[PathCommand]) -> PathMesh {
+    let path = build_path(commands);
+    let mut buffers: VertexBuffers<Vec2, u32> = VertexBuffers::new();
+    let options = FillOptions::tolerance(PATH_TOLERANCE).with_fill_rule(FillRule::NonZero);
+    let result = FillTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| Vec2::new(v.position().x, v.position().y)),
+    );
+    if let Err(e) = result {
+        log::warn!("Path fill failed: {:?}", e);
+        return PathMesh::default();
+    }
+    PathMesh::from_buffers(buffers)
+}
+
+/// How the segments of a stroked polyline meet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PolylineJoin {
+    /// Rounded joins and caps
+    #[default]
+    Round,
+    /// Sharp joins (clipped at 4x the width on tight angles) and flat caps
+    Miter,
+}
+
+/// Triangulate a `width` pixel outline along `commands`
+pub fn stroke_path(commands: &[PathCommand], width: f32) -> PathMesh {
+    if width <= 0.0 {
+        return PathMesh::default();
+    }
+    stroke(&build_path(commands), width, LineJoin::Round, LineCap::Round)
+}
+
+/// Triangulate a `width` pixel outline through `points` (fewer than two
+/// points draw nothing)
+pub fn stroke_polyline(points: &[Vec2], width: f32, join: PolylineJoin) -> PathMesh {
+    let [first, rest @ ..] = points else {
+        return PathMesh::default();
+    };
+    if rest.is_empty() || width <= 0.0 {
+        return PathMesh::default();
+    }
+    let mut builder = Path::builder();
+    builder.begin(point(first.x, first.y));
+    for p in rest {
+        builder.line_to(point(p.x, p.y));
+    }
+    builder.end(false);
+    
+    let (line_join, line_cap) = match join {
+        PolylineJoin::Round => (LineJoin::Round, LineCap::Round),
+        PolylineJoin::Miter => (LineJoin::MiterClip, LineCap::Butt),
+    };
+    stroke(&builder.build(), width, line_join, line_cap)
+}
+
+fn stroke(path: &Path, width: f32, join: LineJoin, cap: LineCap) -> PathMesh {
+    let mut buffers: VertexBuffers<Vec2, u32> = VertexBuffers::new();
+    let options = StrokeOptions::tolerance(PATH_TOLERANCE)
+        .with_line_width(width)
+        .with_line_join(join)
+        .with_line_cap(cap);
+    let result = StrokeTessellator::new().tessellate_path(
+        path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| Vec2::new(v.position().x, v.position().y)),
+    );
+    if let Err(e) = result {
+        log::warn!("Path stroke failed: {:?}", e);
+        return PathMesh::default();
+    }
+    PathMesh::from_buffers(buffers)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(mesh: &PathMesh) -> f32 {
+        mesh.indices.chunks(3).map(|t| {
+            let (a, b, c) = (mesh.vertices[t[0] as usize], mesh.vertices[t[1] as usize], mesh.vertices[t[2] as usize]);
+            (b - a).perp_dot(c - a).abs() * 0.5
+        }).sum()
+    }
+
+    #[test]
+    fn test_fill_covers_the_shape() {
+        // Square with a triangle notch, left open (filled as if closed)
+        let path = [
+            PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::LineTo(10.0, 0.0),
+            PathCommand::LineTo(10.0, 10.0),
+            PathCommand::LineTo(5.0, 5.0),
+            PathCommand::LineTo(0.0, 10.0),
+        ];
+        let mesh = fill_path(&path);
+        assert!((area(&mesh) - 75.0).abs() < 1e-3);
+        assert_eq!(mesh.bounds(), Some(Rect::new(Vec2::ZERO, Vec2::splat(10.0))));
+
+        // Curves are flattened, staying within the tolerance of a circle
+        let r = 100.0;
+        let k = 0.552_284_8 * r;
+        let circle = [
+            PathCommand::MoveTo(r, 0.0),
+            PathCommand::CubicTo { control1: (r, k), control2: (k, r), end: (0.0, r) },
+            PathCommand::CubicTo { control1: (-k, r), control2: (-r, k), end: (-r, 0.0) },
+            PathCommand::CubicTo { control1: (-r, -k), control2: (-k, -r), end: (0.0, -r) },
+            PathCommand::CubicTo { control1: (k, -r), control2: (r, -k), end: (r, 0.0) },
+            PathCommand::Close,
+        ];
+        let expected = std::f32::consts::PI * r * r;
+        assert!((area(&fill_path(&circle)) - expected).abs() < expected * 0.01);
+    }
+
+    #[test]
+    fn test_stroke_follows_the_outline() {
+        let line = [PathCommand::MoveTo(0.0, 0.0), PathCommand::LineTo(20.0, 0.0)];
+        let mesh = stroke_path(&line, 4.0);
+        let bounds = mesh.bounds().unwrap();
+        // Round caps extend half the width past both ends
+        assert!((bounds.min.y + 2.0).abs() < 1e-3 && (bounds.max.y - 2.0).abs() < 1e-3);
+        assert!((bounds.min.x + 2.0).abs() < 0.1 && (bounds.max.x - 22.0).abs() < 0.1);
+
+        assert!(stroke_path(&line, 0.0).is_empty());
+        assert!(fill_path(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_polyline_joins() {
+        let corner = [Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)];
+        
+        // Miter joins fill the outer corner square, flat caps end at the points
+        let miter = stroke_polyline(&corner, 2.0, PolylineJoin::Miter);
+        assert_eq!(miter.bounds(), Some(Rect::new(Vec2::new(0.0, -1.0), Vec2::new(11.0, 11.0))));
+        assert!((area(&miter) - 40.0).abs() < 1e-3);
+        
+        // Round joins cut the corner, round caps overhang
+        let round = stroke_polyline(&corner, 2.0, PolylineJoin::Round);
+        let bounds = round.bounds().unwrap();
+        assert!((bounds.min.x + 1.0).abs() < 0.1 && (bounds.max.y - 11.0).abs() < 0.1);
+        assert!(area(&round) < area(&miter) + std::f32::consts::PI);
+        
+        assert!(stroke_polyline(&corner[..1], 2.0, PolylineJoin::Round).is_empty());
+    }
+}
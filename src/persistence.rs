@@ -99,6 +99,24 @@ impl Default for WorkspaceState {
     }
 }
 
+/// How often and how recently a palette command was used
+/// 
+/// Kept most recent first; see `CommandPalette::recents`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommandUsage {
+    pub id: String,
+    pub count: u32,
+}
+
+impl CommandUsage {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            count: 0,
+        }
+    }
+}
+
 /// Serializable app state (all workspaces)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppState {
@@ -108,6 +126,9 @@ pub struct AppState {
     pub theme: String,
     pub sound_enabled: bool,
     pub master_volume: f32,
+    /// Command palette recents (missing in files saved by older versions)
+    #[serde(default)]
+    pub command_recents: Vec<CommandUsage>,
 }
 
 impl Default for AppState {
@@ -119,6 +140,7 @@ impl Default for AppState {
             theme: "cyberpunk".to_string(),
             sound_enabled: true,
             master_volume: 0.7,
+            command_recents: Vec::new(),
         }
     }
 }
@@ -309,4 +331,19 @@ mod tests {
         let loaded: AppState = serde_json::from_str(&json).unwrap();
         assert_eq!(loaded.version, "1.0");
     }
+    
+    #[test]
+    fn test_command_recents_roundtrip() {
+        let mut state = AppState::default();
+        state.command_recents.push(CommandUsage { id: "save_workspace".to_string(), count: 3 });
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: AppState = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.command_recents, state.command_recents);
+        
+        // Older files without the field still load
+        let legacy = json.replace(r#","command_recents":[{"id":"save_workspace","count":3}]"#, "");
+        assert!(!legacy.contains("command_recents"));
+        let loaded: AppState = serde_json::from_str(&legacy).unwrap();
+        assert!(loaded.command_recents.is_empty());
+    }
 }
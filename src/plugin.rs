@@ -0,0 +1,150 @@
+//! GlassUI Renderer Plugins
+//!
+//! Custom full-screen post-processing (vignette, grain, chromatic aberration):
+//! - `RendererPlugin` - A WGSL fragment shader run over the finished frame
+//! - `POST_PRELUDE` - Bindings and vertex stage shared by every post shader
+//! - `Vignette` - Built-in example plugin
+//!
+//! Plugins run in the order they were added, after the glass pass and
+//! overlays but before present. Each one reads the previous result as a
+//! texture and draws a fullscreen triangle; the last writes the surface.
+
+/// Declarations prepended to every plugin's WGSL
+///
+/// In scope for `fs_main(in: PostInput) -> @location(0) vec4<f32>`:
+/// - `uniforms.time`, `uniforms.resolution` (logical pixels), `uniforms.scale`
+/// - `frame_texture` / `frame_sampler` - the frame so far, sampled at `in.uv`
+/// - `params` - the plugin's `params()` for this frame
+pub const POST_PRELUDE: &str = r#"
+struct Uniforms {
+    time: f32,
+    quality: u32,
+    resolution: vec2<f32>,
+    scale: f32,
+};
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+@group(1) @binding(0) var frame_texture: texture_2d<f32>;
+@group(1) @binding(1) var frame_sampler: sampler;
+
+@group(2) @binding(0) var<uniform> params: vec4<f32>;
+
+struct PostInput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) v_idx: u32) -> PostInput {
+    // Fullscreen triangle
+    let pos = vec2<f32>(f32((v_idx << 1u) & 2u), f32(v_idx & 2u));
+    var out: PostInput;
+    out.position = vec4<f32>(pos * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(pos.x, 1.0 - pos.y);
+    return out;
+}
+"#;
+
+// =============================================================================
+// RENDERER PLUGIN
+// =============================================================================
+
+/// Post-processing pass added with `GlassRenderer::add_plugin`
+pub trait RendererPlugin {
+    /// Identifies the plugin for `remove_plugin` and error messages
+    fn name(&self) -> &str;
+
+    /// WGSL defining `fs_main` (appended to `POST_PRELUDE`)
+    fn fragment_source(&self) -> String;
+
+    /// Per-frame values for the shader's `params` uniform
+    fn params(&self) -> [f32; 4] {
+        [0.0; 4]
+    }
+
+    /// Skipped while false (no extra pass or copy is made)
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Full WGSL module for a plugin
+pub fn post_shader_source(plugin: &dyn RendererPlugin) -> String {
+    format!("{}\n{}", POST_PRELUDE, plugin.fragment_source())
+}
+
+// =============================================================================
+// VIGNETTE
+// =============================================================================
+
+/// Darkens the edges of the frame
+#[derive(Clone, Debug)]
+pub struct Vignette {
+    /// 0 = off, 1 = black corners
+    pub strength: f32,
+    /// Distance from the center (0..1, corner = 1) where darkening starts
+    pub radius: f32,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self { strength: 0.5, radius: 0.6 }
+    }
+}
+
+impl Vignette {
+    pub fn new(strength: f32) -> Self {
+        Self { strength: strength.clamp(0.0, 1.0), ..Default::default() }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl RendererPlugin for Vignette {
+    fn name(&self) -> &str {
+        "vignette"
+    }
+
+    fn fragment_source(&self) -> String {
+        r#"
+@fragment
+fn fs_main(in: PostInput) -> @location(0) vec4<f32> {
+    let color = textureSample(frame_texture, frame_sampler, in.uv);
+    // Distance from the center, 1.0 at the corners
+    let d = length(in.uv - 0.5) * 1.41421356;
+    let shade = 1.0 - params.x * smoothstep(params.y, 1.0, d);
+    return vec4<f32>(color.rgb * shade, color.a);
+}
+"#.to_string()
+    }
+
+    fn params(&self) -> [f32; 4] {
+        [self.strength, self.radius, 0.0, 0.0]
+    }
+
+    fn enabled(&self) -> bool {
+        self.strength > 0.0
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vignette_plugin() {
+        let vignette = Vignette::new(2.0).with_radius(0.4);
+        assert_eq!(vignette.params(), [1.0, 0.4, 0.0, 0.0]);
+        assert!(!Vignette::new(0.0).enabled());
+
+        let source = post_shader_source(&vignette);
+        assert!(source.starts_with(POST_PRELUDE) && source.contains("fn fs_main"));
+    }
+}
@@ -0,0 +1,355 @@
+//! GlassUI Frame Profiling
+//!
+//! CPU and GPU timings for each frame:
+//! - `PerfStage` - Named stages of a frame (update, layout, encode, GPU passes)
+//! - `FrameTimings` - Milliseconds spent in each stage
+//! - `RenderStats` - Timings plus the instances, text quads and batches drawn
+//! - `GlyphCacheStats` - Glyph atlas hits, misses and evictions
+//! - `GpuTimer` - wgpu timestamp queries around the scene, blur and final passes
+//!
+//! GPU timings require `wgpu::Features::TIMESTAMP_QUERY`; when the adapter
+//! lacks it the GPU fields stay `None`. Results are read back asynchronously,
+//! so GPU timings lag the CPU timings by a frame or two.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+// =============================================================================
+// FRAME TIMINGS
+// =============================================================================
+
+/// A timed stage of a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PerfStage {
+    /// Widget `update()` (CPU)
+    Update,
+    /// Widget `layout()` (CPU)
+    Layout,
+    /// Widget `render()` and command encoding (CPU)
+    Encode,
+    /// Background scene pass (GPU)
+    GpuScene,
+    /// Blur compute pass (GPU)
+    GpuBlur,
+    /// Final composite pass (GPU)
+    GpuFinal,
+}
+
+impl PerfStage {
+    pub const ALL: [PerfStage; 6] = [
+        PerfStage::Update,
+        PerfStage::Layout,
+        PerfStage::Encode,
+        PerfStage::GpuScene,
+        PerfStage::GpuBlur,
+        PerfStage::GpuFinal,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PerfStage::Update => "Update",
+            PerfStage::Layout => "Layout",
+            PerfStage::Encode => "Encode",
+            PerfStage::GpuScene => "GPU Scene",
+            PerfStage::GpuBlur => "GPU Blur",
+            PerfStage::GpuFinal => "GPU Final",
+        }
+    }
+
+    pub fn is_gpu(&self) -> bool {
+        matches!(self, PerfStage::GpuScene | PerfStage::GpuBlur | PerfStage::GpuFinal)
+    }
+}
+
+/// Milliseconds spent in each stage of one frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameTimings {
+    pub update_ms: f32,
+    pub layout_ms: f32,
+    pub encode_ms: f32,
+    pub gpu_scene_ms: Option<f32>,
+    pub gpu_blur_ms: Option<f32>,
+    pub gpu_final_ms: Option<f32>,
+}
+
+impl FrameTimings {
+    /// Timing for a stage (GPU stages are `None` when unsupported)
+    pub fn get(&self, stage: PerfStage) -> Option<f32> {
+        match stage {
+            PerfStage::Update => Some(self.update_ms),
+            PerfStage::Layout => Some(self.layout_ms),
+            PerfStage::Encode => Some(self.encode_ms),
+            PerfStage::GpuScene => self.gpu_scene_ms,
+            PerfStage::GpuBlur => self.gpu_blur_ms,
+            PerfStage::GpuFinal => self.gpu_final_ms,
+        }
+    }
+
+    pub fn set(&mut self, stage: PerfStage, ms: f32) {
+        match stage {
+            PerfStage::Update => self.update_ms = ms,
+            PerfStage::Layout => self.layout_ms = ms,
+            PerfStage::Encode => self.encode_ms = ms,
+            PerfStage::GpuScene => self.gpu_scene_ms = Some(ms),
+            PerfStage::GpuBlur => self.gpu_blur_ms = Some(ms),
+            PerfStage::GpuFinal => self.gpu_final_ms = Some(ms),
+        }
+    }
+
+    /// Total CPU time
+    pub fn cpu_ms(&self) -> f32 {
+        self.update_ms + self.layout_ms + self.encode_ms
+    }
+
+    /// Total GPU time, if GPU timings are available
+    pub fn gpu_ms(&self) -> Option<f32> {
+        match (self.gpu_scene_ms, self.gpu_blur_ms, self.gpu_final_ms) {
+            (None, None, None) => None,
+            (a, b, c) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0) + c.unwrap_or(0.0)),
+        }
+    }
+}
+
+// =============================================================================
+// RENDER STATS
+// =============================================================================
+
+/// What the renderer drew in the last frame, from `GlassRenderer::frame_stats`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RenderStats {
+    pub timings: FrameTimings,
+    /// Glass rects, including overlay rects, minus ones hidden under opaque glass
+    pub glass_instances: usize,
+    /// Gradients, shadows and SDF shapes (circles, arcs, lines, strokes)
+    pub shape_instances: usize,
+    /// Triangles of tessellated paths
+    pub mesh_triangles: usize,
+    pub image_instances: usize,
+    /// One quad per glyph, including overlay text
+    pub text_quads: usize,
+    /// Scissor/clip batches in the final pass
+    pub batches: usize,
+    pub glyph_cache: GlyphCacheStats,
+}
+
+/// Glyph atlas activity in the last frame, part of `RenderStats`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GlyphCacheStats {
+    /// Glyphs currently in the atlas
+    pub cached_glyphs: usize,
+    /// Glyphs drawn from the atlas
+    pub hits: usize,
+    /// Glyphs rasterized because they weren't cached
+    pub misses: usize,
+    /// Atlas pages emptied to make room
+    pub evictions: usize,
+    /// Glyphs drawn without a bitmap: bigger than a page, or every page
+    /// was already in use this frame
+    pub dropped: usize,
+}
+
+impl GlyphCacheStats {
+    /// Share of glyph lookups served from the atlas (1.0 with no lookups)
+    pub fn hit_rate(&self) -> f32 {
+        let total = self.hits + self.misses;
+        if total == 0 { 1.0 } else { self.hits as f32 / total as f32 }
+    }
+}
+
+impl RenderStats {
+    /// Instanced quads and triangles of every kind
+    pub fn total_primitives(&self) -> usize {
+        self.glass_instances + self.shape_instances + self.mesh_triangles + self.image_instances + self.text_quads
+    }
+}
+
+// =============================================================================
+// GPU TIMER
+// =============================================================================
+
+/// GPU passes bracketed by timestamp queries
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GpuPass {
+    Scene = 0,
+    Blur = 1,
+    Final = 2,
+}
+
+const QUERY_COUNT: u32 = 6;
+const BUFFER_SIZE: u64 = QUERY_COUNT as u64 * 8;
+
+const READBACK_IDLE: u8 = 0;
+const READBACK_MAPPING: u8 = 1;
+const READBACK_READY: u8 = 2;
+
+/// Timestamp queries around the renderer's passes
+///
+/// Only one readback is in flight at a time; frames recorded while the
+/// previous readback is pending simply aren't timed.
+pub(crate) struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    period_ns: f32,
+    readback_state: Arc<AtomicU8>,
+    recording: bool,
+}
+
+impl GpuTimer {
+    /// Create a timer if the device supports timestamp queries
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Pass Timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: QUERY_COUNT,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Resolve"),
+            size: BUFFER_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Readback"),
+            size: BUFFER_SIZE,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period_ns: queue.get_timestamp_period(),
+            readback_state: Arc::new(AtomicU8::new(READBACK_IDLE)),
+            recording: false,
+        })
+    }
+
+    /// Start timing a frame; returns false if the previous readback is pending
+    pub fn begin_frame(&mut self) -> bool {
+        self.recording = self.readback_state.load(Ordering::Acquire) == READBACK_IDLE;
+        self.recording
+    }
+
+    pub fn render_pass_writes(&self, pass: GpuPass) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        self.recording.then(|| wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(pass as u32 * 2),
+            end_of_pass_write_index: Some(pass as u32 * 2 + 1),
+        })
+    }
+
+    pub fn compute_pass_writes(&self, pass: GpuPass) -> Option<wgpu::ComputePassTimestampWrites<'_>> {
+        self.recording.then(|| wgpu::ComputePassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(pass as u32 * 2),
+            end_of_pass_write_index: Some(pass as u32 * 2 + 1),
+        })
+    }
+
+    /// Copy this frame's timestamps into the readback buffer
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        if !self.recording {
+            return;
+        }
+        encoder.resolve_query_set(&self.query_set, 0..QUERY_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.readback_buffer, 0, BUFFER_SIZE);
+    }
+
+    /// Request an async readback after the frame was submitted
+    pub fn end_frame(&mut self) {
+        if !self.recording {
+            return;
+        }
+        self.recording = false;
+        self.readback_state.store(READBACK_MAPPING, Ordering::Release);
+        let state = Arc::clone(&self.readback_state);
+        self.readback_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            state.store(if result.is_ok() { READBACK_READY } else { READBACK_IDLE }, Ordering::Release);
+        });
+    }
+
+    /// Collect finished timings, in milliseconds per pass (scene, blur, final)
+    pub fn poll(&mut self, device: &wgpu::Device) -> Option<[f32; 3]> {
+        device.poll(wgpu::Maintain::Poll);
+        if self.readback_state.load(Ordering::Acquire) != READBACK_READY {
+            return None;
+        }
+
+        let timings = {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            let stamps: &[u64] = bytemuck::cast_slice(&data);
+            let ms = |pass: GpuPass| {
+                let i = pass as usize * 2;
+                stamps[i + 1].saturating_sub(stamps[i]) as f32 * self.period_ns / 1_000_000.0
+            };
+            [ms(GpuPass::Scene), ms(GpuPass::Blur), ms(GpuPass::Final)]
+        };
+        self.readback_buffer.unmap();
+        self.readback_state.store(READBACK_IDLE, Ordering::Release);
+        Some(timings)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_timings_set_get() {
+        let mut timings = FrameTimings::default();
+        assert_eq!(timings.get(PerfStage::GpuBlur), None);
+
+        timings.set(PerfStage::Layout, 1.5);
+        timings.set(PerfStage::GpuBlur, 0.75);
+        assert_eq!(timings.get(PerfStage::Layout), Some(1.5));
+        assert_eq!(timings.get(PerfStage::GpuBlur), Some(0.75));
+    }
+
+    #[test]
+    fn test_frame_timings_totals() {
+        let mut timings = FrameTimings {
+            update_ms: 1.0,
+            layout_ms: 2.0,
+            encode_ms: 3.0,
+            ..Default::default()
+        };
+        assert_eq!(timings.cpu_ms(), 6.0);
+        assert_eq!(timings.gpu_ms(), None);
+
+        timings.gpu_scene_ms = Some(0.5);
+        timings.gpu_final_ms = Some(1.5);
+        assert_eq!(timings.gpu_ms(), Some(2.0));
+    }
+
+    #[test]
+    fn test_stage_labels() {
+        assert_eq!(PerfStage::ALL.len(), 6);
+        assert!(PerfStage::GpuScene.is_gpu());
+        assert!(!PerfStage::Encode.is_gpu());
+        assert_eq!(PerfStage::GpuFinal.label(), "GPU Final");
+    }
+
+    #[test]
+    fn test_render_stats_total() {
+        let stats = RenderStats {
+            glass_instances: 10,
+            shape_instances: 4,
+            mesh_triangles: 6,
+            image_instances: 2,
+            text_quads: 30,
+            batches: 3,
+            ..Default::default()
+        };
+        assert_eq!(stats.total_primitives(), 52);
+    }
+}
@@ -0,0 +1,513 @@
+//! GlassUI Event Recorder
+//!
+//! Capture and replay input sessions:
+//! - EventRecorder - serializes winit events with timestamps and cursor positions
+//! - Recording - JSON file format
+//! - EventReplayer - feeds a recording back into a widget tree deterministically
+//!
+//! Replay is driven by the `dt` you pass in, not the wall clock, so a
+//! recording plays back identically in tests and in the app.
+//!
+//! winit's `KeyEvent` can't be constructed outside winit, so recorded keys
+//! can't be dispatched as they arrived. Replay types the text of printable
+//! keys into the tree as input method commits (`Ime::Commit`, which text
+//! fields accept) and hands every `RecordedEvent::Key` to the caller to
+//! route the rest (e.g. to shortcut handling).
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, Event, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
+use winit::keyboard::{Key, ModifiersState};
+
+use crate::widgets::Widget;
+
+/// Current recording file format version
+pub const RECORDING_VERSION: u32 = 1;
+
+// =============================================================================
+// SERIALIZABLE EVENTS
+// =============================================================================
+
+/// Mouse button as stored in a recording
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordedButton {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+    Other(u16),
+}
+
+impl From<MouseButton> for RecordedButton {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Self::Left,
+            MouseButton::Right => Self::Right,
+            MouseButton::Middle => Self::Middle,
+            MouseButton::Back => Self::Back,
+            MouseButton::Forward => Self::Forward,
+            MouseButton::Other(n) => Self::Other(n),
+        }
+    }
+}
+
+impl From<RecordedButton> for MouseButton {
+    fn from(button: RecordedButton) -> Self {
+        match button {
+            RecordedButton::Left => Self::Left,
+            RecordedButton::Right => Self::Right,
+            RecordedButton::Middle => Self::Middle,
+            RecordedButton::Back => Self::Back,
+            RecordedButton::Forward => Self::Forward,
+            RecordedButton::Other(n) => Self::Other(n),
+        }
+    }
+}
+
+/// Window event as stored in a recording
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    CursorMoved { x: f64, y: f64 },
+    CursorEntered,
+    CursorLeft,
+    MouseInput { button: RecordedButton, pressed: bool },
+    /// Scroll in lines (`lines = true`) or pixels
+    MouseWheel { dx: f32, dy: f32, lines: bool },
+    /// Logical key (`Debug` name for named keys, the character otherwise)
+    Key { key: String, text: Option<String>, pressed: bool, repeat: bool },
+    /// Text committed by an input method
+    Text(String),
+    Modifiers { bits: u32 },
+    Resized { width: u32, height: u32 },
+    Focused(bool),
+}
+
+impl RecordedEvent {
+    /// Capture a winit event; returns `None` for events that aren't recorded
+    pub fn from_event(event: &Event<()>) -> Option<Self> {
+        let Event::WindowEvent { event, .. } = event else { return None };
+        Some(match event {
+            WindowEvent::CursorMoved { position, .. } => Self::CursorMoved { x: position.x, y: position.y },
+            WindowEvent::CursorEntered { .. } => Self::CursorEntered,
+            WindowEvent::CursorLeft { .. } => Self::CursorLeft,
+            WindowEvent::MouseInput { state, button, .. } => Self::MouseInput {
+                button: (*button).into(),
+                pressed: *state == ElementState::Pressed,
+            },
+            WindowEvent::MouseWheel { delta, .. } => match delta {
+                MouseScrollDelta::LineDelta(dx, dy) => Self::MouseWheel { dx: *dx, dy: *dy, lines: true },
+                MouseScrollDelta::PixelDelta(pos) => Self::MouseWheel { dx: pos.x as f32, dy: pos.y as f32, lines: false },
+            },
+            WindowEvent::KeyboardInput { event, .. } => Self::Key {
+                key: match &event.logical_key {
+                    Key::Named(named) => format!("{:?}", named),
+                    Key::Character(c) => c.to_string(),
+                    other => format!("{:?}", other),
+                },
+                text: event.text.as_ref().map(|t| t.to_string()),
+                pressed: event.state == ElementState::Pressed,
+                repeat: event.repeat,
+            },
+            WindowEvent::Ime(Ime::Commit(text)) => Self::Text(text.clone()),
+            WindowEvent::ModifiersChanged(modifiers) => Self::Modifiers { bits: modifiers.state().bits() },
+            WindowEvent::Resized(size) => Self::Resized { width: size.width, height: size.height },
+            WindowEvent::Focused(focused) => Self::Focused(*focused),
+            _ => return None,
+        })
+    }
+
+    /// Rebuild the winit event; `None` for keyboard input (see module docs)
+    pub fn to_event(&self) -> Option<Event<()>> {
+        // SAFETY: dummy ids are only compared, never used to reach a real window/device
+        let device_id = unsafe { winit::event::DeviceId::dummy() };
+        let window_id = unsafe { winit::window::WindowId::dummy() };
+        let event = match self {
+            Self::CursorMoved { x, y } => WindowEvent::CursorMoved { device_id, position: PhysicalPosition::new(*x, *y) },
+            Self::CursorEntered => WindowEvent::CursorEntered { device_id },
+            Self::CursorLeft => WindowEvent::CursorLeft { device_id },
+            Self::MouseInput { button, pressed } => WindowEvent::MouseInput {
+                device_id,
+                state: if *pressed { ElementState::Pressed } else { ElementState::Released },
+                button: (*button).into(),
+            },
+            Self::MouseWheel { dx, dy, lines } => WindowEvent::MouseWheel {
+                device_id,
+                delta: if *lines {
+                    MouseScrollDelta::LineDelta(*dx, *dy)
+                } else {
+                    MouseScrollDelta::PixelDelta(PhysicalPosition::new(*dx as f64, *dy as f64))
+                },
+                phase: TouchPhase::Moved,
+            },
+            Self::Key { .. } => return None,
+            Self::Text(text) => WindowEvent::Ime(Ime::Commit(text.clone())),
+            Self::Modifiers { bits } => WindowEvent::ModifiersChanged(ModifiersState::from_bits_truncate(*bits).into()),
+            Self::Resized { width, height } => WindowEvent::Resized(PhysicalSize::new(*width, *height)),
+            Self::Focused(focused) => WindowEvent::Focused(*focused),
+        };
+        Some(Event::WindowEvent { window_id, event })
+    }
+    
+    /// Printable text a key press typed, which replay commits to the tree
+    pub fn typed_text(&self) -> Option<&str> {
+        match self {
+            Self::Key { text: Some(text), pressed: true, .. } if !text.chars().any(char::is_control) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded event
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Seconds since recording started
+    pub time: f64,
+    /// Cursor position when the event arrived
+    pub cursor: [f32; 2],
+    pub event: RecordedEvent,
+}
+
+impl RecordedFrame {
+    pub fn cursor(&self) -> Vec2 {
+        Vec2::new(self.cursor[0], self.cursor[1])
+    }
+}
+
+/// A recorded input session
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub version: u32,
+    pub events: Vec<RecordedFrame>,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self { version: RECORDING_VERSION, events: Vec::new() }
+    }
+}
+
+impl Recording {
+    /// Length in seconds (time of the last event)
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0.0, |e| e.time)
+    }
+
+    pub fn to_json(&self) -> Result<String, RecorderError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| RecorderError::SerializeError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RecorderError> {
+        let recording: Self = serde_json::from_str(json)
+            .map_err(|e| RecorderError::ParseError(e.to_string()))?;
+        if recording.version > RECORDING_VERSION {
+            return Err(RecorderError::UnsupportedVersion(recording.version));
+        }
+        Ok(recording)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecorderError> {
+        fs::write(path, self.to_json()?)
+            .map_err(|e| RecorderError::IoError(e.to_string()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecorderError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| RecorderError::IoError(e.to_string()))?;
+        Self::from_json(&content)
+    }
+}
+
+// =============================================================================
+// RECORDER
+// =============================================================================
+
+/// Records incoming winit events while active
+pub struct EventRecorder {
+    recording: Recording,
+    started: Option<Instant>,
+}
+
+impl EventRecorder {
+    pub fn new() -> Self {
+        Self { recording: Recording::default(), started: None }
+    }
+
+    /// Start a fresh recording
+    pub fn start(&mut self) {
+        self.recording = Recording::default();
+        self.started = Some(Instant::now());
+    }
+
+    /// Stop recording and take the captured session
+    pub fn stop(&mut self) -> Recording {
+        self.started = None;
+        std::mem::take(&mut self.recording)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Events captured so far
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Record `event` (call for every event the app receives)
+    pub fn record(&mut self, event: &Event<()>, cursor: Vec2) {
+        if let Some(started) = self.started {
+            self.record_at(event, cursor, started.elapsed().as_secs_f64());
+        }
+    }
+
+    /// Record `event` at an explicit time (seconds since start)
+    pub fn record_at(&mut self, event: &Event<()>, cursor: Vec2, time: f64) {
+        if self.started.is_none() {
+            return;
+        }
+        if let Some(event) = RecordedEvent::from_event(event) {
+            // A move carries the new position; `cursor` may not be updated yet
+            let cursor = match event {
+                RecordedEvent::CursorMoved { x, y } => Vec2::new(x as f32, y as f32),
+                _ => cursor,
+            };
+            self.recording.events.push(RecordedFrame { time, cursor: [cursor.x, cursor.y], event });
+        }
+    }
+}
+
+impl Default for EventRecorder {
+    fn default() -> Self { Self::new() }
+}
+
+// =============================================================================
+// REPLAYER
+// =============================================================================
+
+/// Plays a recording back into a widget tree
+pub struct EventReplayer {
+    recording: Recording,
+    time: f64,
+    next: usize,
+    cursor: Vec2,
+}
+
+impl EventReplayer {
+    pub fn new(recording: Recording) -> Self {
+        Self { recording, time: 0.0, next: 0, cursor: Vec2::ZERO }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecorderError> {
+        Ok(Self::new(Recording::load(path)?))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+
+    /// Playback position in seconds
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Cursor position as of the last replayed event
+    pub fn cursor(&self) -> Vec2 {
+        self.cursor
+    }
+
+    /// Restart from the beginning
+    pub fn rewind(&mut self) {
+        self.time = 0.0;
+        self.next = 0;
+        self.cursor = Vec2::ZERO;
+    }
+
+    /// Advance by `dt` seconds and return the events that became due
+    pub fn advance(&mut self, dt: f32) -> &[RecordedFrame] {
+        self.time += dt as f64;
+        let start = self.next;
+        while self.recording.events.get(self.next).is_some_and(|e| e.time <= self.time) {
+            self.next += 1;
+        }
+        if let Some(last) = self.recording.events[start..self.next].last() {
+            self.cursor = last.cursor();
+        }
+        &self.recording.events[start..self.next]
+    }
+
+    /// Advance by `dt` and dispatch due events to `root`
+    ///
+    /// Keyboard events can't be rebuilt as winit events: the text a key
+    /// typed is committed to `root` and the key is passed to `on_key`.
+    /// Returns the number of events replayed.
+    pub fn replay_into(&mut self, root: &mut dyn Widget, dt: f32, mut on_key: impl FnMut(&RecordedFrame)) -> usize {
+        let due = self.advance(dt);
+        for frame in due {
+            match frame.event.to_event() {
+                Some(event) => {
+                    root.handle_event(&event, frame.cursor());
+                }
+                None => {
+                    if let Some(commit) = frame.event.typed_text().and_then(|text| RecordedEvent::Text(text.into()).to_event()) {
+                        root.handle_event(&commit, frame.cursor());
+                    }
+                    on_key(frame);
+                }
+            }
+        }
+        due.len()
+    }
+
+    /// Replay the whole recording at a fixed frame step, updating and laying out
+    /// `root` every frame like the app's event loop would
+    pub fn run_to_end(&mut self, root: &mut dyn Widget, frame_dt: f32, screen: Vec2) {
+        let frame_dt = frame_dt.max(1e-4);
+        while !self.is_finished() {
+            self.replay_into(root, frame_dt, |_| {});
+            root.update(frame_dt);
+            root.layout(Vec2::ZERO, screen);
+        }
+    }
+}
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+/// Recording load/save errors
+#[derive(Clone, Debug)]
+pub enum RecorderError {
+    IoError(String),
+    ParseError(String),
+    SerializeError(String),
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for RecorderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecorderError::IoError(e) => write!(f, "IO error: {}", e),
+            RecorderError::ParseError(e) => write!(f, "Parse error: {}", e),
+            RecorderError::SerializeError(e) => write!(f, "Serialize error: {}", e),
+            RecorderError::UnsupportedVersion(v) => write!(f, "Unsupported recording version: {}", v),
+        }
+    }
+}
+
+impl std::error::Error for RecorderError {}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::{Checkbox, TextInput};
+
+    fn click(pressed: bool) -> Event<()> {
+        RecordedEvent::MouseInput { button: RecordedButton::Left, pressed }.to_event().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_json() {
+        let mut recorder = EventRecorder::new();
+        recorder.record_at(&click(true), Vec2::new(1.0, 2.0), 0.0);
+        assert!(recorder.recording().events.is_empty(), "not recording yet");
+
+        recorder.start();
+        recorder.record_at(&RecordedEvent::CursorMoved { x: 5.0, y: 6.0 }.to_event().unwrap(), Vec2::new(5.0, 6.0), 0.1);
+        recorder.record_at(&click(true), Vec2::new(5.0, 6.0), 0.2);
+        recorder.record_at(&RecordedEvent::MouseWheel { dx: 0.0, dy: -3.0, lines: true }.to_event().unwrap(), Vec2::new(5.0, 6.0), 0.3);
+        let recording = recorder.stop();
+        assert_eq!(recording.events.len(), 3);
+        assert_eq!(recording.duration(), 0.3);
+
+        let loaded = Recording::from_json(&recording.to_json().unwrap()).unwrap();
+        assert_eq!(loaded, recording);
+        assert_eq!(RecordedEvent::from_event(&loaded.events[1].event.to_event().unwrap()), Some(loaded.events[1].event.clone()));
+    }
+
+    #[test]
+    fn test_replay_is_driven_by_dt() {
+        let recording = Recording {
+            version: RECORDING_VERSION,
+            events: vec![
+                RecordedFrame { time: 0.05, cursor: [1.0, 1.0], event: RecordedEvent::CursorEntered },
+                RecordedFrame { time: 0.20, cursor: [2.0, 2.0], event: RecordedEvent::Focused(true) },
+            ],
+        };
+        let mut replayer = EventReplayer::new(recording);
+        assert_eq!(replayer.advance(0.1).len(), 1);
+        assert_eq!(replayer.cursor(), Vec2::ONE);
+        assert_eq!(replayer.advance(0.05).len(), 0);
+        assert_eq!(replayer.advance(0.1).len(), 1);
+        assert!(replayer.is_finished());
+    }
+
+    #[test]
+    fn test_replay_into_widget() {
+        let mut checkbox = Checkbox::new("Remember me", false);
+        checkbox.layout(Vec2::ZERO, Vec2::new(200.0, 24.0));
+        let recording = Recording {
+            version: RECORDING_VERSION,
+            events: vec![
+                RecordedFrame { time: 0.0, cursor: [5.0, 5.0], event: RecordedEvent::CursorMoved { x: 5.0, y: 5.0 } },
+                RecordedFrame { time: 0.0, cursor: [5.0, 5.0], event: RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true } },
+                RecordedFrame { time: 0.0, cursor: [5.0, 5.0], event: RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: false } },
+                RecordedFrame { time: 0.1, cursor: [5.0, 5.0], event: RecordedEvent::Key { key: "Enter".into(), text: None, pressed: true, repeat: false } },
+            ],
+        };
+
+        let mut replayer = EventReplayer::new(recording.clone());
+        let mut keys = Vec::new();
+        assert_eq!(replayer.replay_into(&mut checkbox, 0.2, |frame| keys.push(frame.event.clone())), 4);
+        assert!(checkbox.is_checked());
+        assert_eq!(keys.len(), 1);
+
+        // Same recording, same result
+        let mut again = Checkbox::new("Remember me", false);
+        again.layout(Vec2::ZERO, Vec2::new(200.0, 24.0));
+        EventReplayer::new(recording).run_to_end(&mut again, 1.0 / 60.0, Vec2::new(200.0, 24.0));
+        assert_eq!(again.is_checked(), checkbox.is_checked());
+    }
+
+    #[test]
+    fn test_replay_types_keys_into_text_fields() {
+        let mut input = TextInput::new("Name");
+        input.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        let key = |key: &str, text: &str| RecordedEvent::Key { key: key.into(), text: Some(text.into()), pressed: true, repeat: false };
+        let frame = |event| RecordedFrame { time: 0.0, cursor: [5.0, 5.0], event };
+        let recording = Recording {
+            version: RECORDING_VERSION,
+            events: vec![
+                frame(RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }),
+                frame(RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: false }),
+                frame(key("h", "h")),
+                frame(key("i", "i")),
+                frame(key("Enter", "\r")),
+                frame(RecordedEvent::Text("!".into())),
+            ],
+        };
+
+        let mut keys = Vec::new();
+        EventReplayer::new(recording.clone()).replay_into(&mut input, 0.1, |frame| keys.push(frame.event.clone()));
+        assert_eq!(input.text, "hi!");
+        assert_eq!(keys.len(), 3, "every key still reaches on_key");
+        
+        let commit = recording.events[5].event.to_event().unwrap();
+        assert_eq!(RecordedEvent::from_event(&commit), Some(RecordedEvent::Text("!".into())));
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let json = format!("{{\"version\": {}, \"events\": []}}", RECORDING_VERSION + 1);
+        assert!(matches!(Recording::from_json(&json), Err(RecorderError::UnsupportedVersion(_))));
+    }
+}
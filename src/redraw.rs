@@ -0,0 +1,295 @@
+//! GlassUI Redraw Scheduling
+//!
+//! Event-driven redraws instead of continuous polling:
+//! - `RedrawMode` - Continuous (game-style) or on-demand rendering
+//! - `RedrawScheduler` - Decides when the next frame is needed
+//! - `RedrawWaker` - Thread-safe handle that wakes the event loop
+//!
+//! In on-demand mode a frame is drawn when input arrives, when a widget
+//! reports `is_animating()`, or when a waker fires (e.g. from a `State`
+//! subscription or a background thread). Between frames the event loop
+//! sleeps with `ControlFlow::Wait` / `ControlFlow::WaitUntil`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use winit::event::{Event, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoopProxy};
+use crate::state::{State, Subscription};
+
+// =============================================================================
+// REDRAW MODE
+// =============================================================================
+
+/// How often the UI is redrawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RedrawMode {
+    /// Redraw every frame (`ControlFlow::Poll`)
+    Continuous,
+    /// Redraw only when something changed (`ControlFlow::Wait`)
+    #[default]
+    OnDemand,
+}
+
+// =============================================================================
+// REDRAW WAKER
+// =============================================================================
+
+/// Cloneable handle that requests a redraw from anywhere
+///
+/// Sets a pending flag and, if an event loop proxy is attached, sends a
+/// user event so a sleeping event loop wakes up immediately.
+#[derive(Clone)]
+pub struct RedrawWaker {
+    pending: Arc<AtomicBool>,
+    proxy: Option<EventLoopProxy<()>>,
+}
+
+impl RedrawWaker {
+    /// Request a redraw
+    pub fn wake(&self) {
+        if !self.pending.swap(true, Ordering::AcqRel) {
+            if let Some(proxy) = &self.proxy {
+                let _ = proxy.send_event(());
+            }
+        }
+    }
+
+    /// Wake whenever the given state changes
+    pub fn watch<T: Clone + 'static>(&self, state: &State<T>) -> Subscription {
+        let waker = self.clone();
+        state.subscribe(move |_| waker.wake())
+    }
+}
+
+// =============================================================================
+// REDRAW SCHEDULER
+// =============================================================================
+
+/// Decides when frames are drawn and how the event loop waits
+pub struct RedrawScheduler {
+    pub mode: RedrawMode,
+    /// Keep drawing for this long after input so hover/press transitions finish
+    pub settle_time: Duration,
+    /// Target interval between frames while active
+    pub frame_interval: Duration,
+    active_until: Option<Instant>,
+    last_frame: Option<Instant>,
+    pending: Arc<AtomicBool>,
+    proxy: Option<EventLoopProxy<()>>,
+}
+
+impl RedrawScheduler {
+    pub fn new(mode: RedrawMode) -> Self {
+        Self {
+            mode,
+            settle_time: Duration::from_millis(500),
+            frame_interval: Duration::from_millis(16),
+            active_until: None,
+            last_frame: None,
+            // First frame is always needed
+            pending: Arc::new(AtomicBool::new(true)),
+            proxy: None,
+        }
+    }
+
+    /// Attach an event loop proxy so wakers can interrupt `ControlFlow::Wait`
+    pub fn with_proxy(mut self, proxy: EventLoopProxy<()>) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn with_settle_time(mut self, settle_time: Duration) -> Self {
+        self.settle_time = settle_time;
+        self
+    }
+
+    pub fn with_frame_interval(mut self, frame_interval: Duration) -> Self {
+        self.frame_interval = frame_interval;
+        self
+    }
+
+    /// Create a waker sharing this scheduler's pending flag
+    pub fn waker(&self) -> RedrawWaker {
+        RedrawWaker {
+            pending: Arc::clone(&self.pending),
+            proxy: self.proxy.clone(),
+        }
+    }
+
+    /// Request a redraw on the next `AboutToWait`
+    pub fn request_redraw(&self) {
+        self.pending.store(true, Ordering::Release);
+    }
+
+    /// Keep redrawing until `settle_time` has passed
+    pub fn mark_active(&mut self, now: Instant) {
+        let until = now + self.settle_time;
+        self.active_until = Some(self.active_until.map_or(until, |t| t.max(until)));
+        self.request_redraw();
+    }
+
+    /// Observe an event; input and wake-ups schedule a redraw
+    pub fn handle_event(&mut self, event: &Event<()>, now: Instant) {
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::RedrawRequested => {}
+                WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. } | WindowEvent::Occluded(false) => {
+                    self.request_redraw();
+                }
+                WindowEvent::CursorMoved { .. }
+                | WindowEvent::CursorLeft { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::KeyboardInput { .. }
+                | WindowEvent::ModifiersChanged(_)
+                | WindowEvent::Ime(_)
+                | WindowEvent::Touch(_)
+                | WindowEvent::Focused(_) => {
+                    self.mark_active(now);
+                }
+                _ => {}
+            },
+            Event::UserEvent(()) => self.request_redraw(),
+            _ => {}
+        }
+    }
+
+    /// Called after a frame was drawn
+    ///
+    /// `animating` should come from the root widget's `is_animating()`.
+    /// Returns the elapsed time since the previous frame, capped so a long
+    /// idle period doesn't produce a huge animation step.
+    pub fn frame_drawn(&mut self, now: Instant, animating: bool) -> f32 {
+        let dt = self.last_frame
+            .map(|t| now.duration_since(t).as_secs_f32())
+            .unwrap_or(self.frame_interval.as_secs_f32())
+            .min(0.1);
+        self.last_frame = Some(now);
+        if animating {
+            self.request_redraw();
+        }
+        dt
+    }
+
+    /// Whether a redraw should be requested now
+    ///
+    /// Consumes the pending flag. Call from `Event::AboutToWait`.
+    pub fn should_redraw(&mut self, now: Instant) -> bool {
+        let pending = self.pending.swap(false, Ordering::AcqRel);
+        match self.mode {
+            RedrawMode::Continuous => true,
+            RedrawMode::OnDemand => pending || self.is_active(now),
+        }
+    }
+
+    /// Control flow to use until the next event
+    pub fn control_flow(&self, now: Instant) -> ControlFlow {
+        match self.mode {
+            RedrawMode::Continuous => ControlFlow::Poll,
+            RedrawMode::OnDemand => {
+                if self.pending.load(Ordering::Acquire) || self.is_active(now) {
+                    let next = self.last_frame.map_or(now, |t| t + self.frame_interval);
+                    ControlFlow::WaitUntil(next.max(now))
+                } else {
+                    ControlFlow::Wait
+                }
+            }
+        }
+    }
+
+    fn is_active(&self, now: Instant) -> bool {
+        self.active_until.is_some_and(|t| now < t)
+    }
+}
+
+impl Default for RedrawScheduler {
+    fn default() -> Self {
+        Self::new(RedrawMode::OnDemand)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_frame_requested() {
+        let mut scheduler = RedrawScheduler::default();
+        let now = Instant::now();
+        assert!(scheduler.should_redraw(now));
+        assert!(!scheduler.should_redraw(now));
+        assert_eq!(scheduler.control_flow(now), ControlFlow::Wait);
+    }
+
+    #[test]
+    fn test_input_keeps_active_until_settled() {
+        let mut scheduler = RedrawScheduler::default();
+        let now = Instant::now();
+        scheduler.should_redraw(now);
+
+        scheduler.mark_active(now);
+        assert!(scheduler.should_redraw(now));
+        assert!(scheduler.should_redraw(now + Duration::from_millis(100)));
+        assert!(!scheduler.should_redraw(now + Duration::from_secs(1)));
+        assert_eq!(scheduler.control_flow(now + Duration::from_secs(1)), ControlFlow::Wait);
+    }
+
+    #[test]
+    fn test_waker_requests_redraw() {
+        let mut scheduler = RedrawScheduler::default();
+        let now = Instant::now();
+        scheduler.should_redraw(now);
+
+        let waker = scheduler.waker();
+        waker.wake();
+        assert!(scheduler.should_redraw(now));
+        assert!(!scheduler.should_redraw(now));
+    }
+
+    #[test]
+    fn test_watch_state() {
+        let mut scheduler = RedrawScheduler::default();
+        let now = Instant::now();
+        scheduler.should_redraw(now);
+
+        let state = State::new(1);
+        let _sub = scheduler.waker().watch(&state);
+        state.set(2);
+        assert!(scheduler.should_redraw(now));
+    }
+
+    #[test]
+    fn test_animating_requests_next_frame() {
+        let mut scheduler = RedrawScheduler::default();
+        let now = Instant::now();
+        scheduler.should_redraw(now);
+
+        scheduler.frame_drawn(now, true);
+        assert!(scheduler.should_redraw(now));
+        scheduler.frame_drawn(now, false);
+        assert!(!scheduler.should_redraw(now));
+    }
+
+    #[test]
+    fn test_continuous_always_redraws() {
+        let mut scheduler = RedrawScheduler::new(RedrawMode::Continuous);
+        let now = Instant::now();
+        assert!(scheduler.should_redraw(now));
+        assert!(scheduler.should_redraw(now));
+        assert_eq!(scheduler.control_flow(now), ControlFlow::Poll);
+    }
+
+    #[test]
+    fn test_frame_dt_capped() {
+        let mut scheduler = RedrawScheduler::default();
+        let now = Instant::now();
+        scheduler.frame_drawn(now, false);
+        let dt = scheduler.frame_drawn(now + Duration::from_secs(5), false);
+        assert!((dt - 0.1).abs() < 1e-6);
+    }
+}
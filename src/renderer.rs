@@ -1,6 +1,24 @@
 use wgpu::util::DeviceExt;
 use winit::window::Window;
 use crate::widget::Widget;
+use crate::widgets::{is_occluded, rounded_opaque_rect, debug_layout, render_layout_debug, get_theme, theme_generation, place_tooltip, TooltipContent};
+use crate::layout::{EdgeInsets, Rect};
+use crate::profiler::{FrameTimings, GpuPass, GpuTimer, PerfStage, RenderStats};
+use crate::plugin::RendererPlugin;
+use crate::background::Background;
+use crate::text::{FontError, FontId, TextLayout, TextOverflow};
+
+/// `wgpu::include_wgsl!` for shaders that sample the batch clip, with the
+/// shared `Clip` struct and `clip_alpha` from clip.wgsl prepended
+macro_rules! clipped_shader {
+    ($path:literal) => {
+        wgpu::ShaderModuleDescriptor {
+            label: Some($path),
+            source: wgpu::ShaderSource::Wgsl(concat!(include_str!("shaders/clip.wgsl"), include_str!($path)).into()),
+        }
+    };
+}
+pub(crate) use clipped_shader;
 
 pub struct GlassRenderer {
     surface: wgpu::Surface<'static>,
@@ -13,21 +31,37 @@ pub struct GlassRenderer {
     bg_pipeline_offscreen: wgpu::RenderPipeline, // Targets Rgba8Unorm
     bg_pipeline_onscreen: wgpu::RenderPipeline,  // Targets Surface Format
     glass_pipeline: wgpu::RenderPipeline,
+    image_pipeline: wgpu::RenderPipeline,
+    cache_image_pipeline: wgpu::RenderPipeline, // Premultiplied blit of RenderCache textures
+    backdrop_pipeline: wgpu::RenderPipeline,
+    gradient_pipeline: wgpu::RenderPipeline,
+    mesh_pipeline: wgpu::RenderPipeline,
     blur_pipeline: wgpu::ComputePipeline,
     
     // Bind Group Layouts (Stored for recreation on resize)
+    bg_bind_group_layout: wgpu::BindGroupLayout,
     blur_bind_group_layout: wgpu::BindGroupLayout,
     glass_texture_layout: wgpu::BindGroupLayout,
+    texture_layout: wgpu::BindGroupLayout,
+    
+    // Uploaded images (slots of unloaded textures are reused)
+    textures: Vec<Option<LoadedTexture>>,
+    image_sampler: wgpu::Sampler,
+    image_format: wgpu::TextureFormat,
     
     // Bind Groups
     bg_bind_group: wgpu::BindGroup,
-    blur_bind_groups: Vec<wgpu::BindGroup>, // [Horizontal, Vertical]
+    blur_bind_groups: Vec<wgpu::BindGroup>, // [Horizontal, Vertical, Horizontal again (iterations > 1)]
     glass_texture_bind_group: wgpu::BindGroup,
     
     // Buffers
     uniform_buffer: wgpu::Buffer,
     blur_params_buffer: wgpu::Buffer,
-    pub instance_buffer: wgpu::Buffer,
+    instance_buffer: GrowableBuffer,
+    image_buffer: GrowableBuffer,
+    gradient_buffer: GrowableBuffer,
+    mesh_vertex_buffer: GrowableBuffer,
+    mesh_index_buffer: GrowableBuffer,
     
     // Render Targets
     scene_texture: wgpu::Texture,
@@ -36,31 +70,723 @@ pub struct GlassRenderer {
     blur_intermediate_view: wgpu::TextureView,
     blur_final_texture: wgpu::Texture,
     blur_final_view: wgpu::TextureView,
+    blur: BlurSettings,
+    // Current theme's override of `blur`, refreshed when the theme changes
+    theme_blur: Option<BlurSettings>,
+    theme_blur_generation: u64,
+    blur_size: (u32, u32),
+    quality: GlassQuality,
+    
+    // Post-processing plugins and the ping-pong targets they read from
+    post_passes: Vec<PostPass>,
+    post_targets: Vec<PostTarget>,
+    
+    // HiDPI: widgets work in logical pixels, scaled by the window's factor
+    // unless overridden with set_ui_scale
+    window_scale: f32,
+    ui_scale: Option<f32>,
+    
+    // Multisampled color target of the final pass (None without MSAA)
+    sample_count: u32,
+    msaa_view: Option<wgpu::TextureView>,
+    
+    // Backdrop glass (re-blurs the frame so far, see draw_backdrop_glass)
+    backdrop_supported: bool,
+    backdrop_pending: bool,
+    
+    // Window translucency (borderless glass windows)
+    opaque_alpha_mode: wgpu::CompositeAlphaMode,
+    translucent_alpha_mode: Option<wgpu::CompositeAlphaMode>,
+    background_opacity: f32,
+    // What bg_pipeline_* draw (solid colors and None are just the clear)
+    background: Background,
     
     start_time: std::time::Instant,
+    // Last uniforms written by update(), restored after drawing a RenderCache
+    uniforms: Uniforms,
     
     // Batching
     instances: Vec<GlassInstance>,
+    /// Instances `hide_covered_glass` collapsed this frame (still in
+    /// `instances`, left out of the stats)
+    hidden_glass: usize,
+    images: Vec<ImageInstance>,
+    image_textures: Vec<TextureId>,
+    gradients: Vec<GradientInstance>,
+    mesh_vertices: Vec<MeshVertex>,
+    mesh_indices: Vec<u32>,
     
     // Batches
     batches: Vec<RenderBatch>,
     pub current_scissor: Option<[u32; 4]>,
+    current_rounded: Option<RoundedClip>,
+    current_layer: i32,
+    clip_stack: Vec<ClipState>,
+    
+    // 2D transforms and group opacities (top = everything pushed composed),
+    // applied to whatever was drawn since draw_marks when either changes
+    transform_stack: Vec<glam::Affine2>,
+    opacity_stack: Vec<f32>,
+    draw_marks: [usize; 5],
+    
+    // Per-batch rounded clip uniforms (one aligned slot per batch, slot 0 = none)
+    clip_layout: wgpu::BindGroupLayout,
+    clip_buffer: wgpu::Buffer,
+    clip_bind_group: wgpu::BindGroup,
+    clip_stride: u32,
 
-    // Tooltips
-    tooltips: Vec<(String, crate::Vec2)>,
+    // Tooltips (title, body and shortcut packed into one queue entry)
+    tooltips: TextQueue<QueuedTooltip>,
     
     // Overlays (dropdowns, popups - render on top of everything)
+    overlay_shadows: Vec<GradientInstance>,
     overlay_rects: Vec<GlassInstance>,
-    overlay_texts: Vec<(String, [f32; 2], f32, [f32; 4])>,
+    overlay_texts: TextQueue<([f32; 2], f32, [f32; 4])>,
+    overlay_buffer: GrowableBuffer,
+    
+    // Reused by draw_text_fmt to avoid allocating a String per call
+    scratch_text: String,
     
     // Text
     text_renderer: crate::text::TextRenderer,
+    
+    // Profiling
+    gpu_timer: Option<GpuTimer>,
+    profiling: bool,
+    frame_timings: FrameTimings,
+}
+
+/// Resolution the backdrop blur runs at, relative to the window
+/// 
+/// The blurred scene is sampled with linear filtering when compositing,
+/// so a downsampled blur is upscaled for free and looks the same for
+/// frosted glass while touching far fewer pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlurDownsample {
+    /// Pick based on window size (½ from 1080p, ¼ from 4K)
+    #[default]
+    Auto,
+    /// Full resolution
+    Full,
+    /// Half resolution in each dimension (¼ of the pixels)
+    Half,
+    /// Quarter resolution in each dimension (1/16 of the pixels)
+    Quarter,
+}
+
+impl BlurDownsample {
+    /// Divisor applied to each dimension for a window of the given size
+    pub fn factor(&self, width: u32, height: u32) -> u32 {
+        match self {
+            BlurDownsample::Auto => {
+                let pixels = width as u64 * height as u64;
+                if pixels >= 3840 * 2160 {
+                    4
+                } else if pixels >= 1920 * 1080 {
+                    2
+                } else {
+                    1
+                }
+            }
+            BlurDownsample::Full => 1,
+            BlurDownsample::Half => 2,
+            BlurDownsample::Quarter => 4,
+        }
+    }
+    
+    /// Blur target size for a window of the given size
+    pub fn scaled(&self, width: u32, height: u32) -> (u32, u32) {
+        let factor = self.factor(width, height);
+        ((width / factor).max(1), (height / factor).max(1))
+    }
+}
+
+/// Strength and cost of the backdrop blur, see `GlassRenderer::set_blur`
+/// 
+/// To drop the blur entirely (low-power mode) use `GlassQuality::Flat`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlurSettings {
+    /// Gaussian radius in logical pixels (sigma is half of it)
+    pub radius: f32,
+    /// Times the horizontal + vertical pass pair runs; each one widens the
+    /// blur by about √2
+    pub iterations: u32,
+    /// Resolution the blur runs at
+    pub downsample: BlurDownsample,
+}
+
+impl Default for BlurSettings {
+    fn default() -> Self {
+        Self { radius: 8.0, iterations: 1, downsample: BlurDownsample::Auto }
+    }
+}
+
+impl BlurSettings {
+    /// Largest radius a single pass samples, in blur-target texels
+    pub const MAX_TAPS: f32 = 32.0;
+    
+    pub fn new(radius: f32) -> Self {
+        Self { radius: radius.max(0.0), ..Default::default() }
+    }
+    
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations.clamp(1, 8);
+        self
+    }
+    
+    pub fn with_downsample(mut self, downsample: BlurDownsample) -> Self {
+        self.downsample = downsample;
+        self
+    }
+    
+    /// Radius in texels of a blur target downsampled by `factor`, for a
+    /// window at `scale` physical pixels per logical pixel
+    pub fn texel_radius(&self, scale: f32, factor: u32) -> f32 {
+        (self.radius * scale / factor.max(1) as f32).min(Self::MAX_TAPS)
+    }
+}
+
+/// How much of the glass effect the renderer draws
+/// 
+/// Lower levels keep the same widget API and layout; only the backdrop
+/// passes and the glass shading change, so this can be switched at
+/// runtime (e.g. when a laptop goes on battery).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GlassQuality {
+    /// Blurred backdrop at the configured `BlurDownsample`, with edge glow
+    #[default]
+    Full,
+    /// Blurred backdrop at quarter resolution, no edge glow
+    Light,
+    /// No backdrop blur; glass is drawn as a flat tinted fill
+    Flat,
+}
+
+impl GlassQuality {
+    pub const ALL: [GlassQuality; 3] = [GlassQuality::Full, GlassQuality::Light, GlassQuality::Flat];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            GlassQuality::Full => "Full glass",
+            GlassQuality::Light => "Light blur",
+            GlassQuality::Flat => "Flat",
+        }
+    }
+    
+    /// Next level, wrapping around (for a settings toggle)
+    pub fn next(&self) -> Self {
+        match self {
+            GlassQuality::Full => GlassQuality::Light,
+            GlassQuality::Light => GlassQuality::Flat,
+            GlassQuality::Flat => GlassQuality::Full,
+        }
+    }
+    
+    /// Whether the scene and blur passes run
+    pub fn uses_blur(&self) -> bool {
+        *self != GlassQuality::Flat
+    }
+    
+    /// Value passed to the glass shader
+    fn shader_level(&self) -> u32 {
+        match self {
+            GlassQuality::Full => 0,
+            GlassQuality::Light => 1,
+            GlassQuality::Flat => 2,
+        }
+    }
+}
+
+/// Edge anti-aliasing of the final (on-screen) pass
+/// 
+/// Shapes already smooth their SDF edges in the shaders; multisampling
+/// also smooths tessellated paths and text, and steadies edges while
+/// panels are dragged. Fixed when the renderer is created, since every
+/// on-screen pipeline is built for one sample count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AntiAliasing {
+    /// Shader edge smoothing only
+    #[default]
+    Off,
+    /// 4x multisampling, resolved into the surface
+    Msaa4x,
+}
+
+impl AntiAliasing {
+    /// Samples per pixel of the final pass
+    pub fn sample_count(&self) -> u32 {
+        match self {
+            AntiAliasing::Off => 1,
+            AntiAliasing::Msaa4x => 4,
+        }
+    }
+    
+    /// `sample_count`, or 1 when `supported` says the surface format can't
+    /// be multisampled that many times
+    fn supported_sample_count(&self, supported: impl Fn(u32) -> bool) -> u32 {
+        match self.sample_count() {
+            1 => 1,
+            count if supported(count) => count,
+            _ => 1,
+        }
+    }
+}
+
+/// Queued strings sharing one backing buffer
+/// 
+/// Avoids allocating a `String` per queued draw; the buffer keeps its
+/// capacity across frames.
+struct TextQueue<T> {
+    buffer: String,
+    entries: Vec<(std::ops::Range<usize>, T)>,
+}
+
+impl<T> TextQueue<T> {
+    fn new() -> Self {
+        Self { buffer: String::new(), entries: Vec::new() }
+    }
+    
+    fn push(&mut self, text: &str, data: T) {
+        let start = self.buffer.len();
+        self.buffer.push_str(text);
+        self.entries.push((start..self.buffer.len(), data));
+    }
+    
+    /// Queue several strings as one entry (read back with `split_at`)
+    fn push_parts(&mut self, parts: &[&str], data: T) {
+        let start = self.buffer.len();
+        for part in parts {
+            self.buffer.push_str(part);
+        }
+        self.entries.push((start..self.buffer.len(), data));
+    }
+    
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.entries.clear();
+    }
+    
+    fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.entries.iter().map(|(range, data)| (&self.buffer[range.clone()], data))
+    }
+}
+
+/// Tooltip waiting for the overlay pass
+/// 
+/// Its text is stored as title, body and shortcut back to back.
+struct QueuedTooltip {
+    anchor: crate::Vec2,
+    alpha: f32,
+    title_len: usize,
+    body_len: usize,
+}
+
+const TOOLTIP_PADDING: f32 = 8.0;
+const TOOLTIP_TITLE_SCALE: f32 = 15.0;
+const TOOLTIP_BODY_SCALE: f32 = 14.0;
+const TOOLTIP_HINT_SCALE: f32 = 12.0;
+/// Tooltip bodies wrap at this width
+const TOOLTIP_MAX_WIDTH: f32 = 320.0;
+
+// =============================================================================
+// GROWABLE BUFFERS
+// =============================================================================
+
+/// Vertex/index buffer rewritten from the start every frame
+/// 
+/// Recreated at the next power of two when a frame outgrows it, so after
+/// the first few frames of a large dashboard nothing is reallocated. A
+/// frame past the device's `max_buffer_size` spills into more buffers
+/// (chunks) of whole elements, and `draw_range` splits draws across them.
+pub(crate) struct GrowableBuffer {
+    chunks: Vec<wgpu::Buffer>,
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    /// Bytes per element
+    stride: usize,
+    /// Elements a chunk boundary mustn't split (6 vertices per glyph quad)
+    group: usize,
+    /// Whether a frame may spill into more chunks; indexed meshes can't,
+    /// since their indices address the whole vertex buffer
+    splittable: bool,
+    /// Elements per chunk under the device limit
+    chunk_len: u32,
+    /// Elements uploaded by the last `write`
+    len: u32,
+}
+
+impl GrowableBuffer {
+    /// Buffer of `T`, with room for `capacity` of them to start with
+    pub(crate) fn new<T>(device: &wgpu::Device, label: &'static str, usage: wgpu::BufferUsages, capacity: usize) -> Self {
+        let usage = usage | wgpu::BufferUsages::COPY_DST;
+        let stride = std::mem::size_of::<T>();
+        let size = (capacity * stride) as u64;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor { label: Some(label), size, usage, mapped_at_creation: false });
+        Self { chunks: vec![buffer], label, usage, stride, group: 1, splittable: true, chunk_len: u32::MAX, len: 0 }
+    }
+    
+    /// Keep runs of `group` elements in one chunk
+    pub(crate) fn grouped(mut self, group: usize) -> Self {
+        self.group = group;
+        self
+    }
+    
+    /// Never spill into a second chunk; what doesn't fit is dropped
+    pub(crate) fn whole(mut self) -> Self {
+        self.splittable = false;
+        self
+    }
+    
+    /// Upload `bytes` from the start, growing or adding chunks as needed
+    pub(crate) fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, bytes: &[u8]) {
+        let max_size = device.limits().max_buffer_size;
+        self.chunk_len = buffer_capacity(max_size, self.stride, self.group) as u32;
+        let total = bytes.len() / self.stride;
+        let limit = if self.splittable { u32::MAX as usize } else { self.chunk_len as usize };
+        let count = total.min(limit - limit % self.group);
+        if count < total {
+            log::warn!("{}: frame exceeds the GPU buffer size limit, dropping {} of {} elements", self.label, total - count, total);
+        }
+        self.len = count as u32;
+        
+        let chunk_bytes = self.chunk_len as usize * self.stride;
+        let mut used = 0;
+        for (i, chunk) in bytes[..count * self.stride].chunks(chunk_bytes).enumerate() {
+            let needed = chunk.len() as u64;
+            if i == self.chunks.len() || needed > self.chunks[i].size() {
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some(self.label),
+                    size: grown_size(needed, max_size),
+                    usage: self.usage,
+                    mapped_at_creation: false,
+                });
+                if i == self.chunks.len() {
+                    self.chunks.push(buffer);
+                } else {
+                    self.chunks[i] = buffer;
+                }
+            }
+            queue.write_buffer(&self.chunks[i], 0, chunk);
+            used = i + 1;
+        }
+        // Let go of chunks a past frame needed but this one doesn't
+        self.chunks.truncate(used.max(1));
+    }
+    
+    /// The first chunk, which draws expect bound (see `draw_range`)
+    pub(crate) fn slice(&self) -> wgpu::BufferSlice<'_> {
+        self.chunks[0].slice(..)
+    }
+    
+    /// `range` cut to the elements uploaded, for buffers drawn from
+    /// directly (e.g. the index buffer of `whole` meshes)
+    pub(crate) fn clamp(&self, range: std::ops::Range<u32>) -> std::ops::Range<u32> {
+        range.start.min(self.len)..range.end.min(self.len)
+    }
+    
+    /// Call `draw` for the elements in `range`, once per chunk they fall in
+    /// and with indices relative to that chunk
+    /// 
+    /// Expects vertex `slot` bound to `slice`; other chunks are bound only
+    /// while drawing from them.
+    pub(crate) fn draw_range<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        slot: u32,
+        range: std::ops::Range<u32>,
+        mut draw: impl FnMut(&mut wgpu::RenderPass<'a>, std::ops::Range<u32>),
+    ) {
+        let mut rebound = false;
+        for (chunk, local) in chunk_ranges(self.clamp(range), self.chunk_len) {
+            if chunk > 0 {
+                render_pass.set_vertex_buffer(slot, self.chunks[chunk].slice(..));
+                rebound = true;
+            }
+            draw(render_pass, local);
+        }
+        if rebound {
+            render_pass.set_vertex_buffer(slot, self.slice());
+        }
+    }
+}
+
+/// `range` split at multiples of `chunk_len`: each piece's chunk index and
+/// its range within that chunk
+fn chunk_ranges(range: std::ops::Range<u32>, chunk_len: u32) -> impl Iterator<Item = (usize, std::ops::Range<u32>)> {
+    let chunk_len = chunk_len.max(1);
+    let mut start = range.start;
+    std::iter::from_fn(move || {
+        if start >= range.end {
+            return None;
+        }
+        let chunk = start / chunk_len;
+        let base = chunk * chunk_len;
+        let end = range.end.min(base.saturating_add(chunk_len));
+        let piece = (chunk as usize, start - base..end - base);
+        start = end;
+        Some(piece)
+    })
+}
+
+/// Buffer size to allocate for `needed` bytes: the next power of two,
+/// capped at the device `limit`
+fn grown_size(needed: u64, limit: u64) -> u64 {
+    needed.next_power_of_two().min(limit).max(needed)
+}
+
+/// How many of `stride` sized elements fit in one buffer, rounded down to
+/// a multiple of `group` (whole glyph quads or triangles)
+fn buffer_capacity(limit: u64, stride: usize, group: usize) -> usize {
+    let count = (limit / stride as u64).min(u32::MAX as u64) as usize;
+    count - count % group
+}
+
+/// Pipeline state currently bound in the final pass
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BoundPipeline {
+    None,
+    Glass,
+    Gradient,
+    Mesh,
+    Image,
+    CacheImage,
+    Text,
+    Overlay,
+}
+
+/// Rounded rect that content is clipped to in the fragment shaders
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RoundedClip {
+    rect: Rect,
+    radius: f32,
+}
+
+impl RoundedClip {
+    /// In physical pixels, as the shaders compare it with `clip_position`
+    fn scaled(self, scale: f32) -> Self {
+        Self { rect: Rect::new(self.rect.min * scale, self.rect.size() * scale), radius: self.radius * scale }
+    }
+}
+
+/// Entry of the `push_clip` stack
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ClipState {
+    /// Intersection of every pushed rect
+    bounds: Rect,
+    /// Innermost rounded clip (the shaders support one at a time)
+    rounded: Option<RoundedClip>,
+}
+
+impl ClipState {
+    fn push(parent: Option<&ClipState>, rect: Rect, radius: f32) -> Self {
+        let bounds = parent.map_or(rect, |p| p.bounds.intersection(&rect));
+        let rounded = if radius > 0.0 {
+            Some(RoundedClip { rect, radius })
+        } else {
+            parent.and_then(|p| p.rounded)
+        };
+        Self { bounds, rounded }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ClipUniform {
+    rect: [f32; 4],
+    radius: f32,
+    _padding: [f32; 3],
+}
+
+impl ClipUniform {
+    fn new(clip: Option<RoundedClip>) -> Self {
+        match clip {
+            Some(c) => Self { rect: [c.rect.min.x, c.rect.min.y, c.rect.max.x, c.rect.max.y], radius: c.radius, _padding: [0.0; 3] },
+            None => Self { rect: [0.0; 4], radius: 0.0, _padding: [0.0; 3] },
+        }
+    }
 }
 
 struct RenderBatch {
+    /// Starts with backdrop glass: re-blur the frame before drawing it
+    backdrop: bool,
     scissor: Option<[u32; 4]>,
+    rounded: Option<RoundedClip>,
     glass_range: std::ops::Range<u32>,
+    gradient_range: std::ops::Range<u32>,
+    /// Indices into the mesh index buffer (tessellated paths)
+    mesh_range: std::ops::Range<u32>,
+    image_range: std::ops::Range<u32>,
     text_range: std::ops::Range<u32>,
+    /// `set_layer` value; batches draw in layer order, then call order
+    layer: i32,
+    /// Area covered by the batch's text (glass, gradients and images drawn
+    /// later may only be merged in if they stay clear of this, since
+    /// batches draw text last)
+    text_bounds: Option<Rect>,
+}
+
+impl RenderBatch {
+    /// Whether a following batch can be folded into this one
+    /// 
+    /// The merged batch keeps this batch's scissor, so the following
+    /// content must fit inside both scissors (i.e. it isn't actually
+    /// clipped). Its glass is drawn before this batch's text once merged,
+    /// so it must not overlap that text.
+    fn can_absorb(&self, layer: i32, scissor: Option<[u32; 4]>, rounded: Option<RoundedClip>, content: Rect, glass: Option<Rect>) -> bool {
+        if self.layer != layer || self.rounded != rounded {
+            return false;
+        }
+        let fits = |scissor: Option<[u32; 4]>| {
+            scissor.is_none_or(|s| scissor_rect(s).contains_rect(&content))
+        };
+        let clip_ok = self.scissor == scissor || (fits(self.scissor) && fits(scissor));
+        let order_ok = match (self.text_bounds, glass) {
+            (Some(text), Some(glass)) => !text.intersects(&glass),
+            _ => true,
+        };
+        clip_ok && order_ok
+    }
+}
+
+fn scissor_rect(s: [u32; 4]) -> Rect {
+    Rect::new(
+        crate::Vec2::new(s[0] as f32, s[1] as f32),
+        crate::Vec2::new(s[2] as f32, s[3] as f32),
+    )
+}
+
+/// Smallest pixel scissor covering `rect`, clamped to a `target` sized
+/// render target (zero-sized when nothing is visible)
+fn scissor_for(rect: Rect, target: (u32, u32)) -> [u32; 4] {
+    let (w, h) = (target.0 as f32, target.1 as f32);
+    let x0 = rect.min.x.floor().clamp(0.0, w);
+    let y0 = rect.min.y.floor().clamp(0.0, h);
+    let x1 = rect.max.x.ceil().clamp(x0, w);
+    let y1 = rect.max.y.ceil().clamp(y0, h);
+    [x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32]
+}
+
+/// Boundaries of the final-pass segments: a new segment (and backdrop
+/// re-blur) starts at every backdrop batch except the first batch
+fn pass_segments(backdrop: impl Iterator<Item = bool>) -> Vec<usize> {
+    let mut segments = vec![0];
+    let mut count = 0;
+    for (i, starts) in backdrop.enumerate() {
+        if starts && i > 0 {
+            segments.push(i);
+        }
+        count = i + 1;
+    }
+    segments.push(count);
+    segments
+}
+
+/// Uniform buffer with `slots` clip entries and its bind group
+fn create_clip_buffer(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, slots: usize, stride: u32) -> (wgpu::Buffer, wgpu::BindGroup) {
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Clip Buffer"),
+        size: (slots * stride as usize) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: &buffer,
+                offset: 0,
+                size: std::num::NonZeroU64::new(std::mem::size_of::<ClipUniform>() as u64),
+            }),
+        }],
+        label: Some("Clip Bind Group"),
+    });
+    (buffer, bind_group)
+}
+
+fn union_bounds(a: Option<Rect>, b: Option<Rect>) -> Option<Rect> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Instance `transform` of an untransformed quad
+const IDENTITY_2X2: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+/// Most covering rects `hide_covered_glass` tests each instance against
+const MAX_OCCLUDERS: usize = 16;
+
+/// Collapse glass instances that a later one in `instances` entirely
+/// covers, returning how many were hidden
+/// 
+/// There is no depth buffer, so this runs front to back on the CPU: glass
+/// replaces what's beneath rather than blending over it (its alpha is the
+/// shape's coverage, not the tint's), so an instance at full opacity
+/// without a transform hides anything under its solid interior.
+fn hide_covered_glass(instances: &mut [GlassInstance]) -> usize {
+    let mut occluders = [Rect::new(crate::Vec2::ZERO, crate::Vec2::ZERO); MAX_OCCLUDERS];
+    let mut count = 0;
+    let mut hidden = 0;
+    for instance in instances.iter_mut().rev() {
+        // Anti-aliasing reaches half a pixel past the shape
+        let bounds = quad_bounds(instance.position, instance.size, instance.transform).inset(-0.5);
+        if occluders[..count].iter().any(|occluder| occluder.contains_rect(&bounds)) {
+            instance.size = [0.0; 2];
+            hidden += 1;
+            continue;
+        }
+        if count < MAX_OCCLUDERS && instance.opacity >= 1.0 && instance.transform == IDENTITY_2X2 {
+            let position = crate::Vec2::from(instance.position);
+            let interior = rounded_opaque_rect(position, crate::Vec2::from(instance.size), instance.corner_radius).inset(0.5);
+            if !interior.is_empty() {
+                occluders[count] = interior;
+                count += 1;
+            }
+        }
+    }
+    hidden
+}
+
+/// Bounding box of a quad after its 2x2 `transform` (applied around `position`)
+fn quad_bounds(position: [f32; 2], size: [f32; 2], transform: [f32; 4]) -> Rect {
+    let matrix = glam::Mat2::from_cols_array(&transform);
+    let corners = [
+        crate::Vec2::ZERO,
+        crate::Vec2::new(size[0], 0.0),
+        crate::Vec2::new(0.0, size[1]),
+        crate::Vec2::from(size),
+    ].map(|corner| matrix * corner);
+    let min = corners.iter().fold(corners[0], |min, &c| min.min(c));
+    let max = corners.iter().fold(corners[0], |max, &c| max.max(c));
+    Rect::new(crate::Vec2::from(position) + min, max - min)
+}
+
+/// 2D part of `m` (x/y columns and translation, ignoring z and projection)
+fn affine_2d(m: crate::Mat4) -> glam::Affine2 {
+    glam::Affine2::from_cols(m.x_axis.truncate().truncate(), m.y_axis.truncate().truncate(), m.w_axis.truncate().truncate())
+}
+
+/// `t` applied with `center` as the origin
+fn around(center: crate::Vec2, t: glam::Affine2) -> glam::Affine2 {
+    glam::Affine2::from_translation(center) * t * glam::Affine2::from_translation(-center)
+}
+
+/// Fade an image `tint` by `opacity` (premultiplied textures scale all
+/// four channels, straight ones only alpha)
+fn fade_tint(tint: &mut [f32; 4], opacity: f32, premultiplied: bool) {
+    if premultiplied {
+        tint.iter_mut().for_each(|c| *c *= opacity);
+    } else {
+        tint[3] *= opacity;
+    }
+}
+
+/// Move a quad by `t`: its origin is mapped, and the linear part is folded
+/// into its 2x2 `transform`
+fn transform_quad(t: &glam::Affine2, position: &mut [f32; 2], transform: &mut [f32; 4]) {
+    *position = t.transform_point2(crate::Vec2::from(*position)).to_array();
+    *transform = (t.matrix2 * glam::Mat2::from_cols_array(transform)).to_cols_array();
 }
 
 #[repr(C)]
@@ -70,96 +796,630 @@ pub struct GlassInstance {
     pub size:  [f32; 2],
     pub color: [f32; 4],
     pub corner_radius: f32,
-    pub _padding: [f32; 3],  // Align to 16 bytes
+    /// How blurred the backdrop looks through the glass (0 sharp, 1 full)
+    pub blur: f32,
+    /// Multiplies the coverage, including the backdrop (see `push_opacity`)
+    pub opacity: f32,
+    pub _padding: f32,  // Align to 16 bytes
+    /// Columns of the 2x2 matrix applied around `position` (see `push_transform`)
+    pub transform: [f32; 4],
 }
 
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct Uniforms {
-    time: f32,
-    _pad1: u32,
-    resolution: [f32; 2],
+impl GlassInstance {
+    /// Instance for a rounded rect, clamping the radius to half the smallest side
+    pub fn new(pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4, radius: f32) -> Self {
+        let max_radius = size.x.min(size.y) * 0.5;
+        Self {
+            position: [pos.x, pos.y],
+            size: [size.x, size.y],
+            color: [color.x, color.y, color.z, color.w],
+            corner_radius: radius.min(max_radius).max(0.0),
+            blur: 1.0,
+            opacity: 1.0,
+            _padding: 0.0,
+            transform: IDENTITY_2X2,
+        }
+    }
+    
+    pub fn with_blur(mut self, blur: f32) -> Self {
+        self.blur = blur.clamp(0.0, 1.0);
+        self
+    }
 }
 
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct BlurParams {
-    direction: [u32; 2],
+/// Handle to a texture uploaded with `GlassRenderer::load_texture`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextureId(u32);
+
+struct LoadedTexture {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    size: (u32, u32),
+    /// Rendered by `draw_cached` (surface format, premultiplied alpha)
+    premultiplied: bool,
 }
 
-impl GlassRenderer {
-    pub async fn new(window: &Window) -> Self {
-        // ... (Previous initialization code remains largely valid until struct Init) ...
-        // To avoid repeating 300 lines of boilerplate, I will output the FULL file content or large chunks?
-        // Ah, replace_file_content replaces range. I only need to preserve what I'm not changing.
-        // But `new` is huge.
-        // I'll re-implement `new` return struct field init.
-        
-        let size = window.inner_size();
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
-        
-        let surface = unsafe { 
-            let target = wgpu::SurfaceTargetUnsafe::from_window(&window).unwrap();
-            instance.create_surface_unsafe(target).unwrap()
-        };
+/// Offscreen copy of a subtree, drawn with `GlassRenderer::draw_cached`
+/// 
+/// Owned by the widget doing the caching. The texture is re-rendered when
+/// its pixel size, the scale factor or the theme changes, or after
+/// `invalidate`; free it with `GlassRenderer::release_cache`, or with
+/// `release` where no renderer is at hand.
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    texture: std::cell::Cell<Option<TextureId>>,
+    key: std::cell::Cell<Option<CacheKey>>,
+}
 
-        let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: Some(&surface),
-            force_fallback_adapter: false,
-        }).await.unwrap();
+thread_local! {
+    /// Textures passed to `release_texture`, freed when the next frame starts
+    static RELEASED_TEXTURES: std::cell::RefCell<Vec<TextureId>> = const { std::cell::RefCell::new(Vec::new()) };
+}
 
-        let (device, queue) = adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                label: None,
-                required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits::default(),
-            },
-            None,
-        ).await.unwrap();
+/// Free a texture when the next frame starts, for owners without the
+/// renderer at hand (e.g. in `Widget::on_unmount`)
+pub fn release_texture(id: TextureId) {
+    RELEASED_TEXTURES.with(|released| released.borrow_mut().push(id));
+}
 
-        let surface_caps = surface.get_capabilities(&adapter);
-        let texture_format = surface_caps.formats[0];
-        
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: texture_format,
-            width: size.width,
-            height: size.height,
-            present_mode: wgpu::PresentMode::Fifo,
-            alpha_mode: surface_caps.alpha_modes[0],
-            view_formats: vec![],
-            desired_maximum_frame_latency: 2,
-        };
-        surface.configure(&device, &config);
+/// Take the textures released since the last frame
+fn take_released_textures() -> Vec<TextureId> {
+    RELEASED_TEXTURES.with(|released| std::mem::take(&mut *released.borrow_mut()))
+}
 
-        // --- Textures ---
-        let texture_desc = wgpu::TextureDescriptor {
-            label: Some("Texture"),
-            size: wgpu::Extent3d { width: size.width, height: size.height, depth_or_array_layers: 1 },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm, 
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::STORAGE_BINDING,
-            view_formats: &[],
-        };
-        
-        let create_tex = |label| {
-            let tex = device.create_texture(&wgpu::TextureDescriptor { label: Some(label), ..texture_desc });
-            let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
-            (tex, view)
-        };
+/// Why pixels couldn't be uploaded to a texture
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextureError {
+    /// The data isn't `width * height` RGBA8 pixels
+    SizeMismatch { expected: usize, actual: usize },
+    /// No texture has that id (never loaded, or unloaded)
+    Unknown,
+}
+
+impl std::fmt::Display for TextureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextureError::SizeMismatch { expected, actual } => {
+                write!(f, "Expected {} bytes of RGBA8 pixels, got {}", expected, actual)
+            }
+            TextureError::Unknown => write!(f, "No texture with that id is loaded"),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
+
+/// Check that `rgba` holds exactly `width * height` RGBA8 pixels
+pub(crate) fn check_rgba(rgba: &[u8], width: u32, height: u32) -> Result<(), TextureError> {
+    let expected = width as usize * height as usize * 4;
+    if rgba.len() == expected {
+        Ok(())
+    } else {
+        Err(TextureError::SizeMismatch { expected, actual: rgba.len() })
+    }
+}
+
+/// What a `RenderCache` texture was rendered for
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CacheKey {
+    size: (u32, u32),
+    scale: f32,
+    theme: u64,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Re-render on the next `draw_cached` (e.g. after the content changed)
+    pub fn invalidate(&self) {
+        self.key.set(None);
+    }
+    
+    /// Whether the next `draw_cached` can blit without re-rendering
+    /// (assuming the same size, scale and theme)
+    pub fn is_valid(&self) -> bool {
+        self.key.get().is_some() && self.texture.get().is_some()
+    }
+    
+    /// Texture holding the last render, if any
+    pub fn texture(&self) -> Option<TextureId> {
+        self.texture.get()
+    }
+    
+    /// Free the texture when the next frame starts (e.g. from
+    /// `Widget::on_unmount`); it is re-created if drawn again
+    pub fn release(&self) {
+        if let Some(id) = self.texture.take() {
+            release_texture(id);
+        }
+        self.key.set(None);
+    }
+}
+
+/// Per-frame draw lists, swapped out while a `RenderCache` is rendered
+#[derive(Default)]
+struct PendingDraws {
+    instances: Vec<GlassInstance>,
+    gradients: Vec<GradientInstance>,
+    mesh_vertices: Vec<MeshVertex>,
+    mesh_indices: Vec<u32>,
+    images: Vec<ImageInstance>,
+    image_textures: Vec<TextureId>,
+    text: Vec<crate::text::TextVertex>,
+    text_groups: Vec<crate::text::TextGroup>,
+    batches: Vec<RenderBatch>,
+    current_scissor: Option<[u32; 4]>,
+    current_rounded: Option<RoundedClip>,
+    current_layer: i32,
+    clip_stack: Vec<ClipState>,
+    transform_stack: Vec<glam::Affine2>,
+    opacity_stack: Vec<f32>,
+    draw_marks: [usize; 5],
+    backdrop_pending: bool,
+}
+
+/// A textured quad, drawn with `GlassRenderer::draw_image_instance`
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ImageInstance {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+    pub tint: [f32; 4],
+    pub corner_radius: f32,
+    pub _padding: [f32; 3],
+    /// Columns of the 2x2 matrix applied around `position` (see `push_transform`)
+    pub transform: [f32; 4],
+}
+
+impl ImageInstance {
+    /// Whole texture stretched over the rect, multiplied by `tint`
+    pub fn new(pos: crate::Vec2, size: crate::Vec2, tint: crate::Vec4) -> Self {
+        Self {
+            position: [pos.x, pos.y],
+            size: [size.x, size.y],
+            uv_min: [0.0, 0.0],
+            uv_max: [1.0, 1.0],
+            tint: tint.to_array(),
+            corner_radius: 0.0,
+            _padding: [0.0; 3],
+            transform: IDENTITY_2X2,
+        }
+    }
+
+    /// Only draw the `min..max` region of the texture (0..1 coordinates)
+    pub fn with_uv(mut self, min: crate::Vec2, max: crate::Vec2) -> Self {
+        self.uv_min = min.to_array();
+        self.uv_max = max.to_array();
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius.max(0.0);
+        self
+    }
+}
+
+/// Two-colour gradient fill for `GlassRenderer::draw_rect_gradient`
+/// 
+/// Points are relative to the filled rect: (0, 0) is its top-left and
+/// (1, 1) its bottom-right corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gradient {
+    /// Blend from `from` at `start` to `to` at `end`
+    Linear { start: crate::Vec2, end: crate::Vec2, from: crate::Vec4, to: crate::Vec4 },
+    /// Blend from `inner` at `center` to `outer` at `radius`
+    Radial { center: crate::Vec2, radius: f32, inner: crate::Vec4, outer: crate::Vec4 },
+}
+
+impl Gradient {
+    /// Top to bottom
+    pub fn vertical(top: crate::Vec4, bottom: crate::Vec4) -> Self {
+        Gradient::Linear { start: crate::Vec2::ZERO, end: crate::Vec2::Y, from: top, to: bottom }
+    }
+    
+    /// Left to right
+    pub fn horizontal(left: crate::Vec4, right: crate::Vec4) -> Self {
+        Gradient::Linear { start: crate::Vec2::ZERO, end: crate::Vec2::X, from: left, to: right }
+    }
+    
+    /// Linear gradient through the rect's center at `degrees` (0 = left
+    /// to right, 90 = top to bottom)
+    pub fn angled(degrees: f32, from: crate::Vec4, to: crate::Vec4) -> Self {
+        let dir = crate::Vec2::from_angle(degrees.to_radians());
+        // Reach the corners: half extent of the unit square along `dir`
+        let half = dir * (dir.x.abs() + dir.y.abs()) * 0.5;
+        let center = crate::Vec2::splat(0.5);
+        Gradient::Linear { start: center - half, end: center + half, from, to }
+    }
+    
+    /// Circular blend from the center out to the edges
+    pub fn radial(inner: crate::Vec4, outer: crate::Vec4) -> Self {
+        Gradient::Radial { center: crate::Vec2::splat(0.5), radius: 0.5, inner, outer }
+    }
+    
+    /// Colour at the start of the gradient
+    pub fn start_color(&self) -> crate::Vec4 {
+        match self {
+            Gradient::Linear { from, .. } => *from,
+            Gradient::Radial { inner, .. } => *inner,
+        }
+    }
+}
+
+/// Gradient, shadow or SDF shape quad (`kind`: 0 linear, 1 radial,
+/// 2 shadow, 3 arc, 4 line, 5 stroke)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct GradientInstance {
+    position: [f32; 2],
+    size: [f32; 2],
+    start_color: [f32; 4],
+    end_color: [f32; 4],
+    start: [f32; 2],
+    end: [f32; 2],
+    corner_radius: f32,
+    kind: u32,
+    _padding: [f32; 2],
+    transform: [f32; 4],
+}
+
+impl GradientInstance {
+    fn new(pos: crate::Vec2, size: crate::Vec2, gradient: &Gradient, radius: f32) -> Self {
+        let (kind, start, end, start_color, end_color) = match *gradient {
+            Gradient::Linear { start, end, from, to } => (0, start, end, from, to),
+            Gradient::Radial { center, radius, inner, outer } => (1, center, crate::Vec2::new(radius, 0.0), inner, outer),
+        };
+        Self {
+            position: [pos.x, pos.y],
+            size: [size.x, size.y],
+            start_color: start_color.to_array(),
+            end_color: end_color.to_array(),
+            start: start.to_array(),
+            end: end.to_array(),
+            corner_radius: radius.min(size.x.min(size.y) * 0.5).max(0.0),
+            kind,
+            _padding: [0.0; 2],
+            transform: IDENTITY_2X2,
+        }
+    }
+    
+    /// Shadow of the rounded rect `pos..pos + size`, blurred by `softness`
+    fn shadow(pos: crate::Vec2, size: crate::Vec2, radius: f32, softness: f32, color: crate::Vec4) -> Self {
+        let softness = softness.max(0.0);
+        let grown = size + crate::Vec2::splat(softness * 2.0);
+        Self {
+            position: (pos - crate::Vec2::splat(softness)).to_array(),
+            size: grown.to_array(),
+            start_color: color.to_array(),
+            end_color: color.to_array(),
+            start: [softness, 0.0],
+            end: [0.0, 0.0],
+            corner_radius: radius.min(size.x.min(size.y) * 0.5).max(0.0),
+            kind: 2,
+            _padding: [0.0; 2],
+            transform: IDENTITY_2X2,
+        }
+    }
+    
+    /// Arc of `radius` around `center`, from `start` radians clockwise by
+    /// `sweep`; `thickness` 0 fills the pie slice instead of stroking it
+    fn arc(center: crate::Vec2, radius: f32, start: f32, sweep: f32, thickness: f32, color: crate::Vec4) -> Self {
+        let radius = radius.max(0.0);
+        // Negative sweeps run counter-clockwise from the same start
+        let (start, sweep) = if sweep < 0.0 { (start + sweep, -sweep) } else { (start, sweep) };
+        let extent = crate::Vec2::splat(radius + 1.0);
+        Self {
+            position: (center - extent).to_array(),
+            size: (extent * 2.0).to_array(),
+            start_color: color.to_array(),
+            end_color: color.to_array(),
+            start: [start, sweep.min(std::f32::consts::TAU)],
+            end: [radius, thickness.clamp(0.0, radius)],
+            corner_radius: 0.0,
+            kind: 3,
+            _padding: [0.0; 2],
+            transform: IDENTITY_2X2,
+        }
+    }
+    
+    /// Outline of the rounded rect `pos..pos + size`, `width` pixels wide
+    /// and entirely inside it
+    fn stroke(pos: crate::Vec2, size: crate::Vec2, radius: f32, width: f32, color: crate::Vec4) -> Self {
+        Self {
+            position: pos.to_array(),
+            size: size.to_array(),
+            start_color: color.to_array(),
+            end_color: color.to_array(),
+            start: [width.clamp(0.0, size.x.min(size.y) * 0.5), 0.0],
+            end: [0.0, 0.0],
+            corner_radius: radius.min(size.x.min(size.y) * 0.5).max(0.0),
+            kind: 5,
+            _padding: [0.0; 2],
+            transform: IDENTITY_2X2,
+        }
+    }
+    
+    /// Round-capped segment from `a` to `b`, `width` pixels wide
+    fn line(a: crate::Vec2, b: crate::Vec2, width: f32, color: crate::Vec4) -> Self {
+        let half = width.max(0.0) * 0.5;
+        let origin = a.min(b) - crate::Vec2::splat(half + 1.0);
+        let size = (a - b).abs() + crate::Vec2::splat((half + 1.0) * 2.0);
+        Self {
+            position: origin.to_array(),
+            size: size.to_array(),
+            start_color: color.to_array(),
+            end_color: color.to_array(),
+            start: (a - origin).to_array(),
+            end: (b - origin).to_array(),
+            corner_radius: half,
+            kind: 4,
+            _padding: [0.0; 2],
+            transform: IDENTITY_2X2,
+        }
+    }
+}
+
+/// Vertex of a tessellated path, already in window pixels
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct MeshVertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+/// Compiled `RendererPlugin` with its parameter uniform
+struct PostPass {
+    plugin: Box<dyn RendererPlugin>,
+    pipeline: wgpu::RenderPipeline,
+    params_buffer: wgpu::Buffer,
+    params_bind_group: wgpu::BindGroup,
+}
+
+/// Offscreen copy of the frame that post passes sample
+struct PostTarget {
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Pipelines drawing a background shader into the scene texture
+/// (Rgba8Unorm) and into the frame
+fn create_background_pipelines(
+    device: &wgpu::Device,
+    source: &str,
+    layouts: &[&wgpu::BindGroupLayout],
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> (wgpu::RenderPipeline, wgpu::RenderPipeline) {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Background Shader"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Bg Pipeline Layout"),
+        bind_group_layouts: layouts,
+        push_constant_ranges: &[],
+    });
+    let pipeline = |label, format, count| device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(&layout),
+        vertex: wgpu::VertexState { module: &shader, entry_point: "vs_main", buffers: &[] },
+        fragment: Some(wgpu::FragmentState { module: &shader, entry_point: "fs_main", targets: &[Some(wgpu::ColorTargetState { format, blend: None, write_mask: wgpu::ColorWrites::ALL })] }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState { count, ..Default::default() },
+        multiview: None,
+    });
+    (
+        pipeline("Bg Pipeline Offscreen", wgpu::TextureFormat::Rgba8Unorm, 1),
+        pipeline("Bg Pipeline Onscreen", format, sample_count),
+    )
+}
+
+/// Multisampled color target matching the surface, when `sample_count` > 1
+fn create_msaa_view(device: &wgpu::Device, format: wgpu::TextureFormat, size: (u32, u32), sample_count: u32) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("MSAA Target"),
+        size: wgpu::Extent3d { width: size.0.max(1), height: size.1.max(1), depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+/// Quads drawing a `texture_size` texture into `rect` as a nine-slice
+/// 
+/// `insets` are the corner sizes in texels, drawn at that many logical
+/// pixels: corners keep their size, edges stretch along one axis and the
+/// center along both. Corners shrink proportionally when `rect` is
+/// smaller than the insets.
+fn nine_slice_quads(rect: Rect, insets: EdgeInsets, texture_size: (u32, u32), tint: crate::Vec4) -> Vec<ImageInstance> {
+    let (tw, th) = (texture_size.0.max(1) as f32, texture_size.1.max(1) as f32);
+    let size = rect.size();
+    let fx = if insets.horizontal() > size.x { size.x / insets.horizontal() } else { 1.0 };
+    let fy = if insets.vertical() > size.y { size.y / insets.vertical() } else { 1.0 };
+    
+    let xs = [rect.min.x, rect.min.x + insets.left * fx, rect.max.x - insets.right * fx, rect.max.x];
+    let ys = [rect.min.y, rect.min.y + insets.top * fy, rect.max.y - insets.bottom * fy, rect.max.y];
+    let us = [0.0, insets.left / tw, 1.0 - insets.right / tw, 1.0];
+    let vs = [0.0, insets.top / th, 1.0 - insets.bottom / th, 1.0];
+    
+    let mut quads = Vec::with_capacity(9);
+    for row in 0..3 {
+        for col in 0..3 {
+            let min = crate::Vec2::new(xs[col], ys[row]);
+            let max = crate::Vec2::new(xs[col + 1], ys[row + 1]);
+            if max.x - min.x <= 0.0 || max.y - min.y <= 0.0 {
+                continue;
+            }
+            quads.push(ImageInstance::new(min, max - min, tint)
+                .with_uv(crate::Vec2::new(us[col], vs[row]), crate::Vec2::new(us[col + 1], vs[row + 1])));
+        }
+    }
+    quads
+}
+
+/// Split `textures` into runs sharing a texture, as instance ranges
+/// offset by `start` (one bind + draw per run)
+fn texture_runs(textures: &[TextureId], start: u32) -> Vec<(TextureId, std::ops::Range<u32>)> {
+    let mut runs: Vec<(TextureId, std::ops::Range<u32>)> = Vec::new();
+    for (i, &id) in textures.iter().enumerate() {
+        let index = start + i as u32;
+        match runs.last_mut() {
+            Some((last, range)) if *last == id => range.end = index + 1,
+            _ => runs.push((id, index..index + 1)),
+        }
+    }
+    runs
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Uniforms {
+    time: f32,
+    quality: u32,
+    /// Window size in logical pixels (what widgets lay out in)
+    resolution: [f32; 2],
+    /// Physical pixels per logical pixel
+    scale: f32,
+    _padding: f32,
+    /// Window position of the render target's top-left (non-zero while
+    /// rendering a `RenderCache`), so glass samples the right backdrop
+    origin: [f32; 2],
+    /// Window size in logical pixels
+    screen: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct BlurParams {
+    direction: [u32; 2],
+    /// Gaussian radius in texels (`BlurSettings::texel_radius`)
+    radius: f32,
+    _padding: f32,
+}
+
+impl GlassRenderer {
+    pub async fn new(window: &Window) -> Self {
+        Self::with_antialiasing(window, AntiAliasing::default()).await
+    }
+    
+    /// Create a renderer with the given anti-aliasing (falls back to `Off`
+    /// when the surface format can't be multisampled)
+    pub async fn with_antialiasing(window: &Window, antialiasing: AntiAliasing) -> Self {
+        // ... (Previous initialization code remains largely valid until struct Init) ...
+        // To avoid repeating 300 lines of boilerplate, I will output the FULL file content or large chunks?
+        // Ah, replace_file_content replaces range. I only need to preserve what I'm not changing.
+        // But `new` is huge.
+        // I'll re-implement `new` return struct field init.
+        
+        let size = window.inner_size();
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+        
+        let surface = unsafe { 
+            let target = wgpu::SurfaceTargetUnsafe::from_window(&window).unwrap();
+            instance.create_surface_unsafe(target).unwrap()
+        };
+
+        let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: Some(&surface),
+            force_fallback_adapter: false,
+        }).await.unwrap();
+
+        let (device, queue) = adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                // Timestamp queries are optional; only used by the profiler
+                required_features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
+                required_limits: wgpu::Limits::default(),
+            },
+            None,
+        ).await.unwrap();
+
+        let surface_caps = surface.get_capabilities(&adapter);
+        let texture_format = surface_caps.formats[0];
+        let translucent_alpha_mode = surface_caps.alpha_modes.iter().copied().find(|mode| {
+            matches!(mode, wgpu::CompositeAlphaMode::PreMultiplied | wgpu::CompositeAlphaMode::PostMultiplied)
+        });
+        
+        // Backdrop glass samples the frame rendered so far, which needs a
+        // surface that can be bound as a texture
+        let backdrop_supported = surface_caps.usages.contains(wgpu::TextureUsages::TEXTURE_BINDING);
+        let config = wgpu::SurfaceConfiguration {
+            usage: if backdrop_supported {
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+            } else {
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+            },
+            format: texture_format,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: surface_caps.alpha_modes[0],
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        surface.configure(&device, &config);
+        
+        let flags = adapter.get_texture_format_features(texture_format).flags;
+        let sample_count = antialiasing.supported_sample_count(|count| flags.sample_count_supported(count));
+        if sample_count != antialiasing.sample_count() {
+            log::warn!("{}x MSAA unsupported for {:?}, anti-aliasing disabled", antialiasing.sample_count(), texture_format);
+        }
+        let multisample = wgpu::MultisampleState { count: sample_count, ..Default::default() };
+        let msaa_view = create_msaa_view(&device, config.format, (config.width, config.height), sample_count);
+
+        // --- Textures ---
+        // Scene and blur targets run at a reduced resolution (see BlurDownsample)
+        let blur = BlurSettings::default();
+        let blur_size = blur.downsample.scaled(size.width, size.height);
+        let texture_desc = wgpu::TextureDescriptor {
+            label: Some("Texture"),
+            size: wgpu::Extent3d { width: blur_size.0, height: blur_size.1, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm, 
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::STORAGE_BINDING,
+            view_formats: &[],
+        };
+        
+        let create_tex = |label| {
+            let tex = device.create_texture(&wgpu::TextureDescriptor { label: Some(label), ..texture_desc });
+            let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
+            (tex, view)
+        };
 
         let (scene_texture, scene_view) = create_tex("Scene");
         let (blur_intermediate_texture, blur_intermediate_view) = create_tex("Blur Inter");
         let (blur_final_texture, blur_final_view) = create_tex("Blur Final");
 
         // --- Buffers ---
-        let uniforms = Uniforms { time: 0.0, _pad1: 0, resolution: [size.width as f32, size.height as f32] };
+        let window_scale = window.scale_factor() as f32;
+        let uniforms = Uniforms {
+            time: 0.0,
+            quality: 0,
+            resolution: [size.width as f32 / window_scale, size.height as f32 / window_scale],
+            scale: window_scale,
+            _padding: 0.0,
+            origin: [0.0, 0.0],
+            screen: [size.width as f32 / window_scale, size.height as f32 / window_scale],
+        };
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Uniform Buffer"),
             contents: bytemuck::cast_slice(&[uniforms]),
@@ -167,8 +1427,8 @@ impl GlassRenderer {
         });
 
         let blur_params = [
-             BlurParams { direction: [1, 0] },
-             BlurParams { direction: [0, 1] },
+             BlurParams { direction: [1, 0], radius: 4.0, _padding: 0.0 },
+             BlurParams { direction: [0, 1], radius: 4.0, _padding: 0.0 },
         ];
         
         let blur_params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
@@ -180,15 +1440,9 @@ impl GlassRenderer {
         queue.write_buffer(&blur_params_buffer, 0, bytemuck::cast_slice(&[blur_params[0]]));
         queue.write_buffer(&blur_params_buffer, 256, bytemuck::cast_slice(&[blur_params[1]]));
 
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Instance Buffer"),
-            size: 1024 * std::mem::size_of::<GlassInstance>() as wgpu::BufferAddress,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let instance_buffer = GrowableBuffer::new::<GlassInstance>(&device, "Instance Buffer", wgpu::BufferUsages::VERTEX, 1024);
 
         // --- Pipelines ---
-        let bg_shader = device.create_shader_module(wgpu::include_wgsl!("shaders/bg.wgsl"));
         let bg_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             entries: &[wgpu::BindGroupLayoutEntry {
                 binding: 0,
@@ -199,33 +1453,13 @@ impl GlassRenderer {
             label: Some("bg_layout"),
         });
         
-        let bg_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Bg Pipeline Layout"),
-            bind_group_layouts: &[&bg_bind_group_layout],
-            push_constant_ranges: &[],
-        });
-        
-        let bg_pipeline_onscreen = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Bg Pipeline Onscreen"),
-            layout: Some(&bg_pipeline_layout),
-            vertex: wgpu::VertexState { module: &bg_shader, entry_point: "vs_main", buffers: &[] },
-            fragment: Some(wgpu::FragmentState { module: &bg_shader, entry_point: "fs_main", targets: &[Some(wgpu::ColorTargetState { format: texture_format, blend: None, write_mask: wgpu::ColorWrites::ALL })] }),
-            primitive: wgpu::PrimitiveState::default(),
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-        });
-        
-        let bg_pipeline_offscreen = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Bg Pipeline Offscreen"),
-            layout: Some(&bg_pipeline_layout),
-            vertex: wgpu::VertexState { module: &bg_shader, entry_point: "vs_main", buffers: &[] },
-            fragment: Some(wgpu::FragmentState { module: &bg_shader, entry_point: "fs_main", targets: &[Some(wgpu::ColorTargetState { format: wgpu::TextureFormat::Rgba8Unorm, blend: None, write_mask: wgpu::ColorWrites::ALL })] }),
-            primitive: wgpu::PrimitiveState::default(),
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-        });
+        let (bg_pipeline_offscreen, bg_pipeline_onscreen) = create_background_pipelines(
+            &device,
+            include_str!("shaders/bg.wgsl"),
+            &[&bg_bind_group_layout],
+            texture_format,
+            sample_count,
+        );
         
         let bg_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &bg_bind_group_layout,
@@ -272,11 +1506,12 @@ impl GlassRenderer {
             vec![
                 create_bg(&scene_view, &blur_intermediate_view, 0),
                 create_bg(&blur_intermediate_view, &blur_final_view, 256),
+                create_bg(&blur_final_view, &blur_intermediate_view, 0),
             ]
         };
 
         // --- Glass Pipeline ---
-        let glass_shader = device.create_shader_module(wgpu::include_wgsl!("shaders/glass.wgsl"));
+        let glass_shader = device.create_shader_module(clipped_shader!("shaders/glass.wgsl"));
         
         let instance_layout = wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<GlassInstance>() as wgpu::BufferAddress,
@@ -286,6 +1521,9 @@ impl GlassRenderer {
                 wgpu::VertexAttribute { offset: 8, shader_location: 1, format: wgpu::VertexFormat::Float32x2 },  // size
                 wgpu::VertexAttribute { offset: 16, shader_location: 2, format: wgpu::VertexFormat::Float32x4 }, // color
                 wgpu::VertexAttribute { offset: 32, shader_location: 3, format: wgpu::VertexFormat::Float32 },   // corner_radius
+                wgpu::VertexAttribute { offset: 36, shader_location: 4, format: wgpu::VertexFormat::Float32 },   // blur
+                wgpu::VertexAttribute { offset: 40, shader_location: 6, format: wgpu::VertexFormat::Float32 },   // opacity
+                wgpu::VertexAttribute { offset: 48, shader_location: 5, format: wgpu::VertexFormat::Float32x4 }, // transform
             ],
         };
 
@@ -293,13 +1531,36 @@ impl GlassRenderer {
             entries: &[
                  wgpu::BindGroupLayoutEntry { binding: 0, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Texture { sample_type: wgpu::TextureSampleType::Float { filterable: true }, view_dimension: wgpu::TextureViewDimension::D2, multisampled: false }, count: None },
                  wgpu::BindGroupLayoutEntry { binding: 1, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering), count: None },
+                 wgpu::BindGroupLayoutEntry { binding: 2, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Texture { sample_type: wgpu::TextureSampleType::Float { filterable: true }, view_dimension: wgpu::TextureViewDimension::D2, multisampled: false }, count: None },
             ],
             label: Some("glass_texture_layout"),
         });
+        
+        // Single texture + sampler (images, backdrop copy)
+        let texture_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                 wgpu::BindGroupLayoutEntry { binding: 0, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Texture { sample_type: wgpu::TextureSampleType::Float { filterable: true }, view_dimension: wgpu::TextureViewDimension::D2, multisampled: false }, count: None },
+                 wgpu::BindGroupLayoutEntry { binding: 1, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering), count: None },
+            ],
+            label: Some("texture_layout"),
+        });
+
+        // Rounded clip of the batch being drawn (dynamic offset per batch)
+        let clip_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Uniform, has_dynamic_offset: true, min_binding_size: std::num::NonZeroU64::new(std::mem::size_of::<ClipUniform>() as u64) },
+                count: None,
+            }],
+            label: Some("clip_layout"),
+        });
+        let clip_stride = device.limits().min_uniform_buffer_offset_alignment.max(std::mem::size_of::<ClipUniform>() as u32);
+        let (clip_buffer, clip_bind_group) = create_clip_buffer(&device, &clip_layout, 64, clip_stride);
 
         let glass_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Glass Pipeline Layout"),
-            bind_group_layouts: &[&bg_bind_group_layout, &glass_texture_layout],
+            bind_group_layouts: &[&bg_bind_group_layout, &glass_texture_layout, &clip_layout],
             push_constant_ranges: &[],
         });
 
@@ -322,7 +1583,7 @@ impl GlassRenderer {
             }),
             primitive: wgpu::PrimitiveState { topology: wgpu::PrimitiveTopology::TriangleStrip, ..Default::default() },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample,
             multiview: None,
         });
         
@@ -339,227 +1600,1958 @@ impl GlassRenderer {
             entries: &[
                 wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&blur_final_view) },
                 wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&sampler) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&scene_view) },
             ],
             label: None,
         });
         
-        // --- Text Renderer ---
-        let text_renderer = crate::text::TextRenderer::new(&device, &config, &bg_bind_group_layout);
-
-        Self {
-            surface, device, queue, config, size,
-            bg_pipeline_offscreen, bg_pipeline_onscreen, glass_pipeline, blur_pipeline,
-            blur_bind_group_layout, glass_texture_layout, 
-            bg_bind_group, blur_bind_groups, glass_texture_bind_group,
-            uniform_buffer, blur_params_buffer, instance_buffer,
-            scene_texture, scene_view,
-            blur_intermediate_texture, blur_intermediate_view,
-            blur_final_texture, blur_final_view,
-            start_time: std::time::Instant::now(),
-            instances: Vec::new(),
-            text_renderer,
-            batches: Vec::new(),
-            current_scissor: None,
-            tooltips: Vec::new(),
-            overlay_rects: Vec::new(),
-            overlay_texts: Vec::new(),
-        }
-    }
-    
-    pub fn resize(&mut self, width: u32, height: u32) {
-         if width > 0 && height > 0 {
-            self.size = winit::dpi::PhysicalSize::new(width, height);
-            self.config.width = width;
-            self.config.height = height;
-            self.surface.configure(&self.device, &self.config);
-            
-             let texture_desc = wgpu::TextureDescriptor {
-                label: Some("Texture"),
-                size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8Unorm, // Always Rgba8Unorm for intermediate
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::STORAGE_BINDING,
-                view_formats: &[],
-            };
-            
-            let create_tex = |label| {
-                let tex = self.device.create_texture(&wgpu::TextureDescriptor { label: Some(label), ..texture_desc });
-                let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
-                (tex, view)
-            };
-
-            let (t1, v1) = create_tex("Scene");
-            let (t2, v2) = create_tex("Blur Inter");
-            let (t3, v3) = create_tex("Blur Final");
-            
-            self.scene_texture = t1; self.scene_view = v1;
-            self.blur_intermediate_texture = t2; self.blur_intermediate_view = v2;
-            self.blur_final_texture = t3; self.blur_final_view = v3;
-            
-             let create_bg = |input: &wgpu::TextureView, output: &wgpu::TextureView, offset: u64| {
-                self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    layout: &self.blur_bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(input) },
-                        wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(output) },
-                        wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding { buffer: &self.blur_params_buffer, offset, size: Some(std::num::NonZeroU64::new(std::mem::size_of::<BlurParams>() as u64).unwrap()) }) },
-                    ],
-                    label: None,
-                })
-            };
-            self.blur_bind_groups = vec![
-                create_bg(&self.scene_view, &self.blur_intermediate_view, 0),
-                create_bg(&self.blur_intermediate_view, &self.blur_final_view, 256),
-            ];
-            
-            let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Linear,
-                ..Default::default()
+        // --- Image Pipeline ---
+        let image_shader = device.create_shader_module(clipped_shader!("shaders/image.wgsl"));
+        let image_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ImageInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute { offset: 0, shader_location: 0, format: wgpu::VertexFormat::Float32x2 },  // position
+                wgpu::VertexAttribute { offset: 8, shader_location: 1, format: wgpu::VertexFormat::Float32x2 },  // size
+                wgpu::VertexAttribute { offset: 16, shader_location: 2, format: wgpu::VertexFormat::Float32x2 }, // uv_min
+                wgpu::VertexAttribute { offset: 24, shader_location: 3, format: wgpu::VertexFormat::Float32x2 }, // uv_max
+                wgpu::VertexAttribute { offset: 32, shader_location: 4, format: wgpu::VertexFormat::Float32x4 }, // tint
+                wgpu::VertexAttribute { offset: 48, shader_location: 5, format: wgpu::VertexFormat::Float32 },   // corner_radius
+                wgpu::VertexAttribute { offset: 64, shader_location: 6, format: wgpu::VertexFormat::Float32x4 }, // transform
+            ],
+        };
+        
+        let image_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Image Pipeline Layout"),
+            bind_group_layouts: &[&bg_bind_group_layout, &texture_layout, &clip_layout],
+            push_constant_ranges: &[],
+        });
+        
+        let image_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Image Pipeline"),
+            layout: Some(&image_pipeline_layout),
+            vertex: wgpu::VertexState { module: &image_shader, entry_point: "vs_main", buffers: std::slice::from_ref(&image_layout) },
+            fragment: Some(wgpu::FragmentState { 
+                module: &image_shader, 
+                entry_point: "fs_main", 
+                targets: &[Some(wgpu::ColorTargetState { 
+                    format: texture_format, 
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING), 
+                    write_mask: wgpu::ColorWrites::ALL 
+                })] 
+            }),
+            primitive: wgpu::PrimitiveState { topology: wgpu::PrimitiveTopology::TriangleStrip, ..Default::default() },
+            depth_stencil: None,
+            multisample,
+            multiview: None,
+        });
+        
+        // Cached widget textures hold premultiplied colour (they were
+        // alpha-blended onto transparent black), so they blend as such
+        let cache_image_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Cache Image Pipeline"),
+            layout: Some(&image_pipeline_layout),
+            vertex: wgpu::VertexState { module: &image_shader, entry_point: "vs_main", buffers: &[image_layout] },
+            fragment: Some(wgpu::FragmentState { 
+                module: &image_shader, 
+                entry_point: "fs_premultiplied", 
+                targets: &[Some(wgpu::ColorTargetState { 
+                    format: texture_format, 
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING), 
+                    write_mask: wgpu::ColorWrites::ALL 
+                })] 
+            }),
+            primitive: wgpu::PrimitiveState { topology: wgpu::PrimitiveTopology::TriangleStrip, ..Default::default() },
+            depth_stencil: None,
+            multisample,
+            multiview: None,
+        });
+        
+        let image_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        
+        // Image pixels are sRGB; decode them only if the surface re-encodes
+        let image_format = if texture_format.is_srgb() {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+        
+        // --- Backdrop Copy Pipeline ---
+        // Copies the frame so far into the scene texture for backdrop glass
+        let backdrop_shader = device.create_shader_module(wgpu::include_wgsl!("shaders/backdrop.wgsl"));
+        let backdrop_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Backdrop Pipeline Layout"),
+            bind_group_layouts: &[&texture_layout],
+            push_constant_ranges: &[],
+        });
+        let backdrop_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Backdrop Copy Pipeline"),
+            layout: Some(&backdrop_pipeline_layout),
+            vertex: wgpu::VertexState { module: &backdrop_shader, entry_point: "vs_main", buffers: &[] },
+            fragment: Some(wgpu::FragmentState { module: &backdrop_shader, entry_point: "fs_main", targets: &[Some(wgpu::ColorTargetState { format: wgpu::TextureFormat::Rgba8Unorm, blend: None, write_mask: wgpu::ColorWrites::ALL })] }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        
+        // --- Gradient Pipeline ---
+        let gradient_shader = device.create_shader_module(clipped_shader!("shaders/gradient.wgsl"));
+        let gradient_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<GradientInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute { offset: 0, shader_location: 0, format: wgpu::VertexFormat::Float32x2 },  // position
+                wgpu::VertexAttribute { offset: 8, shader_location: 1, format: wgpu::VertexFormat::Float32x2 },  // size
+                wgpu::VertexAttribute { offset: 16, shader_location: 2, format: wgpu::VertexFormat::Float32x4 }, // start_color
+                wgpu::VertexAttribute { offset: 32, shader_location: 3, format: wgpu::VertexFormat::Float32x4 }, // end_color
+                wgpu::VertexAttribute { offset: 48, shader_location: 4, format: wgpu::VertexFormat::Float32x2 }, // start
+                wgpu::VertexAttribute { offset: 56, shader_location: 5, format: wgpu::VertexFormat::Float32x2 }, // end
+                wgpu::VertexAttribute { offset: 64, shader_location: 6, format: wgpu::VertexFormat::Float32 },   // corner_radius
+                wgpu::VertexAttribute { offset: 68, shader_location: 7, format: wgpu::VertexFormat::Uint32 },    // kind
+                wgpu::VertexAttribute { offset: 80, shader_location: 8, format: wgpu::VertexFormat::Float32x4 }, // transform
+            ],
+        };
+        
+        let gradient_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Gradient Pipeline Layout"),
+            bind_group_layouts: &[&bg_bind_group_layout, &clip_layout],
+            push_constant_ranges: &[],
+        });
+        
+        let gradient_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Gradient Pipeline"),
+            layout: Some(&gradient_pipeline_layout),
+            vertex: wgpu::VertexState { module: &gradient_shader, entry_point: "vs_main", buffers: &[gradient_layout] },
+            fragment: Some(wgpu::FragmentState { 
+                module: &gradient_shader, 
+                entry_point: "fs_main", 
+                targets: &[Some(wgpu::ColorTargetState { 
+                    format: texture_format, 
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING), 
+                    write_mask: wgpu::ColorWrites::ALL 
+                })] 
+            }),
+            primitive: wgpu::PrimitiveState { topology: wgpu::PrimitiveTopology::TriangleStrip, ..Default::default() },
+            depth_stencil: None,
+            multisample,
+            multiview: None,
+        });
+        
+        let gradient_buffer = GrowableBuffer::new::<GradientInstance>(&device, "Gradient Buffer", wgpu::BufferUsages::VERTEX, 256);
+        
+        // --- Mesh Pipeline (tessellated paths) ---
+        let mesh_shader = device.create_shader_module(clipped_shader!("shaders/mesh.wgsl"));
+        let mesh_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute { offset: 0, shader_location: 0, format: wgpu::VertexFormat::Float32x2 }, // position
+                wgpu::VertexAttribute { offset: 8, shader_location: 1, format: wgpu::VertexFormat::Float32x4 }, // color
+            ],
+        };
+        
+        let mesh_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mesh Pipeline"),
+            // Same bindings as gradients: uniforms and the clip
+            layout: Some(&gradient_pipeline_layout),
+            vertex: wgpu::VertexState { module: &mesh_shader, entry_point: "vs_main", buffers: &[mesh_layout] },
+            fragment: Some(wgpu::FragmentState { 
+                module: &mesh_shader, 
+                entry_point: "fs_main", 
+                targets: &[Some(wgpu::ColorTargetState { 
+                    format: texture_format, 
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING), 
+                    write_mask: wgpu::ColorWrites::ALL 
+                })] 
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample,
+            multiview: None,
+        });
+        
+        let mesh_vertex_buffer = GrowableBuffer::new::<MeshVertex>(&device, "Mesh Vertex Buffer", wgpu::BufferUsages::VERTEX, 1024).whole();
+        
+        let mesh_index_buffer = GrowableBuffer::new::<u32>(&device, "Mesh Index Buffer", wgpu::BufferUsages::INDEX, 4096).grouped(3).whole();
+        
+        let image_buffer = GrowableBuffer::new::<ImageInstance>(&device, "Image Buffer", wgpu::BufferUsages::VERTEX, 256);
+        
+        // --- Text Renderer ---
+        let mut text_renderer = crate::text::TextRenderer::new(&device, &config, &bg_bind_group_layout, &clip_layout, sample_count);
+        text_renderer.dpi_scale = window_scale;
+        
+        let gpu_timer = GpuTimer::new(&device, &queue);
+        
+        let overlay_buffer = GrowableBuffer::new::<GlassInstance>(&device, "Overlay Buffer", wgpu::BufferUsages::VERTEX, 256);
+
+        Self {
+            surface, device, queue, config, size,
+            bg_pipeline_offscreen, bg_pipeline_onscreen, glass_pipeline, image_pipeline, cache_image_pipeline, backdrop_pipeline, gradient_pipeline, mesh_pipeline, blur_pipeline,
+            bg_bind_group_layout, blur_bind_group_layout, glass_texture_layout, texture_layout,
+            textures: Vec::new(), image_sampler, image_format,
+            bg_bind_group, blur_bind_groups, glass_texture_bind_group,
+            uniform_buffer, blur_params_buffer, instance_buffer, image_buffer, gradient_buffer,
+            mesh_vertex_buffer, mesh_index_buffer,
+            scene_texture, scene_view,
+            blur_intermediate_texture, blur_intermediate_view,
+            blur_final_texture, blur_final_view,
+            blur, theme_blur: None, theme_blur_generation: u64::MAX, blur_size,
+            quality: GlassQuality::default(),
+            post_passes: Vec::new(),
+            post_targets: Vec::new(),
+            window_scale,
+            ui_scale: None,
+            sample_count,
+            msaa_view,
+            backdrop_supported,
+            backdrop_pending: false,
+            opaque_alpha_mode: surface_caps.alpha_modes[0],
+            translucent_alpha_mode,
+            background_opacity: 1.0,
+            background: Background::Procedural,
+            start_time: std::time::Instant::now(),
+            uniforms,
+            instances: Vec::new(),
+            hidden_glass: 0,
+            images: Vec::new(),
+            image_textures: Vec::new(),
+            gradients: Vec::new(),
+            mesh_vertices: Vec::new(),
+            mesh_indices: Vec::new(),
+            text_renderer,
+            batches: Vec::new(),
+            current_scissor: None,
+            current_rounded: None,
+            current_layer: 0,
+            clip_stack: Vec::new(),
+            transform_stack: Vec::new(),
+            opacity_stack: Vec::new(),
+            draw_marks: [0; 5],
+            clip_layout,
+            clip_buffer,
+            clip_bind_group,
+            clip_stride,
+            tooltips: TextQueue::new(),
+            overlay_shadows: Vec::new(),
+            overlay_rects: Vec::new(),
+            overlay_texts: TextQueue::new(),
+            overlay_buffer,
+            scratch_text: String::new(),
+            gpu_timer,
+            profiling: false,
+            frame_timings: FrameTimings::default(),
+        }
+    }
+    
+    pub fn resize(&mut self, width: u32, height: u32) {
+         if width > 0 && height > 0 {
+            self.size = winit::dpi::PhysicalSize::new(width, height);
+            self.config.width = width;
+            self.config.height = height;
+            self.surface.configure(&self.device, &self.config);
+            self.msaa_view = create_msaa_view(&self.device, self.config.format, (self.config.width, self.config.height), self.sample_count);
+            // Recreated at the new size on the next frame that needs them
+            self.post_targets.clear();
+            self.recreate_blur_targets();
+         }
+    }
+    
+    // --- Plugins ---
+    
+    /// Add a post-processing pass, run after every pass added before it
+    /// 
+    /// Fails (without adding it) if the plugin's WGSL doesn't compile.
+    pub fn add_plugin(&mut self, plugin: impl RendererPlugin + 'static) -> Result<(), wgpu::Error> {
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(plugin.name()),
+            source: wgpu::ShaderSource::Wgsl(crate::plugin::post_shader_source(&plugin).into()),
+        });
+        let params_layout = self.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Uniform, has_dynamic_offset: false, min_binding_size: None },
+                count: None,
+            }],
+            label: Some("post_params_layout"),
+        });
+        let layout = self.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post Pipeline Layout"),
+            bind_group_layouts: &[&self.bg_bind_group_layout, &self.texture_layout, &params_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = self.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(plugin.name()),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState { module: &shader, entry_point: "vs_main", buffers: &[] },
+            fragment: Some(wgpu::FragmentState { module: &shader, entry_point: "fs_main", targets: &[Some(wgpu::ColorTargetState { format: self.config.format, blend: None, write_mask: wgpu::ColorWrites::ALL })] }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        if let Some(error) = pollster::block_on(self.device.pop_error_scope()) {
+            log::warn!("Plugin '{}' failed to compile: {}", plugin.name(), error);
+            return Err(error);
+        }
+        
+        let params_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Post Params"),
+            size: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let params_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &params_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: params_buffer.as_entire_binding() }],
+            label: Some("Post Params Bind Group"),
+        });
+        self.post_passes.push(PostPass { plugin: Box::new(plugin), pipeline, params_buffer, params_bind_group });
+        Ok(())
+    }
+    
+    /// Remove every plugin with this name; returns whether any was found
+    pub fn remove_plugin(&mut self, name: &str) -> bool {
+        let before = self.post_passes.len();
+        self.post_passes.retain(|pass| pass.plugin.name() != name);
+        self.post_passes.len() != before
+    }
+    
+    /// Make sure `count` surface-sized post targets exist
+    fn ensure_post_targets(&mut self, count: usize) {
+        while self.post_targets.len() < count {
+            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Post Target"),
+                size: wgpu::Extent3d { width: self.config.width.max(1), height: self.config.height.max(1), depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.config.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
             });
-            
-            self.glass_texture_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: &self.glass_texture_layout,
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.texture_layout,
                 entries: &[
-                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&self.blur_final_view) },
-                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&sampler) },
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.image_sampler) },
                 ],
-                label: None,
+                label: Some("Post Target Bind Group"),
+            });
+            self.post_targets.push(PostTarget { _texture: texture, view, bind_group });
+        }
+    }
+    
+    /// Run the enabled plugins, ping-ponging between the post targets
+    /// (the frame starts in the first) and ending in `surface`
+    fn encode_post_passes(&self, encoder: &mut wgpu::CommandEncoder, surface: &wgpu::TextureView) {
+        let active: Vec<&PostPass> = self.post_passes.iter().filter(|pass| pass.plugin.enabled()).collect();
+        for (i, pass) in active.iter().enumerate() {
+            self.queue.write_buffer(&pass.params_buffer, 0, bytemuck::cast_slice(&pass.plugin.params()));
+            let input = &self.post_targets[i % 2];
+            let output = if i + 1 == active.len() { surface } else { &self.post_targets[(i + 1) % 2].view };
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: output,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
             });
+            render_pass.set_pipeline(&pass.pipeline);
+            render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
+            render_pass.set_bind_group(1, &input.bind_group, &[]);
+            render_pass.set_bind_group(2, &pass.params_bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+    
+    // --- HiDPI ---
+    
+    /// Physical pixels per logical pixel (the `set_ui_scale` override, or
+    /// the window's scale factor)
+    pub fn scale_factor(&self) -> f32 {
+        self.ui_scale.unwrap_or(self.window_scale)
+    }
+    
+    /// The window's scale factor changed (`WindowEvent::ScaleFactorChanged`)
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.window_scale = (scale_factor as f32).max(0.1);
+        self.text_renderer.dpi_scale = self.scale_factor();
+    }
+    
+    /// Override the window's scale factor (e.g. a zoom setting); `None`
+    /// follows the window again
+    pub fn set_ui_scale(&mut self, scale: Option<f32>) {
+        self.ui_scale = scale.map(|s| s.max(0.1));
+        self.text_renderer.dpi_scale = self.scale_factor();
+    }
+    
+    /// Window size in logical pixels, the space widgets lay out in
+    pub fn logical_size(&self) -> crate::Vec2 {
+        crate::Vec2::new(self.size.width as f32, self.size.height as f32) / self.scale_factor()
+    }
+    
+    /// Convert a physical position from winit (e.g. `CursorMoved`) to
+    /// logical pixels
+    pub fn to_logical(&self, position: winit::dpi::PhysicalPosition<f64>) -> crate::Vec2 {
+        crate::Vec2::new(position.x as f32, position.y as f32) / self.scale_factor()
+    }
+    
+    /// Logical scissor as a physical one, clamped to a `target` sized attachment
+    fn physical_scissor(&self, scissor: [u32; 4], target: (u32, u32)) -> [u32; 4] {
+        let scale = self.scale_factor();
+        let rect = scissor_rect(scissor);
+        scissor_for(Rect::new(rect.min * scale, rect.size() * scale), target)
+    }
+    
+    /// Logical bounds of the surface, for scissors
+    fn logical_target(&self) -> (u32, u32) {
+        let size = self.logical_size();
+        (size.x.ceil() as u32, size.y.ceil() as u32)
+    }
+    
+    /// Group each batch's text by font, size and atlas page (see
+    /// `TextRenderer::group_range`)
+    fn group_text(&mut self) {
+        for batch in &self.batches {
+            self.text_renderer.group_range(batch.text_range.clone());
+        }
+    }
+    
+    /// Write the batches' clip uniforms and upload their instances,
+    /// growing the buffers as needed
+    fn upload_batches(&mut self) {
+        // Stable, so call order still decides within a layer
+        self.batches.sort_by_key(|batch| batch.layer);
+        
+        // Rounded clips get a uniform slot per batch; slot 0 means no clip
+        let clip_slots = self.batches.len() + 1;
+        if (clip_slots * self.clip_stride as usize) as u64 > self.clip_buffer.size() {
+            (self.clip_buffer, self.clip_bind_group) = create_clip_buffer(&self.device, &self.clip_layout, clip_slots.next_power_of_two(), self.clip_stride);
+            self.queue.write_buffer(&self.clip_buffer, 0, bytemuck::bytes_of(&ClipUniform::new(None)));
+        }
+        for (i, batch) in self.batches.iter().enumerate() {
+            if batch.rounded.is_some() {
+                let offset = ((i + 1) * self.clip_stride as usize) as wgpu::BufferAddress;
+                let rounded = batch.rounded.map(|c| c.scaled(self.scale_factor()));
+                self.queue.write_buffer(&self.clip_buffer, offset, bytemuck::bytes_of(&ClipUniform::new(rounded)));
+            }
+        }
+        
+        // Rounded clips fade the covering glass too, so only unclipped
+        // batches can drop what it hides
+        self.hidden_glass = 0;
+        for batch in self.batches.iter().filter(|batch| batch.rounded.is_none()) {
+            self.hidden_glass += hide_covered_glass(&mut self.instances[batch.glass_range.start as usize..batch.glass_range.end as usize]);
+        }
+        self.instance_buffer.write(&self.device, &self.queue, bytemuck::cast_slice(&self.instances));
+        self.gradient_buffer.write(&self.device, &self.queue, bytemuck::cast_slice(&self.gradients));
+        self.mesh_vertex_buffer.write(&self.device, &self.queue, bytemuck::cast_slice(&self.mesh_vertices));
+        self.mesh_index_buffer.write(&self.device, &self.queue, bytemuck::cast_slice(&self.mesh_indices));
+        self.image_buffer.write(&self.device, &self.queue, bytemuck::cast_slice(&self.images));
+    }
+    
+    /// Encode `batches` into `render_pass`, returning the pipeline left bound
+    /// 
+    /// Batch state is tracked so consecutive batches of the same kind (e.g.
+    /// table cells under their own scissors) don't rebind pipelines, atlases
+    /// and vertex buffers. `target` is the physical size of the attachment.
+    fn draw_batches<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, batches: std::ops::Range<usize>, target: (u32, u32)) -> BoundPipeline {
+        let mut bound = BoundPipeline::None;
+        
+        for (i, batch) in self.batches.iter().enumerate().take(batches.end).skip(batches.start) {
+            if let Some(rect) = batch.scissor.map(|s| self.physical_scissor(s, target)) {
+                // Fully clipped away
+                if rect[2] == 0 || rect[3] == 0 {
+                    continue;
+                }
+                render_pass.set_scissor_rect(rect[0], rect[1], rect[2], rect[3]);
+            } else {
+                render_pass.set_scissor_rect(0, 0, target.0, target.1);
+            }
+            let clip_offset = if batch.rounded.is_some() { (i as u32 + 1) * self.clip_stride } else { 0 };
+            
+            // Draw Glass
+            if batch.glass_range.end > batch.glass_range.start {
+                if bound != BoundPipeline::Glass {
+                    render_pass.set_pipeline(&self.glass_pipeline);
+                    render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
+                    render_pass.set_bind_group(1, &self.glass_texture_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.instance_buffer.slice());
+                    bound = BoundPipeline::Glass;
+                }
+                render_pass.set_bind_group(2, &self.clip_bind_group, &[clip_offset]);
+                self.instance_buffer.draw_range(render_pass, 0, batch.glass_range.clone(), |pass, range| pass.draw(0..4, range));
+            }
+            
+            // Draw Gradients
+            if batch.gradient_range.end > batch.gradient_range.start {
+                if bound != BoundPipeline::Gradient {
+                    render_pass.set_pipeline(&self.gradient_pipeline);
+                    render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.gradient_buffer.slice());
+                    bound = BoundPipeline::Gradient;
+                }
+                render_pass.set_bind_group(1, &self.clip_bind_group, &[clip_offset]);
+                self.gradient_buffer.draw_range(render_pass, 0, batch.gradient_range.clone(), |pass, range| pass.draw(0..4, range));
+            }
+            
+            // Draw Meshes
+            if batch.mesh_range.end > batch.mesh_range.start {
+                if bound != BoundPipeline::Mesh {
+                    render_pass.set_pipeline(&self.mesh_pipeline);
+                    render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.mesh_vertex_buffer.slice());
+                    render_pass.set_index_buffer(self.mesh_index_buffer.slice(), wgpu::IndexFormat::Uint32);
+                    bound = BoundPipeline::Mesh;
+                }
+                render_pass.set_bind_group(1, &self.clip_bind_group, &[clip_offset]);
+                render_pass.draw_indexed(self.mesh_index_buffer.clamp(batch.mesh_range.clone()), 0, 0..1);
+            }
+            
+            // Draw Images (one draw per run of instances sharing a texture)
+            if batch.image_range.end > batch.image_range.start {
+                let ids = &self.image_textures[batch.image_range.start as usize..batch.image_range.end as usize];
+                for (id, range) in texture_runs(ids, batch.image_range.start) {
+                    // Unloaded textures are skipped rather than drawn stale
+                    let Some(Some(texture)) = self.textures.get(id.0 as usize) else {
+                        continue;
+                    };
+                    let pipeline = if texture.premultiplied { BoundPipeline::CacheImage } else { BoundPipeline::Image };
+                    if bound != pipeline {
+                        render_pass.set_pipeline(if texture.premultiplied { &self.cache_image_pipeline } else { &self.image_pipeline });
+                        render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
+                        render_pass.set_vertex_buffer(0, self.image_buffer.slice());
+                        bound = pipeline;
+                    }
+                    render_pass.set_bind_group(1, &texture.bind_group, &[]);
+                    render_pass.set_bind_group(2, &self.clip_bind_group, &[clip_offset]);
+                    self.image_buffer.draw_range(render_pass, 0, range, |pass, range| pass.draw(0..4, range));
+                }
+            }
+            
+            // Draw Text (one draw per batch: a single font atlas holds every size)
+            if batch.text_range.end > batch.text_range.start {
+                if bound != BoundPipeline::Text {
+                    self.text_renderer.bind(render_pass, &self.bg_bind_group);
+                    bound = BoundPipeline::Text;
+                }
+                render_pass.set_bind_group(2, &self.clip_bind_group, &[clip_offset]);
+                self.text_renderer.draw_range(render_pass, batch.text_range.clone());
+            }
+        }
+        bound
+    }
+    
+    /// Anti-aliasing in effect (after any fallback at creation)
+    pub fn antialiasing(&self) -> AntiAliasing {
+        if self.sample_count > 1 { AntiAliasing::Msaa4x } else { AntiAliasing::Off }
+    }
+    
+    /// Set the backdrop blur's radius, iterations and resolution
+    /// 
+    /// Themes with `Theme::blur` set take precedence while active.
+    pub fn set_blur(&mut self, blur: BlurSettings) {
+        self.update_blur(|renderer| renderer.blur = blur);
+    }
+    
+    /// Blur set with `set_blur` (ignoring any theme override)
+    pub fn blur(&self) -> BlurSettings {
+        self.blur
+    }
+    
+    /// Set the resolution the backdrop blur runs at
+    pub fn set_blur_downsample(&mut self, downsample: BlurDownsample) {
+        self.update_blur(|renderer| renderer.blur.downsample = downsample);
+    }
+    
+    pub fn blur_downsample(&self) -> BlurDownsample {
+        self.blur.downsample
+    }
+    
+    /// Blur in effect: the theme's, else the one from `set_blur`
+    fn effective_blur(&self) -> BlurSettings {
+        self.theme_blur.unwrap_or(self.blur)
+    }
+    
+    /// Apply a change to the blur settings, resizing the blur targets if
+    /// their resolution changed
+    fn update_blur(&mut self, change: impl FnOnce(&mut Self)) {
+        let old_downsample = self.effective_blur_downsample();
+        change(self);
+        if self.effective_blur_downsample() != old_downsample {
+            self.recreate_blur_targets();
+        }
+    }
+    
+    /// Switch glass quality at runtime
+    pub fn set_quality(&mut self, quality: GlassQuality) {
+        if self.quality != quality {
+            self.update_blur(|renderer| renderer.quality = quality);
+            if !quality.uses_blur() {
+                // Scene and blur passes no longer run, so their timings are stale
+                self.frame_timings.gpu_scene_ms = None;
+                self.frame_timings.gpu_blur_ms = None;
+            }
+        }
+    }
+    
+    pub fn quality(&self) -> GlassQuality {
+        self.quality
+    }
+    
+    /// Let the desktop show through the window background
+    /// 
+    /// Below 1.0 the procedural background is replaced by a dark tint of
+    /// this opacity, so with a transparent window (see
+    /// `borderless_window_builder`) the app reads as one glass surface.
+    /// Returns false, leaving the window opaque, when the surface can't
+    /// composite with alpha.
+    pub fn set_background_opacity(&mut self, opacity: f32) -> bool {
+        let opacity = opacity.clamp(0.0, 1.0);
+        let alpha_mode = if opacity < 1.0 {
+            match self.translucent_alpha_mode {
+                Some(mode) => mode,
+                None => return false,
+            }
+        } else {
+            self.opaque_alpha_mode
+        };
+        self.background_opacity = opacity;
+        if self.config.alpha_mode != alpha_mode {
+            self.config.alpha_mode = alpha_mode;
+            self.surface.configure(&self.device, &self.config);
+        }
+        true
+    }
+    
+    pub fn background_opacity(&self) -> f32 {
+        self.background_opacity
+    }
+    
+    /// Replace what is drawn behind the widgets (the animated grid by default)
+    /// 
+    /// Glass still blurs whatever the background is. A shader that fails to
+    /// compile leaves the current background in place; an image that gets
+    /// unloaded leaves just the clear color.
+    pub fn set_background(&mut self, background: Background) -> Result<(), wgpu::Error> {
+        if let Some(source) = background.shader_source() {
+            let layouts: &[&wgpu::BindGroupLayout] = match background {
+                Background::Image(_) => &[&self.bg_bind_group_layout, &self.texture_layout],
+                _ => &[&self.bg_bind_group_layout],
+            };
+            self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+            let pipelines = create_background_pipelines(&self.device, &source, layouts, self.config.format, self.sample_count);
+            if let Some(error) = pollster::block_on(self.device.pop_error_scope()) {
+                log::warn!("Background shader failed to compile: {}", error);
+                return Err(error);
+            }
+            (self.bg_pipeline_offscreen, self.bg_pipeline_onscreen) = pipelines;
+        }
+        self.background = background;
+        Ok(())
+    }
+    
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+    
+    /// Draw the background with `pipeline` (no-op for solid colors, missing
+    /// images and `None`, which only clear)
+    fn draw_background<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, pipeline: &'a wgpu::RenderPipeline) {
+        match self.background {
+            Background::Solid(_) | Background::None => return,
+            Background::Image(id) => match self.textures.get(id.0 as usize).and_then(Option::as_ref) {
+                Some(texture) => render_pass.set_bind_group(1, &texture.bind_group, &[]),
+                None => return,
+            },
+            Background::Procedural | Background::Shader(_) => {}
+        }
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+    
+    /// Whether the window background is currently see-through
+    fn is_translucent(&self) -> bool {
+        self.background_opacity < 1.0 && self.config.alpha_mode != self.opaque_alpha_mode
+    }
+    
+    /// Blur resolution after applying the quality level
+    fn effective_blur_downsample(&self) -> BlurDownsample {
+        match self.quality {
+            GlassQuality::Light => BlurDownsample::Quarter,
+            _ => self.effective_blur().downsample,
+        }
+    }
+    
+    /// Recreate scene/blur textures and their bind groups at the current blur size
+    fn recreate_blur_targets(&mut self) {
+        let (width, height) = self.effective_blur_downsample().scaled(self.size.width, self.size.height);
+        self.blur_size = (width, height);
+        let texture_desc = wgpu::TextureDescriptor {
+            label: Some("Texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm, // Always Rgba8Unorm for intermediate
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::STORAGE_BINDING,
+            view_formats: &[],
+        };
+        
+        let create_tex = |label| {
+            let tex = self.device.create_texture(&wgpu::TextureDescriptor { label: Some(label), ..texture_desc });
+            let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
+            (tex, view)
+        };
+
+        let (t1, v1) = create_tex("Scene");
+        let (t2, v2) = create_tex("Blur Inter");
+        let (t3, v3) = create_tex("Blur Final");
+        
+        self.scene_texture = t1; self.scene_view = v1;
+        self.blur_intermediate_texture = t2; self.blur_intermediate_view = v2;
+        self.blur_final_texture = t3; self.blur_final_view = v3;
+        
+        let create_bg = |input: &wgpu::TextureView, output: &wgpu::TextureView, offset: u64| {
+            self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.blur_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(input) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(output) },
+                    wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding { buffer: &self.blur_params_buffer, offset, size: Some(std::num::NonZeroU64::new(std::mem::size_of::<BlurParams>() as u64).unwrap()) }) },
+                ],
+                label: None,
+            })
+        };
+        self.blur_bind_groups = vec![
+            create_bg(&self.scene_view, &self.blur_intermediate_view, 0),
+            create_bg(&self.blur_intermediate_view, &self.blur_final_view, 256),
+            create_bg(&self.blur_final_view, &self.blur_intermediate_view, 0),
+        ];
+        
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        
+        self.glass_texture_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.glass_texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&self.blur_final_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&sampler) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&self.scene_view) },
+            ],
+            label: None,
+        });
+    }
+
+    pub fn update(&mut self, _dt: f32) {
+        let time = self.start_time.elapsed().as_secs_f32();
+        let logical = self.logical_size();
+        let uniforms = Uniforms {
+            time,
+            quality: self.quality.shader_level(),
+            resolution: logical.to_array(),
+            scale: self.scale_factor(),
+            _padding: 0.0,
+            origin: [0.0, 0.0],
+            screen: logical.to_array(),
+        };
+        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        self.uniforms = uniforms;
+    }
+    
+    // --- Profiling ---
+    
+    /// Enable GPU timestamp queries (CPU timings are always recorded)
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiling = enabled;
+        if !enabled {
+            self.frame_timings.gpu_scene_ms = None;
+            self.frame_timings.gpu_blur_ms = None;
+            self.frame_timings.gpu_final_ms = None;
+        }
+    }
+    
+    pub fn is_profiling(&self) -> bool {
+        self.profiling
+    }
+    
+    /// Whether the adapter supports GPU pass timings
+    pub fn gpu_timing_supported(&self) -> bool {
+        self.gpu_timer.is_some()
+    }
+    
+    /// Record a CPU timing measured outside the renderer (update, layout)
+    pub fn record_timing(&mut self, stage: PerfStage, ms: f32) {
+        self.frame_timings.set(stage, ms);
+    }
+    
+    /// Most recent timings for each stage
+    pub fn frame_timings(&self) -> FrameTimings {
+        self.frame_timings
+    }
+    
+    /// Glass instances (including overlay rects) drawn in the last frame,
+    /// not counting ones hidden under opaque glass
+    pub fn instance_count(&self) -> usize {
+        (self.instances.len() + self.overlay_rects.len()).saturating_sub(self.hidden_glass)
+    }
+    
+    /// Timings and primitive counts of the last frame
+    /// 
+    /// GPU pass timings are only filled in while profiling is on (see
+    /// `set_profiling`) and lag a frame or two behind the counts.
+    pub fn frame_stats(&self) -> RenderStats {
+        RenderStats {
+            timings: self.frame_timings,
+            glass_instances: self.instance_count(),
+            shape_instances: self.gradients.len(),
+            mesh_triangles: self.mesh_indices.len() / 3,
+            image_instances: self.images.len(),
+            text_quads: self.text_renderer.queue_buffer.len() / 6,
+            batches: self.batches.len(),
+            glyph_cache: self.text_renderer.glyph_cache_stats(),
+        }
+    }
+    
+    // --- Clipping ---
+    
+    /// Clip everything drawn until the matching `pop_clip` to `rect`
+    /// 
+    /// Clips nest: the effective region is the intersection of every
+    /// pushed rect.
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.clip_stack.push(ClipState::push(self.clip_stack.last(), rect, 0.0));
+        self.apply_clip();
+    }
+    
+    /// Like `push_clip`, with rounded corners (e.g. a panel's content)
+    /// 
+    /// Only the innermost rounded clip is applied per pixel; outer ones
+    /// still clip to their bounding rect.
+    pub fn push_rounded_clip(&mut self, rect: Rect, radius: f32) {
+        self.clip_stack.push(ClipState::push(self.clip_stack.last(), rect, radius));
+        self.apply_clip();
+    }
+    
+    /// Restore the clip that was active before the last `push_clip`
+    pub fn pop_clip(&mut self) {
+        if self.clip_stack.pop().is_none() {
+            log::warn!("pop_clip without a matching push_clip");
+        }
+        self.apply_clip();
+    }
+    
+    /// Current clip region, if any
+    pub fn clip_rect(&self) -> Option<Rect> {
+        self.clip_stack.last().map(|c| c.bounds)
+    }
+    
+    fn apply_clip(&mut self) {
+        self.finish_current_batch();
+        let target = self.logical_target();
+        self.current_scissor = self.clip_stack.last().map(|c| scissor_for(c.bounds, target));
+        self.current_rounded = self.clip_stack.last().and_then(|c| c.rounded);
+    }
+    
+    // --- Transforms ---
+    
+    /// Transform everything drawn until the matching `pop_transform` by `m`
+    /// (composed with the transforms already pushed)
+    /// 
+    /// Only the 2D part of `m` is used: the x/y columns' xy (rotation,
+    /// scale, skew) and the translation's xy. Shapes, meshes, images and
+    /// text are transformed; clips and overlays are not.
+    pub fn push_transform(&mut self, m: crate::Mat4) {
+        self.push_affine(affine_2d(m));
+    }
+    
+    /// Rotate subsequent draws by `radians` (clockwise on screen) around `center`
+    pub fn push_rotation(&mut self, center: crate::Vec2, radians: f32) {
+        self.push_affine(around(center, glam::Affine2::from_angle(radians)));
+    }
+    
+    /// Scale subsequent draws by `scale` around `center`
+    pub fn push_scale(&mut self, center: crate::Vec2, scale: crate::Vec2) {
+        self.push_affine(around(center, glam::Affine2::from_scale(scale)));
+    }
+    
+    fn push_affine(&mut self, t: glam::Affine2) {
+        self.apply_draw_state();
+        let composed = self.transform_stack.last().map_or(t, |&top| top * t);
+        self.transform_stack.push(composed);
+    }
+    
+    /// Restore the transform that was active before the last `push_transform`
+    pub fn pop_transform(&mut self) {
+        self.apply_draw_state();
+        if self.transform_stack.pop().is_none() {
+            log::warn!("pop_transform without a matching push_transform");
+        }
+    }
+    
+    /// Current composed transform (identity when none is pushed)
+    pub fn transform(&self) -> glam::Affine2 {
+        self.transform_stack.last().copied().unwrap_or(glam::Affine2::IDENTITY)
+    }
+    
+    // --- Opacity ---
+    
+    /// Multiply the alpha of everything drawn until the matching
+    /// `pop_opacity` by `opacity` (composed with the groups already pushed)
+    /// 
+    /// Each draw fades on its own, so overlapping children show through
+    /// one another rather than fading as one flattened layer. Overlays
+    /// are not affected.
+    pub fn push_opacity(&mut self, opacity: f32) {
+        self.apply_draw_state();
+        let composed = self.opacity() * opacity.clamp(0.0, 1.0);
+        self.opacity_stack.push(composed);
+    }
+    
+    /// Restore the opacity that was active before the last `push_opacity`
+    pub fn pop_opacity(&mut self) {
+        self.apply_draw_state();
+        if self.opacity_stack.pop().is_none() {
+            log::warn!("pop_opacity without a matching push_opacity");
+        }
+    }
+    
+    /// Current composed group opacity (1 when none is pushed)
+    pub fn opacity(&self) -> f32 {
+        self.opacity_stack.last().copied().unwrap_or(1.0)
+    }
+    
+    /// Apply the current transform and opacity to everything drawn since
+    /// the last call
+    fn apply_draw_state(&mut self) {
+        let marks = self.draw_marks;
+        self.draw_marks = [
+            self.instances.len(),
+            self.gradients.len(),
+            self.images.len(),
+            self.mesh_vertices.len(),
+            self.text_renderer.queue_buffer.len(),
+        ];
+        
+        let opacity = self.opacity();
+        if opacity < 1.0 {
+            self.instances[marks[0]..].iter_mut().for_each(|i| i.opacity *= opacity);
+            self.gradients[marks[1]..].iter_mut().for_each(|g| {
+                g.start_color[3] *= opacity;
+                g.end_color[3] *= opacity;
+            });
+            for (image, id) in self.images[marks[2]..].iter_mut().zip(&self.image_textures[marks[2]..]) {
+                let premultiplied = self.textures.get(id.0 as usize).and_then(Option::as_ref).is_some_and(|t| t.premultiplied);
+                fade_tint(&mut image.tint, opacity, premultiplied);
+            }
+            self.mesh_vertices[marks[3]..].iter_mut().for_each(|v| v.color[3] *= opacity);
+            self.text_renderer.queue_buffer[marks[4]..].iter_mut().for_each(|v| v.color[3] *= opacity);
+        }
+        
+        let Some(t) = self.transform_stack.last().copied() else { return };
+        self.instances[marks[0]..].iter_mut().for_each(|i| transform_quad(&t, &mut i.position, &mut i.transform));
+        self.gradients[marks[1]..].iter_mut().for_each(|g| transform_quad(&t, &mut g.position, &mut g.transform));
+        self.images[marks[2]..].iter_mut().for_each(|i| transform_quad(&t, &mut i.position, &mut i.transform));
+        let point = |p: &mut [f32; 2]| *p = t.transform_point2(crate::Vec2::from(*p)).to_array();
+        self.mesh_vertices[marks[3]..].iter_mut().for_each(|v| point(&mut v.position));
+        self.text_renderer.queue_buffer[marks[4]..].iter_mut().for_each(|v| point(&mut v.position));
+    }
+    
+    // --- Layers ---
+    
+    /// Layer `Modal` and popups (see `draw_in_overlay_layer`) draw in
+    pub const OVERLAY_LAYER: i32 = 1000;
+    
+    /// Draw everything that follows in `layer` (0 by default)
+    /// 
+    /// Higher layers are drawn on top of lower ones whatever the call
+    /// order; within a layer, later calls draw on top. Clips still apply,
+    /// and `draw_overlay_*` content stays above every layer. Restore the
+    /// previous `layer()` when done.
+    pub fn set_layer(&mut self, layer: i32) {
+        if self.current_layer != layer {
+            self.finish_current_batch();
+            self.current_layer = layer;
+        }
+    }
+    
+    pub fn layer(&self) -> i32 {
+        self.current_layer
+    }
+    
+    /// Run `draw` in `OVERLAY_LAYER` with no clip, transform or opacity
+    /// group applied, then restore them
+    /// 
+    /// For popups (dropdown lists, pickers, suggestions) that must show
+    /// over later siblings and outside the scroll area they were opened in.
+    pub fn draw_in_overlay_layer(&mut self, draw: impl FnOnce(&mut Self)) {
+        // Flush what was drawn so far with the current clip and transform
+        self.finish_current_batch();
+        let layer = std::mem::replace(&mut self.current_layer, Self::OVERLAY_LAYER);
+        let clips = std::mem::take(&mut self.clip_stack);
+        let transforms = std::mem::take(&mut self.transform_stack);
+        let opacities = std::mem::take(&mut self.opacity_stack);
+        self.apply_clip();
+        
+        draw(self);
+        
+        self.finish_current_batch();
+        self.clip_stack = clips;
+        self.transform_stack = transforms;
+        self.opacity_stack = opacities;
+        self.current_layer = layer;
+        self.apply_clip();
+    }
+    
+    // --- Scissor Management ---
+    
+    /// Flat scissor in logical pixels, limited to the current `push_clip` region
+    /// 
+    /// Doesn't nest; prefer `push_clip`/`pop_clip`.
+    pub fn set_scissor(&mut self, rect: [u32; 4]) {
+         self.finish_current_batch();
+         
+         // Clamp scissor rect to render target bounds to prevent validation errors
+         let (max_w, max_h) = self.logical_target();
+         
+         // Ensure x,y are within bounds
+         let x = rect[0].min(max_w.saturating_sub(1));
+         let y = rect[1].min(max_h.saturating_sub(1));
+         
+         // Clamp width/height so rect doesn't extend past bounds
+         let w = rect[2].min(max_w.saturating_sub(x)).max(1);
+         let h = rect[3].min(max_h.saturating_sub(y)).max(1);
+         
+         self.current_scissor = Some(match self.clip_stack.last() {
+             Some(clip) => scissor_for(scissor_rect([x, y, w, h]).intersection(&clip.bounds), (max_w, max_h)),
+             None => [x, y, w, h],
+         });
+    }
+    
+    /// Back to the current `push_clip` region (or no clipping)
+    pub fn clear_scissor(&mut self) {
+        self.apply_clip();
+    }
+    
+    fn finish_current_batch(&mut self) {
+        self.apply_draw_state();
+        let glass_count = self.instances.len() as u32;
+        let gradient_count = self.gradients.len() as u32;
+        let mesh_count = self.mesh_indices.len() as u32;
+        let image_count = self.images.len() as u32;
+        let text_count = self.text_renderer.queue_buffer.len() as u32;
+        
+        let last_glass = self.batches.last().map(|b| b.glass_range.end).unwrap_or(0);
+        let last_gradient = self.batches.last().map(|b| b.gradient_range.end).unwrap_or(0);
+        let last_mesh = self.batches.last().map(|b| b.mesh_range.end).unwrap_or(0);
+        let last_image = self.batches.last().map(|b| b.image_range.end).unwrap_or(0);
+        let last_text = self.batches.last().map(|b| b.text_range.end).unwrap_or(0);
+        
+        if glass_count <= last_glass && gradient_count <= last_gradient && mesh_count <= last_mesh
+            && image_count <= last_image && text_count <= last_text {
+            return;
+        }
+        
+        // Gradients, meshes and images draw between glass and text, so they order like glass
+        let glass_bounds = self.instances[last_glass as usize..glass_count as usize].iter()
+            .map(|i| quad_bounds(i.position, i.size, i.transform))
+            .chain(self.gradients[last_gradient as usize..gradient_count as usize].iter()
+                .map(|g| quad_bounds(g.position, g.size, g.transform)))
+            .chain(self.mesh_indices[last_mesh as usize..mesh_count as usize].iter()
+                .map(|&i| Rect::new(crate::Vec2::from(self.mesh_vertices[i as usize].position), crate::Vec2::ZERO)))
+            .chain(self.images[last_image as usize..image_count as usize].iter()
+                .map(|i| quad_bounds(i.position, i.size, i.transform)))
+            .reduce(|a, b| a.union(&b));
+        let text_bounds = self.text_renderer.queue_buffer[last_text as usize..text_count as usize].iter()
+            .map(|v| Rect::new(crate::Vec2::from(v.position), crate::Vec2::ZERO))
+            .reduce(|a, b| a.union(&b));
+        
+        // Fold into the previous batch when the scissor change didn't matter
+        // (identical, or the content sits inside both regions)
+        let backdrop = std::mem::take(&mut self.backdrop_pending);
+        if let (false, Some(last), Some(content)) = (backdrop, self.batches.last_mut(), union_bounds(glass_bounds, text_bounds)) {
+            if last.can_absorb(self.current_layer, self.current_scissor, self.current_rounded, content, glass_bounds) {
+                last.glass_range.end = glass_count;
+                last.gradient_range.end = gradient_count;
+                last.mesh_range.end = mesh_count;
+                last.image_range.end = image_count;
+                last.text_range.end = text_count;
+                last.text_bounds = union_bounds(last.text_bounds, text_bounds);
+                return;
+            }
+        }
+        
+        self.batches.push(RenderBatch {
+            backdrop,
+            scissor: self.current_scissor,
+            rounded: self.current_rounded,
+            glass_range: last_glass..glass_count,
+            gradient_range: last_gradient..gradient_count,
+            mesh_range: last_mesh..mesh_count,
+            image_range: last_image..image_count,
+            text_range: last_text..text_count,
+            layer: self.current_layer,
+            text_bounds,
+        });
+    }
+
+    pub fn draw_rect(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4) {
+        self.draw_rounded_rect(pos, size, color, 0.0);
+    }
+    
+    /// Draw a rectangle with rounded corners (macOS/iOS quality)
+    pub fn draw_rounded_rect(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4, radius: f32) {
+        self.instances.push(GlassInstance::new(pos, size, color, radius));
+    }
+
+    /// Glass that blurs everything drawn before it (other panels, charts)
+    /// rather than just the background
+    /// 
+    /// `blur` goes from 0 (sharp) to 1 (the full backdrop blur). Each call
+    /// copies and re-blurs the frame so far, so keep it to a few large
+    /// panels. Falls back to regular glass when the surface can't be
+    /// sampled.
+    pub fn draw_backdrop_glass(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4, radius: f32, blur: f32) {
+        self.finish_current_batch();
+        self.backdrop_pending = true;
+        self.instances.push(GlassInstance::new(pos, size, color, radius).with_blur(blur));
+    }
+    
+    /// Fill a (rounded) rect with a linear or radial gradient
+    pub fn draw_rect_gradient(&mut self, pos: crate::Vec2, size: crate::Vec2, gradient: &Gradient, radius: f32) {
+        self.gradients.push(GradientInstance::new(pos, size, gradient, radius));
+    }
+    
+    /// Soft shadow of a rounded rect, fading out over `softness` pixels
+    /// 
+    /// Draw it before the shape that casts it, usually offset downwards.
+    pub fn draw_shadow(&mut self, pos: crate::Vec2, size: crate::Vec2, radius: f32, softness: f32, color: crate::Vec4) {
+        self.gradients.push(GradientInstance::shadow(pos, size, radius, softness, color));
+    }
+    
+    /// Hollow outline of a (rounded) rect, `width` pixels wide and drawn
+    /// inside `pos..pos + size`
+    /// 
+    /// Use it for borders instead of a larger rect underneath the fill:
+    /// nothing is drawn twice and translucent fills stay see-through.
+    pub fn draw_rect_stroke(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4, width: f32, radius: f32) {
+        if width <= 0.0 || size.x <= 0.0 || size.y <= 0.0 { return; }
+        self.gradients.push(GradientInstance::stroke(pos, size, radius, width, color));
+    }
+    
+    /// Stroke a style `border` just outside the rect `pos..pos + size`
+    /// whose corners have `radius`
+    pub fn draw_border(&mut self, border: &crate::style::Border, pos: crate::Vec2, size: crate::Vec2, radius: f32) {
+        let one = crate::Vec2::splat(1.0);
+        self.draw_rect_stroke(pos - one, size + one * 2.0, border.color, border.width, radius + 1.0);
+    }
+    
+    /// Filled, anti-aliased circle
+    pub fn draw_circle(&mut self, center: crate::Vec2, radius: f32, color: crate::Vec4) {
+        self.gradients.push(GradientInstance::arc(center, radius, 0.0, std::f32::consts::TAU, 0.0, color));
+    }
+    
+    /// Arc stroked `thickness` pixels inwards from `radius`, with flat ends
+    /// 
+    /// Angles are in radians, 0 pointing right and growing clockwise (down
+    /// the screen). A `thickness` of 0 fills the pie slice instead, and a
+    /// full `TAU` sweep draws a ring. Adjacent arcs tile without gaps, so
+    /// donut charts can draw one arc per slice.
+    pub fn draw_arc(&mut self, center: crate::Vec2, radius: f32, start: f32, sweep: f32, thickness: f32, color: crate::Vec4) {
+        if sweep == 0.0 || radius <= 0.0 { return; }
+        self.gradients.push(GradientInstance::arc(center, radius, start, sweep, thickness, color));
+    }
+    
+    /// Anti-aliased line from `a` to `b` with round caps
+    pub fn draw_line(&mut self, a: crate::Vec2, b: crate::Vec2, width: f32, color: crate::Vec4) {
+        if width <= 0.0 { return; }
+        self.gradients.push(GradientInstance::line(a, b, width, color));
+    }
+    
+    /// Draw a tessellated path, offset by `origin`
+    pub fn draw_mesh(&mut self, origin: crate::Vec2, mesh: &crate::path::PathMesh, color: crate::Vec4) {
+        let base = self.mesh_vertices.len() as u32;
+        self.mesh_vertices.extend(mesh.vertices.iter().map(|&v| MeshVertex {
+            position: (origin + v).to_array(),
+            color: color.to_array(),
+        }));
+        self.mesh_indices.extend(mesh.indices.iter().map(|&i| base + i));
+    }
+    
+    /// Fill a path (coordinates relative to `origin`)
+    /// 
+    /// Tessellates on every call; widgets drawing the same path each frame
+    /// can keep the `path::fill_path` mesh and use `draw_mesh` instead.
+    pub fn fill_path(&mut self, origin: crate::Vec2, path: &[crate::PathCommand], color: crate::Vec4) {
+        self.draw_mesh(origin, &crate::path::fill_path(path), color);
+    }
+    
+    /// Stroke a path `width` pixels wide (coordinates relative to `origin`)
+    pub fn stroke_path(&mut self, origin: crate::Vec2, path: &[crate::PathCommand], width: f32, color: crate::Vec4) {
+        self.draw_mesh(origin, &crate::path::stroke_path(path, width), color);
+    }
+    
+    /// Connect `points` with a `width` pixel line, rounding the joins
+    /// (one mesh, so overlapping segments don't double up the alpha)
+    pub fn draw_polyline(&mut self, points: &[crate::Vec2], width: f32, color: crate::Vec4) {
+        self.draw_polyline_joined(points, width, color, crate::path::PolylineJoin::Round);
+    }
+    
+    /// `draw_polyline` with a choice of round or mitered joins
+    pub fn draw_polyline_joined(&mut self, points: &[crate::Vec2], width: f32, color: crate::Vec4, join: crate::path::PolylineJoin) {
+        self.draw_mesh(crate::Vec2::ZERO, &crate::path::stroke_polyline(points, width, join), color);
+    }
+    
+    /// `draw_shadow` from a style `Shadow` (offset, spread and blur)
+    pub fn draw_box_shadow(&mut self, pos: crate::Vec2, size: crate::Vec2, radius: f32, shadow: &crate::style::Shadow) {
+        let spread = crate::Vec2::splat(shadow.spread);
+        let offset = crate::Vec2::new(shadow.offset_x, shadow.offset_y);
+        self.draw_shadow(pos + offset - spread, size + spread * 2.0, radius + shadow.spread, shadow.blur, shadow.color);
+    }
+    
+    // --- Images ---
+    
+    /// Upload RGBA8 pixels (row-major, `width * height * 4` bytes) as a texture
+    pub fn load_texture(&mut self, rgba: &[u8], width: u32, height: u32) -> Result<TextureId, TextureError> {
+        check_rgba(rgba, width, height)?;
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Image Texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.image_format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.image_sampler) },
+            ],
+            label: Some("Image Bind Group"),
+        });
+        let loaded = LoadedTexture { texture, bind_group, size: (width, height), premultiplied: false };
+        
+        let slot = match self.textures.iter().position(Option::is_none) {
+            Some(slot) => {
+                self.textures[slot] = Some(loaded);
+                slot
+            }
+            None => {
+                self.textures.push(Some(loaded));
+                self.textures.len() - 1
+            }
+        };
+        let id = TextureId(slot as u32);
+        self.update_texture(id, rgba)?;
+        Ok(id)
+    }
+    
+    /// Replace a texture's pixels (same dimensions), e.g. for video frames
+    pub fn update_texture(&mut self, id: TextureId, rgba: &[u8]) -> Result<(), TextureError> {
+        let Some(loaded) = self.textures.get(id.0 as usize).and_then(Option::as_ref) else {
+            return Err(TextureError::Unknown);
+        };
+        let (width, height) = loaded.size;
+        check_rgba(rgba, width, height)?;
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture { texture: &loaded.texture, mip_level: 0, origin: wgpu::Origin3d::ZERO, aspect: wgpu::TextureAspect::All },
+            rgba,
+            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(width * 4), rows_per_image: Some(height) },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        Ok(())
+    }
+    
+    /// Free a texture; its id may be handed out again by `load_texture`
+    pub fn unload_texture(&mut self, id: TextureId) {
+        if let Some(slot) = self.textures.get_mut(id.0 as usize) {
+            *slot = None;
+        }
+    }
+    
+    /// Pixel size of a loaded texture
+    pub fn texture_size(&self, id: TextureId) -> Option<(u32, u32)> {
+        self.textures.get(id.0 as usize)?.as_ref().map(|t| t.size)
+    }
+    
+    /// Stretch a texture over `rect` keeping its `insets` (in texels) crisp
+    /// 
+    /// Corners are drawn at their texel size, edges stretch along the
+    /// panel and the center fills the rest - for themed panel chrome that
+    /// must resize without smearing its corners.
+    pub fn draw_nine_slice(&mut self, texture: TextureId, rect: Rect, insets: EdgeInsets) {
+        self.draw_nine_slice_tinted(texture, rect, insets, crate::Vec4::ONE);
+    }
+    
+    /// `draw_nine_slice` multiplied by `tint`
+    pub fn draw_nine_slice_tinted(&mut self, texture: TextureId, rect: Rect, insets: EdgeInsets, tint: crate::Vec4) {
+        let Some(size) = self.texture_size(texture) else { return };
+        for quad in nine_slice_quads(rect, insets, size, tint) {
+            self.draw_image_instance(texture, quad);
+        }
+    }
+    
+    /// Draw a whole texture stretched over `pos..pos + size`, multiplied by `tint`
+    pub fn draw_image(&mut self, pos: crate::Vec2, size: crate::Vec2, texture: TextureId, tint: crate::Vec4) {
+        self.draw_image_instance(texture, ImageInstance::new(pos, size, tint));
+    }
+    
+    /// Draw a textured quad with custom UVs or rounded corners
+    pub fn draw_image_instance(&mut self, texture: TextureId, instance: ImageInstance) {
+        self.images.push(instance);
+        self.image_textures.push(texture);
+    }
+    
+    // --- Cached Rendering ---
+    
+    /// Draw what `draw` renders within `bounds` from an offscreen copy
+    /// 
+    /// When `cache` is invalid (first use, `invalidate`, or a change of
+    /// size, scale factor or theme) `draw` is rendered into the cache's
+    /// texture; otherwise it isn't called and the texture is just blitted.
+    /// The subtree is rendered without the enclosing `push_clip` regions,
+    /// which clip the blit instead. Glass inside keeps the backdrop it had
+    /// when rendered, and overlays or tooltips it adds only appear on the
+    /// frames that re-render it.
+    pub fn draw_cached(&mut self, cache: &RenderCache, bounds: Rect, draw: impl FnOnce(&mut Self)) {
+        // Snap to physical pixels so the blit samples texels 1:1
+        let scale = self.scale_factor();
+        let min = (bounds.min * scale).floor();
+        let max = (bounds.max * scale).ceil();
+        let size = ((max.x - min.x).max(0.0) as u32, (max.y - min.y).max(0.0) as u32);
+        let limit = self.device.limits().max_texture_dimension_2d;
+        if size.0 == 0 || size.1 == 0 || size.0 > limit || size.1 > limit {
+            draw(self);
+            return;
+        }
+        let origin = min / scale;
+        
+        let key = CacheKey { size, scale, theme: theme_generation() };
+        let reusable = cache.texture.get().filter(|id| {
+            self.textures.get(id.0 as usize).and_then(Option::as_ref).is_some_and(|t| t.premultiplied && t.size == size)
+        });
+        let texture = match reusable {
+            Some(id) if cache.key.get() == Some(key) => id,
+            _ => {
+                let id = reusable.unwrap_or_else(|| {
+                    if let Some(old) = cache.texture.take() {
+                        self.unload_texture(old);
+                    }
+                    self.create_cache_texture(size)
+                });
+                self.render_cache(id, origin, size, draw);
+                cache.texture.set(Some(id));
+                cache.key.set(Some(key));
+                id
+            }
+        };
+        let extent = crate::Vec2::new(size.0 as f32, size.1 as f32) / scale;
+        self.draw_image(origin, extent, texture, crate::Vec4::ONE);
+    }
+    
+    /// Free a cache's texture (it is re-created if drawn again)
+    pub fn release_cache(&mut self, cache: &RenderCache) {
+        if let Some(id) = cache.texture.take() {
+            self.unload_texture(id);
+        }
+        cache.key.set(None);
+    }
+    
+    /// Texture slot `draw_cached` renders into
+    fn create_cache_texture(&mut self, size: (u32, u32)) -> TextureId {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Render Cache"),
+            size: wgpu::Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.config.format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.image_sampler) },
+            ],
+            label: Some("Render Cache Bind Group"),
+        });
+        let loaded = LoadedTexture { texture, bind_group, size, premultiplied: true };
+        
+        match self.textures.iter().position(Option::is_none) {
+            Some(slot) => {
+                self.textures[slot] = Some(loaded);
+                TextureId(slot as u32)
+            }
+            None => {
+                self.textures.push(Some(loaded));
+                TextureId(self.textures.len() as u32 - 1)
+            }
+        }
+    }
+    
+    /// Render `draw` into `texture`, whose top-left sits at `origin`
+    /// 
+    /// The frame's draw lists are set aside meanwhile, and the subtree is
+    /// drawn, uploaded and submitted on its own before they are restored.
+    fn render_cache(&mut self, texture: TextureId, origin: crate::Vec2, size: (u32, u32), draw: impl FnOnce(&mut Self)) {
+        let mut frame = PendingDraws::default();
+        self.swap_pending(&mut frame);
+        
+        draw(self);
+        if !self.clip_stack.is_empty() {
+            log::warn!("{} push_clip calls without pop_clip", self.clip_stack.len());
+        }
+        if !self.transform_stack.is_empty() {
+            log::warn!("{} push_transform calls without pop_transform", self.transform_stack.len());
+        }
+        if !self.opacity_stack.is_empty() {
+            log::warn!("{} push_opacity calls without pop_opacity", self.opacity_stack.len());
+        }
+        self.finish_current_batch();
+        
+        let extent = crate::Vec2::new(size.0 as f32, size.1 as f32) / self.scale_factor();
+        self.translate_pending(-origin, extent);
+        self.upload_batches();
+        self.group_text();
+        self.text_renderer.prepare(&self.device, &self.queue);
+        
+        let uniforms = Uniforms { resolution: extent.to_array(), origin: origin.to_array(), ..self.uniforms };
+        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        
+        if let Some(Some(loaded)) = self.textures.get(texture.0 as usize) {
+            let view = loaded.texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let msaa_view = create_msaa_view(&self.device, self.config.format, size, self.sample_count);
+            let (target, resolve_target) = match &msaa_view {
+                Some(msaa) => (msaa, Some(&view)),
+                None => (&view, None),
+            };
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Cache Encoder"),
+            });
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Render Cache Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: target,
+                        resolve_target,
+                        ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT), store: wgpu::StoreOp::Store },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                self.draw_batches(&mut render_pass, 0..self.batches.len(), size);
+            }
+            self.queue.submit(std::iter::once(encoder.finish()));
+        }
+        
+        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&self.uniforms));
+        self.swap_pending(&mut frame);
+    }
+    
+    /// Exchange the frame's draw lists and clip state with `other`
+    fn swap_pending(&mut self, other: &mut PendingDraws) {
+        std::mem::swap(&mut self.instances, &mut other.instances);
+        std::mem::swap(&mut self.gradients, &mut other.gradients);
+        std::mem::swap(&mut self.mesh_vertices, &mut other.mesh_vertices);
+        std::mem::swap(&mut self.mesh_indices, &mut other.mesh_indices);
+        std::mem::swap(&mut self.images, &mut other.images);
+        std::mem::swap(&mut self.image_textures, &mut other.image_textures);
+        std::mem::swap(&mut self.text_renderer.queue_buffer, &mut other.text);
+        std::mem::swap(&mut self.text_renderer.queue_groups, &mut other.text_groups);
+        std::mem::swap(&mut self.batches, &mut other.batches);
+        std::mem::swap(&mut self.current_scissor, &mut other.current_scissor);
+        std::mem::swap(&mut self.current_rounded, &mut other.current_rounded);
+        std::mem::swap(&mut self.current_layer, &mut other.current_layer);
+        std::mem::swap(&mut self.clip_stack, &mut other.clip_stack);
+        std::mem::swap(&mut self.transform_stack, &mut other.transform_stack);
+        std::mem::swap(&mut self.opacity_stack, &mut other.opacity_stack);
+        std::mem::swap(&mut self.draw_marks, &mut other.draw_marks);
+        std::mem::swap(&mut self.backdrop_pending, &mut other.backdrop_pending);
+    }
+    
+    /// Move everything drawn so far by `offset`, re-clamping scissors to
+    /// an `extent` sized target
+    fn translate_pending(&mut self, offset: crate::Vec2, extent: crate::Vec2) {
+        let shift = |p: &mut [f32; 2]| {
+            p[0] += offset.x;
+            p[1] += offset.y;
+        };
+        self.instances.iter_mut().for_each(|i| shift(&mut i.position));
+        self.gradients.iter_mut().for_each(|g| shift(&mut g.position));
+        self.mesh_vertices.iter_mut().for_each(|v| shift(&mut v.position));
+        self.images.iter_mut().for_each(|i| shift(&mut i.position));
+        self.text_renderer.queue_buffer.iter_mut().for_each(|v| shift(&mut v.position));
+        
+        let target = (extent.x.ceil() as u32, extent.y.ceil() as u32);
+        for batch in &mut self.batches {
+            batch.scissor = batch.scissor.map(|s| {
+                let rect = scissor_rect(s);
+                scissor_for(Rect::new(rect.min + offset, rect.size()), target)
+            });
+            if let Some(rounded) = &mut batch.rounded {
+                rounded.rect = Rect::new(rounded.rect.min + offset, rounded.rect.size());
+            }
+        }
+    }
+
+    pub fn draw_text(&mut self, text: &str, pos: crate::Vec2, scale: f32, color: crate::Vec4) {
+        self.text_renderer.draw_text(&self.device, &self.queue, text, [pos.x, pos.y], scale, [color.x, color.y, color.z, color.w]);
+    }
+
+    /// `draw_text` in `style`'s size and color, with its drop shadow and
+    /// outline drawn underneath
+    pub fn draw_text_styled(&mut self, text: &str, pos: crate::Vec2, style: &crate::style::TextStyle) {
+        let outline = if style.has_outline() { crate::text::outline_offsets(style.outline_width) } else { Vec::new() };
+        if style.has_shadow() {
+            let shadow_pos = pos + style.shadow_offset;
+            for offset in &outline {
+                self.draw_text(text, shadow_pos + crate::Vec2::from(*offset), style.font_size, style.shadow_color);
+            }
+            self.draw_text(text, shadow_pos, style.font_size, style.shadow_color);
         }
+        for offset in &outline {
+            self.draw_text(text, pos + crate::Vec2::from(*offset), style.font_size, style.outline_color);
+        }
+        self.draw_text(text, pos, style.font_size, style.color);
     }
-
-    pub fn update(&mut self, _dt: f32) {
-        let time = self.start_time.elapsed().as_secs_f32();
-        let uniforms = Uniforms {
-            time,
-            _pad1: 0,
-            resolution: [self.size.width as f32, self.size.height as f32],
-        };
-        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    
+    /// Measure text with the renderer's font
+    pub fn measure_text(&self, text: &str, scale: f32) -> crate::text::TextMetrics {
+        self.text_renderer.measure_text(text, scale)
     }
     
-    // --- Scissor Management ---
-    pub fn set_scissor(&mut self, rect: [u32; 4]) {
-         self.finish_current_batch();
-         
-         // Clamp scissor rect to render target bounds to prevent validation errors
-         let max_w = self.size.width;
-         let max_h = self.size.height;
-         
-         // Ensure x,y are within bounds
-         let x = rect[0].min(max_w.saturating_sub(1));
-         let y = rect[1].min(max_h.saturating_sub(1));
-         
-         // Clamp width/height so rect doesn't extend past bounds
-         let w = rect[2].min(max_w.saturating_sub(x)).max(1);
-         let h = rect[3].min(max_h.saturating_sub(y)).max(1);
-         
-         self.current_scissor = Some([x, y, w, h]);
+    /// `draw_text` in a font from `load_font`
+    pub fn draw_text_with_font(&mut self, text: &str, pos: crate::Vec2, scale: f32, color: crate::Vec4, font: FontId) {
+        self.text_renderer.draw_text_with_font(&self.queue, font, text, pos.to_array(), scale, color.to_array());
     }
     
-    pub fn clear_scissor(&mut self) {
-        self.finish_current_batch();
-        self.current_scissor = None;
+    /// `measure_text` in a font from `load_font`
+    pub fn measure_text_with_font(&self, text: &str, scale: f32, font: FontId) -> crate::text::TextMetrics {
+        self.text_renderer.measure_text_with_font(font, text, scale)
     }
     
-    fn finish_current_batch(&mut self) {
-        let glass_count = self.instances.len() as u32;
-        let text_count = self.text_renderer.queue_buffer.len() as u32;
-        
-        let last_glass = self.batches.last().map(|b| b.glass_range.end).unwrap_or(0);
-        let last_text = self.batches.last().map(|b| b.text_range.end).unwrap_or(0);
-        
-        if glass_count > last_glass || text_count > last_text {
-             self.batches.push(RenderBatch {
-                scissor: self.current_scissor,
-                glass_range: last_glass..glass_count,
-                text_range: last_text..text_count,
-             });
+    /// Break `text` into lines no wider than `max_width` (`None` = only at
+    /// newlines) and measure them
+    pub fn layout_text(&self, text: &str, scale: f32, max_width: Option<f32>) -> TextLayout {
+        self.text_renderer.layout_text(self.text_renderer.default_font(), text, scale, max_width)
+    }
+    
+    /// x offset of the caret before byte `index` of `text`
+    pub fn index_to_position(&self, text: &str, scale: f32, index: usize) -> f32 {
+        self.text_renderer.index_to_position(self.text_renderer.default_font(), text, scale, index)
+    }
+    
+    /// Byte index in `text` of the caret position nearest to `x`
+    pub fn position_to_index(&self, text: &str, scale: f32, x: f32) -> usize {
+        self.text_renderer.position_to_index(self.text_renderer.default_font(), text, scale, x)
+    }
+    
+    /// Highlight rects for the bytes in `range`, relative to where `text` is drawn
+    pub fn selection_rects(&self, text: &str, scale: f32, range: std::ops::Range<usize>) -> Vec<crate::layout::Rect> {
+        self.text_renderer.selection_rects(self.text_renderer.default_font(), text, scale, range)
+    }
+    
+    /// `text` cut short with "…" to fit `max_width`
+    pub fn ellipsize<'a>(&self, text: &'a str, scale: f32, max_width: f32) -> std::borrow::Cow<'a, str> {
+        self.text_renderer.ellipsize(self.text_renderer.default_font(), text, scale, max_width)
+    }
+    
+    /// Draw `text` in a box `max_width` wide, handling text that does not
+    /// fit as `overflow` says
+    pub fn draw_text_overflow(&mut self, text: &str, pos: crate::Vec2, scale: f32, color: crate::Vec4, max_width: f32, overflow: TextOverflow) {
+        match overflow {
+            TextOverflow::Visible => self.draw_text(text, pos, scale, color),
+            TextOverflow::Clip => {
+                let height = self.measure_text(text, scale).line_height;
+                self.push_clip(Rect::new(pos, crate::Vec2::new(max_width.max(0.0), height)));
+                self.draw_text(text, pos, scale, color);
+                self.pop_clip();
+            }
+            TextOverflow::Ellipsis => {
+                let fitted = self.ellipsize(text, scale, max_width);
+                self.draw_text(&fitted, pos, scale, color);
+            }
+            TextOverflow::Wrap => {
+                self.draw_text_wrapped(text, pos, scale, color, max_width);
+            }
         }
     }
-
-    pub fn draw_rect(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4) {
-        self.draw_rounded_rect(pos, size, color, 0.0);
+    
+    /// Draw `text` word-wrapped to `max_width`, one line per `line_height`
+    /// below `pos`; returns the layout used
+    pub fn draw_text_wrapped(&mut self, text: &str, pos: crate::Vec2, scale: f32, color: crate::Vec4, max_width: f32) -> TextLayout {
+        let layout = self.layout_text(text, scale, Some(max_width));
+        for (line, line_text) in layout.lines.iter().zip(layout.line_texts(text)) {
+            self.draw_text(line_text, pos + crate::Vec2::new(0.0, line.y), scale, color);
+        }
+        layout
     }
     
-    /// Draw a rectangle with rounded corners (macOS/iOS quality)
-    pub fn draw_rounded_rect(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4, radius: f32) {
-        // Clamp radius to half of smallest dimension
-        let max_radius = size.x.min(size.y) * 0.5;
-        let clamped_radius = radius.min(max_radius).max(0.0);
-        
-        self.instances.push(GlassInstance {
-           position: [pos.x, pos.y],
-           size: [size.x, size.y],
-           color: [color.x, color.y, color.z, color.w],
-           corner_radius: clamped_radius,
-           _padding: [0.0, 0.0, 0.0],
-        });
+    // --- Fonts ---
+    
+    /// Add a TTF/OTF font from its file contents, e.g. an `include_bytes!`
+    /// brand font
+    pub fn load_font(&mut self, bytes: Vec<u8>) -> Result<FontId, FontError> {
+        self.text_renderer.load_font(bytes)
+    }
+    
+    /// Add a font from a .ttf/.otf/.ttc file
+    pub fn load_font_file(&mut self, path: impl AsRef<std::path::Path>) -> Result<FontId, FontError> {
+        self.text_renderer.load_font_file(path)
+    }
+    
+    /// Add an installed font by family name (see `text::find_font_file`)
+    pub fn load_font_family(&mut self, family: &str) -> Result<FontId, FontError> {
+        self.text_renderer.load_font_family(family)
+    }
+    
+    /// Use `font` for all text drawn without an explicit font
+    /// 
+    /// Widgets measure text during layout, so switch before the first
+    /// frame or force a relayout afterwards.
+    pub fn set_default_font(&mut self, font: FontId) {
+        self.text_renderer.set_default_font(font);
+    }
+    
+    pub fn default_font(&self) -> FontId {
+        self.text_renderer.default_font()
+    }
+    
+    /// Font emoji are drawn with (an installed color emoji font is picked
+    /// at startup), e.g. a bundled one from `load_font`
+    pub fn set_emoji_font(&mut self, font: Option<FontId>) {
+        self.text_renderer.set_emoji_font(font);
+    }
+    
+    /// Fonts tried in order for characters the requested and default fonts
+    /// lack (installed CJK, emoji and symbol fonts by default)
+    pub fn set_fallback_fonts(&mut self, fonts: Vec<FontId>) {
+        self.text_renderer.set_fallback_fonts(fonts);
+    }
+    
+    pub fn fallback_fonts(&self) -> &[FontId] {
+        self.text_renderer.fallback_fonts()
     }
 
-    pub fn draw_text(&mut self, text: &str, pos: crate::Vec2, scale: f32, color: crate::Vec4) {
-        self.text_renderer.draw_text(&self.device, &self.queue, text, [pos.x, pos.y], scale, [color.x, color.y, color.z, color.w]);
+    /// Draw formatted text without allocating (use with `format_args!`)
+    pub fn draw_text_fmt(&mut self, args: std::fmt::Arguments, pos: crate::Vec2, scale: f32, color: crate::Vec4) {
+        let text = self.format_scratch(args);
+        self.draw_text(&text, pos, scale, color);
+        self.scratch_text = text;
+    }
+    
+    /// Format into the reusable scratch string (hand it back via `self.scratch_text`)
+    fn format_scratch(&mut self, args: std::fmt::Arguments) -> String {
+        use std::fmt::Write;
+        let mut text = std::mem::take(&mut self.scratch_text);
+        text.clear();
+        let _ = text.write_fmt(args);
+        text
     }
 
+    /// Show a plain tooltip for the cursor at `pos`
+    /// 
+    /// Tooltips are drawn after every other overlay and are kept inside
+    /// the window (see `place_tooltip`).
     pub fn draw_tooltip(&mut self, text: &str, pos: crate::Vec2) {
-        self.tooltips.push((text.to_string(), pos));
+        self.queue_tooltip("", text, "", pos, 1.0);
     }
     
-    /// Queue a rectangle to render on the overlay layer (on top of everything)
-    /// Use this for dropdown popups, context menus, etc.
-    pub fn draw_overlay_rect(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4, radius: f32) {
-        let max_radius = size.x.min(size.y) * 0.5;
-        let clamped_radius = radius.min(max_radius).max(0.0);
+    /// Show a tooltip with optional title and shortcut hint at `alpha` opacity
+    pub fn draw_rich_tooltip(&mut self, content: &TooltipContent, pos: crate::Vec2, alpha: f32) {
+        self.queue_tooltip(
+            content.title.as_deref().unwrap_or(""),
+            &content.body,
+            content.shortcut.as_deref().unwrap_or(""),
+            pos,
+            alpha,
+        );
+    }
+    
+    fn queue_tooltip(&mut self, title: &str, body: &str, shortcut: &str, anchor: crate::Vec2, alpha: f32) {
+        let tooltip = QueuedTooltip { anchor, alpha, title_len: title.len(), body_len: body.len() };
+        self.tooltips.push_parts(&[title, body, shortcut], tooltip);
+    }
+    
+    /// Turn queued tooltips into overlay quads and text
+    fn render_tooltips(&mut self) {
+        if self.tooltips.entries.is_empty() {
+            return;
+        }
         
-        self.overlay_rects.push(GlassInstance {
-            position: [pos.x, pos.y],
-            size: [size.x, size.y],
-            color: [color.x, color.y, color.z, color.w],
-            corner_radius: clamped_radius,
-            _padding: [0.0, 0.0, 0.0],
-        });
+        let theme = get_theme();
+        let screen = self.logical_size();
+        let title_line = self.text_renderer.line_height(TOOLTIP_TITLE_SCALE);
+        let hint_line = self.text_renderer.line_height(TOOLTIP_HINT_SCALE);
+        let with_alpha = |color: crate::Vec4, alpha: f32| color.truncate().extend(color.w * alpha);
+        
+        // Above every widget layer
+        self.set_layer(i32::MAX);
+        
+        // Taken out so drawing can borrow self; handed back to keep the allocation
+        let tooltips = std::mem::replace(&mut self.tooltips, TextQueue::new());
+        for (text, tooltip) in tooltips.iter() {
+            let (title, rest) = text.split_at(tooltip.title_len);
+            let (body, shortcut) = rest.split_at(tooltip.body_len);
+            
+            // Measure
+            let mut content = crate::Vec2::ZERO;
+            if !title.is_empty() {
+                content.x = content.x.max(self.measure_text(title, TOOLTIP_TITLE_SCALE).width);
+                content.y += title_line;
+            }
+            let body_layout = if body.is_empty() {
+                TextLayout::default()
+            } else {
+                self.layout_text(body, TOOLTIP_BODY_SCALE, Some(TOOLTIP_MAX_WIDTH))
+            };
+            content.x = content.x.max(body_layout.width);
+            content.y += body_layout.height;
+            if !shortcut.is_empty() {
+                content.x = content.x.max(self.measure_text(shortcut, TOOLTIP_HINT_SCALE).width);
+                content.y += hint_line + 2.0;
+            }
+            let size = content + crate::Vec2::splat(TOOLTIP_PADDING * 2.0);
+            let pos = place_tooltip(tooltip.anchor, size, screen);
+            let alpha = tooltip.alpha;
+            
+            // Soft shadow
+            self.draw_overlay_shadow(pos + crate::Vec2::new(0.0, 3.0), size, 6.0, 8.0, crate::Vec4::new(0.0, 0.0, 0.0, 0.35 * alpha));
+            
+            // Border and background
+            self.draw_overlay_rect(pos - crate::Vec2::ONE, size + crate::Vec2::splat(2.0), with_alpha(theme.border, 0.5 * alpha), 7.0);
+            self.draw_overlay_rect(pos, size, crate::Vec4::new(0.05, 0.05, 0.08, 0.95 * alpha), 6.0);
+            
+            // Text
+            let mut y = pos.y + TOOLTIP_PADDING;
+            let x = pos.x + TOOLTIP_PADDING;
+            if !title.is_empty() {
+                self.draw_overlay_text(title, crate::Vec2::new(x, y), TOOLTIP_TITLE_SCALE, with_alpha(theme.primary, alpha));
+                y += title_line;
+            }
+            for (line, line_text) in body_layout.lines.iter().zip(body_layout.line_texts(body)) {
+                self.draw_overlay_text(line_text, crate::Vec2::new(x, y + line.y), TOOLTIP_BODY_SCALE, with_alpha(theme.text, alpha));
+            }
+            y += body_layout.height;
+            if !shortcut.is_empty() {
+                self.draw_overlay_text(shortcut, crate::Vec2::new(x, y + 2.0), TOOLTIP_HINT_SCALE, with_alpha(theme.text_secondary, alpha));
+            }
+        }
+        self.tooltips = tooltips;
+    }
+    
+    /// Queue a rectangle to render on the overlay layer (on top of everything,
+    /// `OVERLAY_LAYER` included)
+    /// Widget popups use `draw_in_overlay_layer` instead, so a `Modal`
+    /// opened over them still covers them.
+    pub fn draw_overlay_rect(&mut self, pos: crate::Vec2, size: crate::Vec2, color: crate::Vec4, radius: f32) {
+        self.overlay_rects.push(GlassInstance::new(pos, size, color, radius));
+    }
+    
+    /// Soft shadow drawn in the overlay layer, below all overlay rects
+    pub fn draw_overlay_shadow(&mut self, pos: crate::Vec2, size: crate::Vec2, radius: f32, softness: f32, color: crate::Vec4) {
+        self.overlay_shadows.push(GradientInstance::shadow(pos, size, radius, softness, color));
     }
     
     /// Queue text to render on the overlay layer (on top of everything)
     pub fn draw_overlay_text(&mut self, text: &str, pos: crate::Vec2, scale: f32, color: crate::Vec4) {
-        self.overlay_texts.push((text.to_string(), [pos.x, pos.y], scale, [color.x, color.y, color.z, color.w]));
+        self.overlay_texts.push(text, ([pos.x, pos.y], scale, [color.x, color.y, color.z, color.w]));
+    }
+    
+    /// Overlay counterpart of `draw_text_fmt`
+    pub fn draw_overlay_text_fmt(&mut self, args: std::fmt::Arguments, pos: crate::Vec2, scale: f32, color: crate::Vec4) {
+        let text = self.format_scratch(args);
+        self.draw_overlay_text(&text, pos, scale, color);
+        self.scratch_text = text;
     }
 
+    /// Upload the blur radius for the current settings, scale and target size
+    fn write_blur_params(&self) {
+        let factor = self.effective_blur_downsample().factor(self.size.width, self.size.height);
+        let radius = self.effective_blur().texel_radius(self.scale_factor(), factor);
+        for (offset, direction) in [(0, [1, 0]), (256, [0, 1])] {
+            let params = BlurParams { direction, radius, _padding: 0.0 };
+            self.queue.write_buffer(&self.blur_params_buffer, offset, bytemuck::bytes_of(&params));
+        }
+    }
+    
+    /// Blur the scene texture into the texture glass samples
+    fn encode_blur(&self, encoder: &mut wgpu::CommandEncoder, timestamp_writes: Option<wgpu::ComputePassTimestampWrites<'_>>) {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("Blur Pass"), timestamp_writes });
+        compute_pass.set_pipeline(&self.blur_pipeline);
+        
+        let (width, height) = self.blur_size;
+        
+        // Later iterations blur the previous result again (final -> intermediate -> final)
+        for iteration in 0..self.effective_blur().iterations.max(1) {
+            let horizontal = if iteration == 0 { 0 } else { 2 };
+            compute_pass.set_bind_group(0, &self.blur_bind_groups[horizontal], &[]);
+            compute_pass.dispatch_workgroups((width + 15) / 16, (height + 15) / 16, 1);
+            
+            compute_pass.set_bind_group(0, &self.blur_bind_groups[1], &[]);
+            compute_pass.dispatch_workgroups((width + 15) / 16, (height + 15) / 16, 1);
+        }
+    }
+    
+    /// Copy the frame rendered so far into the scene texture (backdrop glass)
+    fn encode_backdrop_copy(&self, encoder: &mut wgpu::CommandEncoder, frame: &wgpu::TextureView) {
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(frame) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.image_sampler) },
+            ],
+            label: Some("Backdrop Copy Bind Group"),
+        });
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Backdrop Copy Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.scene_view,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.backdrop_pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+    
     pub fn render(&mut self, root_widget: &mut dyn Widget) {
+        self.render_layers(&mut [root_widget]);
+    }
+    
+    /// Render several root widgets back to front in a single frame
+    /// 
+    /// Useful for app-level layers that live outside the main tree
+    /// (status bars, toasts, `PerfOverlay`).
+    pub fn render_layers(&mut self, layers: &mut [&mut dyn Widget]) {
+        let encode_start = std::time::Instant::now();
+        
+        for id in take_released_textures() {
+            self.unload_texture(id);
+        }
+        
+        if let Some(timer) = &mut self.gpu_timer {
+            if let Some([scene, blur, final_pass]) = timer.poll(&self.device) {
+                if self.profiling {
+                    // Skipped passes leave their timestamps unwritten
+                    if self.quality.uses_blur() {
+                        self.frame_timings.set(PerfStage::GpuScene, scene);
+                        self.frame_timings.set(PerfStage::GpuBlur, blur);
+                    }
+                    self.frame_timings.set(PerfStage::GpuFinal, final_pass);
+                }
+            }
+        }
+        
         self.instances.clear();
+        self.hidden_glass = 0;
+        self.gradients.clear();
+        self.mesh_vertices.clear();
+        self.mesh_indices.clear();
+        self.images.clear();
+        self.image_textures.clear();
         self.text_renderer.clear();
         self.batches.clear();
         self.current_scissor = None;
+        self.current_rounded = None;
+        self.current_layer = 0;
+        self.clip_stack.clear();
+        self.transform_stack.clear();
+        self.opacity_stack.clear();
+        self.draw_marks = [0; 5];
+        self.backdrop_pending = false;
         self.tooltips.clear();
+        self.overlay_shadows.clear();
         self.overlay_rects.clear();
         self.overlay_texts.clear();
         
-        root_widget.render(self);
+        if theme_generation() != self.theme_blur_generation {
+            self.theme_blur_generation = theme_generation();
+            let blur = get_theme().blur;
+            self.update_blur(|renderer| renderer.theme_blur = blur);
+        }
+        if self.quality.uses_blur() {
+            self.write_blur_params();
+        }
+        
+        for (i, layer) in layers.iter().enumerate() {
+            if is_occluded(&**layer, layers[i + 1..].iter().map(|l| &**l as &dyn Widget)) {
+                continue;
+            }
+            layer.render(self);
+            if debug_layout() {
+                render_layout_debug(&**layer, self);
+            }
+        }
+        if !self.clip_stack.is_empty() {
+            log::warn!("{} push_clip calls without pop_clip", self.clip_stack.len());
+            self.clip_stack.clear();
+            self.apply_clip();
+        }
+        if !self.transform_stack.is_empty() {
+            log::warn!("{} push_transform calls without pop_transform", self.transform_stack.len());
+            self.apply_draw_state();
+            self.transform_stack.clear();
+        }
+        if !self.opacity_stack.is_empty() {
+            log::warn!("{} push_opacity calls without pop_opacity", self.opacity_stack.len());
+            self.apply_draw_state();
+            self.opacity_stack.clear();
+        }
+        self.render_tooltips();
         self.finish_current_batch(); // Push last batch
         
-        let instance_bytes = bytemuck::cast_slice(&self.instances);
-        if instance_bytes.len() as u64 > self.instance_buffer.size() {
-             self.instance_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Instance Buffer"),
-                contents: instance_bytes,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            });
-        } else {
-            self.queue.write_buffer(&self.instance_buffer, 0, instance_bytes);
+        // With post-processing plugins the frame is drawn offscreen first
+        let post_count = self.post_passes.iter().filter(|pass| pass.plugin.enabled()).count();
+        if post_count > 0 {
+            self.ensure_post_targets(post_count.min(2));
         }
         
-        self.text_renderer.prepare(&self.queue);
+        // Overlay shadows follow the batched gradients in the same buffer
+        let overlay_shadow_start = self.gradients.len() as u32;
+        self.gradients.append(&mut self.overlay_shadows);
+        self.upload_batches();
+        self.group_text();
+        
+        self.overlay_buffer.write(&self.device, &self.queue, bytemuck::cast_slice(&self.overlay_rects));
         
         // Queue overlay text (will be rendered after main pass)
-        for (text, pos, scale, color) in &self.overlay_texts {
+        for (text, (pos, scale, color)) in self.overlay_texts.iter() {
             self.text_renderer.draw_text(&self.device, &self.queue, text, *pos, *scale, *color);
         }
-        self.text_renderer.prepare(&self.queue);
+        self.text_renderer.prepare(&self.device, &self.queue);
 
         // Guard against zero or very small window sizes
         if self.size.width < 2 || self.size.height < 2 {
@@ -587,131 +3579,470 @@ impl GlassRenderer {
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });
+        
+        let timed = self.profiling && self.gpu_timer.as_mut().is_some_and(|t| t.begin_frame());
+        let gpu_timer = if timed { self.gpu_timer.as_ref() } else { None };
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Scene Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &self.scene_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-            render_pass.set_pipeline(&self.bg_pipeline_offscreen); 
-            render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
-            render_pass.draw(0..3, 0..1);
+        let background_clear = {
+            let c = self.background.clear_color().as_dvec4();
+            wgpu::Color { r: c.x, g: c.y, b: c.z, a: c.w }
+        };
+        
+        // Flat quality skips the backdrop entirely; glass doesn't sample it
+        if self.quality.uses_blur() {
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Scene Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &self.scene_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations { load: wgpu::LoadOp::Clear(background_clear), store: wgpu::StoreOp::Store },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: gpu_timer.and_then(|t| t.render_pass_writes(GpuPass::Scene)),
+                    occlusion_query_set: None,
+                });
+                self.draw_background(&mut render_pass, &self.bg_pipeline_offscreen);
+            }
+            
+            self.encode_blur(&mut encoder, gpu_timer.and_then(|t| t.compute_pass_writes(GpuPass::Blur)));
         }
+
+        let translucent = self.is_translucent();
+        let clear_color = if translucent {
+            // Premultiplied dark glass tint
+            let a = self.background_opacity as f64;
+            wgpu::Color { r: 0.02 * a, g: 0.02 * a, b: 0.04 * a, a }
+        } else {
+            background_clear
+        };
         
-        {
-             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("Blur Pass"), timestamp_writes: None });
-             compute_pass.set_pipeline(&self.blur_pipeline);
-             
-             let width = self.size.width;
-             let height = self.size.height;
-             
-             compute_pass.set_bind_group(0, &self.blur_bind_groups[0], &[]);
-             compute_pass.dispatch_workgroups((width + 15) / 16, (height + 15) / 16, 1);
-             
-             compute_pass.set_bind_group(0, &self.blur_bind_groups[1], &[]);
-             compute_pass.dispatch_workgroups((width + 15) / 16, (height + 15) / 16, 1);
-        }
-
-        // Create overlay buffer BEFORE the render pass scope (buffer must outlive render_pass)
-        let overlay_buffer = if !self.overlay_rects.is_empty() {
-            let overlay_bytes = bytemuck::cast_slice(&self.overlay_rects);
-            Some(self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Overlay Buffer"),
-                contents: overlay_bytes,
-                usage: wgpu::BufferUsages::VERTEX,
-            }))
+        // Backdrop glass splits the final pass: before such a batch the
+        // frame so far is copied into the scene texture and re-blurred
+        let backdrop = self.quality.uses_blur() && self.backdrop_supported;
+        let segments = if backdrop {
+            pass_segments(self.batches.iter().map(|b| b.backdrop))
         } else {
-            None
+            vec![0, self.batches.len()]
         };
-
-        {
+        let segment_count = segments.len() - 1;
+        let frame_view = if post_count > 0 { &self.post_targets[0].view } else { &view };
+        
+        for (segment, bounds) in segments.windows(2).enumerate() {
+            let first = segment == 0;
+            let last = segment + 1 == segment_count;
+            if !first {
+                self.encode_backdrop_copy(&mut encoder, frame_view);
+                self.encode_blur(&mut encoder, None);
+            }
+            
+            // With MSAA the samples are kept across segments and resolved
+            // into the frame at the end of each, ready for the backdrop copy
+            let (target, resolve_target) = match &self.msaa_view {
+                Some(msaa) => (msaa, Some(frame_view)),
+                None => (frame_view, None),
+            };
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Final Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                    view: target,
+                    resolve_target,
+                    ops: wgpu::Operations {
+                        load: if first { wgpu::LoadOp::Clear(clear_color) } else { wgpu::LoadOp::Load },
+                        store: wgpu::StoreOp::Store,
+                    },
                 })],
                 depth_stencil_attachment: None,
-                timestamp_writes: None,
+                // Only the first segment is timed when backdrop glass splits the pass
+                timestamp_writes: if first { gpu_timer.and_then(|t| t.render_pass_writes(GpuPass::Final)) } else { None },
                 occlusion_query_set: None,
             });
             
-            render_pass.set_pipeline(&self.bg_pipeline_onscreen); 
-            render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
-            render_pass.draw(0..3, 0..1);
+            // Translucent windows skip the opaque background; the clear
+            // colour is the tint the desktop shows through
+            if first && !translucent {
+                self.draw_background(&mut render_pass, &self.bg_pipeline_onscreen);
+            }
             
-            // Loop batches with state tracking to reduce redundant calls
-            let mut current_pipeline: Option<u8> = None; // 0 = glass, 1 = text
-            let mut glass_buffer_bound = false;
+            let mut bound = self.draw_batches(&mut render_pass, bounds[0]..bounds[1], (self.size.width, self.size.height));
             
-            for batch in &self.batches {
-                 if let Some(rect) = batch.scissor {
-                    render_pass.set_scissor_rect(rect[0], rect[1], rect[2], rect[3]);
-                 } else {
-                    render_pass.set_scissor_rect(0, 0, self.size.width, self.size.height);
-                 }
-                 
-                 // Draw Glass
-                 if batch.glass_range.end > batch.glass_range.start {
-                    if current_pipeline != Some(0) {
-                        render_pass.set_pipeline(&self.glass_pipeline);
-                        render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
-                        render_pass.set_bind_group(1, &self.glass_texture_bind_group, &[]);
-                        current_pipeline = Some(0);
-                        // Need to rebind vertex buffer after switching from text
-                        glass_buffer_bound = false;
-                    }
-                    if !glass_buffer_bound {
-                        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(0..instance_bytes.len() as u64));
-                        glass_buffer_bound = true;
-                    }
-                    render_pass.draw(0..4, batch.glass_range.clone());
-                 }
-                 
-                 // Draw Text
-                 if batch.text_range.end > batch.text_range.start {
-                     // Text renderer sets its own pipeline and vertex buffer
-                     current_pipeline = Some(1);
-                     glass_buffer_bound = false; // Text changes vertex buffer, so we need to rebind glass buffer next time
-                     self.text_renderer.render_range(&mut render_pass, &self.bg_bind_group, batch.text_range.clone());
-                 }
+            if !last {
+                continue;
             }
             
             // Reset scissor for overlay rendering
             render_pass.set_scissor_rect(0, 0, self.size.width, self.size.height);
-            
+        
+            // --- Draw Overlay Shadows (below the overlay rects casting them) ---
+            let gradient_count = self.gradients.len() as u32;
+            if gradient_count > overlay_shadow_start {
+                render_pass.set_pipeline(&self.gradient_pipeline);
+                render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
+                render_pass.set_bind_group(1, &self.clip_bind_group, &[0]);
+                render_pass.set_vertex_buffer(0, self.gradient_buffer.slice());
+                self.gradient_buffer.draw_range(&mut render_pass, 0, overlay_shadow_start..gradient_count, |pass, range| pass.draw(0..4, range));
+                bound = BoundPipeline::Overlay;
+            }
+        
             // --- Draw Overlay Rects (dropdowns, popups - on top of everything) ---
-            if let Some(ref buffer) = overlay_buffer {
+            if !self.overlay_rects.is_empty() {
                 render_pass.set_pipeline(&self.glass_pipeline);
                 render_pass.set_bind_group(0, &self.bg_bind_group, &[]);
                 render_pass.set_bind_group(1, &self.glass_texture_bind_group, &[]);
-                render_pass.set_vertex_buffer(0, buffer.slice(..));
-                render_pass.draw(0..4, 0..self.overlay_rects.len() as u32);
+                render_pass.set_bind_group(2, &self.clip_bind_group, &[0]);
+                render_pass.set_vertex_buffer(0, self.overlay_buffer.slice());
+                self.overlay_buffer.draw_range(&mut render_pass, 0, 0..self.overlay_rects.len() as u32, |pass, range| pass.draw(0..4, range));
+                bound = BoundPipeline::Overlay;
             }
-            
+        
             // --- Draw Overlay Text ---
+            // Overlay text was queued after main text, render remaining
             let main_text_count = self.text_renderer.queue_buffer.len() as u32;
-            if main_text_count > 0 {
-                // Overlay text was queued after main text, render remaining
-                let overlay_text_start = self.batches.last()
-                    .map(|b| b.text_range.end)
-                    .unwrap_or(0);
-                    
-                if main_text_count > overlay_text_start {
-                    self.text_renderer.render_range(&mut render_pass, &self.bg_bind_group, overlay_text_start..main_text_count);
+            let overlay_text_start = self.batches.iter()
+                .map(|b| b.text_range.end)
+                .max()
+                .unwrap_or(0);
+            if main_text_count > overlay_text_start {
+                if bound != BoundPipeline::Text {
+                    self.text_renderer.bind(&mut render_pass, &self.bg_bind_group);
                 }
+                render_pass.set_bind_group(2, &self.clip_bind_group, &[0]);
+                self.text_renderer.draw_range(&mut render_pass, overlay_text_start..main_text_count);
             }
         }
         
+        if post_count > 0 {
+            self.encode_post_passes(&mut encoder, &view);
+        }
+        
+        if let Some(timer) = gpu_timer {
+            timer.resolve(&mut encoder);
+        }
+        
+        self.frame_timings.set(PerfStage::Encode, encode_start.elapsed().as_secs_f32() * 1000.0);
+        
         self.queue.submit(std::iter::once(encoder.finish()));
+        if timed {
+            if let Some(timer) = &mut self.gpu_timer {
+                timer.end_frame();
+            }
+        }
         output.present();
     }
 }
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_texture_runs_group_consecutive_ids() {
+        let (a, b) = (TextureId(0), TextureId(1));
+        let runs = texture_runs(&[a, a, b, a], 10);
+        assert_eq!(runs, vec![(a, 10..12), (b, 12..13), (a, 13..14)]);
+        assert!(texture_runs(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn test_released_cache_frees_texture_next_frame() {
+        let cache = RenderCache::new();
+        cache.texture.set(Some(TextureId(7)));
+        cache.key.set(Some(CacheKey { size: (10, 10), scale: 1.0, theme: 0 }));
+        cache.release();
+        assert!(!cache.is_valid() && cache.texture().is_none());
+        assert_eq!(take_released_textures(), vec![TextureId(7)]);
+        
+        // Nothing is queued twice
+        cache.release();
+        assert!(take_released_textures().is_empty());
+    }
+
+    #[test]
+    fn test_check_rgba_sizes() {
+        assert_eq!(check_rgba(&[0; 16], 2, 2), Ok(()));
+        assert_eq!(check_rgba(&[0; 12], 2, 2), Err(TextureError::SizeMismatch { expected: 16, actual: 12 }));
+        let image = crate::widgets::Image::new(crate::widgets::ImageSource::Memory { data: vec![0; 3], width: 1, height: 1 });
+        assert_eq!(image.error.as_deref(), Some("Expected 4 bytes of RGBA8 pixels, got 3"));
+    }
+
+    #[test]
+    fn test_gradient_instances() {
+        let (a, b) = (crate::Vec4::ONE, crate::Vec4::ZERO);
+        let horizontal = Gradient::angled(0.0, a, b);
+        let Gradient::Linear { start, end, .. } = horizontal else { panic!("expected linear") };
+        assert!(start.abs_diff_eq(crate::Vec2::new(0.0, 0.5), 1e-5));
+        assert!(end.abs_diff_eq(crate::Vec2::new(1.0, 0.5), 1e-5));
+
+        // Diagonals reach the opposite corners
+        let Gradient::Linear { start, end, .. } = Gradient::angled(45.0, a, b) else { panic!("expected linear") };
+        assert!((end - start).abs_diff_eq(crate::Vec2::ONE, 1e-5));
+
+        let size = crate::Vec2::new(100.0, 20.0);
+        let radial = GradientInstance::new(crate::Vec2::ZERO, size, &Gradient::radial(a, b), 50.0);
+        assert_eq!((radial.kind, radial.end[0], radial.corner_radius), (1, 0.5, 10.0));
+    }
+
+    #[test]
+    fn test_nested_clips_intersect() {
+        let outer = ClipState::push(None, Rect::new(crate::Vec2::ZERO, crate::Vec2::splat(100.0)), 12.0);
+        let inner = ClipState::push(Some(&outer), Rect::new(crate::Vec2::new(50.0, 20.0), crate::Vec2::splat(100.0)), 0.0);
+        assert_eq!(inner.bounds, Rect::new(crate::Vec2::new(50.0, 20.0), crate::Vec2::new(50.0, 80.0)));
+        // Square clips keep the enclosing rounded corners
+        assert_eq!(inner.rounded, outer.rounded);
+
+        assert_eq!(scissor_for(Rect::new(crate::Vec2::new(-5.5, 10.2), crate::Vec2::new(50.0, 20.0)), (40, 200)), [0, 10, 40, 21]);
+        assert_eq!(scissor_for(Rect::new(crate::Vec2::splat(300.0), crate::Vec2::splat(10.0)), (200, 200))[2], 0);
+    }
+
+    #[test]
+    fn test_rounded_clip_scales_to_physical_pixels() {
+        let clip = RoundedClip { rect: Rect::new(crate::Vec2::new(10.0, 20.0), crate::Vec2::new(100.0, 50.0)), radius: 8.0 };
+        let scaled = clip.scaled(1.5);
+        assert_eq!(scaled.rect, Rect::new(crate::Vec2::new(15.0, 30.0), crate::Vec2::new(150.0, 75.0)));
+        assert_eq!(scaled.radius, 12.0);
+    }
+
+    #[test]
+    fn test_transformed_quads() {
+        // Quarter turn around the quad's center keeps it in place, turned
+        let mut quad = GlassInstance::new(crate::Vec2::new(10.0, 20.0), crate::Vec2::new(40.0, 20.0), crate::Vec4::ONE, 0.0);
+        let turn = around(crate::Vec2::new(30.0, 30.0), glam::Affine2::from_angle(std::f32::consts::FRAC_PI_2));
+        transform_quad(&turn, &mut quad.position, &mut quad.transform);
+        let bounds = quad_bounds(quad.position, quad.size, quad.transform);
+        assert!((bounds.min - crate::Vec2::new(20.0, 10.0)).length() < 1e-4);
+        assert!((bounds.size() - crate::Vec2::new(20.0, 40.0)).length() < 1e-4);
+
+        // Only the 2D part of a Mat4 is used
+        let m = crate::Mat4::from_translation(glam::Vec3::new(5.0, 6.0, 7.0)) * crate::Mat4::from_scale(glam::Vec3::new(2.0, 3.0, 4.0));
+        assert_eq!(affine_2d(m), glam::Affine2::from_scale_angle_translation(crate::Vec2::new(2.0, 3.0), 0.0, crate::Vec2::new(5.0, 6.0)));
+        assert_eq!(quad_bounds([1.0, 2.0], [3.0, 4.0], IDENTITY_2X2), Rect::new(crate::Vec2::new(1.0, 2.0), crate::Vec2::new(3.0, 4.0)));
+    }
+
+    #[test]
+    fn test_opacity_fades_tints() {
+        let mut straight = [1.0, 0.5, 0.2, 0.8];
+        fade_tint(&mut straight, 0.5, false);
+        assert_eq!(straight, [1.0, 0.5, 0.2, 0.4]);
+        
+        // Premultiplied colors fade with their alpha
+        let mut premultiplied = [0.8, 0.4, 0.2, 0.8];
+        fade_tint(&mut premultiplied, 0.5, true);
+        assert_eq!(premultiplied, [0.4, 0.2, 0.1, 0.4]);
+        
+        assert_eq!(GlassInstance::new(crate::Vec2::ZERO, crate::Vec2::ONE, crate::Vec4::ONE, 0.0).opacity, 1.0);
+    }
+
+    #[test]
+    fn test_blur_downsample_follows_window_size() {
+        assert_eq!(BlurDownsample::Auto.factor(3840, 2160), 4);
+        assert_eq!(BlurDownsample::Auto.factor(1920, 1080), 2);
+        assert_eq!(BlurDownsample::Auto.factor(800, 600), 1);
+        assert_eq!(BlurDownsample::Full.factor(3840, 2160), 1);
+        assert_eq!(BlurDownsample::Half.scaled(101, 51), (50, 25));
+        assert_eq!(BlurDownsample::Quarter.scaled(2, 2), (1, 1));
+        assert_eq!(BlurDownsample::Auto.scaled(3840, 2160), (960, 540));
+    }
+
+    #[test]
+    fn test_blur_radius_follows_scale_and_downsample() {
+        let blur = BlurSettings::default();
+        // Same logical blur on a 1x half-res target and a 2x quarter-res one
+        assert_eq!(blur.texel_radius(1.0, 2), 4.0);
+        assert_eq!(blur.texel_radius(2.0, 4), 4.0);
+        assert_eq!(BlurSettings::new(500.0).texel_radius(1.0, 1), BlurSettings::MAX_TAPS);
+        assert_eq!(blur.with_iterations(0).iterations, 1);
+    }
+
+    #[test]
+    fn test_antialiasing_falls_back_when_unsupported() {
+        assert_eq!(AntiAliasing::default(), AntiAliasing::Off);
+        assert_eq!(AntiAliasing::Off.supported_sample_count(|_| false), 1);
+        assert_eq!(AntiAliasing::Msaa4x.supported_sample_count(|count| count == 4), 4);
+        assert_eq!(AntiAliasing::Msaa4x.supported_sample_count(|count| count == 2), 1);
+    }
+
+    #[test]
+    fn test_batches_only_merge_within_a_layer() {
+        let batch = RenderBatch {
+            backdrop: false,
+            scissor: None,
+            rounded: None,
+            glass_range: 0..1,
+            gradient_range: 0..0,
+            mesh_range: 0..0,
+            image_range: 0..0,
+            text_range: 0..0,
+            layer: 0,
+            text_bounds: None,
+        };
+        let content = Rect::new(crate::Vec2::ZERO, crate::Vec2::splat(10.0));
+        assert!(batch.can_absorb(0, None, None, content, Some(content)));
+        assert!(!batch.can_absorb(GlassRenderer::OVERLAY_LAYER, None, None, content, Some(content)));
+    }
+
+    #[test]
+    fn test_can_absorb_unclipped_content_that_keeps_its_order() {
+        let rect = |x: f32, y: f32, w: f32, h: f32| Rect::new(crate::Vec2::new(x, y), crate::Vec2::new(w, h));
+        let batch = RenderBatch {
+            backdrop: false,
+            scissor: Some([0, 0, 100, 100]),
+            rounded: None,
+            glass_range: 0..1,
+            gradient_range: 0..0,
+            mesh_range: 0..0,
+            image_range: 0..0,
+            text_range: 0..1,
+            layer: 0,
+            text_bounds: Some(rect(0.0, 0.0, 50.0, 20.0)),
+        };
+        let below_text = rect(10.0, 40.0, 30.0, 30.0);
+        
+        // Same scissor, or a nested one that doesn't clip the content
+        assert!(batch.can_absorb(0, Some([0, 0, 100, 100]), None, below_text, Some(below_text)));
+        assert!(batch.can_absorb(0, Some([0, 30, 60, 60]), None, below_text, Some(below_text)));
+        // The new scissor clips the content, or the old one would
+        assert!(!batch.can_absorb(0, Some([0, 50, 60, 60]), None, below_text, Some(below_text)));
+        let outside = rect(90.0, 90.0, 30.0, 30.0);
+        assert!(!batch.can_absorb(0, Some([80, 80, 50, 50]), None, outside, Some(outside)));
+        // A rounded clip can't change hands
+        let rounded = RoundedClip { rect: rect(0.0, 0.0, 100.0, 100.0), radius: 8.0 };
+        assert!(!batch.can_absorb(0, Some([0, 0, 100, 100]), Some(rounded), below_text, Some(below_text)));
+        // Glass merged in would draw under this batch's text
+        let over_text = rect(10.0, 10.0, 30.0, 30.0);
+        assert!(!batch.can_absorb(0, Some([0, 0, 100, 100]), None, over_text, Some(over_text)));
+        assert!(batch.can_absorb(0, Some([0, 0, 100, 100]), None, over_text, None));
+    }
+
+    #[test]
+    fn test_covered_glass_is_hidden_front_to_back() {
+        let glass = |x: f32, y: f32, size: f32, radius: f32| {
+            GlassInstance::new(crate::Vec2::new(x, y), crate::Vec2::splat(size), crate::Vec4::new(0.0, 0.0, 0.0, 0.3), radius)
+        };
+        let faded = |mut instance: GlassInstance| {
+            instance.opacity = 0.5;
+            instance
+        };
+        let mut instances = vec![
+            glass(20.0, 20.0, 10.0, 0.0),           // under the panel
+            glass(95.0, 20.0, 10.0, 0.0),           // sticking out of it
+            glass(2.0, 2.0, 10.0, 0.0),             // in its rounded-off corner
+            glass(0.0, 0.0, 100.0, 16.0),           // the panel
+            glass(120.0, 20.0, 10.0, 0.0),          // under faded glass only
+            faded(glass(110.0, 10.0, 40.0, 0.0)),
+            glass(200.0, 0.0, 10.0, 0.0),           // under a later chip
+            glass(195.0, -5.0, 20.0, 0.0),
+        ];
+        assert_eq!(hide_covered_glass(&mut instances), 2);
+        let hidden: Vec<bool> = instances.iter().map(|i| i.size == [0.0; 2]).collect();
+        assert_eq!(hidden, vec![true, false, false, false, false, false, true, false]);
+    }
+
+    #[test]
+    fn test_backdrop_batches_split_the_pass() {
+        assert_eq!(pass_segments([false, false].into_iter()), vec![0, 2]);
+        assert_eq!(pass_segments([true, false, true, false].into_iter()), vec![0, 2, 4]);
+        assert_eq!(pass_segments(std::iter::empty()), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_shape_quads_cover_the_shape() {
+        let arc = GradientInstance::arc(crate::Vec2::new(50.0, 50.0), 20.0, 1.0, -0.5, 30.0, crate::Vec4::W);
+        assert_eq!((arc.position, arc.size), ([29.0, 29.0], [42.0, 42.0]));
+        // Negative sweep flips to a positive one ending at the old start,
+        // thickness is capped to the radius
+        assert_eq!((arc.kind, arc.start, arc.end), (3, [0.5, 0.5], [20.0, 20.0]));
+
+        let line = GradientInstance::line(crate::Vec2::new(40.0, 10.0), crate::Vec2::new(10.0, 30.0), 4.0, crate::Vec4::W);
+        assert_eq!((line.position, line.size), ([7.0, 7.0], [36.0, 26.0]));
+        assert_eq!((line.start, line.end, line.corner_radius), ([33.0, 3.0], [3.0, 23.0], 2.0));
+    }
+
+    #[test]
+    fn test_rect_stroke_stays_inside_the_rect() {
+        // Strokes cover exactly the rect, at most half its short side wide
+        let stroke = GradientInstance::stroke(crate::Vec2::new(5.0, 5.0), crate::Vec2::new(40.0, 10.0), 20.0, 8.0, crate::Vec4::W);
+        assert_eq!((stroke.position, stroke.size), ([5.0, 5.0], [40.0, 10.0]));
+        assert_eq!((stroke.kind, stroke.start[0], stroke.corner_radius), (5, 5.0, 5.0));
+
+        // A thin border keeps its width and corners, in one solid color
+        let color = crate::Vec4::new(1.0, 0.5, 0.25, 0.5);
+        let border = GradientInstance::stroke(crate::Vec2::ZERO, crate::Vec2::new(100.0, 30.0), 8.0, 1.0, color);
+        assert_eq!((border.start[0], border.corner_radius), (1.0, 8.0));
+        assert_eq!((border.start_color, border.end_color), (color.to_array(), color.to_array()));
+
+        let negative = GradientInstance::stroke(crate::Vec2::ZERO, crate::Vec2::new(10.0, 10.0), -3.0, -1.0, color);
+        assert_eq!((negative.start[0], negative.corner_radius), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_shadow_quad_grows_by_softness() {
+        let shadow = GradientInstance::shadow(crate::Vec2::new(10.0, 20.0), crate::Vec2::new(100.0, 40.0), 12.0, 8.0, crate::Vec4::W);
+        assert_eq!(shadow.position, [2.0, 12.0]);
+        assert_eq!(shadow.size, [116.0, 56.0]);
+        assert_eq!((shadow.kind, shadow.start[0], shadow.corner_radius), (2, 8.0, 12.0));
+    }
+    
+    #[test]
+    fn test_buffers_grow_by_powers_of_two_within_the_limit() {
+        assert_eq!(grown_size(1000, 1 << 28), 1024);
+        assert_eq!(grown_size(1024, 1 << 28), 1024);
+        assert_eq!(grown_size(300 << 20, 1 << 30), 512 << 20);
+        // Near the limit the buffer is only as big as the limit allows
+        assert_eq!(grown_size(200 << 20, 256 << 20), 256 << 20);
+        
+        // Whole glyph quads only
+        assert_eq!(buffer_capacity(1000, 32, 6), 30);
+        assert_eq!(buffer_capacity(1000, 48, 1), 20);
+    }
+    
+    #[test]
+    fn test_draw_ranges_split_at_chunk_boundaries() {
+        let pieces = |range, len| chunk_ranges(range, len).collect::<Vec<_>>();
+        assert_eq!(pieces(3..8, 10), vec![(0, 3..8)]);
+        assert_eq!(pieces(8..25, 10), vec![(0, 8..10), (1, 0..10), (2, 0..5)]);
+        assert_eq!(pieces(10..10, 10), vec![]);
+        assert_eq!(pieces(0..5, u32::MAX), vec![(0, 0..5)]);
+    }
+    
+    #[test]
+    fn test_nine_slice_keeps_corners() {
+        let rect = Rect::new(crate::Vec2::new(10.0, 10.0), crate::Vec2::new(200.0, 100.0));
+        let insets = EdgeInsets::symmetric(16.0, 8.0);
+        let quads = nine_slice_quads(rect, insets, (64, 32), crate::Vec4::ONE);
+        assert_eq!(quads.len(), 9);
+        // Top-left corner: native size, texels 0..16 x 0..8
+        assert_eq!((quads[0].position, quads[0].size), ([10.0, 10.0], [16.0, 8.0]));
+        assert_eq!((quads[0].uv_min, quads[0].uv_max), ([0.0, 0.0], [0.25, 0.25]));
+        // Center stretches over the rest
+        assert_eq!((quads[4].position, quads[4].size), ([26.0, 18.0], [168.0, 84.0]));
+        assert_eq!((quads[4].uv_min, quads[4].uv_max), ([0.25, 0.25], [0.75, 0.75]));
+        
+        // Too short for both corners: they shrink and the middle row vanishes
+        let short = Rect::new(crate::Vec2::ZERO, crate::Vec2::new(100.0, 12.0));
+        let quads = nine_slice_quads(short, insets, (64, 32), crate::Vec4::ONE);
+        assert_eq!(quads.len(), 6);
+        assert_eq!(quads[0].size, [16.0, 6.0]);
+    }
+    
+    #[test]
+    fn test_render_cache_invalidation() {
+        let cache = RenderCache::new();
+        assert!(!cache.is_valid());
+        
+        let key = CacheKey { size: (100, 40), scale: 2.0, theme: theme_generation() };
+        cache.texture.set(Some(TextureId(3)));
+        cache.key.set(Some(key));
+        assert!(cache.is_valid());
+        cache.invalidate();
+        assert!(!cache.is_valid());
+        assert_eq!(cache.texture(), Some(TextureId(3)), "the texture is reused when re-rendering");
+        
+        // A theme change produces a different key
+        crate::widgets::set_theme(get_theme());
+        assert_ne!(CacheKey { theme: theme_generation(), ..key }, key);
+    }
+}
@@ -0,0 +1,25 @@
+// Copies the frame rendered so far into the (downsampled) scene texture,
+// which the blur pass then turns into the backdrop for glass drawn next
+
+@group(0) @binding(0) var t_frame: texture_2d<f32>;
+@group(0) @binding(1) var s_frame: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) v_idx: u32) -> VertexOutput {
+    // Fullscreen triangle
+    let pos = vec2<f32>(f32((v_idx << 1u) & 2u), f32(v_idx & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(pos * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(pos.x, 1.0 - pos.y);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(textureSample(t_frame, s_frame, in.uv).rgb, 1.0);
+}
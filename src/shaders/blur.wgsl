@@ -3,6 +3,7 @@
 
 struct Params {
     direction: vec2<u32>, // (1,0) or (0,1)
+    radius: f32,          // Gaussian radius in texels (sigma = radius / 2)
 };
 @group(0) @binding(2) var<uniform> params: Params;
 
@@ -13,20 +14,25 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         return;
     }
     
-    // Simple Box Blur or Gaussian
-    // Let's do a 9-tap gaussian for simplicity and speed
-    var weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+    // Separable gaussian, one tap per texel out to the radius (capped at 32)
+    let taps = min(i32(ceil(params.radius)), 32);
+    let sigma = max(params.radius * 0.5, 0.5);
+    let max_coord = vec2<i32>(dims) - 1;
+    let center = vec2<i32>(global_id.xy);
+    let step = vec2<i32>(params.direction);
     
-    var color = textureLoad(input_texture, global_id.xy, 0).rgb * weights[0];
+    var color = textureLoad(input_texture, global_id.xy, 0).rgb;
+    var total = 1.0;
     
-    for (var i = 1; i < 5; i++) {
-        let offset = vec2<i32>(i32(params.direction.x) * i, i32(params.direction.y) * i);
+    for (var i = 1; i <= taps; i++) {
+        let weight = exp(-f32(i * i) / (2.0 * sigma * sigma));
+        // Clamp to the edge rather than reading outside the texture
+        let sample1 = textureLoad(input_texture, clamp(center + step * i, vec2<i32>(0), max_coord), 0).rgb;
+        let sample2 = textureLoad(input_texture, clamp(center - step * i, vec2<i32>(0), max_coord), 0).rgb;
         
-        let sample1 = textureLoad(input_texture, vec2<u32>(vec2<i32>(global_id.xy) + offset), 0).rgb;
-        let sample2 = textureLoad(input_texture, vec2<u32>(vec2<i32>(global_id.xy) - offset), 0).rgb;
-        
-        color += (sample1 + sample2) * weights[i];
+        color += (sample1 + sample2) * weight;
+        total += 2.0 * weight;
     }
     
-    textureStore(output_texture, global_id.xy, vec4<f32>(color, 1.0));
+    textureStore(output_texture, global_id.xy, vec4<f32>(color / total, 1.0));
 }
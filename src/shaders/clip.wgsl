@@ -0,0 +1,18 @@
+// Rounded clip shared by the shaders that draw into clipped batches
+// (prepended by `clipped_shader!`). Each one binds the current batch's
+// region as `clip`.
+
+// Rounded clip region (radius 0 = scissor only)
+struct Clip {
+    rect: vec4<f32>, // min.xy, max.xy in pixels
+    radius: f32,
+};
+
+fn clip_alpha(p: vec2<f32>) -> f32 {
+    if (clip.radius <= 0.0) { return 1.0; }
+    let extent = (clip.rect.zw - clip.rect.xy) * 0.5;
+    let r = min(clip.radius, min(extent.x, extent.y));
+    let q = abs(p - (clip.rect.xy + extent)) - extent + r;
+    let d = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - r;
+    return 1.0 - smoothstep(-0.5, 0.5, d);
+}
@@ -3,6 +3,9 @@ struct VertexInput {
     @location(1) size: vec2<f32>,     
     @location(2) color: vec4<f32>,
     @location(3) corner_radius: f32,
+    @location(4) blur: f32,
+    @location(5) transform: vec4<f32>,
+    @location(6) opacity: f32,
 };
 
 struct VertexOutput {
@@ -11,16 +14,28 @@ struct VertexOutput {
     @location(1) uv: vec2<f32>, // 0..1 relative to rect
     @location(2) size: vec2<f32>, // Pixel size of the rect
     @location(3) corner_radius: f32,
+    @location(4) blur: f32,
+    @location(5) opacity: f32,
 };
 
 struct Uniforms {
     time: f32,
-    resolution: vec2<f32>,
+    quality: u32, // 0 = full, 1 = light (no glow), 2 = flat (no backdrop)
+    resolution: vec2<f32>, // logical pixels
+    scale: f32,            // physical pixels per logical pixel
+    origin: vec2<f32>,     // window position of the render target (cached widgets)
+    screen: vec2<f32>,     // window size, logical pixels
 };
 @group(0) @binding(0) var<uniform> uniforms: Uniforms;
 
 @group(1) @binding(0) var bg_texture: texture_2d<f32>;
 @group(1) @binding(1) var bg_sampler: sampler;
+// Unblurred backdrop, mixed in for instances with blur < 1
+@group(1) @binding(2) var scene_texture: texture_2d<f32>;
+
+// Rounded clip region of the current batch (`Clip` and `clip_alpha` are
+// prepended from clip.wgsl)
+@group(2) @binding(0) var<uniform> clip: Clip;
 
 @vertex
 fn vs_main(
@@ -32,7 +47,8 @@ fn vs_main(
     if (v_idx == 2u || v_idx == 3u) { pos.x = 1.0; }
     
     var out: VertexOutput;
-    let world_pos = input.position + pos * input.size;
+    // 2x2 transform (columns) applied around the instance origin
+    let world_pos = input.position + mat2x2<f32>(input.transform.xy, input.transform.zw) * (pos * input.size);
     
     let res = max(uniforms.resolution, vec2<f32>(1.0));
     
@@ -44,6 +60,8 @@ fn vs_main(
     out.uv = pos; 
     out.size = input.size;
     out.corner_radius = input.corner_radius;
+    out.blur = input.blur;
+    out.opacity = input.opacity;
     return out;
 }
 
@@ -70,17 +88,26 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 
     let border_width = 2.0;
     let border_alpha = 1.0 - smoothstep(border_width - 1.0, border_width, abs(dist));
-    let glow_intensity = exp(-0.1 * abs(dist)) * in.color.a * 3.0; // Increased glow
+    var glow_intensity = exp(-0.1 * abs(dist)) * in.color.a * 3.0; // Increased glow
+    if (uniforms.quality >= 1u) {
+        glow_intensity = 0.0;
+    }
     
     // Glass Effect: Sample blurred background
-    let screen_uv = in.clip_position.xy / uniforms.resolution;
+    let screen_uv = (in.clip_position.xy / uniforms.scale + uniforms.origin) / uniforms.screen;
     // Add distortion based on normal (fake normal from center)
     // Simple distortion: move UV towards center based on distance from center of rect
     // let distortion = center_uv * 0.05 * alpha_mask;
     // let glass_color = textureSample(bg_texture, bg_sampler, screen_uv + distortion).rgb;
     
     // For now, simple sampling
-    let glass_color = textureSample(bg_texture, bg_sampler, screen_uv).rgb;
+    var glass_color = textureSample(bg_texture, bg_sampler, screen_uv).rgb;
+    let sharp_color = textureSample(scene_texture, bg_sampler, screen_uv).rgb;
+    glass_color = mix(sharp_color, glass_color, clamp(in.blur, 0.0, 1.0));
+    if (uniforms.quality >= 2u) {
+        // Flat: the backdrop isn't rendered, use a neutral dark fill
+        glass_color = vec3<f32>(0.08, 0.08, 0.11);
+    }
     
     // Tint
     let tint = in.color.rgb;
@@ -102,5 +129,5 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // If we output alpha 1.0, we obscure the sharp BG completely with the blurred sample. That is correct.
     // But at the edges (anti-aliasing), alpha_mask < 1.0. We want to blend with sharp BG there.
     
-    return vec4<f32>(out_col, alpha_mask);
+    return vec4<f32>(out_col, alpha_mask * in.opacity * clip_alpha(in.clip_position.xy));
 }
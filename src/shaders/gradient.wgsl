@@ -0,0 +1,147 @@
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) start_color: vec4<f32>,
+    @location(3) end_color: vec4<f32>,
+    @location(4) start: vec2<f32>,
+    @location(5) end: vec2<f32>,
+    @location(6) corner_radius: f32,
+    @location(7) kind: u32,
+    @location(8) transform: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) local: vec2<f32>,    // 0..1 relative to rect
+    @location(1) size: vec2<f32>,
+    @location(2) start_color: vec4<f32>,
+    @location(3) end_color: vec4<f32>,
+    @location(4) start: vec2<f32>,
+    @location(5) end: vec2<f32>,
+    @location(6) corner_radius: f32,
+    @location(7) @interpolate(flat) kind: u32,
+};
+
+struct Uniforms {
+    time: f32,
+    quality: u32,
+    resolution: vec2<f32>,
+};
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+// Rounded clip region of the current batch (`Clip` and `clip_alpha` are
+// prepended from clip.wgsl)
+@group(1) @binding(0) var<uniform> clip: Clip;
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) v_idx: u32,
+    input: VertexInput
+) -> VertexOutput {
+    var pos = vec2<f32>(0.0, 0.0);
+    if (v_idx == 1u || v_idx == 3u) { pos.y = 1.0; }
+    if (v_idx == 2u || v_idx == 3u) { pos.x = 1.0; }
+
+    var out: VertexOutput;
+    // 2x2 transform (columns) applied around the instance origin
+    let world_pos = input.position + mat2x2<f32>(input.transform.xy, input.transform.zw) * (pos * input.size);
+    let res = max(uniforms.resolution, vec2<f32>(1.0));
+
+    out.clip_position = vec4<f32>((world_pos.x / res.x) * 2.0 - 1.0, 1.0 - (world_pos.y / res.y) * 2.0, 0.0, 1.0);
+    out.local = pos;
+    out.size = input.size;
+    out.start_color = input.start_color;
+    out.end_color = input.end_color;
+    out.start = input.start;
+    out.end = input.end;
+    out.corner_radius = input.corner_radius;
+    out.kind = input.kind;
+    return out;
+}
+
+fn sd_rounded_box(p: vec2<f32>, b: vec2<f32>, r: f32) -> f32 {
+    let q = abs(p) - b + r;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - r;
+}
+
+fn sd_segment(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = clamp(dot(pa, ba) / max(dot(ba, ba), 1e-6), 0.0, 1.0);
+    return length(pa - ba * h);
+}
+
+// Arc or pie slice around the origin: `start`/`sweep` in radians (clockwise
+// in screen space), stroked `thickness` inwards from `radius`, or filled
+// when the thickness is 0. Both end in flat cuts along the radius.
+fn sd_arc(p: vec2<f32>, start: f32, sweep: f32, radius: f32, thickness: f32) -> f32 {
+    let full = sweep >= 6.2831;
+    // Rotate so the middle of the arc points along +y, then mirror
+    let mid = start + sweep * 0.5;
+    let axis = vec2<f32>(cos(mid), sin(mid));
+    let q = vec2<f32>(abs(axis.x * p.y - axis.y * p.x), dot(p, axis));
+    let sc = vec2<f32>(sin(sweep * 0.5), cos(sweep * 0.5));
+
+    var d = length(q) - radius;
+    if (thickness > 0.0) {
+        let ra = radius - thickness * 0.5;
+        d = abs(length(q) - ra) - thickness * 0.5;
+    }
+    if (full) { return d; }
+
+    // Signed distance to the wedge between the two cuts
+    let m = length(q - sc * clamp(dot(q, sc), 0.0, radius));
+    return max(d, m * sign(sc.y * q.x - sc.x * q.y));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let half_size = in.size * 0.5;
+
+    if (in.kind == 2u) {
+        // Shadow: rounded box blurred by start.x pixels (the quad is grown
+        // by that much so the falloff fits inside it)
+        let softness = max(in.start.x, 0.5);
+        let inner = max(half_size - softness, vec2<f32>(0.0));
+        let shadow_r = min(in.corner_radius, min(inner.x, inner.y));
+        let d = sd_rounded_box((in.local - 0.5) * in.size, inner, shadow_r);
+        let falloff = 1.0 - smoothstep(-softness, softness, d);
+        return vec4<f32>(in.start_color.rgb, in.start_color.a * falloff * clip_alpha(in.clip_position.xy));
+    }
+
+    if (in.kind == 3u || in.kind == 4u) {
+        let p = in.local * in.size;
+        var d = 0.0;
+        if (in.kind == 3u) {
+            d = sd_arc(p - half_size, in.start.x, in.start.y, in.end.x, in.end.y);
+        } else {
+            d = sd_segment(p, in.start, in.end) - in.corner_radius;
+        }
+        let coverage = 1.0 - smoothstep(-0.5, 0.5, d);
+        return vec4<f32>(in.start_color.rgb, in.start_color.a * coverage * clip_alpha(in.clip_position.xy));
+    }
+
+    let r = min(in.corner_radius, min(half_size.x, half_size.y));
+    let dist = sd_rounded_box((in.local - 0.5) * in.size, half_size, r);
+    let mask = 1.0 - smoothstep(-0.5, 0.5, dist);
+
+    if (in.kind == 5u) {
+        // Stroke: the band start.x pixels wide inside the rounded box
+        let d = max(dist, -dist - in.start.x);
+        let coverage = 1.0 - smoothstep(-0.5, 0.5, d);
+        return vec4<f32>(in.start_color.rgb, in.start_color.a * coverage * clip_alpha(in.clip_position.xy));
+    }
+
+    var t = 0.0;
+    if (in.kind == 0u) {
+        // Linear: project onto start -> end
+        let axis = in.end - in.start;
+        t = dot(in.local - in.start, axis) / max(dot(axis, axis), 1e-6);
+    } else {
+        // Radial: start is the center, end.x the radius
+        t = length(in.local - in.start) / max(in.end.x, 1e-6);
+    }
+
+    let color = mix(in.start_color, in.end_color, clamp(t, 0.0, 1.0));
+    return vec4<f32>(color.rgb, color.a * mask * clip_alpha(in.clip_position.xy));
+}
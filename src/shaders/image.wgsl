@@ -0,0 +1,83 @@
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) uv_min: vec2<f32>,
+    @location(3) uv_max: vec2<f32>,
+    @location(4) tint: vec4<f32>,
+    @location(5) corner_radius: f32,
+    @location(6) transform: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,       // Texture coordinates
+    @location(1) local: vec2<f32>,    // 0..1 relative to rect
+    @location(2) size: vec2<f32>,
+    @location(3) tint: vec4<f32>,
+    @location(4) corner_radius: f32,
+};
+
+struct Uniforms {
+    time: f32,
+    quality: u32,
+    resolution: vec2<f32>,
+};
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+@group(1) @binding(0) var t_image: texture_2d<f32>;
+@group(1) @binding(1) var s_image: sampler;
+
+// Rounded clip region of the current batch (`Clip` and `clip_alpha` are
+// prepended from clip.wgsl)
+@group(2) @binding(0) var<uniform> clip: Clip;
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) v_idx: u32,
+    input: VertexInput
+) -> VertexOutput {
+    var pos = vec2<f32>(0.0, 0.0);
+    if (v_idx == 1u || v_idx == 3u) { pos.y = 1.0; }
+    if (v_idx == 2u || v_idx == 3u) { pos.x = 1.0; }
+
+    var out: VertexOutput;
+    // 2x2 transform (columns) applied around the instance origin
+    let world_pos = input.position + mat2x2<f32>(input.transform.xy, input.transform.zw) * (pos * input.size);
+    let res = max(uniforms.resolution, vec2<f32>(1.0));
+
+    out.clip_position = vec4<f32>((world_pos.x / res.x) * 2.0 - 1.0, 1.0 - (world_pos.y / res.y) * 2.0, 0.0, 1.0);
+    out.uv = mix(input.uv_min, input.uv_max, pos);
+    out.local = pos;
+    out.size = input.size;
+    out.tint = input.tint;
+    out.corner_radius = input.corner_radius;
+    return out;
+}
+
+fn sd_rounded_box(p: vec2<f32>, b: vec2<f32>, r: f32) -> f32 {
+    let q = abs(p) - b + r;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - r;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let half_size = in.size * 0.5;
+    let r = min(in.corner_radius, min(half_size.x, half_size.y));
+    let dist = sd_rounded_box((in.local - 0.5) * in.size, half_size, r);
+    let mask = 1.0 - smoothstep(-0.5, 0.5, dist);
+
+    let color = textureSample(t_image, s_image, in.uv) * in.tint;
+    return vec4<f32>(color.rgb, color.a * mask * clip_alpha(in.clip_position.xy));
+}
+
+// Premultiplied textures (cached widgets): coverage scales every channel
+@fragment
+fn fs_premultiplied(in: VertexOutput) -> @location(0) vec4<f32> {
+    let half_size = in.size * 0.5;
+    let r = min(in.corner_radius, min(half_size.x, half_size.y));
+    let dist = sd_rounded_box((in.local - 0.5) * in.size, half_size, r);
+    let mask = 1.0 - smoothstep(-0.5, 0.5, dist);
+
+    let color = textureSample(t_image, s_image, in.uv) * in.tint;
+    return color * (mask * clip_alpha(in.clip_position.xy));
+}
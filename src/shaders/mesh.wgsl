@@ -0,0 +1,34 @@
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+struct Uniforms {
+    time: f32,
+    quality: u32,
+    resolution: vec2<f32>,
+};
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+// Rounded clip region of the current batch (`Clip` and `clip_alpha` are
+// prepended from clip.wgsl)
+@group(1) @binding(0) var<uniform> clip: Clip;
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let res = max(uniforms.resolution, vec2<f32>(1.0));
+    out.clip_position = vec4<f32>((input.position.x / res.x) * 2.0 - 1.0, 1.0 - (input.position.y / res.y) * 2.0, 0.0, 1.0);
+    out.color = input.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.color.rgb, in.color.a * clip_alpha(in.clip_position.xy));
+}
@@ -2,12 +2,14 @@ struct VertexInput {
     @location(0) position: vec2<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) color: vec4<f32>,
+    @location(3) color_glyph: u32,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) uv: vec2<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) @interpolate(flat) color_glyph: u32,
 };
 
 struct Uniforms {
@@ -17,8 +19,14 @@ struct Uniforms {
 };
 @group(0) @binding(0) var<uniform> uniforms: Uniforms;
 
-@group(1) @binding(0) var t_diffuse: texture_2d<f32>;
-@group(1) @binding(1) var s_diffuse: sampler;
+// Coverage atlas (outline glyphs) and RGBA atlas (color emoji)
+@group(1) @binding(0) var t_coverage: texture_2d<f32>;
+@group(1) @binding(1) var s_atlas: sampler;
+@group(1) @binding(2) var t_color: texture_2d<f32>;
+
+// Rounded clip region of the current batch (`Clip` and `clip_alpha` are
+// prepended from clip.wgsl)
+@group(2) @binding(0) var<uniform> clip: Clip;
 
 @vertex
 fn vs_main(input: VertexInput) -> VertexOutput {
@@ -33,14 +41,21 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     out.clip_position = vec4<f32>(ndc_x, ndc_y, 0.0, 1.0);
     out.uv = input.uv;
     out.color = input.color;
+    out.color_glyph = input.color_glyph;
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // Sample alpha from texture (R8Unorm usually appears in .r)
-    // If it's single channel Red, we use .r as alpha
-    let alpha = textureSample(t_diffuse, s_diffuse, in.uv).r;
+    // Outline glyphs are coverage tinted by the vertex color; color emoji
+    // keep their texels (only the vertex alpha applies). Both are sampled
+    // so the sampling stays in uniform control flow.
+    let coverage = textureSample(t_coverage, s_atlas, in.uv).r;
+    let texel = textureSample(t_color, s_atlas, in.uv);
+    let clip = clip_alpha(in.clip_position.xy);
     
-    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+    if in.color_glyph != 0u {
+        return vec4<f32>(texel.rgb, in.color.a * texel.a * clip);
+    }
+    return vec4<f32>(in.color.rgb, in.color.a * coverage * clip);
 }
@@ -317,6 +317,12 @@ impl ShortcutManager {
         self.register(Shortcut::ctrl(ShortcutKey::S), "save_workspace", "Save workspace");
         self.register(Shortcut::ctrl(ShortcutKey::O), "load_workspace", "Load workspace");
         self.register(Shortcut::new(ShortcutKey::F11), "fullscreen", "Toggle fullscreen");
+        self.register(Shortcut::new(ShortcutKey::F2), "toggle_frame_stats", "Toggle FPS counter");
+        self.register(Shortcut::new(ShortcutKey::F3), "toggle_perf_overlay", "Toggle performance overlay");
+        self.register(Shortcut::new(ShortcutKey::F4), "cycle_glass_quality", "Cycle glass quality");
+        self.register(Shortcut::new(ShortcutKey::F12), "toggle_inspector", "Toggle widget inspector");
+        self.register(Shortcut::ctrl_shift(ShortcutKey::L), "toggle_layout_debug", "Toggle layout bounds");
+        self.register(Shortcut::ctrl_shift(ShortcutKey::R), "toggle_event_recording", "Start/stop event recording");
         self.register(Shortcut::new(ShortcutKey::Escape), "deselect", "Deselect / Close");
         self.register(Shortcut::ctrl_shift(ShortcutKey::P), "preferences", "Open preferences");
     }
@@ -6,7 +6,7 @@
 //! - Style variants (primary, secondary, danger, etc.)
 //! - Style inheritance and merging
 
-use glam::Vec4;
+use glam::{Vec2, Vec4};
 use crate::layout::EdgeInsets;
 
 // =============================================================================
@@ -153,6 +153,12 @@ pub struct TextStyle {
     pub font_weight: FontWeight,
     pub letter_spacing: f32,
     pub line_height: f32,
+    /// Outline thickness in pixels (0 = no outline)
+    pub outline_width: f32,
+    pub outline_color: Vec4,
+    /// Drop shadow offset; the shadow is drawn when `shadow_color` is visible
+    pub shadow_offset: Vec2,
+    pub shadow_color: Vec4,
 }
 
 impl Default for TextStyle {
@@ -163,6 +169,10 @@ impl Default for TextStyle {
             font_weight: FontWeight::Regular,
             letter_spacing: 0.0,
             line_height: 1.4,
+            outline_width: 0.0,
+            outline_color: Vec4::new(0.0, 0.0, 0.0, 0.8),
+            shadow_offset: Vec2::ZERO,
+            shadow_color: Vec4::ZERO,
         }
     }
 }
@@ -180,6 +190,28 @@ impl TextStyle {
         self.font_weight = weight;
         self
     }
+    
+    /// Stroke the glyphs with `color`, `width` pixels wide
+    pub fn with_outline(mut self, width: f32, color: Vec4) -> Self {
+        self.outline_width = width;
+        self.outline_color = color;
+        self
+    }
+    
+    /// Drop a `color` copy of the text (and its outline) at `offset`
+    pub fn with_shadow(mut self, offset: Vec2, color: Vec4) -> Self {
+        self.shadow_offset = offset;
+        self.shadow_color = color;
+        self
+    }
+    
+    pub fn has_outline(&self) -> bool {
+        self.outline_width > 0.0 && self.outline_color.w > 0.0
+    }
+    
+    pub fn has_shadow(&self) -> bool {
+        self.shadow_color.w > 0.0
+    }
 }
 
 /// Font weight
@@ -307,6 +339,18 @@ impl WidgetStyle {
         self.opacity = Some(opacity);
         self
     }
+    
+    // Rendering helpers for widgets taking `with_style`
+    
+    /// Text color from `text_style`, or `default`
+    pub fn text_color(&self, default: Vec4) -> Vec4 {
+        self.text_style.as_ref().map_or(default, |t| t.color)
+    }
+    
+    /// `border`, or `border_hover` while hovered (falling back to `border`)
+    pub fn border_for(&self, hovered: bool) -> Option<Border> {
+        if hovered { self.border_hover.or(self.border) } else { self.border }
+    }
 }
 
 // =============================================================================
@@ -12,6 +12,7 @@ use std::path::PathBuf;
 
 use crate::widget_id::WidgetId;
 use crate::panel_style::PanelPreset;
+use crate::widgets::{SimpleDate, SimpleTime};
 
 // =============================================================================
 // TASK ID
@@ -115,6 +116,8 @@ pub struct Task {
     pub on_complete_sound: NotificationSound,
     pub on_error_sound: NotificationSound,
     pub panel_preset: PanelPreset,
+    /// When the task should start, e.g. as picked with a `DateTimePicker`
+    pub scheduled_at: Option<(SimpleDate, SimpleTime)>,
 }
 
 impl Task {
@@ -130,6 +133,7 @@ impl Task {
             on_complete_sound: NotificationSound::Success,
             on_error_sound: NotificationSound::Error,
             panel_preset: PanelPreset::Default,
+            scheduled_at: None,
         }
     }
     
@@ -151,6 +155,18 @@ impl Task {
         self
     }
     
+    /// Schedule the task to start at a date and time
+    pub fn with_schedule(mut self, date: SimpleDate, time: SimpleTime) -> Self {
+        self.scheduled_at = Some((date, time));
+        self
+    }
+    
+    /// Whether a scheduled task is still pending at or past its start time
+    pub fn is_due(&self, date: SimpleDate, time: SimpleTime) -> bool {
+        self.status == TaskStatus::Pending
+            && self.scheduled_at.is_some_and(|at| at <= (date, time))
+    }
+    
     /// Start the task
     pub fn start(&mut self) {
         self.status = TaskStatus::Running;
@@ -324,4 +340,19 @@ mod tests {
         manager.get_mut(id1).unwrap().task.complete();
         assert_eq!(manager.completed().len(), 1);
     }
+    
+    #[test]
+    fn test_scheduled_task_is_due() {
+        let date = SimpleDate::new(2024, 3, 9);
+        let mut task = Task::new("Backup").with_schedule(date, SimpleTime::new(14, 30));
+        
+        assert!(!task.is_due(date, SimpleTime::new(14, 29)));
+        assert!(task.is_due(date, SimpleTime::new(14, 30)));
+        assert!(task.is_due(SimpleDate::new(2024, 3, 10), SimpleTime::new(0, 0)));
+        assert!(!task.is_due(SimpleDate::new(2023, 12, 31), SimpleTime::new(23, 59)));
+        
+        task.start();
+        assert!(!task.is_due(date, SimpleTime::new(15, 0)));
+        assert!(!Task::new("Unscheduled").is_due(date, SimpleTime::new(15, 0)));
+    }
 }
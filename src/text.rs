@@ -1,6 +1,48 @@
-use ab_glyph::{Font, FontVec, Point, PxScale, ScaleFont};
+use ab_glyph::{Font, FontVec, GlyphId, GlyphImageFormat, Point, PxScale, ScaleFont};
 
+use std::borrow::Cow;
+use std::cell::{Ref, RefCell};
 use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::bidi;
+use crate::layout::Rect;
+use crate::profiler::GlyphCacheStats;
+use crate::renderer::{clipped_shader, GrowableBuffer};
+
+/// Handle to a font loaded with `TextRenderer::load_font`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FontId(u32);
+
+impl FontId {
+    /// The system UI font picked at startup
+    pub const SYSTEM: FontId = FontId(0);
+}
+
+/// Why a font couldn't be loaded
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontError {
+    /// The file couldn't be read
+    Io(String),
+    /// Not a TrueType/OpenType font (or collection)
+    Invalid,
+    /// No installed font matches the family name
+    NotFound(String),
+}
+
+impl std::fmt::Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontError::Io(s) => write!(f, "Failed to read font: {}", s),
+            FontError::Invalid => write!(f, "Not a TrueType/OpenType font"),
+            FontError::NotFound(family) => write!(f, "No installed font named '{}'", family),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -8,41 +50,318 @@ pub struct TextVertex {
     pub position: [f32; 2],
     pub uv: [f32; 2],
     pub color: [f32; 4],
+    /// 1 for color glyphs (drawn from the color atlas untinted, only the
+    /// alpha of `color` applies), 0 for coverage glyphs
+    pub color_glyph: u32,
+}
+
+/// What a queued glyph quad was drawn from; see `TextRenderer::group_range`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextGroup {
+    pub font: FontId,
+    /// Size the glyph was rasterized at (x10, as in the glyph cache key)
+    pub scale_key: u32,
+    /// Atlas page holding the bitmap (None for empty glyphs)
+    pub page: Option<u32>,
+}
+
+/// The loaded fonts and how glyphs fall back between them
+#[derive(Clone)]
+struct FontSet {
+    /// Indexed by `FontId` (0 = the system font)
+    faces: Vec<Arc<FontVec>>,
+    default_font: FontId,
+    /// Fonts tried in order for glyphs missing from the requested font
+    fallback_fonts: Vec<FontId>,
+    /// Font emoji are drawn with even when the requested font has them
+    emoji_font: Option<FontId>,
+}
+
+impl FontSet {
+    /// Font for `id`, falling back to the default one for unknown ids
+    fn font(&self, id: FontId) -> &FontVec {
+        self.faces.get(id.0 as usize).unwrap_or(&self.faces[self.default_font.0 as usize])
+    }
+    
+    /// Font `c` is drawn with when `font` is requested, see `pick_font`
+    fn glyph_font(&self, font: FontId, c: char) -> FontId {
+        pick_font(c, font, self.default_font, self.emoji_font, &self.fallback_fonts, |id, c| self.font(id).glyph_id(c) != GlyphId(0))
+    }
+    
+    /// `scale` for `font` converted so `glyph_font`'s glyphs have the same
+    /// em size (fonts divide the scale between ascent and descent differently)
+    fn glyph_scale(&self, font: FontId, glyph_font: FontId, scale: f32) -> f32 {
+        if font == glyph_font {
+            return scale;
+        }
+        let em = |f: &FontVec| f.units_per_em().unwrap_or(1000.0) / f.height_unscaled().max(1.0);
+        scale * em(self.font(font)) / em(self.font(glyph_font))
+    }
+    
+    /// Width of `text` in `font`, with emoji and missing glyphs measured in
+    /// the font they're drawn with
+    fn text_width(&self, font: FontId, text: &str, scale: f32) -> f32 {
+        if needs_shaping(text) {
+            return self.shape(font, text, scale).iter().map(|g| g.advance).sum();
+        }
+        text.chars().filter(|&c| !c.is_control() && !is_zero_width(c)).map(|c| {
+            let glyph_font = self.glyph_font(font, c);
+            let scaled = self.font(glyph_font).as_scaled(PxScale::from(self.glyph_scale(font, glyph_font, scale)));
+            scaled.h_advance(scaled.glyph_id(c))
+        }).sum()
+    }
+    
+    /// Glyphs of `text` (one line) in display order, left to right
+    /// 
+    /// Each bidi run is split where the font changes (missing glyphs,
+    /// emoji) and every piece is shaped with rustybuzz, so Arabic letters
+    /// join, ligatures and kerning apply and marks sit on their base.
+    fn shape(&self, font: FontId, text: &str, scale: f32) -> Vec<ShapedGlyph> {
+        let mut glyphs = Vec::new();
+        for run in bidi::visual_runs(text) {
+            let mut segments = self.font_segments(font, &text[run.range.clone()]);
+            if run.rtl {
+                segments.reverse();
+            }
+            for (glyph_font, segment) in segments {
+                self.shape_segment(font, glyph_font, segment, run.rtl, scale, &mut glyphs);
+            }
+        }
+        glyphs
+    }
+    
+    /// `text` split into pieces drawn in one font each (logical order);
+    /// marks and invisible characters stay with the character before them
+    fn font_segments<'a>(&self, font: FontId, text: &'a str) -> Vec<(FontId, &'a str)> {
+        let mut segments: Vec<(FontId, &'a str)> = Vec::new();
+        let mut start = 0;
+        let mut current = None;
+        for (i, c) in text.char_indices() {
+            if c.is_control() || is_zero_width(c) || is_mark(c) {
+                continue;
+            }
+            let glyph_font = self.glyph_font(font, c);
+            match current {
+                Some(f) if f != glyph_font => {
+                    segments.push((f, &text[start..i]));
+                    start = i;
+                }
+                _ => {}
+            }
+            current = Some(glyph_font);
+        }
+        segments.push((current.unwrap_or(font), &text[start..]));
+        segments
+    }
+    
+    fn shape_segment(&self, font: FontId, glyph_font: FontId, text: &str, rtl: bool, scale: f32, out: &mut Vec<ShapedGlyph>) {
+        let data = self.font(glyph_font);
+        // Fonts are loaded from the first face of a collection
+        let Some(face) = rustybuzz::Face::from_slice(data.as_slice(), 0) else { return };
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        buffer.set_direction(if rtl { rustybuzz::Direction::RightToLeft } else { rustybuzz::Direction::LeftToRight });
+        let shaped = rustybuzz::shape(&face, &[], buffer);
+        
+        let scaled = data.as_scaled(PxScale::from(self.glyph_scale(font, glyph_font, scale)));
+        let (sx, sy) = (scaled.h_scale_factor(), scaled.v_scale_factor());
+        for (info, pos) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+            out.push(ShapedGlyph {
+                font: glyph_font,
+                glyph: GlyphId(info.glyph_id as u16),
+                offset: [pos.x_offset as f32 * sx, -pos.y_offset as f32 * sy],
+                advance: pos.x_advance as f32 * sx,
+            });
+        }
+    }
+}
+
+/// A glyph placed by `FontSet::shape`, in pixels at the shaped scale
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ShapedGlyph {
+    font: FontId,
+    glyph: GlyphId,
+    /// From the pen position, y down
+    offset: [f32; 2],
+    advance: f32,
 }
 
 pub struct TextRenderer {
     pipeline: wgpu::RenderPipeline,
     _bind_group_layout: wgpu::BindGroupLayout,
     atlas_bind_group: wgpu::BindGroup,
+    /// One byte of coverage per texel, for outline glyphs
     atlas_texture: wgpu::Texture,
+    /// RGBA, for color emoji
+    color_atlas_texture: wgpu::Texture,
     
-    font: FontVec,
+    /// Loaded fonts, shared with `layout_text_width`
+    fonts: Arc<FontSet>,
     atlas: FontAtlas,
+    color_atlas: FontAtlas,
     
-    vertex_buffer: wgpu::Buffer,
+    vertex_buffer: GrowableBuffer,
     _vertices: Vec<TextVertex>,
+    /// Reused buffer for rasterizing glyphs into an atlas (coverage or RGBA)
+    glyph_pixels: Vec<u8>,
+    /// One atlas page of zeros, uploaded over evicted pages
+    page_zeros: Vec<u8>,
+    /// Reused buffer for `group_range`
+    group_scratch: Vec<(TextGroup, [TextVertex; 6])>,
     
     pub queue_buffer: Vec<TextVertex>, // Pending draws
+    /// Group of each queued quad (one per 6 vertices of `queue_buffer`)
+    pub queue_groups: Vec<TextGroup>,
+    /// Physical pixels per logical pixel; glyphs are rasterized at the
+    /// physical size and laid out in logical pixels
+    pub dpi_scale: f32,
 }
 
+/// (font, glyph, scale_x10): glyphs are cached per size
+type GlyphKey = (FontId, GlyphId, u32);
+
+/// Horizontal bands of the atlas, filled and evicted as a unit
+const ATLAS_PAGES: u32 = 4;
+/// Gap left around glyphs so linear filtering doesn't bleed
+const GLYPH_PADDING: u32 = 2;
+
+/// Glyph cache packed into one texture
+/// 
+/// The texture is split into `ATLAS_PAGES` pages, each shelf-packed. When
+/// a glyph fits in none, the least recently used page that nothing drew
+/// from this frame is emptied for it, so text that keeps changing (metrics,
+/// clocks) cycles through the pages instead of filling the atlas for good.
 struct FontAtlas {
     size: u32,
-    cursor: (u32, u32), // x, y
+    pages: Vec<AtlasPage>,
+    glyphs: HashMap<GlyphKey, GlyphInfo>,
+    /// Advanced once per frame, for page recency
+    frame: u64,
+    stats: GlyphCacheStats,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct AtlasPage {
+    /// Next free spot (x, y) relative to the page
+    cursor: (u32, u32),
     row_height: u32,
-    /// Key is (char, scale_x10) to cache glyphs at different sizes
-    glyphs: HashMap<(char, u32), GlyphInfo>,
+    /// Frame a glyph on this page was last drawn in
+    last_used: u64,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 struct GlyphInfo {
     uv_rect: [f32; 4], // u_min, v_min, u_max, v_max
     screen_rect: [f32; 4], // x_off, y_off, w, h
     advance: f32,
+    /// Color bitmap (emoji): drawn untinted
+    color: bool,
+    /// Atlas page holding the bitmap (None for empty glyphs like spaces)
+    page: Option<u32>,
+}
+
+/// Where `FontAtlas::allocate` put a glyph
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct AtlasSlot {
+    page: u32,
+    x: u32,
+    y: u32,
+    /// The page was evicted to make room (its old pixels should be cleared)
+    evicted: bool,
+}
+
+impl FontAtlas {
+    fn new(size: u32) -> Self {
+        Self {
+            size,
+            pages: vec![AtlasPage::default(); ATLAS_PAGES as usize],
+            glyphs: HashMap::new(),
+            frame: 0,
+            stats: GlyphCacheStats::default(),
+        }
+    }
+    
+    fn page_height(&self) -> u32 {
+        self.size / ATLAS_PAGES
+    }
+    
+    /// Start a new frame: pages drawn from before now may be evicted
+    fn next_frame(&mut self) {
+        self.frame += 1;
+        self.stats = GlyphCacheStats { cached_glyphs: self.glyphs.len(), ..Default::default() };
+    }
+    
+    /// Cached glyph, marking its page as used this frame
+    fn lookup(&mut self, key: &GlyphKey) -> Option<GlyphInfo> {
+        let info = *self.glyphs.get(key)?;
+        if let Some(page) = info.page {
+            self.pages[page as usize].last_used = self.frame;
+        }
+        self.stats.hits += 1;
+        Some(info)
+    }
+    
+    /// Cache a glyph whose bitmap went into `info.page` (if any)
+    fn insert(&mut self, key: GlyphKey, info: GlyphInfo) {
+        self.glyphs.insert(key, info);
+        self.stats.cached_glyphs = self.glyphs.len();
+    }
+    
+    /// Find room for a `w`x`h` bitmap, evicting the least recently used
+    /// page if needed; None if it's bigger than a page or every page was
+    /// drawn from this frame
+    fn allocate(&mut self, w: u32, h: u32) -> Option<AtlasSlot> {
+        let page_height = self.page_height();
+        if w + GLYPH_PADDING > self.size || h + GLYPH_PADDING > page_height {
+            self.stats.dropped += 1;
+            return None;
+        }
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            if let Some((x, y)) = page.pack(w, h, self.size, page_height) {
+                page.last_used = self.frame;
+                return Some(AtlasSlot { page: index as u32, x, y: y + index as u32 * page_height, evicted: false });
+            }
+        }
+        
+        // Full: empty the least recently used page not drawn from this frame
+        let Some(index) = (0..self.pages.len())
+            .filter(|&i| self.pages[i].last_used < self.frame)
+            .min_by_key(|&i| self.pages[i].last_used) else {
+            self.stats.dropped += 1;
+            return None;
+        };
+        self.glyphs.retain(|_, info| info.page != Some(index as u32));
+        self.stats.evictions += 1;
+        self.stats.cached_glyphs = self.glyphs.len();
+        
+        let page = &mut self.pages[index];
+        *page = AtlasPage { last_used: self.frame, ..Default::default() };
+        let (x, y) = page.pack(w, h, self.size, page_height)?;
+        Some(AtlasSlot { page: index as u32, x, y: y + index as u32 * page_height, evicted: true })
+    }
+}
+
+impl AtlasPage {
+    /// Shelf-pack a `w`x`h` bitmap, returning its spot relative to the page
+    fn pack(&mut self, w: u32, h: u32, width: u32, height: u32) -> Option<(u32, u32)> {
+        if self.cursor.0 + w + GLYPH_PADDING > width {
+            self.cursor = (0, self.cursor.1 + self.row_height + GLYPH_PADDING);
+            self.row_height = 0;
+        }
+        if self.cursor.1 + h + GLYPH_PADDING > height {
+            return None;
+        }
+        let spot = self.cursor;
+        self.cursor.0 += w + GLYPH_PADDING;
+        self.row_height = self.row_height.max(h);
+        Some(spot)
+    }
 }
 
 impl TextRenderer {
     /// Try to load font from various system paths, with embedded fallback
-    fn load_system_font() -> FontVec {
+    pub fn load_system_font() -> FontVec {
         // Font search paths for different platforms
         let font_paths = [
             // Windows
@@ -83,24 +402,101 @@ impl TextRenderer {
         );
     }
     
-    pub fn new(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, bg_bind_group_layout: &wgpu::BindGroupLayout) -> Self {
+    /// Load an installed color emoji font (Noto Color Emoji, Apple Color
+    /// Emoji or Segoe UI Emoji), if there is one
+    /// 
+    /// Bitmap fonts (CBDT/sbix) draw their strikes. Layered fonts (COLR, like
+    /// Segoe UI Emoji) draw their layers in flat colors: gradients use the
+    /// average of their stops and COLRv1 transforms are ignored.
+    pub fn load_emoji_font() -> Option<FontVec> {
+        load_first_font("emoji", &[
+            // Windows
+            "C:/Windows/Fonts/seguiemj.ttf",
+            // macOS
+            "/System/Library/Fonts/Apple Color Emoji.ttc",
+            // Linux
+            "/usr/share/fonts/truetype/noto/NotoColorEmoji.ttf",
+            "/usr/share/fonts/noto/NotoColorEmoji.ttf",
+            "/usr/share/fonts/google-noto-emoji/NotoColorEmoji.ttf",
+            "/usr/share/fonts/noto-emoji/NotoColorEmoji.ttf",
+            "/usr/share/fonts/TTF/NotoColorEmoji.ttf",
+        ])
+    }
+    
+    /// Load an installed Chinese/Japanese/Korean font, if there is one
+    pub fn load_cjk_font() -> Option<FontVec> {
+        load_first_font("CJK", &[
+            // Windows
+            "C:/Windows/Fonts/msyh.ttc",
+            "C:/Windows/Fonts/YuGothM.ttc",
+            "C:/Windows/Fonts/malgun.ttf",
+            // macOS
+            "/System/Library/Fonts/PingFang.ttc",
+            "/System/Library/Fonts/Hiragino Sans GB.ttc",
+            "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+            // Linux
+            "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+            "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
+        ])
+    }
+    
+    /// Load an installed symbol font (arrows, math, dingbats), if there is one
+    pub fn load_symbol_font() -> Option<FontVec> {
+        load_first_font("symbol", &[
+            // Windows
+            "C:/Windows/Fonts/seguisym.ttf",
+            // macOS
+            "/System/Library/Fonts/Apple Symbols.ttf",
+            // Linux
+            "/usr/share/fonts/truetype/noto/NotoSansSymbols2-Regular.ttf",
+            "/usr/share/fonts/noto/NotoSansSymbols2-Regular.ttf",
+            "/usr/share/fonts/truetype/noto/NotoSansSymbols-Regular.ttf",
+            "/usr/share/fonts/truetype/ancient-scripts/Symbola_hint.ttf",
+            "/usr/share/fonts/TTF/Symbola.ttf",
+        ])
+    }
+    
+    pub fn new(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, bg_bind_group_layout: &wgpu::BindGroupLayout, clip_layout: &wgpu::BindGroupLayout, sample_count: u32) -> Self {
         // Load font with cross-platform fallback
         let font = Self::load_system_font();
+        let mut fonts = vec![font];
+        
+        // Default fallback chain: CJK, then emoji, then symbols
+        let mut emoji_font = None;
+        let mut fallback_fonts = Vec::new();
+        for (font, is_emoji) in [(Self::load_cjk_font(), false), (Self::load_emoji_font(), true), (Self::load_symbol_font(), false)] {
+            if let Some(font) = font {
+                let id = FontId(fonts.len() as u32);
+                fonts.push(font);
+                fallback_fonts.push(id);
+                if is_emoji {
+                    emoji_font = Some(id);
+                }
+            }
+        }
+        let fonts = Arc::new(FontSet { faces: fonts.into_iter().map(Arc::new).collect(), default_font: FontId::SYSTEM, fallback_fonts, emoji_font });
+        set_layout_fonts(&fonts);
 
-        // Create Atlas Texture (R8Unorm)
+        // Atlas textures: coverage for outline glyphs, RGBA for the (fewer) color emoji
         let atlas_size = 1024;
-        let texture_desc = wgpu::TextureDescriptor {
-            label: Some("Font Atlas"),
-            size: wgpu::Extent3d { width: atlas_size, height: atlas_size, depth_or_array_layers: 1 },
+        let color_atlas_size = 512;
+        let atlas_texture_desc = |label, size, format| wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d { width: size, height: size, depth_or_array_layers: 1 },
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::R8Unorm,
+            format,
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         };
-        let atlas_texture = device.create_texture(&texture_desc);
+        let atlas_texture = device.create_texture(&atlas_texture_desc("Font Atlas", atlas_size, wgpu::TextureFormat::R8Unorm));
         let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let color_atlas_texture = device.create_texture(&atlas_texture_desc("Color Font Atlas", color_atlas_size, wgpu::TextureFormat::Rgba8Unorm));
+        let color_atlas_view = color_atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
         
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             mag_filter: wgpu::FilterMode::Linear,
@@ -112,6 +508,7 @@ impl TextRenderer {
              entries: &[
                  wgpu::BindGroupLayoutEntry { binding: 0, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Texture { sample_type: wgpu::TextureSampleType::Float { filterable: true }, view_dimension: wgpu::TextureViewDimension::D2, multisampled: false }, count: None },
                  wgpu::BindGroupLayoutEntry { binding: 1, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering), count: None },
+                 wgpu::BindGroupLayoutEntry { binding: 2, visibility: wgpu::ShaderStages::FRAGMENT, ty: wgpu::BindingType::Texture { sample_type: wgpu::TextureSampleType::Float { filterable: true }, view_dimension: wgpu::TextureViewDimension::D2, multisampled: false }, count: None },
             ],
             label: Some("text_atlas_layout"),
         });
@@ -121,15 +518,16 @@ impl TextRenderer {
             entries: &[
                 wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&atlas_view) },
                 wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&sampler) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&color_atlas_view) },
             ],
             label: Some("text_atlas_bg"),
         });
         
         // Pipeline
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/text.wgsl"));
+        let shader = device.create_shader_module(clipped_shader!("shaders/text.wgsl"));
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Text Pipeline Layout"),
-            bind_group_layouts: &[bg_bind_group_layout, &bind_group_layout],
+            bind_group_layouts: &[bg_bind_group_layout, &bind_group_layout, clip_layout],
             push_constant_ranges: &[],
         });
         
@@ -140,6 +538,7 @@ impl TextRenderer {
                 wgpu::VertexAttribute { offset: 0, shader_location: 0, format: wgpu::VertexFormat::Float32x2 }, // Pos
                 wgpu::VertexAttribute { offset: 8, shader_location: 1, format: wgpu::VertexFormat::Float32x2 }, // UV
                 wgpu::VertexAttribute { offset: 16, shader_location: 2, format: wgpu::VertexFormat::Float32x4 }, // Color
+                wgpu::VertexAttribute { offset: 32, shader_location: 3, format: wgpu::VertexFormat::Uint32 }, // Color glyph
             ],
         };
 
@@ -158,191 +557,748 @@ impl TextRenderer {
             }),
             primitive: wgpu::PrimitiveState { topology: wgpu::PrimitiveTopology::TriangleList, ..Default::default() },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState { count: sample_count, ..Default::default() },
             multiview: None,
         });
         
-        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Text Vertices"),
-            size: 1024 * 1024, // 1MB for text
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        // Grows with the queued text (6 vertices per glyph)
+        let vertex_buffer = GrowableBuffer::new::<TextVertex>(device, "Text Vertices", wgpu::BufferUsages::VERTEX, 1024 * 1024 / std::mem::size_of::<TextVertex>()).grouped(6);
 
         Self {
-            pipeline, _bind_group_layout: bind_group_layout, atlas_bind_group, atlas_texture,
-            font,
-            atlas: FontAtlas { size: atlas_size, cursor: (0, 0), row_height: 0, glyphs: HashMap::new() },
+            pipeline, _bind_group_layout: bind_group_layout, atlas_bind_group, atlas_texture, color_atlas_texture,
+            fonts,
+            atlas: FontAtlas::new(atlas_size),
+            color_atlas: FontAtlas::new(color_atlas_size),
             vertex_buffer,
             _vertices: Vec::new(),
+            glyph_pixels: Vec::new(),
+            page_zeros: Vec::new(),
+            group_scratch: Vec::new(),
             queue_buffer: Vec::new(),
+            queue_groups: Vec::new(),
+            dpi_scale: 1.0,
         }
     }
 
-    pub fn draw_text(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, text: &str, pos: [f32; 2], scale: f32, color: [f32; 4]) {
+    // --- Fonts ---
+    
+    /// Add a TTF/OTF font from its file contents (the first face of a
+    /// collection)
+    pub fn load_font(&mut self, bytes: Vec<u8>) -> Result<FontId, FontError> {
+        let font = Arc::new(FontVec::try_from_vec(bytes).map_err(|_| FontError::Invalid)?);
+        Ok(self.update_fonts(|fonts| {
+            fonts.faces.push(font);
+            FontId(fonts.faces.len() as u32 - 1)
+        }))
+    }
+    
+    /// Add a font from a .ttf/.otf/.ttc file
+    pub fn load_font_file(&mut self, path: impl AsRef<Path>) -> Result<FontId, FontError> {
+        let bytes = std::fs::read(path.as_ref()).map_err(|e| FontError::Io(format!("{}: {}", path.as_ref().display(), e)))?;
+        self.load_font(bytes)
+    }
+    
+    /// Add an installed font by family name (e.g. "DejaVu Sans"), see
+    /// `find_font_file`
+    pub fn load_font_family(&mut self, family: &str) -> Result<FontId, FontError> {
+        let path = find_font_file(&system_font_dirs(), family).ok_or_else(|| FontError::NotFound(family.to_string()))?;
+        self.load_font_file(path)
+    }
+    
+    /// Font used by `draw_text`, `measure_text` and `line_height`
+    pub fn set_default_font(&mut self, font: FontId) {
+        if (font.0 as usize) < self.fonts.faces.len() {
+            self.update_fonts(|fonts| fonts.default_font = font);
+        } else {
+            log::warn!("set_default_font: {:?} is not loaded", font);
+        }
+    }
+    
+    pub fn default_font(&self) -> FontId {
+        self.fonts.default_font
+    }
+    
+    /// Fonts tried, in order, for characters the requested font has no
+    /// glyph for (the default font is tried first)
+    /// 
+    /// Starts as the installed CJK, emoji and symbol fonts that were found.
+    pub fn set_fallback_fonts(&mut self, fonts: Vec<FontId>) {
+        let (known, unknown): (Vec<FontId>, Vec<FontId>) = fonts.into_iter().partition(|id| (id.0 as usize) < self.fonts.faces.len());
+        if !unknown.is_empty() {
+            log::warn!("set_fallback_fonts: {:?} not loaded", unknown);
+        }
+        self.update_fonts(|fonts| fonts.fallback_fonts = known);
+    }
+    
+    pub fn fallback_fonts(&self) -> &[FontId] {
+        &self.fonts.fallback_fonts
+    }
+    
+    /// Font emoji are drawn with in preference to the requested font (which
+    /// may have monochrome ones); `None` leaves them to the fallback chain
+    pub fn set_emoji_font(&mut self, font: Option<FontId>) {
+        match font {
+            Some(id) if (id.0 as usize) >= self.fonts.faces.len() => log::warn!("set_emoji_font: {:?} is not loaded", id),
+            _ => self.update_fonts(|fonts| fonts.emoji_font = font),
+        }
+    }
+    
+    pub fn emoji_font(&self) -> Option<FontId> {
+        self.fonts.emoji_font
+    }
+    
+    /// Change the fonts and share the result with `layout_text_width`
+    fn update_fonts<R>(&mut self, update: impl FnOnce(&mut FontSet) -> R) -> R {
+        let result = update(Arc::make_mut(&mut self.fonts));
+        set_layout_fonts(&self.fonts);
+        result
+    }
+    
+    pub fn draw_text(&mut self, _device: &wgpu::Device, queue: &wgpu::Queue, text: &str, pos: [f32; 2], scale: f32, color: [f32; 4]) {
+        self.draw_text_with_font(queue, self.fonts.default_font, text, pos, scale, color);
+    }
+    
+    /// `draw_text` in a specific font
+    /// 
+    /// Right-to-left and complex-script text is reordered and shaped for
+    /// display, see `bidi` and `FontSet::shape`.
+    pub fn draw_text_with_font(&mut self, queue: &wgpu::Queue, font: FontId, text: &str, pos: [f32; 2], scale: f32, color: [f32; 4]) {
+        let font = if (font.0 as usize) < self.fonts.faces.len() { font } else { self.fonts.default_font };
         let mut x = pos[0];
         let mut y = pos[1];
-        let px_scale = PxScale::from(scale);
-        let scaled_font = self.font.as_scaled(px_scale);
+        let dpi = self.dpi_scale;
+        let px_scale = PxScale::from(scale * dpi);
+        let scaled_font = self.fonts.font(font).as_scaled(px_scale);
         
         // Round scale for cache key (multiply by 10 to preserve some precision)
-        let scale_key = (scale * 10.0) as u32;
+        let scale_key = (scale * dpi * 10.0) as u32;
         
-        let v_metrics = scaled_font.ascent();
+        let v_metrics = scaled_font.ascent() / dpi;
         y += v_metrics; 
 
-        for c in text.chars() {
-            if c.is_control() { continue; }
-            
-            let cache_key = (c, scale_key);
-            if !self.atlas.glyphs.contains_key(&cache_key) {
-                 self.rasterize_glyph(device, queue, c, px_scale, scale_key);
+        if needs_shaping(text) {
+            let fonts = self.fonts.clone();
+            for glyph in fonts.shape(font, text, scale) {
+                let pen = [x + glyph.offset[0], y + glyph.offset[1]];
+                self.queue_glyph(queue, font, glyph.font, glyph.glyph, px_scale, scale_key, pen, color);
+                x += glyph.advance;
             }
+            return;
+        }
+        for c in text.chars() {
+            if c.is_control() || is_zero_width(c) { continue; }
             
-            if let Some(info) = self.atlas.glyphs.get(&cache_key) {
-                let w = info.screen_rect[2];
-                let h = info.screen_rect[3];
-                let gx = x + info.screen_rect[0];
-                let gy = y + info.screen_rect[1]; 
-                
-                let u0 = info.uv_rect[0];
-                let v0 = info.uv_rect[1];
-                let u1 = info.uv_rect[2];
-                let v1 = info.uv_rect[3];
-                
-                // Quad
-                self.queue_buffer.push(TextVertex { position: [gx, gy], uv: [u0, v0], color }); // TL
-                self.queue_buffer.push(TextVertex { position: [gx, gy + h], uv: [u0, v1], color }); // BL
-                self.queue_buffer.push(TextVertex { position: [gx + w, gy], uv: [u1, v0], color }); // TR
-                
-                self.queue_buffer.push(TextVertex { position: [gx + w, gy], uv: [u1, v0], color }); // TR
-                self.queue_buffer.push(TextVertex { position: [gx, gy + h], uv: [u0, v1], color }); // BL
-                self.queue_buffer.push(TextVertex { position: [gx + w, gy + h], uv: [u1, v1], color }); // BR
-                
-                x += info.advance;
-            }
+            let glyph_font = self.fonts.glyph_font(font, c);
+            let glyph = self.fonts.font(glyph_font).glyph_id(c);
+            x += self.queue_glyph(queue, font, glyph_font, glyph, px_scale, scale_key, [x, y], color);
         }
     }
     
-    fn rasterize_glyph(&mut self, _device: &wgpu::Device, queue: &wgpu::Queue, c: char, scale: PxScale, scale_key: u32) {
-        let glyph = self.font.glyph_id(c).with_scale_and_position(scale, Point { x: 0.0, y: 0.0 });
-        let scaled_font = self.font.as_scaled(scale);
-        let cache_key = (c, scale_key);
+    /// Queue the quad of one glyph with its origin at `pen` (logical
+    /// pixels, on the baseline); returns its advance in logical pixels
+    #[allow(clippy::too_many_arguments)]
+    fn queue_glyph(&mut self, queue: &wgpu::Queue, font: FontId, glyph_font: FontId, glyph: GlyphId, px_scale: PxScale, scale_key: u32, pen: [f32; 2], color: [f32; 4]) -> f32 {
+        let dpi = self.dpi_scale;
+        let cache_key = (glyph_font, glyph, scale_key);
+        let info = match self.atlas.lookup(&cache_key).or_else(|| self.color_atlas.lookup(&cache_key)) {
+            Some(info) => info,
+            None => {
+                let glyph_scale = PxScale::from(self.fonts.glyph_scale(font, glyph_font, px_scale.y));
+                self.rasterize_glyph(queue, glyph_font, glyph, glyph_scale, scale_key)
+            }
+        };
+        
+        let color_glyph = info.color as u32;
+        let w = info.screen_rect[2] / dpi;
+        let h = info.screen_rect[3] / dpi;
+        let gx = pen[0] + info.screen_rect[0] / dpi;
+        let gy = pen[1] + info.screen_rect[1] / dpi; 
+        
+        let u0 = info.uv_rect[0];
+        let v0 = info.uv_rect[1];
+        let u1 = info.uv_rect[2];
+        let v1 = info.uv_rect[3];
         
-        if let Some(outlined) = self.font.outline_glyph(glyph) {
+        // Quad
+        self.queue_buffer.push(TextVertex { position: [gx, gy], uv: [u0, v0], color, color_glyph }); // TL
+        self.queue_buffer.push(TextVertex { position: [gx, gy + h], uv: [u0, v1], color, color_glyph }); // BL
+        self.queue_buffer.push(TextVertex { position: [gx + w, gy], uv: [u1, v0], color, color_glyph }); // TR
+        
+        self.queue_buffer.push(TextVertex { position: [gx + w, gy], uv: [u1, v0], color, color_glyph }); // TR
+        self.queue_buffer.push(TextVertex { position: [gx, gy + h], uv: [u0, v1], color, color_glyph }); // BL
+        self.queue_buffer.push(TextVertex { position: [gx + w, gy + h], uv: [u1, v1], color, color_glyph }); // BR
+        self.queue_groups.push(TextGroup { font: glyph_font, scale_key, page: info.page });
+        
+        info.advance / dpi
+    }
+    
+    /// Rasterize a glyph into the atlas and cache it; a glyph that doesn't
+    /// fit comes back without a bitmap (it still advances the pen)
+    fn rasterize_glyph(&mut self, queue: &wgpu::Queue, font_id: FontId, glyph_id: GlyphId, scale: PxScale, scale_key: u32) -> GlyphInfo {
+        self.atlas.stats.misses += 1;
+        let font = &self.fonts.faces[font_id.0 as usize];
+        let advance = font.as_scaled(scale).h_advance(glyph_id);
+        let cache_key = (font_id, glyph_id, scale_key);
+        let mut info = GlyphInfo { advance, ..Default::default() };
+        
+        let pixels = &mut self.glyph_pixels;
+        pixels.clear();
+        // Only the emoji font is checked for layers, to keep other misses cheap
+        let layered = Some(font_id) == self.fonts.emoji_font;
+        if let Some((w, h, offset)) = color_glyph(font, glyph_id, scale, pixels)
+            .or_else(|| layered.then(|| layered_glyph(font, glyph_id, scale, pixels)).flatten()) {
+            info.screen_rect = [offset.x, offset.y, w as f32, h as f32];
+            info.color = true;
+        } else if let Some(outlined) = font.outline_glyph(glyph_id.with_scale_and_position(scale, Point { x: 0.0, y: 0.0 })) {
             let bounds = outlined.px_bounds();
             let w = bounds.width() as u32;
             let h = bounds.height() as u32;
-            
-            if w > 0 && h > 0 {
-                // Atlas fit check simpl
-                if self.atlas.cursor.0 + w >= self.atlas.size {
-                    self.atlas.cursor.0 = 0;
-                    self.atlas.cursor.1 += self.atlas.row_height + 2; 
-                    self.atlas.row_height = 0;
-                }
-                
-                if self.atlas.cursor.1 + h < self.atlas.size {
-                    let mut pixels = vec![0u8; (w * h) as usize];
-                    outlined.draw(|x, y, v| {
-                       let idx = (y * w + x) as usize;
-                       if idx < pixels.len() {
-                           pixels[idx] = (v * 255.0) as u8;
-                       }
-                    });
-                    
-                    queue.write_texture(
-                        wgpu::ImageCopyTexture {
-                            texture: &self.atlas_texture,
-                            mip_level: 0,
-                            origin: wgpu::Origin3d { x: self.atlas.cursor.0, y: self.atlas.cursor.1, z: 0 },
-                            aspect: wgpu::TextureAspect::All,
-                        },
-                        &pixels,
-                        wgpu::ImageDataLayout {
-                            offset: 0,
-                            bytes_per_row: Some(w),
-                            rows_per_image: Some(h),
-                        },
-                        wgpu::Extent3d { width: w, height: h, depth_or_array_layers: 1 },
-                    );
-                    
-                    let u0 = self.atlas.cursor.0 as f32 / self.atlas.size as f32;
-                    let v0 = self.atlas.cursor.1 as f32 / self.atlas.size as f32;
-                    let u1 = (self.atlas.cursor.0 + w) as f32 / self.atlas.size as f32;
-                    let v1 = (self.atlas.cursor.1 + h) as f32 / self.atlas.size as f32;
-                    
-                    self.atlas.glyphs.insert(cache_key, GlyphInfo {
-                        uv_rect: [u0, v0, u1, v1],
-                        screen_rect: [bounds.min.x, bounds.min.y, w as f32, h as f32],
-                        advance: scaled_font.h_advance(self.font.glyph_id(c)),
-                    });
-                    
-                    self.atlas.cursor.0 += w + 2;
-                    self.atlas.row_height = self.atlas.row_height.max(h);
-                }
-            }
-        } else {
-             self.atlas.glyphs.insert(cache_key, GlyphInfo {
-                uv_rect: [0.0; 4],
-                screen_rect: [0.0; 4],
-                advance: scaled_font.h_advance(self.font.glyph_id(c)),
+            pixels.resize((w * h) as usize, 0);
+            outlined.draw(|x, y, v| {
+               let idx = (y * w + x) as usize;
+               if idx < pixels.len() {
+                   pixels[idx] = (v * 255.0) as u8;
+               }
             });
+            info.screen_rect = [bounds.min.x, bounds.min.y, w as f32, h as f32];
+        }
+        
+        let (w, h) = (info.screen_rect[2] as u32, info.screen_rect[3] as u32);
+        if w == 0 || h == 0 {
+            info.screen_rect = [0.0; 4];
+            self.atlas.insert(cache_key, info);
+            return info;
+        }
+        let (atlas, texture) = if info.color {
+            (&mut self.color_atlas, &self.color_atlas_texture)
+        } else {
+            (&mut self.atlas, &self.atlas_texture)
+        };
+        let Some(slot) = atlas.allocate(w, h) else {
+            // Not cached: tried again next time
+            return GlyphInfo { advance, ..Default::default() };
+        };
+        
+        if slot.evicted {
+            // Clear the old glyphs' pixels so they can't bleed into new neighbours
+            let page_height = atlas.page_height();
+            let len = (atlas.size * page_height * texel_size(texture)) as usize;
+            if self.page_zeros.len() < len {
+                self.page_zeros.resize(len, 0);
+            }
+            write_atlas(queue, texture, &self.page_zeros[..len], 0, slot.page * page_height, atlas.size, page_height);
         }
+        write_atlas(queue, texture, &self.glyph_pixels, slot.x, slot.y, w, h);
+        
+        let size = atlas.size as f32;
+        info.uv_rect = [slot.x as f32 / size, slot.y as f32 / size, (slot.x + w) as f32 / size, (slot.y + h) as f32 / size];
+        info.page = Some(slot.page);
+        atlas.insert(cache_key, info);
+        info
     }
     
-    pub fn prepare(&mut self, queue: &wgpu::Queue) {
-        if !self.queue_buffer.is_empty() {
-             queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&self.queue_buffer));
+    /// Put the quads in `range` (of vertices) drawn from the same font,
+    /// size and atlas page next to each other
+    /// 
+    /// The sort is stable, so quads with the same group keep their order
+    /// and shadows and outlines stay under their text.
+    pub fn group_range(&mut self, range: Range<u32>) {
+        let (start, end) = (range.start as usize, range.end as usize);
+        if start % 6 != 0 || end > self.queue_buffer.len() || self.queue_groups.len() * 6 != self.queue_buffer.len() {
+            return;
         }
+        let quads = start / 6..end / 6;
+        group_quads(&mut self.queue_buffer[start..end], &mut self.queue_groups[quads], &mut self.group_scratch);
     }
     
-    pub fn render_range<'a>(&'a self, rpass: &mut wgpu::RenderPass<'a>, bg_bind_group: &'a wgpu::BindGroup, range: std::ops::Range<u32>) {
+    /// Upload the queued vertices
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.vertex_buffer.write(device, queue, bytemuck::cast_slice(&self.queue_buffer));
+    }
+    
+    /// Bind and draw `range` in one go; `clip_bind_group` is bound with no clip
+    pub fn render_range<'a>(&'a self, rpass: &mut wgpu::RenderPass<'a>, bg_bind_group: &'a wgpu::BindGroup, clip_bind_group: &'a wgpu::BindGroup, range: std::ops::Range<u32>) {
         if self.queue_buffer.is_empty() || range.start >= range.end { return; }
         
+        self.bind(rpass, bg_bind_group);
+        rpass.set_bind_group(2, clip_bind_group, &[0]);
+        self.draw_range(rpass, range);
+    }
+    
+    /// Bind the text pipeline, atlas and vertex buffer
+    /// 
+    /// All queued text shares one pipeline and atlas, so callers drawing
+    /// several ranges in a row only need to bind once (see `draw_range`).
+    /// The clip uniform (group 2) is bound by the caller per batch.
+    pub fn bind<'a>(&'a self, rpass: &mut wgpu::RenderPass<'a>, bg_bind_group: &'a wgpu::BindGroup) {
+        if self.queue_buffer.is_empty() { return; }
+        
         rpass.set_pipeline(&self.pipeline);
         rpass.set_bind_group(0, bg_bind_group, &[]);
         rpass.set_bind_group(1, &self.atlas_bind_group, &[]);
-        rpass.set_vertex_buffer(0, self.vertex_buffer.slice(0..(self.queue_buffer.len() * std::mem::size_of::<TextVertex>()) as u64));
-        rpass.draw(range, 0..1);
+        rpass.set_vertex_buffer(0, self.vertex_buffer.slice());
+    }
+    
+    /// Draw queued vertices; requires a prior `bind` in the same pass
+    pub fn draw_range<'a>(&'a self, rpass: &mut wgpu::RenderPass<'a>, range: std::ops::Range<u32>) {
+        if range.start >= range.end { return; }
+        self.vertex_buffer.draw_range(rpass, 0, range, |pass, range| pass.draw(range, 0..1));
     }
     
+    /// Drop the queued text and start a new frame of glyph cache use
     pub fn clear(&mut self) {
         self.queue_buffer.clear();
+        self.queue_groups.clear();
+        self.atlas.next_frame();
+        self.color_atlas.next_frame();
+    }
+    
+    /// Glyph cache activity since the last `clear` (both atlases)
+    pub fn glyph_cache_stats(&self) -> GlyphCacheStats {
+        let (glyphs, color) = (self.atlas.stats, self.color_atlas.stats);
+        GlyphCacheStats {
+            cached_glyphs: glyphs.cached_glyphs + color.cached_glyphs,
+            hits: glyphs.hits + color.hits,
+            misses: glyphs.misses + color.misses,
+            evictions: glyphs.evictions + color.evictions,
+            dropped: glyphs.dropped + color.dropped,
+        }
     }
     
     /// Measure text dimensions without rendering
     /// Returns (width, height) in pixels
     pub fn measure_text(&self, text: &str, scale: f32) -> TextMetrics {
-        let px_scale = PxScale::from(scale);
-        let scaled_font = self.font.as_scaled(px_scale);
-        
-        let mut width = 0.0f32;
-        let height = scaled_font.ascent() - scaled_font.descent();
-        
-        for c in text.chars() {
-            if c.is_control() { continue; }
-            let glyph_id = self.font.glyph_id(c);
-            width += scaled_font.h_advance(glyph_id);
-        }
-        
-        TextMetrics {
-            width,
-            height,
-            ascent: scaled_font.ascent(),
-            descent: scaled_font.descent(),
-            line_height: height * 1.2, // Standard 120% line height
-        }
+        self.measure_text_with_font(self.fonts.default_font, text, scale)
+    }
+    
+    /// `measure_text` in a specific font
+    pub fn measure_text_with_font(&self, font: FontId, text: &str, scale: f32) -> TextMetrics {
+        TextMetrics { width: self.fonts.text_width(font, text, scale), ..measure_text_with(self.fonts.font(font), "", scale) }
+    }
+    
+    /// Break `text` into lines no wider than `max_width` (see
+    /// `layout_text_with`)
+    pub fn layout_text(&self, font: FontId, text: &str, scale: f32, max_width: Option<f32>) -> TextLayout {
+        let line_height = measure_text_with(self.fonts.font(font), "", scale).line_height;
+        layout_text_with(text, max_width, line_height, |run| self.fonts.text_width(font, run, scale))
+    }
+    
+    /// `text` cut short with "…" to fit `max_width` (see `ellipsize`)
+    pub fn ellipsize<'a>(&self, font: FontId, text: &'a str, scale: f32, max_width: f32) -> Cow<'a, str> {
+        ellipsize(text, max_width, |run| self.fonts.text_width(font, run, scale))
+    }
+    
+    /// x offset from the text's origin of the caret before byte `index`
+    /// (see `caret_stops`)
+    pub fn index_to_position(&self, font: FontId, text: &str, scale: f32, index: usize) -> f32 {
+        index_to_x(text, index, |run| self.fonts.text_width(font, run, scale))
+    }
+    
+    /// Byte index of the caret position nearest to `x` (relative to the
+    /// text's origin), e.g. for a click
+    pub fn position_to_index(&self, font: FontId, text: &str, scale: f32, x: f32) -> usize {
+        x_to_index(text, x, |run| self.fonts.text_width(font, run, scale))
+    }
+    
+    /// Highlight rects (relative to the text's origin, one glyph box tall)
+    /// covering the bytes in `range`; right-to-left runs can split a
+    /// selection into several
+    pub fn selection_rects(&self, font: FontId, text: &str, scale: f32, range: Range<usize>) -> Vec<Rect> {
+        let height = measure_text_with(self.fonts.font(font), "", scale).height;
+        selection_spans(text, range, |run| self.fonts.text_width(font, run, scale)).into_iter()
+            .map(|span| Rect::new(glam::Vec2::new(span.start, 0.0), glam::Vec2::new(span.end - span.start, height)))
+            .collect()
     }
     
     /// Get line height for given font scale
     pub fn line_height(&self, scale: f32) -> f32 {
         let px_scale = PxScale::from(scale);
-        let scaled_font = self.font.as_scaled(px_scale);
+        let scaled_font = self.fonts.font(self.fonts.default_font).as_scaled(px_scale);
         (scaled_font.ascent() - scaled_font.descent()) * 1.2
     }
 }
 
+/// Bytes per texel of an atlas texture
+fn texel_size(texture: &wgpu::Texture) -> u32 {
+    texture.format().block_copy_size(None).unwrap_or(4)
+}
+
+/// Copy a `w`x`h` bitmap (in the texture's format) into an atlas at (x, y)
+fn write_atlas(queue: &wgpu::Queue, texture: &wgpu::Texture, pixels: &[u8], x: u32, y: u32, w: u32, h: u32) {
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x, y, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        },
+        pixels,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(w * texel_size(texture)),
+            rows_per_image: Some(h),
+        },
+        wgpu::Extent3d { width: w, height: h, depth_or_array_layers: 1 },
+    );
+}
+
+// =============================================================================
+// COLOR EMOJI
+// =============================================================================
+
+/// Characters drawn from the emoji font even when the requested font has
+/// a (monochrome) glyph: the pictograph and emoticon blocks
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF)
+}
+
+/// Font to draw `c` with: the emoji font for emoji, else the first of
+/// `requested`, `default` and `fallbacks` with a glyph for it (`requested`
+/// if none has, to show its missing-glyph box)
+fn pick_font(c: char, requested: FontId, default: FontId, emoji: Option<FontId>, fallbacks: &[FontId], has_glyph: impl Fn(FontId, char) -> bool) -> FontId {
+    let emoji = emoji.filter(|_| is_emoji(c));
+    emoji.into_iter()
+        .chain([requested, default])
+        .chain(fallbacks.iter().copied())
+        .find(|&id| has_glyph(id, c))
+        .unwrap_or(requested)
+}
+
+/// Variation selectors and joiners: no glyph of their own (emoji ZWJ
+/// sequences draw as their separate parts)
+fn is_zero_width(c: char) -> bool {
+    matches!(c, '\u{200D}' | '\u{FE0E}' | '\u{FE0F}')
+}
+
+/// Combining mark, drawn over the character before it
+fn is_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}' | '\u{0483}'..='\u{0489}' | '\u{0591}'..='\u{05C7}' | '\u{064B}'..='\u{065F}'
+        | '\u{0670}' | '\u{06D6}'..='\u{06ED}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}')
+}
+
+/// Text that has to go through `FontSet::shape`: right-to-left, combining
+/// marks, or a script whose letters change shape (Indic, Thai, Lao)
+fn needs_shaping(text: &str) -> bool {
+    bidi::has_rtl(text) || text.chars().any(|c| is_mark(c) || matches!(c, '\u{0900}'..='\u{0DFF}' | '\u{0E00}'..='\u{0EFF}'))
+}
+
+/// Decode `glyph`'s color bitmap (CBDT/sbix) into `out` as straight RGBA,
+/// scaled to `scale`; returns its size and top-left relative to the
+/// baseline origin
+fn color_glyph(font: &FontVec, glyph: GlyphId, scale: PxScale, out: &mut Vec<u8>) -> Option<(u32, u32, Point)> {
+    let em = scale.y * font.units_per_em()? / font.height_unscaled().max(1.0);
+    let image = font.glyph_raster_image2(glyph, em.round().clamp(1.0, u16::MAX as f32) as u16)?;
+    let (src_w, src_h, premultiplied) = match image.format {
+        GlyphImageFormat::Png => {
+            let (w, h, mut rgba) = decode_png(image.data)?;
+            premultiply(&mut rgba);
+            (w, h, rgba)
+        }
+        GlyphImageFormat::BitmapPremulBgra32 => {
+            let bgra = image.data.chunks_exact(4).flat_map(|p| [p[2], p[1], p[0], p[3]]).collect();
+            (image.width as u32, image.height as u32, bgra)
+        }
+        // Monochrome strikes: the outline looks better
+        _ => return None,
+    };
+    if premultiplied.len() != (src_w * src_h * 4) as usize || src_w == 0 || src_h == 0 {
+        return None;
+    }
+    
+    // Strikes come in a few fixed sizes: resample to the requested one
+    let factor = em / image.pixels_per_em.max(1) as f32;
+    let w = ((src_w as f32 * factor).round() as u32).max(1);
+    let h = ((src_h as f32 * factor).round() as u32).max(1);
+    resample_premultiplied(&premultiplied, src_w, src_h, w, h, out);
+    // Image origin is its bottom-left, y up
+    let offset = Point { x: image.origin.x * factor, y: -(image.origin.y + src_h as f32) * factor };
+    Some((w, h, offset))
+}
+
+/// Draw `glyph`'s COLR layers into `out` as straight RGBA, like
+/// `color_glyph`
+fn layered_glyph(font: &FontVec, glyph: GlyphId, scale: PxScale, out: &mut Vec<u8>) -> Option<(u32, u32, Point)> {
+    // Fonts are loaded from the first face of a collection
+    let face = ttf_parser::Face::parse(font.as_slice(), 0).ok()?;
+    let mut layers = ColorLayers::default();
+    face.paint_color_glyph(ttf_parser::GlyphId(glyph.0), 0, FOREGROUND, &mut layers)?;
+    let outlines: Vec<_> = layers.layers.iter()
+        .filter_map(|&(id, color)| Some((font.outline_glyph(id.with_scale_and_position(scale, Point { x: 0.0, y: 0.0 }))?, color)))
+        .collect();
+    let bounds = outlines.iter().map(|(outlined, _)| outlined.px_bounds()).reduce(|a, b| ab_glyph::Rect {
+        min: Point { x: a.min.x.min(b.min.x), y: a.min.y.min(b.min.y) },
+        max: Point { x: a.max.x.max(b.max.x), y: a.max.y.max(b.max.y) },
+    })?;
+    let (w, h) = (bounds.width() as u32, bounds.height() as u32);
+    
+    // Composite the layers bottom-up in premultiplied alpha
+    let mut premultiplied = vec![[0.0f32; 4]; (w * h) as usize];
+    for (outlined, color) in &outlines {
+        let layer = outlined.px_bounds();
+        let (dx, dy) = ((layer.min.x - bounds.min.x) as u32, (layer.min.y - bounds.min.y) as u32);
+        let alpha = color[3] as f32 / 255.0;
+        outlined.draw(|x, y, v| {
+            let (x, y) = (x + dx, y + dy);
+            if x < w && y < h {
+                let pixel = &mut premultiplied[(y * w + x) as usize];
+                let a = v * alpha;
+                for (channel, &c) in pixel.iter_mut().zip(&color[..3]) {
+                    *channel = c as f32 / 255.0 * a + *channel * (1.0 - a);
+                }
+                pixel[3] = a + pixel[3] * (1.0 - a);
+            }
+        });
+    }
+    out.clear();
+    out.extend(premultiplied.iter().flat_map(|&[r, g, b, a]| {
+        let straight = |c: f32| if a > 0.0 { (c / a * 255.0).round().min(255.0) as u8 } else { 0 };
+        [straight(r), straight(g), straight(b), (a * 255.0).round() as u8]
+    }));
+    Some((w, h, bounds.min))
+}
+
+/// Color of COLR layers drawn "in the text color": cached glyphs don't
+/// know it, so they're drawn black
+const FOREGROUND: ttf_parser::RgbaColor = ttf_parser::RgbaColor { red: 0, green: 0, blue: 0, alpha: 255 };
+
+/// Collects the layers of a COLR glyph as (outline glyph, RGBA) pairs,
+/// bottom first
+/// 
+/// Each paint fills the innermost glyph clip (or the last outline), in
+/// one flat color.
+#[derive(Default)]
+struct ColorLayers {
+    layers: Vec<(GlyphId, [u8; 4])>,
+    /// Last outlined glyph
+    outline: Option<GlyphId>,
+    /// Pushed clips; None for clip boxes, which don't change the shape
+    clips: Vec<Option<GlyphId>>,
+}
+
+impl<'a> ttf_parser::colr::Painter<'a> for ColorLayers {
+    fn outline_glyph(&mut self, glyph: ttf_parser::GlyphId) {
+        self.outline = Some(GlyphId(glyph.0));
+    }
+    
+    fn paint(&mut self, paint: ttf_parser::colr::Paint<'a>) {
+        use ttf_parser::colr::Paint;
+        let color = match paint {
+            Paint::Solid(color) => color,
+            Paint::LinearGradient(gradient) => average_color(gradient.stops(0, &[])),
+            Paint::RadialGradient(gradient) => average_color(gradient.stops(0, &[])),
+            Paint::SweepGradient(gradient) => average_color(gradient.stops(0, &[])),
+        };
+        let shape = self.clips.iter().rev().find_map(|clip| *clip).or(self.outline);
+        if let Some(glyph) = shape {
+            self.layers.push((glyph, [color.red, color.green, color.blue, color.alpha]));
+        }
+    }
+    
+    fn push_clip(&mut self) {
+        self.clips.push(self.outline);
+    }
+    
+    fn push_clip_box(&mut self, _clipbox: ttf_parser::colr::ClipBox) {
+        self.clips.push(None);
+    }
+    
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+    
+    fn push_layer(&mut self, _mode: ttf_parser::colr::CompositeMode) {}
+    fn pop_layer(&mut self) {}
+    fn push_transform(&mut self, _transform: ttf_parser::Transform) {}
+    fn pop_transform(&mut self) {}
+}
+
+/// Average of a gradient's stop colors, which layers are drawn in
+fn average_color(stops: impl Iterator<Item = ttf_parser::colr::ColorStop>) -> ttf_parser::RgbaColor {
+    let (mut sum, mut count) = ([0u32; 4], 0);
+    for stop in stops {
+        let c = stop.color;
+        for (s, v) in sum.iter_mut().zip([c.red, c.green, c.blue, c.alpha]) {
+            *s += v as u32;
+        }
+        count += 1;
+    }
+    let [red, green, blue, alpha] = sum.map(|s| (s / count.max(1)) as u8);
+    ttf_parser::RgbaColor { red, green, blue, alpha }
+}
+
+/// Stable-sort the glyph quads (6 vertices each) of `vertices` by their
+/// `groups`, through `scratch`
+fn group_quads(vertices: &mut [TextVertex], groups: &mut [TextGroup], scratch: &mut Vec<(TextGroup, [TextVertex; 6])>) {
+    if groups.windows(2).all(|pair| pair[0] <= pair[1]) {
+        return;
+    }
+    scratch.clear();
+    scratch.extend(groups.iter().zip(vertices.chunks_exact(6)).map(|(group, quad)| {
+        (*group, [quad[0], quad[1], quad[2], quad[3], quad[4], quad[5]])
+    }));
+    scratch.sort_by_key(|(group, _)| *group);
+    for ((group, quad), (slot, out)) in scratch.iter().zip(groups.iter_mut().zip(vertices.chunks_exact_mut(6))) {
+        *slot = *group;
+        out.copy_from_slice(quad);
+    }
+}
+
+/// Decode a PNG to RGBA8: (width, height, straight-alpha pixels)
+fn decode_png(data: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()?];
+    let info = reader.next_frame(&mut buf).ok()?;
+    buf.truncate(info.buffer_size());
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buf.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => return None,
+    };
+    Some((info.width, info.height, rgba))
+}
+
+fn premultiply(rgba: &mut [u8]) {
+    for p in rgba.chunks_exact_mut(4) {
+        let alpha = p[3] as u32;
+        for channel in &mut p[..3] {
+            *channel = (*channel as u32 * alpha / 255) as u8;
+        }
+    }
+}
+
+/// Box-filter premultiplied RGBA from `src_w`x`src_h` to `w`x`h` into
+/// `out` as straight alpha (each output pixel averages the source pixels
+/// whose centers it covers, or the nearest one when enlarging)
+fn resample_premultiplied(src: &[u8], src_w: u32, src_h: u32, w: u32, h: u32, out: &mut Vec<u8>) {
+    out.clear();
+    out.reserve((w * h * 4) as usize);
+    let (sx, sy) = (src_w as f32 / w as f32, src_h as f32 / h as f32);
+    let span = |i: u32, step: f32, max: u32| {
+        let start = ((i as f32 * step) as u32).min(max - 1);
+        let end = (((i + 1) as f32 * step).round() as u32).clamp(start + 1, max);
+        start..end
+    };
+    for y in 0..h {
+        let rows = span(y, sy, src_h);
+        for x in 0..w {
+            let cols = span(x, sx, src_w);
+            let mut sum = [0u32; 4];
+            for row in rows.clone() {
+                for col in cols.clone() {
+                    let i = ((row * src_w + col) * 4) as usize;
+                    for (s, &v) in sum.iter_mut().zip(&src[i..i + 4]) {
+                        *s += v as u32;
+                    }
+                }
+            }
+            let count = (rows.len() * cols.len()) as u32;
+            let unpremultiply = |v: u32| (v * 255).checked_div(sum[3]).map_or(0, |v| v.min(255) as u8);
+            out.extend([unpremultiply(sum[0]), unpremultiply(sum[1]), unpremultiply(sum[2]), (sum[3] / count) as u8]);
+        }
+    }
+}
+
+// =============================================================================
+// SYSTEM FONTS
+// =============================================================================
+
+/// Load the first of `paths` that is a readable font, logging which (`what`
+/// names the kind of font for the log)
+fn load_first_font(what: &str, paths: &[&str]) -> Option<FontVec> {
+    let font = paths.iter().find_map(|path| {
+        let font = FontVec::try_from_vec(std::fs::read(path).ok()?).ok()?;
+        log::info!("Loaded {} font from: {}", what, path);
+        Some(font)
+    });
+    if font.is_none() {
+        log::info!("No {} font found", what);
+    }
+    font
+}
+
+/// Directories fonts are installed in on Windows, macOS and Linux
+pub fn system_font_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = [
+        "C:/Windows/Fonts",
+        "/System/Library/Fonts",
+        "/Library/Fonts",
+        "/usr/share/fonts",
+        "/usr/local/share/fonts",
+    ].iter().map(PathBuf::from).collect();
+    if let Some(home) = std::env::var_os("HOME") {
+        let home = PathBuf::from(home);
+        dirs.extend([home.join("Library/Fonts"), home.join(".local/share/fonts"), home.join(".fonts")]);
+    }
+    if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local).join("Microsoft/Windows/Fonts"));
+    }
+    dirs
+}
+
+/// Search `dirs` (and their subdirectories) for a font file named after
+/// `family`
+/// 
+/// Matching ignores case, spaces and punctuation, and accepts a
+/// "Regular" suffix: "DejaVu Sans" finds `DejaVuSans.ttf`, "Liberation
+/// Sans" finds `LiberationSans-Regular.ttf`. Font files aren't parsed, so
+/// families whose files are named differently aren't found.
+pub fn find_font_file(dirs: &[PathBuf], family: &str) -> Option<PathBuf> {
+    let normalize = |s: &str| s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>();
+    let wanted = normalize(family);
+    if wanted.is_empty() {
+        return None;
+    }
+    let regular = format!("{}regular", wanted);
+    
+    let mut pending: Vec<(PathBuf, u32)> = dirs.iter().map(|d| (d.clone(), 0)).collect();
+    while let Some((dir, depth)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if depth < 4 {
+                    pending.push((path, depth + 1));
+                }
+                continue;
+            }
+            let is_font = path.extension().and_then(|e| e.to_str())
+                .is_some_and(|e| ["ttf", "otf", "ttc"].contains(&e.to_ascii_lowercase().as_str()));
+            let stem = path.file_stem().and_then(|s| s.to_str()).map(normalize);
+            if is_font && stem.is_some_and(|s| s == wanted || s == regular) {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+/// Measure text with a given font (no GPU resources needed)
+pub fn measure_text_with(font: &FontVec, text: &str, scale: f32) -> TextMetrics {
+    let px_scale = PxScale::from(scale);
+    let scaled_font = font.as_scaled(px_scale);
+    
+    let mut width = 0.0f32;
+    let height = scaled_font.ascent() - scaled_font.descent();
+    
+    for c in text.chars() {
+        if c.is_control() { continue; }
+        let glyph_id = font.glyph_id(c);
+        width += scaled_font.h_advance(glyph_id);
+    }
+    
+    TextMetrics {
+        width,
+        height,
+        ascent: scaled_font.ascent(),
+        descent: scaled_font.descent(),
+        line_height: height * 1.2, // Standard 120% line height
+    }
+}
+
 /// Text measurement results
 #[derive(Clone, Copy, Debug, Default)]
 pub struct TextMetrics {
@@ -364,3 +1320,552 @@ impl TextMetrics {
         (self.width, self.height)
     }
 }
+
+// =============================================================================
+// TEXT LAYOUT
+// =============================================================================
+
+/// One line of a `TextLayout`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextLine {
+    /// Byte range of the line in the laid out text (trailing spaces and
+    /// the newline excluded)
+    pub range: Range<usize>,
+    /// Measured width in pixels
+    pub width: f32,
+    /// Offset of the line's top from the top of the text
+    pub y: f32,
+}
+
+/// Text broken into lines, see `layout_text_with`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextLayout {
+    pub lines: Vec<TextLine>,
+    /// Widest line
+    pub width: f32,
+    /// `lines.len() * line_height`
+    pub height: f32,
+    pub line_height: f32,
+}
+
+impl TextLayout {
+    /// Text of each line, in order
+    pub fn line_texts<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.lines.iter().map(move |line| &text[line.range.clone()])
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+}
+
+thread_local! {
+    /// Fonts of the `TextRenderer` last created or changed on this thread
+    static LAYOUT_FONTS: RefCell<Option<Arc<FontSet>>> = const { RefCell::new(None) };
+}
+
+fn set_layout_fonts(fonts: &Arc<FontSet>) {
+    LAYOUT_FONTS.with(|layout| *layout.borrow_mut() = Some(fonts.clone()));
+}
+
+/// Width of `text` in the renderer's default font, measured the way it is
+/// drawn, for widgets sizing text during layout
+/// 
+/// Falls back to `approximate_text_width` before a renderer exists.
+pub fn layout_text_width(text: &str, scale: f32) -> f32 {
+    LAYOUT_FONTS.with(|layout| match &*layout.borrow() {
+        Some(fonts) => fonts.text_width(fonts.default_font, text, scale),
+        None => approximate_text_width(text, scale),
+    })
+}
+
+/// Width of `text` assuming every character is half the font size wide,
+/// for widgets sizing text during layout (no font available)
+pub fn approximate_text_width(text: &str, scale: f32) -> f32 {
+    text.chars().filter(|c| !c.is_control()).count() as f32 * scale * 0.5
+}
+
+/// Break `text` into lines no wider than `max_width` (`None` = only at
+/// explicit newlines), measuring runs with `measure`
+/// 
+/// Lines break at `\n` and, when wrapping, between words. A word wider
+/// than a whole line is split between characters. Spaces at a wrap point
+/// are dropped; leading spaces of a paragraph are kept.
+pub fn layout_text_with(text: &str, max_width: Option<f32>, line_height: f32, measure: impl Fn(&str) -> f32) -> TextLayout {
+    let max_width = max_width.filter(|w| w.is_finite());
+    let fits = |run: &str| max_width.is_none_or(|max| measure(run) <= max);
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    
+    let mut para_start = 0;
+    for para in text.split('\n') {
+        let para_end = para_start + para.trim_end_matches('\r').len();
+        // Start of the current line (None until it has a word) and its end
+        let mut line: Option<usize> = None;
+        let mut line_end = para_start;
+        let mut first = true;
+        
+        for word in word_ranges(&text[para_start..para_end], para_start) {
+            let mut start = if first { para_start } else { word.start };
+            first = false;
+            if let Some(line_start) = line {
+                if fits(&text[line_start..word.end]) {
+                    line_end = word.end;
+                    continue;
+                }
+                ranges.push(line_start..line_end);
+            }
+            
+            // Split words wider than a whole line
+            while !fits(&text[start..word.end]) {
+                let split = text[start..word.end].char_indices().skip(1)
+                    .map(|(i, _)| start + i)
+                    .take_while(|&i| fits(&text[start..i]))
+                    .last()
+                    .unwrap_or_else(|| start + text[start..].chars().next().map_or(0, char::len_utf8));
+                if split >= word.end {
+                    break;
+                }
+                ranges.push(start..split);
+                start = split;
+            }
+            line = Some(start);
+            line_end = word.end;
+        }
+        ranges.push(line.unwrap_or(para_start)..line_end);
+        para_start += para.len() + 1;
+    }
+    
+    let lines: Vec<TextLine> = ranges.into_iter().enumerate().map(|(i, range)| TextLine {
+        width: measure(&text[range.clone()]),
+        y: i as f32 * line_height,
+        range,
+    }).collect();
+    TextLayout {
+        width: lines.iter().map(|l| l.width).fold(0.0, f32::max),
+        height: lines.len() as f32 * line_height,
+        line_height,
+        lines,
+    }
+}
+
+/// Byte ranges (offset by `offset`) of the whitespace-separated words in `text`
+fn word_ranges(text: &str, offset: usize) -> impl Iterator<Item = Range<usize>> + '_ {
+    text.split_whitespace().map(move |word| {
+        let start = word.as_ptr() as usize - text.as_ptr() as usize + offset;
+        start..start + word.len()
+    })
+}
+
+/// What a single line of text does when it is wider than its box
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextOverflow {
+    /// Draw past the edge
+    #[default]
+    Visible,
+    /// Cut off at the edge
+    Clip,
+    /// Shorten to fit and end with "…"
+    Ellipsis,
+    /// Wrap between words onto more lines
+    Wrap,
+}
+
+/// `text` shortened to end in "…" so it measures at most `max_width`;
+/// borrowed unchanged when it already fits
+pub fn ellipsize<'a>(text: &'a str, max_width: f32, measure: impl Fn(&str) -> f32) -> Cow<'a, str> {
+    if measure(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let mut budget = max_width - measure("…");
+    if budget < 0.0 {
+        return Cow::Borrowed("");
+    }
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        budget -= measure(&text[i..i + c.len_utf8()]);
+        if budget < 0.0 {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+    Cow::Owned(format!("{}…", text[..end].trim_end()))
+}
+
+// =============================================================================
+// OUTLINES
+// =============================================================================
+
+/// Offsets to stamp a glyph run at to stroke it `width` pixels wide: a ring
+/// of copies no more than ~1.5px apart (at least 8)
+pub fn outline_offsets(width: f32) -> Vec<[f32; 2]> {
+    if width <= 0.0 {
+        return Vec::new();
+    }
+    let count = ((std::f32::consts::TAU * width / 1.5).ceil() as usize).max(8);
+    (0..count)
+        .map(|i| {
+            let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+            [angle.cos() * width, angle.sin() * width]
+        })
+        .collect()
+}
+
+// =============================================================================
+// HIT TESTING
+// =============================================================================
+
+/// Caret positions of one line: (byte index, x) for every character
+/// boundary in logical order, with right-to-left runs reordered as drawn
+pub fn caret_stops(text: &str, measure: impl Fn(&str) -> f32) -> Vec<(usize, f32)> {
+    let boundaries = text.char_indices().map(|(i, _)| i).chain(std::iter::once(text.len()));
+    if !bidi::has_rtl(text) {
+        let mut x = 0.0;
+        let mut last = 0;
+        return boundaries.map(|i| {
+            x += measure(&text[last..i]);
+            last = i;
+            (i, x)
+        }).collect();
+    }
+    bidi::caret_positions(text, measure)
+}
+
+/// x of the caret before byte `index` (clamped to the text)
+pub fn index_to_x(text: &str, index: usize, measure: impl Fn(&str) -> f32) -> f32 {
+    let stops = caret_stops(text, measure);
+    stops.iter().rev().find(|&&(i, _)| i <= index).map_or(0.0, |&(_, x)| x)
+}
+
+/// Byte index of the caret stop nearest to `x`
+pub fn x_to_index(text: &str, x: f32, measure: impl Fn(&str) -> f32) -> usize {
+    caret_stops(text, measure).into_iter()
+        .min_by(|a, b| (a.1 - x).abs().total_cmp(&(b.1 - x).abs()))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Horizontal extents of the parts of each bidi run inside `range` (on
+/// character boundaries), left to right, touching extents merged
+pub fn selection_spans(text: &str, range: Range<usize>, measure: impl Fn(&str) -> f32) -> Vec<Range<f32>> {
+    let mut spans: Vec<Range<f32>> = Vec::new();
+    if range.is_empty() {
+        return spans;
+    }
+    let mut x = 0.0;
+    for run in bidi::visual_runs(text) {
+        let width = measure(&text[run.range.clone()]);
+        let (start, end) = (range.start.max(run.range.start), range.end.min(run.range.end));
+        if start < end {
+            // Measured from the run's logical start, which is its right edge
+            // when it's right-to-left
+            let (left, right) = if run.rtl {
+                (x + measure(&text[end..run.range.end]), x + measure(&text[start..run.range.end]))
+            } else {
+                (x + measure(&text[run.range.start..start]), x + measure(&text[run.range.start..end]))
+            };
+            match spans.last_mut() {
+                Some(last) if last.end == left => last.end = right,
+                _ => spans.push(left..right),
+            }
+        }
+        x += width;
+    }
+    spans
+}
+
+// =============================================================================
+// LABEL CACHE
+// =============================================================================
+
+/// Text of a label formatted from a value, formatted again only when the
+/// value changes
+/// 
+/// For widgets drawing a number or count every frame: `render` takes
+/// `&self`, so the text lives behind a `RefCell`, and a miss reuses the
+/// string's allocation.
+#[derive(Debug, Default)]
+pub struct LabelCache<K> {
+    entry: RefCell<(Option<K>, String)>,
+}
+
+impl<K: PartialEq> LabelCache<K> {
+    pub fn new() -> Self {
+        Self { entry: RefCell::new((None, String::new())) }
+    }
+    
+    /// Text for `key`, written by `format` unless `key` is the one the
+    /// cached text was made for
+    pub fn get(&self, key: K, format: impl FnOnce(&K, &mut String)) -> Ref<'_, str> {
+        let stale = self.entry.borrow().0.as_ref() != Some(&key);
+        if stale {
+            let mut entry = self.entry.borrow_mut();
+            entry.1.clear();
+            format(&key, &mut entry.1);
+            entry.0 = Some(key);
+        }
+        Ref::map(self.entry.borrow(), |(_, text)| text.as_str())
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_font_file_by_family() {
+        let root = std::env::temp_dir().join(format!("glassui-fonts-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("truetype/liberation")).unwrap();
+        for file in ["truetype/liberation/LiberationSans-Regular.ttf", "truetype/liberation/LiberationSans-Bold.ttf", "DejaVuSans.TTF", "notes.txt"] {
+            std::fs::write(root.join(file), b"").unwrap();
+        }
+        let dirs = [root.clone()];
+        
+        assert_eq!(find_font_file(&dirs, "Liberation Sans"), Some(root.join("truetype/liberation/LiberationSans-Regular.ttf")));
+        assert_eq!(find_font_file(&dirs, "liberation sans bold"), Some(root.join("truetype/liberation/LiberationSans-Bold.ttf")));
+        assert_eq!(find_font_file(&dirs, "DejaVu Sans"), Some(root.join("DejaVuSans.TTF")));
+        assert_eq!(find_font_file(&dirs, "notes"), None);
+        assert_eq!(find_font_file(&dirs, " "), None);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_layout_wraps_words_and_newlines() {
+        // Every character 10px wide
+        let layout = |text: &str, max_width: Option<f32>| layout_text_with(text, max_width, 20.0, |run| run.chars().count() as f32 * 10.0);
+        let lines = |text: &str, max_width: Option<f32>| -> Vec<String> {
+            layout(text, max_width).line_texts(text).map(str::to_string).collect()
+        };
+        
+        assert_eq!(lines("the quick brown fox", Some(100.0)), ["the quick", "brown fox"]);
+        assert_eq!(lines("one\n\n  two three", Some(80.0)), ["one", "", "  two", "three"]);
+        assert_eq!(lines("abcdefghij kl", Some(40.0)), ["abcd", "efgh", "ij", "kl"]);
+        assert_eq!(lines("no wrap here\r\nok", None), ["no wrap here", "ok"]);
+        assert_eq!(lines("", Some(50.0)), [""]);
+        
+        let metrics = layout("the quick brown fox", Some(100.0));
+        assert_eq!(metrics.size(), (90.0, 40.0));
+        assert_eq!(metrics.lines[1].y, 20.0);
+        assert_eq!(metrics.lines[1].range, 10..19);
+    }
+
+    #[test]
+    fn test_fallback_chain_picks_first_font_with_glyph() {
+        // 0: Latin, 1: custom, 2: CJK, 3: emoji (also has '★'), 4: symbols
+        let (latin, custom, cjk, emoji, symbols) = (FontId(0), FontId(1), FontId(2), FontId(3), FontId(4));
+        let has_glyph = |id: FontId, c: char| match id.0 {
+            0 => c.is_ascii(),
+            1 => c == 'x',
+            2 => c == '漢',
+            3 => c == '🤖' || c == '★',
+            _ => c == '★' || c == '⇄',
+        };
+        let pick = |c, requested| pick_font(c, requested, latin, Some(emoji), &[cjk, emoji, symbols], has_glyph);
+        
+        assert_eq!(pick('a', latin), latin);
+        assert_eq!(pick('x', custom), custom);
+        // Missing from a custom font: the default font before the chain
+        assert_eq!(pick('a', custom), latin);
+        assert_eq!(pick('漢', latin), cjk);
+        assert_eq!(pick('⇄', latin), symbols);
+        // Emoji prefer the emoji font, other characters follow the chain order
+        assert_eq!(pick('🤖', latin), emoji);
+        assert_eq!(pick('★', latin), emoji);
+        // Nobody has it: the requested font's missing-glyph box
+        assert_eq!(pick('Ж', custom), custom);
+    }
+
+    #[test]
+    fn test_ellipsize_fits_max_width() {
+        let measure = |run: &str| run.chars().count() as f32 * 10.0;
+        
+        assert_eq!(ellipsize("short", 50.0, measure), "short");
+        assert!(matches!(ellipsize("short", 50.0, measure), Cow::Borrowed(_)));
+        assert_eq!(ellipsize("much longer", 50.0, measure), "much…");
+        // Spaces before the ellipsis are dropped
+        assert_eq!(ellipsize("ab cdefgh", 40.0, measure), "ab…");
+        assert_eq!(ellipsize("abc", 15.0, measure), "…");
+        assert_eq!(ellipsize("abc", 5.0, measure), "");
+    }
+
+    #[test]
+    fn test_outline_offsets_ring_the_glyph() {
+        assert!(outline_offsets(0.0).is_empty());
+        
+        let thin = outline_offsets(1.0);
+        assert_eq!(thin.len(), 8);
+        assert!(thin.iter().all(|[x, y]| ((x * x + y * y).sqrt() - 1.0).abs() < 1e-4));
+        
+        // Thick strokes add copies so neighbours stay close together
+        let thick = outline_offsets(6.0);
+        assert!(thick.len() > 8);
+        let [x0, y0] = thick[0];
+        let [x1, y1] = thick[1];
+        assert!(((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt() <= 1.5);
+    }
+
+    #[test]
+    fn test_hit_testing_and_selection() {
+        let measure = |run: &str| run.chars().count() as f32 * 10.0;
+        
+        assert_eq!(index_to_x("hello", 2, measure), 20.0);
+        assert_eq!(index_to_x("hello", 99, measure), 50.0);
+        assert_eq!(x_to_index("hello", 23.0, measure), 2);
+        assert_eq!(x_to_index("hello", 27.0, measure), 3);
+        assert_eq!(x_to_index("hello", -5.0, measure), 0);
+        assert_eq!(selection_spans("hello", 1..3, measure), [10.0..30.0]);
+        assert!(selection_spans("hello", 2..2, measure).is_empty());
+        
+        // "ab אבג": the Hebrew is drawn reversed after "ab "
+        let text = "ab אבג";
+        let alef = 3;
+        assert_eq!(index_to_x(text, alef, measure), 30.0);
+        assert_eq!(index_to_x(text, alef + 2, measure), 50.0);
+        assert_eq!(index_to_x(text, text.len(), measure), 30.0);
+        assert_eq!(x_to_index(text, 48.0, measure), alef + 2);
+        // Selecting "b א" covers two separate stretches on screen
+        assert_eq!(selection_spans(text, 1..alef + 2, measure), [10.0..30.0, 50.0..60.0]);
+    }
+
+    #[test]
+    fn test_atlas_evicts_least_recently_used_page() {
+        // 4 pages of 64x16: one 10x10 glyph per shelf, five per page
+        let mut atlas = FontAtlas::new(64);
+        let key = |n: u32| (FontId::SYSTEM, GlyphId(1), n);
+        let fill = |atlas: &mut FontAtlas, n: u32| {
+            let slot = atlas.allocate(10, 10).unwrap();
+            atlas.insert(key(n), GlyphInfo { page: Some(slot.page), ..Default::default() });
+            slot
+        };
+        for n in 0..20 {
+            let slot = fill(&mut atlas, n);
+            assert_eq!((slot.page, slot.y), (n / 5, (n / 5) * 16));
+            assert!(!slot.evicted);
+        }
+        
+        // Next frame: pages 1-3 are drawn from, page 0 is the oldest
+        atlas.next_frame();
+        for n in [5, 10, 15] {
+            assert!(atlas.lookup(&key(n)).is_some());
+        }
+        let slot = fill(&mut atlas, 20);
+        assert_eq!((slot.page, slot.x, slot.y, slot.evicted), (0, 0, 0, true));
+        assert!(atlas.lookup(&key(0)).is_none() && atlas.lookup(&key(6)).is_some());
+        assert_eq!(atlas.stats.evictions, 1);
+        assert_eq!(atlas.stats.cached_glyphs, 16);
+        
+        // Every page in use this frame: nothing to evict
+        for _ in 0..4 {
+            fill(&mut atlas, 21);
+        }
+        assert!(atlas.allocate(10, 10).is_none());
+        assert_eq!(atlas.stats.dropped, 1);
+        // Taller than a page never fits
+        assert!(atlas.allocate(10, 20).is_none());
+    }
+
+    #[test]
+    fn test_color_glyph_bitmaps() {
+        assert!(is_emoji('📊') && is_emoji('🤖') && !is_emoji('A') && is_zero_width('\u{FE0F}'));
+        
+        // Opaque red next to transparent blue
+        let mut encoded = Vec::new();
+        let mut encoder = png::Encoder::new(&mut encoded, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.write_header().unwrap().write_image_data(&[255, 0, 0, 255, 0, 0, 255, 0]).unwrap();
+        let (w, h, mut rgba) = decode_png(&encoded).unwrap();
+        assert_eq!((w, h), (2, 1));
+        premultiply(&mut rgba);
+        
+        // Shrinking averages premultiplied colors, so transparent pixels don't tint
+        let mut out = Vec::new();
+        resample_premultiplied(&rgba, 2, 1, 1, 1, &mut out);
+        assert_eq!(out, [255, 0, 0, 127]);
+        
+        // Enlarging repeats the nearest pixel
+        resample_premultiplied(&rgba, 2, 1, 4, 1, &mut out);
+        assert_eq!(out, [255, 0, 0, 255, 255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+    
+    #[test]
+    fn test_shapes_right_to_left_runs() {
+        let Ok(data) = std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf") else { return };
+        let font = Arc::new(FontVec::try_from_vec(data).unwrap());
+        let fonts = FontSet { faces: vec![font.clone()], default_font: FontId::SYSTEM, fallback_fonts: Vec::new(), emoji_font: None };
+        let ids = |text: &str| fonts.shape(FontId::SYSTEM, text, 16.0).iter().map(|g| g.glyph).collect::<Vec<_>>();
+        assert!(needs_shaping("سلام") && needs_shaping("e\u{0301}") && !needs_shaping("hello"));
+        
+        // Hebrew comes out left to right in display order
+        assert_eq!(ids("אב"), [font.glyph_id('ב'), font.glyph_id('א')]);
+        
+        // Arabic letters take their joining forms: seen starts the word
+        // (rightmost) in its initial form, not the isolated one
+        let salam = ids("سلام");
+        assert!(!salam.is_empty() && salam.len() <= 4);
+        assert_ne!(salam[salam.len() - 1], font.glyph_id('س'));
+        assert!(fonts.text_width(FontId::SYSTEM, "سلام", 16.0) > 0.0);
+        
+        // A combining mark takes no room of its own
+        let marked = fonts.shape(FontId::SYSTEM, "e\u{0301}", 16.0);
+        assert_eq!(marked.iter().map(|g| g.advance).sum::<f32>(), fonts.text_width(FontId::SYSTEM, "e", 16.0));
+    }
+    
+    #[test]
+    fn test_label_cache_formats_on_miss_only() {
+        use std::fmt::Write;
+        let cache = LabelCache::new();
+        let mut formats = 0;
+        let mut label = |value: u32| {
+            cache.get(value, |v, text| {
+                formats += 1;
+                let _ = write!(text, "{} msgs", v);
+            }).to_string()
+        };
+        assert_eq!(label(3), "3 msgs");
+        assert_eq!(label(3), "3 msgs");
+        assert_eq!(label(4), "4 msgs");
+        assert_eq!(label(4), "4 msgs");
+        assert_eq!(formats, 2);
+    }
+    
+    #[test]
+    fn test_color_layers_fill_the_innermost_clip() {
+        use ttf_parser::colr::Painter;
+        let rgba = |red, green, blue, alpha| ttf_parser::RgbaColor { red, green, blue, alpha };
+        let mut layers = ColorLayers::default();
+        // COLRv0: outline then paint, per layer
+        layers.outline_glyph(ttf_parser::GlyphId(1));
+        layers.paint(ttf_parser::colr::Paint::Solid(rgba(255, 0, 0, 255)));
+        // COLRv1: the glyph clips what's painted under it, even after
+        // another outline
+        layers.outline_glyph(ttf_parser::GlyphId(2));
+        layers.push_clip();
+        layers.push_clip_box(ttf_parser::colr::ClipBox { x_min: 0.0, y_min: 0.0, x_max: 1.0, y_max: 1.0 });
+        layers.outline_glyph(ttf_parser::GlyphId(3));
+        layers.paint(ttf_parser::colr::Paint::Solid(rgba(0, 0, 255, 128)));
+        layers.pop_clip();
+        layers.pop_clip();
+        assert_eq!(layers.layers, vec![(GlyphId(1), [255, 0, 0, 255]), (GlyphId(2), [0, 0, 255, 128])]);
+        
+        let stops = [(0, 0, 100), (255, 200, 0)].map(|(red, green, blue)| ttf_parser::colr::ColorStop { stop_offset: 0.0, color: rgba(red, green, blue, 255) });
+        assert_eq!(average_color(stops.into_iter()), rgba(127, 100, 50, 255));
+    }
+    
+    #[test]
+    fn test_group_quads_is_stable_per_font_size_and_page() {
+        let quad = |id: f32| [TextVertex { position: [id, 0.0], uv: [0.0; 2], color: [1.0; 4], color_glyph: 0 }; 6];
+        let group = |font, scale_key, page| TextGroup { font: FontId(font), scale_key, page: Some(page) };
+        // Shadow (0) and text (1) of a 14px label, a 20px title (2), then
+        // another 14px label (3) whose glyph went onto page 1 (4)
+        let mut groups = vec![group(0, 140, 0), group(0, 140, 0), group(0, 200, 0), group(0, 140, 0), group(0, 140, 1)];
+        let mut vertices: Vec<TextVertex> = (0..5).flat_map(|id| quad(id as f32)).collect();
+        group_quads(&mut vertices, &mut groups, &mut Vec::new());
+        
+        let order: Vec<f32> = vertices.chunks_exact(6).map(|q| q[0].position[0]).collect();
+        assert_eq!(order, vec![0.0, 1.0, 3.0, 4.0, 2.0]);
+        assert!(vertices.chunks_exact(6).all(|q| q.iter().all(|v| v.position == q[0].position)));
+        assert!(groups.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
@@ -0,0 +1,442 @@
+//! GlassUI System Tray
+//!
+//! Tray icon with a context menu and minimize/close-to-tray behavior, so
+//! long-running dashboards keep working in the background. Enabled with
+//! the `tray` feature.
+//!
+//! The platform side is a `TrayBackend`: wrap whichever tray library the
+//! app ships with (or use `HeadlessTray` in tests and headless runs). The
+//! `Tray` itself owns the menu callbacks and decides when the window hides
+//! or comes back; like `TitleBar`, it records `TrayAction`s that
+//! `Tray::apply` carries out on the winit window.
+//!
+//! While hidden the window gets no redraws, so keep calling `update` on
+//! toasts and tasks from `AboutToWait`; `Tray::notify` turns their
+//! notifications into OS notifications until the window is restored.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use winit::event::WindowEvent;
+use winit::window::Window;
+use crate::widgets::MenuItem;
+
+// =============================================================================
+// BACKEND
+// =============================================================================
+
+/// RGBA icon image for the tray
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrayIconImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl TrayIconImage {
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Self {
+        Self { width, height, rgba }
+    }
+
+    /// Single-colour square icon (handy before real artwork exists)
+    pub fn solid(size: u32, rgba: [u8; 4]) -> Self {
+        Self::new(size, size, rgba.repeat((size * size) as usize))
+    }
+}
+
+/// Menu entry as handed to the backend (callbacks stay in the `Tray`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrayMenuEntry {
+    /// Reported back in `TrayEvent::MenuItem` when chosen
+    pub id: usize,
+    pub label: String,
+    pub enabled: bool,
+    pub checked: Option<bool>,
+    pub separator: bool,
+    pub children: Vec<TrayMenuEntry>,
+}
+
+/// Input from the tray icon
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayEvent {
+    Click,
+    DoubleClick,
+    MenuItem(usize),
+}
+
+/// Platform tray implementation
+pub trait TrayBackend {
+    fn set_icon(&mut self, icon: &TrayIconImage);
+    fn set_tooltip(&mut self, tooltip: &str);
+    fn set_menu(&mut self, menu: &[TrayMenuEntry]);
+    /// Show an OS notification
+    fn notify(&mut self, title: &str, body: &str);
+    /// Next pending icon or menu event
+    fn poll_event(&mut self) -> Option<TrayEvent>;
+}
+
+/// Shared backends, so the app can keep a handle to its backend
+impl<T: TrayBackend> TrayBackend for Rc<RefCell<T>> {
+    fn set_icon(&mut self, icon: &TrayIconImage) {
+        self.borrow_mut().set_icon(icon);
+    }
+
+    fn set_tooltip(&mut self, tooltip: &str) {
+        self.borrow_mut().set_tooltip(tooltip);
+    }
+
+    fn set_menu(&mut self, menu: &[TrayMenuEntry]) {
+        self.borrow_mut().set_menu(menu);
+    }
+
+    fn notify(&mut self, title: &str, body: &str) {
+        self.borrow_mut().notify(title, body);
+    }
+
+    fn poll_event(&mut self) -> Option<TrayEvent> {
+        self.borrow_mut().poll_event()
+    }
+}
+
+/// Backend without a platform tray: records state, replays queued events
+#[derive(Debug, Default)]
+pub struct HeadlessTray {
+    pub icon: Option<TrayIconImage>,
+    pub tooltip: String,
+    pub menu: Vec<TrayMenuEntry>,
+    pub notifications: Vec<(String, String)>,
+    pub events: VecDeque<TrayEvent>,
+}
+
+impl HeadlessTray {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an event as if the user interacted with the tray
+    pub fn push_event(&mut self, event: TrayEvent) {
+        self.events.push_back(event);
+    }
+}
+
+impl TrayBackend for HeadlessTray {
+    fn set_icon(&mut self, icon: &TrayIconImage) {
+        self.icon = Some(icon.clone());
+    }
+
+    fn set_tooltip(&mut self, tooltip: &str) {
+        self.tooltip = tooltip.to_string();
+    }
+
+    fn set_menu(&mut self, menu: &[TrayMenuEntry]) {
+        self.menu = menu.to_vec();
+    }
+
+    fn notify(&mut self, title: &str, body: &str) {
+        log::info!("Tray notification: {} - {}", title, body);
+        self.notifications.push((title.to_string(), body.to_string()));
+    }
+
+    fn poll_event(&mut self) -> Option<TrayEvent> {
+        self.events.pop_front()
+    }
+}
+
+// =============================================================================
+// TRAY
+// =============================================================================
+
+/// Something the tray wants the window or app to do
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayAction {
+    /// Hide the window; the app keeps running in the tray
+    Hide,
+    /// Show and focus the window again
+    Restore,
+    /// Exit the app
+    Quit,
+}
+
+/// Ids of the built-in entries appended below the app's menu
+const SHOW_ID: usize = usize::MAX - 1;
+const QUIT_ID: usize = usize::MAX;
+
+/// Tray icon controller
+pub struct Tray {
+    backend: Box<dyn TrayBackend>,
+    items: Vec<MenuItem>,
+    /// Hide instead of exiting when the window is closed
+    pub close_to_tray: bool,
+    /// Hide when the window is minimized
+    pub minimize_to_tray: bool,
+    hidden: bool,
+    actions: Vec<TrayAction>,
+}
+
+impl Tray {
+    pub fn new(mut backend: Box<dyn TrayBackend>, icon: TrayIconImage) -> Self {
+        backend.set_icon(&icon);
+        let mut tray = Self {
+            backend,
+            items: Vec::new(),
+            close_to_tray: true,
+            minimize_to_tray: false,
+            hidden: false,
+            actions: Vec::new(),
+        };
+        tray.sync_menu();
+        tray
+    }
+
+    /// App entries shown above the built-in Show/Quit entries
+    pub fn with_menu(mut self, items: Vec<MenuItem>) -> Self {
+        self.items = items;
+        self.sync_menu();
+        self
+    }
+
+    pub fn with_tooltip(mut self, tooltip: &str) -> Self {
+        self.backend.set_tooltip(tooltip);
+        self
+    }
+
+    pub fn with_close_to_tray(mut self, enabled: bool) -> Self {
+        self.close_to_tray = enabled;
+        self
+    }
+
+    pub fn with_minimize_to_tray(mut self, enabled: bool) -> Self {
+        self.minimize_to_tray = enabled;
+        self
+    }
+
+    pub fn set_icon(&mut self, icon: &TrayIconImage) {
+        self.backend.set_icon(icon);
+    }
+
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        self.backend.set_tooltip(tooltip);
+    }
+
+    /// Whether the window is currently hidden in the tray
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Forward a notification to the OS while the window is hidden
+    ///
+    /// Returns true when it was shown; visible windows show their own toasts.
+    pub fn notify(&mut self, title: &str, body: &str) -> bool {
+        if self.hidden {
+            self.backend.notify(title, body);
+        }
+        self.hidden
+    }
+
+    pub fn hide_to_tray(&mut self) {
+        if !self.hidden {
+            self.hidden = true;
+            self.actions.push(TrayAction::Hide);
+        }
+    }
+
+    pub fn restore(&mut self) {
+        self.hidden = false;
+        self.actions.push(TrayAction::Restore);
+    }
+
+    /// Intercept window events that should send the app to the tray
+    ///
+    /// Returns true when the event was handled here (e.g. a close request
+    /// turned into hiding) and the app should ignore it.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CloseRequested if self.close_to_tray => {
+                self.hide_to_tray();
+                true
+            }
+            // Minimizing reports a zero-sized window on most platforms
+            WindowEvent::Resized(size) if self.minimize_to_tray && (size.width == 0 || size.height == 0) => {
+                self.hide_to_tray();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Process tray icon clicks and menu picks
+    pub fn poll(&mut self) {
+        while let Some(event) = self.backend.poll_event() {
+            match event {
+                TrayEvent::Click | TrayEvent::DoubleClick => {
+                    if self.hidden {
+                        self.restore();
+                    }
+                }
+                TrayEvent::MenuItem(SHOW_ID) => self.restore(),
+                TrayEvent::MenuItem(QUIT_ID) => self.actions.push(TrayAction::Quit),
+                TrayEvent::MenuItem(id) => self.activate(id),
+            }
+        }
+    }
+
+    /// Take the actions recorded since the last call
+    pub fn take_actions(&mut self) -> Vec<TrayAction> {
+        std::mem::take(&mut self.actions)
+    }
+
+    /// Poll the tray and carry out pending actions on `window`
+    ///
+    /// Returns true when Quit was chosen; exiting is left to the app.
+    pub fn apply(&mut self, window: &Window) -> bool {
+        self.poll();
+        let mut quit = false;
+        for action in self.take_actions() {
+            match action {
+                TrayAction::Hide => window.set_visible(false),
+                TrayAction::Restore => {
+                    window.set_visible(true);
+                    window.set_minimized(false);
+                    window.focus_window();
+                }
+                TrayAction::Quit => quit = true,
+            }
+        }
+        quit
+    }
+
+    /// Run the callback of the app menu item with flattened index `id`
+    fn activate(&mut self, id: usize) {
+        let mut next = 0;
+        if let Some(item) = find_item(&mut self.items, id, &mut next) {
+            if !item.is_selectable() || item.submenu.is_some() {
+                return;
+            }
+            if let Some(checked) = &mut item.checked {
+                *checked = !*checked;
+            }
+            if let Some(callback) = &mut item.on_click {
+                callback();
+            }
+            // Check marks may have changed
+            self.sync_menu();
+        }
+    }
+
+    fn sync_menu(&mut self) {
+        let mut next = 0;
+        let mut entries = menu_entries(&self.items, &mut next);
+        if !entries.is_empty() {
+            entries.push(builtin_entry(next, "", true));
+        }
+        entries.push(builtin_entry(SHOW_ID, "Show Window", false));
+        entries.push(builtin_entry(QUIT_ID, "Quit", false));
+        self.backend.set_menu(&entries);
+    }
+}
+
+fn builtin_entry(id: usize, label: &str, separator: bool) -> TrayMenuEntry {
+    TrayMenuEntry {
+        id,
+        label: label.to_string(),
+        enabled: !separator,
+        checked: None,
+        separator,
+        children: Vec::new(),
+    }
+}
+
+/// Describe `items` for the backend, numbering them depth-first from `next`
+fn menu_entries(items: &[MenuItem], next: &mut usize) -> Vec<TrayMenuEntry> {
+    items.iter().map(|item| {
+        let id = *next;
+        *next += 1;
+        TrayMenuEntry {
+            id,
+            label: item.label.clone(),
+            enabled: item.enabled,
+            checked: item.checked,
+            separator: item.separator,
+            children: item.submenu.as_ref().map_or_else(Vec::new, |menu| menu_entries(&menu.items, next)),
+        }
+    }).collect()
+}
+
+/// Find the item numbered `id` in the same order as `menu_entries`
+fn find_item<'a>(items: &'a mut [MenuItem], id: usize, next: &mut usize) -> Option<&'a mut MenuItem> {
+    for item in items {
+        if *next == id {
+            return Some(item);
+        }
+        *next += 1;
+        if let Some(menu) = &mut item.submenu {
+            if let Some(found) = find_item(&mut menu.items, id, next) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn tray() -> (Tray, Rc<RefCell<HeadlessTray>>) {
+        let backend = Rc::new(RefCell::new(HeadlessTray::new()));
+        let tray = Tray::new(Box::new(backend.clone()), TrayIconImage::solid(16, [0, 200, 255, 255]));
+        (tray, backend)
+    }
+
+    #[test]
+    fn test_close_to_tray_and_restore() {
+        let (mut tray, backend) = tray();
+        assert!(tray.handle_window_event(&WindowEvent::CloseRequested));
+        assert!(tray.is_hidden());
+        assert_eq!(tray.take_actions(), vec![TrayAction::Hide]);
+
+        // Notifications go to the OS only while hidden
+        assert!(tray.notify("Task done", "Indexing finished"));
+        backend.borrow_mut().push_event(TrayEvent::Click);
+        tray.poll();
+        assert!(!tray.is_hidden());
+        assert_eq!(tray.take_actions(), vec![TrayAction::Restore]);
+        assert!(!tray.notify("Ignored", ""));
+        assert_eq!(backend.borrow().notifications.len(), 1);
+
+        // Without close-to-tray the close request is left to the app
+        tray.close_to_tray = false;
+        assert!(!tray.handle_window_event(&WindowEvent::CloseRequested));
+    }
+
+    #[test]
+    fn test_menu_items_run_callbacks() {
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        let (tray, backend) = tray();
+        let mut tray = tray.with_menu(vec![
+            MenuItem::new("Pause agents").checkable(false),
+            MenuItem::new("Tasks").with_submenu(vec![
+                MenuItem::new("Run now").with_callback(move || counter.set(counter.get() + 1)),
+            ]),
+        ]);
+
+        let menu = backend.borrow().menu.clone();
+        let labels: Vec<_> = menu.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, vec!["Pause agents", "Tasks", "", "Show Window", "Quit"]);
+        let run_now = menu[1].children[0].id;
+
+        backend.borrow_mut().push_event(TrayEvent::MenuItem(run_now));
+        backend.borrow_mut().push_event(TrayEvent::MenuItem(menu[0].id));
+        backend.borrow_mut().push_event(TrayEvent::MenuItem(QUIT_ID));
+        tray.poll();
+        assert_eq!(clicks.get(), 1);
+        assert_eq!(backend.borrow().menu[0].checked, Some(true));
+        assert_eq!(tray.take_actions(), vec![TrayAction::Quit]);
+    }
+}
@@ -39,7 +39,7 @@ pub use crate::widgets::{
     ScrollArea, TabBar,
     
     // Overlays
-    Tooltip, MenuItem, ContextMenu, ContextMenuTrigger, Modal,
+    Tooltip, TooltipContent, MenuItem, ContextMenu, ContextMenuTrigger, Modal,
     
     // Advanced
     Draggable, Resizable,
@@ -82,10 +82,41 @@ impl Accessible {
 impl Widget for Accessible {
     fn layout(&mut self, origin: glam::Vec2, max_size: glam::Vec2) -> glam::Vec2 {
         self.position = origin;
-        self.size = self.child.layout(origin, max_size);
+        self.size = self.child.layout_child(origin, max_size);
         self.size
     }
 
+    fn layout_with_constraints(&mut self, constraints: crate::layout::BoxConstraints) -> crate::layout::Size {
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = glam::Vec2::new(size.width, size.height);
+        size
+    }
+
+    fn set_position(&mut self, position: crate::layout::Offset) {
+        self.position = glam::Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+
+    fn get_position(&self) -> crate::layout::Offset {
+        crate::layout::Offset::new(self.position.x, self.position.y)
+    }
+
+    fn get_size(&self) -> crate::layout::Size {
+        crate::layout::Size::new(self.size.x, self.size.y)
+    }
+
+    fn positioned(&self) -> Option<&crate::widgets::Positioned> {
+        self.child.positioned()
+    }
+
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: glam::Vec2) -> bool {
         self.child.handle_event(event, mouse_pos)
     }
@@ -121,10 +152,41 @@ impl DragSource {
 impl Widget for DragSource {
     fn layout(&mut self, origin: glam::Vec2, max_size: glam::Vec2) -> glam::Vec2 {
         self.position = origin;
-        self.size = self.child.layout(origin, max_size);
+        self.size = self.child.layout_child(origin, max_size);
         self.size
     }
 
+    fn layout_with_constraints(&mut self, constraints: crate::layout::BoxConstraints) -> crate::layout::Size {
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = glam::Vec2::new(size.width, size.height);
+        size
+    }
+
+    fn set_position(&mut self, position: crate::layout::Offset) {
+        self.position = glam::Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+
+    fn get_position(&self) -> crate::layout::Offset {
+        crate::layout::Offset::new(self.position.x, self.position.y)
+    }
+
+    fn get_size(&self) -> crate::layout::Size {
+        crate::layout::Size::new(self.size.x, self.size.y)
+    }
+
+    fn positioned(&self) -> Option<&crate::widgets::Positioned> {
+        self.child.positioned()
+    }
+
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: glam::Vec2) -> bool {
         self.child.handle_event(event, mouse_pos)
     }
@@ -158,10 +220,41 @@ impl DropTarget {
 impl Widget for DropTarget {
     fn layout(&mut self, origin: glam::Vec2, max_size: glam::Vec2) -> glam::Vec2 {
         self.position = origin;
-        self.size = self.child.layout(origin, max_size);
+        self.size = self.child.layout_child(origin, max_size);
         self.size
     }
 
+    fn layout_with_constraints(&mut self, constraints: crate::layout::BoxConstraints) -> crate::layout::Size {
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = glam::Vec2::new(size.width, size.height);
+        size
+    }
+
+    fn set_position(&mut self, position: crate::layout::Offset) {
+        self.position = glam::Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+
+    fn get_position(&self) -> crate::layout::Offset {
+        crate::layout::Offset::new(self.position.x, self.position.y)
+    }
+
+    fn get_size(&self) -> crate::layout::Size {
+        crate::layout::Size::new(self.size.x, self.size.y)
+    }
+
+    fn positioned(&self) -> Option<&crate::widgets::Positioned> {
+        self.child.positioned()
+    }
+
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: glam::Vec2) -> bool {
         self.child.handle_event(event, mouse_pos)
     }
@@ -5,7 +5,8 @@
 use glam::{Vec2, Vec4};
 use winit::event::{ElementState, MouseButton};
 use crate::renderer::GlassRenderer;
-use super::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, Rect, Offset, Size};
+use super::core::{Widget, UpdateState, get_theme};
 
 // =============================================================================
 // DRAGGABLE
@@ -20,6 +21,8 @@ pub struct Draggable {
     drag_start_mouse: Vec2,
     drag_start_pos: Vec2,
     initialized: bool,
+    /// Position the child was last laid out at
+    layout_position: Option<Vec2>,
 }
 
 impl Draggable {
@@ -32,6 +35,7 @@ impl Draggable {
             drag_start_mouse: Vec2::ZERO,
             drag_start_pos: Vec2::ZERO,
             initialized: false,
+            layout_position: None,
         }
     }
     
@@ -41,28 +45,9 @@ impl Draggable {
         self.initialized = true;
         self
     }
-}
-
-impl Widget for Draggable {
-    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
-        // First layout: accept parent's suggested position
-        if !self.initialized && !self.dragging {
-            self.position = origin;
-            self.initialized = true;
-        }
-        
-        // Layout child at our current position
-        let child_size = self.child.layout(self.position, max_size);
-        self.size = child_size;
-        child_size
-    }
-
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        // Child gets first chance to handle events
-        if self.child.handle_event(event, mouse_pos) {
-            return true;
-        }
-        
+    
+    /// Press and drag on the area the child left unhandled
+    fn handle_drag_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Check if mouse is inside
         let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
                      mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
@@ -90,11 +75,92 @@ impl Widget for Draggable {
         
         false
     }
+}
+
+impl Widget for Draggable {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        // First layout: accept parent's suggested position
+        if !self.initialized && !self.dragging {
+            self.position = origin;
+            self.initialized = true;
+        }
+        
+        // Layout child at our current position
+        let child_size = self.child.layout_child(self.position, max_size);
+        self.size = child_size;
+        self.layout_position = Some(self.position);
+        child_size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    /// Only the first placement is taken; after that the widget stays where it was dragged
+    fn set_position(&mut self, position: Offset) {
+        if !self.initialized && !self.dragging {
+            self.position = Vec2::new(position.x, position.y);
+            self.initialized = true;
+        }
+        self.child.set_position(Offset::new(self.position.x, self.position.y));
+        self.layout_position = Some(self.position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_position != Some(self.position) || self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        // Child gets first chance to handle events
+        if self.child.handle_event(event, mouse_pos) {
+            return true;
+        }
+        
+        self.handle_drag_event(event, mouse_pos)
+    }
+    
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.handle_drag_event(event, mouse_pos)
+    }
 
     fn update(&mut self, dt: f32) {
         self.child.update(dt);
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
     fn render(&self, renderer: &mut GlassRenderer) {
         self.child.render(renderer);
     }
@@ -116,6 +182,8 @@ pub struct Resizable {
     resize_start_size: Vec2,
     handle_size: f32,
     corner_radius: f32,
+    /// Size the child was last laid out at
+    layout_size: Option<Vec2>,
 }
 
 impl Resizable {
@@ -131,6 +199,7 @@ impl Resizable {
             resize_start_size: Vec2::ZERO,
             handle_size: 20.0,
             corner_radius: 4.0,
+            layout_size: None,
         }
     }
     
@@ -143,17 +212,9 @@ impl Resizable {
         self.max_size = max;
         self
     }
-}
-
-impl Widget for Resizable {
-    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
-        self.position = origin;
-        // Constrain child to our current size
-        self.child.layout(origin, self.current_size);
-        self.current_size
-    }
-
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+    
+    /// Press and drag on the bottom-right resize handle
+    fn handle_resize_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Check if in resize handle (bottom-right corner)
         let handle_pos = self.position + self.current_size - Vec2::splat(self.handle_size);
         let in_handle = mouse_pos.x >= handle_pos.x && mouse_pos.y >= handle_pos.y &&
@@ -183,6 +244,70 @@ impl Widget for Resizable {
             return true;
         }
         
+        false
+    }
+}
+
+impl Widget for Resizable {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        // Constrain child to our current size
+        self.child.layout_child(origin, self.current_size);
+        self.layout_size = Some(self.current_size);
+        self.current_size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.current_size.x, self.current_size.y));
+        self.current_size = Vec2::new(size.width, size.height);
+        self.child.layout_child_with_constraints(BoxConstraints::loose(size));
+        self.layout_size = Some(self.current_size);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.current_size.x, self.current_size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_size != Some(self.current_size) || self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        // Resize grip is drawn inside our own rect
+        self.child.render_bounds().map(|b| b.union(&Rect::new(self.position, self.current_size)))
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.handle_resize_event(event, mouse_pos) {
+            return true;
+        }
+        
         // Child events
         if self.child.handle_event(event, mouse_pos) {
             return true;
@@ -190,11 +315,24 @@ impl Widget for Resizable {
         
         false
     }
+    
+    fn capture_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        // The handle is drawn over the child, so it wins over the child
+        self.handle_resize_event(event, mouse_pos)
+    }
+    
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.handle_resize_event(event, mouse_pos)
+    }
 
     fn update(&mut self, dt: f32) {
         self.child.update(dt);
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
         
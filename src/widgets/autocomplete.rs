@@ -0,0 +1,830 @@
+//! GlassUI Autocomplete
+//!
+//! `AutocompleteInput` is a `TextInput` that lists matching suggestions in
+//! an overlay popup as the user types. Suggestions come from a fixed list
+//! or from a `SuggestionProvider`, which may answer later from another
+//! thread (a history search, an index lookup, a model call).
+//!
+//! `Combobox` uses the same popup as an editable dropdown over a list of
+//! options.
+
+use std::sync::mpsc;
+use glam::{Vec2, Vec4};
+use winit::event::{ElementState, MouseButton};
+use crate::renderer::GlassRenderer;
+use super::core::{Widget, WidgetState, get_theme, render_faded};
+use super::input::TextInput;
+use super::scroll::ScrollBar;
+use crate::layout::{BoxConstraints, Offset, Rect, Size};
+use crate::focus::Focusable;
+
+/// Height of one suggestion row in the popup
+const ITEM_HEIGHT: f32 = 30.0;
+
+/// Suggestions for the query of the given generation
+type Reply = (u64, Vec<String>);
+
+// =============================================================================
+// SUGGESTION PROVIDER
+// =============================================================================
+
+/// Where an `AutocompleteInput` gets suggestions for what has been typed
+///
+/// Implemented for closures taking the query and a reply handle, so a
+/// provider can answer at once or move the handle to a worker thread.
+pub trait SuggestionProvider {
+    /// Look up suggestions for `query` and pass them to `reply`, now or
+    /// later; replies for queries typed over are ignored
+    fn suggest(&mut self, query: &str, reply: SuggestionReply);
+}
+
+impl<F: FnMut(&str, SuggestionReply)> SuggestionProvider for F {
+    fn suggest(&mut self, query: &str, reply: SuggestionReply) {
+        self(query, reply)
+    }
+}
+
+/// Handle a `SuggestionProvider` answers one query through; it can be sent
+/// to another thread
+#[derive(Clone)]
+pub struct SuggestionReply {
+    generation: u64,
+    sender: mpsc::Sender<Reply>,
+}
+
+impl SuggestionReply {
+    pub fn send(&self, suggestions: Vec<String>) {
+        // The input may be gone by the time a slow lookup finishes
+        let _ = self.sender.send((self.generation, suggestions));
+    }
+}
+
+/// Candidates containing `query` (ignoring case), those starting with it
+/// first; an exact match isn't suggested back
+pub fn filter_suggestions(candidates: &[String], query: &str) -> Vec<String> {
+    let query = query.to_lowercase();
+    let (mut prefixed, mut containing): (Vec<String>, Vec<String>) = candidates.iter()
+        .filter(|candidate| {
+            let lower = candidate.to_lowercase();
+            lower != query && lower.contains(&query)
+        })
+        .cloned()
+        .partition(|candidate| candidate.to_lowercase().starts_with(&query));
+    prefixed.append(&mut containing);
+    prefixed
+}
+
+// =============================================================================
+// AUTOCOMPLETE INPUT
+// =============================================================================
+
+/// Text field with a typeahead popup of suggestions
+///
+/// Up/Down move through the suggestions, Enter takes the highlighted one
+/// and Escape closes the popup; a click picks one too. Past `max_visible`
+/// suggestions the popup scrolls, a whole row at a time.
+pub struct AutocompleteInput {
+    pub input: TextInput,
+    /// Suggestions listed in the popup
+    pub suggestions: Vec<String>,
+    /// Suggestion Enter would take
+    pub highlighted: Option<usize>,
+    pub hovered: Option<usize>,
+    pub open: bool,
+    /// Most rows the popup shows at once
+    pub max_visible: usize,
+    /// Popup scroll, kept to whole rows
+    pub scroll: ScrollBar,
+    /// Called with the suggestion taken
+    pub on_select: Option<SelectCallback>,
+    /// Fixed list filtered as the user types, when there's no provider
+    candidates: Vec<String>,
+    provider: Option<Box<dyn SuggestionProvider>>,
+    /// Bumped for each query so late replies to older ones are dropped
+    generation: u64,
+    pending: bool,
+    sender: mpsc::Sender<Reply>,
+    receiver: mpsc::Receiver<Reply>,
+}
+
+type SelectCallback = Box<dyn FnMut(&str)>;
+
+impl AutocompleteInput {
+    pub fn new(placeholder: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            input: TextInput::new(placeholder),
+            suggestions: Vec::new(),
+            highlighted: None,
+            hovered: None,
+            open: false,
+            max_visible: 8,
+            scroll: ScrollBar::new(),
+            on_select: None,
+            candidates: Vec::new(),
+            provider: None,
+            generation: 0,
+            pending: false,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Suggest from a fixed list (see `filter_suggestions`)
+    pub fn with_suggestions(mut self, candidates: Vec<String>) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Ask `provider` for suggestions instead of filtering a fixed list
+    pub fn with_provider(mut self, provider: impl SuggestionProvider + 'static) -> Self {
+        self.provider = Some(Box::new(provider));
+        self
+    }
+
+    pub fn on_select(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+
+    pub fn get_text(&self) -> &str {
+        self.input.get_text()
+    }
+
+    /// Look up suggestions for the current text
+    pub fn refresh(&mut self) {
+        self.generation += 1;
+        self.highlighted = None;
+        let query = self.input.get_text().to_string();
+        if query.is_empty() {
+            self.pending = false;
+            self.show(Vec::new());
+            return;
+        }
+        if let Some(provider) = &mut self.provider {
+            self.pending = true;
+            provider.suggest(&query, SuggestionReply { generation: self.generation, sender: self.sender.clone() });
+        } else {
+            let suggestions = filter_suggestions(&self.candidates, &query);
+            self.show(suggestions);
+        }
+    }
+
+    /// List `suggestions`, opening the popup if there are any
+    fn show(&mut self, suggestions: Vec<String>) {
+        self.open = !suggestions.is_empty() && self.input.focused;
+        self.suggestions = suggestions;
+        self.highlighted = None;
+        self.hovered = None;
+        self.scroll.set(0.0, 0.0);
+    }
+
+    /// Rows the popup shows at once
+    fn visible_rows(&self) -> usize {
+        self.suggestions.len().min(self.max_visible)
+    }
+
+    fn max_scroll(&self) -> f32 {
+        (self.suggestions.len() - self.visible_rows()) as f32 * ITEM_HEIGHT
+    }
+
+    /// Suggestion in the popup's top row
+    pub fn first_visible(&self) -> usize {
+        (self.scroll.offset() / ITEM_HEIGHT).round() as usize
+    }
+
+    /// Scroll the popup `rows` rows down (up if negative)
+    pub fn scroll_rows(&mut self, rows: i32) {
+        let offset = (self.first_visible() as i32 + rows).max(0) as f32 * ITEM_HEIGHT;
+        self.scroll.set(offset, self.max_scroll());
+    }
+
+    /// Scroll just far enough to show the highlighted suggestion
+    fn reveal_highlighted(&mut self) {
+        if let Some(index) = self.highlighted {
+            let max_scroll = self.max_scroll();
+            let viewport = self.visible_rows() as f32 * ITEM_HEIGHT;
+            self.scroll.reveal(index as f32 * ITEM_HEIGHT, ITEM_HEIGHT, viewport, max_scroll);
+            self.scroll.set(self.scroll.target(), max_scroll);
+        }
+    }
+
+    /// Take provider replies that arrived, keeping only the latest query's
+    fn poll_replies(&mut self) {
+        let latest = self.receiver.try_iter()
+            .filter(|(generation, _)| *generation == self.generation)
+            .last();
+        if let Some((_, suggestions)) = latest {
+            self.pending = false;
+            self.show(suggestions);
+        }
+    }
+
+    /// Move the highlight down (or up) one suggestion, wrapping around
+    pub fn highlight_next(&mut self, forward: bool) {
+        let count = self.suggestions.len();
+        if count == 0 {
+            return;
+        }
+        self.open = true;
+        self.highlighted = Some(match (self.highlighted, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        });
+        self.reveal_highlighted();
+    }
+
+    /// Put suggestion `index` in the field and close the popup
+    pub fn accept(&mut self, index: usize) {
+        let Some(suggestion) = self.suggestions.get(index).cloned() else {
+            return;
+        };
+        self.input.set_text(&suggestion);
+        self.close();
+        self.generation += 1;
+        self.pending = false;
+        if let Some(callback) = &mut self.input.on_change {
+            callback(&suggestion);
+        }
+        if let Some(callback) = &mut self.on_select {
+            callback(&suggestion);
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.highlighted = None;
+        self.hovered = None;
+    }
+
+    fn popup_rect(&self) -> Rect {
+        let position = Vec2::new(self.input.position.x, self.input.position.y + self.input.size.y + 2.0);
+        Rect::new(position, Vec2::new(self.input.size.x, self.visible_rows() as f32 * ITEM_HEIGHT))
+    }
+
+    /// Suggestion list, drawn in the overlay layer
+    fn render_popup(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        let popup = self.popup_rect();
+        let radius = self.input.corner_radius;
+        renderer.draw_shadow(popup.min + Vec2::new(0.0, 4.0), popup.size(), radius, 12.0, Vec4::new(0.0, 0.0, 0.0, 0.4));
+        renderer.draw_rounded_rect(popup.min, popup.size(), Vec4::new(0.08, 0.08, 0.1, 0.98), radius);
+
+        let first = self.first_visible();
+        for (i, suggestion) in self.suggestions.iter().enumerate().skip(first).take(self.visible_rows()) {
+            let item_y = popup.min.y + (i - first) as f32 * ITEM_HEIGHT;
+            if self.highlighted == Some(i) || self.hovered == Some(i) {
+                let alpha = if self.highlighted == Some(i) { 0.35 } else { 0.2 };
+                renderer.draw_rounded_rect(
+                    Vec2::new(popup.min.x + 2.0, item_y + 2.0),
+                    Vec2::new(popup.size().x - 4.0, ITEM_HEIGHT - 4.0),
+                    Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, alpha),
+                    4.0
+                );
+            }
+            renderer.draw_text(suggestion, Vec2::new(popup.min.x + 10.0, item_y + 6.0), 16.0, theme.text);
+        }
+
+        // Thumb, when there is more than fits
+        if self.suggestions.len() > self.visible_rows() {
+            let track = popup.size().y - 8.0;
+            let content = self.suggestions.len() as f32 * ITEM_HEIGHT;
+            let (start, length) = self.scroll.thumb(popup.size().y, content, track);
+            renderer.draw_rounded_rect(
+                Vec2::new(popup.max.x - 7.0, popup.min.y + 4.0 + start),
+                Vec2::new(4.0, length),
+                self.scroll.thumb_color(theme.primary),
+                2.0
+            );
+        }
+    }
+
+    /// Suggestion row under `point`, while the popup is open
+    pub(super) fn item_at(&self, point: Vec2) -> Option<usize> {
+        let popup = self.popup_rect();
+        (self.open && popup.contains(point))
+            .then(|| self.first_visible() + ((point.y - popup.min.y) / ITEM_HEIGHT) as usize)
+            .filter(|&i| i < self.suggestions.len())
+    }
+
+    /// Wheel over the open popup scrolls it; returns whether it did
+    pub(super) fn handle_popup_wheel(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, MouseScrollDelta, WindowEvent};
+        let Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } = event else {
+            return false;
+        };
+        if !self.open || !self.popup_rect().contains(mouse_pos) {
+            return false;
+        }
+        let rows = match delta {
+            MouseScrollDelta::LineDelta(_, y) => -y.signum() * y.abs().ceil(),
+            MouseScrollDelta::PixelDelta(p) => -(p.y as f32 / ITEM_HEIGHT).round(),
+        };
+        self.scroll_rows(rows as i32);
+        self.hovered = self.item_at(mouse_pos);
+        true
+    }
+
+    /// Popup keys: Up/Down, Enter and Escape; returns whether one was used
+    fn handle_popup_key(&mut self, key: &winit::keyboard::Key) -> bool {
+        use winit::keyboard::{Key, NamedKey};
+        match key {
+            Key::Named(NamedKey::ArrowDown) if !self.suggestions.is_empty() => self.highlight_next(true),
+            Key::Named(NamedKey::ArrowUp) if self.open => self.highlight_next(false),
+            Key::Named(NamedKey::Enter) if self.open && self.highlighted.is_some() => {
+                self.accept(self.highlighted.unwrap_or_default());
+            }
+            Key::Named(NamedKey::Escape) if self.open => self.close(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Widget for AutocompleteInput {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.input.layout_child(origin, max_size)
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.input.layout_child_with_constraints(constraints)
+    }
+
+    fn set_position(&mut self, position: Offset) {
+        self.input.set_position(position);
+    }
+
+    fn get_position(&self) -> Offset {
+        self.input.get_position()
+    }
+
+    fn get_size(&self) -> Size {
+        self.input.get_size()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.input.needs_layout()
+    }
+
+    fn is_animating(&self) -> bool {
+        // Keep polling while a provider is still working
+        self.pending
+    }
+
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(&self.input)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(&mut self.input)
+    }
+
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        self.item_at(point).is_some()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        if self.input.disabled {
+            return false;
+        }
+
+        if self.handle_popup_wheel(event, mouse_pos) {
+            return true;
+        }
+        self.hovered = self.item_at(mouse_pos);
+        match event {
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                if let Some(index) = self.hovered {
+                    self.accept(index);
+                    return true;
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. }
+                if self.input.focused && key_event.state.is_pressed() && self.handle_popup_key(&key_event.logical_key) =>
+            {
+                return true;
+            }
+            _ => {}
+        }
+
+        let before = self.input.text.clone();
+        let handled = self.input.handle_event(event, mouse_pos);
+        if self.input.text != before {
+            self.refresh();
+        } else if !self.input.focused {
+            self.close();
+        }
+        handled
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.input.update(dt);
+        self.poll_replies();
+        if !self.input.focused {
+            self.close();
+        }
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.input.render(renderer);
+        if !self.open {
+            return;
+        }
+
+        // Over later siblings and outside any scroll area the field is in
+        renderer.draw_in_overlay_layer(|renderer| self.render_popup(renderer));
+    }
+
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        self.input.debug_properties(props);
+        props.push(("suggestions", self.suggestions.len().to_string()));
+    }
+
+    fn saved_state(&self) -> Option<WidgetState> {
+        self.input.saved_state()
+    }
+
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        self.input.text_origin()
+    }
+}
+
+// =============================================================================
+// COMBOBOX
+// =============================================================================
+
+/// Editable dropdown: a text field listing the options that match what is
+/// typed, while still taking any other value
+///
+/// The arrow (or Down) lists every option. Enter takes the highlighted
+/// option, or the typed text when none is highlighted; leaving the field
+/// keeps the typed text too. Escape closes the list, then puts back the
+/// last value taken.
+pub struct Combobox {
+    pub input: AutocompleteInput,
+    pub options: Vec<String>,
+    /// Called with the value taken, whether an option or free-form text
+    pub on_change: Option<SelectCallback>,
+    /// Value last taken, restored by Escape
+    committed: String,
+}
+
+/// Width of the arrow at the right of a combobox
+const ARROW_WIDTH: f32 = 28.0;
+
+impl Combobox {
+    pub fn new(placeholder: &str) -> Self {
+        let mut input = AutocompleteInput::new(placeholder);
+        input.max_visible = 10;
+        Self {
+            input,
+            options: Vec::new(),
+            on_change: None,
+            committed: String::new(),
+        }
+    }
+
+    pub fn with_options(mut self, options: Vec<String>) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_value(mut self, value: &str) -> Self {
+        self.input.input.set_text(value);
+        self.committed = value.to_string();
+        self
+    }
+
+    pub fn on_change(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Current value: the text in the field
+    pub fn value(&self) -> &str {
+        self.input.get_text()
+    }
+
+    /// Options matching the text: an exact match first, then those
+    /// starting with it, then those containing it; all of them when the
+    /// field is empty
+    fn matching(&self) -> Vec<String> {
+        let query = self.input.get_text();
+        if query.is_empty() {
+            return self.options.clone();
+        }
+        let mut matches = filter_suggestions(&self.options, query);
+        if let Some(exact) = self.options.iter().find(|option| option.to_lowercase() == query.to_lowercase()) {
+            matches.insert(0, exact.clone());
+        }
+        matches
+    }
+
+    /// List every option, highlighting the current value if it is one
+    pub fn open_all(&mut self) {
+        self.input.show(self.options.clone());
+        self.input.open = !self.input.suggestions.is_empty();
+        self.input.highlighted = self.input.suggestions.iter().position(|option| option == self.value());
+        self.input.reveal_highlighted();
+    }
+
+    /// Take option `index` from the list
+    pub fn select(&mut self, index: usize) {
+        self.input.accept(index);
+        self.commit();
+    }
+
+    /// Take the text in the field as the value
+    pub fn commit(&mut self) {
+        self.input.close();
+        if self.value() == self.committed {
+            return;
+        }
+        self.committed = self.value().to_string();
+        if let Some(callback) = &mut self.on_change {
+            callback(&self.committed);
+        }
+    }
+
+    /// Put back the value last taken
+    fn revert(&mut self) {
+        let committed = self.committed.clone();
+        self.input.input.set_text(&committed);
+        if let Some(callback) = &mut self.input.input.on_change {
+            callback(&committed);
+        }
+    }
+
+    fn arrow_rect(&self) -> Rect {
+        let field = &self.input.input;
+        Rect::new(Vec2::new(field.position.x + field.size.x - ARROW_WIDTH, field.position.y), Vec2::new(ARROW_WIDTH, field.size.y))
+    }
+
+    /// Combobox keys: Up/Down, Enter and Escape; returns whether one was
+    /// used
+    fn handle_combo_key(&mut self, key: &winit::keyboard::Key) -> bool {
+        use winit::keyboard::{Key, NamedKey};
+        match key {
+            Key::Named(NamedKey::ArrowDown | NamedKey::ArrowUp) if !self.input.open => {
+                self.open_all();
+                if self.input.highlighted.is_none() {
+                    self.input.highlight_next(matches!(key, Key::Named(NamedKey::ArrowDown)));
+                }
+            }
+            Key::Named(NamedKey::Enter) => match self.input.highlighted.filter(|_| self.input.open) {
+                Some(index) => self.select(index),
+                None => self.commit(),
+            },
+            Key::Named(NamedKey::Escape) if !self.input.open && self.value() != self.committed => self.revert(),
+            _ => return self.input.handle_popup_key(key),
+        }
+        true
+    }
+}
+
+impl Widget for Combobox {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.input.layout_child(origin, max_size)
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.input.layout_child_with_constraints(constraints)
+    }
+
+    fn set_position(&mut self, position: Offset) {
+        self.input.set_position(position);
+    }
+
+    fn get_position(&self) -> Offset {
+        self.input.get_position()
+    }
+
+    fn get_size(&self) -> Size {
+        self.input.get_size()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.input.needs_layout()
+    }
+
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(&self.input.input)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(&mut self.input.input)
+    }
+
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        self.input.hit_test_popup(point)
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        if self.input.input.disabled {
+            return false;
+        }
+
+        if self.input.handle_popup_wheel(event, mouse_pos) {
+            return true;
+        }
+        self.input.hovered = self.input.item_at(mouse_pos);
+        match event {
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                if let Some(index) = self.input.hovered {
+                    self.select(index);
+                    return true;
+                }
+                if self.arrow_rect().contains(mouse_pos) {
+                    // Let the field take focus, then toggle the full list
+                    let was_open = self.input.open;
+                    self.input.input.handle_event(event, mouse_pos);
+                    if was_open {
+                        self.input.close();
+                    } else {
+                        self.open_all();
+                    }
+                    return true;
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. }
+                if self.input.input.focused && key_event.state.is_pressed() && self.handle_combo_key(&key_event.logical_key) =>
+            {
+                return true;
+            }
+            _ => {}
+        }
+
+        let before = self.input.input.text.clone();
+        let handled = self.input.input.handle_event(event, mouse_pos);
+        if self.input.input.text != before {
+            self.input.show(self.matching());
+        }
+        if !self.input.input.focused {
+            self.commit();
+        }
+        handled
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.input.update(dt);
+        if !self.input.input.focused && self.value() != self.committed {
+            self.commit();
+        }
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.input.render(renderer);
+
+        let theme = get_theme();
+        let arrow = self.arrow_rect();
+        render_faded(renderer, self.input.input.disabled, None, |renderer| {
+            renderer.draw_text(if self.input.open { "▲" } else { "▼" }, arrow.min + Vec2::new(6.0, 10.0), 14.0, theme.text_secondary);
+        });
+    }
+
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        self.input.debug_properties(props);
+        props.push(("options", self.options.len().to_string()));
+    }
+
+    fn saved_state(&self) -> Option<WidgetState> {
+        self.input.saved_state()
+    }
+
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        self.input.text_origin()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_suggestions_filter_and_navigate() {
+        let names = ["Ollama", "OpenAI", "llama.cpp", "Anthropic"].map(String::from).to_vec();
+        assert_eq!(filter_suggestions(&names, "LLA"), vec!["llama.cpp", "Ollama"]);
+        assert_eq!(filter_suggestions(&names, "ollama"), Vec::<String>::new());
+
+        let mut input = AutocompleteInput::new("Backend").with_suggestions(names);
+        input.input.focused = true;
+        input.input.set_text("o");
+        input.refresh();
+        assert!(input.open);
+        assert_eq!(input.suggestions, vec!["Ollama", "OpenAI", "Anthropic"]);
+        input.highlight_next(false);
+        assert_eq!(input.highlighted, Some(2));
+        input.highlight_next(true);
+        assert_eq!(input.highlighted, Some(0));
+        input.accept(1);
+        assert_eq!((input.get_text(), input.open), ("OpenAI", false));
+    }
+
+    #[test]
+    fn test_async_provider_drops_stale_replies() {
+        let replies = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let held = replies.clone();
+        let mut input = AutocompleteInput::new("Prompt").with_provider(move |query: &str, reply: SuggestionReply| {
+            held.borrow_mut().push((query.to_string(), reply));
+        });
+        input.input.focused = true;
+        input.input.set_text("ex");
+        input.refresh();
+        input.input.set_text("exp");
+        input.refresh();
+        assert!(input.is_animating());
+
+        // The answer to the older query arrives last and is ignored
+        let replies = replies.borrow();
+        let thread_reply = replies[1].1.clone();
+        std::thread::spawn(move || thread_reply.send(vec!["explain this".to_string()])).join().unwrap();
+        replies[0].1.send(vec!["example".to_string()]);
+        input.update(0.0);
+        assert_eq!(input.suggestions, vec!["explain this"]);
+        assert!(input.open && !input.is_animating());
+    }
+
+    #[test]
+    fn test_combobox_filters_and_takes_free_form_values() {
+        use winit::keyboard::{Key, NamedKey};
+        let taken = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let log = taken.clone();
+        let mut combo = Combobox::new("Model")
+            .with_options(["phi3", "llama3.2", "mistral", "codellama"].map(String::from).to_vec())
+            .with_value("phi3")
+            .on_change(move |value| log.borrow_mut().push(value.to_string()));
+        combo.input.input.focused = true;
+
+        // Down lists everything with the current value highlighted
+        assert!(combo.handle_combo_key(&Key::Named(NamedKey::ArrowDown)));
+        assert_eq!(combo.input.suggestions.len(), 4);
+        assert_eq!(combo.input.highlighted, Some(0));
+
+        combo.input.input.set_text("LLAMA");
+        combo.input.show(combo.matching());
+        assert_eq!(combo.input.suggestions, ["llama3.2", "codellama"]);
+        combo.input.highlight_next(false);
+        assert!(combo.handle_combo_key(&Key::Named(NamedKey::Enter)));
+        assert_eq!(combo.value(), "codellama");
+
+        // Anything typed is a value too, and Escape puts the last one back
+        combo.input.input.set_text("gemma2");
+        combo.input.show(combo.matching());
+        assert!(!combo.input.open);
+        assert!(combo.handle_combo_key(&Key::Named(NamedKey::Enter)));
+        combo.input.input.set_text("gem");
+        assert!(combo.handle_combo_key(&Key::Named(NamedKey::Escape)));
+        assert_eq!(combo.value(), "gemma2");
+        assert_eq!(*taken.borrow(), ["codellama", "gemma2"]);
+    }
+
+    #[test]
+    fn test_long_option_lists_scroll() {
+        use winit::event::{DeviceId, Event, MouseScrollDelta, TouchPhase, WindowEvent};
+        use winit::keyboard::{Key, NamedKey};
+        let options: Vec<String> = (0..25).map(|i| format!("model-{i}")).collect();
+        let mut combo = Combobox::new("Model").with_options(options).with_value("model-17");
+        combo.layout(Vec2::ZERO, Vec2::new(300.0, 40.0));
+        combo.input.input.focused = true;
+
+        // Every option is listed; the popup shows ten and scrolls to the value
+        combo.open_all();
+        assert_eq!(combo.input.suggestions.len(), 25);
+        assert_eq!(combo.input.popup_rect().size().y, 10.0 * ITEM_HEIGHT);
+        assert_eq!(combo.input.first_visible(), 8);
+        let top_row = combo.input.popup_rect().min + Vec2::new(20.0, 5.0);
+        assert_eq!(combo.input.item_at(top_row), Some(8));
+
+        // The wheel scrolls by rows, no further than the last one
+        let wheel = |lines: f32| Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::MouseWheel {
+                device_id: unsafe { DeviceId::dummy() },
+                delta: MouseScrollDelta::LineDelta(0.0, lines),
+                phase: TouchPhase::Moved,
+            },
+        };
+        assert!(combo.handle_event(&wheel(-3.0), top_row));
+        assert_eq!(combo.input.first_visible(), 11);
+        assert!(combo.handle_event(&wheel(-10.0), top_row));
+        assert_eq!(combo.input.first_visible(), 15);
+        assert_eq!(combo.input.hovered, Some(15));
+
+        // Moving the highlight past the top brings it back into view
+        for _ in 0..8 {
+            combo.input.highlight_next(false);
+        }
+        assert_eq!(combo.input.highlighted, Some(9));
+        assert_eq!(combo.input.first_visible(), 9);
+        assert!(combo.handle_combo_key(&Key::Named(NamedKey::Enter)));
+        assert_eq!(combo.value(), "model-9");
+    }
+}
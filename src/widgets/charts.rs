@@ -9,7 +9,7 @@
 use glam::{Vec2, Vec4};
 use crate::widgets::Widget;
 use crate::renderer::GlassRenderer;
-use crate::layout::{Size, Offset};
+use crate::layout::{BoxConstraints, Size, Offset};
 
 // =============================================================================
 // DATA POINT
@@ -179,17 +179,12 @@ impl Widget for LineChart {
             let point_count = series.data.len();
             let x_step = chart_w / (point_count - 1).max(1) as f32;
             
-            // Lines
-            for i in 0..point_count.saturating_sub(1) {
-                let v1 = series.data[i].value;
-                let v2 = series.data[i + 1].value;
-                let x1 = chart_pos.x + i as f32 * x_step;
-                let x2 = chart_pos.x + (i + 1) as f32 * x_step;
-                let y1 = chart_pos.y + chart_h - ((v1 - min_val) / value_range) as f32 * chart_h;
-                let y2 = chart_pos.y + chart_h - ((v2 - min_val) / value_range) as f32 * chart_h;
-                let len = ((x2-x1).powi(2) + (y2-y1).powi(2)).sqrt();
-                renderer.draw_rect(Vec2::new((x1+x2)/2.0 - len/2.0, (y1+y2)/2.0 - self.line_width/2.0), Vec2::new(len, self.line_width), series.color);
-            }
+            // Line
+            let points: Vec<Vec2> = series.data.iter().enumerate().map(|(i, point)| {
+                let y = chart_pos.y + chart_h - ((point.value - min_val) / value_range) as f32 * chart_h;
+                Vec2::new(chart_pos.x + i as f32 * x_step, y)
+            }).collect();
+            renderer.draw_polyline(&points, self.line_width, series.color);
             
             // Points
             if self.show_points {
@@ -203,6 +198,10 @@ impl Widget for LineChart {
         }
     }
     
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.size = constraints.constrain(self.size);
+        self.size
+    }
     fn set_position(&mut self, pos: Offset) { self.position = Vec2::new(pos.x, pos.y); }
     fn get_position(&self) -> Offset { Offset::new(self.position.x, self.position.y) }
     fn get_size(&self) -> Size { self.size }
@@ -311,6 +310,10 @@ impl Widget for BarChart {
         }
     }
     
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.size = constraints.constrain(self.size);
+        self.size
+    }
     fn set_position(&mut self, pos: Offset) { self.position = Vec2::new(pos.x, pos.y); }
     fn get_position(&self) -> Offset { Offset::new(self.position.x, self.position.y) }
     fn get_size(&self) -> Size { self.size }
@@ -387,22 +390,17 @@ impl Widget for PieChart {
         for (i, point) in self.data.iter().enumerate() {
             let slice_angle = (point.value / total) as f32 * std::f32::consts::TAU;
             let color = self.colors[i % self.colors.len()];
-            let segments = ((slice_angle / 0.1) as i32).max(1);
-            let seg_angle = slice_angle / segments as f32;
-            
-            for s in 0..segments {
-                let mid_a = angle + (s as f32 + 0.5) * seg_angle;
-                let inner_r = radius * self.donut_ratio;
-                let r = (radius + inner_r) / 2.0;
-                let px = cx + mid_a.cos() * r;
-                let py = cy + mid_a.sin() * r;
-                let seg_w = (radius - inner_r) * 0.7;
-                renderer.draw_rounded_rect(Vec2::new(px - seg_w/2.0, py - seg_w/2.0), Vec2::new(seg_w, seg_w), color, seg_w/2.0);
-            }
+            // Thickness 0 fills the slice; donuts stroke the outer ring
+            let thickness = if self.donut_ratio > 0.0 { radius * (1.0 - self.donut_ratio) } else { 0.0 };
+            renderer.draw_arc(Vec2::new(cx, cy), radius, angle, slice_angle, thickness, color);
             angle += slice_angle;
         }
     }
     
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.size = constraints.constrain(self.size);
+        self.size
+    }
     fn set_position(&mut self, pos: Offset) { self.position = Vec2::new(pos.x, pos.y); }
     fn get_position(&self) -> Offset { Offset::new(self.position.x, self.position.y) }
     fn get_size(&self) -> Size { self.size }
@@ -456,18 +454,16 @@ impl Widget for Sparkline {
         let range = (max - min).max(0.001);
         let step = w / (self.data.len() - 1).max(1) as f32;
         
-        for i in 0..self.data.len().saturating_sub(1) {
-            let v1 = self.data[i];
-            let v2 = self.data[i + 1];
-            let x1 = pos.x + i as f32 * step;
-            let x2 = pos.x + (i + 1) as f32 * step;
-            let y1 = pos.y + h - ((v1 - min) / range) as f32 * h;
-            let y2 = pos.y + h - ((v2 - min) / range) as f32 * h;
-            let len = ((x2-x1).powi(2) + (y2-y1).powi(2)).sqrt();
-            renderer.draw_rect(Vec2::new((x1+x2)/2.0 - len/2.0, (y1+y2)/2.0 - 1.0), Vec2::new(len, 2.0), self.color);
-        }
+        let points: Vec<Vec2> = self.data.iter().enumerate()
+            .map(|(i, &v)| Vec2::new(pos.x + i as f32 * step, pos.y + h - ((v - min) / range) as f32 * h))
+            .collect();
+        renderer.draw_polyline(&points, 2.0, self.color);
     }
     
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.size = constraints.constrain(self.size);
+        self.size
+    }
     fn set_position(&mut self, pos: Offset) { self.position = Vec2::new(pos.x, pos.y); }
     fn get_position(&self) -> Offset { Offset::new(self.position.x, self.position.y) }
     fn get_size(&self) -> Size { self.size }
@@ -8,9 +8,21 @@
 use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
+use crate::focus::{FocusId, Focusable};
 use crate::widgets::core::{Widget, get_theme};
 use crate::ai::{MessageRole, ChatMessage as AiChatMessage};
 use crate::panel_style::PanelPreset;
+use crate::layout::{BoxConstraints, Offset, Size};
+use crate::text::{layout_text_with, layout_text_width};
+use super::richtext::Markdown;
+
+/// Message text size and its inset from the bubble's sides
+const CONTENT_SCALE: f32 = 14.0;
+const CONTENT_INSET: f32 = 12.0;
+/// Top of the message text, below the role line
+const CONTENT_TOP: f32 = 22.0;
+/// Space left under Markdown content
+const MARKDOWN_BOTTOM: f32 = 8.0;
 
 // =============================================================================
 // CHAT MESSAGE WIDGET
@@ -27,6 +39,9 @@ pub struct ChatMessageWidget {
     pub is_streaming: bool,
     stream_cursor_visible: bool,
     stream_cursor_timer: f32,
+    /// Finished assistant answers, rendered as Markdown (re-parsed when
+    /// `content` changes)
+    markdown: Markdown,
 }
 
 impl ChatMessageWidget {
@@ -41,6 +56,7 @@ impl ChatMessageWidget {
             is_streaming: false,
             stream_cursor_visible: true,
             stream_cursor_timer: 0.0,
+            markdown: Markdown::new("").with_font_size(CONTENT_SCALE),
         }
     }
     
@@ -55,6 +71,7 @@ impl ChatMessageWidget {
             is_streaming: false,
             stream_cursor_visible: true,
             stream_cursor_timer: 0.0,
+            markdown: Markdown::new("").with_font_size(CONTENT_SCALE),
         }
     }
     
@@ -69,6 +86,7 @@ impl ChatMessageWidget {
             is_streaming: false,
             stream_cursor_visible: true,
             stream_cursor_timer: 0.0,
+            markdown: Markdown::new("").with_font_size(CONTENT_SCALE),
         }
     }
     
@@ -83,6 +101,7 @@ impl ChatMessageWidget {
             is_streaming: true,
             stream_cursor_visible: true,
             stream_cursor_timer: 0.0,
+            markdown: Markdown::new("").with_font_size(CONTENT_SCALE),
         }
     }
     
@@ -102,20 +121,59 @@ impl ChatMessageWidget {
         }
     }
     
-    fn calculate_height(&self) -> f32 {
-        // Approximate: 20px per line, assume 40 chars per line
-        let lines = (self.content.len() / 40).max(1) as f32;
+    /// Whether the content is shown as Markdown rather than plain text
+    fn shows_markdown(&self) -> bool {
+        self.role == MessageRole::Assistant && !self.is_streaming && self.markdown.source() == self.content
+    }
+    
+    /// Height of a bubble `width` wide: the role line, the wrapped
+    /// content (20px per line) and padding
+    fn calculate_height(&self, width: f32) -> f32 {
+        let content_width = (width - CONTENT_INSET * 2.0).max(1.0);
+        if self.shows_markdown() {
+            return CONTENT_TOP + self.markdown.height_for_width(content_width) + MARKDOWN_BOTTOM;
+        }
+        let layout = layout_text_with(&self.content, Some(content_width), 20.0, |run| layout_text_width(run, CONTENT_SCALE));
         let padding = 24.0;
-        lines * 20.0 + padding
+        layout.height + padding
     }
 }
 
 impl Widget for ChatMessageWidget {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(max_size.x, self.calculate_height());
+        if self.role == MessageRole::Assistant && !self.is_streaming && self.markdown.source() != self.content {
+            self.markdown.set_source(&self.content);
+        }
+        self.size = Vec2::new(max_size.x, self.calculate_height(max_size.x));
+        if self.shows_markdown() {
+            let content_width = (max_size.x - CONTENT_INSET * 2.0).max(1.0);
+            self.markdown.layout_child(origin + Vec2::new(CONTENT_INSET, CONTENT_TOP), Vec2::new(content_width, f32::INFINITY));
+        }
         self.size
     }
+    
+    /// Laid out in place at the full width it is given; `set_position` moves it
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let width = constraints.biggest_or(Size::new(self.size.x, 0.0)).width;
+        self.layout(self.position, Vec2::new(width, 0.0));
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.markdown.set_position(position + Offset::new(CONTENT_INSET, CONTENT_TOP));
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
@@ -147,11 +205,16 @@ impl Widget for ChatMessageWidget {
         renderer.draw_text(role_text, self.position + Vec2::new(12.0, 6.0), 12.0, theme.text_secondary);
         
         // Content
+        if self.shows_markdown() {
+            self.markdown.render(renderer);
+            return;
+        }
         let mut display_content = self.content.clone();
         if self.is_streaming && self.stream_cursor_visible {
             display_content.push('▌');
         }
-        renderer.draw_text(&display_content, self.position + Vec2::new(12.0, 22.0), 14.0, theme.text);
+        let content_width = (self.size.x - CONTENT_INSET * 2.0).max(1.0);
+        renderer.draw_text_wrapped(&display_content, self.position + Vec2::new(CONTENT_INSET, CONTENT_TOP), CONTENT_SCALE, theme.text, content_width);
     }
 }
 
@@ -219,7 +282,7 @@ impl ChatView {
     fn calculate_content_height(&self) -> f32 {
         let mut height = 0.0;
         for msg in &self.messages {
-            height += msg.calculate_height() + 8.0; // gap
+            height += msg.calculate_height(self.size.x - 16.0) + 8.0; // gap
         }
         height
     }
@@ -240,7 +303,7 @@ impl Widget for ChatView {
         let mut y_offset = 0.0;
         for msg in &mut self.messages {
             let msg_origin = Vec2::new(origin.x + 8.0, origin.y + y_offset - self.scroll_offset + 8.0);
-            let msg_size = msg.layout(msg_origin, Vec2::new(max_size.x - 16.0, 0.0));
+            let msg_size = msg.layout_child(msg_origin, Vec2::new(max_size.x - 16.0, 0.0));
             y_offset += msg_size.y + 8.0;
         }
         
@@ -250,6 +313,25 @@ impl Widget for ChatView {
         
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.biggest_or(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    /// Messages are placed in absolute coordinates, so they are laid out again
+    fn set_position(&mut self, position: Offset) {
+        self.layout(Vec2::new(position.x, position.y), self.size);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         // Handle scroll wheel
@@ -304,6 +386,8 @@ pub struct PromptInput {
     pub text: String,
     pub placeholder: String,
     pub focused: bool,
+    /// Identifies the field to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
     pub cursor_pos: usize,
     cursor_visible: bool,
     cursor_timer: f32,
@@ -319,6 +403,7 @@ impl PromptInput {
             text: String::new(),
             placeholder: "Type a message...".to_string(),
             focused: false,
+            focus_id: FocusId::new(),
             cursor_pos: 0,
             cursor_visible: true,
             cursor_timer: 0.0,
@@ -354,12 +439,58 @@ impl Default for PromptInput {
     }
 }
 
+impl Focusable for PromptInput {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+    }
+}
+
 impl Widget for PromptInput {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
         self.size = Vec2::new(max_size.x, 44.0);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let width = constraints.biggest_or(Size::new(self.size.x, 44.0)).width;
+        let size = Size::new(width, constraints.constrain_height(44.0));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    /// Only the first pass matters: the size follows the width it is given
+    fn needs_layout(&self) -> bool {
+        self.size == Vec2::ZERO
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         match event {
@@ -375,6 +506,15 @@ impl Widget for PromptInput {
                 self.focused = inside;
                 return inside;
             },
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::Ime(winit::event::Ime::Commit(text)), .. } if self.focused => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    self.text.insert(self.cursor_pos, c);
+                    self.cursor_pos += c.len_utf8();
+                }
+                self.cursor_visible = true;
+                self.cursor_timer = 0.0;
+                return true;
+            },
             winit::event::Event::WindowEvent { event: winit::event::WindowEvent::KeyboardInput { event: key_event, .. }, .. } => {
                 if self.focused && key_event.state.is_pressed() {
                     // Handle backspace
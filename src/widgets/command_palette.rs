@@ -2,13 +2,105 @@
 //!
 //! Quick action command palette (Ctrl+K / Cmd+K):
 //! - Fuzzy search commands
-//! - Keyboard navigation
+//! - Recently used commands ranked first
+//! - Keyboard navigation and paging
 //! - Action execution
 
 use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
 use crate::widgets::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, Offset, Size};
+use crate::persistence::CommandUsage;
+
+/// Rows visible at once (also the PageUp/PageDown step)
+const VISIBLE_ROWS: usize = 7;
+const ITEM_HEIGHT: f32 = 48.0;
+const LIST_TOP: f32 = 56.0;
+
+// =============================================================================
+// FUZZY MATCHING
+// =============================================================================
+
+/// Result of fuzzy-matching a query against some text
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i32,
+    /// Char indices of the matched characters, for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` as a case-insensitive subsequence of `text`
+///
+/// Finds the best-scoring alignment: consecutive matches, matches at word
+/// starts (after a separator or at a camelCase boundary) and an early first
+/// match score higher; gaps cost.
+/// Whitespace in the query is ignored. Returns `None` when some query
+/// character can't be found in order.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch::default());
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    
+    let (n, m) = (chars.len(), query.len());
+    let char_score = |i: usize| {
+        let word_start = i == 0 || !chars[i - 1].is_alphanumeric() || (chars[i].is_uppercase() && chars[i - 1].is_lowercase());
+        16 + if word_start { 12 } else { 0 }
+    };
+    
+    // best[k][i]: best (score, previous index) with query[k] matched at text[i]
+    let mut best: Vec<Vec<Option<(i32, usize)>>> = vec![vec![None; n]; m];
+    for i in (0..n).filter(|&i| lower[i] == query[0]) {
+        best[0][i] = Some((char_score(i) - i.min(6) as i32 * 2, 0));
+    }
+    for k in 1..m {
+        for i in (k..n).filter(|&i| lower[i] == query[k]) {
+            best[k][i] = (k - 1..i)
+                .filter_map(|j| best[k - 1][j].map(|(score, _)| (score, j)))
+                .map(|(score, j)| {
+                    let gap = i - j - 1;
+                    let link = if gap == 0 { 10 } else { -(gap.min(6) as i32) };
+                    (score + link + char_score(i), j)
+                })
+                .max_by_key(|(score, _)| *score);
+        }
+    }
+    
+    // Pick the best end and walk back to recover the positions
+    let (end, (mut score, _)) = (0..n)
+        .filter_map(|i| best[m - 1][i].map(|entry| (i, entry)))
+        .max_by_key(|(_, (score, _))| *score)?;
+    if m == n {
+        score += 20;
+    }
+    let mut positions = vec![end; m];
+    for k in (1..m).rev() {
+        positions[k - 1] = best[k][positions[k]].map_or(0, |(_, prev)| prev);
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Split an optional `category:` prefix off a palette query
+/// 
+/// The prefix only counts if it starts one of the `commands`' categories,
+/// so a query like "glass quality: full" still matches a label with a
+/// colon in it.
+fn split_category<'a>(query: &'a str, commands: &[Command]) -> (Option<&'a str>, &'a str) {
+    match query.split_once(':') {
+        Some((category, rest)) if !category.trim().is_empty() => {
+            let prefix = category.trim().to_lowercase();
+            match commands.iter().any(|cmd| cmd.category.to_lowercase().starts_with(&prefix)) {
+                true => (Some(category.trim()), rest.trim_start()),
+                false => (None, query),
+            }
+        }
+        _ => (None, query),
+    }
+}
 
 // =============================================================================
 // COMMAND
@@ -59,24 +151,29 @@ impl Command {
     
     /// Check if command matches search query
     pub fn matches(&self, query: &str) -> bool {
-        if query.is_empty() { return true; }
-        let query_lower = query.to_lowercase();
-        self.label.to_lowercase().contains(&query_lower) ||
-        self.description.to_lowercase().contains(&query_lower) ||
-        self.category.to_lowercase().contains(&query_lower)
+        self.fuzzy_match(query).is_some()
     }
     
     /// Get match score for sorting (higher is better)
     pub fn match_score(&self, query: &str) -> i32 {
-        if query.is_empty() { return 0; }
-        let query_lower = query.to_lowercase();
-        let label_lower = self.label.to_lowercase();
-        
-        if label_lower == query_lower { return 100; }
-        if label_lower.starts_with(&query_lower) { return 80; }
-        if label_lower.contains(&query_lower) { return 60; }
-        if self.description.to_lowercase().contains(&query_lower) { return 40; }
-        0
+        self.fuzzy_match(query).map_or(0, |m| m.score)
+    }
+    
+    /// Fuzzy-match the label, falling back to description and category
+    /// 
+    /// Matches outside the label score half and carry no highlight positions.
+    pub fn fuzzy_match(&self, query: &str) -> Option<FuzzyMatch> {
+        let label = fuzzy_match(query, &self.label);
+        let other = [&self.description, &self.category].into_iter()
+            .filter_map(|text| fuzzy_match(query, text))
+            .map(|m| m.score / 2)
+            .max();
+        match (label, other) {
+            (Some(label), Some(other)) if other > label.score => Some(FuzzyMatch { score: other, positions: Vec::new() }),
+            (Some(label), _) => Some(label),
+            (None, Some(other)) => Some(FuzzyMatch { score: other, positions: Vec::new() }),
+            (None, None) => None,
+        }
     }
 }
 
@@ -85,6 +182,11 @@ impl Command {
 // =============================================================================
 
 /// Quick command palette widget
+///
+/// Results are ranked by fuzzy score plus a bonus for recently and
+/// frequently used commands; with an empty query recents come first.
+/// A `category:` prefix (e.g. `debug: insp`) limits results to categories
+/// starting with it.
 pub struct CommandPalette {
     pub id: WidgetId,
     pub position: Vec2,
@@ -94,6 +196,11 @@ pub struct CommandPalette {
     pub commands: Vec<Command>,
     pub filtered: Vec<usize>,  // Indices into commands
     pub selected_index: usize,
+    /// Matched label characters per entry of `filtered`
+    highlights: Vec<Vec<usize>>,
+    scroll_offset: usize,
+    recents: Vec<CommandUsage>,
+    pub max_recents: usize,
     cursor_pos: usize,
     cursor_visible: bool,
     cursor_timer: f32,
@@ -111,6 +218,10 @@ impl CommandPalette {
             commands: Vec::new(),
             filtered: Vec::new(),
             selected_index: 0,
+            highlights: Vec::new(),
+            scroll_offset: 0,
+            recents: Vec::new(),
+            max_recents: 20,
             cursor_pos: 0,
             cursor_visible: true,
             cursor_timer: 0.0,
@@ -136,12 +247,46 @@ impl CommandPalette {
         self
     }
     
+    /// Restore usage history (e.g. from `AppState::command_recents`)
+    pub fn with_recents(mut self, recents: Vec<CommandUsage>) -> Self {
+        self.set_recents(recents);
+        self
+    }
+    
+    pub fn set_recents(&mut self, mut recents: Vec<CommandUsage>) {
+        recents.truncate(self.max_recents);
+        self.recents = recents;
+        self.update_filtered();
+    }
+    
+    /// Usage history, most recent first, for persisting
+    pub fn recents(&self) -> &[CommandUsage] {
+        &self.recents
+    }
+    
+    /// Note that a command ran, moving it to the front of the recents
+    pub fn record_use(&mut self, id: &str) {
+        let mut usage = match self.recents.iter().position(|u| u.id == id) {
+            Some(index) => self.recents.remove(index),
+            None => CommandUsage::new(id),
+        };
+        usage.count += 1;
+        self.recents.insert(0, usage);
+        self.recents.truncate(self.max_recents);
+    }
+    
+    /// Matched label characters for the `n`th filtered command
+    pub fn highlight_positions(&self, n: usize) -> &[usize] {
+        self.highlights.get(n).map_or(&[], Vec::as_slice)
+    }
+    
     /// Show the palette
     pub fn show(&mut self) {
         self.visible = true;
         self.query.clear();
         self.cursor_pos = 0;
         self.selected_index = 0;
+        self.scroll_offset = 0;
         self.update_filtered();
     }
     
@@ -157,36 +302,71 @@ impl CommandPalette {
     
     /// Execute the selected command
     pub fn execute_selected(&mut self) {
-        if let Some(idx) = self.filtered.get(self.selected_index) {
+        if let Some(&idx) = self.filtered.get(self.selected_index) {
+            let cmd = self.commands[idx].clone();
+            self.record_use(&cmd.id);
             if let Some(callback) = &mut self.on_execute {
-                let cmd = self.commands[*idx].clone();
                 callback(&cmd);
             }
         }
         self.hide();
     }
     
+    /// Move the selection, scrolling to keep it in view
+    pub fn select(&mut self, index: usize) {
+        self.selected_index = index.min(self.filtered.len().saturating_sub(1));
+        if self.selected_index < self.scroll_offset {
+            self.scroll_offset = self.selected_index;
+        } else if self.selected_index >= self.scroll_offset + VISIBLE_ROWS {
+            self.scroll_offset = self.selected_index + 1 - VISIBLE_ROWS;
+        }
+    }
+    
+    /// Position in the recents list, if the command was used before
+    fn recent_rank(&self, id: &str) -> Option<usize> {
+        self.recents.iter().position(|u| u.id == id)
+    }
+    
+    /// Ranking bonus for recently and frequently used commands
+    fn usage_bonus(&self, id: &str) -> i32 {
+        match self.recent_rank(id) {
+            Some(rank) => (20 - 2 * rank as i32).max(0) + (self.recents[rank].count as i32 * 3).min(15),
+            None => 0,
+        }
+    }
+    
     /// Update filtered commands based on query
     fn update_filtered(&mut self) {
-        self.filtered = self.commands
+        let (category, text) = split_category(&self.query, &self.commands);
+        let category = category.map(str::to_lowercase);
+        
+        let mut scored: Vec<(usize, i32, Vec<usize>)> = self.commands
             .iter()
             .enumerate()
-            .filter(|(_, cmd)| cmd.matches(&self.query))
-            .map(|(i, _)| i)
+            .filter(|(_, cmd)| category.as_ref().is_none_or(|c| cmd.category.to_lowercase().starts_with(c.as_str())))
+            .filter_map(|(i, cmd)| {
+                let m = cmd.fuzzy_match(text)?;
+                Some((i, m.score + self.usage_bonus(&cmd.id), m.positions))
+            })
             .collect();
         
-        // Sort by match score
-        let query = self.query.clone();
-        self.filtered.sort_by(|a, b| {
-            let score_a = self.commands[*a].match_score(&query);
-            let score_b = self.commands[*b].match_score(&query);
-            score_b.cmp(&score_a)
-        });
+        if text.trim().is_empty() {
+            // Recents first, then everything else in registration order
+            scored.sort_by_key(|(i, _, _)| (self.recent_rank(&self.commands[*i].id).unwrap_or(usize::MAX), *i));
+        } else {
+            // Stable, so equal scores keep registration order
+            scored.sort_by_key(|(_, score, _)| std::cmp::Reverse(*score));
+        }
+        
+        self.filtered = scored.iter().map(|(i, _, _)| *i).collect();
+        self.highlights = scored.into_iter().map(|(_, _, positions)| positions).collect();
         
         // Reset selection if out of bounds
         if self.selected_index >= self.filtered.len() {
             self.selected_index = 0;
         }
+        self.scroll_offset = 0;
+        self.select(self.selected_index);
     }
     
     /// Center on screen
@@ -208,11 +388,40 @@ impl CommandPalette {
             Command::new("clear_chat", "Clear Chat").with_icon("🗑").with_category("AI"),
             Command::new("toggle_theme", "Toggle Theme").with_icon("🎨").with_category("Settings"),
             Command::new("toggle_sound", "Toggle Sounds").with_icon("🔊").with_category("Settings"),
+            Command::new("cycle_glass_quality", "Glass Quality: Full / Light / Flat").with_icon("◐").with_shortcut("F4").with_category("Settings"),
+            Command::new("toggle_frame_stats", "Toggle FPS Counter").with_icon("⏱").with_shortcut("F2").with_category("Debug"),
+            Command::new("toggle_inspector", "Toggle Widget Inspector").with_icon("🔍").with_shortcut("F12").with_category("Debug"),
+            Command::new("toggle_layout_debug", "Toggle Layout Bounds").with_icon("⬚").with_shortcut("Ctrl+Shift+L").with_category("Debug"),
+            Command::new("toggle_event_recording", "Start/Stop Event Recording").with_icon("⏺").with_shortcut("Ctrl+Shift+R").with_category("Debug"),
         ]);
         self
     }
 }
 
+/// Draw `text`, recolouring the chars at `positions` (char indices)
+fn draw_highlighted(renderer: &mut GlassRenderer, text: &str, positions: &[usize], pos: Vec2, scale: f32, color: Vec4, highlight: Vec4) {
+    if positions.is_empty() {
+        renderer.draw_text(text, pos, scale, color);
+        return;
+    }
+    
+    // Draw runs of matched / unmatched chars side by side
+    let mut run_start = 0;
+    let mut x = pos.x;
+    let mut chars = text.char_indices().map(|(byte, _)| byte).enumerate().peekable();
+    while let Some((n, _)) = chars.next() {
+        let matched = positions.contains(&n);
+        let next = chars.peek().map(|&(m, byte)| (positions.contains(&m), byte));
+        if next.is_none_or(|(next_matched, _)| next_matched != matched) {
+            let end = next.map_or(text.len(), |(_, byte)| byte);
+            let run = &text[run_start..end];
+            renderer.draw_text(run, Vec2::new(x, pos.y), scale, if matched { highlight } else { color });
+            x += renderer.measure_text(run, scale).width;
+            run_start = end;
+        }
+    }
+}
+
 impl Default for CommandPalette {
     fn default() -> Self {
         Self::new()
@@ -224,11 +433,32 @@ impl Widget for CommandPalette {
         // Palette uses its own positioning
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        // Palette uses its own positioning; `set_position` leaves it alone
+        constraints.constrain(Size::new(self.size.x, self.size.y))
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         if !self.visible { return false; }
         
         match event {
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::Ime(winit::event::Ime::Commit(text)), .. } => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    self.query.insert(self.cursor_pos, c);
+                    self.cursor_pos += c.len_utf8();
+                }
+                self.update_filtered();
+                return true;
+            },
             winit::event::Event::WindowEvent { 
                 event: winit::event::WindowEvent::KeyboardInput { event: key_event, .. }, 
                 .. 
@@ -246,18 +476,21 @@ impl Widget for CommandPalette {
                         return true;
                     }
                     
-                    // Arrow navigation
-                    if let winit::keyboard::Key::Named(winit::keyboard::NamedKey::ArrowUp) = key_event.logical_key {
-                        if self.selected_index > 0 {
-                            self.selected_index -= 1;
-                        }
-                        return true;
-                    }
-                    if let winit::keyboard::Key::Named(winit::keyboard::NamedKey::ArrowDown) = key_event.logical_key {
-                        if self.selected_index < self.filtered.len().saturating_sub(1) {
-                            self.selected_index += 1;
+                    // Arrow navigation and paging
+                    if let winit::keyboard::Key::Named(key) = key_event.logical_key {
+                        let target = match key {
+                            winit::keyboard::NamedKey::ArrowUp => Some(self.selected_index.saturating_sub(1)),
+                            winit::keyboard::NamedKey::ArrowDown => Some(self.selected_index + 1),
+                            winit::keyboard::NamedKey::PageUp => Some(self.selected_index.saturating_sub(VISIBLE_ROWS)),
+                            winit::keyboard::NamedKey::PageDown => Some(self.selected_index + VISIBLE_ROWS),
+                            winit::keyboard::NamedKey::Home => Some(0),
+                            winit::keyboard::NamedKey::End => Some(usize::MAX),
+                            _ => None,
+                        };
+                        if let Some(target) = target {
+                            self.select(target);
+                            return true;
                         }
-                        return true;
                     }
                     
                     // Backspace
@@ -292,14 +525,14 @@ impl Widget for CommandPalette {
                     ..
                 }, .. 
             } => {
-                let list_y = self.position.y + 52.0;
-                let item_height = 48.0;
+                let list_y = self.position.y + LIST_TOP;
                 
                 if mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x {
-                    for i in 0..self.filtered.len().min(7) {
-                        let y = list_y + i as f32 * item_height;
-                        if mouse_pos.y >= y && mouse_pos.y <= y + item_height {
-                            self.selected_index = i;
+                    let rows = self.filtered.len().saturating_sub(self.scroll_offset).min(VISIBLE_ROWS);
+                    for i in 0..rows {
+                        let y = list_y + i as f32 * ITEM_HEIGHT;
+                        if mouse_pos.y >= y && mouse_pos.y <= y + ITEM_HEIGHT {
+                            self.selected_index = self.scroll_offset + i;
                             self.execute_selected();
                             return true;
                         }
@@ -378,14 +611,14 @@ impl Widget for CommandPalette {
         }
         
         // Results
-        let list_y = self.position.y + 56.0;
-        let item_height = 48.0;
+        let list_y = self.position.y + LIST_TOP;
+        let show_recents = split_category(&self.query, &self.commands).1.trim().is_empty();
         
-        for (i, &cmd_idx) in self.filtered.iter().take(7).enumerate() {
+        for (i, &cmd_idx) in self.filtered.iter().enumerate().skip(self.scroll_offset).take(VISIBLE_ROWS) {
             let cmd = &self.commands[cmd_idx];
-            let y = list_y + i as f32 * item_height;
+            let y = list_y + (i - self.scroll_offset) as f32 * ITEM_HEIGHT;
             let item_pos = Vec2::new(self.position.x + 8.0, y);
-            let item_size = Vec2::new(self.size.x - 16.0, item_height - 4.0);
+            let item_size = Vec2::new(self.size.x - 16.0, ITEM_HEIGHT - 4.0);
             
             // Selected highlight
             if i == self.selected_index {
@@ -402,21 +635,39 @@ impl Widget for CommandPalette {
                 renderer.draw_text(icon, item_pos + Vec2::new(12.0, 12.0), 18.0, theme.text);
             }
             
-            // Label
-            renderer.draw_text(&cmd.label, item_pos + Vec2::new(44.0, 8.0), 14.0, theme.text);
+            // Label with matched characters highlighted
+            draw_highlighted(renderer, &cmd.label, self.highlight_positions(i), item_pos + Vec2::new(44.0, 8.0), 14.0, theme.text, theme.primary);
             
             // Description
             if !cmd.description.is_empty() {
                 renderer.draw_text(&cmd.description, item_pos + Vec2::new(44.0, 26.0), 11.0, theme.text_secondary);
             }
             
+            // Category (or "Recent") above the shortcut
+            let tag = if show_recents && self.recent_rank(&cmd.id).is_some() { "Recent" } else { cmd.category.as_str() };
+            let tag_x = item_pos.x + item_size.x - tag.len() as f32 * 6.0 - 12.0;
+            renderer.draw_text(tag, Vec2::new(tag_x, item_pos.y + 6.0), 10.0, Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.7));
+            
             // Shortcut
             if let Some(shortcut) = &cmd.shortcut {
                 let shortcut_x = item_pos.x + item_size.x - shortcut.len() as f32 * 7.0 - 12.0;
-                renderer.draw_text(shortcut, Vec2::new(shortcut_x, item_pos.y + 16.0), 11.0, theme.text_secondary);
+                renderer.draw_text(shortcut, Vec2::new(shortcut_x, item_pos.y + 24.0), 11.0, theme.text_secondary);
             }
         }
         
+        // Scroll position
+        if self.filtered.len() > VISIBLE_ROWS {
+            let track_height = VISIBLE_ROWS as f32 * ITEM_HEIGHT;
+            let thumb_height = track_height * VISIBLE_ROWS as f32 / self.filtered.len() as f32;
+            let thumb_y = list_y + track_height * self.scroll_offset as f32 / self.filtered.len() as f32;
+            renderer.draw_rounded_rect(
+                Vec2::new(self.position.x + self.size.x - 6.0, thumb_y),
+                Vec2::new(3.0, thumb_height),
+                Vec4::new(theme.text_secondary.x, theme.text_secondary.y, theme.text_secondary.z, 0.4),
+                1.5
+            );
+        }
+        
         // No results message
         if self.filtered.is_empty() && !self.query.is_empty() {
             renderer.draw_text(
@@ -459,4 +710,67 @@ mod tests {
         
         assert_eq!(palette.filtered.len(), 1);
     }
+    
+    #[test]
+    fn test_fuzzy_match_positions_and_ranking() {
+        let m = fuzzy_match("tgi", "Toggle Widget Inspector").unwrap();
+        assert_eq!(m.positions, vec![0, 2, 14]);
+        assert!(fuzzy_match("xyz", "Toggle Widget Inspector").is_none());
+        
+        // Word starts and consecutive runs beat scattered matches
+        let word_starts = fuzzy_match("np", "New Panel").unwrap().score;
+        let scattered = fuzzy_match("np", "Open Map").unwrap().score;
+        assert!(word_starts > scattered);
+        assert!(fuzzy_match("save", "Save").unwrap().score > fuzzy_match("save", "Save Workspace").unwrap().score);
+        
+        let mut palette = CommandPalette::new().with_dashboard_commands();
+        palette.query = "tfps".to_string();
+        palette.update_filtered();
+        assert_eq!(palette.commands[palette.filtered[0]].id, "toggle_frame_stats");
+        assert!(!palette.highlight_positions(0).is_empty());
+    }
+    
+    #[test]
+    fn test_recents_rank_first_and_persist() {
+        let mut palette = CommandPalette::new().with_dashboard_commands();
+        palette.record_use("toggle_theme");
+        palette.record_use("new_agent");
+        palette.record_use("toggle_theme");
+        assert_eq!(palette.recents()[0], CommandUsage { id: "toggle_theme".to_string(), count: 2 });
+        
+        palette.show();
+        let ids: Vec<_> = palette.filtered.iter().take(2).map(|&i| palette.commands[i].id.as_str()).collect();
+        assert_eq!(ids, vec!["toggle_theme", "new_agent"]);
+        
+        // Equal fuzzy scores are broken by usage
+        palette.query = "toggle".to_string();
+        palette.update_filtered();
+        assert_eq!(palette.commands[palette.filtered[0]].id, "toggle_theme");
+        
+        let restored = CommandPalette::new().with_dashboard_commands().with_recents(palette.recents().to_vec());
+        assert_eq!(restored.commands[restored.filtered[0]].id, "toggle_theme");
+    }
+    
+    #[test]
+    fn test_category_filter_and_paging() {
+        let mut palette = CommandPalette::new().with_dashboard_commands();
+        palette.query = "debug:".to_string();
+        palette.update_filtered();
+        assert_eq!(palette.filtered.len(), 4);
+        assert!(palette.filtered.iter().all(|&i| palette.commands[i].category == "Debug"));
+        
+        // Not a category: the colon is part of a label
+        palette.query = "Glass Quality: Full".to_string();
+        palette.update_filtered();
+        assert_eq!(palette.commands[palette.filtered[0]].id, "cycle_glass_quality");
+        
+        palette.query.clear();
+        palette.update_filtered();
+        palette.select(VISIBLE_ROWS + 2);
+        assert_eq!(palette.scroll_offset, 3);
+        palette.select(usize::MAX);
+        assert_eq!(palette.selected_index, palette.filtered.len() - 1);
+        palette.select(0);
+        assert_eq!(palette.scroll_offset, 0);
+    }
 }
@@ -1,115 +1,186 @@
 //! GlassUI Container Widgets
 //!
-//! Container widgets that manage child content: ScrollArea, TabBar
+//! Container widgets that manage child content: ScrollArea, TabBar, CachedWidget, Opacity, Visible
 
 use glam::{Vec2, Vec4};
 use winit::event::{ElementState, MouseButton};
-use crate::renderer::GlassRenderer;
-use super::core::{Widget, get_theme};
+use crate::renderer::{GlassRenderer, RenderCache};
+use crate::layout::{BoxConstraints, EdgeInsets, Rect, Offset, Size};
+use crate::state::State;
+use crate::widget_id::WidgetId;
+use super::core::{Widget, UpdateState, get_theme, mount, unmount, UNBOUNDED_EXTENT};
+use super::scroll::ScrollBar;
 
 // =============================================================================
 // SCROLL AREA
 // =============================================================================
 
-/// Scrollable container with optional scrollbar
+/// Scrollable container with scrollbars
+/// 
+/// Scrolls vertically, and horizontally when the child is wider than the
+/// view (shift+wheel or a trackpad's sideways swipe). The child is laid out
+/// in the view's width unless `with_horizontal_scroll` lets it be as wide as
+/// it likes.
+/// 
+/// Wheel input eases to its destination; the scrollbar thumbs can be
+/// dragged, and clicking a track pages by one view.
 pub struct ScrollArea {
     pub position: Vec2,
     pub size: Vec2,
     pub child: Box<dyn Widget>,
-    pub scroll_offset: f32,
+    pub scroll_x: ScrollBar,
+    pub scroll_y: ScrollBar,
     pub content_height: f32,
-    pub scrollbar_hovered: bool,
-    pub scrollbar_dragging: bool,
-    pub drag_start_y: f32,
-    pub drag_start_offset: f32,
+    pub content_width: f32,
+    /// Lay the child out at its natural width instead of the view's
+    pub horizontal: bool,
+    /// Shift is held, so the wheel scrolls sideways
+    shift: bool,
+    /// Scroll offsets the child was last laid out at
+    layout_offset: Option<Vec2>,
 }
 
+/// Room kept for a scrollbar along the right and bottom edges
+const SCROLLBAR_SPACE: f32 = 12.0;
+
 impl ScrollArea {
     pub fn new(child: Box<dyn Widget>) -> Self {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
             child,
-            scroll_offset: 0.0,
+            scroll_x: ScrollBar::new(),
+            scroll_y: ScrollBar::new(),
             content_height: 0.0,
-            scrollbar_hovered: false,
-            scrollbar_dragging: false,
-            drag_start_y: 0.0,
-            drag_start_offset: 0.0,
+            content_width: 0.0,
+            horizontal: false,
+            shift: false,
+            layout_offset: None,
         }
     }
-}
-
-impl Widget for ScrollArea {
-    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
-        self.position = origin;
-        self.size = max_size;
-        
-        let child_origin = origin + Vec2::new(0.0, -self.scroll_offset);
-        let child_size = self.child.layout(child_origin, Vec2::new(max_size.x - 12.0, 10000.0));
-        self.content_height = child_size.y;
-        
-        let max_scroll = (self.content_height - self.size.y).max(0.0);
-        self.scroll_offset = self.scroll_offset.clamp(0.0, max_scroll);
-        
-        self.size
+    
+    pub fn with_horizontal_scroll(mut self) -> Self {
+        self.horizontal = true;
+        self
     }
-
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
-
-        // Pass to child first
-        if inside && self.child.handle_event(event, mouse_pos) {
-            return true;
+    
+    /// Both scroll offsets, as drawn this frame
+    pub fn offset(&self) -> Vec2 {
+        Vec2::new(self.scroll_x.offset(), self.scroll_y.offset())
+    }
+    
+    /// Ease both offsets to `offset` (clamped to the content)
+    pub fn scroll_to(&mut self, offset: Vec2) {
+        let max_scroll = self.max_scroll();
+        self.scroll_x.scroll_to(offset.x, max_scroll.x);
+        self.scroll_y.scroll_to(offset.y, max_scroll.y);
+    }
+    
+    /// Ease just far enough that `rect` (in screen coordinates at the
+    /// current offset) is in view
+    pub fn scroll_rect_into_view(&mut self, rect: Rect) {
+        let viewport = self.viewport();
+        let max_scroll = self.max_scroll();
+        let content = rect.min - self.position + self.offset();
+        let size = rect.size();
+        self.scroll_x.reveal(content.x, size.x, viewport.x, max_scroll.x);
+        self.scroll_y.reveal(content.y, size.y, viewport.y, max_scroll.y);
+    }
+    
+    /// Ease the descendant whose `widget_id` is `id` into view; returns
+    /// whether it was found
+    pub fn scroll_into_view(&mut self, id: WidgetId) -> bool {
+        let Some(widget) = self.child.find_by_id(id) else {
+            return false;
+        };
+        let (position, size) = (widget.get_position(), widget.get_size());
+        let rect = Rect::new(Vec2::new(position.x, position.y), Vec2::new(size.width, size.height));
+        self.scroll_rect_into_view(rect);
+        true
+    }
+    
+    /// Visible part of the content: the width left of the vertical
+    /// scrollbar's space, and the height above the horizontal scrollbar
+    /// while it shows
+    pub fn viewport(&self) -> Vec2 {
+        let width = (self.size.x - SCROLLBAR_SPACE).max(0.0);
+        let hbar = self.content_width > width;
+        Vec2::new(width, (self.size.y - if hbar { SCROLLBAR_SPACE } else { 0.0 }).max(0.0))
+    }
+    
+    /// Furthest each offset can scroll
+    pub fn max_scroll(&self) -> Vec2 {
+        let viewport = self.viewport();
+        Vec2::new(
+            (self.content_width - viewport.x).max(0.0),
+            (self.content_height - viewport.y).max(0.0),
+        )
+    }
+    
+    /// Clamp the offsets after the content or view changed size
+    fn clamp_offsets(&mut self) {
+        let max_scroll = self.max_scroll();
+        self.scroll_x.clamp(max_scroll.x);
+        self.scroll_y.clamp(max_scroll.y);
+        self.layout_offset = Some(self.offset());
+    }
+    
+    /// Scrollbar drag and mouse wheel, after the child declined the event
+    fn handle_scroll_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::ModifiersChanged(modifiers), .. } = event {
+            self.shift = modifiers.state().shift_key();
+            return false;
         }
+        let inside = Rect::new(self.position, self.size).contains(mouse_pos);
+        let viewport = self.viewport();
+        let max_scroll = self.max_scroll();
         
-        // Scrollbar handling
-        let scrollbar_x = self.position.x + self.size.x - 10.0;
-        let visible_ratio = (self.size.y / self.content_height).min(1.0);
-        let thumb_height = (self.size.y * visible_ratio).max(30.0);
-        let max_scroll = (self.content_height - self.size.y).max(0.0);
-        let scroll_ratio = if max_scroll > 0.0 { self.scroll_offset / max_scroll } else { 0.0 };
-        let thumb_y = self.position.y + scroll_ratio * (self.size.y - thumb_height);
-        
-        let in_scrollbar = mouse_pos.x >= scrollbar_x && mouse_pos.x <= scrollbar_x + 10.0 &&
-                           mouse_pos.y >= thumb_y && mouse_pos.y <= thumb_y + thumb_height;
-        
-        self.scrollbar_hovered = in_scrollbar;
+        // Scrollbars: positions along each track, and whether the pointer
+        // is across it
+        let along = mouse_pos - self.position;
+        let in_vbar = max_scroll.y > 0.0 && along.x >= self.size.x - 10.0 && along.x <= self.size.x;
+        let in_hbar = max_scroll.x > 0.0 && along.y >= self.size.y - 10.0 && along.y <= self.size.y;
+        self.scroll_y.hovered = in_vbar && self.scroll_y.thumb_contains(along.y, viewport.y, self.content_height, viewport.y);
+        self.scroll_x.hovered = in_hbar && self.scroll_x.thumb_contains(along.x, viewport.x, self.content_width, viewport.x);
         
         if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } = event {
-            if *state == ElementState::Pressed && in_scrollbar {
-                self.scrollbar_dragging = true;
-                self.drag_start_y = mouse_pos.y;
-                self.drag_start_offset = self.scroll_offset;
-                return true;
-            } else if *state == ElementState::Released {
-                self.scrollbar_dragging = false;
+            if *state == ElementState::Pressed {
+                // Grab a thumb, or page towards the press on its track
+                if in_vbar && self.scroll_y.press(along.y, viewport.y, self.content_height, viewport.y) {
+                    return true;
+                }
+                if in_hbar && self.scroll_x.press(along.x, viewport.x, self.content_width, viewport.x) {
+                    return true;
+                }
+            } else {
+                self.scroll_y.release();
+                self.scroll_x.release();
             }
         }
         
-        if self.scrollbar_dragging {
-            let delta_y = mouse_pos.y - self.drag_start_y;
-            let scroll_range = self.size.y - thumb_height;
-            if scroll_range > 0.0 {
-                let delta_scroll = (delta_y / scroll_range) * max_scroll;
-                self.scroll_offset = (self.drag_start_offset + delta_scroll).clamp(0.0, max_scroll);
-            }
+        if self.scroll_y.drag(along.y, viewport.y, self.content_height, viewport.y) {
+            return true;
+        }
+        if self.scroll_x.drag(along.x, viewport.x, self.content_width, viewport.x) {
             return true;
         }
         
-        // Mouse wheel
+        // Mouse wheel, eased; shift turns a plain wheel sideways
         if inside {
             if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseWheel { delta, .. }, .. } = event {
                 let scroll_amount = match delta {
-                    winit::event::MouseScrollDelta::LineDelta(_, y) => y * 40.0,
-                    winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
+                    winit::event::MouseScrollDelta::LineDelta(x, y) => Vec2::new(*x, *y) * 40.0,
+                    winit::event::MouseScrollDelta::PixelDelta(pos) => Vec2::new(pos.x as f32, pos.y as f32),
+                };
+                let scroll_amount = if self.shift && scroll_amount.x == 0.0 {
+                    Vec2::new(scroll_amount.y, 0.0)
+                } else {
+                    scroll_amount
                 };
                 
-                let old_scroll = self.scroll_offset;
-                self.scroll_offset = (self.scroll_offset - scroll_amount).clamp(0.0, max_scroll);
-                
-                if (self.scroll_offset - old_scroll).abs() > 0.1 {
+                let moved_x = self.scroll_x.scroll_by(-scroll_amount.x, max_scroll.x);
+                let moved_y = self.scroll_y.scroll_by(-scroll_amount.y, max_scroll.y);
+                if moved_x || moved_y {
                     return true;
                 }
             }
@@ -117,15 +188,117 @@ impl Widget for ScrollArea {
         
         false
     }
+    
+    /// Max width the child is laid out in
+    fn child_max_width(&self, width: f32) -> f32 {
+        if self.horizontal { f32::INFINITY } else { (width - SCROLLBAR_SPACE).max(0.0) }
+    }
+}
+
+impl Widget for ScrollArea {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        self.size = max_size;
+        
+        let child_origin = origin - self.offset();
+        let child_width = self.child_max_width(max_size.x).min(UNBOUNDED_EXTENT);
+        let child_size = self.child.layout_child(child_origin, Vec2::new(child_width, UNBOUNDED_EXTENT));
+        self.content_width = child_size.x;
+        self.content_height = child_size.y;
+        self.clamp_offsets();
+        
+        // The child was placed at the unclamped offset
+        if child_origin != origin - self.offset() {
+            self.child.layout_child(origin - self.offset(), Vec2::new(child_width, UNBOUNDED_EXTENT));
+        }
+        
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        // The child scrolls, so only its width may be bounded
+        let child_width = self.child_max_width(constraints.max_width);
+        let child_size = self.child.layout_child_with_constraints(BoxConstraints::new(0.0, child_width, 0.0, f32::INFINITY));
+        self.content_width = child_size.width;
+        self.content_height = child_size.height;
+        
+        let natural = Size::new(child_size.width + SCROLLBAR_SPACE, child_size.height);
+        let size = constraints.biggest_or(natural);
+        self.size = Vec2::new(size.width, size.height);
+        self.clamp_offsets();
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position - Offset::from(self.offset()));
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn clips_content(&self) -> bool {
+        true
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_offset != Some(self.offset()) || self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.scroll_x.is_animating() || self.scroll_y.is_animating() || self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        // Content is clipped; only the frame extends past our rect
+        Some(Rect::new(self.position, self.size).inset(-1.0))
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
+                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
+
+        // Pass to child first
+        if inside && self.child.handle_event(event, mouse_pos) {
+            return true;
+        }
+        
+        self.handle_scroll_event(event, mouse_pos)
+    }
+    
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.handle_scroll_event(event, mouse_pos)
+    }
 
     fn update(&mut self, dt: f32) {
+        self.scroll_x.update(dt);
+        self.scroll_y.update(dt);
         self.child.update(dt);
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.scroll_x.update(dt);
+        self.scroll_y.update(dt);
+        self.child.split_update(dt, jobs);
+    }
+
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
         
-        // Draw background frame BEFORE scissor (so border is always visible)
+        // Draw background frame BEFORE clipping (so border is always visible)
         renderer.draw_rounded_rect(
             self.position - Vec2::splat(1.0),
             self.size + Vec2::splat(2.0),
@@ -133,19 +306,14 @@ impl Widget for ScrollArea {
             8.0
         );
         
-        // Set scissor for clipping content
-        renderer.set_scissor([
-            self.position.x as u32,
-            self.position.y as u32,
-            self.size.x as u32,
-            self.size.y as u32,
-        ]);
+        // Clip content to the rounded frame
+        renderer.push_rounded_clip(Rect::new(self.position, self.size), 8.0);
         
         self.child.render(renderer);
         
-        renderer.clear_scissor();
+        renderer.pop_clip();
         
-        // Draw visible border frame AFTER scissor cleared (renders on top)
+        // Draw visible border frame AFTER the clip is popped (renders on top)
         renderer.draw_rounded_rect(
             self.position - Vec2::splat(1.0),
             self.size + Vec2::splat(2.0),
@@ -153,39 +321,62 @@ impl Widget for ScrollArea {
             8.0
         );
         
-        // Scrollbar
-        if self.content_height > self.size.y {
+        let viewport = self.viewport();
+        let max_scroll = self.max_scroll();
+        let track_color = Vec4::new(0.1, 0.1, 0.1, 0.3);
+        
+        // Vertical scrollbar, stopping short of the corner when the
+        // horizontal one shows too
+        if max_scroll.y > 0.0 {
             let scrollbar_x = self.position.x + self.size.x - 8.0;
-            let visible_ratio = (self.size.y / self.content_height).min(1.0);
-            let thumb_height = (self.size.y * visible_ratio).max(30.0);
-            let max_scroll = self.content_height - self.size.y;
-            let scroll_ratio = if max_scroll > 0.0 { self.scroll_offset / max_scroll } else { 0.0 };
-            let thumb_y = self.position.y + scroll_ratio * (self.size.y - thumb_height);
+            let (thumb_y, thumb_height) = self.scroll_y.thumb(viewport.y, self.content_height, viewport.y);
             
             // Track
             renderer.draw_rounded_rect(
                 Vec2::new(scrollbar_x, self.position.y + 4.0),
-                Vec2::new(6.0, self.size.y - 8.0),
-                Vec4::new(0.1, 0.1, 0.1, 0.3),
+                Vec2::new(6.0, viewport.y - 8.0),
+                track_color,
                 3.0
             );
             
             // Thumb
-            let thumb_color = if self.scrollbar_dragging {
-                theme.primary
-            } else if self.scrollbar_hovered {
-                Vec4::new(0.6, 0.6, 0.6, 0.8)
-            } else {
-                Vec4::new(0.4, 0.4, 0.4, 0.6)
-            };
-            
             renderer.draw_rounded_rect(
-                Vec2::new(scrollbar_x, thumb_y),
+                Vec2::new(scrollbar_x, self.position.y + thumb_y),
                 Vec2::new(6.0, thumb_height),
-                thumb_color,
+                self.scroll_y.thumb_color(theme.primary),
                 3.0
             );
         }
+        
+        // Horizontal scrollbar
+        if max_scroll.x > 0.0 {
+            let scrollbar_y = self.position.y + self.size.y - 8.0;
+            let (thumb_x, thumb_width) = self.scroll_x.thumb(viewport.x, self.content_width, viewport.x);
+            
+            renderer.draw_rounded_rect(
+                Vec2::new(self.position.x + 4.0, scrollbar_y),
+                Vec2::new(viewport.x - 8.0, 6.0),
+                track_color,
+                3.0
+            );
+            
+            renderer.draw_rounded_rect(
+                Vec2::new(self.position.x + thumb_x, scrollbar_y),
+                Vec2::new(thumb_width, 6.0),
+                self.scroll_x.thumb_color(theme.primary),
+                3.0
+            );
+            
+            // Corner square where the two scrollbars meet
+            if max_scroll.y > 0.0 {
+                renderer.draw_rounded_rect(
+                    self.position + self.size - Vec2::splat(SCROLLBAR_SPACE - 2.0),
+                    Vec2::splat(6.0),
+                    track_color,
+                    3.0
+                );
+            }
+        }
     }
 }
 
@@ -193,6 +384,9 @@ impl Widget for ScrollArea {
 // TAB BAR
 // =============================================================================
 
+/// Height of the tab strip above the active tab's content
+const TAB_HEIGHT: f32 = 40.0;
+
 /// Tab bar for switching between views
 pub struct TabBar {
     pub position: Vec2,
@@ -202,6 +396,8 @@ pub struct TabBar {
     pub active_index: usize,
     pub hovered_index: Option<usize>,
     pub tab_animated_t: f32,
+    /// Called with the index of the tab clicked
+    pub on_select: Option<Box<dyn FnMut(usize)>>,
 }
 
 impl TabBar {
@@ -214,6 +410,7 @@ impl TabBar {
             active_index: 0,
             hovered_index: None,
             tab_animated_t: 0.0,
+            on_select: None,
         }
     }
     
@@ -222,6 +419,42 @@ impl TabBar {
         self.children.push(content);
         self
     }
+    
+    pub fn on_select(mut self, callback: impl FnMut(usize) + 'static) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+    
+    /// Tab hover and clicks on the tab strip
+    fn handle_tab_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let tab_width = if self.tabs.is_empty() { 0.0 } else { self.size.x / self.tabs.len() as f32 };
+        
+        // Check if in tab bar
+        let in_tab_bar = mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + TAB_HEIGHT &&
+                         mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x;
+        
+        if in_tab_bar {
+            let relative_x = mouse_pos.x - self.position.x;
+            let index = (relative_x / tab_width) as usize;
+            if index < self.tabs.len() {
+                self.hovered_index = Some(index);
+            }
+            
+            if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
+                if let Some(idx) = self.hovered_index {
+                    self.active_index = idx;
+                    if let Some(callback) = &mut self.on_select {
+                        callback(idx);
+                    }
+                    return true;
+                }
+            }
+        } else {
+            self.hovered_index = None;
+        }
+        
+        false
+    }
 }
 
 impl Default for TabBar {
@@ -235,40 +468,75 @@ impl Widget for TabBar {
         self.position = origin;
         self.size = max_size;
         
-        let tab_height = 40.0;
-        let content_origin = origin + Vec2::new(0.0, tab_height);
-        let content_size = max_size - Vec2::new(0.0, tab_height);
+        let content_origin = origin + Vec2::new(0.0, TAB_HEIGHT);
+        let content_size = max_size - Vec2::new(0.0, TAB_HEIGHT);
         
         if let Some(child) = self.children.get_mut(self.active_index) {
-            child.layout(content_origin, content_size);
+            child.layout_child(content_origin, content_size);
         }
         
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let content_size = match self.children.get_mut(self.active_index) {
+            Some(child) => child.layout_child_with_constraints(constraints.deflate(EdgeInsets::only(TAB_HEIGHT, 0.0, 0.0, 0.0)).loosen()),
+            None => Size::ZERO,
+        };
+        let size = constraints.biggest_or(Size::new(content_size.width, content_size.height + TAB_HEIGHT));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        if let Some(child) = self.children.get_mut(self.active_index) {
+            child.set_position(position + Offset::new(0.0, TAB_HEIGHT));
+        }
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        if let Some(child) = self.children.get(self.active_index) {
+            visitor(child.as_ref());
+        }
+    }
 
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        let tab_height = 40.0;
-        let tab_width = if self.tabs.is_empty() { 0.0 } else { self.size.x / self.tabs.len() as f32 };
-        
-        // Check if in tab bar
-        let in_tab_bar = mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + tab_height &&
-                         mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x;
-        
-        if in_tab_bar {
-            let relative_x = mouse_pos.x - self.position.x;
-            let index = (relative_x / tab_width) as usize;
-            if index < self.tabs.len() {
-                self.hovered_index = Some(index);
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        if let Some(child) = self.children.get_mut(self.active_index) {
+            visitor(child.as_mut());
+        }
+    }
+    
+    /// Inactive tabs stay in the tree, out of sight of `visit_children_mut`
+    fn on_mount(&mut self) {
+        let active = self.active_index;
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if i != active {
+                mount(child.as_mut());
             }
-            
-            if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
-                if let Some(idx) = self.hovered_index {
-                    self.active_index = idx;
-                    return true;
-                }
+        }
+    }
+    
+    fn on_unmount(&mut self) {
+        let active = self.active_index;
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if i != active {
+                unmount(child.as_mut());
             }
-        } else {
-            self.hovered_index = None;
+        }
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.handle_tab_event(event, mouse_pos) {
+            return true;
         }
         
         // Pass to active child
@@ -280,6 +548,10 @@ impl Widget for TabBar {
         
         false
     }
+    
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.handle_tab_event(event, mouse_pos)
+    }
 
     fn update(&mut self, dt: f32) {
         // Animate tab indicator
@@ -293,13 +565,12 @@ impl Widget for TabBar {
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        let tab_height = 40.0;
         let tab_width = if self.tabs.is_empty() { 0.0 } else { self.size.x / self.tabs.len() as f32 };
         
         // Tab bar background
         renderer.draw_rounded_rect(
             self.position,
-            Vec2::new(self.size.x, tab_height),
+            Vec2::new(self.size.x, TAB_HEIGHT),
             Vec4::new(0.06, 0.06, 0.08, 0.9),
             8.0
         );
@@ -307,7 +578,7 @@ impl Widget for TabBar {
         // Active indicator (animated)
         let indicator_x = self.position.x + self.tab_animated_t * tab_width;
         renderer.draw_rounded_rect(
-            Vec2::new(indicator_x + 4.0, self.position.y + tab_height - 4.0),
+            Vec2::new(indicator_x + 4.0, self.position.y + TAB_HEIGHT - 4.0),
             Vec2::new(tab_width - 8.0, 3.0),
             theme.primary,
             2.0
@@ -321,7 +592,7 @@ impl Widget for TabBar {
             if self.hovered_index == Some(i) {
                 renderer.draw_rounded_rect(
                     Vec2::new(tab_x + 2.0, self.position.y + 2.0),
-                    Vec2::new(tab_width - 4.0, tab_height - 4.0),
+                    Vec2::new(tab_width - 4.0, TAB_HEIGHT - 4.0),
                     Vec4::new(1.0, 1.0, 1.0, 0.05),
                     6.0
                 );
@@ -338,3 +609,597 @@ impl Widget for TabBar {
         }
     }
 }
+
+// =============================================================================
+// CACHED WIDGET
+// =============================================================================
+
+/// Draws its child from an offscreen texture instead of re-issuing its
+/// instances every frame
+/// 
+/// Meant for complex, mostly static subtrees (timelines, big tables). The
+/// texture is re-rendered when the size, scale factor or theme changes,
+/// when the child consumes an event or is animating, and after
+/// `invalidate`. Hover effects that change without consuming events need
+/// an explicit `invalidate`. Content outside the widget's rect is cut off.
+pub struct CachedWidget {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub child: Box<dyn Widget>,
+    cache: RenderCache,
+}
+
+impl CachedWidget {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            child,
+            cache: RenderCache::new(),
+        }
+    }
+    
+    /// Re-render the child on the next frame (after changing it directly)
+    pub fn invalidate(&self) {
+        self.cache.invalidate();
+    }
+    
+    /// The texture cache, freed when the widget is unmounted
+    pub fn cache(&self) -> &RenderCache {
+        &self.cache
+    }
+}
+
+impl Widget for CachedWidget {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        self.size = self.child.layout_child(origin, max_size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        Some(Rect::new(self.position, self.size))
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+    
+    fn positioned(&self) -> Option<&super::layout::Positioned> {
+        self.child.positioned()
+    }
+    
+    fn on_unmount(&mut self) {
+        self.cache.release();
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let consumed = self.child.handle_event(event, mouse_pos);
+        if consumed {
+            self.cache.invalidate();
+        }
+        consumed
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+        if self.child.is_animating() {
+            self.cache.invalidate();
+        }
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let child = &self.child;
+        renderer.draw_cached(&self.cache, Rect::new(self.position, self.size), |r| child.render(r));
+    }
+}
+
+// =============================================================================
+// OPACITY
+// =============================================================================
+
+/// Fades its whole subtree (text included) with `GlassRenderer::push_opacity`
+/// 
+/// Animate `opacity` to fade a group in or out; at 0 nothing is drawn,
+/// though the child still lays out and receives events.
+pub struct Opacity {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub child: Box<dyn Widget>,
+    /// 0 = invisible, 1 = unchanged
+    pub opacity: f32,
+}
+
+impl Opacity {
+    pub fn new(opacity: f32, child: Box<dyn Widget>) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            child,
+            opacity: opacity.clamp(0.0, 1.0),
+        }
+    }
+    
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+}
+
+impl Widget for Opacity {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        self.size = self.child.layout_child(origin, max_size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        // Anything faded lets the layers below show through
+        if self.opacity >= 1.0 { self.child.opaque_rect() } else { None }
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        if self.opacity <= 0.0 {
+            return;
+        }
+        renderer.push_opacity(self.opacity);
+        self.child.render(renderer);
+        renderer.pop_opacity();
+    }
+}
+
+// =============================================================================
+// VISIBLE
+// =============================================================================
+
+/// Shows or hides its child without removing it from the tree
+/// 
+/// While hidden the child takes no space, gets no events and isn't drawn
+/// or visited, but keeps its state (and keeps updating) for when it comes
+/// back. `bound` follows a `State<bool>` so app logic can toggle panels.
+pub struct Visible {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub child: Box<dyn Widget>,
+    visible: bool,
+    binding: Option<State<bool>>,
+    /// Visibility the last layout pass used
+    laid_out: Option<bool>,
+}
+
+impl Visible {
+    pub fn new(visible: bool, child: Box<dyn Widget>) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            child,
+            visible,
+            binding: None,
+            laid_out: None,
+        }
+    }
+    
+    /// Visible while `state` holds true
+    pub fn bound(state: &State<bool>, child: Box<dyn Widget>) -> Self {
+        Self {
+            binding: Some(state.share()),
+            ..Self::new(state.get(), child)
+        }
+    }
+    
+    pub fn is_visible(&self) -> bool {
+        match &self.binding {
+            Some(state) => state.get(),
+            None => self.visible,
+        }
+    }
+    
+    /// Show or hide the child (sets the bound state, if any)
+    pub fn set_visible(&mut self, visible: bool) {
+        match &self.binding {
+            Some(state) => state.set(visible),
+            None => self.visible = visible,
+        }
+    }
+    
+    pub fn toggle(&mut self) {
+        self.set_visible(!self.is_visible());
+    }
+}
+
+impl Widget for Visible {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        let visible = self.is_visible();
+        self.laid_out = Some(visible);
+        self.position = origin;
+        self.size = if visible { self.child.layout_child(origin, max_size) } else { Vec2::ZERO };
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let visible = self.is_visible();
+        self.laid_out = Some(visible);
+        let size = if visible { self.child.layout_child_with_constraints(constraints) } else { constraints.smallest() };
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        if self.is_visible() {
+            visitor(self.child.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        if self.is_visible() {
+            visitor(self.child.as_mut());
+        }
+    }
+    
+    /// A hidden child is still mounted, it just isn't visited
+    fn on_mount(&mut self) {
+        if !self.is_visible() {
+            mount(self.child.as_mut());
+        }
+    }
+    
+    fn on_unmount(&mut self) {
+        if !self.is_visible() {
+            unmount(self.child.as_mut());
+        }
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn needs_layout(&self) -> bool {
+        let visible = self.is_visible();
+        self.laid_out != Some(visible) || (visible && self.child.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.is_visible() && self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        if self.is_visible() { self.child.render_bounds() } else { None }
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        if self.is_visible() { self.child.opaque_rect() } else { None }
+    }
+    
+    fn positioned(&self) -> Option<&super::layout::Positioned> {
+        self.child.positioned()
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("visible", self.is_visible().to_string()));
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.is_visible() && self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        if self.is_visible() {
+            self.child.render(renderer);
+        }
+    }
+}
+
+// =============================================================================
+// PASS THROUGH
+// =============================================================================
+
+/// Lets pointer events through its child to the widgets beneath
+/// 
+/// With `hit_test` off (the default) the dispatcher's hit testing skips
+/// the whole subtree, and broadcast presses and scrolls aren't passed down.
+/// Wrap purely decorative overlays (glows, separators, placeholders) so
+/// they don't swallow clicks meant for what they cover.
+pub struct PassThrough {
+    pub child: Box<dyn Widget>,
+    /// Whether the child takes pointer events after all
+    pub hit_test: bool,
+}
+
+impl PassThrough {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self { child, hit_test: false }
+    }
+    
+    pub fn with_hit_test(mut self, hit_test: bool) -> Self {
+        self.hit_test = hit_test;
+        self
+    }
+}
+
+impl Widget for PassThrough {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.child.layout_child(origin, max_size)
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout_child_with_constraints(constraints)
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        self.child.get_position()
+    }
+    
+    fn get_size(&self) -> Size {
+        self.child.get_size()
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn hit_test_visible(&self) -> bool {
+        self.hit_test
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+    
+    fn positioned(&self) -> Option<&super::layout::Positioned> {
+        self.child.positioned()
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("hit test", self.hit_test.to_string()));
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        let pointer = matches!(event, Event::WindowEvent { event: WindowEvent::MouseInput { .. } | WindowEvent::MouseWheel { .. }, .. });
+        (self.hit_test || !pointer) && self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::{Button, Column, Label, count_widgets};
+    use winit::event::{Event, WindowEvent};
+    
+    fn press(widget: &mut dyn Widget, pos: Vec2) -> bool {
+        widget.handle_event(&Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::MouseInput {
+                device_id: unsafe { winit::event::DeviceId::dummy() },
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+            },
+        }, pos)
+    }
+    
+    #[test]
+    fn test_visible_follows_state() {
+        let shown = State::new(false);
+        let mut column = Column::new()
+            .add_child(Box::new(Visible::bound(&shown, Box::new(Button::new("Details")))))
+            .add_child(Box::new(Label::new("Footer")));
+        let max = Vec2::new(400.0, 400.0);
+        let collapsed = column.layout(Vec2::ZERO, max);
+        assert_eq!(count_widgets(&column), 3);
+        assert!(!press(&mut column, Vec2::splat(15.0)));
+        assert!(!column.needs_layout());
+        
+        // Flipping the state asks for a relayout that makes room for the child
+        shown.set(true);
+        assert!(column.needs_layout());
+        let expanded = column.layout(Vec2::ZERO, max);
+        assert!(expanded.y > collapsed.y);
+        assert_eq!(count_widgets(&column), 4);
+        assert!(press(&mut column, Vec2::splat(15.0)));
+        
+        let visible = column.children[0].0.downcast_mut::<Visible>().unwrap();
+        visible.toggle();
+        assert!(!shown.get());
+    }
+    
+    #[test]
+    fn test_scroll_area_scrolls_both_ways() {
+        use crate::recorder::RecordedEvent;
+        use crate::widgets::Spacer;
+        let wheel = |dx, dy| RecordedEvent::MouseWheel { dx, dy, lines: false }.to_event().unwrap();
+        let mut scroll = ScrollArea::new(Box::new(Spacer::new(Vec2::new(600.0, 400.0))));
+        scroll.layout(Vec2::ZERO, Vec2::new(212.0, 112.0));
+        // The horizontal scrollbar takes 12px off the height
+        assert_eq!(scroll.viewport(), Vec2::new(200.0, 100.0));
+        assert_eq!(scroll.max_scroll(), Vec2::new(400.0, 300.0));
+        
+        // A trackpad scrolls both axes at once, easing there
+        assert!(scroll.handle_event(&wheel(-50.0, -30.0), Vec2::splat(50.0)));
+        assert!(scroll.is_animating());
+        scroll.update(1.0);
+        assert_eq!(scroll.offset(), Vec2::new(50.0, 30.0));
+        
+        // With shift held, a plain wheel scrolls sideways
+        let shift = RecordedEvent::Modifiers { bits: winit::keyboard::ModifiersState::SHIFT.bits() };
+        scroll.handle_event(&shift.to_event().unwrap(), Vec2::splat(50.0));
+        assert!(scroll.handle_event(&wheel(0.0, -1000.0), Vec2::splat(50.0)));
+        scroll.update(1.0);
+        assert_eq!(scroll.offset(), Vec2::new(400.0, 30.0));
+        assert!(scroll.needs_layout());
+    }
+    
+    #[test]
+    fn test_scroll_area_thumb_track_and_scroll_into_view() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        use crate::widgets::{Column, Identified, SizedBox, Spacer};
+        let button = |pressed| RecordedEvent::MouseInput { button: RecordedButton::Left, pressed }.to_event().unwrap();
+        let moved = |y| RecordedEvent::CursorMoved { x: 206.0, y }.to_event().unwrap();
+        let target = WidgetId::new();
+        let mut column = Column::new().with_spacing(0.0).with_padding(0.0);
+        for i in 0..10 {
+            let row: Box<dyn Widget> = Box::new(SizedBox::new(100.0, 50.0, Box::new(Spacer::new(Vec2::ZERO))));
+            column = column.add_child(if i == 6 { Box::new(Identified::new(target, row)) } else { row });
+        }
+        let mut scroll = ScrollArea::new(Box::new(column));
+        scroll.layout(Vec2::ZERO, Vec2::new(212.0, 100.0));
+        assert_eq!(scroll.max_scroll(), Vec2::new(0.0, 400.0));
+        
+        // Clicking the track below the 30px thumb pages down one view
+        assert!(scroll.handle_event(&button(true), Vec2::new(206.0, 90.0)));
+        scroll.handle_event(&button(false), Vec2::new(206.0, 90.0));
+        assert_eq!(scroll.scroll_y.target(), 100.0);
+        
+        // Dragging the thumb scrolls at once: 70px of track is 400px
+        scroll.scroll_y.set(0.0, 400.0);
+        assert!(scroll.handle_event(&button(true), Vec2::new(206.0, 10.0)));
+        assert!(scroll.handle_event(&moved(45.0), Vec2::new(206.0, 45.0)));
+        assert_eq!(scroll.offset().y, 200.0);
+        scroll.handle_event(&button(false), Vec2::new(206.0, 45.0));
+        assert!(!scroll.scroll_y.is_dragging());
+        
+        // Row 6 spans 300..350 of the content; it ends up at the bottom
+        scroll.scroll_y.set(0.0, 400.0);
+        scroll.layout(Vec2::ZERO, Vec2::new(212.0, 100.0));
+        assert!(scroll.scroll_into_view(target));
+        assert_eq!(scroll.scroll_y.target(), 250.0);
+        assert!(!scroll.scroll_into_view(WidgetId::new()));
+    }
+}
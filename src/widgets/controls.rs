@@ -1,12 +1,15 @@
 //! GlassUI Control Widgets
 //!
-//! Interactive controls: Button, Label, Slider, Checkbox
+//! Interactive controls: Button, Label, Slider, Knob, Checkbox
 
 use glam::{Vec2, Vec4};
 use winit::event::{ElementState, MouseButton};
-use crate::renderer::GlassRenderer;
-use crate::layout::{BoxConstraints, Size, Offset, EdgeInsets};
-use super::core::{Widget, get_theme};
+use crate::renderer::{GlassRenderer, Gradient};
+use crate::style::{Shadow, TextStyle, WidgetStyle};
+use crate::text::{layout_text_with, layout_text_width, TextLayout, TextOverflow};
+use crate::layout::{BoxConstraints, Size, Offset, EdgeInsets, LayoutCache, Rect};
+use super::core::{Widget, WidgetState, UpdateState, get_theme, render_faded, rounded_opaque_rect, constraint_property, format_color};
+use crate::focus::{FocusId, Focusable};
 
 // =============================================================================
 // BUTTON
@@ -34,6 +37,12 @@ pub struct Button {
     pub min_width: f32,
     /// Minimum height (0 = use intrinsic)
     pub min_height: f32,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
+    /// Per-instance overrides of the theme colors (see `with_style`)
+    pub style: WidgetStyle,
 }
 
 impl Button {
@@ -51,6 +60,23 @@ impl Button {
             padding: EdgeInsets::symmetric(24.0, 12.0),
             min_width: 80.0,
             min_height: 36.0,
+            layout_intrinsic: None,
+            disabled: false,
+            style: WidgetStyle::default(),
+        }
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; drops hover and press
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hovered = false;
+            self.pressed = false;
         }
     }
     
@@ -59,6 +85,11 @@ impl Button {
         self
     }
     
+    /// Same as `with_callback`, named like the other widgets' callbacks
+    pub fn on_click(self, callback: impl FnMut() + 'static) -> Self {
+        self.with_callback(callback)
+    }
+    
     pub fn with_radius(mut self, radius: f32) -> Self {
         self.corner_radius = radius;
         self
@@ -75,6 +106,27 @@ impl Button {
         self
     }
     
+    /// Override theme values for this button: padding, radius and min size
+    /// replace the fields, while backgrounds (normal/hover/pressed/disabled),
+    /// border, shadow, text color and opacity are read when rendering.
+    /// Repeated calls layer on top of each other.
+    pub fn with_style(mut self, style: WidgetStyle) -> Self {
+        if let Some(padding) = style.padding {
+            self.padding = padding;
+        }
+        if let Some(radius) = style.corner_radius {
+            self.corner_radius = radius;
+        }
+        if let Some(width) = style.min_width {
+            self.min_width = width;
+        }
+        if let Some(height) = style.min_height {
+            self.min_height = height;
+        }
+        self.style = self.style.merge(&style);
+        self
+    }
+    
     /// Calculate intrinsic size based on text
     fn calculate_intrinsic_size(&self) -> Size {
         // Approximate text width (10px per character at size 20)
@@ -86,6 +138,12 @@ impl Button {
             (text_height + self.padding.vertical()).max(self.min_height),
         )
     }
+    
+    /// Top-left of the centered label, nudged down while pressed
+    fn text_position(&self) -> Vec2 {
+        let text_len = self.text.len() as f32 * 10.0;
+        self.position + (self.size - Vec2::new(text_len, 20.0)) * 0.5 + Vec2::new(0.0, self.press_t * 2.0)
+    }
 }
 
 impl Widget for Button {
@@ -97,9 +155,14 @@ impl Widget for Button {
             intrinsic.width.min(max_size.x),
             intrinsic.height.min(max_size.y),
         );
+        self.layout_intrinsic = Some(intrinsic);
         self.size
     }
     
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.calculate_intrinsic_size())
+    }
+    
     fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
         let intrinsic = self.calculate_intrinsic_size();
         self.size = constraints.constrain(intrinsic).into();
@@ -127,6 +190,9 @@ impl Widget for Button {
     }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.disabled {
+            return false;
+        }
         let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
                      mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
         
@@ -158,38 +224,62 @@ impl Widget for Button {
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        
-        let base_col = Vec4::new(0.0, 0.0, 0.0, 0.3);
-        let hover_col = theme.hover;
-        let press_col = theme.pressed;
-        
-        let mut color = base_col.lerp(hover_col, self.hover_t);
-        color = color.lerp(press_col, self.press_t);
-        
-        let scale = self.hover_t * 4.0 - self.press_t * 2.0;
-        
-        // Glow effect
-        if self.hover_t > 0.01 {
-            renderer.draw_rounded_rect(
-                self.position - Vec2::splat(scale),
-                self.size + Vec2::splat(scale * 2.0),
-                Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, self.hover_t * 0.3),
-                self.corner_radius + 4.0
-            );
-        }
-        
-        // Button body
-        renderer.draw_rounded_rect(
-            self.position + Vec2::splat(self.press_t * 2.0), 
-            self.size - Vec2::splat(self.press_t * 4.0), 
-            color,
-            self.corner_radius
-        );
-        
-        // Text
-        let text_len = self.text.len() as f32 * 10.0;
-        let text_pos = self.position + (self.size - Vec2::new(text_len, 20.0)) * 0.5 + Vec2::new(0.0, self.press_t * 2.0);
-        renderer.draw_text(&self.text, text_pos, 20.0, theme.text);
+        render_faded(renderer, self.disabled, self.style.opacity, |renderer| {
+            let base_col = match self.style.background_disabled {
+                Some(color) if self.disabled => color,
+                _ => self.style.background.unwrap_or(Vec4::new(0.0, 0.0, 0.0, 0.3)),
+            };
+            let hover_col = self.style.background_hover.unwrap_or(theme.hover);
+            let press_col = self.style.background_pressed.unwrap_or(theme.pressed);
+            
+            let mut color = base_col.lerp(hover_col, self.hover_t);
+            color = color.lerp(press_col, self.press_t);
+            
+            let scale = self.hover_t * 4.0 - self.press_t * 2.0;
+            
+            // Glow effect
+            if self.hover_t > 0.01 {
+                renderer.draw_rounded_rect(
+                    self.position - Vec2::splat(scale),
+                    self.size + Vec2::splat(scale * 2.0),
+                    Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, self.hover_t * 0.3),
+                    self.corner_radius + 4.0
+                );
+            }
+            
+            // Button body
+            let body_pos = self.position + Vec2::splat(self.press_t * 2.0);
+            let body_size = self.size - Vec2::splat(self.press_t * 4.0);
+            if let Some(shadow) = &self.style.shadow {
+                renderer.draw_box_shadow(body_pos, body_size, self.corner_radius, shadow);
+            }
+            renderer.draw_rounded_rect(body_pos, body_size, color, self.corner_radius);
+            if let Some(border) = self.style.border_for(self.hovered) {
+                renderer.draw_border(&border, body_pos, body_size, self.corner_radius);
+            }
+            
+            // Text
+            renderer.draw_text(&self.text, self.text_position(), 20.0, self.style.text_color(theme.text));
+        });
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("text", self.text.clone()));
+        props.push(("hovered", self.hovered.to_string()));
+        props.push(("pressed", self.pressed.to_string()));
+        props.push(("corner radius", format!("{:.0}", self.corner_radius)));
+    }
+    
+    fn widget_style(&self) -> Option<&WidgetStyle> {
+        Some(&self.style)
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        self.padding
+    }
+    
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        Some((self.text_position(), 20.0))
     }
 }
 
@@ -198,12 +288,23 @@ impl Widget for Button {
 // =============================================================================
 
 /// Simple text label
+/// 
+/// Explicit newlines start new lines. Lines wider than the label is laid
+/// out in spill over unless `overflow` clips, ellipsizes or wraps them.
 pub struct Label {
     pub position: Vec2,
     pub size: Vec2,
     pub text: String,
     pub font_size: f32,
     pub color: Option<Vec4>,
+    pub overflow: TextOverflow,
+    /// Outline and drop shadow (size and color come from the label)
+    pub effects: TextStyle,
+    /// Width the text has to fit, from the last layout pass (unset when
+    /// overflow is visible)
+    max_width: Option<f32>,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
 }
 
 impl Label {
@@ -214,6 +315,10 @@ impl Label {
             text: text.to_string(),
             font_size: 24.0,
             color: None,
+            overflow: TextOverflow::Visible,
+            effects: TextStyle::default(),
+            max_width: None,
+            layout_intrinsic: None,
         }
     }
     
@@ -227,28 +332,66 @@ impl Label {
         self
     }
     
+    /// Wrap between words to the available width, short for
+    /// `with_overflow(TextOverflow::Wrap)`
+    pub fn with_wrap(mut self) -> Self {
+        self.overflow = TextOverflow::Wrap;
+        self
+    }
+    
+    /// Clip, ellipsize or wrap lines wider than the available width
+    pub fn with_overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+    
+    /// Stroke the text so it stays readable over busy backgrounds
+    pub fn with_outline(mut self, width: f32, color: Vec4) -> Self {
+        self.effects = self.effects.with_outline(width, color);
+        self
+    }
+    
+    pub fn with_shadow(mut self, offset: Vec2, color: Vec4) -> Self {
+        self.effects = self.effects.with_shadow(offset, color);
+        self
+    }
+    
     pub fn set_text(&mut self, text: &str) {
         self.text = text.to_string();
     }
     
+    /// Line layout in the font `render` draws with, wrapped at `max_width`
+    /// when wrapping is on
+    fn text_layout(&self, max_width: Option<f32>) -> TextLayout {
+        let max_width = max_width.filter(|_| self.overflow == TextOverflow::Wrap);
+        layout_text_with(&self.text, max_width, self.font_size * 1.2, |run| layout_text_width(run, self.font_size))
+    }
+    
     /// Calculate intrinsic size based on text
     fn calculate_intrinsic_size(&self) -> Size {
-        // Approximate: font_size * 0.5 per character width
-        let width = self.text.len() as f32 * (self.font_size * 0.5);
-        let height = self.font_size;
-        Size::new(width, height)
+        let layout = self.text_layout(self.max_width);
+        let extra_lines = layout.lines.len().saturating_sub(1) as f32;
+        let width = layout.width.min(self.max_width.unwrap_or(f32::INFINITY));
+        Size::new(width, self.font_size + extra_lines * layout.line_height)
     }
 }
 
 impl Widget for Label {
-    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
+        self.max_width = (self.overflow != TextOverflow::Visible).then_some(max_size.x);
         let intrinsic = self.calculate_intrinsic_size();
         self.size = Vec2::new(intrinsic.width, intrinsic.height);
+        self.layout_intrinsic = Some(intrinsic);
         self.size
     }
     
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.calculate_intrinsic_size())
+    }
+    
     fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.max_width = (self.overflow != TextOverflow::Visible && constraints.max_width.is_finite()).then_some(constraints.max_width);
         let intrinsic = self.calculate_intrinsic_size();
         let constrained = constraints.constrain(intrinsic);
         self.size = Vec2::new(constrained.width, constrained.height);
@@ -268,18 +411,64 @@ impl Widget for Label {
     }
     
     fn intrinsic_width(&self, _height: f32) -> Option<f32> {
-        Some(self.calculate_intrinsic_size().width)
+        Some(self.text_layout(None).width)
     }
     
-    fn intrinsic_height(&self, _width: f32) -> Option<f32> {
-        Some(self.calculate_intrinsic_size().height)
+    fn intrinsic_height(&self, width: f32) -> Option<f32> {
+        let lines = self.text_layout(Some(width)).lines.len().max(1) as f32;
+        Some(self.font_size + (lines - 1.0) * self.font_size * 1.2)
     }
     
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool { false }
     fn update(&mut self, _dt: f32) {}
     fn render(&self, renderer: &mut GlassRenderer) {
         let color = self.color.unwrap_or_else(|| get_theme().text);
-        renderer.draw_text(&self.text, self.position, self.font_size, color);
+        let style = TextStyle { font_size: self.font_size, color, ..self.effects.clone() };
+        let Some(max_width) = self.max_width else {
+            if self.text.contains('\n') {
+                let layout = renderer.layout_text(&self.text, self.font_size, None);
+                for (line, text) in layout.lines.iter().zip(layout.line_texts(&self.text)) {
+                    renderer.draw_text_styled(text, self.position + Vec2::new(0.0, line.y), &style);
+                }
+            } else {
+                renderer.draw_text_styled(&self.text, self.position, &style);
+            }
+            return;
+        };
+        
+        let wrap_width = (self.overflow == TextOverflow::Wrap).then_some(max_width);
+        let layout = renderer.layout_text(&self.text, self.font_size, wrap_width);
+        let clip = self.overflow == TextOverflow::Clip;
+        if clip {
+            renderer.push_clip(Rect::new(self.position, Vec2::new(max_width, layout.height.max(self.size.y))));
+        }
+        for (line, text) in layout.lines.iter().zip(layout.line_texts(&self.text)) {
+            let pos = self.position + Vec2::new(0.0, line.y);
+            if self.overflow == TextOverflow::Ellipsis {
+                let fitted = renderer.ellipsize(text, self.font_size, max_width);
+                renderer.draw_text_styled(&fitted, pos, &style);
+            } else {
+                renderer.draw_text_styled(text, pos, &style);
+            }
+        }
+        if clip {
+            renderer.pop_clip();
+        }
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("text", self.text.clone()));
+        props.push(("font size", format!("{:.0}", self.font_size)));
+        if self.overflow != TextOverflow::Visible {
+            props.push(("overflow", format!("{:?} at {:.0}", self.overflow, self.max_width.unwrap_or(f32::INFINITY))));
+        }
+        if let Some(color) = self.color {
+            props.push(("color", format_color(color)));
+        }
+    }
+    
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        Some((self.position, self.font_size))
     }
 }
 
@@ -287,14 +476,73 @@ impl Widget for Label {
 // SLIDER
 // =============================================================================
 
-/// Horizontal slider for value selection
+/// Direction a Slider's track runs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    /// Bottom to top
+    Vertical,
+}
+
+/// Fraction of its range the arrow keys move an unstepped Slider by
+const SLIDER_KEY_STEP: f32 = 0.05;
+
+/// Most steps a control draws ticks for; past that they blur together
+const MAX_STEP_TICKS: usize = 50;
+
+/// `value` within `min..=max`, on the nearest multiple of `step` from
+/// `min` when `step` is positive
+pub(super) fn snap_to_step(value: f32, min: f32, max: f32, step: f32) -> f32 {
+    let value = value.clamp(min, max);
+    if step > 0.0 {
+        (min + ((value - min) / step).round() * step).min(max)
+    } else {
+        value
+    }
+}
+
+/// Call `draw` with how far along `min..=max` each step lies (0 and 1
+/// included), when `step` is set and there are few enough to tell apart
+pub(super) fn draw_step_ticks(min: f32, max: f32, step: f32, mut draw: impl FnMut(f32)) {
+    if step <= 0.0 || max <= min {
+        return;
+    }
+    let steps = ((max - min) / step).round() as usize;
+    if steps <= MAX_STEP_TICKS {
+        for i in 0..=steps {
+            draw(i as f32 / steps as f32);
+        }
+    }
+}
+
+/// Slider for a value from `min` to `max` (0 to 1 unless set)
+///
+/// With a `step` the value snaps to multiples of it and the track shows a
+/// tick at each. A press focuses the slider; while focused, the arrow keys
+/// nudge the value by a step (Right/Up increase it) and Home/End jump to
+/// the ends.
 pub struct Slider {
     pub position: Vec2,
     pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
     pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub orientation: Orientation,
+    /// Snapping interval from `min`; 0 slides freely
+    pub step: f32,
     pub dragging: bool,
     pub hovered: bool,
+    pub focused: bool,
+    /// Identifies the slider to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
     pub corner_radius: f32,
+    /// Called with the new value while the handle is dragged or nudged
+    pub on_change: Option<Box<dyn FnMut(f32)>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
 }
 
 impl Slider {
@@ -302,26 +550,195 @@ impl Slider {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            layout_intrinsic: None,
             value: value.clamp(0.0, 1.0),
+            min: 0.0,
+            max: 1.0,
+            orientation: Orientation::Horizontal,
+            step: 0.0,
             dragging: false,
             hovered: false,
+            focused: false,
+            focus_id: FocusId::new(),
             corner_radius: 4.0,
+            on_change: None,
+            disabled: false,
+        }
+    }
+    
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+    
+    /// Slide between `min` and `max` instead of 0 and 1; the value is
+    /// kept within them (set it after, with `with_value`)
+    pub fn with_bounds(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max.max(min);
+        self.value = self.snap(self.value);
+        self
+    }
+    
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = self.snap(value);
+        self
+    }
+    
+    /// Snap to multiples of `step` from `min` (e.g. 0.1 for ten steps
+    /// from 0 to 1)
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step.max(0.0);
+        self.value = self.snap(self.value);
+        self
+    }
+    
+    /// `value` on the nearest step, within `min..=max`
+    fn snap(&self, value: f32) -> f32 {
+        snap_to_step(value, self.min, self.max, self.step)
+    }
+    
+    /// How far along the track the value is, 0..1
+    fn fraction(&self) -> f32 {
+        let span = self.max - self.min;
+        if span > 0.0 { ((self.value - self.min) / span).clamp(0.0, 1.0) } else { 0.0 }
+    }
+    
+    /// Set the value from user input, snapped, reporting a change
+    fn set_from_input(&mut self, value: f32) {
+        let value = self.snap(value);
+        if value != self.value {
+            self.value = value;
+            if let Some(callback) = &mut self.on_change {
+                callback(value);
+            }
+        }
+    }
+    
+    /// Move `steps` steps up (or down, if negative), as the arrow keys do
+    pub fn nudge(&mut self, steps: f32) {
+        let step = if self.step > 0.0 { self.step } else { SLIDER_KEY_STEP * (self.max - self.min) };
+        self.set_from_input(self.value + steps * step);
+    }
+    
+    /// Value under the pointer at `point`
+    fn value_at(&self, point: Vec2) -> f32 {
+        let t = match self.orientation {
+            Orientation::Horizontal => (point.x - self.position.x) / self.size.x.max(1.0),
+            Orientation::Vertical => 1.0 - (point.y - self.position.y) / self.size.y.max(1.0),
+        };
+        self.min + t * (self.max - self.min)
+    }
+    
+    /// Length along the track the default layout gives it, and its thickness
+    fn default_size(&self) -> Size {
+        match self.orientation {
+            Orientation::Horizontal => Size::new(200.0, 20.0),
+            Orientation::Vertical => Size::new(20.0, 200.0),
+        }
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; ends a drag and drops focus
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hovered = false;
+            self.dragging = false;
+            self.focused = false;
         }
     }
     
+    pub fn on_change(mut self, callback: impl FnMut(f32) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
     pub fn get_value(&self) -> f32 {
         self.value
     }
 }
 
+impl Focusable for Slider {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn can_focus(&self) -> bool {
+        !self.disabled
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+    }
+}
+
 impl Widget for Slider {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(200.0, 20.0);
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.disabled {
+            return false;
+        }
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::KeyboardInput { event: key_event, .. }, .. } = event {
+            use winit::keyboard::{Key, NamedKey};
+            if !self.focused || !key_event.state.is_pressed() {
+                return false;
+            }
+            match key_event.logical_key {
+                Key::Named(NamedKey::ArrowRight | NamedKey::ArrowUp) => self.nudge(1.0),
+                Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowDown) => self.nudge(-1.0),
+                Key::Named(NamedKey::Home) => self.set_from_input(self.min),
+                Key::Named(NamedKey::End) => self.set_from_input(self.max),
+                _ => return false,
+            }
+            return true;
+        }
         let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
                      mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
         
@@ -331,6 +748,9 @@ impl Widget for Slider {
             winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } => {
                 if *state == ElementState::Pressed && inside {
                     self.dragging = true;
+                    self.focused = true;
+                } else if *state == ElementState::Pressed {
+                    self.on_blur();
                 } else if *state == ElementState::Released {
                     self.dragging = false;
                 }
@@ -339,8 +759,7 @@ impl Widget for Slider {
         }
         
         if self.dragging {
-            let relative_x = (mouse_pos.x - self.position.x).clamp(0.0, self.size.x);
-            self.value = relative_x / self.size.x;
+            self.set_from_input(self.value_at(mouse_pos));
             return true;
         }
         
@@ -351,127 +770,997 @@ impl Widget for Slider {
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        
-        // Track
-        renderer.draw_rounded_rect(self.position, self.size, Vec4::new(0.0, 0.0, 0.0, 0.5), self.corner_radius);
-        
-        // Fill
-        let fill_width = self.size.x * self.value;
-        if fill_width > 0.0 {
-            renderer.draw_rounded_rect(self.position, Vec2::new(fill_width, self.size.y), theme.primary * Vec4::new(1.0, 1.0, 1.0, 0.6), self.corner_radius);
-        }
-        
-        // Handle
-        let handle_size = Vec2::new(12.0, self.size.y + 8.0);
-        let handle_pos = Vec2::new(
-            self.position.x + fill_width - handle_size.x * 0.5, 
-            self.position.y - 4.0
-        );
-        
-        // Handle glow
-        if self.hovered || self.dragging {
-            renderer.draw_rounded_rect(
-                handle_pos - Vec2::splat(2.0), 
-                handle_size + Vec2::splat(4.0), 
-                Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.4),
-                6.0
-            );
-        }
-        
-        renderer.draw_rounded_rect(handle_pos, handle_size, Vec4::new(1.0, 1.0, 1.0, 0.95), 4.0);
+        render_faded(renderer, self.disabled, None, |renderer| {
+            // Track
+            renderer.draw_rounded_rect(self.position, self.size, Vec4::new(0.0, 0.0, 0.0, 0.5), self.corner_radius);
+            
+            // Fill, from the left or the bottom
+            let fill_color = theme.primary * Vec4::new(1.0, 1.0, 1.0, 0.6);
+            let (handle_size, handle_pos) = match self.orientation {
+                Orientation::Horizontal => {
+                    let fill_width = self.size.x * self.fraction();
+                    if fill_width > 0.0 {
+                        renderer.draw_rounded_rect(self.position, Vec2::new(fill_width, self.size.y), fill_color, self.corner_radius);
+                    }
+                    let handle_size = Vec2::new(12.0, self.size.y + 8.0);
+                    (handle_size, Vec2::new(self.position.x + fill_width - handle_size.x * 0.5, self.position.y - 4.0))
+                }
+                Orientation::Vertical => {
+                    let fill_height = self.size.y * self.fraction();
+                    let fill_top = self.position.y + self.size.y - fill_height;
+                    if fill_height > 0.0 {
+                        renderer.draw_rounded_rect(Vec2::new(self.position.x, fill_top), Vec2::new(self.size.x, fill_height), fill_color, self.corner_radius);
+                    }
+                    let handle_size = Vec2::new(self.size.x + 8.0, 12.0);
+                    (handle_size, Vec2::new(self.position.x - 4.0, fill_top - handle_size.y * 0.5))
+                }
+            };
+            
+            // Tick between each step
+            draw_step_ticks(self.min, self.max, self.step, |t| {
+                if t <= 0.0 || t >= 1.0 {
+                    return;
+                }
+                let (pos, size) = match self.orientation {
+                    Orientation::Horizontal => (Vec2::new(self.position.x + t * self.size.x, self.position.y + 3.0), Vec2::new(1.0, self.size.y - 6.0)),
+                    Orientation::Vertical => (Vec2::new(self.position.x + 3.0, self.position.y + (1.0 - t) * self.size.y), Vec2::new(self.size.x - 6.0, 1.0)),
+                };
+                renderer.draw_rect(pos, size, Vec4::new(1.0, 1.0, 1.0, 0.25));
+            });
+            
+            // Handle glow
+            if self.hovered || self.dragging || self.focused {
+                renderer.draw_rounded_rect(
+                    handle_pos - Vec2::splat(2.0), 
+                    handle_size + Vec2::splat(4.0), 
+                    Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.4),
+                    6.0
+                );
+            }
+            
+            renderer.draw_rounded_rect(handle_pos, handle_size, Vec4::new(1.0, 1.0, 1.0, 0.95), 4.0);
+        });
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("value", format!("{:.2}", self.value)));
+        props.push(("hovered", self.hovered.to_string()));
+        props.push(("dragging", self.dragging.to_string()));
+    }
+    
+    fn saved_state(&self) -> Option<WidgetState> {
+        Some(WidgetState::Number(self.value))
     }
 }
 
 // =============================================================================
-// CHECKBOX
+// RANGE SLIDER
 // =============================================================================
 
-/// Toggle checkbox with label
-pub struct Checkbox {
+/// One of a RangeSlider's two handles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeHandle {
+    Low,
+    High,
+}
+
+/// Height of the value labels over a RangeSlider's handles
+const RANGE_LABEL_HEIGHT: f32 = 18.0;
+
+/// Horizontal slider picking a `low..=high` range between `min` and `max`
+///
+/// A press moves the nearer handle and drags it; handles can meet but not
+/// cross. Values snap to `step` when it is set, and each handle's value is
+/// shown above it unless labels are turned off.
+pub struct RangeSlider {
     pub position: Vec2,
     pub size: Vec2,
-    pub checked: bool,
-    pub label: String,
-    pub hovered: bool,
-    pub check_t: f32,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
+    pub min: f32,
+    pub max: f32,
+    /// Snapping interval; 0 slides freely
+    pub step: f32,
+    pub low: f32,
+    pub high: f32,
+    /// Decimal places in the value labels
+    pub precision: usize,
+    pub show_labels: bool,
+    pub dragging: Option<RangeHandle>,
+    pub hovered: Option<RangeHandle>,
+    pub corner_radius: f32,
+    /// Called with the new range while a handle is dragged
+    pub on_change: Option<Box<dyn FnMut(f32, f32)>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
 }
 
-impl Checkbox {
-    pub fn new(label: &str, checked: bool) -> Self {
+impl RangeSlider {
+    pub fn new(min: f32, max: f32) -> Self {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
-            checked,
-            label: label.to_string(),
-            hovered: false,
-            check_t: if checked { 1.0 } else { 0.0 },
+            layout_intrinsic: None,
+            min,
+            max,
+            step: 0.0,
+            low: min,
+            high: max,
+            precision: 0,
+            show_labels: true,
+            dragging: None,
+            hovered: None,
+            corner_radius: 4.0,
+            on_change: None,
+            disabled: false,
         }
     }
     
-    pub fn is_checked(&self) -> bool {
-        self.checked
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self.set_range(self.low, self.high);
+        self
     }
-}
-
-impl Widget for Checkbox {
-    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
-        self.position = origin;
-        self.size = Vec2::new(24.0, 24.0);
-        Vec2::new(200.0, 24.0)
+    
+    pub fn with_range(mut self, low: f32, high: f32) -> Self {
+        self.set_range(low, high);
+        self
     }
-
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        let full_width = 200.0; // Label area too
-        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + full_width &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
-        
-        self.hovered = inside;
-                     
-        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
-            if inside {
-                self.checked = !self.checked;
-                return true;
-            }
+    
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+    
+    pub fn with_labels(mut self, show_labels: bool) -> Self {
+        self.show_labels = show_labels;
+        self
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; ends a drag
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hovered = None;
+            self.dragging = None;
         }
-        false
     }
-
-    fn update(&mut self, dt: f32) {
-        let target = if self.checked { 1.0 } else { 0.0 };
-        self.check_t += (target - self.check_t) * 15.0 * dt;
+    
+    pub fn on_change(mut self, callback: impl FnMut(f32, f32) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
     }
-
-    fn render(&self, renderer: &mut GlassRenderer) {
-        let theme = get_theme();
-        
-        // Box background
-        let bg_color = Vec4::new(0.1, 0.1, 0.12, 0.9).lerp(theme.primary, self.check_t * 0.3);
-        renderer.draw_rounded_rect(self.position, self.size, bg_color, 4.0);
-        
-        // Border
-        if self.hovered {
-            renderer.draw_rounded_rect(
-                self.position - Vec2::splat(1.0), 
-                self.size + Vec2::splat(2.0), 
-                Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.5),
-                5.0
-            );
+    
+    pub fn get_range(&self) -> (f32, f32) {
+        (self.low, self.high)
+    }
+    
+    /// Select `low..=high`, snapped and kept within `min..=max`
+    pub fn set_range(&mut self, low: f32, high: f32) {
+        let (low, high) = (self.snap(low.min(high)), self.snap(low.max(high)));
+        self.low = low;
+        self.high = high;
+    }
+    
+    /// `value` on the nearest step, within `min..=max`
+    fn snap(&self, value: f32) -> f32 {
+        snap_to_step(value, self.min, self.max, self.step)
+    }
+    
+    /// Top of the track, below the labels
+    fn track_y(&self) -> f32 {
+        self.position.y + if self.show_labels { RANGE_LABEL_HEIGHT } else { 0.0 }
+    }
+    
+    fn x_of(&self, value: f32) -> f32 {
+        let span = self.max - self.min;
+        let t = if span > 0.0 { (value - self.min) / span } else { 0.0 };
+        self.position.x + t * self.size.x
+    }
+    
+    fn value_at(&self, x: f32) -> f32 {
+        let t = ((x - self.position.x) / self.size.x.max(1.0)).clamp(0.0, 1.0);
+        self.snap(self.min + t * (self.max - self.min))
+    }
+    
+    /// Handle nearer to `x`; when they overlap, the one on the pointer's side
+    fn handle_near(&self, x: f32) -> RangeHandle {
+        let (low_x, high_x) = (self.x_of(self.low), self.x_of(self.high));
+        if (x - low_x).abs() < (x - high_x).abs() || (low_x == high_x && x < low_x) {
+            RangeHandle::Low
+        } else {
+            RangeHandle::High
         }
-        
-        // Check mark (using inner rect for now)
-        if self.check_t > 0.01 {
-            let inner_size = self.size * 0.5 * self.check_t;
-            let inner_pos = self.position + (self.size - inner_size) * 0.5;
-            renderer.draw_rounded_rect(inner_pos, inner_size, theme.primary, 2.0);
-        }
-        
-        // Label
-        renderer.draw_text(
-            &self.label, 
-            self.position + Vec2::new(self.size.x + 10.0, 2.0), 
-            16.0, 
-            if self.checked { theme.text } else { theme.text_secondary }
-        );
+    }
+    
+    /// Move `handle` to the value at `x`, stopping at the other handle
+    fn drag_to(&mut self, handle: RangeHandle, x: f32) {
+        let value = self.value_at(x);
+        let (low, high) = match handle {
+            RangeHandle::Low => (value.min(self.high), self.high),
+            RangeHandle::High => (self.low, value.max(self.low)),
+        };
+        if (low, high) != (self.low, self.high) {
+            self.low = low;
+            self.high = high;
+            if let Some(callback) = &mut self.on_change {
+                callback(low, high);
+            }
+        }
+    }
+    
+    /// The track plus the labels above it
+    fn default_size(&self) -> Size {
+        let labels = if self.show_labels { RANGE_LABEL_HEIGHT } else { 0.0 };
+        Size::new(200.0, 20.0 + labels)
+    }
+}
+
+impl Widget for RangeSlider {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        self.size = Vec2::new(200.0, 20.0);
+        self.layout_intrinsic = Some(self.default_size());
+        self.get_size().into()
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let labels = if self.show_labels { RANGE_LABEL_HEIGHT } else { 0.0 };
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, (size.height - labels).max(0.0));
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    /// The track plus the labels above it
+    fn get_size(&self) -> Size {
+        let labels = if self.show_labels { RANGE_LABEL_HEIGHT } else { 0.0 };
+        Size::new(self.size.x, self.size.y + labels)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.disabled {
+            return false;
+        }
+        let size = self.get_size();
+        let inside = mouse_pos.x >= self.position.x - 6.0 && mouse_pos.x <= self.position.x + size.width + 6.0 &&
+                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + size.height;
+        self.hovered = inside.then(|| self.handle_near(mouse_pos.x));
+
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } = event {
+            if *state == ElementState::Pressed && inside {
+                self.dragging = Some(self.handle_near(mouse_pos.x));
+            } else if *state == ElementState::Released && self.dragging.take().is_some() {
+                return true;
+            }
+        }
+        
+        if let Some(handle) = self.dragging {
+            self.drag_to(handle, mouse_pos.x);
+            return true;
+        }
+        
+        false
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        render_faded(renderer, self.disabled, None, |renderer| {
+            let track_pos = Vec2::new(self.position.x, self.track_y());
+            
+            // Track, with the selected range filled
+            renderer.draw_rounded_rect(track_pos, self.size, Vec4::new(0.0, 0.0, 0.0, 0.5), self.corner_radius);
+            let (low_x, high_x) = (self.x_of(self.low), self.x_of(self.high));
+            if high_x > low_x {
+                renderer.draw_rounded_rect(Vec2::new(low_x, track_pos.y), Vec2::new(high_x - low_x, self.size.y), theme.primary * Vec4::new(1.0, 1.0, 1.0, 0.6), self.corner_radius);
+            }
+            
+            // Tick between each step, as on a Slider
+            draw_step_ticks(self.min, self.max, self.step, |t| {
+                if t > 0.0 && t < 1.0 {
+                    renderer.draw_rect(Vec2::new(self.position.x + t * self.size.x, track_pos.y + 3.0), Vec2::new(1.0, self.size.y - 6.0), Vec4::new(1.0, 1.0, 1.0, 0.25));
+                }
+            });
+            
+            // Handles, with glow on the active one and values above
+            let handle_size = Vec2::new(12.0, self.size.y + 8.0);
+            for (handle, x, value) in [(RangeHandle::Low, low_x, self.low), (RangeHandle::High, high_x, self.high)] {
+                let handle_pos = Vec2::new(x - handle_size.x * 0.5, track_pos.y - 4.0);
+                if self.dragging == Some(handle) || (self.dragging.is_none() && self.hovered == Some(handle)) {
+                    renderer.draw_rounded_rect(
+                        handle_pos - Vec2::splat(2.0),
+                        handle_size + Vec2::splat(4.0),
+                        Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.4),
+                        6.0
+                    );
+                }
+                renderer.draw_rounded_rect(handle_pos, handle_size, Vec4::new(1.0, 1.0, 1.0, 0.95), 4.0);
+                
+                if self.show_labels {
+                    let label = format!("{:.*}", self.precision, value);
+                    let width = renderer.measure_text(&label, 12.0).width;
+                    // Keep labels inside the track's ends
+                    let label_x = (x - width / 2.0).clamp(self.position.x, (self.position.x + self.size.x - width).max(self.position.x));
+                    renderer.draw_text(&label, Vec2::new(label_x, self.position.y), 12.0, theme.text_secondary);
+                }
+            }
+        });
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("low", format!("{:.2}", self.low)));
+        props.push(("high", format!("{:.2}", self.high)));
+        props.push(("dragging", format!("{:?}", self.dragging)));
+    }
+}
+
+// =============================================================================
+// KNOB
+// =============================================================================
+
+/// How dragging turns a Knob
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KnobDrag {
+    /// Up increases the value, whatever the pointer's angle
+    #[default]
+    Vertical,
+    /// The indicator follows the pointer around the dial
+    Circular,
+}
+
+/// Angle of a Knob's minimum, pointing down-left (0 points right)
+const KNOB_START_ANGLE: f32 = 0.75 * std::f32::consts::PI;
+/// Angle from a Knob's minimum round to its maximum
+const KNOB_SWEEP: f32 = 1.5 * std::f32::consts::PI;
+/// Pixels of vertical drag from one end of a Knob's range to the other
+const KNOB_DRAG_PIXELS: f32 = 200.0;
+/// Height of the value shown under a Knob's dial
+const KNOB_LABEL_HEIGHT: f32 = 18.0;
+
+/// Rotary control for a value between `min` and `max`
+///
+/// Takes less room than a slider: the dial's arc shows the value (from
+/// zero when the range spans it) and the value is printed underneath.
+/// Drag up/down (or around, see `KnobDrag`), scroll, or use the arrow
+/// keys while focused; with `detents` set the value clicks into steps.
+pub struct Knob {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    /// Snapping interval; 0 turns freely
+    pub detents: f32,
+    pub drag: KnobDrag,
+    /// Decimal places in the value label
+    pub precision: usize,
+    /// Shown after the value, e.g. "dB" or "%"
+    pub unit: String,
+    /// Pointer y and value when the drag began
+    pub dragging: Option<(f32, f32)>,
+    pub hovered: bool,
+    pub focused: bool,
+    /// Identifies the knob to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
+    /// Called with the new value while the knob is turned
+    pub on_change: Option<Box<dyn FnMut(f32)>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
+}
+
+impl Knob {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            layout_intrinsic: None,
+            min,
+            max,
+            value: min,
+            detents: 0.0,
+            drag: KnobDrag::Vertical,
+            precision: 0,
+            unit: String::new(),
+            dragging: None,
+            hovered: false,
+            focused: false,
+            focus_id: FocusId::new(),
+            on_change: None,
+            disabled: false,
+        }
+    }
+    
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = self.snap(value);
+        self
+    }
+    
+    /// Click into multiples of `step` from `min`
+    pub fn with_detents(mut self, step: f32) -> Self {
+        self.detents = step.max(0.0);
+        self.value = self.snap(self.value);
+        self
+    }
+    
+    pub fn with_drag(mut self, drag: KnobDrag) -> Self {
+        self.drag = drag;
+        self
+    }
+    
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+    
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = unit.to_string();
+        self
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; ends a drag and drops focus
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hovered = false;
+            self.dragging = None;
+            self.focused = false;
+        }
+    }
+    
+    pub fn on_change(mut self, callback: impl FnMut(f32) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+    
+    /// `value` on the nearest detent, within `min..=max`
+    fn snap(&self, value: f32) -> f32 {
+        snap_to_step(value, self.min, self.max, self.detents)
+    }
+    
+    /// Set the value from user input, snapped, reporting a change
+    fn set_from_input(&mut self, value: f32) {
+        let value = self.snap(value);
+        if value != self.value {
+            self.value = value;
+            if let Some(callback) = &mut self.on_change {
+                callback(value);
+            }
+        }
+    }
+    
+    /// Turn `steps` detents up (or down, if negative), as the arrow keys
+    /// and the wheel do; without detents a step is 1% of the range
+    pub fn nudge(&mut self, steps: f32) {
+        let step = if self.detents > 0.0 { self.detents } else { (self.max - self.min) / 100.0 };
+        self.set_from_input(self.value + steps * step);
+    }
+    
+    /// Position of `value` along the dial, from 0 at `min` to 1 at `max`
+    fn fraction(&self, value: f32) -> f32 {
+        let span = self.max - self.min;
+        if span > 0.0 { ((value - self.min) / span).clamp(0.0, 1.0) } else { 0.0 }
+    }
+    
+    fn center(&self) -> Vec2 {
+        self.position + Vec2::splat(self.radius())
+    }
+    
+    fn radius(&self) -> f32 {
+        self.size.x.min(self.size.y - KNOB_LABEL_HEIGHT).max(0.0) / 2.0
+    }
+    
+    /// Value for the pointer at `point` in circular mode; positions in
+    /// the gap at the bottom go to the nearer end
+    fn value_at_angle(&self, point: Vec2) -> f32 {
+        let offset = point - self.center();
+        let angle = (offset.y.atan2(offset.x) - KNOB_START_ANGLE).rem_euclid(std::f32::consts::TAU);
+        let t = if angle <= KNOB_SWEEP {
+            angle / KNOB_SWEEP
+        } else if angle < KNOB_SWEEP + (std::f32::consts::TAU - KNOB_SWEEP) / 2.0 {
+            1.0
+        } else {
+            0.0
+        };
+        self.min + t * (self.max - self.min)
+    }
+    
+    /// Value for the pointer at `y` in vertical mode
+    fn value_at_drag(&self, start_y: f32, start_value: f32, y: f32) -> f32 {
+        start_value + (start_y - y) / KNOB_DRAG_PIXELS * (self.max - self.min)
+    }
+    
+    fn default_size(&self) -> Size {
+        Size::new(64.0, 64.0 + KNOB_LABEL_HEIGHT)
+    }
+}
+
+impl Focusable for Knob {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn can_focus(&self) -> bool {
+        !self.disabled
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+    }
+}
+
+impl Widget for Knob {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        if self.disabled {
+            return false;
+        }
+        let on_dial = mouse_pos.distance(self.center()) <= self.radius();
+        self.hovered = on_dial;
+        
+        match event {
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. } => {
+                use winit::keyboard::{Key, NamedKey};
+                if !self.focused || !key_event.state.is_pressed() {
+                    return false;
+                }
+                match key_event.logical_key {
+                    Key::Named(NamedKey::ArrowRight | NamedKey::ArrowUp) => self.nudge(1.0),
+                    Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowDown) => self.nudge(-1.0),
+                    Key::Named(NamedKey::Home) => self.set_from_input(self.min),
+                    Key::Named(NamedKey::End) => self.set_from_input(self.max),
+                    _ => return false,
+                }
+                return true;
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } => {
+                if *state == ElementState::Pressed && on_dial {
+                    self.dragging = Some((mouse_pos.y, self.value));
+                    if self.drag == KnobDrag::Circular {
+                        self.set_from_input(self.value_at_angle(mouse_pos));
+                    }
+                    return true;
+                } else if *state == ElementState::Released && self.dragging.take().is_some() {
+                    return true;
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } if on_dial => {
+                let lines = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(_, y) => *y,
+                    winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
+                };
+                if lines != 0.0 {
+                    self.nudge(lines.signum());
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        
+        if let Some((start_y, start_value)) = self.dragging {
+            let value = match self.drag {
+                KnobDrag::Vertical => self.value_at_drag(start_y, start_value, mouse_pos.y),
+                KnobDrag::Circular => self.value_at_angle(mouse_pos),
+            };
+            self.set_from_input(value);
+            return true;
+        }
+        
+        false
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        render_faded(renderer, self.disabled, None, |renderer| {
+            let center = self.center();
+            let radius = self.radius();
+            let ring = (radius * 0.16).max(3.0);
+            
+            // Track, then the value arc from the minimum (or from zero)
+            renderer.draw_arc(center, radius, KNOB_START_ANGLE, KNOB_SWEEP, ring, Vec4::new(0.0, 0.0, 0.0, 0.5));
+            let origin = if self.min < 0.0 && self.max > 0.0 { self.fraction(0.0) } else { 0.0 };
+            let t = self.fraction(self.value);
+            renderer.draw_arc(center, radius, KNOB_START_ANGLE + origin * KNOB_SWEEP, (t - origin) * KNOB_SWEEP, ring, theme.primary);
+            
+            // Detent ticks
+            draw_step_ticks(self.min, self.max, self.detents, |t| {
+                let angle = KNOB_START_ANGLE + t * KNOB_SWEEP;
+                let dir = Vec2::new(angle.cos(), angle.sin());
+                renderer.draw_line(center + dir * (radius - ring - 4.0), center + dir * (radius - ring - 1.0), 1.0, Vec4::new(1.0, 1.0, 1.0, 0.3));
+            });
+            
+            // Body, glowing while hovered, dragged or focused
+            let body = radius - ring - 6.0;
+            if self.hovered || self.dragging.is_some() || self.focused {
+                renderer.draw_circle(center, body + 3.0, Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.4));
+            }
+            renderer.draw_circle(center, body, Vec4::new(0.16, 0.16, 0.2, 0.95));
+            
+            // Indicator pointing at the value
+            let angle = KNOB_START_ANGLE + t * KNOB_SWEEP;
+            let dir = Vec2::new(angle.cos(), angle.sin());
+            renderer.draw_line(center + dir * body * 0.35, center + dir * (body - 3.0), 2.5, Vec4::new(1.0, 1.0, 1.0, 0.95));
+            
+            // Value under the dial
+            let label = format!("{:.*}{}", self.precision, self.value, self.unit);
+            let width = renderer.measure_text(&label, 12.0).width;
+            renderer.draw_text(&label, Vec2::new(center.x - width / 2.0, self.position.y + radius * 2.0 + 3.0), 12.0, theme.text_secondary);
+        });
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("value", format!("{:.*}", self.precision.max(2), self.value)));
+        props.push(("dragging", self.dragging.is_some().to_string()));
+    }
+    
+    fn saved_state(&self) -> Option<WidgetState> {
+        Some(WidgetState::Number(self.value))
+    }
+}
+
+// =============================================================================
+// CHECKBOX
+// =============================================================================
+
+/// State of a tri-state checkbox
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CheckState {
+    #[default]
+    Unchecked,
+    Checked,
+    /// Some but not all of the items it stands for are checked
+    Indeterminate,
+}
+
+impl CheckState {
+    /// State of a "select all" box over `total` items, `checked` of them on
+    pub fn from_counts(checked: usize, total: usize) -> Self {
+        match checked {
+            0 => CheckState::Unchecked,
+            n if n >= total => CheckState::Checked,
+            _ => CheckState::Indeterminate,
+        }
+    }
+    
+    /// State of a box over items in these states, such as a parent node's
+    /// children; checked only if all are, unchecked only if none is
+    pub fn combine(states: impl IntoIterator<Item = CheckState>) -> Self {
+        let (mut any_on, mut any_off) = (false, false);
+        for state in states {
+            match state {
+                CheckState::Checked => any_on = true,
+                CheckState::Unchecked => any_off = true,
+                CheckState::Indeterminate => return CheckState::Indeterminate,
+            }
+            if any_on && any_off {
+                return CheckState::Indeterminate;
+            }
+        }
+        if any_on { CheckState::Checked } else { CheckState::Unchecked }
+    }
+    
+    pub fn from_bool(checked: bool) -> Self {
+        if checked { CheckState::Checked } else { CheckState::Unchecked }
+    }
+    
+    /// What a click does: mixed and unchecked boxes become checked
+    pub fn toggled(self) -> Self {
+        Self::from_bool(self != CheckState::Checked)
+    }
+}
+
+/// Side of the square box, and the height of a checkbox row
+const CHECKBOX_SIZE: f32 = 24.0;
+
+/// Toggle checkbox with label
+///
+/// Besides on and off the box can show a mixed state (a dash), for
+/// "select all" boxes over partly checked items. Clicking a mixed box
+/// checks it.
+pub struct Checkbox {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
+    pub state: CheckState,
+    pub label: String,
+    pub hovered: bool,
+    pub check_t: f32,
+    /// Called with the new state when the box is clicked
+    pub on_toggle: Option<Box<dyn FnMut(bool) + Send>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
+}
+
+impl Checkbox {
+    pub fn new(label: &str, checked: bool) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            layout_intrinsic: None,
+            state: CheckState::from_bool(checked),
+            label: label.to_string(),
+            hovered: false,
+            check_t: if checked { 1.0 } else { 0.0 },
+            on_toggle: None,
+            disabled: false,
+        }
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hovered = false;
+        }
+    }
+    
+    pub fn on_toggle(mut self, callback: impl FnMut(bool) + Send + 'static) -> Self {
+        self.on_toggle = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn is_checked(&self) -> bool {
+        self.state == CheckState::Checked
+    }
+    
+    /// Whether the box is checked (an indeterminate box isn't); replaces
+    /// reading the old `checked` field
+    pub fn checked(&self) -> bool {
+        self.is_checked()
+    }
+    
+    /// Check or clear the box from code; replaces writing the old `checked`
+    /// field. Doesn't call `on_toggle`.
+    pub fn set_checked(&mut self, checked: bool) {
+        self.set_state(CheckState::from_bool(checked));
+    }
+    
+    pub fn with_state(mut self, state: CheckState) -> Self {
+        self.set_state(state);
+        self.check_t = if state == CheckState::Unchecked { 0.0 } else { 1.0 };
+        self
+    }
+    
+    pub fn state(&self) -> CheckState {
+        self.state
+    }
+    
+    /// Set the state from code, e.g. from the items a "select all" box
+    /// covers; doesn't call `on_toggle`
+    pub fn set_state(&mut self, state: CheckState) {
+        self.state = state;
+    }
+    
+    fn toggle(&mut self) {
+        self.state = self.state.toggled();
+        let checked = self.is_checked();
+        if let Some(callback) = &mut self.on_toggle {
+            callback(checked);
+        }
+    }
+    
+    fn label_position(&self) -> Vec2 {
+        self.position + Vec2::new(self.box_size().x + 10.0, 2.0)
+    }
+    
+    /// The box stays square at the left; the label beside it takes the rest
+    fn box_size(&self) -> Vec2 {
+        Vec2::splat(CHECKBOX_SIZE.min(self.size.y))
+    }
+    
+    fn default_size(&self) -> Size {
+        Size::new(200.0, CHECKBOX_SIZE)
+    }
+}
+
+impl Widget for Checkbox {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.disabled {
+            return false;
+        }
+        // Label area too
+        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
+                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
+        
+        self.hovered = inside;
+                     
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
+            if inside {
+                self.toggle();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.update_state(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, _dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        jobs.push(self);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        render_faded(renderer, self.disabled, None, |renderer| {
+            draw_check_box(renderer, self.position, self.box_size(), self.state, self.check_t, self.hovered);
+            
+            // Label
+            renderer.draw_text(
+                &self.label, 
+                self.label_position(), 
+                16.0, 
+                if self.state != CheckState::Unchecked { theme.text } else { theme.text_secondary }
+            );
+        });
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("label", self.label.clone()));
+        props.push(("state", format!("{:?}", self.state())));
+        props.push(("hovered", self.hovered.to_string()));
+    }
+    
+    fn saved_state(&self) -> Option<WidgetState> {
+        Some(WidgetState::Bool(self.is_checked()))
+    }
+    
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        Some((self.label_position(), 16.0))
+    }
+}
+
+impl UpdateState for Checkbox {
+    fn update_state(&mut self, dt: f32) {
+        let target = if self.state == CheckState::Unchecked { 0.0 } else { 1.0 };
+        self.check_t += (target - self.check_t) * 15.0 * dt;
+    }
+}
+
+/// Draw a checkbox's box at `position`, `t` of the way from empty to
+/// showing `state`; shared with list widgets that draw rows of boxes
+pub(super) fn draw_check_box(renderer: &mut GlassRenderer, position: Vec2, size: Vec2, state: CheckState, t: f32, hovered: bool) {
+    let theme = get_theme();
+    
+    // Box background
+    let bg_color = Vec4::new(0.1, 0.1, 0.12, 0.9).lerp(theme.primary, t * 0.3);
+    renderer.draw_rounded_rect(position, size, bg_color, 4.0);
+    
+    // Border
+    if hovered {
+        renderer.draw_rounded_rect(
+            position - Vec2::splat(1.0), 
+            size + Vec2::splat(2.0), 
+            Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.5),
+            5.0
+        );
+    }
+    
+    if t <= 0.01 {
+        return;
+    }
+    if state == CheckState::Indeterminate {
+        // Mixed: a dash across the middle
+        let dash_size = Vec2::new(size.x * 0.55 * t, 3.0);
+        let dash_pos = position + (size - dash_size) * 0.5;
+        renderer.draw_rounded_rect(dash_pos, dash_size, theme.primary, 1.5);
+    } else {
+        // Check mark (using inner rect for now)
+        let inner_size = size * 0.5 * t;
+        let inner_pos = position + (size - inner_size) * 0.5;
+        renderer.draw_rounded_rect(inner_pos, inner_size, theme.primary, 2.0);
     }
 }
 
@@ -485,9 +1774,22 @@ pub struct Panel {
     pub size: Vec2,
     pub content: Option<Box<dyn Widget>>,
     pub color: Vec4,
+    /// Drawn instead of `color` when set
+    pub gradient: Option<Gradient>,
+    /// Drop shadow cast below the panel
+    pub shadow: Option<Shadow>,
+    /// Clip content to the panel's rounded rect
+    pub clip: bool,
+    /// Blur whatever is drawn behind the panel, not just the background
+    /// (0 sharp to 1 full blur, see `GlassRenderer::draw_backdrop_glass`)
+    pub backdrop_blur: Option<f32>,
     pub fill: bool,
     pub corner_radius: f32,
     pub padding: f32,
+    /// Per-instance overrides: per-side padding, border and opacity
+    /// (see `with_style`)
+    pub style: WidgetStyle,
+    layout_cache: LayoutCache,
 }
 
 impl Panel {
@@ -497,9 +1799,15 @@ impl Panel {
             size: Vec2::ZERO,
             content: Some(content),
             color: Vec4::new(1.0, 1.0, 1.0, 0.05),
+            gradient: None,
+            shadow: None,
+            clip: false,
+            backdrop_blur: None,
             fill: false,
             corner_radius: 12.0,
             padding: 20.0,
+            style: WidgetStyle::default(),
+            layout_cache: LayoutCache::new(),
         }
     }
     
@@ -509,9 +1817,15 @@ impl Panel {
             size: Vec2::ZERO,
             content: None,
             color: Vec4::new(1.0, 1.0, 1.0, 0.05),
+            gradient: None,
+            shadow: None,
+            clip: false,
+            backdrop_blur: None,
             fill: false,
             corner_radius: 12.0,
             padding: 20.0,
+            style: WidgetStyle::default(),
+            layout_cache: LayoutCache::new(),
         }
     }
 
@@ -520,6 +1834,29 @@ impl Panel {
         self
     }
     
+    /// Gradient background (e.g. a header fading into the content)
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+    
+    /// Clip content to the rounded corners instead of letting it overflow
+    pub fn with_clip(mut self, clip: bool) -> Self {
+        self.clip = clip;
+        self
+    }
+    
+    /// Frosted glass over other panels and charts, not just the background
+    pub fn with_backdrop_blur(mut self, strength: f32) -> Self {
+        self.backdrop_blur = Some(strength.clamp(0.0, 1.0));
+        self
+    }
+    
+    pub fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+    
     pub fn with_fill(mut self, fill: bool) -> Self {
         self.fill = fill;
         self
@@ -529,17 +1866,42 @@ impl Panel {
         self.corner_radius = radius;
         self
     }
+    
+    /// Override theme values for this panel: background, shadow and radius
+    /// replace the fields, while padding (per side, taking over from the
+    /// uniform `padding`), border and opacity are kept in `style`.
+    /// Repeated calls layer on top of each other.
+    pub fn with_style(mut self, style: WidgetStyle) -> Self {
+        if let Some(color) = style.background {
+            self.color = color;
+        }
+        if let Some(shadow) = style.shadow {
+            self.shadow = Some(shadow);
+        }
+        if let Some(radius) = style.corner_radius {
+            self.corner_radius = radius;
+        }
+        self.style = self.style.merge(&style);
+        self
+    }
 }
 
 impl Widget for Panel {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
         self.position = origin;
         
-        let content_available = max_size - Vec2::splat(self.padding * 2.0);
+        let insets = self.content_insets();
+        let inset_size = Vec2::new(insets.horizontal(), insets.vertical());
+        let content_available = max_size - inset_size;
 
         let content_size = if let Some(content) = &mut self.content {
-            let content_origin = origin + Vec2::splat(self.padding);
-            content.layout(content_origin, content_available)
+            let content_origin = origin + Vec2::new(insets.left, insets.top);
+            content.layout_child(content_origin, content_available)
         } else {
             Vec2::ZERO
         };
@@ -547,11 +1909,92 @@ impl Widget for Panel {
         self.size = if self.fill {
             max_size
         } else {
-            content_size + Vec2::splat(self.padding * 2.0)
+            content_size + inset_size
         };
         
+        self.layout_cache.store(origin, max_size, self.size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let insets = self.content_insets();
+        let content_size = match &mut self.content {
+            Some(content) => content.layout_child_with_constraints(constraints.deflate(insets).loosen()),
+            None => Size::ZERO,
+        };
+        let natural = Size::new(content_size.width + insets.horizontal(), content_size.height + insets.vertical());
+        let size = if self.fill { constraints.biggest_or(natural) } else { constraints.constrain(natural) };
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        let insets = self.content_insets();
+        if let Some(content) = &mut self.content {
+            content.set_position(position + Offset::new(insets.left, insets.top));
+        }
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        if let Some(content) = &self.content {
+            visitor(content.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        if let Some(content) = &mut self.content {
+            visitor(content.as_mut());
+        }
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("padding", format!("{:.0}", self.padding)));
+        props.push(("corner radius", format!("{:.0}", self.corner_radius)));
+        props.push(("color", format_color(self.color)));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn widget_style(&self) -> Option<&WidgetStyle> {
+        Some(&self.style)
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        self.style.padding.unwrap_or(EdgeInsets::all(self.padding))
+    }
+    
+    fn clips_content(&self) -> bool {
+        self.clip
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.content.as_ref().is_some_and(|c| c.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.content.as_ref().is_some_and(|c| c.is_animating())
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        let own = Rect::new(self.position, self.size);
+        match self.content.as_ref().and_then(|c| c.render_bounds()) {
+            Some(content) if !self.clip => Some(own.union(&content)),
+            _ => Some(own),
+        }
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        Some(rounded_opaque_rect(self.position, self.size, self.corner_radius))
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         if let Some(content) = &mut self.content {
@@ -567,11 +2010,361 @@ impl Widget for Panel {
         }
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        if let Some(content) = &mut self.content {
+            content.split_update(dt, jobs);
+        }
+    }
+
     fn render(&self, renderer: &mut GlassRenderer) {
-        renderer.draw_rounded_rect(self.position, self.size, self.color, self.corner_radius);
-        
-        if let Some(content) = &self.content {
-            content.render(renderer);
+        render_faded(renderer, false, self.style.opacity, |renderer| {
+            if let Some(shadow) = &self.shadow {
+                renderer.draw_box_shadow(self.position, self.size, self.corner_radius, shadow);
+            }
+            match (&self.gradient, self.backdrop_blur) {
+                (Some(gradient), _) => renderer.draw_rect_gradient(self.position, self.size, gradient, self.corner_radius),
+                (None, Some(blur)) => renderer.draw_backdrop_glass(self.position, self.size, self.color, self.corner_radius, blur),
+                (None, None) => renderer.draw_rounded_rect(self.position, self.size, self.color, self.corner_radius),
+            }
+            
+            if let Some(content) = &self.content {
+                if self.clip {
+                    renderer.push_rounded_clip(Rect::new(self.position, self.size), self.corner_radius);
+                    content.render(renderer);
+                    renderer.pop_clip();
+                } else {
+                    content.render(renderer);
+                }
+            }
+            if let Some(border) = self.style.border_for(false) {
+                renderer.draw_border(&border, self.position, self.size, self.corner_radius);
+            }
+        });
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use winit::event::{Event, WindowEvent};
+    use crate::events::EventDispatcher;
+    use crate::widgets::TextInput;
+    
+    fn mouse_event(state: ElementState) -> Event<()> {
+        Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::MouseInput {
+                device_id: unsafe { winit::event::DeviceId::dummy() },
+                state,
+                button: MouseButton::Left,
+            },
         }
     }
+    
+    fn mouse(widget: &mut dyn Widget, state: ElementState, pos: Vec2) -> bool {
+        widget.handle_event(&mouse_event(state), pos)
+    }
+    
+    #[test]
+    fn test_control_callbacks() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        
+        let sink = log.clone();
+        let mut slider = Slider::new(0.0).on_change(move |v| sink.lock().unwrap().push(format!("slider {v}")));
+        slider.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        mouse(&mut slider, ElementState::Pressed, Vec2::new(50.0, 10.0));
+        mouse(&mut slider, ElementState::Released, Vec2::new(50.0, 10.0));
+        
+        let sink = log.clone();
+        let mut checkbox = Checkbox::new("Sound", false).on_toggle(move |on| sink.lock().unwrap().push(format!("checkbox {on}")));
+        checkbox.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        mouse(&mut checkbox, ElementState::Pressed, Vec2::new(10.0, 10.0));
+        mouse(&mut checkbox, ElementState::Pressed, Vec2::new(10.0, 10.0));
+        
+        // A press that changes nothing reports nothing
+        let sink = log.clone();
+        let mut button = Button::new("Go").on_click(move || sink.lock().unwrap().push("button".to_string()));
+        button.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        mouse(&mut button, ElementState::Pressed, Vec2::new(300.0, 90.0));
+        
+        assert_eq!(*log.lock().unwrap(), vec!["slider 0.25", "checkbox true", "checkbox false"]);
+    }
+    
+    #[test]
+    fn test_disabled_controls_ignore_input() {
+        let toggles = Arc::new(Mutex::new(0));
+        let sink = toggles.clone();
+        let mut checkbox = Checkbox::new("Sound", false).on_toggle(move |_| *sink.lock().unwrap() += 1).with_disabled(true);
+        checkbox.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        assert!(!mouse(&mut checkbox, ElementState::Pressed, Vec2::new(10.0, 10.0)));
+        assert!(!checkbox.is_checked());
+        
+        checkbox.set_disabled(false);
+        assert!(mouse(&mut checkbox, ElementState::Pressed, Vec2::new(10.0, 10.0)));
+        assert_eq!(*toggles.lock().unwrap(), 1);
+        
+        // The callback doesn't keep the check animation off worker threads
+        let mut jobs = Vec::new();
+        checkbox.split_update(0.1, &mut jobs);
+        assert_eq!(jobs.len(), 1);
+        
+        // A disabled field can't be focused by clicking it
+        let mut dispatcher = EventDispatcher::new();
+        let mut input = TextInput::new("Name").with_disabled(true);
+        input.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        dispatcher.dispatch(&mut input, &mouse_event(ElementState::Pressed), Vec2::new(10.0, 10.0));
+        assert!(!input.focused);
+        assert_eq!(dispatcher.focused(), None);
+    }
+    
+    #[test]
+    fn test_with_style_overrides_layout() {
+        let style = WidgetStyle::new()
+            .padding(EdgeInsets::symmetric(8.0, 4.0))
+            .corner_radius(2.0)
+            .background(Vec4::ONE);
+        let mut button = Button::new("Go").with_min_size(0.0, 0.0).with_style(style.clone());
+        assert_eq!(button.layout(Vec2::ZERO, Vec2::splat(400.0)), Vec2::new(36.0, 28.0));
+        assert_eq!(button.corner_radius, 2.0);
+        assert_eq!(button.style.background, Some(Vec4::ONE));
+        
+        // Per-side padding takes over from the panel's uniform padding
+        let style = style.padding(EdgeInsets { top: 1.0, right: 2.0, bottom: 3.0, left: 4.0 });
+        let mut panel = Panel::new(Box::new(button)).with_style(style);
+        assert_eq!(panel.layout(Vec2::ZERO, Vec2::splat(400.0)), Vec2::new(42.0, 32.0));
+        assert_eq!(panel.color, Vec4::ONE);
+        let content = panel.content.as_ref().unwrap();
+        assert_eq!((content.get_position().x, content.get_position().y), (4.0, 1.0));
+    }
+    
+    #[test]
+    fn test_vertical_stepped_slider() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let sink = log.clone();
+        let mut slider = Slider::new(0.33).with_orientation(Orientation::Vertical).with_step(0.25)
+            .on_change(move |v| sink.borrow_mut().push(v));
+        assert_eq!(slider.value, 0.25);
+        assert_eq!(slider.layout(Vec2::ZERO, Vec2::splat(400.0)), Vec2::new(20.0, 200.0));
+        
+        // Up the track is up the range: 140px from the bottom snaps to 0.75
+        mouse(&mut slider, ElementState::Pressed, Vec2::new(10.0, 60.0));
+        mouse(&mut slider, ElementState::Released, Vec2::new(10.0, 60.0));
+        assert_eq!(slider.value, 0.75);
+        slider.nudge(1.0);
+        slider.nudge(1.0);
+        assert_eq!(slider.value, 1.0);
+        slider.nudge(-1.0);
+        assert_eq!(*log.borrow(), vec![0.75, 1.0, 0.75]);
+    }
+    
+    #[test]
+    fn test_slider_bounds_and_focus_on_press() {
+        let mut slider = Slider::new(0.5).with_bounds(-20.0, 20.0).with_step(5.0).with_value(12.0);
+        assert_eq!(slider.get_value(), 10.0);
+        slider.layout(Vec2::ZERO, Vec2::splat(400.0));
+        
+        // A press focuses and sets the value from the track position
+        mouse(&mut slider, ElementState::Pressed, Vec2::new(150.0, 10.0));
+        mouse(&mut slider, ElementState::Released, Vec2::new(150.0, 10.0));
+        assert!(slider.focused);
+        assert_eq!(slider.get_value(), 10.0);
+        assert_eq!(slider.fraction(), 0.75);
+        slider.nudge(1.0);
+        assert_eq!(slider.get_value(), 15.0);
+        
+        // Unstepped, keys move a twentieth of the range
+        slider.step = 0.0;
+        slider.nudge(-1.0);
+        assert_eq!(slider.get_value(), 13.0);
+        
+        mouse(&mut slider, ElementState::Pressed, Vec2::new(300.0, 300.0));
+        assert!(!slider.focused);
+    }
+    
+    #[test]
+    fn test_snap_to_step_and_ticks() {
+        assert_eq!(snap_to_step(0.33, 0.0, 1.0, 0.25), 0.25);
+        assert_eq!(snap_to_step(-40.0, -50.0, 50.0, 30.0), -50.0);
+        // The last step may be short; the value never passes `max`
+        assert_eq!(snap_to_step(48.0, -50.0, 50.0, 30.0), 40.0);
+        assert_eq!(snap_to_step(60.0, -50.0, 50.0, 40.0), 50.0);
+        assert_eq!(snap_to_step(7.3, 0.0, 5.0, 0.0), 5.0);
+        
+        let mut ticks = Vec::new();
+        draw_step_ticks(-1.0, 1.0, 0.5, |t| ticks.push(t));
+        assert_eq!(ticks, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        ticks.clear();
+        draw_step_ticks(0.0, 1.0, 0.001, |t| ticks.push(t));
+        draw_step_ticks(0.0, 1.0, 0.0, |t| ticks.push(t));
+        assert!(ticks.is_empty());
+    }
+    
+    #[test]
+    fn test_range_slider_handles_snap_and_stop_at_each_other() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let sink = log.clone();
+        let mut range = RangeSlider::new(0.0, 100.0).with_step(10.0).with_range(20.0, 80.0)
+            .on_change(move |low, high| sink.borrow_mut().push((low, high)));
+        assert_eq!(range.layout_with_constraints(BoxConstraints::loose(Size::new(200.0, 100.0))), Size::new(200.0, 38.0));
+        range.set_position(Offset::ZERO);
+        
+        // The press picks the nearer handle; values snap to the step
+        mouse(&mut range, ElementState::Pressed, Vec2::new(150.0, 28.0));
+        assert_eq!(range.dragging, Some(RangeHandle::High));
+        let moved = crate::recorder::RecordedEvent::CursorMoved { x: 133.0, y: 28.0 }.to_event().unwrap();
+        range.handle_event(&moved, Vec2::new(133.0, 28.0));
+        assert_eq!(range.get_range(), (20.0, 70.0));
+        
+        // Dragged past the low handle, the high one stops on it
+        range.handle_event(&moved, Vec2::new(10.0, 28.0));
+        mouse(&mut range, ElementState::Released, Vec2::new(10.0, 28.0));
+        assert_eq!(range.get_range(), (20.0, 20.0));
+        assert_eq!(range.dragging, None);
+        assert_eq!(*log.borrow(), vec![(20.0, 70.0), (20.0, 20.0)]);
+    }
+    
+    #[test]
+    fn test_knob_turns_by_drag_angle_and_wheel() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let sink = log.clone();
+        let mut knob = Knob::new(-12.0, 12.0).with_detents(3.0).with_value(1.0)
+            .on_change(move |v| sink.borrow_mut().push(v));
+        assert_eq!(knob.value, 0.0);
+        assert_eq!(knob.layout(Vec2::ZERO, Vec2::splat(400.0)), Vec2::new(64.0, 82.0));
+        
+        // 200px of drag covers the range: 50px up is +6
+        let moved = |y: f64| crate::recorder::RecordedEvent::CursorMoved { x: 32.0, y }.to_event().unwrap();
+        mouse(&mut knob, ElementState::Pressed, Vec2::new(32.0, 32.0));
+        knob.handle_event(&moved(-18.0), Vec2::new(32.0, -18.0));
+        mouse(&mut knob, ElementState::Released, Vec2::new(32.0, -18.0));
+        assert_eq!(knob.value, 6.0);
+        
+        // Circular: straight up is the middle, the gap's halves go to the ends
+        knob.drag = KnobDrag::Circular;
+        assert_eq!(knob.value_at_angle(Vec2::new(32.0, 0.0)), 0.0);
+        assert!((knob.value_at_angle(Vec2::new(64.0, 32.0)) - 8.0).abs() < 1e-4);
+        assert_eq!(knob.value_at_angle(Vec2::new(31.0, 64.0)), -12.0);
+        assert_eq!(knob.value_at_angle(Vec2::new(33.0, 64.0)), 12.0);
+        
+        let wheel = crate::recorder::RecordedEvent::MouseWheel { dx: 0.0, dy: -1.0, lines: true }.to_event().unwrap();
+        assert!(knob.handle_event(&wheel, Vec2::new(32.0, 32.0)));
+        assert_eq!(*log.borrow(), vec![6.0, 3.0]);
+    }
+    
+    #[test]
+    fn test_label_overflow_modes() {
+        // No renderer: 10px per character at size 20
+        let label = |overflow| Label::new("hello world").with_size(20.0).with_overflow(overflow);
+        assert_eq!(Label::new("").with_wrap().overflow, TextOverflow::Wrap);
+        
+        let mut wrapped = label(TextOverflow::Wrap);
+        assert_eq!(wrapped.layout(Vec2::ZERO, Vec2::new(60.0, 200.0)), Vec2::new(50.0, 44.0));
+        assert!(!wrapped.needs_layout());
+        
+        let mut ellipsized = label(TextOverflow::Ellipsis);
+        assert_eq!(ellipsized.layout(Vec2::ZERO, Vec2::new(60.0, 200.0)), Vec2::new(60.0, 20.0));
+        
+        let mut visible = label(TextOverflow::Visible);
+        assert_eq!(visible.layout(Vec2::ZERO, Vec2::new(60.0, 200.0)), Vec2::new(110.0, 20.0));
+    }
+    
+    #[test]
+    fn test_checkbox_size_covers_label() {
+        let mut checkbox = Checkbox::new("Sound", false);
+        let size = checkbox.layout_with_constraints(BoxConstraints::loose(Size::new(400.0, 100.0)));
+        assert_eq!(size, Size::new(200.0, CHECKBOX_SIZE));
+        assert_eq!(checkbox.get_size(), size);
+        assert_eq!(checkbox.box_size(), Vec2::splat(CHECKBOX_SIZE));
+        
+        // The label is part of the hit area
+        checkbox.set_position(Offset::new(20.0, 30.0));
+        mouse(&mut checkbox, ElementState::Pressed, Vec2::new(180.0, 40.0));
+        assert!(checkbox.is_checked());
+        
+        // Squeezed, the box shrinks with the row and stays square
+        checkbox.layout_with_constraints(BoxConstraints::tight(Size::new(120.0, 16.0)));
+        assert_eq!(checkbox.box_size(), Vec2::splat(16.0));
+    }
+    
+    #[test]
+    fn test_tri_state_checkbox() {
+        use crate::widgets::{CellValue, DataTable, GridRow, TreeNode, TreeView};
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let sink = log.clone();
+        let mut table = DataTable::new().with_rows(vec![
+            GridRow::new("a", vec![CellValue::text("a")]).with_selected(true),
+            GridRow::new("b", vec![CellValue::text("b")]),
+        ]);
+        
+        // A partly selected table gives a mixed header box; a click checks it
+        let mut header = Checkbox::new("All", false)
+            .with_state(table.select_all_state())
+            .on_toggle(move |on| sink.lock().unwrap().push(on));
+        header.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        assert_eq!(header.state(), CheckState::Indeterminate);
+        mouse(&mut header, ElementState::Pressed, Vec2::new(10.0, 10.0));
+        assert_eq!(header.state(), CheckState::Checked);
+        table.set_all_selected(header.is_checked());
+        assert_eq!(table.select_all_state(), CheckState::Checked);
+        mouse(&mut header, ElementState::Pressed, Vec2::new(10.0, 10.0));
+        assert_eq!(*log.lock().unwrap(), vec![true, false]);
+        
+        // Parents follow the leaves below them
+        let mut tree = TreeNode::new("src").with_children(vec![
+            TreeNode::new("main.rs"),
+            TreeNode::new("widgets").with_children(vec![TreeNode::new("a.rs"), TreeNode::new("b.rs")]),
+        ]);
+        assert_eq!(tree.check_state(), CheckState::Unchecked);
+        tree.children[1].children[0].selected = true;
+        assert_eq!(tree.children[1].check_state(), CheckState::Indeterminate);
+        assert_eq!(tree.check_state(), CheckState::Indeterminate);
+        tree.children[1].set_selected_recursive(true);
+        assert_eq!(tree.children[1].check_state(), CheckState::Checked);
+        tree.children[0].selected = true;
+        assert_eq!(tree.check_state(), CheckState::Checked);
+        assert_eq!(CheckState::from_counts(0, 0), CheckState::Unchecked);
+        
+        // In a TreeView the boxes sit after the arrow; a mixed parent's box
+        // checks its whole subtree
+        tree.expanded = true;
+        tree.children[1].expanded = true;
+        tree.children[1].children[1].selected = false;
+        let mut view = TreeView::new().with_roots(vec![tree]).with_checkboxes(true);
+        view.layout(Vec2::ZERO, Vec2::new(300.0, 400.0));
+        let row_y = |row: f32| row * 28.0 + 14.0;
+        assert!(!mouse(&mut view, ElementState::Pressed, Vec2::new(100.0, row_y(2.0))));
+        assert!(mouse(&mut view, ElementState::Pressed, Vec2::new(8.0 + 20.0 + 26.0, row_y(2.0))));
+        assert_eq!(view.roots[0].children[1].check_state(), CheckState::Checked);
+        
+        // A leaf two levels down toggles on its own
+        assert!(mouse(&mut view, ElementState::Pressed, Vec2::new(8.0 + 40.0 + 26.0, row_y(4.0))));
+        assert!(!view.roots[0].children[1].children[1].selected);
+        assert_eq!(view.roots[0].check_state(), CheckState::Indeterminate);
+        
+        // on_check hears about each click with the node's new state
+        let checks = Arc::new(Mutex::new(Vec::new()));
+        let sink = checks.clone();
+        view = view.on_check(move |node, checked| sink.lock().unwrap().push((node.label.clone(), checked)));
+        assert!(mouse(&mut view, ElementState::Pressed, Vec2::new(8.0 + 26.0, row_y(0.0))));
+        assert!(mouse(&mut view, ElementState::Pressed, Vec2::new(8.0 + 40.0 + 26.0, row_y(4.0))));
+        assert_eq!(*checks.lock().unwrap(), vec![("src".to_string(), true), ("b.rs".to_string(), false)]);
+    }
+    
+    #[test]
+    fn test_checkbox_checked_accessors() {
+        let mut checkbox = Checkbox::new("Sound", false).with_state(CheckState::Indeterminate);
+        assert!(!checkbox.checked());
+        checkbox.set_checked(true);
+        assert!(checkbox.checked());
+        assert_eq!(checkbox.state(), CheckState::Checked);
+        checkbox.set_checked(false);
+        assert_eq!(checkbox.state(), CheckState::Unchecked);
+    }
 }
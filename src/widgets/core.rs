@@ -3,8 +3,11 @@
 //! Contains the Widget trait, Theme system, and easing functions used by all widgets.
 
 use glam::{Vec2, Vec4};
-use std::cell::RefCell;
-use crate::renderer::GlassRenderer;
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use crate::renderer::{BlurSettings, GlassRenderer};
+use crate::widget_id::WidgetId;
+use crate::focus::Focusable;
 
 // =============================================================================
 // THEME SYSTEM
@@ -26,6 +29,14 @@ pub struct Theme {
     pub success: Vec4,
     pub error: Vec4,
     pub warning: Vec4,
+    /// Opacity disabled widgets are drawn with
+    pub disabled_opacity: f32,
+    /// Seconds between presses that still count toward a double-click
+    pub double_click_time: f32,
+    /// Pixels the pointer may move between presses of a double-click
+    pub double_click_distance: f32,
+    /// Backdrop blur while this theme is active (None = the renderer's own)
+    pub blur: Option<BlurSettings>,
 }
 
 impl Default for Theme {
@@ -49,6 +60,10 @@ impl Theme {
             success: Vec4::new(0.0, 1.0, 0.5, 1.0),
             error: Vec4::new(1.0, 0.3, 0.3, 1.0),
             warning: Vec4::new(1.0, 0.8, 0.0, 1.0),
+            disabled_opacity: 0.4,
+            double_click_time: 0.4,
+            double_click_distance: 4.0,
+            blur: None,
         }
     }
     
@@ -68,6 +83,10 @@ impl Theme {
             success: Vec4::new(0.2, 0.9, 0.4, 1.0),
             error: Vec4::new(0.9, 0.25, 0.25, 1.0),
             warning: Vec4::new(0.95, 0.75, 0.1, 1.0),
+            disabled_opacity: 0.4,
+            double_click_time: 0.4,
+            double_click_distance: 4.0,
+            blur: None,
         }
     }
     
@@ -87,6 +106,10 @@ impl Theme {
             success: Vec4::new(0.1, 0.7, 0.3, 1.0),
             error: Vec4::new(0.8, 0.2, 0.2, 1.0),
             warning: Vec4::new(0.85, 0.65, 0.0, 1.0),
+            disabled_opacity: 0.5,
+            double_click_time: 0.4,
+            double_click_distance: 4.0,
+            blur: None,
         }
     }
     
@@ -106,17 +129,29 @@ impl Theme {
             success: Vec4::new(0.3, 1.0, 0.6, 1.0),
             error: Vec4::new(1.0, 0.4, 0.4, 1.0),
             warning: Vec4::new(1.0, 0.85, 0.3, 1.0),
+            disabled_opacity: 0.4,
+            double_click_time: 0.4,
+            double_click_distance: 4.0,
+            // Softer, wider frost than the default
+            blur: Some(BlurSettings::new(12.0).with_iterations(2)),
         }
     }
 }
 
 thread_local! {
     static CURRENT_THEME: RefCell<Theme> = RefCell::new(Theme::default());
+    static THEME_GENERATION: Cell<u64> = const { Cell::new(0) };
 }
 
 /// Set the global theme
 pub fn set_theme(theme: Theme) {
     CURRENT_THEME.with(|t| *t.borrow_mut() = theme);
+    THEME_GENERATION.with(|g| g.set(g.get() + 1));
+}
+
+/// Counter bumped by every `set_theme`, so caches can tell the theme changed
+pub fn theme_generation() -> u64 {
+    THEME_GENERATION.with(|g| g.get())
 }
 
 /// Get a clone of the current theme
@@ -124,11 +159,44 @@ pub fn get_theme() -> Theme {
     CURRENT_THEME.with(|t| t.borrow().clone())
 }
 
+/// Run `draw` faded by `Theme::disabled_opacity` while `disabled` and by a
+/// style `opacity`, in one opacity group
+pub fn render_faded(renderer: &mut GlassRenderer, disabled: bool, opacity: Option<f32>, draw: impl FnOnce(&mut GlassRenderer)) {
+    let disabled = if disabled { get_theme().disabled_opacity } else { 1.0 };
+    let opacity = disabled * opacity.unwrap_or(1.0);
+    if opacity >= 1.0 {
+        draw(renderer);
+        return;
+    }
+    renderer.push_opacity(opacity);
+    draw(renderer);
+    renderer.pop_opacity();
+}
+
+thread_local! {
+    static DEBUG_LAYOUT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Outline every widget's layout rect, padding and baseline when rendering
+pub fn set_debug_layout(enabled: bool) {
+    DEBUG_LAYOUT.with(|d| d.set(enabled));
+}
+
+/// Whether the layout debug view is on
+pub fn debug_layout() -> bool {
+    DEBUG_LAYOUT.with(|d| d.get())
+}
+
 // =============================================================================
 // WIDGET TRAIT
 // =============================================================================
 
-use crate::layout::{BoxConstraints, Size, Offset};
+use crate::layout::{BoxConstraints, Size, Offset, Rect, LayoutCache, EdgeInsets, note_constraints};
+use super::layout::Positioned;
+
+/// Stand-in for an unbounded side in the legacy `layout(origin, max_size)`
+/// protocol, which has no infinity; sizes this large count as unbounded
+pub(crate) const UNBOUNDED_EXTENT: f32 = 10000.0;
 
 /// Core trait that all UI components implement
 /// 
@@ -158,11 +226,30 @@ pub trait Widget {
     fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
         // Default: convert to old API
         let max_size = Vec2::new(
-            if constraints.max_width.is_finite() { constraints.max_width } else { 10000.0 },
-            if constraints.max_height.is_finite() { constraints.max_height } else { 10000.0 },
+            if constraints.max_width.is_finite() { constraints.max_width } else { UNBOUNDED_EXTENT },
+            if constraints.max_height.is_finite() { constraints.max_height } else { UNBOUNDED_EXTENT },
         );
         let result = self.layout(Vec2::ZERO, max_size);
-        Size::new(result.x, result.y)
+        constraints.constrain(Size::new(result.x, result.y))
+    }
+    
+    /// `layout`, as containers call it on their children: notes `max_size`
+    /// (as loose constraints) for the `Inspector` first
+    /// 
+    /// Not meant to be overridden; see `crate::layout::record_constraints`.
+    fn layout_child(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        let unbounded = |extent: f32| if extent >= UNBOUNDED_EXTENT { f32::INFINITY } else { extent };
+        note_constraints(self, BoxConstraints::loose(Size::new(unbounded(max_size.x), unbounded(max_size.y))));
+        self.layout(origin, max_size)
+    }
+    
+    /// `layout_with_constraints`, as containers call it on their children:
+    /// notes `constraints` for the `Inspector` first
+    /// 
+    /// Not meant to be overridden.
+    fn layout_child_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        note_constraints(self, constraints);
+        self.layout_with_constraints(constraints)
     }
     
     /// Set the widget's position (called by parent after layout)
@@ -206,6 +293,575 @@ pub trait Widget {
     fn intrinsic_height(&self, _width: f32) -> Option<f32> {
         None // Default: no intrinsic height preference
     }
+    
+    /// Whether this widget or any descendant changed since its last layout
+    /// 
+    /// Containers skip laying out a subtree again when they receive the
+    /// same origin and max size as last frame and this returns false.
+    /// Defaults to true so widgets that don't track changes are always
+    /// laid out.
+    fn needs_layout(&self) -> bool {
+        true
+    }
+    
+    /// Whether this widget or any descendant wants another frame
+    /// 
+    /// Used by on-demand redraw (see `RedrawScheduler`) to keep drawing
+    /// while long-running animations play. Short input-driven transitions
+    /// are covered by the scheduler's settle time.
+    fn is_animating(&self) -> bool {
+        false
+    }
+    
+    /// Screen area everything this widget draws falls within
+    /// 
+    /// `None` means unknown; such widgets are never skipped as occluded.
+    fn render_bounds(&self) -> Option<Rect> {
+        None
+    }
+    
+    /// Screen area this widget covers completely when rendered
+    /// 
+    /// Glass surfaces replace the backdrop with their own blurred sample,
+    /// so a panel's interior hides whatever was drawn beneath it. Stacks
+    /// skip rendering siblings whose `render_bounds` fall inside a later
+    /// sibling's opaque rect.
+    fn opaque_rect(&self) -> Option<Rect> {
+        None
+    }
+    
+    /// Short type name shown by debugging tools (e.g. `"Button"`)
+    fn type_name(&self) -> &'static str {
+        short_type_name(std::any::type_name::<Self>())
+    }
+    
+    /// `TypeId` of the concrete widget, for `downcast_ref`; the argument
+    /// can't be named outside this module, so it can't be overridden
+    #[doc(hidden)]
+    fn widget_type_id(&self, _: lookup::Sealed) -> TypeId where Self: 'static {
+        TypeId::of::<Self>()
+    }
+    
+    /// Call `visitor` with each direct child
+    /// 
+    /// Lets tools such as the `Inspector` walk the tree. Containers
+    /// override this; leaf widgets keep the default.
+    fn visit_children<'a>(&'a self, _visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {}
+    
+    /// Call `visitor` with each direct child, mutably
+    /// 
+    /// Same children, in the same order, as `visit_children`; used to route
+    /// events down the tree (see `EventDispatcher`).
+    fn visit_children_mut<'a>(&'a mut self, _visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {}
+    
+    /// Whether `point` is over this widget, for routing pointer events
+    /// 
+    /// Defaults to the laid-out rect. Widgets that don't report a size are
+    /// never hit, so they only see events that no widget handled; neither
+    /// are layout-only widgets (see `is_layout_only`).
+    fn hit_test(&self, point: Vec2) -> bool {
+        if self.is_layout_only() {
+            return false;
+        }
+        let (position, size) = (self.get_position(), self.get_size());
+        size.width > 0.0 && size.height > 0.0
+            && Rect::new(Vec2::new(position.x, position.y), Vec2::new(size.width, size.height)).contains(point)
+    }
+    
+    /// Whether this widget only arranges its children and draws nothing of
+    /// its own (Column, Row, Stack, Padding, ...)
+    /// 
+    /// The default `hit_test` never hits such a widget, so a press on the
+    /// space between or around its children falls through to whatever is
+    /// beneath; its children are still hit-tested as usual.
+    fn is_layout_only(&self) -> bool {
+        false
+    }
+    
+    /// Whether pointer hit testing looks at this widget and its subtree
+    /// 
+    /// False for purely decorative widgets (see `PassThrough`): presses,
+    /// scrolls and hover skip them and go to the widgets beneath.
+    fn hit_test_visible(&self) -> bool {
+        true
+    }
+    
+    /// Whether `point` is over a popup this widget draws above everything
+    /// else (an open dropdown list, a context menu, a modal's backdrop)
+    /// 
+    /// Popups are hit-tested before the rest of the tree.
+    fn hit_test_popup(&self, _point: Vec2) -> bool {
+        false
+    }
+    
+    /// Handle a pointer event routed to this widget by `EventDispatcher`
+    /// 
+    /// Unlike `handle_event`, children are not included: the dispatcher
+    /// calls the widget under the pointer first and then each ancestor
+    /// (bubbling) until one returns true. Leaf widgets default to
+    /// `handle_event`; containers handle nothing unless they override this
+    /// (a scroll area's wheel and scrollbar, a draggable's drag).
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let mut has_children = false;
+        self.visit_children(&mut |_| has_children = true);
+        !has_children && self.handle_event(event, mouse_pos)
+    }
+    
+    /// Capture phase of a routed pointer event, called on each ancestor of
+    /// the widget under the pointer (root first) before it
+    /// 
+    /// Return true to take the event from the descendants, e.g. a resize
+    /// handle drawn over the child.
+    fn capture_routed_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
+        false
+    }
+    
+    /// A routed press was handled inside child `index`; returns that
+    /// child's index afterwards (`Stack` brings it to the front)
+    fn child_pressed(&mut self, index: usize) -> usize {
+        index
+    }
+    
+    /// Extra state shown by the `Inspector` (hover/focus state, style, ...)
+    fn debug_properties(&self, _props: &mut Vec<(&'static str, String)>) {}
+    
+    /// Style the widget is drawn with, for widgets styled by a
+    /// `WidgetStyle` (shown by the `Inspector`)
+    fn widget_style(&self) -> Option<&crate::style::WidgetStyle> {
+        None
+    }
+    
+    /// Padding between this widget's rect and its content
+    /// 
+    /// Drawn by the layout debug view; children are expected to fit
+    /// inside the rect shrunk by these insets.
+    fn content_insets(&self) -> EdgeInsets {
+        EdgeInsets::ZERO
+    }
+    
+    /// Whether children may extend past this widget's rect (e.g. scrolling)
+    /// 
+    /// The layout debug view doesn't flag overflow inside such widgets.
+    fn clips_content(&self) -> bool {
+        false
+    }
+    
+    /// Key identifying this widget across rebuilds (see `declarative::Keyed`)
+    fn state_key(&self) -> Option<&str> {
+        None
+    }
+    
+    /// Id application code looks this widget up by (see `Identified`)
+    fn widget_id(&self) -> Option<WidgetId> {
+        None
+    }
+    
+    /// Placement in a `Stack`, for a `Positioned` or a wrapper around one
+    /// 
+    /// Wrappers that lay their child out in the constraints they are given
+    /// forward this, so a `Stack` still positions `Identified(Positioned)`
+    /// instead of letting it size the stack.
+    fn positioned(&self) -> Option<&Positioned> {
+        None
+    }
+    
+    /// Keyboard focus hooks for widgets that take text or key input
+    /// 
+    /// `EventDispatcher` gives focus to the deepest focusable widget under a
+    /// press and sends keyboard events to the focused widget only.
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        None
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        None
+    }
+    
+    /// Second and later presses of a quick run at one spot (`clicks` is 2
+    /// for a double-click), sent by `EventDispatcher` after the press itself
+    /// 
+    /// Bubbles from the widget under the pointer until one returns true.
+    /// The time and distance allowed between presses come from the theme.
+    fn handle_multi_click(&mut self, _clicks: u32, _mouse_pos: Vec2) -> bool {
+        false
+    }
+    
+    /// Called by `EventDispatcher` when the pointer moves onto this widget
+    /// 
+    /// Ancestors of the topmost widget under the pointer are hovered too;
+    /// they are entered outermost first.
+    fn on_hover_enter(&mut self) {}
+    
+    /// Called when the pointer moves off this widget or out of the window
+    fn on_hover_leave(&mut self) {}
+    
+    /// Whether this widget overrides `on_hover_enter`/`on_hover_leave`
+    /// 
+    /// `EventDispatcher` only hit-tests pointer moves when a widget in the
+    /// tree returns true; otherwise nobody is told about hover changes.
+    fn wants_hover(&self) -> bool {
+        false
+    }
+    
+    /// Called by `mount` when the widget joins a live tree, before its children
+    /// 
+    /// Register with global services (focus, hero flights, accessibility)
+    /// here rather than in the constructor, so a widget that is built but
+    /// never shown leaves nothing behind. Containers that keep children out
+    /// of `visit_children_mut` (inactive tabs, a hidden `Visible`) mount
+    /// those children themselves.
+    fn on_mount(&mut self) {}
+    
+    /// Called by `unmount` when the widget leaves the tree, after its children
+    fn on_unmount(&mut self) {}
+    
+    /// User-editable state to carry over when the tree is rebuilt
+    fn saved_state(&self) -> Option<WidgetState> {
+        None
+    }
+    
+    /// Top-left and font size of the first line of text, for baseline display
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        None
+    }
+    
+    /// Update, deferring thread-safe work to `jobs`
+    /// 
+    /// Used by `update_parallel`. The default runs `update` right away on
+    /// the calling thread. Containers recurse into their children; widgets
+    /// whose update only touches `Send` state push an `UpdateState` instead.
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        let _ = jobs;
+        self.update(dt);
+    }
+}
+
+/// Strip the module path (and generic arguments) from a type name
+fn short_type_name(full: &'static str) -> &'static str {
+    let base = full.split('<').next().unwrap_or(full);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+/// Format a color as `rgba(r, g, b, a)` for the `Inspector`
+pub(crate) fn format_color(color: Vec4) -> String {
+    format!("rgba({:.2}, {:.2}, {:.2}, {:.2})", color.x, color.y, color.z, color.w)
+}
+
+/// Inspector property for the max size a container was last laid out with
+pub(crate) fn constraint_property(cache: &LayoutCache) -> Option<(&'static str, String)> {
+    cache.max_size().map(|max| ("max size", format!("{:.0} x {:.0}", max.x, max.y)))
+}
+
+/// Number of widgets in the tree rooted at `root` (including `root`)
+pub fn count_widgets(root: &dyn Widget) -> usize {
+    let mut count = 1;
+    root.visit_children(&mut |child| count += count_widgets(child));
+    count
+}
+
+thread_local! {
+    static TREE_GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Counter bumped by every `mount` and `unmount`, so caches of what is in
+/// the tree (like `EventDispatcher`'s tab order) can tell it changed
+pub fn tree_generation() -> u64 {
+    TREE_GENERATION.with(|g| g.get())
+}
+
+/// Run `on_mount` over the tree rooted at `root`, parents before children
+pub fn mount(root: &mut dyn Widget) {
+    TREE_GENERATION.with(|g| g.set(g.get() + 1));
+    root.on_mount();
+    root.visit_children_mut(&mut |child| mount(child));
+}
+
+/// Run `on_unmount` over the tree rooted at `root`, children before parents
+pub fn unmount(root: &mut dyn Widget) {
+    TREE_GENERATION.with(|g| g.set(g.get() + 1));
+    root.visit_children_mut(&mut |child| unmount(child));
+    root.on_unmount();
+}
+
+/// Whether `widget` is entirely hidden behind one of the widgets drawn after it
+pub fn is_occluded<'a>(widget: &dyn Widget, above: impl IntoIterator<Item = &'a dyn Widget>) -> bool {
+    let Some(bounds) = widget.render_bounds() else { return false };
+    above.into_iter().any(|w| w.opaque_rect().is_some_and(|rect| rect.contains_rect(&bounds)))
+}
+
+/// Opaque interior of a rounded glass rectangle
+/// 
+/// Insets by the part of the corner radius that the rounded corners cut
+/// away, giving the largest axis-aligned rect fully covered by the shape.
+pub fn rounded_opaque_rect(position: Vec2, size: Vec2, corner_radius: f32) -> Rect {
+    let radius = corner_radius.min(size.x.min(size.y) * 0.5).max(0.0);
+    Rect::new(position, size).inset(radius * (1.0 - std::f32::consts::FRAC_1_SQRT_2))
+}
+
+/// User-editable widget state, e.g. preserved across hot reloads
+#[derive(Clone, Debug, PartialEq)]
+pub enum WidgetState {
+    Bool(bool),
+    Number(f32),
+    Text(String),
+}
+
+/// Thread-safe half of a widget's per-frame update
+/// 
+/// Implemented by widgets (or parts of widgets) that hold only `Send`
+/// state, so `update_parallel` can advance them on worker threads.
+pub trait UpdateState: Send {
+    fn update_state(&mut self, dt: f32);
+}
+
+/// Update a widget tree, running thread-safe updates in parallel
+/// 
+/// Widgets that don't split their update run first, serially, on the
+/// calling thread. With the `parallel` feature the collected
+/// `UpdateState`s then run on the rayon thread pool; without it they run
+/// serially, so the result is identical either way.
+pub fn update_parallel(root: &mut dyn Widget, dt: f32) {
+    let mut jobs: Vec<&mut dyn UpdateState> = Vec::new();
+    root.split_update(dt, &mut jobs);
+    
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        jobs.par_iter_mut().for_each(|job| job.update_state(dt));
+    }
+    #[cfg(not(feature = "parallel"))]
+    for job in jobs {
+        job.update_state(dt);
+    }
+}
+
+// =============================================================================
+// WIDGET LOOKUP
+// =============================================================================
+
+mod lookup {
+    /// Argument of `Widget::widget_type_id`
+    pub struct Sealed;
+}
+
+impl<'w> dyn Widget + 'w {
+    /// Descendant at `path`, a list of child indices in `visit_children` order
+    pub fn widget_at(&self, path: &[usize]) -> Option<&(dyn Widget + 'w)> {
+        let Some((&first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let mut child = None;
+        let mut index = 0;
+        self.visit_children(&mut |c| {
+            if index == first {
+                child = Some(c);
+            }
+            index += 1;
+        });
+        child?.widget_at(rest)
+    }
+    
+    pub fn widget_at_mut(&mut self, path: &[usize]) -> Option<&mut (dyn Widget + 'w)> {
+        let Some((&first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let mut child: Option<&mut (dyn Widget + 'w)> = None;
+        let mut index = 0;
+        self.visit_children_mut(&mut |c| {
+            if index == first {
+                child = Some(c);
+            }
+            index += 1;
+        });
+        child?.widget_at_mut(rest)
+    }
+    
+    /// First widget (depth-first, this one included) whose `widget_id` is `id`
+    pub fn find_by_id(&self, id: WidgetId) -> Option<&(dyn Widget + 'w)> {
+        if self.widget_id() == Some(id) {
+            return Some(self);
+        }
+        let mut found = None;
+        self.visit_children(&mut |child| {
+            if found.is_none() {
+                found = child.find_by_id(id);
+            }
+        });
+        found
+    }
+    
+    pub fn find_by_id_mut(&mut self, id: WidgetId) -> Option<&mut (dyn Widget + 'w)> {
+        if self.widget_id() == Some(id) {
+            return Some(self);
+        }
+        let mut found = None;
+        self.visit_children_mut(&mut |child| {
+            if found.is_none() {
+                let child: &mut (dyn Widget + 'w) = child;
+                found = child.find_by_id_mut(id);
+            }
+        });
+        found
+    }
+}
+
+/// Lookup works on `'static` widgets (the ones in a `Box<dyn Widget>`
+/// tree); `Widget` itself has no such bound
+impl dyn Widget {
+    /// The concrete widget behind this trait object, if it is a `T`
+    pub fn downcast_ref<T: Widget + 'static>(&self) -> Option<&T> {
+        if self.widget_type_id(lookup::Sealed) != TypeId::of::<T>() {
+            return None;
+        }
+        // SAFETY: `widget_type_id` can't be overridden, since its
+        // `lookup::Sealed` argument can't be named outside this module, so
+        // the provided method's TypeId is that of the concrete type: the
+        // widget is a `T`
+        Some(unsafe { &*(self as *const dyn Widget as *const T) })
+    }
+    
+    pub fn downcast_mut<T: Widget + 'static>(&mut self) -> Option<&mut T> {
+        if self.widget_type_id(lookup::Sealed) != TypeId::of::<T>() {
+            return None;
+        }
+        // SAFETY: as in `downcast_ref`
+        Some(unsafe { &mut *(self as *mut dyn Widget as *mut T) })
+    }
+    
+    /// First `T` in this subtree (this widget included), depth-first
+    pub fn first_of_type<T: Widget + 'static>(&self) -> Option<&T> {
+        if let Some(widget) = self.downcast_ref() {
+            return Some(widget);
+        }
+        let mut found = None;
+        self.visit_children(&mut |child| {
+            if found.is_none() {
+                found = child.first_of_type();
+            }
+        });
+        found
+    }
+    
+    pub fn first_of_type_mut<T: Widget + 'static>(&mut self) -> Option<&mut T> {
+        if self.downcast_ref::<T>().is_some() {
+            return self.downcast_mut();
+        }
+        let mut found = None;
+        self.visit_children_mut(&mut |child| {
+            if found.is_none() {
+                found = child.first_of_type_mut();
+            }
+        });
+        found
+    }
+    
+    /// The `T` tagged with `id`
+    /// 
+    /// The id may sit on a wrapper (`Identified` around a `Draggable`
+    /// around the widget, say): the first `T` inside the tagged widget
+    /// is returned.
+    /// 
+    /// ```ignore
+    /// root.find_mut::<Label>(status_id).unwrap().text = "Saved".into();
+    /// ```
+    pub fn find<T: Widget + 'static>(&self, id: WidgetId) -> Option<&T> {
+        self.find_by_id(id)?.first_of_type()
+    }
+    
+    pub fn find_mut<T: Widget + 'static>(&mut self, id: WidgetId) -> Option<&mut T> {
+        self.find_by_id_mut(id)?.first_of_type_mut()
+    }
+}
+
+/// Tags a widget with a `WidgetId` so it can be found once it's boxed
+/// into the tree (see `find_mut`)
+pub struct Identified {
+    pub id: WidgetId,
+    pub child: Box<dyn Widget>,
+}
+
+impl Identified {
+    pub fn new(id: WidgetId, child: Box<dyn Widget>) -> Self {
+        Self { id, child }
+    }
+}
+
+impl Widget for Identified {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.child.layout_child(origin, max_size)
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout_child_with_constraints(constraints)
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        self.child.get_position()
+    }
+    
+    fn get_size(&self) -> Size {
+        self.child.get_size()
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("id", self.id.to_string()));
+    }
+    
+    fn widget_id(&self) -> Option<WidgetId> {
+        Some(self.id)
+    }
+    
+    fn positioned(&self) -> Option<&Positioned> {
+        self.child.positioned()
+    }
+    
+    fn saved_state(&self) -> Option<WidgetState> {
+        self.child.saved_state()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+    
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+    
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+    
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
 }
 
 // =============================================================================
@@ -248,3 +904,135 @@ pub mod easing {
         a + (b - a) * t
     }
 }
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::declarative::KeyedList;
+    use crate::widgets::{Button, Column, Draggable, Label, Modal, TabBar};
+
+    /// Logs its mount hooks
+    struct Probe {
+        name: String,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Probe {
+        fn boxed(name: &str, log: &Rc<RefCell<Vec<String>>>) -> Box<dyn Widget> {
+            Box::new(Probe { name: name.to_string(), log: log.clone() })
+        }
+    }
+
+    impl Widget for Probe {
+        fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
+            Vec2::ZERO
+        }
+
+        fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
+            false
+        }
+
+        fn update(&mut self, _dt: f32) {}
+
+        fn render(&self, _renderer: &mut GlassRenderer) {}
+
+        fn on_mount(&mut self) {
+            self.log.borrow_mut().push(format!("mount {}", self.name));
+        }
+
+        fn on_unmount(&mut self) {
+            self.log.borrow_mut().push(format!("unmount {}", self.name));
+        }
+    }
+
+    /// Borrows the text it lays out
+    struct Borrowed<'a> {
+        text: &'a str,
+    }
+
+    impl Widget for Borrowed<'_> {
+        fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
+            Vec2::new(self.text.len() as f32, 0.0)
+        }
+
+        fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
+            false
+        }
+
+        fn update(&mut self, _dt: f32) {}
+
+        fn render(&self, _renderer: &mut GlassRenderer) {}
+    }
+
+    #[test]
+    fn test_widgets_may_borrow() {
+        // Only downcasting needs `'static` widgets
+        let text = String::from("borrowed");
+        let mut borrowed = Borrowed { text: &text };
+        let widget: &mut dyn Widget = &mut borrowed;
+        assert_eq!(widget.layout(Vec2::ZERO, Vec2::splat(100.0)), Vec2::new(8.0, 0.0));
+        assert_eq!(count_widgets(widget), 1);
+        assert!(widget.widget_at_mut(&[]).is_some());
+        
+        let label: Box<dyn Widget> = Box::new(Label::new("Idle"));
+        assert!(label.downcast_ref::<Label>().is_some());
+        assert!(label.downcast_ref::<Button>().is_none());
+    }
+
+    #[test]
+    fn test_find_mut_by_widget_id() {
+        let status = WidgetId::new();
+        let mut root: Box<dyn Widget> = Box::new(Column::new()
+            .add_child(Box::new(Button::new("Save")))
+            .add_child(Box::new(Identified::new(status, Box::new(Draggable::new(Box::new(Label::new("Idle"))))))));
+
+        root.find_mut::<Label>(status).unwrap().text = "Saved".into();
+        assert_eq!(root.find::<Label>(status).unwrap().text, "Saved");
+        assert!(root.find::<Button>(status).is_none(), "only the tagged subtree is searched");
+        assert!(root.find::<Label>(WidgetId::new()).is_none());
+
+        // Paths follow visit_children order
+        assert_eq!(root.widget_at(&[1]).and_then(|w| w.widget_id()), Some(status));
+        assert!(root.widget_at(&[1, 0, 0]).unwrap().downcast_ref::<Label>().is_some());
+        assert!(root.widget_at(&[2]).is_none());
+        assert_eq!(root.first_of_type_mut::<Button>().unwrap().text, "Save");
+    }
+
+    #[test]
+    fn test_mount_hooks_reach_whole_tree() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let tabs = TabBar::new()
+            .add_tab("One", Probe::boxed("active tab", &log))
+            .add_tab("Two", Probe::boxed("inactive tab", &log));
+        let mut root = Column::new()
+            .add_child(Probe::boxed("first", &log))
+            .add_child(Box::new(tabs));
+        mount(&mut root);
+        assert_eq!(*log.borrow(), vec!["mount first", "mount inactive tab", "mount active tab"]);
+        
+        log.borrow_mut().clear();
+        unmount(&mut root);
+        assert_eq!(*log.borrow(), vec!["unmount first", "unmount active tab", "unmount inactive tab"]);
+        
+        // A mounted KeyedList mounts what it builds and unmounts what it drops
+        let sink = log.clone();
+        let mut list = KeyedList::new(|name: &String| name.clone(), move |name| Probe::boxed(name, &sink))
+            .with_items(["a".to_string()]);
+        log.borrow_mut().clear();
+        mount(&mut list);
+        list.set_items(["b".to_string()]);
+        assert_eq!(*log.borrow(), vec!["mount a", "mount b", "unmount a"]);
+        
+        // So does a closed Modal, whose content is out of `visit_children_mut`
+        log.borrow_mut().clear();
+        let mut modal = Modal::new("Confirm", Probe::boxed("modal content", &log));
+        mount(&mut modal);
+        unmount(&mut modal);
+        assert_eq!(*log.borrow(), vec!["mount modal content", "unmount modal content"]);
+    }
+}
@@ -4,8 +4,16 @@
 
 use glam::{Vec2, Vec4};
 use winit::event::{ElementState, MouseButton};
+use winit::keyboard::ModifiersState;
 use crate::renderer::GlassRenderer;
-use super::core::{Widget, get_theme};
+use crate::clipboard::ClipboardAction;
+use crate::focus::{FocusId, Focusable};
+use super::core::{Widget, get_theme, render_faded};
+use super::controls::{CheckState, draw_check_box};
+use super::scroll::ScrollBar;
+use crate::layout::{BoxConstraints, Offset, Rect, Size};
+use crate::text::TextOverflow;
+use crate::style::{Border, WidgetStyle};
 
 // =============================================================================
 // TABLE
@@ -52,6 +60,9 @@ impl TableRow {
 }
 
 /// Data table with headers, sortable columns, and row selection
+///
+/// Ctrl+C while the table has focus copies the selected rows as
+/// tab-separated lines, ready to paste into a spreadsheet.
 pub struct Table {
     pub position: Vec2,
     pub size: Vec2,
@@ -59,16 +70,21 @@ pub struct Table {
     pub rows: Vec<TableRow>,
     pub row_height: f32,
     pub header_height: f32,
-    pub scroll_offset: f32,
+    pub scroll: ScrollBar,
     pub selected_row: Option<usize>,
     pub hovered_row: Option<usize>,
     pub corner_radius: f32,
-    
-    // Scrollbar state
-    scrollbar_hovered: bool,
-    scrollbar_dragging: bool,
-    drag_start_y: f32,
-    drag_start_offset: f32,
+    /// How header and cell text wider than its column is cut
+    pub overflow: TextOverflow,
+    /// Called when a row click changes the selection (`None` when it clears it)
+    pub on_select: Option<Box<dyn FnMut(Option<usize>)>>,
+    /// Per-instance overrides of the theme colors (see `with_style`)
+    pub style: WidgetStyle,
+    /// Identifies the table to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
+    pub focused: bool,
+    /// Modifier keys held, from the last `ModifiersChanged`
+    modifiers: ModifiersState,
 }
 
 impl Table {
@@ -80,14 +96,16 @@ impl Table {
             rows: Vec::new(),
             row_height: 32.0,
             header_height: 36.0,
-            scroll_offset: 0.0,
+            scroll: ScrollBar::new(),
             selected_row: None,
             hovered_row: None,
             corner_radius: 8.0,
-            scrollbar_hovered: false,
-            scrollbar_dragging: false,
-            drag_start_y: 0.0,
-            drag_start_offset: 0.0,
+            overflow: TextOverflow::Ellipsis,
+            on_select: None,
+            style: WidgetStyle::default(),
+            focus_id: FocusId::new(),
+            focused: false,
+            modifiers: ModifiersState::empty(),
         }
     }
     
@@ -96,6 +114,27 @@ impl Table {
         self
     }
     
+    pub fn on_select(mut self, callback: impl FnMut(Option<usize>) + 'static) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn with_overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+    
+    /// Override theme values for this table: the radius replaces the field,
+    /// while background, border, shadow, text color and opacity are read
+    /// when rendering. Repeated calls layer on top of each other.
+    pub fn with_style(mut self, style: WidgetStyle) -> Self {
+        if let Some(radius) = style.corner_radius {
+            self.corner_radius = radius;
+        }
+        self.style = self.style.merge(&style);
+        self
+    }
+    
     pub fn add_row(&mut self, row: TableRow) {
         self.rows.push(row);
     }
@@ -115,28 +154,131 @@ impl Table {
     fn max_scroll(&self) -> f32 {
         (self.content_height() - self.visible_height()).max(0.0)
     }
+    
+    /// Ease the rows to `offset` pixels from the top
+    pub fn scroll_to(&mut self, offset: f32) {
+        let max_scroll = self.max_scroll();
+        self.scroll.scroll_to(offset, max_scroll);
+    }
+    
+    /// Ease just far enough that row `index` is visible
+    pub fn scroll_into_view(&mut self, index: usize) {
+        let (visible, max_scroll) = (self.visible_height(), self.max_scroll());
+        self.scroll.reveal(index as f32 * self.row_height, self.row_height, visible, max_scroll);
+    }
+    
+    /// The clicked row and rows marked `selected`, one line each with
+    /// cells separated by tabs
+    pub fn selected_text(&self) -> String {
+        self.rows.iter().enumerate()
+            .filter(|(i, row)| row.selected || self.selected_row == Some(*i))
+            .map(|(_, row)| row.cells.join("\t"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Focusable for Table {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+    }
 }
 
 impl Widget for Table {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
         self.size = Vec2::new(self.total_width().min(max_size.x), max_size.y.min(400.0));
+        self.scroll.clamp(self.max_scroll());
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.total_width(), 400.0));
+        self.size = Vec2::new(size.width, size.height);
+        self.scroll.clamp(self.max_scroll());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        match event {
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::ModifiersChanged(modifiers), .. } => {
+                self.modifiers = modifiers.state();
+                return false;
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::KeyboardInput { event: key_event, .. }, .. } => {
+                if self.focused && key_event.state.is_pressed()
+                    && ClipboardAction::from_key(&key_event.logical_key, self.modifiers) == Some(ClipboardAction::Copy)
+                {
+                    let text = self.selected_text();
+                    if !text.is_empty() {
+                        let _ = crate::clipboard::copy_to_clipboard(&text);
+                        return true;
+                    }
+                }
+                return false;
+            }
+            _ => {}
+        }
         let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
                      mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
         
-        if !inside && !self.scrollbar_dragging {
+        if !inside && !self.scroll.is_dragging() {
             self.hovered_row = None;
+            self.scroll.hovered = false;
             return false;
         }
         
-        // Calculate hovered row
+        // Scrollbar: grab the thumb or page on the track, then follow drags
         let body_y = self.position.y + self.header_height;
+        let (content, visible) = (self.content_height(), self.visible_height());
+        let along = mouse_pos.y - body_y;
+        let in_bar = mouse_pos.x >= self.position.x + self.size.x - 10.0;
+        self.scroll.hovered = in_bar && self.scroll.thumb_contains(along, visible, content, visible);
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } = event {
+            if *state == ElementState::Pressed {
+                if in_bar && self.scroll.press(along, visible, content, visible) {
+                    return true;
+                }
+            } else {
+                self.scroll.release();
+            }
+        }
+        if self.scroll.drag(along, visible, content, visible) {
+            self.hovered_row = None;
+            return true;
+        }
+        
+        // Calculate hovered row
         if mouse_pos.y > body_y {
-            let relative_y = mouse_pos.y - body_y + self.scroll_offset;
+            let relative_y = mouse_pos.y - body_y + self.scroll.offset();
             let row_idx = (relative_y / self.row_height) as usize;
             if row_idx < self.rows.len() {
                 self.hovered_row = Some(row_idx);
@@ -156,6 +298,9 @@ impl Widget for Table {
                 } else {
                     self.selected_row = Some(idx);
                 }
+                if let Some(callback) = &mut self.on_select {
+                    callback(self.selected_row);
+                }
                 return true;
             }
         }
@@ -167,9 +312,8 @@ impl Widget for Table {
                 winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
             };
             
-            let old = self.scroll_offset;
-            self.scroll_offset = (self.scroll_offset - scroll_amount).clamp(0.0, self.max_scroll());
-            if (self.scroll_offset - old).abs() > 0.1 {
+            let max_scroll = self.max_scroll();
+            if self.scroll.scroll_by(-scroll_amount, max_scroll) {
                 return true;
             }
         }
@@ -177,124 +321,130 @@ impl Widget for Table {
         false
     }
 
-    fn update(&mut self, _dt: f32) {}
+    fn update(&mut self, dt: f32) {
+        self.scroll.update(dt);
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.scroll.is_animating()
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        
-        // Background
-        renderer.draw_rounded_rect(
-            self.position,
-            self.size,
-            Vec4::new(0.06, 0.06, 0.08, 0.9),
-            self.corner_radius
-        );
-        
-        // Header background
-        renderer.draw_rounded_rect(
-            self.position,
-            Vec2::new(self.size.x, self.header_height),
-            Vec4::new(0.08, 0.08, 0.1, 0.95),
-            self.corner_radius
-        );
-        
-        // Header text
-        let mut x = self.position.x;
-        for col in &self.columns {
-            renderer.draw_text(&col.header, Vec2::new(x + 12.0, self.position.y + 10.0), 14.0, theme.text);
-            // Column separator
+        let text_color = self.style.text_color(theme.text);
+        render_faded(renderer, false, self.style.opacity, |renderer| {
+            // Background
+            if let Some(shadow) = &self.style.shadow {
+                renderer.draw_box_shadow(self.position, self.size, self.corner_radius, shadow);
+            }
             renderer.draw_rounded_rect(
-                Vec2::new(x + col.width - 1.0, self.position.y + 6.0),
-                Vec2::new(1.0, self.header_height - 12.0),
-                Vec4::new(0.3, 0.3, 0.35, 0.5),
-                0.5
+                self.position,
+                self.size,
+                self.style.background.unwrap_or(Vec4::new(0.06, 0.06, 0.08, 0.9)),
+                self.corner_radius
             );
-            x += col.width;
-        }
-        
-        // Set scissor for rows
-        let body_y = self.position.y + self.header_height;
-        renderer.set_scissor([
-            self.position.x as u32,
-            body_y as u32,
-            self.size.x as u32,
-            self.visible_height() as u32,
-        ]);
-        
-        // Rows
-        for (i, row) in self.rows.iter().enumerate() {
-            let row_y = body_y + i as f32 * self.row_height - self.scroll_offset;
-            
-            // Skip rows outside visible area
-            if row_y + self.row_height < body_y || row_y > self.position.y + self.size.y {
-                continue;
-            }
             
-            // Row background
-            let row_bg = if self.selected_row == Some(i) {
-                Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.3)
-            } else if self.hovered_row == Some(i) {
-                Vec4::new(0.15, 0.15, 0.18, 0.8)
-            } else if i % 2 == 1 {
-                Vec4::new(0.07, 0.07, 0.09, 0.5)
-            } else {
-                Vec4::ZERO
-            };
+            // Header background
+            renderer.draw_rounded_rect(
+                self.position,
+                Vec2::new(self.size.x, self.header_height),
+                Vec4::new(0.08, 0.08, 0.1, 0.95),
+                self.corner_radius
+            );
             
-            if row_bg.w > 0.0 {
+            // Header text
+            let mut x = self.position.x;
+            for col in &self.columns {
+                renderer.draw_text_overflow(&col.header, Vec2::new(x + 12.0, self.position.y + 10.0), 14.0, text_color, col.width - 24.0, self.overflow);
+                // Column separator
                 renderer.draw_rounded_rect(
-                    Vec2::new(self.position.x + 2.0, row_y),
-                    Vec2::new(self.size.x - 4.0, self.row_height),
-                    row_bg,
-                    4.0
+                    Vec2::new(x + col.width - 1.0, self.position.y + 6.0),
+                    Vec2::new(1.0, self.header_height - 12.0),
+                    Vec4::new(0.3, 0.3, 0.35, 0.5),
+                    0.5
                 );
+                x += col.width;
             }
             
-            // Cell text
-            let mut cell_x = self.position.x;
-            for (col_idx, col) in self.columns.iter().enumerate() {
-                if let Some(text) = row.cells.get(col_idx) {
-                    let text_color = if self.selected_row == Some(i) { theme.text } else { theme.text };
-                    renderer.draw_text(text, Vec2::new(cell_x + 12.0, row_y + 8.0), 14.0, text_color);
+            // Clip rows (nests inside any enclosing scroll area)
+            let body_y = self.position.y + self.header_height;
+            renderer.push_clip(Rect::new(Vec2::new(self.position.x, body_y), Vec2::new(self.size.x, self.visible_height())));
+            
+            // Rows
+            for (i, row) in self.rows.iter().enumerate() {
+                let row_y = body_y + i as f32 * self.row_height - self.scroll.offset();
+                
+                // Skip rows outside visible area
+                if row_y + self.row_height < body_y || row_y > self.position.y + self.size.y {
+                    continue;
+                }
+                
+                // Row background
+                let row_bg = if self.selected_row == Some(i) {
+                    Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.3)
+                } else if self.hovered_row == Some(i) {
+                    Vec4::new(0.15, 0.15, 0.18, 0.8)
+                } else if i % 2 == 1 {
+                    Vec4::new(0.07, 0.07, 0.09, 0.5)
+                } else {
+                    Vec4::ZERO
+                };
+                
+                if row_bg.w > 0.0 {
+                    renderer.draw_rounded_rect(
+                        Vec2::new(self.position.x + 2.0, row_y),
+                        Vec2::new(self.size.x - 4.0, self.row_height),
+                        row_bg,
+                        4.0
+                    );
+                }
+                
+                // Cell text
+                let mut cell_x = self.position.x;
+                for (col_idx, col) in self.columns.iter().enumerate() {
+                    if let Some(text) = row.cells.get(col_idx) {
+                        renderer.draw_text_overflow(text, Vec2::new(cell_x + 12.0, row_y + 8.0), 14.0, text_color, col.width - 24.0, self.overflow);
+                    }
+                    cell_x += col.width;
                 }
-                cell_x += col.width;
             }
-        }
-        
-        renderer.clear_scissor();
-        
-        // Border
-        renderer.draw_rounded_rect(
-            self.position - Vec2::splat(1.0),
-            self.size + Vec2::splat(2.0),
-            Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.4),
-            self.corner_radius + 1.0
-        );
-        
-        // Scrollbar (if needed)
-        if self.content_height() > self.visible_height() {
-            let scrollbar_x = self.position.x + self.size.x - 8.0;
-            let visible_ratio = (self.visible_height() / self.content_height()).min(1.0);
-            let thumb_height = (self.visible_height() * visible_ratio).max(30.0);
-            let scroll_ratio = if self.max_scroll() > 0.0 { self.scroll_offset / self.max_scroll() } else { 0.0 };
-            let thumb_y = body_y + scroll_ratio * (self.visible_height() - thumb_height);
             
-            // Track
-            renderer.draw_rounded_rect(
-                Vec2::new(scrollbar_x, body_y + 4.0),
-                Vec2::new(6.0, self.visible_height() - 8.0),
-                Vec4::new(0.1, 0.1, 0.1, 0.3),
-                3.0
-            );
+            renderer.pop_clip();
             
-            // Thumb
-            renderer.draw_rounded_rect(
-                Vec2::new(scrollbar_x, thumb_y),
-                Vec2::new(6.0, thumb_height),
-                Vec4::new(0.4, 0.4, 0.4, 0.6),
-                3.0
-            );
-        }
+            // Border
+            let border = self.style.border_for(false).unwrap_or(Border {
+                width: 1.0,
+                color: Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.4),
+                radius: self.corner_radius,
+            });
+            renderer.draw_border(&border, self.position, self.size, self.corner_radius);
+            
+            // Scrollbar (if needed)
+            if self.content_height() > self.visible_height() {
+                let scrollbar_x = self.position.x + self.size.x - 8.0;
+                let (thumb_y, thumb_height) = self.scroll.thumb(self.visible_height(), self.content_height(), self.visible_height());
+                
+                // Track
+                renderer.draw_rounded_rect(
+                    Vec2::new(scrollbar_x, body_y + 4.0),
+                    Vec2::new(6.0, self.visible_height() - 8.0),
+                    Vec4::new(0.1, 0.1, 0.1, 0.3),
+                    3.0
+                );
+                
+                // Thumb
+                renderer.draw_rounded_rect(
+                    Vec2::new(scrollbar_x, body_y + thumb_y),
+                    Vec2::new(6.0, thumb_height),
+                    self.scroll.thumb_color(theme.primary),
+                    3.0
+                );
+            }
+        });
+    }
+    
+    fn widget_style(&self) -> Option<&WidgetStyle> {
+        Some(&self.style)
     }
 }
 
@@ -330,8 +480,12 @@ pub struct ListView {
     pub item_height: f32,
     pub selected_index: Option<usize>,
     pub hovered_index: Option<usize>,
-    pub scroll_offset: f32,
+    pub scroll: ScrollBar,
     pub corner_radius: f32,
+    /// How item text wider than the list is cut
+    pub overflow: TextOverflow,
+    /// Called with the index of the item clicked
+    pub on_select: Option<Box<dyn FnMut(usize)>>,
 }
 
 impl ListView {
@@ -343,8 +497,10 @@ impl ListView {
             item_height: 36.0,
             selected_index: None,
             hovered_index: None,
-            scroll_offset: 0.0,
+            scroll: ScrollBar::new(),
             corner_radius: 8.0,
+            overflow: TextOverflow::Ellipsis,
+            on_select: None,
         }
     }
     
@@ -353,6 +509,16 @@ impl ListView {
         self
     }
     
+    pub fn on_select(mut self, callback: impl FnMut(usize) + 'static) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn with_overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+    
     pub fn add_item(&mut self, item: ListItem) {
         self.items.push(item);
     }
@@ -364,6 +530,18 @@ impl ListView {
     fn max_scroll(&self) -> f32 {
         (self.content_height() - self.size.y).max(0.0)
     }
+    
+    /// Ease the items to `offset` pixels from the top
+    pub fn scroll_to(&mut self, offset: f32) {
+        let max_scroll = self.max_scroll();
+        self.scroll.scroll_to(offset, max_scroll);
+    }
+    
+    /// Ease just far enough that item `index` is visible
+    pub fn scroll_into_view(&mut self, index: usize) {
+        let max_scroll = self.max_scroll();
+        self.scroll.reveal(index as f32 * self.item_height, self.item_height, self.size.y, max_scroll);
+    }
 }
 
 impl Default for ListView {
@@ -376,20 +554,65 @@ impl Widget for ListView {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
         self.size = Vec2::new(max_size.x.min(300.0), max_size.y.min(400.0));
+        self.scroll.clamp(self.max_scroll());
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(300.0, 400.0));
+        self.size = Vec2::new(size.width, size.height);
+        self.scroll.clamp(self.max_scroll());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    /// Only the first pass matters: the size follows the space it is given
+    fn needs_layout(&self) -> bool {
+        self.size == Vec2::ZERO
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
                      mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
         
-        if !inside {
+        if !inside && !self.scroll.is_dragging() {
             self.hovered_index = None;
+            self.scroll.hovered = false;
             return false;
         }
         
+        // Scrollbar: grab the thumb or page on the track, then follow drags
+        let (content, visible) = (self.content_height(), self.size.y);
+        let along = mouse_pos.y - self.position.y;
+        let in_bar = mouse_pos.x >= self.position.x + self.size.x - 10.0;
+        self.scroll.hovered = in_bar && self.scroll.thumb_contains(along, visible, content, visible);
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } = event {
+            if *state == ElementState::Pressed {
+                if in_bar && self.scroll.press(along, visible, content, visible) {
+                    return true;
+                }
+            } else {
+                self.scroll.release();
+            }
+        }
+        if self.scroll.drag(along, visible, content, visible) {
+            self.hovered_index = None;
+            return true;
+        }
+        
         // Calculate hovered item
-        let relative_y = mouse_pos.y - self.position.y + self.scroll_offset;
+        let relative_y = mouse_pos.y - self.position.y + self.scroll.offset();
         let idx = (relative_y / self.item_height) as usize;
         self.hovered_index = if idx < self.items.len() { Some(idx) } else { None };
         
@@ -397,6 +620,9 @@ impl Widget for ListView {
         if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
             if let Some(idx) = self.hovered_index {
                 self.selected_index = Some(idx);
+                if let Some(callback) = &mut self.on_select {
+                    callback(idx);
+                }
                 return true;
             }
         }
@@ -407,14 +633,20 @@ impl Widget for ListView {
                 winit::event::MouseScrollDelta::LineDelta(_, y) => y * 40.0,
                 winit::event::MouseScrollDelta::PixelDelta(p) => p.y as f32,
             };
-            self.scroll_offset = (self.scroll_offset - amount).clamp(0.0, self.max_scroll());
-            return true;
+            let max_scroll = self.max_scroll();
+            return self.scroll.scroll_by(-amount, max_scroll);
         }
         
         false
     }
 
-    fn update(&mut self, _dt: f32) {}
+    fn update(&mut self, dt: f32) {
+        self.scroll.update(dt);
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.scroll.is_animating()
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
@@ -427,17 +659,12 @@ impl Widget for ListView {
             self.corner_radius
         );
         
-        // Set scissor
-        renderer.set_scissor([
-            self.position.x as u32,
-            self.position.y as u32,
-            self.size.x as u32,
-            self.size.y as u32,
-        ]);
+        // Clip to the rounded body
+        renderer.push_rounded_clip(Rect::new(self.position, self.size), self.corner_radius);
         
         // Items
         for (i, item) in self.items.iter().enumerate() {
-            let item_y = self.position.y + i as f32 * self.item_height - self.scroll_offset;
+            let item_y = self.position.y + i as f32 * self.item_height - self.scroll.offset();
             
             if item_y + self.item_height < self.position.y || item_y > self.position.y + self.size.y {
                 continue;
@@ -470,18 +697,42 @@ impl Widget for ListView {
             };
             
             // Text
-            renderer.draw_text(&item.text, Vec2::new(text_x, item_y + 10.0), 15.0, theme.text);
+            let text_width = self.position.x + self.size.x - 12.0 - text_x;
+            renderer.draw_text_overflow(&item.text, Vec2::new(text_x, item_y + 10.0), 15.0, theme.text, text_width, self.overflow);
         }
         
-        renderer.clear_scissor();
+        renderer.pop_clip();
         
         // Border
-        renderer.draw_rounded_rect(
+        renderer.draw_rect_stroke(
             self.position - Vec2::splat(1.0),
             self.size + Vec2::splat(2.0),
             Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.4),
+            1.0,
             self.corner_radius + 1.0
         );
+        
+        // Scrollbar (if needed)
+        if self.content_height() > self.size.y {
+            let scrollbar_x = self.position.x + self.size.x - 8.0;
+            let (thumb_y, thumb_height) = self.scroll.thumb(self.size.y, self.content_height(), self.size.y);
+            
+            // Track
+            renderer.draw_rounded_rect(
+                Vec2::new(scrollbar_x, self.position.y + 4.0),
+                Vec2::new(6.0, self.size.y - 8.0),
+                Vec4::new(0.1, 0.1, 0.1, 0.3),
+                3.0
+            );
+            
+            // Thumb
+            renderer.draw_rounded_rect(
+                Vec2::new(scrollbar_x, self.position.y + thumb_y),
+                Vec2::new(6.0, thumb_height),
+                self.scroll.thumb_color(theme.primary),
+                3.0
+            );
+        }
     }
 }
 
@@ -523,6 +774,23 @@ impl TreeNode {
         self.children.push(child);
     }
     
+    /// Checkbox state of the node: a leaf's own `selected`, or for a parent
+    /// whether none, some or all of the leaves below it are selected
+    pub fn check_state(&self) -> CheckState {
+        if self.children.is_empty() {
+            return if self.selected { CheckState::Checked } else { CheckState::Unchecked };
+        }
+        CheckState::combine(self.children.iter().map(|child| child.check_state()))
+    }
+    
+    /// Select or deselect the node and everything below it
+    pub fn set_selected_recursive(&mut self, selected: bool) {
+        self.selected = selected;
+        for child in &mut self.children {
+            child.set_selected_recursive(selected);
+        }
+    }
+    
     fn count_visible(&self) -> usize {
         if self.expanded {
             1 + self.children.iter().map(|c| c.count_visible()).sum::<usize>()
@@ -530,8 +798,30 @@ impl TreeNode {
             1
         }
     }
+    
+    /// The `row`th visible node in these trees and its depth, counting
+    /// down from `row` as rows are passed
+    fn visible_mut<'a>(nodes: &'a mut [TreeNode], row: &mut usize, depth: usize) -> Option<(&'a mut TreeNode, usize)> {
+        for node in nodes {
+            if *row == 0 {
+                return Some((node, depth));
+            }
+            *row -= 1;
+            if node.expanded {
+                if let Some(found) = Self::visible_mut(&mut node.children, row, depth + 1) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
 }
 
+/// Side of the check boxes drawn by `TreeView::with_checkboxes`
+const TREE_CHECK_SIZE: f32 = 16.0;
+
+type CheckCallback = Box<dyn FnMut(&TreeNode, bool)>;
+
 /// Hierarchical tree view
 pub struct TreeView {
     pub position: Vec2,
@@ -541,6 +831,12 @@ pub struct TreeView {
     pub indent: f32,
     pub scroll_offset: f32,
     pub corner_radius: f32,
+    /// Draw each node's `check_state` in a box before its label; clicking
+    /// the box checks or clears the node and everything below it
+    pub checkboxes: bool,
+    /// Called with the clicked node (after it and its subtree change) and
+    /// whether it's now checked
+    pub on_check: Option<CheckCallback>,
     hovered_path: Option<Vec<usize>>,
     selected_path: Option<Vec<usize>>,
 }
@@ -555,6 +851,8 @@ impl TreeView {
             indent: 20.0,
             scroll_offset: 0.0,
             corner_radius: 8.0,
+            checkboxes: false,
+            on_check: None,
             hovered_path: None,
             selected_path: None,
         }
@@ -565,6 +863,21 @@ impl TreeView {
         self
     }
     
+    pub fn with_checkboxes(mut self, checkboxes: bool) -> Self {
+        self.checkboxes = checkboxes;
+        self
+    }
+    
+    pub fn on_check(mut self, callback: impl FnMut(&TreeNode, bool) + 'static) -> Self {
+        self.on_check = Some(Box::new(callback));
+        self
+    }
+    
+    /// Left edge of a node's content: arrow, then box, icon and label
+    fn node_x(&self, depth: usize) -> f32 {
+        self.position.x + depth as f32 * self.indent + 8.0
+    }
+    
     fn count_visible(&self) -> usize {
         self.roots.iter().map(|r| r.count_visible()).sum()
     }
@@ -590,6 +903,29 @@ impl Widget for TreeView {
         self.size = Vec2::new(max_size.x.min(300.0), max_size.y.min(400.0));
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(300.0, 400.0));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    /// Only the first pass matters: the size follows the space it is given
+    fn needs_layout(&self) -> bool {
+        self.size == Vec2::ZERO
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
@@ -610,6 +946,26 @@ impl Widget for TreeView {
             return true;
         }
         
+        // Check box click
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
+            if self.checkboxes {
+                let content_y = mouse_pos.y - self.position.y + self.scroll_offset;
+                let mut row = (content_y / self.item_height) as usize;
+                let (left, indent) = (self.node_x(0), self.indent);
+                if let Some((node, depth)) = TreeNode::visible_mut(&mut self.roots, &mut row, 0) {
+                    let box_x = left + depth as f32 * indent + 18.0;
+                    if mouse_pos.x >= box_x && mouse_pos.x <= box_x + TREE_CHECK_SIZE {
+                        let checked = node.check_state().toggled() == CheckState::Checked;
+                        node.set_selected_recursive(checked);
+                        if let Some(callback) = &mut self.on_check {
+                            callback(node, checked);
+                        }
+                        return true;
+                    }
+                }
+            }
+        }
+        
         false
     }
 
@@ -626,13 +982,8 @@ impl Widget for TreeView {
             self.corner_radius
         );
         
-        // Set scissor
-        renderer.set_scissor([
-            self.position.x as u32,
-            self.position.y as u32,
-            self.size.x as u32,
-            self.size.y as u32,
-        ]);
+        // Clip to the rounded body
+        renderer.push_rounded_clip(Rect::new(self.position, self.size), self.corner_radius);
         
         // Render tree recursively
         let mut y = self.position.y - self.scroll_offset;
@@ -640,13 +991,14 @@ impl Widget for TreeView {
             y = self.render_node(renderer, root, 0, y, &theme);
         }
         
-        renderer.clear_scissor();
+        renderer.pop_clip();
         
         // Border
-        renderer.draw_rounded_rect(
+        renderer.draw_rect_stroke(
             self.position - Vec2::splat(1.0),
             self.size + Vec2::splat(2.0),
             Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.4),
+            1.0,
             self.corner_radius + 1.0
         );
     }
@@ -658,7 +1010,7 @@ impl TreeView {
         
         // Skip if outside visible area
         if current_y + self.item_height >= self.position.y && current_y <= self.position.y + self.size.y {
-            let x = self.position.x + depth as f32 * self.indent + 8.0;
+            let mut x = self.node_x(depth);
             
             // Expand/collapse arrow
             if !node.children.is_empty() {
@@ -666,6 +1018,15 @@ impl TreeView {
                 renderer.draw_text(arrow, Vec2::new(x, current_y + 6.0), 12.0, theme.text_secondary);
             }
             
+            // Check box
+            if self.checkboxes {
+                let state = node.check_state();
+                let t = if state == CheckState::Unchecked { 0.0 } else { 1.0 };
+                let box_pos = Vec2::new(x + 18.0, current_y + (self.item_height - TREE_CHECK_SIZE) * 0.5);
+                draw_check_box(renderer, box_pos, Vec2::splat(TREE_CHECK_SIZE), state, t, false);
+                x += TREE_CHECK_SIZE + 6.0;
+            }
+            
             // Icon
             let text_x = if let Some(icon) = &node.icon {
                 renderer.draw_text(icon, Vec2::new(x + 18.0, current_y + 6.0), 14.0, theme.text_secondary);
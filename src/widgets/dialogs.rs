@@ -0,0 +1,409 @@
+//! GlassUI Dialogs
+//!
+//! Queued modal dialogs with result signals:
+//! - DialogManager - shows one dialog at a time over a dimmed backdrop
+//! - alert / confirm / prompt helpers returning a `Signal` with the answer
+//!
+//! Render the manager as the top layer and give it events first; while a
+//! dialog is open it consumes all input. Enter accepts, Escape cancels.
+
+use std::collections::VecDeque;
+use glam::{Vec2, Vec4};
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+use winit::keyboard::{Key, NamedKey};
+use crate::layout::{BoxConstraints, Rect, Size};
+use crate::renderer::GlassRenderer;
+use crate::state::Signal;
+use crate::widget_id::WidgetId;
+use crate::widgets::core::{Widget, get_theme, easing};
+
+// =============================================================================
+// DIALOG
+// =============================================================================
+
+/// What a dialog asks for, and where its answer goes
+enum DialogKind {
+    Alert(Signal<()>),
+    Confirm(Signal<bool>),
+    Prompt { input: String, result: Signal<Option<String>> },
+}
+
+struct Dialog {
+    title: String,
+    message: String,
+    kind: DialogKind,
+}
+
+impl Dialog {
+    fn has_cancel(&self) -> bool {
+        !matches!(self.kind, DialogKind::Alert(_))
+    }
+
+    fn height(&self) -> f32 {
+        match self.kind {
+            DialogKind::Prompt { .. } => 210.0,
+            _ => 160.0,
+        }
+    }
+
+    fn finish(self, accepted: bool) {
+        match self.kind {
+            DialogKind::Alert(signal) => signal.emit(()),
+            DialogKind::Confirm(signal) => signal.emit(accepted),
+            DialogKind::Prompt { input, result } => result.emit(accepted.then_some(input)),
+        }
+    }
+}
+
+/// Dialog button under the cursor
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DialogButton {
+    Ok,
+    Cancel,
+}
+
+// =============================================================================
+// DIALOG MANAGER
+// =============================================================================
+
+/// Queue of modal dialogs, shown one at a time
+pub struct DialogManager {
+    pub id: WidgetId,
+    pub width: f32,
+    pub corner_radius: f32,
+    pub backdrop_alpha: f32,
+    queue: VecDeque<Dialog>,
+    screen_size: Vec2,
+    hovered: Option<DialogButton>,
+    /// 0..1 fade-in of the active dialog
+    appear_t: f32,
+}
+
+impl DialogManager {
+    pub fn new() -> Self {
+        Self {
+            id: WidgetId::new(),
+            width: 420.0,
+            corner_radius: 12.0,
+            backdrop_alpha: 0.6,
+            queue: VecDeque::new(),
+            screen_size: Vec2::ZERO,
+            hovered: None,
+            appear_t: 0.0,
+        }
+    }
+
+    /// Show a message with a single OK button
+    pub fn alert(&mut self, title: &str, message: &str) -> Signal<()> {
+        let signal = Signal::new();
+        self.push(title, message, DialogKind::Alert(signal.clone()));
+        signal
+    }
+
+    /// Ask a yes/no question; emits `true` for OK, `false` for Cancel
+    pub fn confirm(&mut self, title: &str, message: &str) -> Signal<bool> {
+        let signal = Signal::new();
+        self.push(title, message, DialogKind::Confirm(signal.clone()));
+        signal
+    }
+
+    /// Ask for a line of text; emits `None` when cancelled
+    pub fn prompt(&mut self, title: &str, message: &str, default: &str) -> Signal<Option<String>> {
+        let signal = Signal::new();
+        self.push(title, message, DialogKind::Prompt { input: default.to_string(), result: signal.clone() });
+        signal
+    }
+
+    fn push(&mut self, title: &str, message: &str, kind: DialogKind) {
+        self.queue.push_back(Dialog { title: title.to_string(), message: message.to_string(), kind });
+    }
+
+    /// Whether a dialog is showing
+    pub fn is_open(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    /// Dialogs waiting behind the active one
+    pub fn pending(&self) -> usize {
+        self.queue.len().saturating_sub(1)
+    }
+
+    /// Title of the active dialog
+    pub fn active_title(&self) -> Option<&str> {
+        self.queue.front().map(|d| d.title.as_str())
+    }
+
+    /// Text typed into the active prompt
+    pub fn prompt_text(&self) -> Option<&str> {
+        match &self.queue.front()?.kind {
+            DialogKind::Prompt { input, .. } => Some(input),
+            _ => None,
+        }
+    }
+
+    /// Close the active dialog as if OK was pressed
+    pub fn accept(&mut self) {
+        self.close(true);
+    }
+
+    /// Close the active dialog as if Cancel was pressed
+    pub fn cancel(&mut self) {
+        self.close(false);
+    }
+
+    fn close(&mut self, accepted: bool) {
+        if let Some(dialog) = self.queue.pop_front() {
+            // Next dialog fades in fresh
+            self.appear_t = 0.0;
+            self.hovered = None;
+            dialog.finish(accepted);
+        }
+    }
+
+    fn dialog_rect(&self, dialog: &Dialog) -> Rect {
+        let size = Vec2::new(self.width, dialog.height());
+        Rect::new((self.screen_size - size) * 0.5, size)
+    }
+
+    fn button_rect(&self, dialog: &Dialog, button: DialogButton) -> Rect {
+        let rect = self.dialog_rect(dialog);
+        let size = Vec2::new(96.0, 34.0);
+        let ok_pos = rect.max - size - Vec2::splat(16.0);
+        match button {
+            DialogButton::Ok => Rect::new(ok_pos, size),
+            DialogButton::Cancel => Rect::new(ok_pos - Vec2::new(size.x + 10.0, 0.0), size),
+        }
+    }
+
+    fn input_rect(&self, dialog: &Dialog) -> Rect {
+        let rect = self.dialog_rect(dialog);
+        Rect::new(rect.min + Vec2::new(16.0, 100.0), Vec2::new(self.width - 32.0, 36.0))
+    }
+
+    fn button_at(&self, dialog: &Dialog, point: Vec2) -> Option<DialogButton> {
+        if self.button_rect(dialog, DialogButton::Ok).contains(point) {
+            Some(DialogButton::Ok)
+        } else if dialog.has_cancel() && self.button_rect(dialog, DialogButton::Cancel).contains(point) {
+            Some(DialogButton::Cancel)
+        } else {
+            None
+        }
+    }
+
+    fn render_button(&self, renderer: &mut GlassRenderer, dialog: &Dialog, button: DialogButton, alpha: f32) {
+        let theme = get_theme();
+        let rect = self.button_rect(dialog, button);
+        let hovered = self.hovered == Some(button);
+        let (mut color, label) = match button {
+            DialogButton::Ok => (theme.primary, "OK"),
+            DialogButton::Cancel => (Vec4::new(1.0, 1.0, 1.0, 0.08), "Cancel"),
+        };
+        if hovered {
+            color.w = (color.w + 0.15).min(1.0);
+        }
+        color.w *= alpha;
+        renderer.draw_overlay_rect(rect.min, rect.size(), color, 8.0);
+        let text_width = label.len() as f32 * 8.0;
+        renderer.draw_overlay_text(label, rect.min + Vec2::new((rect.size().x - text_width) * 0.5, 8.0), 16.0, theme.text);
+    }
+}
+
+impl Default for DialogManager {
+    fn default() -> Self { Self::new() }
+}
+
+impl Widget for DialogManager {
+    fn layout(&mut self, _origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.screen_size = max_size;
+        Vec2::ZERO // Dialogs float above the layout
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let screen = constraints.biggest_or(Size::new(self.screen_size.x, self.screen_size.y));
+        self.screen_size = Vec2::new(screen.width, screen.height);
+        constraints.smallest()
+    }
+
+    /// Laid out once to learn the screen size dialogs are centered in
+    fn needs_layout(&self) -> bool {
+        self.screen_size == Vec2::ZERO
+    }
+
+    fn handle_event(&mut self, event: &Event<()>, mouse_pos: Vec2) -> bool {
+        let Some(dialog) = self.queue.front() else { return false };
+        let Event::WindowEvent { event, .. } = event else { return true };
+
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                self.hovered = self.button_at(dialog, mouse_pos);
+            }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                match self.button_at(dialog, mouse_pos) {
+                    Some(DialogButton::Ok) => self.accept(),
+                    Some(DialogButton::Cancel) => self.cancel(),
+                    None => {}
+                }
+            }
+            WindowEvent::KeyboardInput { event: key_event, .. } if key_event.state.is_pressed() => {
+                match &key_event.logical_key {
+                    Key::Named(NamedKey::Enter) => self.accept(),
+                    Key::Named(NamedKey::Escape) => self.cancel(),
+                    Key::Named(NamedKey::Backspace) => {
+                        if let Some(Dialog { kind: DialogKind::Prompt { input, .. }, .. }) = self.queue.front_mut() {
+                            input.pop();
+                        }
+                    }
+                    _ => {
+                        if let (Some(Dialog { kind: DialogKind::Prompt { input, .. }, .. }), Some(text)) = (self.queue.front_mut(), &key_event.text) {
+                            input.extend(text.chars().filter(|c| !c.is_control()));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        // Modal: nothing underneath sees input while a dialog is open
+        true
+    }
+    
+    fn hit_test_popup(&self, _point: Vec2) -> bool {
+        // The backdrop covers the whole screen
+        self.is_open()
+    }
+
+    fn update(&mut self, dt: f32) {
+        if self.is_open() {
+            self.appear_t = (self.appear_t + dt * 8.0).min(1.0);
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        self.is_open() && self.appear_t < 1.0
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let Some(dialog) = self.queue.front() else { return };
+        let theme = get_theme();
+        let t = easing::ease_out_cubic(self.appear_t);
+
+        // Dimmed backdrop
+        renderer.draw_overlay_rect(Vec2::ZERO, self.screen_size, Vec4::new(0.0, 0.0, 0.0, self.backdrop_alpha * t), 0.0);
+
+        // Dialog body, sliding up slightly as it appears
+        let mut rect = self.dialog_rect(dialog);
+        let slide = Vec2::new(0.0, (1.0 - t) * 12.0);
+        rect.min += slide;
+        rect.max += slide;
+        let mut glow = theme.primary;
+        glow.w = 0.3 * t;
+        renderer.draw_overlay_rect(rect.min - Vec2::splat(1.0), rect.size() + Vec2::splat(2.0), glow, self.corner_radius + 1.0);
+        renderer.draw_overlay_rect(rect.min, rect.size(), Vec4::new(0.1, 0.1, 0.12, 0.98 * t), self.corner_radius);
+
+        renderer.draw_overlay_text(&dialog.title, rect.min + Vec2::new(16.0, 16.0), 18.0, theme.text);
+        renderer.draw_overlay_text(&dialog.message, rect.min + Vec2::new(16.0, 52.0), 14.0, theme.text_secondary);
+
+        if let DialogKind::Prompt { input, .. } = &dialog.kind {
+            let input_rect = self.input_rect(dialog);
+            renderer.draw_overlay_rect(input_rect.min, input_rect.size(), Vec4::new(1.0, 1.0, 1.0, 0.06), 6.0);
+            renderer.draw_overlay_text(input, input_rect.min + Vec2::new(10.0, 9.0), 16.0, theme.text);
+            // Caret after the text
+            let caret_x = input_rect.min.x + 10.0 + renderer.measure_text(input, 16.0).width + 1.0;
+            renderer.draw_overlay_rect(Vec2::new(caret_x, input_rect.min.y + 8.0), Vec2::new(2.0, 20.0), theme.primary, 0.0);
+        }
+
+        self.render_button(renderer, dialog, DialogButton::Ok, t);
+        if dialog.has_cancel() {
+            self.render_button(renderer, dialog, DialogButton::Cancel, t);
+        }
+
+        if self.pending() > 0 {
+            renderer.draw_overlay_text_fmt(
+                format_args!("+{} more", self.pending()),
+                Vec2::new(rect.min.x + 16.0, rect.max.y - 30.0),
+                12.0,
+                theme.text_secondary,
+            );
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn click(manager: &mut DialogManager, pos: Vec2) -> bool {
+        manager.handle_event(&Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::MouseInput {
+                device_id: unsafe { winit::event::DeviceId::dummy() },
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+            },
+        }, pos)
+    }
+
+    fn center(rect: Rect) -> Vec2 {
+        (rect.min + rect.max) * 0.5
+    }
+
+    #[test]
+    fn test_dialogs_are_queued() {
+        let mut manager = DialogManager::new();
+        assert!(!manager.is_open());
+        manager.alert("First", "one");
+        manager.confirm("Second", "two");
+        assert_eq!(manager.active_title(), Some("First"));
+        assert_eq!(manager.pending(), 1);
+
+        manager.accept();
+        assert_eq!(manager.active_title(), Some("Second"));
+        manager.cancel();
+        assert!(!manager.is_open());
+    }
+
+    #[test]
+    fn test_confirm_buttons_emit_result() {
+        let mut manager = DialogManager::new();
+        manager.layout(Vec2::ZERO, Vec2::new(1280.0, 720.0));
+        let answers = Rc::new(RefCell::new(Vec::new()));
+        for _ in 0..2 {
+            let answers = answers.clone();
+            manager.confirm("Delete", "Delete panel?").connect_forever(move |ok| answers.borrow_mut().push(ok));
+        }
+
+        // Clicking outside the dialog doesn't dismiss it but is still consumed
+        assert!(click(&mut manager, Vec2::new(5.0, 5.0)));
+        assert!(manager.is_open());
+
+        let dialog = manager.queue.front().unwrap();
+        let cancel = center(manager.button_rect(dialog, DialogButton::Cancel));
+        let ok = center(manager.button_rect(dialog, DialogButton::Ok));
+        click(&mut manager, cancel);
+        click(&mut manager, ok);
+        assert_eq!(*answers.borrow(), vec![false, true]);
+        assert!(!click(&mut manager, ok), "closed manager passes events through");
+    }
+
+    #[test]
+    fn test_prompt_result() {
+        let mut manager = DialogManager::new();
+        let result = Rc::new(RefCell::new(None));
+        let sink = result.clone();
+        manager.prompt("Rename", "New name:", "Panel 1").connect_forever(move |name| *sink.borrow_mut() = Some(name));
+        assert_eq!(manager.prompt_text(), Some("Panel 1"));
+
+        manager.accept();
+        assert_eq!(*result.borrow(), Some(Some("Panel 1".to_string())));
+
+        let sink = result.clone();
+        manager.prompt("Rename", "New name:", "").connect_forever(move |name| *sink.borrow_mut() = Some(name));
+        manager.cancel();
+        assert_eq!(*result.borrow(), Some(None));
+    }
+}
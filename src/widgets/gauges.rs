@@ -9,7 +9,9 @@
 use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
-use crate::widgets::core::{Widget, get_theme};
+use crate::widgets::core::{Widget, UpdateState, get_theme};
+use crate::layout::{BoxConstraints, Offset, Size};
+use crate::panel_style::PathCommand;
 
 // =============================================================================
 // PROGRESS BAR
@@ -83,16 +85,35 @@ impl Widget for AnimatedProgressBar {
         self.size = Vec2::new(max_size.x.min(self.size.x), self.size.y);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
     }
 
     fn update(&mut self, dt: f32) {
-        // Smooth animation
-        if self.animated && (self.value - self.target_value).abs() > 0.001 {
-            self.value += (self.target_value - self.value) * 8.0 * dt;
-        }
+        self.update_state(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, _dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        jobs.push(self);
     }
 
     fn render(&self, renderer: &mut GlassRenderer) {
@@ -121,6 +142,15 @@ impl Widget for AnimatedProgressBar {
     }
 }
 
+impl UpdateState for AnimatedProgressBar {
+    fn update_state(&mut self, dt: f32) {
+        // Smooth animation
+        if self.animated && (self.value - self.target_value).abs() > 0.001 {
+            self.value += (self.target_value - self.value) * 8.0 * dt;
+        }
+    }
+}
+
 // =============================================================================
 // CIRCULAR GAUGE
 // =============================================================================
@@ -130,6 +160,8 @@ pub struct CircularGauge {
     pub id: WidgetId,
     pub position: Vec2,
     pub radius: f32,
+    /// Box the ring is centered in; its diameter unless a parent sizes it
+    size: Vec2,
     pub value: f32,           // 0.0 to 1.0
     pub target_value: f32,
     pub color: Vec4,
@@ -146,6 +178,7 @@ impl CircularGauge {
             id: WidgetId::new(),
             position: Vec2::ZERO,
             radius: 50.0,
+            size: Vec2::splat(100.0),
             value: value.clamp(0.0, 1.0),
             target_value: value.clamp(0.0, 1.0),
             color: theme.primary,
@@ -179,7 +212,26 @@ impl CircularGauge {
 impl Widget for CircularGauge {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        Vec2::splat(self.radius * 2.0)
+        self.size = Vec2::splat(self.radius * 2.0);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.radius * 2.0, self.radius * 2.0));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
     }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
@@ -187,48 +239,24 @@ impl Widget for CircularGauge {
     }
 
     fn update(&mut self, dt: f32) {
-        // Smooth animation
-        if (self.value - self.target_value).abs() > 0.001 {
-            self.value += (self.target_value - self.value) * 6.0 * dt;
-        }
+        self.update_state(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, _dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        jobs.push(self);
     }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        let center = self.position + Vec2::splat(self.radius);
+        let center = self.position + self.size * 0.5;
         
-        // Background circle (approximated with rounded rect)
-        renderer.draw_rounded_rect(
-            self.position,
-            Vec2::splat(self.radius * 2.0),
-            self.background_color,
-            self.radius
-        );
+        // Track ring
+        let tau = std::f32::consts::TAU;
+        renderer.draw_arc(center, self.radius, 0.0, tau, self.thickness, self.background_color);
         
-        // Inner circle (creates ring effect)
-        let inner_radius = self.radius - self.thickness;
-        renderer.draw_rounded_rect(
-            self.position + Vec2::splat(self.thickness),
-            Vec2::splat(inner_radius * 2.0),
-            Vec4::new(0.05, 0.05, 0.07, 0.95),
-            inner_radius
-        );
-        
-        // Value arc (simplified as a partial fill for now)
-        // In a real implementation, this would use arc rendering
+        // Value arc, clockwise from the top
         if self.value > 0.0 {
-            // Approximate with a colored indicator
-            let indicator_angle = self.value * std::f32::consts::TAU - std::f32::consts::FRAC_PI_2;
-            let indicator_pos = center + Vec2::new(
-                indicator_angle.cos() * (self.radius - self.thickness / 2.0),
-                indicator_angle.sin() * (self.radius - self.thickness / 2.0)
-            );
-            renderer.draw_rounded_rect(
-                indicator_pos - Vec2::splat(self.thickness / 2.0),
-                Vec2::splat(self.thickness),
-                self.color,
-                self.thickness / 2.0
-            );
+            renderer.draw_arc(center, self.radius, -std::f32::consts::FRAC_PI_2, self.value * tau, self.thickness, self.color);
         }
         
         // Value text
@@ -246,6 +274,15 @@ impl Widget for CircularGauge {
     }
 }
 
+impl UpdateState for CircularGauge {
+    fn update_state(&mut self, dt: f32) {
+        // Smooth animation
+        if (self.value - self.target_value).abs() > 0.001 {
+            self.value += (self.target_value - self.value) * 6.0 * dt;
+        }
+    }
+}
+
 // =============================================================================
 // SPARKLINE
 // =============================================================================
@@ -322,6 +359,24 @@ impl Widget for MiniSparkline {
         self.size = Vec2::new(max_size.x.min(self.size.x), self.size.y);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
@@ -338,28 +393,23 @@ impl Widget for MiniSparkline {
         let range = max - min;
         let point_width = self.size.x / (self.data.len() - 1) as f32;
         
-        // Draw line segments
-        for i in 0..self.data.len() - 1 {
-            let x1 = self.position.x + i as f32 * point_width;
-            let x2 = self.position.x + (i + 1) as f32 * point_width;
-            let y1 = self.position.y + self.size.y - ((self.data[i] - min) / range) * self.size.y;
-            let y2 = self.position.y + self.size.y - ((self.data[i + 1] - min) / range) * self.size.y;
-            
-            // Line segment (approximated)
-            let dx = x2 - x1;
-            let dy = y2 - y1;
-            let len = (dx * dx + dy * dy).sqrt();
-            let angle = dy.atan2(dx);
-            
-            // Draw as thin rectangle rotated (simplified: just draw points)
-            renderer.draw_rounded_rect(
-                Vec2::new(x1, y1.min(y2)),
-                Vec2::new(point_width, 2.0),
-                self.color,
-                1.0
-            );
+        // Area under the line, closed along the bottom edge
+        if self.fill {
+            let mut area = vec![PathCommand::MoveTo(0.0, self.size.y)];
+            area.extend(self.data.iter().enumerate().map(|(i, &v)| {
+                PathCommand::LineTo(i as f32 * point_width, self.size.y - ((v - min) / range) * self.size.y)
+            }));
+            area.push(PathCommand::LineTo(self.size.x, self.size.y));
+            area.push(PathCommand::Close);
+            renderer.fill_path(self.position, &area, self.color * Vec4::new(1.0, 1.0, 1.0, 0.2));
         }
         
+        // Draw the line
+        let points: Vec<Vec2> = self.data.iter().enumerate()
+            .map(|(i, &v)| self.position + Vec2::new(i as f32 * point_width, self.size.y - ((v - min) / range) * self.size.y))
+            .collect();
+        renderer.draw_polyline(&points, 2.0, self.color);
+        
         // End point dot
         if let Some(&last) = self.data.last() {
             let last_x = self.position.x + self.size.x;
@@ -373,8 +423,7 @@ impl Widget for MiniSparkline {
             
             // Show value
             if self.show_last_value {
-                let value_text = format!("{:.1}", last);
-                renderer.draw_text(&value_text, Vec2::new(last_x + 4.0, last_y - 6.0), 10.0, theme.text_secondary);
+                renderer.draw_text_fmt(format_args!("{:.1}", last), Vec2::new(last_x + 4.0, last_y - 6.0), 10.0, theme.text_secondary);
             }
         }
     }
@@ -454,19 +503,39 @@ impl MetricDisplay {
     pub fn add_data_point(&mut self, value: f32) {
         self.sparkline.push(value);
     }
+    
+    /// Layout sparkline at bottom
+    fn place_sparkline(&mut self) {
+        self.sparkline.position = self.position + Vec2::new(8.0, self.size.y - 32.0);
+        self.sparkline.size = Vec2::new(self.size.x - 16.0, 24.0);
+    }
 }
 
 impl Widget for MetricDisplay {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        
-        // Layout sparkline at bottom
-        let sparkline_size = Vec2::new(self.size.x - 16.0, 24.0);
-        self.sparkline.position = self.position + Vec2::new(8.0, self.size.y - 32.0);
-        self.sparkline.size = sparkline_size;
-        
+        self.place_sparkline();
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.place_sparkline();
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
@@ -542,4 +611,26 @@ mod tests {
         assert_eq!(metric.label, "CPU");
         assert_eq!(metric.trend, MetricTrend::Up);
     }
+    
+    #[test]
+    fn test_update_parallel() {
+        use crate::widgets::{Column, update_parallel};
+        
+        let mut bar = AnimatedProgressBar::new(0.0);
+        bar.set_value(1.0);
+        let mut gauge = CircularGauge::new(0.0);
+        gauge.set_value(1.0);
+        let mut column = Column::new()
+            .add_child(Box::new(bar))
+            .add_child(Box::new(gauge));
+        
+        let mut jobs = Vec::new();
+        column.split_update(0.1, &mut jobs);
+        assert_eq!(jobs.len(), 2);
+        
+        let mut gauge = CircularGauge::new(0.0);
+        gauge.set_value(1.0);
+        update_parallel(&mut gauge, 0.1);
+        assert!(gauge.value > 0.0);
+    }
 }
@@ -1,44 +1,295 @@
 //! GlassUI Input Widgets
 //!
-//! Text and data input controls: TextInput, Dropdown
+//! Text and data input controls: TextInput, Dropdown, date and time pickers
 
+use std::cell::RefCell;
+use std::ops::Range;
 use glam::{Vec2, Vec4};
 use winit::event::{ElementState, MouseButton};
+use winit::keyboard::ModifiersState;
 use crate::renderer::GlassRenderer;
-use super::core::{Widget, get_theme};
+use super::core::{Widget, WidgetState, get_theme, render_faded};
+use crate::layout::{BoxConstraints, EdgeInsets, Offset, Rect, Size};
+use crate::bidi;
+use crate::clipboard::ClipboardAction;
+use crate::focus::{FocusId, Focusable};
+use crate::style::WidgetStyle;
 
 // =============================================================================
 // TEXT INPUT
 // =============================================================================
 
 /// Single-line text input field
+/// 
+/// Left/Right move the caret on screen, so they step backwards through
+/// right-to-left text; with Ctrl (or Alt) held they jump a word, and
+/// Backspace/Delete remove one. Home/End go to the start/end of the text.
+/// Holding Shift while moving the caret, or dragging with the mouse,
+/// selects text; typing, pasting or deleting then replaces the selection.
+///
+/// In password mode (see `password`) the text is drawn as bullets and
+/// can't be copied, cut or saved with the window state.
+///
+/// A field with a mask or validators (see `with_mask`, `with_validator`)
+/// checks its text once it loses focus and after each later edit, drawing
+/// the first error below itself in the theme's error color.
 pub struct TextInput {
     pub position: Vec2,
     pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
     pub text: String,
+    /// Caret position, a byte offset into `text`
+    pub cursor: usize,
+    /// Other end of the selection from the caret (byte offset), if any
+    pub selection_start: Option<usize>,
     pub placeholder: String,
     pub focused: bool,
+    /// Identifies the field to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
     pub cursor_visible: bool,
     pub cursor_timer: f32,
     pub corner_radius: f32,
+    /// Called with the whole text after each edit
+    pub on_change: Option<TextCallback>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
+    /// Per-instance overrides of the theme colors (see `with_style`)
+    pub style: WidgetStyle,
+    /// Draw the text masked and keep it off the clipboard
+    pub password: bool,
+    /// Show a button that unmasks a password while toggled on
+    pub reveal_toggle: bool,
+    /// A password is currently shown in plain text
+    pub revealed: bool,
+    /// Keeps the text in shape while it is typed
+    pub mask: Option<InputMask>,
+    /// Checked in order by `validate`; the first error is shown
+    pub validators: Vec<Validator>,
+    /// Message from the last `validate`, if it failed
+    pub error: Option<String>,
+    /// Validated once already, so edits re-check the text
+    touched: bool,
+    /// Modifier keys held, from the last `ModifiersChanged`
+    modifiers: ModifiersState,
+    /// The mouse is held down, extending the selection as it moves
+    dragging: bool,
+    /// Text last drawn and its caret stops, for placing the caret at a click
+    measured: RefCell<(String, Vec<(usize, f32)>)>,
 }
 
+type TextCallback = Box<dyn FnMut(&str)>;
+
+/// Drawn in place of each character of a masked password
+const MASK: char = '•';
+
+/// Width of a password field's Show/Hide button
+const REVEAL_WIDTH: f32 = 48.0;
+
+/// Space below a validated field for its error message
+pub(super) const ERROR_LINE: f32 = 18.0;
+
+/// Size of a `TextInput`'s text area, inside its padding
+const TEXT_AREA: Vec2 = Vec2::new(180.0, 20.0);
+
 impl TextInput {
     pub fn new(placeholder: &str) -> Self {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            layout_intrinsic: None,
             text: String::new(),
+            cursor: 0,
+            selection_start: None,
             placeholder: placeholder.to_string(),
             focused: false,
+            focus_id: FocusId::new(),
             cursor_visible: true,
             cursor_timer: 0.0,
             corner_radius: 6.0,
+            on_change: None,
+            disabled: false,
+            style: WidgetStyle::default(),
+            password: false,
+            reveal_toggle: false,
+            revealed: false,
+            mask: None,
+            validators: Vec::new(),
+            error: None,
+            touched: false,
+            modifiers: ModifiersState::empty(),
+            dragging: false,
+            measured: RefCell::new((String::new(), Vec::new())),
+        }
+    }
+    
+    /// Override theme values for this field: the radius replaces the field's
+    /// and padding sizes it around the text, while background, border
+    /// (shown unless focused), text color and opacity are read when
+    /// rendering.
+    /// Repeated calls layer on top of each other.
+    pub fn with_style(mut self, style: WidgetStyle) -> Self {
+        if let Some(radius) = style.corner_radius {
+            self.corner_radius = radius;
+        }
+        self.style = self.style.merge(&style);
+        self
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; drops focus, and `EventDispatcher`
+    /// won't give it back while disabled
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.focused = false;
+        }
+    }
+    
+    pub fn on_change(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
+    /// Mask the text as bullets and block copying it
+    pub fn password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+    
+    /// Add a Show/Hide button at the right end of a password field
+    pub fn with_reveal_toggle(mut self, reveal_toggle: bool) -> Self {
+        self.reveal_toggle = reveal_toggle;
+        self
+    }
+    
+    /// Reshape typed text to `mask`, refusing keys that can't fit it
+    pub fn with_mask(mut self, mask: InputMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+    
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+    
+    /// Whether the text passes the mask and validators, e.g. before a form
+    /// is submitted
+    pub fn is_valid(&self) -> bool {
+        self.check().is_ok()
+    }
+    
+    /// Check the text now and show the error, if any; later edits keep
+    /// re-checking. Returns whether the text is valid.
+    pub fn validate(&mut self) -> bool {
+        self.touched = true;
+        self.error = self.check().err();
+        self.error.is_none()
+    }
+    
+    /// First complaint of the mask (about unfinished text) or validators
+    fn check(&self) -> Result<(), String> {
+        if let Some(mask) = &self.mask {
+            if !self.text.is_empty() && !mask.is_complete(&self.text) {
+                return Err(mask.hint());
+            }
+        }
+        self.validators.iter().try_for_each(|validator| validator.check(&self.text))
+    }
+    
+    fn has_validation(&self) -> bool {
+        self.mask.is_some() || !self.validators.is_empty()
+    }
+    
+    /// Field size (the text area plus padding), with room below for an
+    /// error message once validated
+    fn default_size(&self) -> Size {
+        let error_line = if self.has_validation() { ERROR_LINE } else { 0.0 };
+        let padding = self.padding();
+        Size::new(
+            TEXT_AREA.x + padding.horizontal(),
+            TEXT_AREA.y + padding.vertical() + error_line,
+        )
+    }
+    
+    /// Space around the text: the style's, else 10 by 8
+    fn padding(&self) -> EdgeInsets {
+        self.style.padding.unwrap_or(EdgeInsets::symmetric(10.0, 8.0))
+    }
+    
+    /// Height of the box itself, without the line kept for errors
+    fn field_size(&self) -> Vec2 {
+        let error_line = if self.has_validation() { ERROR_LINE } else { 0.0 };
+        Vec2::new(self.size.x, (self.size.y - error_line).max(0.0))
+    }
+    
+    /// Refit the text to the mask after an edit, or undo the edit back to
+    /// `before` (text and caret) if it can't fit
+    fn apply_mask(&mut self, before: (String, usize)) {
+        let Some(mask) = &self.mask else {
+            return;
+        };
+        match mask.apply(&self.text) {
+            Some(masked) => {
+                // Keep the caret the same distance from the end
+                let from_end = self.text.len() - self.cursor.min(self.text.len());
+                self.cursor = masked.len().saturating_sub(from_end);
+                self.text = masked;
+            }
+            None => (self.text, self.cursor) = before,
+        }
+        self.selection_start = None;
+        self.clamp_cursor();
+    }
+    
+    /// Whether the text is drawn as bullets
+    pub fn is_masked(&self) -> bool {
+        self.password && !self.revealed
+    }
+    
+    /// Text as drawn: one bullet per character while masked
+    fn display_text(&self) -> std::borrow::Cow<'_, str> {
+        if self.is_masked() {
+            MASK.to_string().repeat(self.text.chars().count()).into()
+        } else {
+            self.text.as_str().into()
+        }
+    }
+    
+    /// Byte offset into the drawn text of byte `index` of `text`
+    fn display_index(&self, index: usize) -> usize {
+        if self.is_masked() {
+            self.text[..index].chars().count() * MASK.len_utf8()
+        } else {
+            index
+        }
+    }
+    
+    /// Byte offset into `text` of byte `index` of the drawn text
+    fn text_index(&self, index: usize) -> usize {
+        if self.is_masked() {
+            self.text.char_indices().nth(index / MASK.len_utf8()).map_or(self.text.len(), |(i, _)| i)
+        } else {
+            index
         }
     }
     
+    /// Bounds of the Show/Hide button, when the field has one
+    fn reveal_button(&self) -> Option<Rect> {
+        (self.password && self.reveal_toggle).then(|| {
+            let field = self.field_size();
+            let width = REVEAL_WIDTH.min(field.x);
+            Rect::new(Vec2::new(self.position.x + field.x - width, self.position.y), Vec2::new(width, field.y))
+        })
+    }
+    
     pub fn with_text(mut self, text: &str) -> Self {
-        self.text = text.to_string();
+        self.set_text(text);
         self
     }
     
@@ -46,92 +297,286 @@ impl TextInput {
         &self.text
     }
     
+    /// Replace the text, moving the caret to its end
     pub fn set_text(&mut self, text: &str) {
         self.text = text.to_string();
+        self.cursor = self.text.len();
+        self.selection_start = None;
     }
-}
-
-impl Widget for TextInput {
-    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
-        self.position = origin;
-        self.size = Vec2::new(200.0, 36.0);
-        self.size
+    
+    /// Selected byte range, if any text is selected
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let start = self.selection_start?;
+        let range = start.min(self.cursor)..start.max(self.cursor);
+        (!range.is_empty()).then_some(range)
     }
-
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
+    
+    /// Select `range` of the text, leaving the caret at its end
+    pub fn set_selection(&mut self, range: Range<usize>) {
+        self.selection_start = Some(range.start);
+        self.cursor = range.end;
+        self.clamp_cursor();
+    }
+    
+    pub fn select_all(&mut self) {
+        self.set_selection(0..self.text.len());
+    }
+    
+    pub fn selected_text(&self) -> &str {
+        self.selection().map_or("", |range| &self.text[range])
+    }
+    
+    /// Keep the caret and selection on character boundaries when `text`
+    /// was changed directly
+    fn clamp_cursor(&mut self) {
+        let clamp = |text: &str, mut index: usize| {
+            index = index.min(text.len());
+            while !text.is_char_boundary(index) {
+                index -= 1;
+            }
+            index
+        };
+        self.cursor = clamp(&self.text, self.cursor);
+        self.selection_start = self.selection_start.map(|start| clamp(&self.text, start));
+    }
+    
+    /// Move the caret to `index`, extending the selection when `extend` is
+    /// set and dropping it otherwise
+    fn move_caret(&mut self, index: usize, extend: bool) {
+        if extend {
+            self.selection_start.get_or_insert(self.cursor);
+        } else {
+            self.selection_start = None;
+        }
+        self.cursor = index;
+    }
+    
+    /// Remove the selected text, if any; returns whether there was some
+    fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection() else {
+            self.selection_start = None;
+            return false;
+        };
+        self.text.replace_range(range.clone(), "");
+        self.cursor = range.start;
+        self.selection_start = None;
+        true
+    }
+    
+    /// Caret position nearest to the window x coordinate `x`, using the
+    /// stops measured when the text was last drawn
+    fn index_at(&self, x: f32) -> usize {
+        let x = x - self.position.x - self.text_offset().x;
+        let display = self.display_text();
+        let measured = self.measured.borrow();
+        let stops = if measured.0 == display {
+            measured.1.clone()
+        } else {
+            crate::text::caret_stops(&display, |run| crate::text::approximate_text_width(run, 18.0))
+        };
+        stops.into_iter()
+            .min_by(|a, b| (a.1 - x).abs().total_cmp(&(b.1 - x).abs()))
+            .map_or(0, |(i, _)| self.text_index(i))
+    }
+    
+    /// Where the text starts relative to the field
+    fn text_offset(&self) -> Vec2 {
+        let padding = self.padding();
+        Vec2::new(padding.left, padding.top)
+    }
+    
+    /// Insert at the caret, replacing any selection, and move the caret
+    /// past it
+    fn insert(&mut self, text: &str) {
+        self.clamp_cursor();
+        self.delete_selection();
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+    
+    /// Whether Ctrl or Alt is held, turning caret keys into word moves
+    fn word_modifier(&self) -> bool {
+        self.modifiers.control_key() || self.modifiers.alt_key()
+    }
+    
+    /// Caret after a word jump towards the left (`right = false`) or right
+    /// of the screen; in right-to-left text the left is the end
+    fn word_move(&self, right: bool) -> usize {
+        // Word breaks of a hidden password would give its shape away
+        if self.is_masked() {
+            return if right { self.text.len() } else { 0 };
+        }
+        let forward = right == (bidi::paragraph_direction(&self.text) == bidi::Direction::Ltr);
+        if forward {
+            next_word_end(&self.text, self.cursor)
+        } else {
+            previous_word_start(&self.text, self.cursor)
+        }
+    }
+    
+    fn handle_input_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let field = self.field_size();
+        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + field.x &&
+                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + field.y;
 
         match event {
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::ModifiersChanged(modifiers), .. } => {
+                self.modifiers = modifiers.state();
+            },
             winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                if self.reveal_button().is_some_and(|button| button.contains(mouse_pos)) {
+                    self.revealed = !self.revealed;
+                    return true;
+                }
                 if inside {
                     self.focused = true;
+                    self.clamp_cursor();
+                    let index = self.index_at(mouse_pos.x);
+                    self.move_caret(index, self.modifiers.shift_key());
+                    self.selection_start.get_or_insert(index);
+                    self.dragging = true;
+                    self.cursor_visible = true;
+                    self.cursor_timer = 0.0;
                     return true;
-                } else {
-                    self.focused = false;
+                } else if self.focused {
+                    self.on_blur();
                 }
             },
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::Ime(winit::event::Ime::Commit(text)), .. } if self.focused => {
+                self.clamp_cursor();
+                self.insert(text);
+                return true;
+            },
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::CursorMoved { .. }, .. } if self.dragging => {
+                self.cursor = self.index_at(mouse_pos.x);
+                return true;
+            },
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Released, button: MouseButton::Left, .. }, .. } if self.dragging => {
+                self.dragging = false;
+                return true;
+            },
             winit::event::Event::WindowEvent { event: winit::event::WindowEvent::KeyboardInput { event: key_event, .. }, .. } => {
                 if self.focused && key_event.state.is_pressed() {
-                    // Handle backspace
-                    if let winit::keyboard::Key::Named(winit::keyboard::NamedKey::Backspace) = key_event.logical_key {
-                        self.text.pop();
-                        return true;
-                    }
+                    use winit::keyboard::{Key, NamedKey};
+                    self.clamp_cursor();
                     
-                    // Check for Ctrl modifier (for clipboard shortcuts)
-                    let ctrl_held = key_event.text.is_none() || {
-                        // Check if this is a control character
-                        if let Some(text) = &key_event.text {
-                            text.chars().next().map(|c| c.is_control()).unwrap_or(false)
-                        } else {
-                            false
+                    // Caret movement and deletion
+                    if let Key::Named(named) = key_event.logical_key {
+                        let word = self.word_modifier();
+                        let shift = self.modifiers.shift_key();
+                        let handled = match named {
+                            NamedKey::Backspace | NamedKey::Delete if self.delete_selection() => true,
+                            NamedKey::Backspace if word => {
+                                let start = if self.is_masked() { 0 } else { previous_word_start(&self.text, self.cursor) };
+                                self.text.replace_range(start..self.cursor, "");
+                                self.cursor = start;
+                                true
+                            }
+                            NamedKey::Backspace => {
+                                if let Some(c) = self.text[..self.cursor].chars().next_back() {
+                                    self.cursor -= c.len_utf8();
+                                    self.text.remove(self.cursor);
+                                }
+                                true
+                            }
+                            NamedKey::Delete if word => {
+                                let end = if self.is_masked() { self.text.len() } else { next_word_end(&self.text, self.cursor) };
+                                self.text.replace_range(self.cursor..end, "");
+                                true
+                            }
+                            NamedKey::Delete => {
+                                if self.cursor < self.text.len() {
+                                    self.text.remove(self.cursor);
+                                }
+                                true
+                            }
+                            NamedKey::ArrowLeft | NamedKey::ArrowRight if word => {
+                                let index = self.word_move(named == NamedKey::ArrowRight);
+                                self.move_caret(index, shift);
+                                true
+                            }
+                            NamedKey::ArrowLeft | NamedKey::ArrowRight if !shift && self.selection().is_some() => {
+                                // Collapse the selection to the end the arrow points at
+                                let range = self.selection().unwrap_or_default();
+                                let index = if named == NamedKey::ArrowRight { range.end } else { range.start };
+                                self.move_caret(index, false);
+                                true
+                            }
+                            NamedKey::ArrowLeft | NamedKey::ArrowRight => {
+                                let moved = bidi::move_visually(&self.display_text(), self.display_index(self.cursor), named == NamedKey::ArrowRight);
+                                let index = self.text_index(moved);
+                                self.move_caret(index, shift);
+                                true
+                            }
+                            NamedKey::Home => {
+                                self.move_caret(0, shift);
+                                true
+                            }
+                            NamedKey::End => {
+                                self.move_caret(self.text.len(), shift);
+                                true
+                            }
+                            _ => false,
+                        };
+                        if handled {
+                            self.cursor_visible = true;
+                            self.cursor_timer = 0.0;
+                            return true;
                         }
-                    };
+                    }
                     
                     // Handle Ctrl+C (copy), Ctrl+V (paste), Ctrl+X (cut)
-                    if let winit::keyboard::Key::Character(ref c) = key_event.logical_key {
-                        match c.as_str() {
-                            "c" if ctrl_held => {
-                                // Copy all text
-                                if !self.text.is_empty() {
-                                    let _ = crate::clipboard::copy_to_clipboard(&self.text);
-                                }
-                                return true;
+                    match ClipboardAction::from_key(&key_event.logical_key, self.modifiers) {
+                        Some(ClipboardAction::Copy | ClipboardAction::Cut) if self.password => {
+                            // Passwords stay off the clipboard
+                            return true;
+                        }
+                        Some(ClipboardAction::Copy) => {
+                            // Copy the selection, or all text without one
+                            let copied = if self.selection().is_some() { self.selected_text() } else { &self.text };
+                            if !copied.is_empty() {
+                                let _ = crate::clipboard::copy_to_clipboard(copied);
                             }
-                            "v" if ctrl_held => {
-                                // Paste at end
-                                if let Ok(pasted) = crate::clipboard::paste_from_clipboard() {
-                                    // Filter to printable characters
-                                    let filtered: String = pasted.chars()
-                                        .filter(|c| !c.is_control() || *c == '\n')
-                                        .take(1000) // Limit paste length
-                                        .collect();
-                                    self.text.push_str(&filtered);
-                                }
-                                return true;
+                            return true;
+                        }
+                        Some(ClipboardAction::Paste) => {
+                            // Paste at the caret
+                            if let Ok(pasted) = crate::clipboard::paste_from_clipboard() {
+                                // Filter to printable characters
+                                let filtered: String = pasted.chars()
+                                    .filter(|c| !c.is_control() || *c == '\n')
+                                    .take(1000) // Limit paste length
+                                    .collect();
+                                self.insert(&filtered);
                             }
-                            "x" if ctrl_held => {
-                                // Cut all text
-                                if !self.text.is_empty() {
-                                    let _ = crate::clipboard::copy_to_clipboard(&self.text);
-                                    self.text.clear();
-                                }
-                                return true;
+                            return true;
+                        }
+                        Some(ClipboardAction::Cut) => {
+                            // Cut the selection, or all text without one
+                            if self.selection().is_none() {
+                                self.select_all();
                             }
-                            "a" if ctrl_held => {
-                                // Select all (for future text selection support)
-                                return true;
+                            if !self.text.is_empty() {
+                                let _ = crate::clipboard::copy_to_clipboard(self.selected_text());
+                                self.delete_selection();
                             }
-                            _ => {}
+                            return true;
                         }
+                        None => {}
+                    }
+                    
+                    // Ctrl+A (or Cmd+A) selects everything
+                    let command = self.modifiers.control_key() || self.modifiers.super_key();
+                    if command && matches!(&key_event.logical_key, Key::Character(c) if c.eq_ignore_ascii_case("a")) {
+                        self.select_all();
+                        return true;
                     }
                     
                     // Regular text input
                     if let Some(text) = &key_event.text {
                         if let Some(c) = text.chars().next() {
                             if !c.is_control() {
-                                self.text.push_str(text);
+                                self.insert(text);
                                 return true;
                             }
                         }
@@ -143,6 +588,112 @@ impl Widget for TextInput {
         
         false
     }
+}
+
+/// Part of a word for Ctrl+arrow movement
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Start of the word before byte `cursor` of `text`, skipping the spaces
+/// and punctuation in between
+fn previous_word_start(text: &str, cursor: usize) -> usize {
+    let mut chars = text[..cursor].char_indices().rev().peekable();
+    while chars.next_if(|&(_, c)| !is_word_char(c)).is_some() {}
+    while chars.next_if(|&(_, c)| is_word_char(c)).is_some() {}
+    chars.peek().map_or(0, |&(i, c)| i + c.len_utf8())
+}
+
+/// End of the word after byte `cursor` of `text`, skipping the spaces and
+/// punctuation in between
+fn next_word_end(text: &str, cursor: usize) -> usize {
+    let mut chars = text[cursor..].char_indices().peekable();
+    while chars.next_if(|&(_, c)| !is_word_char(c)).is_some() {}
+    while chars.next_if(|&(_, c)| is_word_char(c)).is_some() {}
+    chars.peek().map_or(text.len(), |&(i, _)| cursor + i)
+}
+
+impl Focusable for TextInput {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn can_focus(&self) -> bool {
+        !self.disabled
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+        if self.has_validation() {
+            self.validate();
+        }
+    }
+}
+
+impl Widget for TextInput {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.disabled {
+            return false;
+        }
+        let before = (self.on_change.is_some() || self.has_validation()).then(|| (self.text.clone(), self.cursor));
+        let handled = self.handle_input_event(event, mouse_pos);
+        if let Some(before) = before.filter(|before| before.0 != self.text) {
+            let old_text = before.0.clone();
+            self.apply_mask(before);
+            if self.touched {
+                self.validate();
+            }
+            if let Some(callback) = &mut self.on_change {
+                if old_text != self.text {
+                    callback(&self.text);
+                }
+            }
+        }
+        handled
+    }
 
     fn update(&mut self, dt: f32) {
         if self.focused {
@@ -158,74 +709,333 @@ impl Widget for TextInput {
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        
-        // Background
-        let bg_col = if self.focused { 
-            Vec4::new(0.1, 0.1, 0.12, 0.95) 
-        } else { 
-            Vec4::new(0.08, 0.08, 0.1, 0.9) 
-        };
-        renderer.draw_rounded_rect(self.position, self.size, bg_col, self.corner_radius);
-        
-        // Border
-        if self.focused {
-            renderer.draw_rounded_rect(
-                self.position - Vec2::splat(1.0), 
-                self.size + Vec2::splat(2.0), 
-                Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.6),
-                self.corner_radius + 1.0
-            );
-        }
+        render_faded(renderer, self.disabled, self.style.opacity, |renderer| {
+            // Background
+            let bg_col = match self.style.background {
+                Some(color) => color,
+                None if self.focused => Vec4::new(0.1, 0.1, 0.12, 0.95),
+                None => Vec4::new(0.08, 0.08, 0.1, 0.9),
+            };
+            let field = self.field_size();
+            if let Some(shadow) = &self.style.shadow {
+                renderer.draw_box_shadow(self.position, field, self.corner_radius, shadow);
+            }
+            renderer.draw_rounded_rect(self.position, field, bg_col, self.corner_radius);
+            
+            // Border, in the error color while the text is invalid
+            if self.error.is_some() || self.focused {
+                let accent = if self.error.is_some() { theme.error } else { theme.primary };
+                renderer.draw_rect_stroke(
+                    self.position - Vec2::splat(1.0), 
+                    field + Vec2::splat(2.0), 
+                    Vec4::new(accent.x, accent.y, accent.z, 0.6),
+                    1.5,
+                    self.corner_radius + 1.0
+                );
+            } else if let Some(border) = self.style.border_for(false) {
+                renderer.draw_border(&border, self.position, field, self.corner_radius);
+            }
+            if let Some(error) = &self.error {
+                renderer.draw_text(error, self.position + Vec2::new(4.0, field.y + 3.0), 12.0, theme.error);
+            }
 
-        // Text or placeholder
-        let display_text = if self.text.is_empty() && !self.focused {
-            &self.placeholder
-        } else {
-            &self.text
-        };
-        let text_color = if self.text.is_empty() && !self.focused {
-            theme.text_secondary
-        } else {
-            theme.text
-        };
-        
-        renderer.draw_text(display_text, self.position + Vec2::new(10.0, 8.0), 18.0, text_color);
-        
-        // Cursor
-        if self.focused && self.cursor_visible {
-            let text_width = self.text.len() as f32 * 9.0;
-            let cursor_pos = self.position + Vec2::new(10.0 + text_width, 6.0);
-            renderer.draw_rounded_rect(cursor_pos, Vec2::new(2.0, 22.0), theme.primary, 1.0);
-        }
+            // Text or placeholder
+            let shown = self.display_text();
+            let display_text = if self.text.is_empty() && !self.focused {
+                &self.placeholder
+            } else {
+                shown.as_ref()
+            };
+            let text_color = if self.text.is_empty() && !self.focused {
+                theme.text_secondary
+            } else {
+                self.style.text_color(theme.text)
+            };
+            
+            let text_offset = self.text_offset();
+            
+            // Selection highlight, behind the text
+            if let Some(range) = self.selection() {
+                let highlight = Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, if self.focused { 0.35 } else { 0.2 });
+                let range = self.display_index(range.start)..self.display_index(range.end);
+                for rect in renderer.selection_rects(&shown, 18.0, range) {
+                    renderer.draw_rect(self.position + text_offset + rect.min, rect.size(), highlight);
+                }
+            }
+            
+            renderer.draw_text(display_text, self.position + text_offset, 18.0, text_color);
+            
+            // Remember where the caret can go, for clicks and drags
+            if self.measured.borrow().0 != shown {
+                let stops = crate::text::caret_stops(&shown, |run| renderer.measure_text(run, 18.0).width);
+                *self.measured.borrow_mut() = (shown.to_string(), stops);
+            }
+            
+            // Cursor, after the characters left of it on screen
+            if self.focused && self.cursor_visible {
+                let text_width = renderer.index_to_position(&shown, 18.0, self.display_index(self.cursor.min(self.text.len())));
+                let cursor_pos = self.position + Vec2::new(text_offset.x + text_width, text_offset.y - 2.0);
+                renderer.draw_rounded_rect(cursor_pos, Vec2::new(2.0, 22.0), theme.primary, 1.0);
+            }
+            
+            // Show/Hide button over the right end
+            if let Some(button) = self.reveal_button() {
+                renderer.draw_rounded_rect(button.min, button.size(), bg_col, self.corner_radius);
+                let label = if self.revealed { "Hide" } else { "Show" };
+                let label_width = renderer.measure_text(label, 13.0).width;
+                let label_pos = button.min + Vec2::new((button.size().x - label_width) / 2.0, (button.size().y - 13.0) / 2.0);
+                renderer.draw_text(label, label_pos, 13.0, theme.primary);
+            }
+        });
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("text", self.display_text().into_owned()));
+        props.push(("focused", self.focused.to_string()));
+    }
+    
+    fn widget_style(&self) -> Option<&WidgetStyle> {
+        Some(&self.style)
+    }
+    
+    fn saved_state(&self) -> Option<WidgetState> {
+        // Passwords are never written to disk
+        (!self.password).then(|| WidgetState::Text(self.text.clone()))
+    }
+    
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        Some((self.position + self.text_offset(), 18.0))
     }
 }
 
 // =============================================================================
-// DROPDOWN
+// VALIDATION
 // =============================================================================
 
-/// Dropdown select box
-pub struct Dropdown {
-    pub position: Vec2,
-    pub size: Vec2,
-    pub options: Vec<String>,
-    pub selected_index: usize,
-    pub open: bool,
-    pub hovered_index: Option<usize>,
-    pub corner_radius: f32,
-}
+/// Check on an input's text; the error is the message shown under it
+pub struct Validator(ValidateFn);
 
-impl Dropdown {
-    pub fn new(options: Vec<String>) -> Self {
-        Self {
-            position: Vec2::ZERO,
-            size: Vec2::ZERO,
-            options,
-            selected_index: 0,
-            open: false,
-            hovered_index: None,
-            corner_radius: 6.0,
-        }
+type ValidateFn = Box<dyn Fn(&str) -> Result<(), String>>;
+
+impl Validator {
+    pub fn new(check: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        Self(Box::new(check))
+    }
+    
+    /// Fails with `message` unless the whole text matches `pattern`; empty
+    /// text passes, so pair it with `required` for a mandatory field
+    ///
+    /// Panics if `pattern` isn't a valid regular expression.
+    pub fn regex(pattern: &str, message: &str) -> Self {
+        let regex = regex::Regex::new(&format!("^(?:{})$", pattern)).expect("invalid validator pattern");
+        let message = message.to_string();
+        Self::new(move |text| if text.is_empty() || regex.is_match(text) { Ok(()) } else { Err(message.clone()) })
+    }
+    
+    /// Fails with `message` while the text is blank
+    pub fn required(message: &str) -> Self {
+        let message = message.to_string();
+        Self::new(move |text| if text.trim().is_empty() { Err(message.clone()) } else { Ok(()) })
+    }
+    
+    pub fn check(&self, text: &str) -> Result<(), String> {
+        (self.0)(text)
+    }
+}
+
+/// Shape a `TextInput`'s text keeps while it is typed
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputMask {
+    /// Dotted IPv4 address: four numbers up to 255
+    Ipv4,
+    /// `#` followed by 3, 4, 6 or 8 hex digits
+    HexColor,
+    /// Template where `9` takes a digit, `a` a letter, `h` a hex digit and
+    /// `*` anything; other characters are filled in as the text reaches
+    /// them, e.g. `"99/99/9999"`
+    Pattern(String),
+}
+
+impl InputMask {
+    /// `text` fitted to the mask, or `None` if it can't be
+    pub fn apply(&self, text: &str) -> Option<String> {
+        match self {
+            Self::Ipv4 => {
+                let parts: Vec<&str> = text.split('.').collect();
+                let fits = parts.len() <= 4 && parts.iter().all(|part| {
+                    part.len() <= 3 && part.chars().all(|c| c.is_ascii_digit())
+                        && part.parse::<u32>().map_or(true, |n| n <= 255)
+                });
+                fits.then(|| text.to_string())
+            }
+            Self::HexColor => {
+                let digits = text.strip_prefix('#').unwrap_or(text);
+                if text.is_empty() {
+                    Some(String::new())
+                } else if digits.len() <= 8 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                    Some(format!("#{}", digits))
+                } else {
+                    None
+                }
+            }
+            Self::Pattern(template) => {
+                let mut slots = template.chars().peekable();
+                let mut out = String::new();
+                for c in text.chars() {
+                    loop {
+                        let &slot = slots.peek()?;
+                        if is_mask_slot(slot) {
+                            if mask_slot_fits(slot, c) {
+                                out.push(c);
+                                slots.next();
+                            } else if !template.chars().any(|t| t == c && !is_mask_slot(t)) {
+                                return None;
+                            }
+                            // A separator out of place (left after a
+                            // deletion) is dropped and filled in again
+                            break;
+                        }
+                        slots.next();
+                        out.push(slot);
+                        if c == slot {
+                            break;
+                        }
+                    }
+                }
+                Some(out)
+            }
+        }
+    }
+    
+    /// Whether `text` fills the mask completely
+    pub fn is_complete(&self, text: &str) -> bool {
+        if self.apply(text).as_deref() != Some(text) {
+            return false;
+        }
+        match self {
+            Self::Ipv4 => text.split('.').count() == 4 && text.split('.').all(|part| !part.is_empty()),
+            Self::HexColor => matches!(text.len(), 4 | 5 | 7 | 9),
+            Self::Pattern(template) => text.chars().count() == template.chars().count(),
+        }
+    }
+    
+    /// Error shown while the text is unfinished
+    pub fn hint(&self) -> String {
+        match self {
+            Self::Ipv4 => "Enter an address like 192.168.0.1".to_string(),
+            Self::HexColor => "Enter a color like #ff8800".to_string(),
+            Self::Pattern(template) => format!("Enter {}", template),
+        }
+    }
+}
+
+/// Characters of an `InputMask::Pattern` that take typed input
+fn is_mask_slot(c: char) -> bool {
+    matches!(c, '9' | 'a' | 'h' | '*')
+}
+
+fn mask_slot_fits(slot: char, c: char) -> bool {
+    match slot {
+        '9' => c.is_ascii_digit(),
+        'a' => c.is_alphabetic(),
+        'h' => c.is_ascii_hexdigit(),
+        _ => true,
+    }
+}
+
+// =============================================================================
+// DROPDOWN
+// =============================================================================
+
+/// Seconds after the last typed character before type-ahead starts over
+const TYPEAHEAD_RESET: f32 = 1.0;
+
+type IndicesCallback = Box<dyn FnMut(&[usize])>;
+
+/// Dropdown select box
+///
+/// A press on the header focuses it and opens or closes the list; a press
+/// elsewhere drops focus. While focused, Up/Down/Enter/Space open the list, the arrows and
+/// Home/End move through it, Enter or Space picks the highlighted option
+/// and Escape closes it. Typing jumps to the first option starting with
+/// what was typed; repeating one letter cycles through its options.
+///
+/// In multi-select mode (see `with_multi_select`) options have checkboxes,
+/// picking one toggles it and leaves the list open, and the header sums up
+/// the selection.
+pub struct Dropdown {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
+    pub options: Vec<String>,
+    pub selected_index: usize,
+    pub open: bool,
+    /// Option under the mouse, or moved to with the keys
+    pub hovered_index: Option<usize>,
+    pub corner_radius: f32,
+    /// Called with the index of the option picked from the list
+    pub on_select: Option<Box<dyn FnMut(usize)>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
+    pub focused: bool,
+    /// Identifies the dropdown to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
+    /// Options toggle on and off instead of replacing each other
+    pub multi_select: bool,
+    /// Checked options in multi-select mode, in list order
+    pub selected_indices: Vec<usize>,
+    /// Header text while nothing is checked
+    pub placeholder: String,
+    /// Called with every checked index after one is toggled
+    pub on_multi_select: Option<IndicesCallback>,
+    /// Characters typed so far, and the time since the last one
+    typeahead: String,
+    typeahead_timer: f32,
+}
+
+impl Dropdown {
+    pub fn new(options: Vec<String>) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            layout_intrinsic: None,
+            options,
+            selected_index: 0,
+            open: false,
+            hovered_index: None,
+            corner_radius: 6.0,
+            on_select: None,
+            disabled: false,
+            focused: false,
+            focus_id: FocusId::new(),
+            multi_select: false,
+            selected_indices: Vec::new(),
+            placeholder: "None selected".to_string(),
+            on_multi_select: None,
+            typeahead: String::new(),
+            typeahead_timer: 0.0,
+        }
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; closes the list and drops focus
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.open = false;
+            self.hovered_index = None;
+            self.focused = false;
+        }
+    }
+    
+    pub fn on_select(mut self, callback: impl FnMut(usize) + 'static) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
     }
     
     pub fn with_selected(mut self, index: usize) -> Self {
@@ -238,16 +1048,311 @@ impl Dropdown {
     pub fn selected(&self) -> Option<&str> {
         self.options.get(self.selected_index).map(|s| s.as_str())
     }
+    
+    /// Let several options be checked at once
+    pub fn with_multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+    
+    /// Check these options (multi-select mode)
+    pub fn with_selected_indices(mut self, indices: Vec<usize>) -> Self {
+        self.selected_indices = indices.into_iter().filter(|&i| i < self.options.len()).collect();
+        self.selected_indices.sort_unstable();
+        self.selected_indices.dedup();
+        self
+    }
+    
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+    
+    pub fn on_multi_select(mut self, callback: impl FnMut(&[usize]) + 'static) -> Self {
+        self.on_multi_select = Some(Box::new(callback));
+        self
+    }
+    
+    /// Checked options (multi-select mode)
+    pub fn selected_options(&self) -> Vec<&str> {
+        self.selected_indices.iter().filter_map(|&i| self.options.get(i).map(|s| s.as_str())).collect()
+    }
+    
+    /// Pick option `index`: select it and close the list, or toggle it in
+    /// multi-select mode
+    fn choose(&mut self, index: usize) {
+        if index >= self.options.len() {
+            return;
+        }
+        if self.multi_select {
+            match self.selected_indices.binary_search(&index) {
+                Ok(at) => { self.selected_indices.remove(at); }
+                Err(at) => self.selected_indices.insert(at, index),
+            }
+            if let Some(callback) = &mut self.on_multi_select {
+                callback(&self.selected_indices);
+            }
+            return;
+        }
+        self.selected_index = index;
+        self.open = false;
+        if let Some(callback) = &mut self.on_select {
+            callback(index);
+        }
+    }
+    
+    /// Open the list with the current option highlighted
+    fn open_list(&mut self) {
+        self.open = !self.options.is_empty();
+        self.hovered_index = if self.multi_select {
+            self.selected_indices.first().copied().or(Some(0))
+        } else {
+            Some(self.selected_index)
+        }.filter(|_| self.open);
+    }
+    
+    /// Move the highlight `delta` options, stopping at the ends
+    fn move_highlight(&mut self, delta: isize) {
+        let last = self.options.len().saturating_sub(1) as isize;
+        let current = self.hovered_index.map_or(-1, |i| i as isize);
+        let next = if current < 0 && delta < 0 { last } else { (current + delta).clamp(0, last) };
+        self.hovered_index = Some(next as usize);
+    }
+    
+    /// Navigation keys; returns whether one was used
+    fn handle_key(&mut self, key: &winit::keyboard::Key) -> bool {
+        use winit::keyboard::{Key, NamedKey};
+        if !self.open {
+            return match key {
+                Key::Named(NamedKey::ArrowDown | NamedKey::ArrowUp | NamedKey::Enter) => {
+                    self.open_list();
+                    true
+                }
+                Key::Named(NamedKey::Space) if self.typeahead.is_empty() => {
+                    self.open_list();
+                    true
+                }
+                _ => false,
+            };
+        }
+        match key {
+            Key::Named(NamedKey::ArrowDown) => self.move_highlight(1),
+            Key::Named(NamedKey::ArrowUp) => self.move_highlight(-1),
+            Key::Named(NamedKey::Home) => self.hovered_index = Some(0),
+            Key::Named(NamedKey::End) => self.move_highlight(isize::MAX / 2),
+            Key::Named(NamedKey::Enter) => match self.hovered_index {
+                Some(index) => self.choose(index),
+                None => self.open = false,
+            },
+            Key::Named(NamedKey::Space) if self.typeahead.is_empty() => {
+                if let Some(index) = self.hovered_index {
+                    self.choose(index);
+                }
+            }
+            Key::Named(NamedKey::Escape | NamedKey::Tab) => {
+                self.open = false;
+                return matches!(key, Key::Named(NamedKey::Escape));
+            }
+            _ => return false,
+        }
+        true
+    }
+    
+    /// Jump to the next option starting with the characters typed so far
+    fn type_ahead(&mut self, text: &str) {
+        self.typeahead.push_str(&text.to_lowercase());
+        self.typeahead_timer = 0.0;
+        let count = self.options.len();
+        if count == 0 {
+            return;
+        }
+        
+        // The same letter again moves on to the next option starting with it
+        let first = self.typeahead.chars().next().unwrap_or_default();
+        let repeating = self.typeahead.chars().all(|c| c == first);
+        let prefix = if repeating { &self.typeahead[..first.len_utf8()] } else { self.typeahead.as_str() };
+        let current = if self.open { self.hovered_index.unwrap_or(0) } else { self.selected_index };
+        let start = if repeating { current + 1 } else { current };
+        let found = (0..count)
+            .map(|i| (start + i) % count)
+            .find(|&i| self.options[i].to_lowercase().starts_with(prefix));
+        
+        if let Some(index) = found {
+            if self.open || self.multi_select {
+                self.open = true;
+                self.hovered_index = Some(index);
+            } else if index != self.selected_index {
+                self.choose(index);
+            }
+        }
+    }
+    
+    /// Option list below the header, drawn in the overlay layer
+    fn render_list(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        let item_height = 32.0;
+        let list_y = self.position.y + self.size.y + 2.0;
+        let list_height = self.options.len() as f32 * item_height;
+        
+        // Shadow and background
+        renderer.draw_shadow(
+            Vec2::new(self.position.x, list_y + 4.0),
+            Vec2::new(self.size.x, list_height),
+            self.corner_radius,
+            12.0,
+            Vec4::new(0.0, 0.0, 0.0, 0.4)
+        );
+        renderer.draw_rounded_rect(
+            Vec2::new(self.position.x, list_y),
+            Vec2::new(self.size.x, list_height),
+            Vec4::new(0.08, 0.08, 0.1, 0.98),
+            self.corner_radius
+        );
+        
+        // Items
+        for (i, option) in self.options.iter().enumerate() {
+            let item_y = list_y + i as f32 * item_height;
+            
+            // Hover highlight
+            if self.hovered_index == Some(i) {
+                renderer.draw_rounded_rect(
+                    Vec2::new(self.position.x + 2.0, item_y + 2.0),
+                    Vec2::new(self.size.x - 4.0, item_height - 4.0),
+                    Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.3),
+                    4.0
+                );
+            }
+            
+            // Checkbox in multi-select mode
+            let mut text_x = self.position.x + 10.0;
+            let checked = if self.multi_select {
+                let checked = self.selected_indices.binary_search(&i).is_ok();
+                let box_pos = Vec2::new(text_x, item_y + 8.0);
+                let box_color = if checked { theme.primary } else { Vec4::new(1.0, 1.0, 1.0, 0.15) };
+                renderer.draw_rounded_rect(box_pos, Vec2::splat(16.0), box_color, 3.0);
+                if checked {
+                    renderer.draw_text("✓", box_pos + Vec2::new(3.0, 0.0), 14.0, Vec4::new(1.0, 1.0, 1.0, 1.0));
+                }
+                text_x += 26.0;
+                checked
+            } else {
+                i == self.selected_index
+            };
+            
+            // Text
+            let color = if checked && !self.multi_select { theme.primary } else { theme.text };
+            renderer.draw_text(option, Vec2::new(text_x, item_y + 6.0), 16.0, color);
+        }
+    }
+    
+    /// Header text: the selected option, or a summary of the checked ones
+    fn header_label(&self) -> String {
+        if !self.multi_select {
+            return self.selected().unwrap_or_default().to_string();
+        }
+        let checked = self.selected_options();
+        match checked.len() {
+            0 => self.placeholder.clone(),
+            n if n == self.options.len() && n > 1 => format!("All ({})", n),
+            n => {
+                let joined = checked.join(", ");
+                // Room left of the arrow
+                if n == 1 || crate::text::approximate_text_width(&joined, 16.0) <= self.size.x - 44.0 {
+                    joined
+                } else {
+                    format!("{} selected", n)
+                }
+            }
+        }
+    }
+    
+    /// Header size; the option list opens below it
+    fn default_size(&self) -> Size {
+        Size::new(180.0, 36.0)
+    }
+}
+
+impl Focusable for Dropdown {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn can_focus(&self) -> bool {
+        !self.disabled
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+        self.open = false;
+        self.typeahead.clear();
+    }
 }
 
 impl Widget for Dropdown {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(180.0, 36.0);
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        if self.disabled {
+            return false;
+        }
+        if let Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. } = event {
+            if !self.focused || !key_event.state.is_pressed() {
+                return false;
+            }
+            if self.handle_key(&key_event.logical_key) {
+                return true;
+            }
+            return match &key_event.text {
+                Some(text) if !text.chars().any(|c| c.is_control()) => {
+                    self.type_ahead(text);
+                    true
+                }
+                _ => false,
+            };
+        }
+        
         let in_header = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
                         mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
         
@@ -259,27 +1364,36 @@ impl Widget for Dropdown {
             mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
             mouse_pos.y >= dropdown_y && mouse_pos.y <= dropdown_y + dropdown_height;
         
-        // Update hovered
-        if in_dropdown {
-            let relative_y = mouse_pos.y - dropdown_y;
-            let index = (relative_y / item_height) as usize;
-            if index < self.options.len() {
-                self.hovered_index = Some(index);
+        // Update hovered (only the mouse moves it; keys keep their highlight)
+        let mouse_event = matches!(event, Event::WindowEvent { event: WindowEvent::CursorMoved { .. } | WindowEvent::MouseInput { .. }, .. });
+        if mouse_event {
+            if in_dropdown {
+                let relative_y = mouse_pos.y - dropdown_y;
+                let index = (relative_y / item_height) as usize;
+                if index < self.options.len() {
+                    self.hovered_index = Some(index);
+                }
+            } else if !self.focused || !self.open {
+                self.hovered_index = None;
             }
-        } else {
-            self.hovered_index = None;
         }
         
-        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
+        if let Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
             if in_header {
-                self.open = !self.open;
+                self.focused = true;
+                if self.open {
+                    self.open = false;
+                } else {
+                    self.open_list();
+                }
                 return true;
             } else if in_dropdown {
                 if let Some(index) = self.hovered_index {
-                    self.selected_index = index;
-                    self.open = false;
+                    self.choose(index);
                     return true;
                 }
+            } else if self.focused {
+                self.on_blur();
             } else {
                 self.open = false;
             }
@@ -287,57 +1401,50 @@ impl Widget for Dropdown {
         
         false
     }
+    
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        let list_height = self.options.len() as f32 * 32.0;
+        self.open && Rect::new(self.position + Vec2::new(0.0, self.size.y), Vec2::new(self.size.x, list_height)).contains(point)
+    }
 
-    fn update(&mut self, _dt: f32) {}
+    fn update(&mut self, dt: f32) {
+        if !self.typeahead.is_empty() {
+            self.typeahead_timer += dt;
+            if self.typeahead_timer > TYPEAHEAD_RESET {
+                self.typeahead.clear();
+            }
+        }
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        
-        // Header (normal rendering)
-        renderer.draw_rounded_rect(self.position, self.size, Vec4::new(0.1, 0.1, 0.12, 0.9), self.corner_radius);
-        
-        // Selected text
-        if let Some(text) = self.options.get(self.selected_index) {
-            renderer.draw_text(text, self.position + Vec2::new(10.0, 8.0), 16.0, theme.text);
-        }
-        
-        // Arrow
-        let arrow = if self.open { "▲" } else { "▼" };
-        renderer.draw_text(arrow, Vec2::new(self.position.x + self.size.x - 24.0, self.position.y + 10.0), 14.0, theme.text_secondary);
-        
-        // Dropdown list - USE OVERLAY RENDERING (on top of everything)
-        if self.open {
-            let item_height = 32.0;
-            let list_y = self.position.y + self.size.y + 2.0;
-            let list_height = self.options.len() as f32 * item_height;
+        render_faded(renderer, self.disabled, None, |renderer| {
+            // Header (normal rendering)
+            renderer.draw_rounded_rect(self.position, self.size, Vec4::new(0.1, 0.1, 0.12, 0.9), self.corner_radius);
+            if self.focused {
+                renderer.draw_rect_stroke(
+                    self.position - Vec2::splat(1.0),
+                    self.size + Vec2::splat(2.0),
+                    Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.6),
+                    1.5,
+                    self.corner_radius + 1.0
+                );
+            }
             
-            // Background - use overlay (renders on top)
-            renderer.draw_overlay_rect(
-                Vec2::new(self.position.x, list_y),
-                Vec2::new(self.size.x, list_height),
-                Vec4::new(0.08, 0.08, 0.1, 0.98),
-                self.corner_radius
-            );
+            // Selected text, or the summary of checked options
+            let label = self.header_label();
+            let nothing_checked = self.multi_select && self.selected_indices.is_empty();
+            renderer.draw_text(&label, self.position + Vec2::new(10.0, 8.0), 16.0, if nothing_checked { theme.text_secondary } else { theme.text });
             
-            // Items
-            for (i, option) in self.options.iter().enumerate() {
-                let item_y = list_y + i as f32 * item_height;
-                
-                // Hover highlight
-                if self.hovered_index == Some(i) {
-                    renderer.draw_overlay_rect(
-                        Vec2::new(self.position.x + 2.0, item_y + 2.0),
-                        Vec2::new(self.size.x - 4.0, item_height - 4.0),
-                        Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.3),
-                        4.0
-                    );
-                }
-                
-                // Text - use overlay
-                let color = if i == self.selected_index { theme.primary } else { theme.text };
-                renderer.draw_overlay_text(option, Vec2::new(self.position.x + 10.0, item_y + 6.0), 16.0, color);
+            // Arrow
+            let arrow = if self.open { "▲" } else { "▼" };
+            renderer.draw_text(arrow, Vec2::new(self.position.x + self.size.x - 24.0, self.position.y + 10.0), 14.0, theme.text_secondary);
+            
+            // Option list, over later siblings and outside any scroll area
+            if self.open {
+                renderer.draw_in_overlay_layer(|renderer| self.render_list(renderer));
             }
-        }
+        });
     }
 }
 
@@ -346,7 +1453,7 @@ impl Widget for Dropdown {
 // =============================================================================
 
 /// Simple date representation (no external chrono dependency)
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SimpleDate {
     pub year: i32,
     pub month: u32,  // 1-12
@@ -365,9 +1472,12 @@ impl SimpleDate {
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
-        
+        Self::from_unix_days(secs / 86400)
+    }
+    
+    /// Date `days_since_epoch` days after 1970-01-01 (UTC)
+    pub fn from_unix_days(days_since_epoch: u64) -> Self {
         // Rough calculation (not accounting for leap seconds, etc.)
-        let days_since_epoch = secs / 86400;
         let mut year = 1970;
         let mut remaining_days = days_since_epoch as i32;
         
@@ -423,6 +1533,29 @@ impl SimpleDate {
         ((h + 6) % 7) as u32 // Convert to 0=Sunday
     }
     
+    /// Days since 1970-01-01 (negative before it)
+    pub fn to_unix_days(&self) -> i64 {
+        // Days-from-civil over 400-year eras, with years starting in March
+        let (month, day) = (self.month.clamp(1, 12) as i64, self.day as i64);
+        let year = self.year as i64 - if month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+    
+    /// Date `days` days later (earlier if negative), not before 1970-01-01
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_unix_days((self.to_unix_days() + days).max(0) as u64)
+    }
+    
+    /// First day of the month `months` months later (earlier if negative)
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        Self::new(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+    }
+    
     pub fn format(&self) -> String {
         format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
     }
@@ -435,117 +1568,344 @@ impl Default for SimpleDate {
 }
 
 /// Date picker with calendar popup
+///
+/// In range mode (see `range_mode`) the popup shows two months side by
+/// side: the first click anchors the range, hovering previews it and the
+/// second click completes it.
 pub struct DatePicker {
     pub position: Vec2,
     pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
     pub value: SimpleDate,
     pub display_month: SimpleDate, // Month being displayed in calendar
     pub open: bool,
-    pub hovered_day: Option<u32>,
+    pub hovered_date: Option<SimpleDate>,
     pub corner_radius: f32,
+    /// Pick a start and end date instead of a single day
+    pub range_mode: bool,
+    /// Selected range (start <= end) in range mode
+    pub range: Option<(SimpleDate, SimpleDate)>,
+    /// First date clicked while a range is being picked
+    range_anchor: Option<SimpleDate>,
+    /// Called with the date picked from the calendar
+    pub on_change: Option<Box<dyn FnMut(SimpleDate)>>,
+    /// Called with the start and end date once a range is picked
+    pub on_range_change: Option<Box<dyn FnMut(SimpleDate, SimpleDate)>>,
 }
 
+/// Width of one month in the calendar popup
+const CALENDAR_MONTH_WIDTH: f32 = 240.0;
+
 impl DatePicker {
     pub fn new() -> Self {
         let today = SimpleDate::today();
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            layout_intrinsic: None,
             value: today,
             display_month: today,
             open: false,
-            hovered_day: None,
+            hovered_date: None,
             corner_radius: 6.0,
+            range_mode: false,
+            range: None,
+            range_anchor: None,
+            on_change: None,
+            on_range_change: None,
         }
     }
     
+    pub fn on_change(mut self, callback: impl FnMut(SimpleDate) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn on_range_change(mut self, callback: impl FnMut(SimpleDate, SimpleDate) + 'static) -> Self {
+        self.on_range_change = Some(Box::new(callback));
+        self
+    }
+    
     pub fn with_value(mut self, date: SimpleDate) -> Self {
         self.value = date;
         self.display_month = date;
         self
     }
     
+    /// Switch to picking a start and end date
+    pub fn range_mode(mut self, enabled: bool) -> Self {
+        self.range_mode = enabled;
+        self
+    }
+    
+    /// Preselect a range (the dates may be given in either order)
+    pub fn with_range(mut self, start: SimpleDate, end: SimpleDate) -> Self {
+        let (start, end) = (start.min(end), start.max(end));
+        self.range_mode = true;
+        self.range = Some((start, end));
+        self.value = start;
+        // Keep the end month in view, on the right when they differ
+        self.display_month = if (start.year, start.month) == (end.year, end.month) { end } else { end.add_months(-1) };
+        self
+    }
+    
+    /// Range covering the last `days` days up to and including `end`
+    pub fn with_last_days(self, days: u32, end: SimpleDate) -> Self {
+        self.with_range(end.add_days(1 - days.max(1) as i64), end)
+    }
+    
     fn prev_month(&mut self) {
-        if self.display_month.month == 1 {
-            self.display_month.month = 12;
-            self.display_month.year -= 1;
-        } else {
-            self.display_month.month -= 1;
-        }
+        self.display_month = self.display_month.add_months(-1);
     }
     
     fn next_month(&mut self) {
-        if self.display_month.month == 12 {
-            self.display_month.month = 1;
-            self.display_month.year += 1;
-        } else {
-            self.display_month.month += 1;
-        }
+        self.display_month = self.display_month.add_months(1);
+    }
+    
+    fn month_count(&self) -> u32 {
+        if self.range_mode { 2 } else { 1 }
     }
     
     fn calendar_size(&self) -> Vec2 {
-        Vec2::new(240.0, 220.0)
+        Vec2::new(CALENDAR_MONTH_WIDTH * self.month_count() as f32, 220.0)
     }
-}
-
-impl Default for DatePicker {
-    fn default() -> Self {
-        Self::new()
+    
+    fn calendar_position(&self) -> Vec2 {
+        Vec2::new(self.position.x, self.position.y + self.size.y + 2.0)
     }
-}
-
-impl Widget for DatePicker {
-    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
-        self.position = origin;
-        self.size = Vec2::new(160.0, 36.0);
-        self.size
+    
+    /// Date under `point` in the open calendar
+    fn date_at(&self, point: Vec2) -> Option<SimpleDate> {
+        let cal_pos = self.calendar_position();
+        if !self.open || !Rect::new(cal_pos, self.calendar_size()).contains(point) {
+            return None;
+        }
+        let grid_y = cal_pos.y + 50.0; // Below header and weekday labels
+        if point.y < grid_y {
+            return None;
+        }
+        let panel = ((point.x - cal_pos.x) / CALENDAR_MONTH_WIDTH) as i32;
+        let month = self.display_month.add_months(panel);
+        let cell_w = CALENDAR_MONTH_WIDTH / 7.0;
+        let col = ((point.x - cal_pos.x - panel as f32 * CALENDAR_MONTH_WIDTH) / cell_w) as u32;
+        let row = ((point.y - grid_y) / 24.0) as u32;
+        let day_idx = row * 7 + col;
+        let first_dow = month.first_day_of_month();
+        if day_idx < first_dow {
+            return None;
+        }
+        let day = day_idx - first_dow + 1;
+        (day <= SimpleDate::days_in_month(month.year, month.month))
+            .then(|| SimpleDate::new(month.year, month.month, day))
     }
-
+    
+    /// Range to highlight: the one being picked (previewed up to the
+    /// hovered date) or the selected one
+    fn highlighted_range(&self) -> Option<(SimpleDate, SimpleDate)> {
+        match self.range_anchor {
+            Some(anchor) => {
+                let other = self.hovered_date.unwrap_or(anchor);
+                Some((anchor.min(other), anchor.max(other)))
+            }
+            None => self.range,
+        }
+    }
+    
+    /// Handle a click on a day in the calendar
+    fn pick(&mut self, date: SimpleDate) {
+        if !self.range_mode {
+            self.value = date;
+            self.open = false;
+            if let Some(callback) = &mut self.on_change {
+                callback(self.value);
+            }
+            return;
+        }
+        
+        match self.range_anchor.take() {
+            None => self.range_anchor = Some(date),
+            Some(anchor) => {
+                let (start, end) = (anchor.min(date), anchor.max(date));
+                self.range = Some((start, end));
+                self.value = start;
+                self.open = false;
+                if let Some(callback) = &mut self.on_range_change {
+                    callback(start, end);
+                }
+            }
+        }
+    }
+    
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+        if !open {
+            self.range_anchor = None;
+        }
+    }
+    
+    fn header_text(&self) -> String {
+        if !self.range_mode {
+            return self.value.format();
+        }
+        match (self.range_anchor, self.range) {
+            (Some(anchor), _) => format!("{} – …", anchor.format()),
+            (None, Some((start, end))) => format!("{} – {}", start.format(), end.format()),
+            (None, None) => "Select dates".to_string(),
+        }
+    }
+    
+    fn default_size(&self) -> Size {
+        Size::new(if self.range_mode { 240.0 } else { 160.0 }, 36.0)
+    }
+    
+    /// Calendar below the header, drawn in the overlay layer
+    fn render_calendar(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        let cal_pos = self.calendar_position();
+        let cal_size = self.calendar_size();
+        
+        // Background
+        renderer.draw_rounded_rect(cal_pos, cal_size, Vec4::new(0.08, 0.08, 0.1, 0.98), 8.0);
+        
+        // Border
+        renderer.draw_rounded_rect(
+            cal_pos - Vec2::splat(1.0),
+            cal_size + Vec2::splat(2.0),
+            Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.4),
+            9.0
+        );
+        
+        // Nav arrows
+        renderer.draw_text("◀", cal_pos + Vec2::new(10.0, 8.0), 14.0, theme.text_secondary);
+        renderer.draw_text("▶", Vec2::new(cal_pos.x + cal_size.x - 22.0, cal_pos.y + 8.0), 14.0, theme.text_secondary);
+        
+        for panel in 0..self.month_count() {
+            let origin = cal_pos + Vec2::new(panel as f32 * CALENDAR_MONTH_WIDTH, 0.0);
+            self.render_month(renderer, origin, self.display_month.add_months(panel as i32));
+        }
+    }
+    
+    fn render_month(&self, renderer: &mut GlassRenderer, origin: Vec2, month: SimpleDate) {
+        let theme = get_theme();
+        let range = self.highlighted_range();
+        
+        // Month/Year title
+        let month_names = ["", "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+        let month_str = format!("{} {}", month_names[month.month as usize], month.year);
+        let title_x = origin.x + (CALENDAR_MONTH_WIDTH - month_str.len() as f32 * 8.0) / 2.0;
+        renderer.draw_text(&month_str, Vec2::new(title_x, origin.y + 8.0), 16.0, theme.text);
+        
+        // Weekday headers
+        let weekdays = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+        let cell_w = CALENDAR_MONTH_WIDTH / 7.0;
+        for (i, wd) in weekdays.iter().enumerate() {
+            let x = origin.x + i as f32 * cell_w + cell_w / 2.0 - 8.0;
+            renderer.draw_text(wd, Vec2::new(x, origin.y + 32.0), 12.0, theme.text_secondary);
+        }
+        
+        // Day grid
+        let first_dow = month.first_day_of_month();
+        let days_in_month = SimpleDate::days_in_month(month.year, month.month);
+        let cell_h = 24.0;
+        let grid_y = origin.y + 50.0;
+        
+        for day in 1..=days_in_month {
+            let date = SimpleDate::new(month.year, month.month, day);
+            let cell_idx = first_dow + day - 1;
+            let col = cell_idx % 7;
+            let row = cell_idx / 7;
+            
+            let x = origin.x + col as f32 * cell_w;
+            let y = grid_y + row as f32 * cell_h;
+            
+            // Highlight selected day, or the ends of the range
+            let is_selected = match range {
+                Some((start, end)) => date == start || date == end,
+                None => !self.range_mode && self.value == date,
+            };
+            let in_range = range.is_some_and(|(start, end)| start < date && date < end);
+            let is_hovered = self.hovered_date == Some(date);
+            
+            if is_selected {
+                renderer.draw_rounded_rect(
+                    Vec2::new(x + 2.0, y),
+                    Vec2::new(cell_w - 4.0, cell_h - 2.0),
+                    theme.primary,
+                    4.0
+                );
+            } else if in_range || is_hovered {
+                renderer.draw_rounded_rect(
+                    Vec2::new(x + 2.0, y),
+                    Vec2::new(cell_w - 4.0, cell_h - 2.0),
+                    Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, if in_range { 0.2 } else { 0.3 }),
+                    4.0
+                );
+            }
+            
+            let text_color = if is_selected { Vec4::new(1.0, 1.0, 1.0, 1.0) } else { theme.text };
+            let text_x = x + cell_w / 2.0 - if day >= 10 { 8.0 } else { 4.0 };
+            renderer.draw_text_fmt(format_args!("{}", day), Vec2::new(text_x, y + 4.0), 14.0, text_color);
+        }
+    }
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for DatePicker {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Header click
         let in_header = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
                         mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
         
-        let cal_pos = Vec2::new(self.position.x, self.position.y + self.size.y + 2.0);
+        let cal_pos = self.calendar_position();
         let cal_size = self.calendar_size();
         
         let in_calendar = self.open &&
             mouse_pos.x >= cal_pos.x && mouse_pos.x <= cal_pos.x + cal_size.x &&
             mouse_pos.y >= cal_pos.y && mouse_pos.y <= cal_pos.y + cal_size.y;
         
-        // Detect hovered day
-        if in_calendar {
-            let grid_y = cal_pos.y + 50.0; // Below header and weekday labels
-            let cell_w = cal_size.x / 7.0;
-            let cell_h = 24.0;
-            
-            if mouse_pos.y >= grid_y {
-                let col = ((mouse_pos.x - cal_pos.x) / cell_w) as u32;
-                let row = ((mouse_pos.y - grid_y) / cell_h) as u32;
-                let first_dow = self.display_month.first_day_of_month();
-                let day_idx = row * 7 + col;
-                
-                if day_idx >= first_dow {
-                    let day = day_idx - first_dow + 1;
-                    let max_days = SimpleDate::days_in_month(self.display_month.year, self.display_month.month);
-                    if day >= 1 && day <= max_days {
-                        self.hovered_day = Some(day);
-                    } else {
-                        self.hovered_day = None;
-                    }
-                } else {
-                    self.hovered_day = None;
-                }
-            } else {
-                self.hovered_day = None;
-            }
-        } else {
-            self.hovered_day = None;
-        }
+        // Detect hovered day (this also previews a range being picked)
+        self.hovered_date = self.date_at(mouse_pos);
         
         if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
             if in_header {
-                self.open = !self.open;
+                self.set_open(!self.open);
                 return true;
             } else if in_calendar {
                 // Check nav buttons (top of calendar)
@@ -564,109 +1924,789 @@ impl Widget for DatePicker {
                 }
                 
                 // Select day
-                if let Some(day) = self.hovered_day {
-                    self.value = SimpleDate::new(self.display_month.year, self.display_month.month, day);
-                    self.open = false;
+                if let Some(date) = self.hovered_date {
+                    self.pick(date);
                     return true;
                 }
             } else {
-                self.open = false;
+                self.set_open(false);
             }
         }
         
         false
     }
+    
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        self.open && Rect::new(self.calendar_position(), self.calendar_size()).contains(point)
+    }
 
     fn update(&mut self, _dt: f32) {}
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
         
-        // Header (shows selected date)
+        // Header (shows selected date or range)
         renderer.draw_rounded_rect(self.position, self.size, Vec4::new(0.1, 0.1, 0.12, 0.9), self.corner_radius);
-        renderer.draw_text(&self.value.format(), self.position + Vec2::new(10.0, 8.0), 16.0, theme.text);
+        let header_color = if self.range_mode && self.range.is_none() && self.range_anchor.is_none() { theme.text_secondary } else { theme.text };
+        renderer.draw_text(&self.header_text(), self.position + Vec2::new(10.0, 8.0), 16.0, header_color);
         
         // Calendar icon
         renderer.draw_text("📅", Vec2::new(self.position.x + self.size.x - 28.0, self.position.y + 8.0), 16.0, theme.text_secondary);
         
-        // Calendar popup
+        // Calendar popup, over later siblings and outside any scroll area
         if self.open {
-            let cal_pos = Vec2::new(self.position.x, self.position.y + self.size.y + 2.0);
-            let cal_size = self.calendar_size();
-            
-            // Background
-            renderer.draw_overlay_rect(cal_pos, cal_size, Vec4::new(0.08, 0.08, 0.1, 0.98), 8.0);
-            
-            // Border
-            renderer.draw_overlay_rect(
-                cal_pos - Vec2::splat(1.0),
-                cal_size + Vec2::splat(2.0),
-                Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.4),
-                9.0
-            );
-            
-            // Month/Year header with nav
-            let month_names = ["", "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
-            let month_str = format!("{} {}", month_names[self.display_month.month as usize], self.display_month.year);
-            
-            // Nav arrows
-            renderer.draw_overlay_text("◀", cal_pos + Vec2::new(10.0, 8.0), 14.0, theme.text_secondary);
-            renderer.draw_overlay_text("▶", Vec2::new(cal_pos.x + cal_size.x - 22.0, cal_pos.y + 8.0), 14.0, theme.text_secondary);
-            
-            // Month title
-            let title_x = cal_pos.x + (cal_size.x - month_str.len() as f32 * 8.0) / 2.0;
-            renderer.draw_overlay_text(&month_str, Vec2::new(title_x, cal_pos.y + 8.0), 16.0, theme.text);
-            
-            // Weekday headers
-            let weekdays = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
-            let cell_w = cal_size.x / 7.0;
-            for (i, wd) in weekdays.iter().enumerate() {
-                let x = cal_pos.x + i as f32 * cell_w + cell_w / 2.0 - 8.0;
-                renderer.draw_overlay_text(wd, Vec2::new(x, cal_pos.y + 32.0), 12.0, theme.text_secondary);
-            }
-            
-            // Day grid
-            let first_dow = self.display_month.first_day_of_month();
-            let days_in_month = SimpleDate::days_in_month(self.display_month.year, self.display_month.month);
-            let cell_h = 24.0;
-            let grid_y = cal_pos.y + 50.0;
+            renderer.draw_in_overlay_layer(|renderer| self.render_calendar(renderer));
+        }
+    }
+}
+
+// =============================================================================
+// TIME PICKER
+// =============================================================================
+
+/// Simple time of day (24-hour clock, minute precision)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SimpleTime {
+    pub hour: u32,   // 0-23
+    pub minute: u32, // 0-59
+}
+
+impl SimpleTime {
+    pub fn new(hour: u32, minute: u32) -> Self {
+        Self { hour: hour.min(23), minute: minute.min(59) }
+    }
+    
+    /// Get the current time of day (UTC, like `SimpleDate::today`)
+    pub fn now() -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::from_minutes(((secs % 86400) / 60) as i64)
+    }
+    
+    /// Time `minutes` after midnight, wrapping around the day
+    pub fn from_minutes(minutes: i64) -> Self {
+        let minutes = minutes.rem_euclid(24 * 60) as u32;
+        Self { hour: minutes / 60, minute: minutes % 60 }
+    }
+    
+    /// Minutes since midnight
+    pub fn total_minutes(&self) -> i64 {
+        (self.hour * 60 + self.minute) as i64
+    }
+    
+    pub fn format(&self) -> String {
+        format!("{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+impl Default for SimpleTime {
+    fn default() -> Self {
+        Self::now()
+    }
+}
+
+/// Which spinner column of the time popup
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TimeColumn {
+    Hour,
+    Minute,
+}
+
+/// Time picker with an hour/minute spinner popup
+///
+/// Click the ▲/▼ arrows or scroll over a column to step it; hours and
+/// minutes wrap around independently.
+pub struct TimePicker {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
+    pub value: SimpleTime,
+    pub open: bool,
+    /// Minutes added or removed per minute-spinner step
+    pub minute_step: u32,
+    pub corner_radius: f32,
+    hovered: Option<(TimeColumn, bool)>,
+    /// Called with the time whenever a spinner changes it
+    pub on_change: Option<Box<dyn FnMut(SimpleTime)>>,
+}
+
+impl TimePicker {
+    pub fn new() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            layout_intrinsic: None,
+            value: SimpleTime::now(),
+            open: false,
+            minute_step: 1,
+            corner_radius: 6.0,
+            hovered: None,
+            on_change: None,
+        }
+    }
+    
+    pub fn on_change(mut self, callback: impl FnMut(SimpleTime) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn with_value(mut self, time: SimpleTime) -> Self {
+        self.value = time;
+        self
+    }
+    
+    /// Step minutes in increments of `step` (e.g. 5 or 15)
+    pub fn with_minute_step(mut self, step: u32) -> Self {
+        self.minute_step = step.clamp(1, 30);
+        self
+    }
+    
+    /// Move the hour by `delta`, wrapping around midnight
+    pub fn step_hour(&mut self, delta: i32) {
+        let hour = (self.value.hour as i32 + delta).rem_euclid(24) as u32;
+        self.set_time(SimpleTime::new(hour, self.value.minute));
+    }
+    
+    /// Move the minute by `delta` steps, wrapping within the hour
+    pub fn step_minute(&mut self, delta: i32) {
+        let step = self.minute_step as i32;
+        // Snap onto the step grid first so 07 + 15 lands on 15, not 22
+        let snapped = self.value.minute as i32 / step * step;
+        let base = if delta < 0 && snapped != self.value.minute as i32 { snapped + step } else { snapped };
+        let minute = (base + delta * step).rem_euclid(60) as u32;
+        self.set_time(SimpleTime::new(self.value.hour, minute));
+    }
+    
+    fn set_time(&mut self, time: SimpleTime) {
+        if time == self.value {
+            return;
+        }
+        self.value = time;
+        if let Some(callback) = &mut self.on_change {
+            callback(self.value);
+        }
+    }
+    
+    fn popup_rect(&self) -> Rect {
+        Rect::new(
+            Vec2::new(self.position.x, self.position.y + self.size.y + 2.0),
+            Vec2::new(self.size.x.max(120.0), 112.0),
+        )
+    }
+    
+    /// Hour and minute spinners, drawn in the overlay layer
+    fn render_popup(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        let popup = self.popup_rect();
+        renderer.draw_rounded_rect(popup.min, popup.size(), Vec4::new(0.08, 0.08, 0.1, 0.98), 8.0);
+        renderer.draw_rounded_rect(
+            popup.min - Vec2::splat(1.0),
+            popup.size() + Vec2::splat(2.0),
+            Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.4),
+            9.0
+        );
+        
+        let column_w = popup.size().x / 2.0;
+        let columns = [(TimeColumn::Hour, self.value.hour), (TimeColumn::Minute, self.value.minute)];
+        for (i, (column, value)) in columns.into_iter().enumerate() {
+            let x = popup.min.x + i as f32 * column_w;
+            let center = x + column_w / 2.0;
             
-            for day in 1..=days_in_month {
-                let cell_idx = first_dow + day - 1;
-                let col = cell_idx % 7;
-                let row = cell_idx / 7;
-                
-                let x = cal_pos.x + col as f32 * cell_w;
-                let y = grid_y + row as f32 * cell_h;
-                
-                // Highlight selected day
-                let is_selected = self.value.year == self.display_month.year &&
-                                  self.value.month == self.display_month.month &&
-                                  self.value.day == day;
-                
-                let is_hovered = self.hovered_day == Some(day);
-                
-                if is_selected {
-                    renderer.draw_overlay_rect(
-                        Vec2::new(x + 2.0, y),
-                        Vec2::new(cell_w - 4.0, cell_h - 2.0),
-                        theme.primary,
-                        4.0
-                    );
-                } else if is_hovered {
-                    renderer.draw_overlay_rect(
-                        Vec2::new(x + 2.0, y),
-                        Vec2::new(cell_w - 4.0, cell_h - 2.0),
+            for up in [true, false] {
+                let y = if up { popup.min.y } else { popup.min.y + popup.size().y - 32.0 };
+                if self.hovered == Some((column, up)) {
+                    renderer.draw_rounded_rect(
+                        Vec2::new(x + 4.0, y + 4.0),
+                        Vec2::new(column_w - 8.0, 24.0),
                         Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.3),
                         4.0
                     );
                 }
-                
-                let day_str = format!("{}", day);
-                let text_color = if is_selected { Vec4::new(1.0, 1.0, 1.0, 1.0) } else { theme.text };
-                let text_x = x + cell_w / 2.0 - if day >= 10 { 8.0 } else { 4.0 };
-                renderer.draw_overlay_text(&day_str, Vec2::new(text_x, y + 4.0), 14.0, text_color);
+                renderer.draw_text(if up { "▲" } else { "▼" }, Vec2::new(center - 6.0, y + 8.0), 14.0, theme.text_secondary);
+            }
+            
+            renderer.draw_text_fmt(format_args!("{:02}", value), Vec2::new(center - 12.0, popup.min.y + 42.0), 24.0, theme.text);
+        }
+        renderer.draw_text(":", Vec2::new(popup.min.x + column_w - 3.0, popup.min.y + 42.0), 24.0, theme.text_secondary);
+    }
+    
+    /// Spinner column and arrow (true = up) under `point`; the arrow is
+    /// None over the value itself
+    fn spinner_at(&self, point: Vec2) -> Option<(TimeColumn, Option<bool>)> {
+        let popup = self.popup_rect();
+        if !self.open || !popup.contains(point) {
+            return None;
+        }
+        let column = if point.x < popup.min.x + popup.size().x / 2.0 { TimeColumn::Hour } else { TimeColumn::Minute };
+        let y = point.y - popup.min.y;
+        let arrow = if y < 32.0 {
+            Some(true)
+        } else if y > popup.size().y - 32.0 {
+            Some(false)
+        } else {
+            None
+        };
+        Some((column, arrow))
+    }
+    
+    fn step(&mut self, column: TimeColumn, delta: i32) {
+        match column {
+            TimeColumn::Hour => self.step_hour(delta),
+            TimeColumn::Minute => self.step_minute(delta),
+        }
+    }
+    
+    /// Header size; the spinners open below it
+    fn default_size(&self) -> Size {
+        Size::new(110.0, 36.0)
+    }
+}
+
+impl Default for TimePicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for TimePicker {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let in_header = Rect::new(self.position, self.size).contains(mouse_pos);
+        let spinner = self.spinner_at(mouse_pos);
+        self.hovered = spinner.and_then(|(column, arrow)| arrow.map(|up| (column, up)));
+        
+        match event {
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                if in_header {
+                    self.open = !self.open;
+                    return true;
+                }
+                if let Some((column, arrow)) = spinner {
+                    if let Some(up) = arrow {
+                        self.step(column, if up { 1 } else { -1 });
+                    }
+                    return true;
+                }
+                self.open = false;
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseWheel { delta, .. }, .. } => {
+                if let Some((column, _)) = spinner {
+                    let lines = match delta {
+                        winit::event::MouseScrollDelta::LineDelta(_, y) => *y,
+                        winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
+                    };
+                    if lines != 0.0 {
+                        self.step(column, lines.signum() as i32);
+                    }
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+    
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        self.open && self.popup_rect().contains(point)
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        
+        // Header (shows selected time)
+        renderer.draw_rounded_rect(self.position, self.size, Vec4::new(0.1, 0.1, 0.12, 0.9), self.corner_radius);
+        renderer.draw_text(&self.value.format(), self.position + Vec2::new(10.0, 8.0), 16.0, theme.text);
+        renderer.draw_text("🕒", Vec2::new(self.position.x + self.size.x - 28.0, self.position.y + 8.0), 16.0, theme.text_secondary);
+        
+        if !self.open {
+            return;
+        }
+        
+        // Spinner popup, over later siblings and outside any scroll area
+        renderer.draw_in_overlay_layer(|renderer| self.render_popup(renderer));
+    }
+}
+
+// =============================================================================
+// DATE TIME PICKER
+// =============================================================================
+
+/// Date and time picker: a `DatePicker` and a `TimePicker` side by side
+///
+/// Only one of the two popups is open at a time.
+pub struct DateTimePicker {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub date: DatePicker,
+    pub time: TimePicker,
+    pub spacing: f32,
+    /// Called with the date and time whenever either part changes
+    pub on_change: Option<Box<dyn FnMut(SimpleDate, SimpleTime)>>,
+}
+
+impl DateTimePicker {
+    pub fn new() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            date: DatePicker::new(),
+            time: TimePicker::new(),
+            spacing: 8.0,
+            on_change: None,
+        }
+    }
+    
+    pub fn on_change(mut self, callback: impl FnMut(SimpleDate, SimpleTime) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn with_value(mut self, date: SimpleDate, time: SimpleTime) -> Self {
+        self.date = self.date.with_value(date);
+        self.time = self.time.with_value(time);
+        self
+    }
+    
+    pub fn with_minute_step(mut self, step: u32) -> Self {
+        self.time = self.time.with_minute_step(step);
+        self
+    }
+    
+    /// Selected date and time
+    pub fn value(&self) -> (SimpleDate, SimpleTime) {
+        (self.date.value, self.time.value)
+    }
+    
+    fn place_children(&mut self) {
+        self.date.position = self.position;
+        self.time.position = Vec2::new(self.position.x + self.date.size.x + self.spacing, self.position.y);
+        self.size = Vec2::new(
+            self.date.size.x + self.spacing + self.time.size.x,
+            self.date.size.y.max(self.time.size.y),
+        );
+    }
+}
+
+impl Default for DateTimePicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for DateTimePicker {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        self.date.layout_child(origin, max_size);
+        self.time.layout_child(origin, max_size);
+        self.place_children();
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let child = constraints.loosen();
+        self.date.layout_child_with_constraints(child);
+        self.time.layout_child_with_constraints(child);
+        self.place_children();
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        let size = self.size;
+        self.place_children();
+        self.size = size;
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.date.needs_layout() || self.time.needs_layout()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let before = self.value();
+        let (date_was_open, time_was_open) = (self.date.open, self.time.open);
+        
+        // An open popup gets the event first so a click on it isn't
+        // treated as a click outside by the other picker
+        let mut pickers: [&mut dyn Widget; 2] = [&mut self.date, &mut self.time];
+        if time_was_open {
+            pickers.reverse();
+        }
+        let handled = pickers.into_iter().any(|picker| picker.handle_event(event, mouse_pos));
+        
+        if self.date.open && !date_was_open {
+            self.time.open = false;
+        } else if self.time.open && !time_was_open {
+            self.date.open = false;
+        }
+        
+        let after = self.value();
+        if after != before {
+            if let Some(callback) = &mut self.on_change {
+                callback(after.0, after.1);
             }
         }
+        handled
+    }
+    
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        self.date.hit_test_popup(point) || self.time.hit_test_popup(point)
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.date.render(renderer);
+        self.time.render(renderer);
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_padding_sizes_text_input() {
+        let mut input = TextInput::new("Name");
+        assert_eq!(input.layout(Vec2::ZERO, Vec2::splat(500.0)), Vec2::new(200.0, 36.0));
+        
+        let mut input = TextInput::new("Name").with_style(WidgetStyle::new().padding(EdgeInsets::all(20.0)));
+        assert!(input.needs_layout());
+        assert_eq!(input.layout(Vec2::ZERO, Vec2::splat(500.0)), Vec2::new(220.0, 60.0));
+        assert_eq!(input.text_offset(), Vec2::splat(20.0));
+    }
+
+    #[test]
+    fn test_word_movement_skips_punctuation() {
+        let text = "let x = foo_bar(1);  ünï";
+        assert_eq!(previous_word_start(text, text.len()), 21);
+        assert_eq!(previous_word_start(text, 21), 16);
+        assert_eq!(previous_word_start(text, 11), 8);
+        assert_eq!(previous_word_start(text, 3), 0);
+        assert_eq!(previous_word_start(text, 0), 0);
+        assert_eq!(next_word_end(text, 0), 3);
+        assert_eq!(next_word_end(text, 5), 15);
+        assert_eq!(next_word_end(text, 17), text.len());
+        assert_eq!(next_word_end(text, text.len()), text.len());
+    }
+
+    #[test]
+    fn test_modifiers_switch_word_movement() {
+        use crate::recorder::RecordedEvent;
+        let modifiers = |state: ModifiersState| RecordedEvent::Modifiers { bits: state.bits() }.to_event().unwrap();
+        let mut input = TextInput::new("Name").with_text("hello brave world");
+        input.cursor = 8;
+        assert!(!input.word_modifier());
+        input.handle_event(&modifiers(ModifiersState::CONTROL), Vec2::ZERO);
+        assert!(input.word_modifier());
+        assert_eq!(input.word_move(false), 6);
+        assert_eq!(input.word_move(true), 11);
+        input.handle_event(&modifiers(ModifiersState::empty()), Vec2::ZERO);
+        assert!(!input.word_modifier());
+    }
+
+    #[test]
+    fn test_drag_selects_and_typing_replaces_selection() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        let mouse = |pressed| RecordedEvent::MouseInput { button: RecordedButton::Left, pressed }.to_event().unwrap();
+        let moved = RecordedEvent::CursorMoved { x: 73.0, y: 10.0 }.to_event().unwrap();
+        let mut input = TextInput::new("Name").with_text("hello world");
+        input.size = Vec2::new(200.0, 36.0);
+
+        // Unmeasured text falls back to 9px per character past the 10px inset
+        assert!(input.handle_event(&mouse(true), Vec2::new(28.0, 10.0)));
+        assert!(input.focused);
+        assert_eq!(input.cursor, 2);
+        assert_eq!(input.selection(), None);
+        assert!(input.handle_event(&moved, Vec2::new(73.0, 10.0)));
+        assert!(input.handle_event(&mouse(false), Vec2::new(73.0, 10.0)));
+        assert_eq!(input.selection(), Some(2..7));
+        assert_eq!(input.selected_text(), "llo w");
+
+        // Moving without the button held leaves the selection alone
+        assert!(!input.handle_event(&moved, Vec2::new(100.0, 10.0)));
+        input.insert("y");
+        assert_eq!(input.text, "heyorld");
+        assert_eq!((input.cursor, input.selection()), (3, None));
+
+        input.select_all();
+        assert!(input.delete_selection());
+        assert_eq!((input.text.as_str(), input.cursor), ("", 0));
+        assert!(!input.delete_selection());
+    }
+
+    #[test]
+    fn test_password_masks_and_reveals() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        let press = RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }.to_event().unwrap();
+        let mut input = TextInput::new("API key").with_text("sk-ünï").password(true).with_reveal_toggle(true);
+        input.size = Vec2::new(200.0, 36.0);
+        assert!(input.is_masked());
+        assert_eq!(input.display_text(), "••••••");
+        assert_eq!(input.display_index(5), 12);
+        assert_eq!(input.text_index(12), 5);
+        assert_eq!(input.text_index(18), input.text.len());
+        assert_eq!(input.word_move(false), 0);
+        assert!(input.saved_state().is_none());
+
+        // The button at the right end unmasks without taking focus
+        assert!(input.handle_event(&press, Vec2::new(190.0, 18.0)));
+        assert!(input.revealed && !input.focused);
+        assert_eq!(input.display_text(), "sk-ünï");
+        assert_eq!(input.display_index(5), 5);
+    }
+
+    #[test]
+    fn test_input_masks() {
+        let date = InputMask::Pattern("99/99/9999".to_string());
+        assert_eq!(date.apply("1234").as_deref(), Some("12/34"));
+        assert_eq!(date.apply("12/").as_deref(), Some("12/"));
+        // A deletion before a separator pulls the digits back into place
+        assert_eq!(date.apply("1/34").as_deref(), Some("13/4"));
+        assert_eq!(date.apply("12a"), None);
+        assert_eq!(date.apply("12/34/56789"), None);
+        assert!(date.is_complete("12/34/5678") && !date.is_complete("12/34"));
+
+        assert_eq!(InputMask::Ipv4.apply("192.168.1").as_deref(), Some("192.168.1"));
+        assert_eq!(InputMask::Ipv4.apply("256"), None);
+        assert_eq!(InputMask::Ipv4.apply("1.2.3.4.5"), None);
+        assert!(InputMask::Ipv4.is_complete("10.0.0.1") && !InputMask::Ipv4.is_complete("10.0.0."));
+
+        assert_eq!(InputMask::HexColor.apply("ff8").as_deref(), Some("#ff8"));
+        assert_eq!(InputMask::HexColor.apply("#ggg"), None);
+        assert!(InputMask::HexColor.is_complete("#ff8800") && !InputMask::HexColor.is_complete("#ff88800"));
+    }
+
+    #[test]
+    fn test_validation_reports_first_error() {
+        let mut input = TextInput::new("Host")
+            .with_mask(InputMask::Ipv4)
+            .with_validator(Validator::regex(r"10\..*", "Must be on the 10.x network"));
+        assert!(input.is_valid());
+        assert_eq!(input.error, None);
+
+        // An edit the mask refuses is undone
+        input.set_text("25");
+        input.text.push('6');
+        input.apply_mask(("25".to_string(), 2));
+        assert_eq!((input.text.as_str(), input.cursor), ("25", 2));
+
+        input.set_text("10.0.0");
+        assert!(!input.validate());
+        assert_eq!(input.error.as_deref(), Some("Enter an address like 192.168.0.1"));
+        input.set_text("192.168.0.1");
+        assert!(!input.validate());
+        assert_eq!(input.error.as_deref(), Some("Must be on the 10.x network"));
+        input.set_text("10.0.0.1");
+        assert!(input.validate() && input.is_valid());
+        assert_eq!(input.error, None);
+
+        // Validated fields keep a line below for the message
+        let size = input.layout_with_constraints(BoxConstraints::loose(Size::new(300.0, 300.0)));
+        assert_eq!(size.height, 36.0 + ERROR_LINE);
+    }
+
+    #[test]
+    fn test_time_picker_spinners_and_date_time_picker() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        let click = RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }.to_event().unwrap();
+        let wheel = RecordedEvent::MouseWheel { dx: 0.0, dy: -1.0, lines: true }.to_event().unwrap();
+
+        let mut picker = TimePicker::new().with_value(SimpleTime::new(23, 7)).with_minute_step(15);
+        picker.step_hour(1);
+        assert_eq!(picker.value, SimpleTime::new(0, 7));
+        picker.step_minute(1);
+        assert_eq!(picker.value, SimpleTime::new(0, 15));
+        picker.step_minute(-2);
+        assert_eq!(picker.value, SimpleTime::new(0, 45));
+        assert_eq!(SimpleTime::from_minutes(-1), SimpleTime::new(23, 59));
+
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let log = changes.clone();
+        let mut picker = DateTimePicker::new()
+            .with_value(SimpleDate::new(2024, 2, 28), SimpleTime::new(9, 30))
+            .on_change(move |date, time| log.borrow_mut().push(format!("{} {}", date.format(), time.format())));
+        picker.layout(Vec2::ZERO, Vec2::new(400.0, 400.0));
+        assert_eq!(picker.time.position, Vec2::new(168.0, 0.0));
+
+        // Open the time popup, then step the hour up and scroll the minutes down
+        assert!(picker.handle_event(&click, Vec2::new(200.0, 10.0)));
+        assert!(picker.time.open);
+        assert!(picker.hit_test_popup(Vec2::new(180.0, 60.0)));
+        assert!(picker.handle_event(&click, Vec2::new(190.0, 50.0)));
+        assert!(picker.handle_event(&wheel, Vec2::new(260.0, 90.0)));
+        assert_eq!(picker.value().1, SimpleTime::new(10, 29));
+
+        // Opening the calendar closes the spinners
+        assert!(picker.handle_event(&click, Vec2::new(20.0, 10.0)));
+        assert!(picker.date.open && !picker.time.open);
+        assert_eq!(*changes.borrow(), ["2024-02-28 10:30", "2024-02-28 10:29"]);
+    }
+
+    #[test]
+    fn test_date_picker_range_mode() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        let click = RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }.to_event().unwrap();
+        let moved = RecordedEvent::CursorMoved { x: 0.0, y: 0.0 }.to_event().unwrap();
+
+        let end = SimpleDate::new(2024, 3, 3);
+        assert_eq!(SimpleDate::new(1970, 1, 1).to_unix_days(), 0);
+        assert_eq!(end.add_days(-7), SimpleDate::new(2024, 2, 25));
+        assert_eq!(SimpleDate::from_unix_days(end.to_unix_days() as u64), end);
+        assert_eq!(end.add_months(-3), SimpleDate::new(2023, 12, 1));
+
+        let picked = Rc::new(RefCell::new(Vec::new()));
+        let log = picked.clone();
+        let mut picker = DatePicker::new()
+            .with_last_days(7, end)
+            .on_range_change(move |start, end| log.borrow_mut().push((start, end)));
+        assert_eq!(picker.range, Some((SimpleDate::new(2024, 2, 26), end)));
+        assert_eq!(picker.header_text(), "2024-02-26 – 2024-03-03");
+        assert_eq!(picker.display_month.month, 2);
+
+        picker.layout(Vec2::ZERO, Vec2::new(800.0, 600.0));
+        assert_eq!(picker.size.x, 240.0);
+        assert!(picker.handle_event(&click, Vec2::new(20.0, 10.0)));
+        assert!(picker.hit_test_popup(Vec2::new(470.0, 100.0)));
+
+        // Feb 2024 starts on a Thursday and March on a Friday; grid rows
+        // start 88px down and cells are ~34px wide
+        let cell = |panel: f32, col: f32, row: f32| Vec2::new(panel * 240.0 + col * 34.3 + 17.0, 88.0 + row * 24.0 + 12.0);
+        assert!(picker.handle_event(&click, cell(1.0, 1.0, 1.0)));
+        assert_eq!(picker.header_text(), "2024-03-04 – …");
+        assert!(picker.open);
+
+        // Hovering an earlier day previews the range backwards
+        picker.handle_event(&moved, cell(0.0, 4.0, 4.0));
+        assert_eq!(picker.hovered_date, Some(SimpleDate::new(2024, 2, 29)));
+        assert_eq!(picker.highlighted_range(), Some((SimpleDate::new(2024, 2, 29), SimpleDate::new(2024, 3, 4))));
+
+        assert!(picker.handle_event(&click, cell(0.0, 4.0, 4.0)));
+        assert!(!picker.open);
+        assert_eq!(*picked.borrow(), [(SimpleDate::new(2024, 2, 29), SimpleDate::new(2024, 3, 4))]);
+        assert_eq!(picker.value, SimpleDate::new(2024, 2, 29));
+
+        // Closing halfway keeps the previous range
+        picker.handle_event(&click, Vec2::new(20.0, 10.0));
+        picker.handle_event(&click, cell(1.0, 5.0, 0.0));
+        picker.handle_event(&click, Vec2::new(600.0, 500.0));
+        assert!(!picker.open);
+        assert_eq!(picker.highlighted_range(), Some((SimpleDate::new(2024, 2, 29), SimpleDate::new(2024, 3, 4))));
+    }
+
+    #[test]
+    fn test_dropdown_keyboard_and_multi_select() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        use winit::keyboard::{Key, NamedKey};
+        let picked = std::rc::Rc::new(RefCell::new(Vec::new()));
+        let log = picked.clone();
+        let mut dropdown = Dropdown::new(vec!["Apple".into(), "Banana".into(), "Blueberry".into(), "Cherry".into()])
+            .on_select(move |index| log.borrow_mut().push(index));
+        dropdown.layout(Vec2::ZERO, Vec2::new(300.0, 300.0));
+
+        // Arrows open the list on the current option, Enter picks
+        assert!(dropdown.handle_key(&Key::Named(NamedKey::ArrowDown)));
+        assert!(dropdown.open);
+        assert_eq!(dropdown.hovered_index, Some(0));
+        dropdown.handle_key(&Key::Named(NamedKey::ArrowDown));
+        dropdown.handle_key(&Key::Named(NamedKey::End));
+        assert_eq!(dropdown.hovered_index, Some(3));
+        dropdown.handle_key(&Key::Named(NamedKey::Enter));
+        assert!(!dropdown.open);
+        assert_eq!(dropdown.selected(), Some("Cherry"));
+
+        // Type-ahead selects while closed; a repeated letter cycles
+        dropdown.type_ahead("b");
+        assert_eq!(dropdown.selected(), Some("Banana"));
+        dropdown.type_ahead("b");
+        assert_eq!(dropdown.selected(), Some("Blueberry"));
+        dropdown.update(1.5);
+        dropdown.type_ahead("a");
+        assert_eq!(*picked.borrow(), [3, 1, 2, 0]);
+        dropdown.update(1.5);
+        assert!(dropdown.handle_key(&Key::Named(NamedKey::Space)));
+        assert!(dropdown.handle_key(&Key::Named(NamedKey::Escape)));
+        assert!(!dropdown.open);
+
+        // Multi-select toggles and stays open; the header sums up
+        let mut multi = Dropdown::new(vec!["Rust".into(), "Go".into(), "Zig".into()])
+            .with_multi_select(true)
+            .with_placeholder("Languages");
+        multi.layout(Vec2::ZERO, Vec2::new(300.0, 300.0));
+        assert_eq!(multi.header_label(), "Languages");
+        multi.handle_key(&Key::Named(NamedKey::Enter));
+        multi.handle_key(&Key::Named(NamedKey::Space));
+        multi.handle_key(&Key::Named(NamedKey::ArrowDown));
+        multi.handle_key(&Key::Named(NamedKey::Enter));
+        assert!(multi.open);
+        assert_eq!(multi.selected_options(), ["Rust", "Go"]);
+        assert_eq!(multi.header_label(), "Rust, Go");
+        multi.choose(2);
+        assert_eq!(multi.header_label(), "All (3)");
+        multi.choose(0);
+        multi.size.x = 80.0;
+        assert_eq!(multi.header_label(), "2 selected");
+
+        // A click on the header focuses, one elsewhere blurs and closes
+        let click = RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }.to_event().unwrap();
+        let mut clicked = Dropdown::new(vec!["One".into(), "Two".into()]);
+        clicked.layout(Vec2::ZERO, Vec2::new(300.0, 300.0));
+        assert!(clicked.handle_event(&click, Vec2::new(20.0, 10.0)));
+        assert!(clicked.focused && clicked.open);
+        clicked.handle_event(&click, Vec2::new(250.0, 250.0));
+        assert!(!clicked.focused && !clicked.open);
     }
 }
@@ -0,0 +1,606 @@
+//! GlassUI Widget Inspector
+//!
+//! Devtools-style overlay for the widget tree:
+//! - Inspector - hover to highlight, click to select, side panel with details
+//! - WidgetSnapshot - type, bounds, constraints, focus/hover state, style
+//!   and debug properties of one widget
+//! - render_layout_debug - outlines, padding, baselines and overflow for a tree
+//!
+//! Call `inspect(&root)` (or `inspect_with` to show focus and hover) after
+//! layout each frame and render the inspector as a top layer. While
+//! visible it consumes left clicks to pick widgets, and the constraints
+//! parents lay widgets out with are recorded for it.
+//! The layout debug view is drawn by the renderer for every layer while
+//! `set_debug_layout(true)` is in effect.
+
+use glam::{Vec2, Vec4};
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+use crate::focus::{FocusId, FocusManager};
+use crate::layout::{BoxConstraints, Rect, Offset, Size, EdgeInsets, record_constraints, recorded_constraints};
+use crate::renderer::GlassRenderer;
+use crate::style::WidgetStyle;
+use crate::widget_id::WidgetId;
+use crate::widgets::core::{Widget, get_theme};
+
+// =============================================================================
+// WIDGET SNAPSHOT
+// =============================================================================
+
+/// Copy of one widget's inspectable state
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidgetSnapshot {
+    pub type_name: &'static str,
+    /// Screen rect, if the widget reports one
+    pub bounds: Option<Rect>,
+    /// Nesting depth below the root (root = 0)
+    pub depth: usize,
+    /// Child indices from the root down to this widget
+    pub path: Vec<usize>,
+    /// Constraints its parent last laid it out with, recorded while the
+    /// inspector is visible (None until the next layout pass)
+    pub constraints: Option<BoxConstraints>,
+    /// Set for widgets that can take focus
+    pub focus_id: Option<FocusId>,
+    /// Whether it has focus, per the `FocusManager` given to `inspect_with`
+    pub focused: bool,
+    /// Whether the pointer is over it or a descendant, per the hover path
+    /// given to `inspect_with`
+    pub hovered: bool,
+    /// Fields set in its `WidgetStyle`, if it's styled by one
+    pub style: Vec<(&'static str, String)>,
+    pub properties: Vec<(&'static str, String)>,
+}
+
+impl WidgetSnapshot {
+    fn capture(widget: &dyn Widget, path: &[usize]) -> Self {
+        let mut properties = Vec::new();
+        widget.debug_properties(&mut properties);
+        let mut style = Vec::new();
+        if let Some(widget_style) = widget.widget_style() {
+            style_properties(widget_style, &mut style);
+        }
+        Self {
+            type_name: widget.type_name(),
+            bounds: widget_bounds(widget),
+            depth: path.len(),
+            path: path.to_vec(),
+            constraints: recorded_constraints(widget),
+            focus_id: widget.as_focusable().map(|focusable| focusable.focus_id()),
+            focused: false,
+            hovered: false,
+            style,
+            properties,
+        }
+    }
+
+    /// Fill in `focused` and `hovered`
+    fn annotate(&mut self, focus: Option<&FocusManager>, hovered: Option<&[usize]>) {
+        self.focused = self.focus_id.is_some() && focus.and_then(FocusManager::focused) == self.focus_id;
+        self.hovered = hovered.is_some_and(|hovered| hovered.starts_with(&self.path));
+    }
+}
+
+/// The fields set in `style`, formatted for the inspector panel
+fn style_properties(style: &WidgetStyle, props: &mut Vec<(&'static str, String)>) {
+    let insets = |i: &EdgeInsets| format!("{:.0} {:.0} {:.0} {:.0}", i.top, i.right, i.bottom, i.left);
+    let color = |c: &Vec4| format!("{:.2} {:.2} {:.2} {:.2}", c.x, c.y, c.z, c.w);
+    let mut push = |key: &'static str, value: Option<String>| props.extend(value.map(|value| (key, value)));
+    push("padding", style.padding.as_ref().map(insets));
+    push("margin", style.margin.as_ref().map(insets));
+    push("min width", style.min_width.map(|v| format!("{:.0}", v)));
+    push("max width", style.max_width.map(|v| format!("{:.0}", v)));
+    push("min height", style.min_height.map(|v| format!("{:.0}", v)));
+    push("max height", style.max_height.map(|v| format!("{:.0}", v)));
+    push("background", style.background.as_ref().map(color));
+    push("bg hover", style.background_hover.as_ref().map(color));
+    push("bg pressed", style.background_pressed.as_ref().map(color));
+    push("bg disabled", style.background_disabled.as_ref().map(color));
+    push("border", style.border.map(|b| format!("{:.1} r{:.0} {}", b.width, b.radius, color(&b.color))));
+    push("border hover", style.border_hover.map(|b| format!("{:.1} r{:.0} {}", b.width, b.radius, color(&b.color))));
+    push("shadow", style.shadow.map(|s| format!("{:.0},{:.0} blur {:.0}", s.offset_x, s.offset_y, s.blur)));
+    push("font size", style.text_style.as_ref().map(|t| format!("{:.0}", t.font_size)));
+    push("opacity", style.opacity.map(|v| format!("{:.2}", v)));
+    push("corner radius", style.corner_radius.map(|v| format!("{:.0}", v)));
+}
+
+/// `min..max` of each axis, e.g. "0..400 x 0..inf"
+fn format_constraints(c: &BoxConstraints) -> String {
+    let extent = |v: f32| if v.is_finite() { format!("{:.0}", v) } else { "inf".to_string() };
+    format!("{}..{} x {}..{}", extent(c.min_width), extent(c.max_width), extent(c.min_height), extent(c.max_height))
+}
+
+/// Laid-out rect of a widget, falling back to its render bounds
+pub fn widget_bounds(widget: &dyn Widget) -> Option<Rect> {
+    let Size { width, height } = widget.get_size();
+    if width > 0.0 && height > 0.0 {
+        let Offset { x, y } = widget.get_position();
+        return Some(Rect::new(Vec2::new(x, y), Vec2::new(width, height)));
+    }
+    widget.render_bounds().filter(|rect| !rect.is_empty())
+}
+
+/// Deepest widget under `point`; later siblings win since they draw on top
+fn hit_path(widget: &dyn Widget, point: Vec2, path: &mut Vec<usize>) -> Option<WidgetSnapshot> {
+    let mut hit = None;
+    let mut index = 0;
+    widget.visit_children(&mut |child| {
+        path.push(index);
+        if let Some(found) = hit_path(child, point, path) {
+            hit = Some(found);
+        }
+        path.pop();
+        index += 1;
+    });
+    if hit.is_some() {
+        return hit;
+    }
+    widget_bounds(widget)
+        .filter(|rect| rect.contains(point))
+        .map(|_| WidgetSnapshot::capture(widget, path))
+}
+
+/// Widget at `path` below `root`
+fn find_path(root: &dyn Widget, path: &[usize]) -> Option<WidgetSnapshot> {
+    fn walk(widget: &dyn Widget, path: &[usize], depth: usize) -> Option<WidgetSnapshot> {
+        if depth == path.len() {
+            return Some(WidgetSnapshot::capture(widget, &path[..depth]));
+        }
+        let mut found = None;
+        let mut index = 0;
+        widget.visit_children(&mut |child| {
+            if index == path[depth] {
+                found = walk(child, path, depth + 1);
+            }
+            index += 1;
+        });
+        found
+    }
+    walk(root, path, 0)
+}
+
+// =============================================================================
+// LAYOUT DEBUG
+// =============================================================================
+
+const DEBUG_RECT_COLOR: Vec4 = Vec4::new(0.2, 0.9, 1.0, 0.7);
+const DEBUG_PADDING_COLOR: Vec4 = Vec4::new(1.0, 0.6, 0.1, 0.25);
+const DEBUG_BASELINE_COLOR: Vec4 = Vec4::new(1.0, 0.2, 0.9, 0.9);
+const DEBUG_OVERFLOW_COLOR: Vec4 = Vec4::new(1.0, 0.2, 0.2, 0.95);
+
+/// How far `child` sticks out of `content` on its worst side (0 if it fits)
+pub fn overflow_amount(content: &Rect, child: &Rect) -> f32 {
+    let before = content.min - child.min;
+    let after = child.max - content.max;
+    before.max(after).max_element().max(0.0)
+}
+
+/// Draw layout rects, padding, text baselines and overflow for `root` and its descendants
+pub fn render_layout_debug(root: &dyn Widget, renderer: &mut GlassRenderer) {
+    fn walk(widget: &dyn Widget, renderer: &mut GlassRenderer) {
+        let bounds = widget_bounds(widget);
+        if let Some(rect) = bounds {
+            draw_outline(renderer, rect, 1.0, DEBUG_RECT_COLOR);
+            let insets = widget.content_insets();
+            if insets != EdgeInsets::ZERO {
+                draw_padding(renderer, rect, &insets);
+            }
+        }
+        if let Some((origin, scale)) = widget.text_origin() {
+            let ascent = renderer.measure_text("", scale).ascent;
+            let width = bounds.map_or(scale * 4.0, |rect| rect.max.x - origin.x).max(1.0);
+            renderer.draw_overlay_rect(Vec2::new(origin.x, origin.y + ascent), Vec2::new(width, 1.0), DEBUG_BASELINE_COLOR, 0.0);
+        }
+
+        let content = bounds
+            .filter(|_| !widget.clips_content())
+            .map(|rect| rect.deflate(&widget.content_insets()));
+        widget.visit_children(&mut |child| {
+            if let (Some(content), Some(child_rect)) = (content, widget_bounds(child)) {
+                let overflow = overflow_amount(&content, &child_rect);
+                if overflow >= 0.5 {
+                    draw_outline(renderer, child_rect, 2.0, DEBUG_OVERFLOW_COLOR);
+                    renderer.draw_overlay_text_fmt(
+                        format_args!("{} overflow {:.0}px", child.type_name(), overflow),
+                        child_rect.min + Vec2::new(2.0, 2.0),
+                        11.0,
+                        DEBUG_OVERFLOW_COLOR,
+                    );
+                }
+            }
+            walk(child, renderer);
+        });
+    }
+    walk(root, renderer);
+}
+
+fn draw_outline(renderer: &mut GlassRenderer, rect: Rect, thickness: f32, color: Vec4) {
+    let size = rect.size();
+    renderer.draw_overlay_rect(rect.min, Vec2::new(size.x, thickness), color, 0.0);
+    renderer.draw_overlay_rect(Vec2::new(rect.min.x, rect.max.y - thickness), Vec2::new(size.x, thickness), color, 0.0);
+    renderer.draw_overlay_rect(rect.min, Vec2::new(thickness, size.y), color, 0.0);
+    renderer.draw_overlay_rect(Vec2::new(rect.max.x - thickness, rect.min.y), Vec2::new(thickness, size.y), color, 0.0);
+}
+
+/// Fill the bands between `rect` and its content box
+fn draw_padding(renderer: &mut GlassRenderer, rect: Rect, insets: &EdgeInsets) {
+    let size = rect.size();
+    let middle = size.y - insets.vertical();
+    renderer.draw_overlay_rect(rect.min, Vec2::new(size.x, insets.top), DEBUG_PADDING_COLOR, 0.0);
+    renderer.draw_overlay_rect(Vec2::new(rect.min.x, rect.max.y - insets.bottom), Vec2::new(size.x, insets.bottom), DEBUG_PADDING_COLOR, 0.0);
+    renderer.draw_overlay_rect(Vec2::new(rect.min.x, rect.min.y + insets.top), Vec2::new(insets.left, middle), DEBUG_PADDING_COLOR, 0.0);
+    renderer.draw_overlay_rect(Vec2::new(rect.max.x - insets.right, rect.min.y + insets.top), Vec2::new(insets.right, middle), DEBUG_PADDING_COLOR, 0.0);
+}
+
+// =============================================================================
+// INSPECTOR
+// =============================================================================
+
+/// Toggleable devtools overlay for the glass widget tree
+pub struct Inspector {
+    pub id: WidgetId,
+    pub visible: bool,
+    /// Width of the details panel docked to the right edge
+    pub panel_width: f32,
+    screen: Vec2,
+    cursor: Vec2,
+    hovered: Option<WidgetSnapshot>,
+    selected: Option<WidgetSnapshot>,
+    selected_path: Option<Vec<usize>>,
+    /// Set by a click, resolved against the tree on the next `inspect`
+    pick_pending: bool,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self {
+            id: WidgetId::new(),
+            visible: false,
+            panel_width: 300.0,
+            screen: Vec2::ZERO,
+            cursor: Vec2::ZERO,
+            hovered: None,
+            selected: None,
+            selected_path: None,
+            pick_pending: false,
+        }
+    }
+
+    pub fn with_panel_width(mut self, width: f32) -> Self {
+        self.panel_width = width.max(120.0);
+        self
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        record_constraints(self.visible);
+        if !self.visible {
+            self.hovered = None;
+            self.pick_pending = false;
+        }
+    }
+
+    /// Widget currently under the cursor
+    pub fn hovered(&self) -> Option<&WidgetSnapshot> {
+        self.hovered.as_ref()
+    }
+
+    /// Widget picked with the last click
+    pub fn selected(&self) -> Option<&WidgetSnapshot> {
+        self.selected.as_ref()
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected = None;
+        self.selected_path = None;
+    }
+
+    /// Refresh hover and selection against `root`; call after layout
+    pub fn inspect(&mut self, root: &dyn Widget) {
+        self.inspect_with(root, None, None);
+    }
+
+    /// `inspect`, also showing which widget has focus in `focus` and which
+    /// are under the pointer (`hovered`, from `EventDispatcher::hovered`)
+    pub fn inspect_with(&mut self, root: &dyn Widget, focus: Option<&FocusManager>, hovered: Option<&[usize]>) {
+        if !self.visible {
+            return;
+        }
+        self.hovered = if self.over_panel(self.cursor) {
+            None
+        } else {
+            hit_path(root, self.cursor, &mut Vec::new())
+        };
+        if std::mem::take(&mut self.pick_pending) {
+            self.selected_path = self.hovered.as_ref().map(|s| s.path.clone());
+        }
+        self.selected = self.selected_path.as_deref().and_then(|path| find_path(root, path));
+        if self.selected.is_none() {
+            self.selected_path = None;
+        }
+        for snapshot in self.hovered.iter_mut().chain(self.selected.iter_mut()) {
+            snapshot.annotate(focus, hovered);
+        }
+    }
+
+    fn panel_rect(&self) -> Rect {
+        let width = self.panel_width.min(self.screen.x);
+        Rect::new(Vec2::new(self.screen.x - width, 0.0), Vec2::new(width, self.screen.y))
+    }
+
+    fn over_panel(&self, point: Vec2) -> bool {
+        self.selected.is_some() && self.panel_rect().contains(point)
+    }
+
+    fn render_panel(&self, renderer: &mut GlassRenderer, snapshot: &WidgetSnapshot) {
+        let theme = get_theme();
+        let panel = self.panel_rect();
+        let padding = 12.0;
+        let line_height = 18.0;
+        renderer.draw_overlay_rect(panel.min, panel.size(), Vec4::new(0.04, 0.04, 0.08, 0.92), 0.0);
+
+        let x = panel.min.x + padding;
+        let mut y = panel.min.y + padding;
+        renderer.draw_overlay_text(snapshot.type_name, Vec2::new(x, y), 16.0, theme.primary);
+        y += line_height + 6.0;
+
+        let mut row = |renderer: &mut GlassRenderer, key: &str, value: std::fmt::Arguments| {
+            renderer.draw_overlay_text(key, Vec2::new(x, y), 12.0, theme.text_secondary);
+            renderer.draw_overlay_text_fmt(value, Vec2::new(x + 96.0, y), 12.0, theme.text);
+            y += line_height;
+        };
+        let path: Vec<String> = snapshot.path.iter().map(|i| i.to_string()).collect();
+        row(renderer, "path", format_args!("/{}", path.join("/")));
+        match snapshot.bounds {
+            Some(rect) => {
+                row(renderer, "position", format_args!("{:.0}, {:.0}", rect.min.x, rect.min.y));
+                row(renderer, "size", format_args!("{:.0} x {:.0}", rect.size().x, rect.size().y));
+            }
+            None => row(renderer, "bounds", format_args!("n/a")),
+        }
+        match &snapshot.constraints {
+            Some(constraints) => row(renderer, "constraints", format_args!("{}", format_constraints(constraints))),
+            None => row(renderer, "constraints", format_args!("n/a")),
+        }
+        if snapshot.focus_id.is_some() {
+            row(renderer, "focused", format_args!("{}", snapshot.focused));
+        }
+        row(renderer, "pointer over", format_args!("{}", snapshot.hovered));
+        for (key, value) in &snapshot.properties {
+            row(renderer, key, format_args!("{}", value));
+        }
+
+        if !snapshot.style.is_empty() {
+            y += 6.0;
+            renderer.draw_overlay_text("Style", Vec2::new(x, y), 13.0, theme.primary);
+            y += line_height;
+            for (key, value) in &snapshot.style {
+                renderer.draw_overlay_text(key, Vec2::new(x, y), 12.0, theme.text_secondary);
+                renderer.draw_overlay_text(value, Vec2::new(x + 96.0, y), 12.0, theme.text);
+                y += line_height;
+            }
+        }
+    }
+}
+
+impl Default for Inspector {
+    fn default() -> Self { Self::new() }
+}
+
+impl Widget for Inspector {
+    fn layout(&mut self, _origin: Vec2, max_size: Vec2) -> Vec2 {
+        // Covers the whole screen but takes no space in the parent layout
+        self.screen = max_size;
+        Vec2::ZERO
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let screen = constraints.biggest_or(Size::new(self.screen.x, self.screen.y));
+        self.screen = Vec2::new(screen.width, screen.height);
+        constraints.smallest()
+    }
+
+    /// Laid out once to learn the screen size the panel is placed in
+    fn needs_layout(&self) -> bool {
+        self.screen == Vec2::ZERO
+    }
+
+    fn handle_event(&mut self, event: &Event<()>, mouse_pos: Vec2) -> bool {
+        if !self.visible {
+            return false;
+        }
+        match event {
+            Event::WindowEvent { event: WindowEvent::CursorMoved { .. }, .. } => {
+                self.cursor = mouse_pos;
+                false
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                self.cursor = mouse_pos;
+                if self.over_panel(mouse_pos) {
+                    return true;
+                }
+                self.pick_pending = true;
+                true
+            }
+            // Swallow the matching release so widgets don't see half a click
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Released, button: MouseButton::Left, .. }, .. } => true,
+            _ => false,
+        }
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn is_animating(&self) -> bool {
+        // Keep redrawing so the highlight follows the cursor
+        self.visible
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        if !self.visible {
+            return;
+        }
+        let theme = get_theme();
+
+        if let Some(rect) = self.hovered.as_ref().and_then(|s| s.bounds) {
+            let mut fill = theme.primary;
+            fill.w = 0.18;
+            renderer.draw_overlay_rect(rect.min, rect.size(), fill, 0.0);
+            draw_outline(renderer, rect, 1.0, theme.primary);
+            if let Some(hovered) = &self.hovered {
+                renderer.draw_overlay_text_fmt(
+                    format_args!("{} {:.0}x{:.0}", hovered.type_name, rect.size().x, rect.size().y),
+                    Vec2::new(rect.min.x, (rect.min.y - 16.0).max(0.0)),
+                    12.0,
+                    theme.text,
+                );
+            }
+        }
+
+        if let Some(selected) = &self.selected {
+            if let Some(rect) = selected.bounds {
+                draw_outline(renderer, rect, 2.0, theme.accent);
+            }
+            self.render_panel(renderer, selected);
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::{Column, Label, Button};
+
+    fn tree() -> Column {
+        let mut root = Column::new()
+            .add_child(Box::new(Label::new("Title")))
+            .add_child(Box::new(Button::new("OK")));
+        root.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        root
+    }
+
+    fn child_center(root: &dyn Widget, target: usize) -> Vec2 {
+        let mut center = Vec2::ZERO;
+        let mut index = 0;
+        root.visit_children(&mut |child| {
+            if index == target {
+                let rect = widget_bounds(child).unwrap();
+                center = (rect.min + rect.max) * 0.5;
+            }
+            index += 1;
+        });
+        center
+    }
+
+    fn press() -> Event<()> {
+        Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::MouseInput {
+                device_id: unsafe { winit::event::DeviceId::dummy() },
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+            },
+        }
+    }
+
+    #[test]
+    fn test_hover_finds_deepest_widget() {
+        let root = tree();
+        let mut inspector = Inspector::new();
+        inspector.toggle();
+        inspector.cursor = child_center(&root, 1);
+        inspector.inspect(&root);
+
+        let hovered = inspector.hovered().unwrap();
+        assert_eq!(hovered.type_name, "Button");
+        assert_eq!(hovered.path, vec![1]);
+        assert!(hovered.properties.iter().any(|(k, v)| *k == "text" && v == "OK"));
+    }
+
+    #[test]
+    fn test_click_selects_and_is_consumed() {
+        let root = tree();
+        let label_center = child_center(&root, 0);
+        let mut inspector = Inspector::new();
+        assert!(!inspector.handle_event(&press(), label_center));
+
+        inspector.toggle();
+        assert!(inspector.handle_event(&press(), label_center));
+        inspector.inspect(&root);
+        let selected = inspector.selected().unwrap();
+        assert_eq!(selected.type_name, "Label");
+        assert_eq!(selected.depth, 1);
+
+        // Selection survives the cursor moving away
+        inspector.cursor = Vec2::new(399.0, 299.0);
+        inspector.inspect(&root);
+        assert_eq!(inspector.selected().unwrap().type_name, "Label");
+    }
+
+    #[test]
+    fn test_selection_shows_constraints_focus_hover_and_style() {
+        use crate::focus::FocusNode;
+        use crate::widgets::TextInput;
+
+        let mut inspector = Inspector::new();
+        inspector.toggle();
+        let mut root = Column::new()
+            .add_child(Box::new(Label::new("Title")))
+            .add_child(Box::new(TextInput::new("Name").with_style(WidgetStyle::new().corner_radius(4.0))));
+        root.layout_child(Vec2::ZERO, Vec2::new(400.0, 300.0));
+
+        let mut focus_id = None;
+        let mut index = 0;
+        root.visit_children(&mut |child| {
+            if index == 1 {
+                focus_id = child.as_focusable().map(|focusable| focusable.focus_id());
+            }
+            index += 1;
+        });
+        let mut focus = FocusManager::new();
+        focus.register(FocusNode::new(focus_id.unwrap()));
+        focus.request_focus(focus_id.unwrap());
+
+        assert!(inspector.handle_event(&press(), child_center(&root, 1)));
+        inspector.inspect_with(&root, Some(&focus), Some(&[1]));
+        let selected = inspector.selected().unwrap();
+        let constraints = selected.constraints.unwrap();
+        // The column's default padding comes off the width it passes down
+        assert_eq!((constraints.min_width, constraints.max_width), (0.0, 380.0));
+        assert!(selected.focused);
+        assert!(selected.hovered);
+        assert!(selected.style.iter().any(|(k, v)| *k == "corner radius" && v == "4"));
+
+        // The label isn't focusable, hovered or styled
+        inspector.cursor = child_center(&root, 0);
+        inspector.inspect_with(&root, Some(&focus), Some(&[0]));
+        let label = inspector.hovered().unwrap();
+        assert_eq!(label.focus_id, None);
+        assert!(!label.focused);
+        assert!(label.hovered);
+        assert!(label.style.is_empty());
+        assert!(!inspector.selected().unwrap().hovered);
+
+        inspector.toggle();
+        assert!(recorded_constraints(&root).is_none());
+    }
+
+    #[test]
+    fn test_overflow_amount() {
+        let content = Rect::new(Vec2::ZERO, Vec2::new(100.0, 50.0));
+        assert_eq!(overflow_amount(&content, &Rect::new(Vec2::new(10.0, 10.0), Vec2::new(80.0, 30.0))), 0.0);
+        assert_eq!(overflow_amount(&content, &Rect::new(Vec2::new(10.0, 10.0), Vec2::new(120.0, 30.0))), 30.0);
+        assert_eq!(overflow_amount(&content, &Rect::new(Vec2::new(-5.0, 0.0), Vec2::new(20.0, 20.0))), 5.0);
+
+        let padded = Rect::new(Vec2::ZERO, Vec2::new(100.0, 50.0)).deflate(&EdgeInsets::symmetric(10.0, 5.0));
+        assert_eq!(padded, Rect::new(Vec2::new(10.0, 5.0), Vec2::new(80.0, 40.0)));
+    }
+
+    #[test]
+    fn test_type_name_is_short() {
+        assert_eq!(Column::new().type_name(), "Column");
+        assert_eq!(Inspector::new().type_name(), "Inspector");
+    }
+}
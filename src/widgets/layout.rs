@@ -5,7 +5,9 @@
 use glam::{Vec2, Vec4};
 use winit::event::{ElementState, MouseButton};
 use crate::renderer::GlassRenderer;
-use super::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, LayoutCache, Rect, Offset, Size, EdgeInsets};
+use crate::widget_id::WidgetId;
+use super::core::{Widget, UpdateState, get_theme, is_occluded, constraint_property, UNBOUNDED_EXTENT};
 
 // =============================================================================
 // COLUMN
@@ -15,9 +17,14 @@ use super::core::{Widget, get_theme};
 pub struct Column {
     pub position: Vec2,
     pub size: Vec2,
-    pub children: Vec<Box<dyn Widget>>,
+    /// Children, each with its flex factor (0 = its own size)
+    pub children: Vec<(Box<dyn Widget>, f32)>,
     pub spacing: f32,
-    pub padding: f32,
+    /// Space between the edges and the children
+    pub padding: EdgeInsets,
+    /// Child positions relative to ours, from the last constraint layout
+    offsets: Vec<Vec2>,
+    layout_cache: LayoutCache,
 }
 
 impl Column {
@@ -27,24 +34,48 @@ impl Column {
             size: Vec2::ZERO,
             children: Vec::new(),
             spacing: 10.0,
-            padding: 10.0,
+            padding: EdgeInsets::all(10.0),
+            offsets: Vec::new(),
+            layout_cache: LayoutCache::new(),
         }
     }
     
-    pub fn add_child(mut self, child: Box<dyn Widget>) -> Self {
-        self.children.push(child);
+    pub fn add_child(self, child: Box<dyn Widget>) -> Self {
+        self.add_flex_child(child, 0.0)
+    }
+    
+    /// Add a child that takes a share of the height left over after the
+    /// other children, in proportion to `flex` (0 = its own size, like
+    /// `add_child`)
+    pub fn add_flex_child(mut self, child: Box<dyn Widget>, flex: f32) -> Self {
+        self.children.push((child, flex.max(0.0)));
         self
     }
     
+    /// Flex factor of child `index`
+    pub fn flex(&self, index: usize) -> f32 {
+        self.children.get(index).map_or(0.0, |(_, flex)| *flex)
+    }
+    
     pub fn with_spacing(mut self, spacing: f32) -> Self {
         self.spacing = spacing;
         self
     }
     
+    /// Same padding on all sides
     pub fn with_padding(mut self, padding: f32) -> Self {
-        self.padding = padding;
+        self.padding = EdgeInsets::all(padding);
+        self
+    }
+    
+    pub fn with_insets(mut self, insets: EdgeInsets) -> Self {
+        self.padding = insets;
         self
     }
+    
+    fn linear(&self) -> LinearLayout {
+        LinearLayout { direction: FlexDirection::Column, spacing: self.spacing, padding: self.padding }
+    }
 }
 
 impl Default for Column {
@@ -53,28 +84,77 @@ impl Default for Column {
 
 impl Widget for Column {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
-        self.position = origin;
-        
-        let mut cursor = origin + Vec2::splat(self.padding);
-        let content_width = max_size.x - self.padding * 2.0;
-        let mut max_width = 0.0f32;
-        
-        for child in &mut self.children {
-            let used_height = cursor.y - origin.y - self.padding;
-            let remaining_height = (max_size.y - self.padding * 2.0 - used_height).max(0.0);
-            
-            let child_size = child.layout(cursor, Vec2::new(content_width, remaining_height)); 
-            cursor.y += child_size.y + self.spacing;
-            max_width = max_width.max(child_size.x);
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
         }
-        
-        self.size = Vec2::new(max_width + self.padding * 2.0, cursor.y - origin.y + self.padding - self.spacing);
+        self.position = origin;
+        let linear = self.linear();
+        let mut children: Vec<_> = self.children.iter_mut().map(|(child, flex)| (child.as_mut() as &mut dyn Widget, *flex)).collect();
+        self.size = linear.layout(&mut children, origin, max_size);
+        self.layout_cache.store(origin, max_size, self.size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let linear = self.linear();
+        let mut children: Vec<_> = self.children.iter_mut().map(|(child, flex)| (child.as_mut() as &mut dyn Widget, *flex)).collect();
+        let size = linear.layout_with_constraints(&mut children, constraints, &mut self.offsets);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        place_children(self.children.iter_mut().map(|(child, _)| child), &self.offsets, position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        for (child, _) in &self.children {
+            visitor(child.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        for (child, _) in &mut self.children {
+            visitor(child.as_mut());
+        }
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("children", self.children.len().to_string()));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        self.padding
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.children.iter().any(|(c, _)| c.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|(c, _)| c.is_animating())
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         let mut handled = false;
-        for child in &mut self.children {
+        for (child, _) in &mut self.children {
             if child.handle_event(event, mouse_pos) {
                 handled = true;
             }
@@ -83,13 +163,19 @@ impl Widget for Column {
     }
 
     fn update(&mut self, dt: f32) {
-        for child in &mut self.children {
+        for (child, _) in &mut self.children {
             child.update(dt);
         }
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        for (child, _) in &mut self.children {
+            child.split_update(dt, jobs);
+        }
+    }
+
     fn render(&self, renderer: &mut GlassRenderer) {
-        for child in &self.children {
+        for (child, _) in &self.children {
             child.render(renderer);
         }
     }
@@ -103,9 +189,14 @@ impl Widget for Column {
 pub struct Row {
     pub position: Vec2,
     pub size: Vec2,
-    pub children: Vec<Box<dyn Widget>>,
+    /// Children, each with its flex factor (0 = its own size)
+    pub children: Vec<(Box<dyn Widget>, f32)>,
     pub spacing: f32,
-    pub padding: f32,
+    /// Space between the edges and the children
+    pub padding: EdgeInsets,
+    /// Child positions relative to ours, from the last constraint layout
+    offsets: Vec<Vec2>,
+    layout_cache: LayoutCache,
 }
 
 impl Row {
@@ -115,19 +206,48 @@ impl Row {
             size: Vec2::ZERO,
             children: Vec::new(),
             spacing: 10.0,
-            padding: 10.0,
+            padding: EdgeInsets::all(10.0),
+            offsets: Vec::new(),
+            layout_cache: LayoutCache::new(),
         }
     }
     
-    pub fn add_child(mut self, child: Box<dyn Widget>) -> Self {
-        self.children.push(child);
+    pub fn add_child(self, child: Box<dyn Widget>) -> Self {
+        self.add_flex_child(child, 0.0)
+    }
+    
+    /// Add a child that takes a share of the width left over after the
+    /// other children, in proportion to `flex` (0 = its own size, like
+    /// `add_child`)
+    pub fn add_flex_child(mut self, child: Box<dyn Widget>, flex: f32) -> Self {
+        self.children.push((child, flex.max(0.0)));
         self
     }
     
+    /// Flex factor of child `index`
+    pub fn flex(&self, index: usize) -> f32 {
+        self.children.get(index).map_or(0.0, |(_, flex)| *flex)
+    }
+    
     pub fn with_spacing(mut self, spacing: f32) -> Self {
         self.spacing = spacing;
         self
     }
+    
+    /// Same padding on all sides
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = EdgeInsets::all(padding);
+        self
+    }
+    
+    pub fn with_insets(mut self, insets: EdgeInsets) -> Self {
+        self.padding = insets;
+        self
+    }
+    
+    fn linear(&self) -> LinearLayout {
+        LinearLayout { direction: FlexDirection::Row, spacing: self.spacing, padding: self.padding }
+    }
 }
 
 impl Default for Row {
@@ -136,27 +256,77 @@ impl Default for Row {
 
 impl Widget for Row {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
-        self.position = origin;
-        
-        let mut cursor = origin + Vec2::splat(self.padding);
-        let mut max_height = 0.0f32;
-        
-        for child in &mut self.children {
-            let used_width = cursor.x - origin.x - self.padding;
-            let remaining_width = (max_size.x - self.padding * 2.0 - used_width).max(0.0);
-            
-            let child_size = child.layout(cursor, Vec2::new(remaining_width, max_size.y)); 
-            cursor.x += child_size.x + self.spacing;
-            max_height = max_height.max(child_size.y);
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
         }
-        
-        self.size = Vec2::new(cursor.x - origin.x + self.padding - self.spacing, max_height + self.padding * 2.0);
+        self.position = origin;
+        let linear = self.linear();
+        let mut children: Vec<_> = self.children.iter_mut().map(|(child, flex)| (child.as_mut() as &mut dyn Widget, *flex)).collect();
+        self.size = linear.layout(&mut children, origin, max_size);
+        self.layout_cache.store(origin, max_size, self.size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let linear = self.linear();
+        let mut children: Vec<_> = self.children.iter_mut().map(|(child, flex)| (child.as_mut() as &mut dyn Widget, *flex)).collect();
+        let size = linear.layout_with_constraints(&mut children, constraints, &mut self.offsets);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        place_children(self.children.iter_mut().map(|(child, _)| child), &self.offsets, position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        for (child, _) in &self.children {
+            visitor(child.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        for (child, _) in &mut self.children {
+            visitor(child.as_mut());
+        }
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("children", self.children.len().to_string()));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        self.padding
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.children.iter().any(|(c, _)| c.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|(c, _)| c.is_animating())
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         let mut handled = false;
-        for child in &mut self.children {
+        for (child, _) in &mut self.children {
             if child.handle_event(event, mouse_pos) {
                 handled = true;
             }
@@ -165,38 +335,254 @@ impl Widget for Row {
     }
 
     fn update(&mut self, dt: f32) {
-        for child in &mut self.children {
+        for (child, _) in &mut self.children {
             child.update(dt);
         }
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        for (child, _) in &mut self.children {
+            child.split_update(dt, jobs);
+        }
+    }
+
     fn render(&self, renderer: &mut GlassRenderer) {
-        for child in &self.children {
+        for (child, _) in &self.children {
             child.render(renderer);
         }
     }
 }
 
+// =============================================================================
+// LINEAR LAYOUT
+// =============================================================================
+
+/// Children one after another along `direction`, each with a flex factor
+/// (0 = its own size); shared by Column, Row and `KeyedList`
+#[derive(Clone, Copy)]
+pub(crate) struct LinearLayout {
+    pub direction: FlexDirection,
+    pub spacing: f32,
+    pub padding: EdgeInsets,
+}
+
+impl LinearLayout {
+    /// Place `children` from `origin` within `max_size`; returns the size used
+    /// 
+    /// Flex children share what the others and the gaps leave of the main
+    /// axis, in proportion to their factors. On an unbounded main axis
+    /// (see `UNBOUNDED_EXTENT`) there's nothing to share and they take
+    /// their own size, as in `layout_with_constraints`.
+    pub fn layout(&self, children: &mut [(&mut dyn Widget, f32)], origin: Vec2, max_size: Vec2) -> Vec2 {
+        let axis = self.direction;
+        let start = origin + Vec2::new(self.padding.left, self.padding.top);
+        let content = max_size - Vec2::new(self.padding.horizontal(), self.padding.vertical());
+        
+        let bounded = axis.main(max_size) < UNBOUNDED_EXTENT;
+        let flex_total: f32 = if bounded { children.iter().map(|(_, flex)| flex).sum() } else { 0.0 };
+        let flex_space = if flex_total > 0.0 {
+            let mut used = self.spacing * children.len().saturating_sub(1) as f32;
+            for (child, flex) in children.iter_mut() {
+                if *flex == 0.0 {
+                    used += axis.main(child.layout_child(origin, content));
+                }
+            }
+            (axis.main(content) - used).max(0.0)
+        } else {
+            0.0
+        };
+        
+        let mut cursor = 0.0;
+        let mut max_cross = 0.0f32;
+        for (child, flex) in children.iter_mut() {
+            let flexed = flex_total > 0.0 && *flex > 0.0;
+            let share = flex_space * *flex / flex_total.max(f32::EPSILON);
+            let max_main = if flexed { share } else { (axis.main(content) - cursor).max(0.0) };
+            let child_size = child.layout_child(start + axis.vec(cursor, 0.0), axis.vec(max_main, axis.cross(content)));
+            cursor += if flexed { share } else { axis.main(child_size) } + self.spacing;
+            max_cross = max_cross.max(axis.cross(child_size));
+        }
+        
+        let padding = Vec2::new(self.padding.horizontal(), self.padding.vertical());
+        axis.vec((cursor - self.spacing).max(0.0), max_cross) + padding
+    }
+    
+    /// Size `children` within `constraints`, writing their offsets from our
+    /// top-left to `offsets` for `place_children`
+    /// 
+    /// Flex children only share a bounded main axis.
+    pub fn layout_with_constraints(&self, children: &mut [(&mut dyn Widget, f32)], constraints: BoxConstraints, offsets: &mut Vec<Vec2>) -> Size {
+        let axis = self.direction;
+        let inner = constraints.deflate(self.padding);
+        let max = Vec2::new(inner.max_width, inner.max_height);
+        let along = |min_main: f32, max_main: f32| {
+            let (min, max) = (axis.vec(min_main, 0.0), axis.vec(max_main, axis.cross(max)));
+            BoxConstraints::new(min.x, max.x, min.y, max.y)
+        };
+        let loose = along(0.0, f32::INFINITY);
+        let flex_total: f32 = if axis.main(max).is_finite() { children.iter().map(|(_, flex)| flex).sum() } else { 0.0 };
+        
+        let mut sizes = vec![Vec2::ZERO; children.len()];
+        let mut used = self.spacing * children.len().saturating_sub(1) as f32;
+        for (i, (child, flex)) in children.iter_mut().enumerate() {
+            if flex_total == 0.0 || *flex == 0.0 {
+                sizes[i] = child.layout_child_with_constraints(loose).into();
+                used += axis.main(sizes[i]);
+            }
+        }
+        let flex_space = (axis.main(max) - used).max(0.0);
+        
+        offsets.clear();
+        let mut cursor = 0.0;
+        let mut max_cross = 0.0f32;
+        for (i, (child, flex)) in children.iter_mut().enumerate() {
+            if flex_total > 0.0 && *flex > 0.0 {
+                let share = flex_space * *flex / flex_total;
+                sizes[i] = child.layout_child_with_constraints(along(share, share)).into();
+            }
+            offsets.push(Vec2::new(self.padding.left, self.padding.top) + axis.vec(cursor, 0.0));
+            cursor += axis.main(sizes[i]) + self.spacing;
+            max_cross = max_cross.max(axis.cross(sizes[i]));
+        }
+        
+        let content = axis.vec((cursor - self.spacing).max(0.0), max_cross);
+        constraints.constrain(Size::new(content.x + self.padding.horizontal(), content.y + self.padding.vertical()))
+    }
+}
+
+/// Move children along with their container to `position`, keeping the
+/// offsets its last constraint layout gave them
+pub(crate) fn place_children<'a>(children: impl IntoIterator<Item = &'a mut Box<dyn Widget>>, offsets: &[Vec2], position: Offset) {
+    for (child, offset) in children.into_iter().zip(offsets) {
+        child.set_position(position + Offset::from(*offset));
+    }
+}
+
 // =============================================================================
 // STACK
 // =============================================================================
 
+/// Stacking order of a `Stack` child
+/// 
+/// Children are drawn sorted by `(pinned, z)`, so pinned children stay
+/// above everything else. Equal keys keep their relative order; a pressed
+/// or raised child moves to the top of its key only.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StackOrder {
+    pub z: i32,
+    pub pinned: bool,
+}
+
+impl StackOrder {
+    fn key(self) -> (bool, i32) {
+        (self.pinned, self.z)
+    }
+}
+
 /// Z-layered overlay container (last child on top)
+/// 
+/// Children fill the stack unless wrapped in `Positioned`, which pins them
+/// to its edges or center instead.
 pub struct Stack {
     pub position: Vec2,
     pub size: Vec2,
-    pub children: Vec<Box<dyn Widget>>,
+    /// Children in drawing order (last on top), each with its stacking order
+    children: Vec<(Box<dyn Widget>, StackOrder)>,
+    layout_cache: LayoutCache,
 }
 
 impl Stack {
     pub fn new() -> Self {
-        Self { position: Vec2::ZERO, size: Vec2::ZERO, children: Vec::new() }
+        Self { position: Vec2::ZERO, size: Vec2::ZERO, children: Vec::new(), layout_cache: LayoutCache::new() }
+    }
+    
+    pub fn add_child(self, child: Box<dyn Widget>) -> Self {
+        self.add_child_with_z(child, 0)
     }
     
-    pub fn add_child(mut self, child: Box<dyn Widget>) -> Self {
-        self.children.push(child);
+    /// Add a child above children with a lower `z` and below higher ones
+    pub fn add_child_with_z(mut self, child: Box<dyn Widget>, z: i32) -> Self {
+        self.insert(child, StackOrder { z, pinned: false });
         self
     }
+    
+    /// Add a child that stays above every unpinned child
+    pub fn add_pinned_child(mut self, child: Box<dyn Widget>) -> Self {
+        self.insert(child, StackOrder { z: 0, pinned: true });
+        self
+    }
+    
+    /// Stacking order of child `index`
+    pub fn order(&self, index: usize) -> StackOrder {
+        self.children.get(index).map(|(_, order)| *order).unwrap_or_default()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+    
+    /// Child `index`, in drawing order
+    pub fn child(&self, index: usize) -> Option<&(dyn Widget + 'static)> {
+        self.children.get(index).map(|(child, _)| child.as_ref())
+    }
+    
+    pub fn child_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'static)> {
+        let (child, _) = self.children.get_mut(index)?;
+        Some(child.as_mut())
+    }
+    
+    /// Children in drawing order, bottom first
+    pub fn children(&self) -> impl Iterator<Item = &(dyn Widget + 'static)> {
+        self.children.iter().map(|(child, _)| child.as_ref())
+    }
+    
+    /// Move child `index` to the front of its z layer; returns its new index
+    pub fn raise(&mut self, index: usize) -> usize {
+        self.reposition(index, true)
+    }
+    
+    /// Move child `index` to the back of its z layer; returns its new index
+    pub fn lower(&mut self, index: usize) -> usize {
+        self.reposition(index, false)
+    }
+    
+    /// Change child `index`'s z; returns its new index
+    pub fn set_z(&mut self, index: usize, z: i32) -> usize {
+        self.children[index].1.z = z;
+        self.reposition(index, true)
+    }
+    
+    /// Pin or unpin child `index` above the unpinned children; returns its new index
+    pub fn set_pinned(&mut self, index: usize, pinned: bool) -> usize {
+        self.children[index].1.pinned = pinned;
+        self.reposition(index, true)
+    }
+    
+    /// Index of the child containing the widget tagged `id`
+    pub fn index_of(&self, id: WidgetId) -> Option<usize> {
+        self.children.iter().position(|(c, _)| c.as_ref().find_by_id(id).is_some())
+    }
+    
+    fn insert(&mut self, child: Box<dyn Widget>, order: StackOrder) {
+        self.children.push((child, order));
+        self.reposition(self.children.len() - 1, true);
+    }
+    
+    /// Re-insert child `index` at the front or back of the children
+    /// sharing its key
+    fn reposition(&mut self, index: usize, to_front: bool) -> usize {
+        let entry = self.children.remove(index);
+        let key = entry.1.key();
+        let target = self.children.iter()
+            .position(|(_, o)| if to_front { o.key() > key } else { o.key() >= key })
+            .unwrap_or(self.children.len());
+        self.children.insert(target, entry);
+        target
+    }
 }
 
 impl Default for Stack {
@@ -205,14 +591,113 @@ impl Default for Stack {
 
 impl Widget for Stack {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
         self.position = origin;
-        self.size = max_size;
         
-        for child in &mut self.children {
-            child.layout(origin, max_size);
+        // As in the constraint path: positioned children are placed in the
+        // area the others size, not counted in it
+        let mut largest = Vec2::ZERO;
+        for (child, _) in &mut self.children {
+            if child.positioned().is_none() {
+                largest = largest.max(child.layout_child(origin, max_size));
+            }
+        }
+        self.size = Vec2::new(
+            if max_size.x.is_finite() { max_size.x } else { largest.x },
+            if max_size.y.is_finite() { max_size.y } else { largest.y },
+        );
+        for (child, _) in &mut self.children {
+            if child.positioned().is_some() {
+                child.layout_child(origin, self.size);
+            }
         }
+        self.layout_cache.store(origin, max_size, self.size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        // Positioned children don't size the stack; they are placed in it
+        let mut largest = Size::ZERO;
+        for (child, _) in &mut self.children {
+            if child.positioned().is_none() {
+                let size = child.layout_child_with_constraints(constraints.loosen());
+                largest = Size::new(largest.width.max(size.width), largest.height.max(size.height));
+            }
+        }
+        let size = constraints.biggest_or(largest);
+        for (child, _) in &mut self.children {
+            if child.positioned().is_some() {
+                child.layout_child_with_constraints(BoxConstraints::tight(size));
+            }
+        }
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        for (child, _) in &mut self.children {
+            child.set_position(position);
+        }
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        for (child, _) in &self.children {
+            visitor(child.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        for (child, _) in &mut self.children {
+            visitor(child.as_mut());
+        }
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("children", self.children.len().to_string()));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.children.iter().any(|(c, _)| c.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|(c, _)| c.is_animating())
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        let mut children = self.children.iter().map(|(c, _)| c.render_bounds());
+        let first = children.next()??;
+        children.try_fold(first, |acc, b| b.map(|b| acc.union(&b)))
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        // Largest opaque child stands in for the whole stack
+        self.children.iter()
+            .filter_map(|(c, _)| c.opaque_rect())
+            .max_by(|a, b| {
+                let (a, b) = (a.size(), b.size());
+                (a.x * a.y).total_cmp(&(b.x * b.y))
+            })
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Reverse order for events (top-most first)
@@ -220,63 +705,277 @@ impl Widget for Stack {
         let len = self.children.len();
         
         for i in (0..len).rev() {
-            if self.children[i].handle_event(event, mouse_pos) {
+            if self.children[i].0.handle_event(event, mouse_pos) {
                 handled_index = Some(i);
                 break;
             }
         }
         
         if let Some(index) = handled_index {
-            // Bring to front (end of list) if interaction happened
-            if index != len - 1 {
-                let child = self.children.remove(index);
-                self.children.push(child);
-            }
+            self.child_pressed(index);
             return true;
         }
         
         false
     }
     
+    fn child_pressed(&mut self, index: usize) -> usize {
+        // Bring to front of its z layer if interaction happened
+        self.raise(index)
+    }
+    
     fn update(&mut self, dt: f32) {
-        for child in &mut self.children {
+        for (child, _) in &mut self.children {
             child.update(dt);
         }
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        for (child, _) in &mut self.children {
+            child.split_update(dt, jobs);
+        }
+    }
+
     fn render(&self, renderer: &mut GlassRenderer) {
-        for child in &self.children {
+        for (i, (child, _)) in self.children.iter().enumerate() {
+            // Skip children entirely hidden behind a later opaque sibling
+            if is_occluded(child.as_ref(), self.children[i + 1..].iter().map(|(c, _)| c.as_ref())) {
+                continue;
+            }
             child.render(renderer);
         }
     }
 }
 
 // =============================================================================
-// SPACER
+// POSITIONED
 // =============================================================================
 
-/// Flexible spacing element
-pub struct Spacer {
+/// Places a `Stack` child by its distance from the stack's edges
+/// 
+/// Setting both edges of an axis stretches the child between them;
+/// otherwise it keeps `width`/`height` (or its own size) and hangs off the
+/// edge that is set, or is centered when neither is. A stack sizes itself
+/// from its other children and then lays positioned ones out in that area.
+pub struct Positioned {
+    pub left: Option<f32>,
+    pub top: Option<f32>,
+    pub right: Option<f32>,
+    pub bottom: Option<f32>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub child: Box<dyn Widget>,
+    /// The area positioned in (the whole stack)
+    pub position: Vec2,
     pub size: Vec2,
+    /// Child position relative to ours, from the last layout
+    child_offset: Vec2,
 }
 
-impl Spacer {
-    pub fn new(size: Vec2) -> Self {
-        Self { size }
+/// Min and max length of one axis of a positioned child in `area`
+fn positioned_span(start: Option<f32>, end: Option<f32>, length: Option<f32>, area: f32) -> (f32, f32) {
+    match (start, end, length) {
+        (Some(start), Some(end), _) => {
+            let length = (area - start - end).max(0.0);
+            (length, length)
+        }
+        (_, _, Some(length)) => (length, length),
+        (start, end, None) => (0.0, (area - start.unwrap_or(0.0) - end.unwrap_or(0.0)).max(0.0)),
+    }
+}
+
+/// Offset along one axis of a positioned child `length` long in `area`
+fn positioned_offset(start: Option<f32>, end: Option<f32>, length: f32, area: f32) -> f32 {
+    match (start, end) {
+        (Some(start), _) => start,
+        _ if !area.is_finite() => 0.0,
+        (None, Some(end)) => area - end - length,
+        (None, None) => (area - length) / 2.0,
+    }
+}
+
+impl Positioned {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            left: None,
+            top: None,
+            right: None,
+            bottom: None,
+            width: None,
+            height: None,
+            child,
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            child_offset: Vec2::ZERO,
+        }
     }
     
-    pub fn horizontal(width: f32) -> Self {
-        Self { size: Vec2::new(width, 0.0) }
+    /// Stretch the child over the whole stack
+    pub fn fill(child: Box<dyn Widget>) -> Self {
+        Self::new(child).with_left(0.0).with_top(0.0).with_right(0.0).with_bottom(0.0)
     }
     
-    pub fn vertical(height: f32) -> Self {
-        Self { size: Vec2::new(0.0, height) }
+    pub fn with_left(mut self, left: f32) -> Self {
+        self.left = Some(left);
+        self
+    }
+    
+    pub fn with_top(mut self, top: f32) -> Self {
+        self.top = Some(top);
+        self
+    }
+    
+    pub fn with_right(mut self, right: f32) -> Self {
+        self.right = Some(right);
+        self
+    }
+    
+    pub fn with_bottom(mut self, bottom: f32) -> Self {
+        self.bottom = Some(bottom);
+        self
+    }
+    
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+    
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
     }
 }
 
-impl Widget for Spacer {
-    fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
-        self.size
+impl Widget for Positioned {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        // The space handed down is the area to position in
+        let size = self.layout_with_constraints(BoxConstraints::tight(Size::new(max_size.x, max_size.y)));
+        self.set_position(Offset::new(origin.x, origin.y));
+        Vec2::new(size.width, size.height)
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let (min_width, max_width) = positioned_span(self.left, self.right, self.width, constraints.max_width);
+        let (min_height, max_height) = positioned_span(self.top, self.bottom, self.height, constraints.max_height);
+        let child_constraints = BoxConstraints::new(min_width, max_width, min_height, max_height);
+        let child = child_constraints.constrain(self.child.layout_child_with_constraints(child_constraints));
+        
+        let size = constraints.biggest_or(child);
+        self.child_offset = Vec2::new(
+            positioned_offset(self.left, self.right, child.width, constraints.max_width),
+            positioned_offset(self.top, self.bottom, child.height, constraints.max_height),
+        );
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position + Offset::from(self.child_offset));
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn positioned(&self) -> Option<&Positioned> {
+        Some(self)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        let edges = [("left", self.left), ("top", self.top), ("right", self.right), ("bottom", self.bottom), ("width", self.width), ("height", self.height)];
+        for (name, value) in edges {
+            if let Some(value) = value {
+                props.push((name, format!("{:.0}", value)));
+            }
+        }
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
+}
+
+// =============================================================================
+// SPACER
+// =============================================================================
+
+/// Flexible spacing element
+pub struct Spacer {
+    pub size: Vec2,
+    /// Size reported by the last layout pass
+    layout_size: Option<Vec2>,
+}
+
+impl Spacer {
+    pub fn new(size: Vec2) -> Self {
+        Self { size, layout_size: None }
+    }
+    
+    pub fn horizontal(width: f32) -> Self {
+        Self::new(Vec2::new(width, 0.0))
+    }
+    
+    pub fn vertical(height: f32) -> Self {
+        Self::new(Vec2::new(0.0, height))
+    }
+}
+
+impl Widget for Spacer {
+    fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.layout_size = Some(self.size);
+        self.size
+    }
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_size = Some(self.size);
+        constraints.constrain(Size::new(self.size.x, self.size.y))
+    }
+    fn needs_layout(&self) -> bool {
+        self.layout_size != Some(self.size)
     }
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool { false }
     fn update(&mut self, _dt: f32) {}
@@ -287,68 +986,822 @@ impl Widget for Spacer {
 // ALIGN
 // =============================================================================
 
-/// Alignment options for Align widget
+/// Where `Align` puts its child in the space it is given
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Alignment {
-    Center,
     TopLeft,
+    TopCenter,
     TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
     BottomLeft,
+    BottomCenter,
     BottomRight,
+    /// Fraction of the free space left of and above the child: (0, 0) is
+    /// top-left, (1, 1) bottom-right (see `Alignment::new`)
+    Fraction(Vec2),
+}
+
+impl Alignment {
+    /// Anchor at a fraction of the free space on each axis
+    pub fn new(x: f32, y: f32) -> Self {
+        Alignment::Fraction(Vec2::new(x, y))
+    }
+    
+    /// This anchor as a fraction of the free space on each axis
+    pub fn fraction(self) -> Vec2 {
+        match self {
+            Alignment::TopLeft => Vec2::new(0.0, 0.0),
+            Alignment::TopCenter => Vec2::new(0.5, 0.0),
+            Alignment::TopRight => Vec2::new(1.0, 0.0),
+            Alignment::CenterLeft => Vec2::new(0.0, 0.5),
+            Alignment::Center => Vec2::new(0.5, 0.5),
+            Alignment::CenterRight => Vec2::new(1.0, 0.5),
+            Alignment::BottomLeft => Vec2::new(0.0, 1.0),
+            Alignment::BottomCenter => Vec2::new(0.5, 1.0),
+            Alignment::BottomRight => Vec2::new(1.0, 1.0),
+            Alignment::Fraction(fraction) => fraction,
+        }
+    }
 }
 
 /// Alignment wrapper
 pub struct Align {
     pub alignment: Alignment,
+    /// Pixels added to the aligned position (e.g. a margin from the corner)
+    pub offset: Vec2,
     pub child: Box<dyn Widget>,
     pub position: Vec2,
     pub size: Vec2,
+    /// Child position relative to ours, from the last constraint layout
+    child_offset: Vec2,
+    /// Max size and resulting size of the child's last legacy layout
+    measured: Option<(Vec2, Vec2)>,
+    layout_cache: LayoutCache,
 }
 
 impl Align {
     pub fn new(alignment: Alignment, child: Box<dyn Widget>) -> Self {
         Self {
             alignment,
+            offset: Vec2::ZERO,
             child,
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            child_offset: Vec2::ZERO,
+            measured: None,
+            layout_cache: LayoutCache::new(),
         }
     }
     
     pub fn center(child: Box<dyn Widget>) -> Self {
         Self::new(Alignment::Center, child)
     }
+    
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
 }
 
 impl Widget for Align {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
         self.position = origin;
         self.size = max_size;
         
-        let child_size = self.child.layout(origin, max_size);
-        
-        let final_pos = match self.alignment {
-            Alignment::Center => origin + (max_size - child_size) * 0.5,
-            Alignment::TopLeft => origin,
-            Alignment::TopRight => Vec2::new(origin.x + max_size.x - child_size.x, origin.y),
-            Alignment::BottomLeft => Vec2::new(origin.x, origin.y + max_size.y - child_size.y),
-            Alignment::BottomRight => origin + max_size - child_size,
+        // Legacy layout places as it sizes, so an unchanged child is
+        // sized from last time and laid out once, where it belongs
+        let (child_size, measured_at) = match self.measured {
+            Some((max, size)) if max == max_size && !self.child.needs_layout() => (size, None),
+            _ => (self.child.layout_child(origin, max_size), Some(origin)),
         };
         
-        self.child.layout(final_pos, max_size);
+        let final_pos = origin + (max_size - child_size) * self.alignment.fraction() + self.offset;
+        
+        if measured_at != Some(final_pos) {
+            self.child.layout_child(final_pos, max_size);
+        }
+        self.measured = Some((max_size, child_size));
         
+        self.layout_cache.store(origin, max_size, self.size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        self.measured = None;
+        let child = self.child.layout_child_with_constraints(constraints.loosen());
+        let size = constraints.biggest_or(child);
+        let free = Vec2::new(size.width - child.width, size.height - child.height);
+        self.child_offset = free * self.alignment.fraction() + self.offset;
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position + Offset::from(self.child_offset));
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("alignment", format!("{:?}", self.alignment)));
+        if self.offset != Vec2::ZERO {
+            props.push(("offset", format!("{:.0}, {:.0}", self.offset.x, self.offset.y)));
+        }
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
+}
+
+// =============================================================================
+// PADDING
+// =============================================================================
+
+/// Empty space around a child, set per side
+pub struct Padding {
+    pub insets: EdgeInsets,
+    pub child: Box<dyn Widget>,
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl Padding {
+    pub fn new(insets: EdgeInsets, child: Box<dyn Widget>) -> Self {
+        Self {
+            insets,
+            child,
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+        }
+    }
+    
+    /// Same space on all sides
+    pub fn all(value: f32, child: Box<dyn Widget>) -> Self {
+        Self::new(EdgeInsets::all(value), child)
+    }
+}
+
+impl Widget for Padding {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let inset = self.insets.size();
+        let inner = (max_size - Vec2::new(inset.width, inset.height)).max(Vec2::ZERO);
+        let child_size = self.child.layout_child(origin + Vec2::new(self.insets.left, self.insets.top), inner);
+        self.size = child_size + Vec2::new(inset.width, inset.height);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let child = self.child.layout_child_with_constraints(constraints.deflate(self.insets));
+        let size = constraints.constrain(Size::new(child.width + self.insets.horizontal(), child.height + self.insets.vertical()));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position + self.insets.top_left());
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        let EdgeInsets { top, right, bottom, left } = self.insets;
+        props.push(("insets", format!("{:.0} {:.0} {:.0} {:.0}", top, right, bottom, left)));
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        self.insets
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
+}
+
+// =============================================================================
+// ASPECT RATIO
+// =============================================================================
+
+/// Sizes its child to the largest box of a fixed width/height ratio that
+/// fits the space it is given, so media doesn't stretch with its parent
+pub struct AspectRatio {
+    /// Width divided by height
+    pub ratio: f32,
+    pub child: Box<dyn Widget>,
+    pub position: Vec2,
+    pub size: Vec2,
+    layout_cache: LayoutCache,
+}
+
+impl AspectRatio {
+    pub fn new(ratio: f32, child: Box<dyn Widget>) -> Self {
+        Self {
+            ratio,
+            child,
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            layout_cache: LayoutCache::new(),
+        }
+    }
+    
+    /// Largest size of this ratio within `max`; an unbounded side is
+    /// derived from the other one
+    fn fit(&self, max: Size) -> Size {
+        let width = max.width.min(max.height * self.ratio);
+        if self.ratio > 0.0 && width.is_finite() {
+            Size::new(width, width / self.ratio)
+        } else {
+            Size::ZERO
+        }
+    }
+}
+
+impl Widget for AspectRatio {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
+        self.position = origin;
+        let fitted = self.fit(Size::new(max_size.x, max_size.y));
+        self.size = Vec2::new(fitted.width, fitted.height);
+        
+        self.child.layout_child(origin, self.size);
+        
+        self.layout_cache.store(origin, max_size, self.size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let max = Size::new(constraints.max_width, constraints.max_height);
+        let size = constraints.constrain(self.fit(max));
+        self.child.layout_child_with_constraints(BoxConstraints::tight(size));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("ratio", format!("{:.3}", self.ratio)));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
+}
+
+// =============================================================================
+// SIZED BOX
+// =============================================================================
+
+/// How `SizedBox` sizes one axis
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Length {
+    /// The child's own size
+    Auto,
+    /// Fixed size in pixels
+    Px(f32),
+    /// Fraction of the space the parent offers (0.5 = half)
+    Percent(f32),
+    /// All the space the parent offers
+    Fill,
+}
+
+impl Length {
+    /// Size on this axis given `available` space, `None` for the child's own
+    /// size (also for `Percent` and `Fill` when the space is unbounded)
+    pub fn resolve(self, available: f32) -> Option<f32> {
+        match self {
+            Length::Auto => None,
+            Length::Px(px) => Some(px),
+            Length::Percent(fraction) => available.is_finite().then_some(available * fraction),
+            Length::Fill => available.is_finite().then_some(available),
+        }
+    }
+}
+
+impl From<f32> for Length {
+    fn from(px: f32) -> Self {
+        Length::Px(px)
+    }
+}
+
+/// Gives its child a fixed, fractional or full width and/or height of the
+/// parent's space, forcing it to that size
+pub struct SizedBox {
+    pub width: Length,
+    pub height: Length,
+    pub child: Box<dyn Widget>,
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl SizedBox {
+    pub fn new(width: impl Into<Length>, height: impl Into<Length>, child: Box<dyn Widget>) -> Self {
+        Self {
+            width: width.into(),
+            height: height.into(),
+            child,
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+        }
+    }
+    
+    /// Fill the parent's space on both axes
+    pub fn fill(child: Box<dyn Widget>) -> Self {
+        Self::new(Length::Fill, Length::Fill, child)
+    }
+    
+    /// Take a fraction of the parent's width and height
+    pub fn fractional(width: f32, height: f32, child: Box<dyn Widget>) -> Self {
+        Self::new(Length::Percent(width), Length::Percent(height), child)
+    }
+    
+    pub fn with_width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+    
+    pub fn with_height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+    
+    /// `constraints` made tight on every axis that isn't `Auto`
+    fn child_constraints(&self, constraints: BoxConstraints) -> BoxConstraints {
+        let mut child = constraints;
+        if let Some(width) = self.width.resolve(constraints.max_width) {
+            child.min_width = constraints.constrain_width(width);
+            child.max_width = child.min_width;
+        }
+        if let Some(height) = self.height.resolve(constraints.max_height) {
+            child.min_height = constraints.constrain_height(height);
+            child.max_height = child.min_height;
+        }
+        child
+    }
+}
+
+impl Widget for SizedBox {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        // Legacy layout can't force a size, so go through constraints
+        let size = self.layout_with_constraints(BoxConstraints::loose(Size::new(max_size.x, max_size.y)));
+        self.set_position(Offset::new(origin.x, origin.y));
+        Vec2::new(size.width, size.height)
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let child_constraints = self.child_constraints(constraints);
+        let size = child_constraints.constrain(self.child.layout_child_with_constraints(child_constraints));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("width", format!("{:?}", self.width)));
+        props.push(("height", format!("{:?}", self.height)));
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        self.child.render_bounds()
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        self.child.opaque_rect()
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.handle_event(event, mouse_pos)
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.child.update(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        self.child.split_update(dt, jobs);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.child.render(renderer);
+    }
+}
+
+// =============================================================================
+// DOCK PANEL
+// =============================================================================
+
+/// Edge a `DockPanel` child is pinned to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dock {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    /// Whatever the edge children leave
+    Center,
+}
+
+/// Pins children to its edges in the order they were added, each taking a
+/// strip off the space the earlier ones left; `Center` children share the
+/// rest. Fills the space it is given.
+/// 
+/// Center children are drawn first, so edge children such as a header stay
+/// above content dragged or scrolled under them.
+pub struct DockPanel {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Children, each with the edge it is pinned to
+    pub children: Vec<(Box<dyn Widget>, Dock)>,
+    /// Child positions relative to ours, from the last constraint layout
+    offsets: Vec<Vec2>,
+    layout_cache: LayoutCache,
+}
+
+impl DockPanel {
+    pub fn new() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            children: Vec::new(),
+            offsets: Vec::new(),
+            layout_cache: LayoutCache::new(),
+        }
+    }
+    
+    pub fn add_docked(mut self, dock: Dock, child: Box<dyn Widget>) -> Self {
+        self.children.push((child, dock));
+        self
+    }
+    
+    /// Add a child filling the center
+    pub fn add_child(self, child: Box<dyn Widget>) -> Self {
+        self.add_docked(Dock::Center, child)
+    }
+    
+    /// Edge child `index` is pinned to
+    pub fn dock(&self, index: usize) -> Dock {
+        self.children.get(index).map_or(Dock::Center, |(_, dock)| *dock)
+    }
+    
+    /// Child indices in paint order: center children, then edges
+    fn paint_order(&self) -> impl Iterator<Item = usize> + '_ {
+        let count = self.children.len();
+        let center = (0..count).filter(|&i| self.dock(i) == Dock::Center);
+        center.chain((0..count).filter(|&i| self.dock(i) != Dock::Center))
+    }
+}
+
+impl Default for DockPanel {
+    fn default() -> Self { Self::new() }
+}
+
+impl Widget for DockPanel {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
+        self.position = origin;
+        self.size = max_size;
+        
+        let mut free = Rect::new(origin, max_size);
+        for i in 0..self.children.len() {
+            let dock = self.dock(i);
+            let child = &mut self.children[i].0;
+            match dock {
+                Dock::Top => free.min.y += child.layout_child(free.min, free.size()).y,
+                Dock::Left => free.min.x += child.layout_child(free.min, free.size()).x,
+                Dock::Bottom => {
+                    let height = child.layout_child(free.min, free.size()).y;
+                    free.max.y -= height;
+                    child.layout_child(Vec2::new(free.min.x, free.max.y), Vec2::new(free.size().x, height));
+                }
+                Dock::Right => {
+                    let width = child.layout_child(free.min, free.size()).x;
+                    free.max.x -= width;
+                    child.layout_child(Vec2::new(free.max.x, free.min.y), Vec2::new(width, free.size().y));
+                }
+                Dock::Center => {}
+            }
+            free.max = free.max.max(free.min);
+        }
+        for i in 0..self.children.len() {
+            if self.dock(i) == Dock::Center {
+                self.children[i].0.layout_child(free.min, free.size());
+            }
+        }
+        
+        self.layout_cache.store(origin, max_size, self.size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let size = constraints.biggest_or(Size::ZERO);
+        self.offsets = vec![Vec2::ZERO; self.children.len()];
+        
+        // Edge children span the free space across the edge
+        let mut free = Rect::new(Vec2::ZERO, Vec2::new(size.width, size.height));
+        for i in 0..self.children.len() {
+            let dock = self.dock(i);
+            let child = &mut self.children[i].0;
+            let available = free.size();
+            match dock {
+                Dock::Top | Dock::Bottom => {
+                    let height = child.layout_child_with_constraints(BoxConstraints::new(available.x, available.x, 0.0, available.y)).height;
+                    if dock == Dock::Top {
+                        self.offsets[i] = free.min;
+                        free.min.y += height;
+                    } else {
+                        free.max.y -= height;
+                        self.offsets[i] = Vec2::new(free.min.x, free.max.y);
+                    }
+                }
+                Dock::Left | Dock::Right => {
+                    let width = child.layout_child_with_constraints(BoxConstraints::new(0.0, available.x, available.y, available.y)).width;
+                    if dock == Dock::Left {
+                        self.offsets[i] = free.min;
+                        free.min.x += width;
+                    } else {
+                        free.max.x -= width;
+                        self.offsets[i] = Vec2::new(free.max.x, free.min.y);
+                    }
+                }
+                Dock::Center => {}
+            }
+            free.max = free.max.max(free.min);
+        }
+        for i in 0..self.children.len() {
+            if self.dock(i) == Dock::Center {
+                self.children[i].0.layout_child_with_constraints(BoxConstraints::tight(free.size().into()));
+                self.offsets[i] = free.min;
+            }
+        }
+        
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        place_children(self.children.iter_mut().map(|(child, _)| child), &self.offsets, position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        for i in self.paint_order() {
+            visitor(self.children[i].0.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        let order: Vec<usize> = self.paint_order().collect();
+        let mut children: Vec<Option<&'a mut Box<dyn Widget>>> = self.children.iter_mut().map(|(child, _)| Some(child)).collect();
+        for i in order {
+            if let Some(child) = children[i].take() {
+                visitor(child.as_mut());
+            }
+        }
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("children", self.children.len().to_string()));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.children.iter().any(|(c, _)| c.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|(c, _)| c.is_animating())
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        self.child.handle_event(event, mouse_pos)
+        // Topmost first: edges, then center
+        let order: Vec<usize> = self.paint_order().collect();
+        order.into_iter().rev().any(|i| self.children[i].0.handle_event(event, mouse_pos))
     }
-
+    
     fn update(&mut self, dt: f32) {
-        self.child.update(dt);
+        self.children.iter_mut().for_each(|(c, _)| c.update(dt));
     }
 
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        for (child, _) in &mut self.children {
+            child.split_update(dt, jobs);
+        }
+    }
+    
     fn render(&self, renderer: &mut GlassRenderer) {
-        self.child.render(renderer);
+        for i in self.paint_order() {
+            self.children[i].0.render(renderer);
+        }
     }
 }
 
@@ -356,14 +1809,47 @@ impl Widget for Align {
 // GRID
 // =============================================================================
 
+/// How wide a `Grid` column is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridTrack {
+    /// Fixed width in pixels
+    Px(f32),
+    /// As wide as the widest child spanning only this column
+    Auto,
+    /// Share of the width the `Px` and `Auto` columns leave, like CSS `fr`
+    /// (sized like `Auto` when the width is unbounded)
+    Fr(f32),
+}
+
+/// Where auto-placement put a `Grid` child
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridCell {
+    pub column: usize,
+    pub row: usize,
+    pub column_span: usize,
+    pub row_span: usize,
+}
+
 /// CSS Grid-inspired multi-column layout
+/// 
+/// Children fill the grid row by row, each taking the first free spot its
+/// span fits in (spots skipped for a wide child stay empty, as in CSS).
 pub struct Grid {
     pub position: Vec2,
     pub size: Vec2,
-    pub children: Vec<Box<dyn Widget>>,
+    /// Children, each with its (column span, row span)
+    pub children: Vec<(Box<dyn Widget>, (usize, usize))>,
     pub columns: usize,
+    /// Column sizing; columns past the end are `Fr(1.0)`
+    pub tracks: Vec<GridTrack>,
+    /// Gap between columns
     pub gap: f32,
-    pub padding: f32,
+    /// Gap between rows
+    pub row_gap: f32,
+    pub padding: EdgeInsets,
+    /// Child positions relative to ours, from the last constraint layout
+    offsets: Vec<Vec2>,
+    layout_cache: LayoutCache,
 }
 
 impl Grid {
@@ -373,58 +1859,271 @@ impl Grid {
             size: Vec2::ZERO,
             children: Vec::new(),
             columns: columns.max(1),
+            tracks: Vec::new(),
             gap: 10.0,
-            padding: 10.0,
+            row_gap: 10.0,
+            padding: EdgeInsets::all(10.0),
+            offsets: Vec::new(),
+            layout_cache: LayoutCache::new(),
         }
     }
     
-    pub fn add_child(mut self, child: Box<dyn Widget>) -> Self {
-        self.children.push(child);
+    /// One column per track
+    pub fn with_columns(mut self, tracks: Vec<GridTrack>) -> Self {
+        self.columns = tracks.len().max(1);
+        self.tracks = tracks;
+        self
+    }
+    
+    pub fn add_child(self, child: Box<dyn Widget>) -> Self {
+        self.add_spanning_child(child, 1, 1)
+    }
+    
+    /// Add a child covering `column_span` columns and `row_span` rows
+    pub fn add_spanning_child(mut self, child: Box<dyn Widget>, column_span: usize, row_span: usize) -> Self {
+        self.children.push((child, (column_span.max(1), row_span.max(1))));
+        self
+    }
+    
+    /// Gap between both columns and rows
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self.row_gap = gap;
         self
     }
     
-    pub fn with_gap(mut self, gap: f32) -> Self { self.gap = gap; self }
+    pub fn with_row_gap(mut self, row_gap: f32) -> Self {
+        self.row_gap = row_gap;
+        self
+    }
+    
+    /// Same padding on all sides
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = EdgeInsets::all(padding);
+        self
+    }
+    
+    pub fn with_insets(mut self, insets: EdgeInsets) -> Self {
+        self.padding = insets;
+        self
+    }
+    
+    fn track(&self, column: usize) -> GridTrack {
+        self.tracks.get(column).copied().unwrap_or(GridTrack::Fr(1.0))
+    }
+    
+    /// Cell of every child, in child order
+    pub fn cells(&self) -> Vec<GridCell> {
+        let cols = self.columns.max(1);
+        let mut occupied: Vec<Vec<bool>> = Vec::new();
+        let mut cursor = (0, 0);
+        self.children.iter().map(|&(_, (column_span, row_span))| {
+            let column_span = column_span.min(cols);
+            let (mut row, mut column) = cursor;
+            loop {
+                if column + column_span > cols {
+                    row += 1;
+                    column = 0;
+                    continue;
+                }
+                let free = (row..row + row_span).all(|r| {
+                    occupied.get(r).is_none_or(|cells| !cells[column..column + column_span].contains(&true))
+                });
+                if free {
+                    break;
+                }
+                column += 1;
+            }
+            if occupied.len() < row + row_span {
+                occupied.resize(row + row_span, vec![false; cols]);
+            }
+            for cells in &mut occupied[row..row + row_span] {
+                cells[column..column + column_span].fill(true);
+            }
+            cursor = (row, column + column_span);
+            GridCell { column, row, column_span, row_span }
+        }).collect()
+    }
+    
+    /// Each child's rect relative to the content origin, and the content
+    /// height, for `content_width` (unbounded if infinite); `measure` lays
+    /// a child out in a max size and returns its size
+    fn solve(&mut self, content_width: f32, measure: &mut dyn FnMut(&mut dyn Widget, Vec2) -> Vec2) -> (Vec<Rect>, f32) {
+        let cells = self.cells();
+        let cols = self.columns.max(1);
+        let bounded = content_width.is_finite();
+        let measure_width = if bounded { content_width } else { 10000.0 };
+        
+        // Columns: fixed, then auto from single-column children, then fr
+        // shares of what's left
+        let mut widths = vec![0.0f32; cols];
+        let mut used = (cols - 1) as f32 * self.gap;
+        let mut fr_total = 0.0;
+        for (column, width) in widths.iter_mut().enumerate() {
+            match self.track(column) {
+                GridTrack::Px(px) => *width = px,
+                GridTrack::Fr(fr) if bounded => {
+                    fr_total += fr.max(0.0);
+                    continue;
+                }
+                _ => {
+                    for (child, cell) in self.children.iter_mut().map(|(child, _)| child).zip(&cells) {
+                        if cell.column == column && cell.column_span == 1 {
+                            *width = width.max(measure(child.as_mut(), Vec2::new(measure_width, 10000.0)).x);
+                        }
+                    }
+                }
+            }
+            used += *width;
+        }
+        if fr_total > 0.0 {
+            let leftover = (content_width - used).max(0.0);
+            for (column, width) in widths.iter_mut().enumerate() {
+                if let GridTrack::Fr(fr) = self.track(column) {
+                    *width = leftover * fr.max(0.0) / fr_total;
+                }
+            }
+        }
+        let span_width = |cell: &GridCell| {
+            widths[cell.column..cell.column + cell.column_span].iter().sum::<f32>() + (cell.column_span - 1) as f32 * self.gap
+        };
+        
+        // Rows: tallest single-row child, then stretch the last row of any
+        // multi-row child that still doesn't fit
+        let rows = cells.iter().map(|c| c.row + c.row_span).max().unwrap_or(0);
+        let mut heights = vec![0.0f32; rows];
+        let measured: Vec<f32> = self.children.iter_mut().map(|(child, _)| child).zip(&cells)
+            .map(|(child, cell)| measure(child.as_mut(), Vec2::new(span_width(cell), 10000.0)).y)
+            .collect();
+        for (cell, &height) in cells.iter().zip(&measured) {
+            if cell.row_span == 1 {
+                heights[cell.row] = heights[cell.row].max(height);
+            }
+        }
+        for (cell, &height) in cells.iter().zip(&measured) {
+            let last = cell.row + cell.row_span - 1;
+            let spanned = heights[cell.row..=last].iter().sum::<f32>() + (cell.row_span - 1) as f32 * self.row_gap;
+            if height > spanned {
+                heights[last] += height - spanned;
+            }
+        }
+        
+        let start = |sizes: &[f32], index: usize, gap: f32| sizes[..index].iter().sum::<f32>() + index as f32 * gap;
+        let rects = cells.iter().map(|cell| {
+            let span_height = heights[cell.row..cell.row + cell.row_span].iter().sum::<f32>() + (cell.row_span - 1) as f32 * self.row_gap;
+            Rect::new(
+                Vec2::new(start(&widths, cell.column, self.gap), start(&heights, cell.row, self.row_gap)),
+                Vec2::new(span_width(cell), span_height),
+            )
+        }).collect();
+        let content_height = heights.iter().sum::<f32>() + rows.saturating_sub(1) as f32 * self.row_gap;
+        (rects, content_height)
+    }
 }
 
 impl Widget for Grid {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
         self.position = origin;
-        let cols = self.columns.max(1);
-        let content_width = max_size.x - self.padding * 2.0;
-        let cell_width = (content_width - (cols - 1) as f32 * self.gap) / cols as f32;
-        
-        let mut max_row_height = 0.0f32;
-        let mut total_height = self.padding;
-        let child_count = self.children.len();
+        let content_origin = origin + Vec2::new(self.padding.left, self.padding.top);
+        let (rects, content_height) = self.solve(max_size.x - self.padding.horizontal(), &mut |child, max| child.layout_child(content_origin, max));
         
-        for (i, child) in self.children.iter_mut().enumerate() {
-            let col = i % cols;
-            let x = origin.x + self.padding + col as f32 * (cell_width + self.gap);
-            let y = origin.y + total_height;
-            
-            let child_size = child.layout(Vec2::new(x, y), Vec2::new(cell_width, 10000.0));
-            max_row_height = max_row_height.max(child_size.y);
-            
-            if col == cols - 1 || i == child_count - 1 {
-                total_height += max_row_height + self.gap;
-                max_row_height = 0.0;
-            }
+        for (child, rect) in self.children.iter_mut().map(|(child, _)| child).zip(&rects) {
+            child.layout_child(content_origin + rect.min, rect.size());
         }
         
-        self.size = Vec2::new(max_size.x, total_height + self.padding);
+        self.size = Vec2::new(max_size.x, content_height + self.padding.vertical());
+        self.layout_cache.store(origin, max_size, self.size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let inner = constraints.deflate(self.padding);
+        let (rects, content_height) = self.solve(inner.max_width, &mut |child, max| {
+            child.layout_child_with_constraints(BoxConstraints::loose(Size::new(max.x, max.y))).into()
+        });
+        
+        self.offsets.clear();
+        let mut content_width = 0.0f32;
+        for (child, rect) in self.children.iter_mut().map(|(child, _)| child).zip(&rects) {
+            child.layout_child_with_constraints(BoxConstraints::loose(rect.size().into()));
+            self.offsets.push(rect.min + Vec2::new(self.padding.left, self.padding.top));
+            content_width = content_width.max(rect.max.x);
+        }
+        
+        let natural = Size::new(content_width + self.padding.horizontal(), content_height + self.padding.vertical());
+        let size = Size::new(constraints.biggest_or(natural).width, constraints.constrain_height(natural.height));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        place_children(self.children.iter_mut().map(|(child, _)| child), &self.offsets, position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        for (child, _) in &self.children {
+            visitor(child.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        for (child, _) in &mut self.children {
+            visitor(child.as_mut());
+        }
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("children", self.children.len().to_string()));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        self.padding
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.children.iter().any(|(c, _)| c.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|(c, _)| c.is_animating())
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        self.children.iter_mut().any(|c| c.handle_event(event, mouse_pos))
+        self.children.iter_mut().any(|(c, _)| c.handle_event(event, mouse_pos))
     }
     
     fn update(&mut self, dt: f32) { 
-        self.children.iter_mut().for_each(|c| c.update(dt)); 
+        self.children.iter_mut().for_each(|(c, _)| c.update(dt)); 
+    }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        for (child, _) in &mut self.children {
+            child.split_update(dt, jobs);
+        }
     }
     
     fn render(&self, renderer: &mut GlassRenderer) { 
-        self.children.iter().for_each(|c| c.render(renderer)); 
+        self.children.iter().for_each(|(c, _)| c.render(renderer)); 
     }
 }
 
@@ -435,6 +2134,32 @@ impl Widget for Grid {
 #[derive(Clone, Copy, PartialEq)]
 pub enum FlexDirection { Row, Column }
 
+impl FlexDirection {
+    /// Extent of `v` along this direction
+    pub(crate) fn main(self, v: Vec2) -> f32 {
+        match self {
+            FlexDirection::Row => v.x,
+            FlexDirection::Column => v.y,
+        }
+    }
+    
+    /// Extent of `v` across this direction
+    pub(crate) fn cross(self, v: Vec2) -> f32 {
+        match self {
+            FlexDirection::Row => v.y,
+            FlexDirection::Column => v.x,
+        }
+    }
+    
+    /// Vector with `main` along this direction and `cross` across it
+    pub(crate) fn vec(self, main: f32, cross: f32) -> Vec2 {
+        match self {
+            FlexDirection::Row => Vec2::new(main, cross),
+            FlexDirection::Column => Vec2::new(cross, main),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum FlexJustify { Start, Center, End, SpaceBetween, SpaceAround }
 
@@ -450,7 +2175,10 @@ pub struct Flex {
     pub justify: FlexJustify,
     pub align: FlexAlign,
     pub gap: f32,
-    pub padding: f32,
+    pub padding: EdgeInsets,
+    /// Child positions relative to ours, from the last constraint layout
+    offsets: Vec<Vec2>,
+    layout_cache: LayoutCache,
 }
 
 impl Flex {
@@ -463,7 +2191,9 @@ impl Flex {
             justify: FlexJustify::Start,
             align: FlexAlign::Start,
             gap: 10.0,
-            padding: 10.0,
+            padding: EdgeInsets::all(10.0),
+            offsets: Vec::new(),
+            layout_cache: LayoutCache::new(),
         }
     }
     
@@ -478,13 +2208,45 @@ impl Flex {
     pub fn with_justify(mut self, j: FlexJustify) -> Self { self.justify = j; self }
     pub fn with_align(mut self, a: FlexAlign) -> Self { self.align = a; self }
     pub fn with_gap(mut self, g: f32) -> Self { self.gap = g; self }
+    
+    /// Same padding on all sides
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = EdgeInsets::all(padding);
+        self
+    }
+    
+    pub fn with_insets(mut self, insets: EdgeInsets) -> Self {
+        self.padding = insets;
+        self
+    }
+    
+    /// Start offset and spacing along the main axis for `free_space`
+    fn justify_spacing(&self, free_space: f32) -> (f32, f32) {
+        match self.justify {
+            FlexJustify::Start => (0.0, self.gap),
+            FlexJustify::End => (free_space, self.gap),
+            FlexJustify::Center => (free_space / 2.0, self.gap),
+            FlexJustify::SpaceBetween if self.children.len() > 1 => 
+                (0.0, self.gap + free_space / (self.children.len() - 1) as f32),
+            FlexJustify::SpaceAround if !self.children.is_empty() => {
+                let s = free_space / self.children.len() as f32;
+                (s / 2.0, self.gap + s)
+            },
+            _ => (0.0, self.gap),
+        }
+    }
 }
 
 impl Widget for Flex {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        if let Some(size) = self.layout_cache.get(origin, max_size) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
         self.position = origin;
         let is_row = self.direction == FlexDirection::Row;
-        let content_area = max_size - Vec2::splat(self.padding * 2.0);
+        let content_area = max_size - Vec2::new(self.padding.horizontal(), self.padding.vertical());
         
         // First pass: measure children
         let mut child_sizes = Vec::new();
@@ -492,7 +2254,7 @@ impl Widget for Flex {
         let mut max_cross = 0.0f32;
         
         for child in &mut self.children {
-            let size = child.layout(origin, content_area);
+            let size = child.layout_child(origin, content_area);
             let (main, cross) = if is_row { (size.x, size.y) } else { (size.y, size.x) };
             total_main += main;
             max_cross = max_cross.max(cross);
@@ -503,19 +2265,7 @@ impl Widget for Flex {
         let total_gaps = self.gap * (self.children.len().saturating_sub(1)) as f32;
         let free_space = (main_axis - total_main - total_gaps).max(0.0);
         
-        // Calculate start offset and spacing
-        let (start_offset, item_spacing) = match self.justify {
-            FlexJustify::Start => (0.0, self.gap),
-            FlexJustify::End => (free_space, self.gap),
-            FlexJustify::Center => (free_space / 2.0, self.gap),
-            FlexJustify::SpaceBetween if self.children.len() > 1 => 
-                (0.0, self.gap + free_space / (self.children.len() - 1) as f32),
-            FlexJustify::SpaceAround if !self.children.is_empty() => {
-                let s = free_space / self.children.len() as f32;
-                (s / 2.0, self.gap + s)
-            },
-            _ => (0.0, self.gap),
-        };
+        let (start_offset, item_spacing) = self.justify_spacing(free_space);
         
         // Second pass: position children
         let mut cursor = start_offset;
@@ -531,22 +2281,126 @@ impl Widget for Flex {
             };
             
             let pos = if is_row {
-                Vec2::new(origin.x + self.padding + cursor, origin.y + self.padding + cross_offset)
+                Vec2::new(origin.x + self.padding.left + cursor, origin.y + self.padding.top + cross_offset)
             } else {
-                Vec2::new(origin.x + self.padding + cross_offset, origin.y + self.padding + cursor)
+                Vec2::new(origin.x + self.padding.left + cross_offset, origin.y + self.padding.top + cursor)
             };
             
-            child.layout(pos, size);
+            child.layout_child(pos, size);
             cursor += main_size + item_spacing;
         }
         
         self.size = if is_row {
-            Vec2::new(max_size.x, max_cross + self.padding * 2.0)
+            Vec2::new(max_size.x, max_cross + self.padding.vertical())
         } else {
-            Vec2::new(max_cross + self.padding * 2.0, cursor - item_spacing + self.padding * 2.0)
+            Vec2::new(max_cross + self.padding.horizontal(), cursor - item_spacing + self.padding.vertical())
         };
+        self.layout_cache.store(origin, max_size, self.size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_cache.invalidate();
+        let is_row = self.direction == FlexDirection::Row;
+        let axes = |s: Size| if is_row { (s.width, s.height) } else { (s.height, s.width) };
+        let inner = constraints.deflate(self.padding).loosen();
+        
+        let mut sizes: Vec<Size> = self.children.iter_mut()
+            .map(|c| c.layout_child_with_constraints(inner))
+            .collect();
+        let max_cross = sizes.iter().map(|&s| axes(s).1).fold(0.0, f32::max);
+        if self.align == FlexAlign::Stretch {
+            for (child, size) in self.children.iter_mut().zip(&mut sizes) {
+                let stretched = if is_row { Size::new(size.width, max_cross) } else { Size::new(max_cross, size.height) };
+                *size = child.layout_child_with_constraints(BoxConstraints::tight(stretched));
+            }
+        }
+        
+        let total_main: f32 = sizes.iter().map(|&s| axes(s).0).sum();
+        let main_axis = if is_row { inner.max_width } else { inner.max_height };
+        let total_gaps = self.gap * (self.children.len().saturating_sub(1)) as f32;
+        let free_space = if main_axis.is_finite() { (main_axis - total_main - total_gaps).max(0.0) } else { 0.0 };
+        let (start_offset, item_spacing) = self.justify_spacing(free_space);
+        
+        self.offsets.clear();
+        let mut cursor = start_offset;
+        for &size in &sizes {
+            let (main_size, cross_size) = axes(size);
+            let cross_offset = match self.align {
+                FlexAlign::Start | FlexAlign::Stretch => 0.0,
+                FlexAlign::End => max_cross - cross_size,
+                FlexAlign::Center => (max_cross - cross_size) / 2.0,
+            };
+            self.offsets.push(if is_row {
+                Vec2::new(self.padding.left + cursor, self.padding.top + cross_offset)
+            } else {
+                Vec2::new(self.padding.left + cross_offset, self.padding.top + cursor)
+            });
+            cursor += main_size + item_spacing;
+        }
+        
+        // Like `layout`, a row fills the width it is given
+        let (main_padding, cross_padding) = if is_row {
+            (self.padding.horizontal(), self.padding.vertical())
+        } else {
+            (self.padding.vertical(), self.padding.horizontal())
+        };
+        let main = (cursor - item_spacing).max(0.0) + main_padding;
+        let cross = max_cross + cross_padding;
+        let size = if is_row {
+            Size::new(constraints.biggest_or(Size::new(main, cross)).width, constraints.constrain_height(cross))
+        } else {
+            constraints.constrain(Size::new(cross, main))
+        };
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        place_children(&mut self.children, &self.offsets, position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        for child in &self.children {
+            visitor(child.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        for child in &mut self.children {
+            visitor(child.as_mut());
+        }
+    }
+    
+    fn is_layout_only(&self) -> bool {
+        true
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("children", self.children.len().to_string()));
+        props.extend(constraint_property(&self.layout_cache));
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        self.padding
+    }
+    
+    fn needs_layout(&self) -> bool {
+        !self.layout_cache.is_valid() || self.children.iter().any(|c| c.needs_layout())
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|c| c.is_animating())
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         self.children.iter_mut().any(|c| c.handle_event(event, mouse_pos))
@@ -555,8 +2409,406 @@ impl Widget for Flex {
     fn update(&mut self, dt: f32) { 
         self.children.iter_mut().for_each(|c| c.update(dt)); 
     }
+
+    fn split_update<'a>(&'a mut self, dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        for child in &mut self.children {
+            child.split_update(dt, jobs);
+        }
+    }
     
     fn render(&self, renderer: &mut GlassRenderer) { 
         self.children.iter().for_each(|c| c.render(renderer)); 
     }
 }
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::{Identified, Label};
+
+    fn names(stack: &Stack) -> Vec<String> {
+        stack.children()
+            .map(|c| c.first_of_type::<Label>().unwrap().text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_flex_children_share_leftover_space() {
+        use crate::widgets::Button;
+        let button = || Box::new(Button::new("Go"));
+        let mut column = Column::new().with_spacing(0.0).with_padding(0.0)
+            .add_child(button())
+            .add_flex_child(button(), 1.0)
+            .add_flex_child(button(), 3.0)
+            .add_child(button());
+        assert_eq!(column.layout(Vec2::ZERO, Vec2::new(200.0, 300.0)).y, 300.0);
+        // Two 44px buttons leave 212px, split 1:3
+        let ys: Vec<f32> = column.children.iter().map(|(c, _)| c.get_position().y).collect();
+        assert_eq!(ys, [0.0, 44.0, 97.0, 256.0]);
+        assert_eq!(column.flex(2), 3.0);
+        
+        let mut row = Row::new().with_spacing(0.0)
+            .add_flex_child(button(), 1.0)
+            .add_child(button());
+        row.layout(Vec2::ZERO, Vec2::new(400.0, 100.0));
+        // 10px padding each side, the fixed 80px button pinned to the end
+        assert_eq!(row.children[1].0.get_position().x, 310.0);
+        assert_eq!(row.get_size().width, 400.0);
+        
+        // Nothing to share in a scroll area's unbounded height
+        let mut scrolled = Column::new().with_spacing(0.0).with_padding(0.0)
+            .add_child(button())
+            .add_flex_child(button(), 1.0);
+        assert_eq!(scrolled.layout(Vec2::ZERO, Vec2::new(200.0, UNBOUNDED_EXTENT)).y, 88.0);
+    }
+
+    #[test]
+    fn test_align_anchors_and_fractions() {
+        use crate::widgets::Button;
+        // A 80x44 button in 400x300 leaves 320x256 free
+        let placed = |alignment, offset| {
+            let mut align = Align::new(alignment, Box::new(Button::new("Go"))).with_offset(offset);
+            align.layout(Vec2::new(10.0, 10.0), Vec2::new(400.0, 300.0));
+            let Offset { x, y } = align.child.get_position();
+            Vec2::new(x, y) - Vec2::splat(10.0)
+        };
+        assert_eq!(placed(Alignment::BottomCenter, Vec2::ZERO), Vec2::new(160.0, 256.0));
+        assert_eq!(placed(Alignment::CenterRight, Vec2::ZERO), Vec2::new(320.0, 128.0));
+        assert_eq!(placed(Alignment::new(0.25, 0.5), Vec2::ZERO), Vec2::new(80.0, 128.0));
+        assert_eq!(placed(Alignment::TopRight, Vec2::new(-16.0, 16.0)), Vec2::new(304.0, 16.0));
+    }
+
+    #[test]
+    fn test_unchanged_subtrees_reuse_layout() {
+        use crate::layout::take_layout_count;
+        let inner = Column::new().add_child(Box::new(Label::new("Status")));
+        let mut root = Column::new()
+            .add_child(Box::new(Label::new("Title")))
+            .add_child(Box::new(Align::new(Alignment::TopLeft, Box::new(inner))));
+        take_layout_count();
+        
+        // Outer column, align, inner column: the top-left child is laid
+        // out once, where it was measured
+        root.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        assert_eq!(take_layout_count(), 3);
+        assert!(!root.needs_layout());
+        root.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        assert_eq!(take_layout_count(), 0);
+        
+        // Moving reuses the inner column's size instead of measuring it again
+        root.layout(Vec2::new(10.0, 0.0), Vec2::new(400.0, 300.0));
+        assert_eq!(take_layout_count(), 3);
+        
+        // A label changing size dirties its ancestors only
+        let tree: &mut dyn Widget = &mut root;
+        tree.widget_at_mut(&[1, 0, 0]).unwrap().downcast_mut::<Label>().unwrap().set_text("A much longer status line");
+        assert!(root.needs_layout());
+        root.layout(Vec2::new(10.0, 0.0), Vec2::new(400.0, 300.0));
+        assert_eq!(take_layout_count(), 3);
+        assert!(!root.children[0].0.needs_layout());
+    }
+    
+    #[test]
+    fn test_input_leaves_dirty_layout_when_their_size_changes() {
+        use crate::widgets::{TagInput, TextInput, Validator};
+        let mut root = Column::new()
+            .add_child(Box::new(TextInput::new("Name")))
+            .add_child(Box::new(TagInput::new("Labels").with_tags(vec!["alpha".to_string()])));
+        assert!(root.needs_layout());
+        root.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        assert!(!root.needs_layout());
+        
+        // A chip that fits on the row doesn't change the height; one that
+        // wraps onto the next does
+        let tree: &mut dyn Widget = &mut root;
+        let tags = tree.widget_at_mut(&[1]).unwrap().downcast_mut::<TagInput>().unwrap();
+        tags.add_tag("beta");
+        assert!(!tags.needs_layout());
+        tags.add_tag("gamma");
+        assert!(root.needs_layout());
+        root.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        assert!(!root.needs_layout());
+        
+        // A validator makes room for the error line
+        let tree: &mut dyn Widget = &mut root;
+        tree.widget_at_mut(&[0]).unwrap().downcast_mut::<TextInput>().unwrap()
+            .validators.push(Validator::required("Required"));
+        assert!(root.needs_layout());
+    }
+
+    #[test]
+    fn test_constraint_layout_sizes_and_places_children() {
+        use crate::widgets::{Button, TextInput};
+        let mut column = Column::new().with_spacing(0.0)
+            .add_child(Box::new(Button::new("Go")))
+            .add_flex_child(Box::new(TextInput::new("Name")), 1.0);
+        let size = column.layout_with_constraints(BoxConstraints::tight(Size::new(200.0, 300.0)));
+        assert_eq!(size, Size::new(200.0, 300.0));
+        column.set_position(Offset::new(5.0, 5.0));
+        // The input is held to the 180px inner width and stretched to the
+        // 236px the button leaves
+        assert_eq!(column.children[1].0.get_position(), Offset::new(15.0, 59.0));
+        assert_eq!(column.children[1].0.get_size(), Size::new(180.0, 236.0));
+        
+        let mut align = Align::new(Alignment::BottomRight, Box::new(Button::new("Go")));
+        align.layout_with_constraints(BoxConstraints::loose(Size::new(400.0, 300.0)));
+        align.set_position(Offset::ZERO);
+        assert_eq!(align.child.get_position(), Offset::new(320.0, 256.0));
+    }
+
+    #[test]
+    fn test_sized_box_fixed_percent_and_fill() {
+        use crate::widgets::{Button, TextInput};
+        let mut half = SizedBox::new(Length::Percent(0.5), Length::Auto, Box::new(TextInput::new("Name")));
+        assert_eq!(half.layout(Vec2::new(10.0, 20.0), Vec2::new(600.0, 400.0)), Vec2::new(300.0, 36.0));
+        assert_eq!(half.child.get_size(), Size::new(300.0, 36.0));
+        assert_eq!(half.child.get_position(), Offset::new(10.0, 20.0));
+        
+        let mut fixed = SizedBox::new(120.0, Length::Fill, Box::new(Button::new("Go")));
+        assert_eq!(fixed.layout(Vec2::ZERO, Vec2::new(600.0, 400.0)), Vec2::new(120.0, 400.0));
+        // Fixed sizes still fit the parent
+        assert_eq!(fixed.layout(Vec2::ZERO, Vec2::new(100.0, 50.0)), Vec2::new(100.0, 50.0));
+        
+        // Unbounded, fill falls back to the child's own size
+        let mut fill = SizedBox::fill(Box::new(Button::new("Go")));
+        let size = fill.layout_with_constraints(BoxConstraints::new(0.0, 200.0, 0.0, f32::INFINITY));
+        assert_eq!(size, Size::new(200.0, 44.0));
+    }
+
+    #[test]
+    fn test_dock_panel_carves_edges() {
+        use crate::widgets::Button;
+        let button = || Box::new(Button::new("Go"));
+        let mut dock = DockPanel::new()
+            .add_docked(Dock::Top, button())
+            .add_child(button())
+            .add_docked(Dock::Left, button())
+            .add_docked(Dock::Bottom, button());
+        assert_eq!(dock.layout(Vec2::ZERO, Vec2::new(400.0, 300.0)), Vec2::new(400.0, 300.0));
+        let placed: Vec<Offset> = dock.children.iter().map(|(c, _)| c.get_position()).collect();
+        // Top takes 44px; left 80px of the rest; bottom sits beside the left
+        // strip; the center gets what's left
+        assert_eq!(placed, [
+            Offset::new(0.0, 0.0),
+            Offset::new(80.0, 44.0),
+            Offset::new(0.0, 44.0),
+            Offset::new(80.0, 256.0),
+        ]);
+        
+        // Under constraints, edge children stretch across and the center fills
+        dock.layout_with_constraints(BoxConstraints::tight(Size::new(400.0, 300.0)));
+        dock.set_position(Offset::ZERO);
+        assert_eq!(dock.children[0].0.get_size(), Size::new(400.0, 44.0));
+        assert_eq!(dock.children[1].0.get_size(), Size::new(320.0, 212.0));
+        // Drawn first, so it is hit last
+        let mut order = Vec::new();
+        dock.visit_children(&mut |c| order.push(c.get_position()));
+        assert_eq!(order[0], Offset::new(80.0, 44.0));
+    }
+
+    #[test]
+    fn test_padding_insets_each_side() {
+        let block = || -> Box<dyn Widget> { Box::new(SizedBox::new(100.0, 40.0, Box::new(Spacer::new(Vec2::ZERO)))) };
+        let insets = EdgeInsets::only(4.0, 8.0, 12.0, 16.0);
+        let mut padded = Padding::new(insets, block());
+        assert_eq!(padded.layout_with_constraints(BoxConstraints::loose(Size::new(500.0, 500.0))), Size::new(124.0, 56.0));
+        padded.set_position(Offset::new(10.0, 10.0));
+        assert_eq!(padded.child.get_position(), Offset::new(26.0, 14.0));
+        assert_eq!(padded.layout(Vec2::ZERO, Vec2::new(500.0, 500.0)), Vec2::new(124.0, 56.0));
+        assert_eq!(padded.child.get_position(), Offset::new(16.0, 4.0));
+        
+        // Columns and rows take asymmetric insets too
+        let mut column = Column::new().with_spacing(0.0).with_insets(insets).add_child(block());
+        assert_eq!(column.layout(Vec2::ZERO, Vec2::new(500.0, 500.0)), Vec2::new(124.0, 56.0));
+        assert_eq!(column.children[0].0.get_position(), Offset::new(16.0, 4.0));
+        let size = column.layout_with_constraints(BoxConstraints::loose(Size::new(500.0, 500.0)));
+        column.set_position(Offset::ZERO);
+        assert_eq!((size, column.children[0].0.get_position()), (Size::new(124.0, 56.0), Offset::new(16.0, 4.0)));
+        
+        // So do grids and flex boxes
+        let mut grid = Grid::new(1).with_gap(0.0).with_insets(insets).add_child(block());
+        let size = grid.layout_with_constraints(BoxConstraints::loose(Size::new(500.0, 500.0)));
+        grid.set_position(Offset::ZERO);
+        assert_eq!((size.height, grid.children[0].0.get_position()), (56.0, Offset::new(16.0, 4.0)));
+        let mut flex = Flex::column().with_gap(0.0).with_insets(insets).add_child(block());
+        let size = flex.layout_with_constraints(BoxConstraints::loose(Size::new(500.0, 500.0)));
+        flex.set_position(Offset::ZERO);
+        assert_eq!((size, flex.children[0].get_position()), (Size::new(124.0, 56.0), Offset::new(16.0, 4.0)));
+        assert_eq!(flex.layout(Vec2::ZERO, Vec2::new(500.0, 500.0)), Vec2::new(124.0, 56.0));
+    }
+
+    #[test]
+    fn test_positioned_children_in_stack() {
+        let block = |w: f32, h: f32| -> Box<dyn Widget> { Box::new(SizedBox::new(w, h, Box::new(Spacer::new(Vec2::ZERO)))) };
+        let mut stack = Stack::new()
+            .add_child(block(300.0, 200.0))
+            // Badge on the bottom-right corner
+            .add_child(Box::new(Positioned::new(block(20.0, 20.0)).with_right(8.0).with_bottom(8.0)))
+            // Palette centered across, 40px from the top
+            .add_child(Box::new(Positioned::new(block(100.0, 50.0)).with_top(40.0)))
+            // Bar stretched between the side edges
+            .add_child(Box::new(Positioned::new(block(0.0, 10.0)).with_left(10.0).with_right(10.0).with_top(0.0)));
+        
+        // Positioned children don't grow an unbounded stack
+        let size = stack.layout_with_constraints(BoxConstraints::loose(Size::new(f32::INFINITY, f32::INFINITY)));
+        assert_eq!(size, Size::new(300.0, 200.0));
+        stack.set_position(Offset::new(10.0, 10.0));
+        let child = |stack: &Stack, i: usize| {
+            let child = stack.child(i).unwrap().widget_at(&[0]).unwrap();
+            (child.get_position(), child.get_size())
+        };
+        assert_eq!(child(&stack, 1), (Offset::new(282.0, 182.0), Size::new(20.0, 20.0)));
+        assert_eq!(child(&stack, 2), (Offset::new(110.0, 50.0), Size::new(100.0, 50.0)));
+        assert_eq!(child(&stack, 3), (Offset::new(20.0, 10.0), Size::new(280.0, 10.0)));
+        
+        // Legacy layout positions in the space handed down
+        stack.layout(Vec2::ZERO, Vec2::new(400.0, 300.0));
+        assert_eq!(child(&stack, 1).0, Offset::new(372.0, 272.0));
+        assert!(!stack.child(1).unwrap().hit_test(Vec2::new(10.0, 10.0)));
+    }
+
+    #[test]
+    fn test_wrapped_positioned_children_do_not_size_stack() {
+        let block = |w: f32, h: f32| -> Box<dyn Widget> { Box::new(SizedBox::new(w, h, Box::new(Spacer::new(Vec2::ZERO)))) };
+        let badge = Positioned::new(block(500.0, 500.0)).with_right(0.0).with_bottom(0.0);
+        let mut stack = Stack::new()
+            .add_child(block(300.0, 200.0))
+            .add_child(Box::new(Identified::new(WidgetId::new(), Box::new(badge))));
+        assert!(stack.child(1).unwrap().positioned().is_some());
+        assert!(stack.child(0).unwrap().positioned().is_none());
+        
+        let unbounded = Size::new(f32::INFINITY, f32::INFINITY);
+        assert_eq!(stack.layout_with_constraints(BoxConstraints::loose(unbounded)), Size::new(300.0, 200.0));
+        // The legacy path agrees, and places the badge in that area
+        assert_eq!(stack.layout(Vec2::ZERO, Vec2::new(unbounded.width, unbounded.height)), Vec2::new(300.0, 200.0));
+        assert_eq!(stack.child(1).unwrap().get_size(), Size::new(300.0, 200.0));
+    }
+
+    #[test]
+    fn test_grid_tracks_and_spans() {
+        use crate::widgets::Button;
+        let button = || Box::new(Button::new("Go"));
+        let mut grid = Grid::new(1)
+            .with_columns(vec![GridTrack::Px(100.0), GridTrack::Auto, GridTrack::Fr(1.0)])
+            .with_gap(10.0)
+            .add_spanning_child(button(), 2, 1)
+            .add_spanning_child(button(), 1, 2)
+            .add_child(button())
+            .add_child(button());
+        let cells: Vec<(usize, usize)> = grid.cells().iter().map(|c| (c.column, c.row)).collect();
+        assert_eq!(cells, [(0, 0), (2, 0), (0, 1), (1, 1)]);
+        
+        let size = grid.layout(Vec2::ZERO, Vec2::new(420.0, 500.0));
+        // 400px inside the padding: 100 fixed, 80 auto (a button), 200 fr
+        let x: Vec<f32> = grid.children.iter().map(|(c, _)| c.get_position().x).collect();
+        assert_eq!(x, [10.0, 210.0, 10.0, 120.0]);
+        assert_eq!(grid.children[3].0.get_position().y, 64.0);
+        // Two 44px rows and a gap
+        assert_eq!(size, Vec2::new(420.0, 118.0));
+    }
+
+    #[test]
+    fn test_aspect_ratio_fits_available_space() {
+        use crate::widgets::Spacer;
+        let mut wide = AspectRatio::new(16.0 / 9.0, Box::new(Spacer::new(Vec2::ZERO)));
+        // Height-limited: 400x180 gives 320x180
+        assert_eq!(wide.layout(Vec2::ZERO, Vec2::new(400.0, 180.0)), Vec2::new(320.0, 180.0));
+        // Width-limited: 160x300 gives 160x90
+        assert_eq!(wide.layout(Vec2::ZERO, Vec2::new(160.0, 300.0)), Vec2::new(160.0, 90.0));
+        
+        let size = wide.layout_with_constraints(BoxConstraints::new(0.0, 320.0, 0.0, f32::INFINITY));
+        assert_eq!(size, Size::new(320.0, 180.0));
+    }
+
+    #[test]
+    fn test_stack_z_order() {
+        let tagged = WidgetId::new();
+        let mut stack = Stack::new()
+            .add_child(Box::new(Label::new("a")))
+            .add_pinned_child(Box::new(Label::new("header")))
+            .add_child(Box::new(Identified::new(tagged, Box::new(Label::new("b")))))
+            .add_child_with_z(Box::new(Label::new("popup")), 5);
+        assert_eq!(names(&stack), ["a", "b", "popup", "header"]);
+        assert_eq!(stack.order(3), StackOrder { z: 0, pinned: true });
+
+        // Pressing raises within the z layer only
+        assert_eq!(stack.child_pressed(0), 1);
+        assert_eq!(names(&stack), ["b", "a", "popup", "header"]);
+        assert_eq!(stack.child_pressed(3), 3, "pinned header stays on top");
+
+        let b = stack.index_of(tagged).unwrap();
+        let raised = stack.raise(b);
+        assert_eq!(stack.lower(raised), 0);
+        assert_eq!(stack.set_z(0, 10), 2);
+        assert_eq!(names(&stack), ["a", "popup", "b", "header"]);
+        assert_eq!(stack.set_pinned(3, false), 1);
+        assert_eq!(names(&stack), ["a", "header", "popup", "b"]);
+    }
+
+    #[test]
+    fn test_widgets_take_tight_constraints() {
+        use crate::ai::ChatMessage;
+        use crate::widgets::*;
+
+        let label = || -> Box<dyn Widget> { Box::new(Label::new("content")) };
+        let widgets: Vec<Box<dyn Widget>> = vec![
+            Box::new(Button::new("Save")),
+            Box::new(Slider::new(0.5)),
+            Box::new(Checkbox::new("Sound", true)),
+            Box::new(Panel::new(label())),
+            Box::new(TextInput::new("Name")),
+            Box::new(Dropdown::new(vec!["a".into(), "b".into()])),
+            Box::new(DatePicker::new()),
+            Box::new(Table::new(Vec::new())),
+            Box::new(ListView::new()),
+            Box::new(TreeView::new()),
+            Box::new(DataTable::new()),
+            Box::new(ProgressBar::new(0.5)),
+            Box::new(Toggle::new("Wi-Fi", true)),
+            Box::new(RadioGroup::new(vec!["a".into(), "b".into()])),
+            Box::new(NumberInput::new(3.0)),
+            Box::new(AnimatedProgressBar::new(0.5)),
+            Box::new(CircularGauge::new(0.5)),
+            Box::new(MiniSparkline::new()),
+            Box::new(MetricDisplay::new("CPU", "42%")),
+            Box::new(LineChart::new()),
+            Box::new(BarChart::new()),
+            Box::new(PieChart::new()),
+            Box::new(Sparkline::new(vec![1.0, 2.0])),
+            Box::new(RichText::new()),
+            Box::new(RichTextEditor::new()),
+            Box::new(Markdown::new("# Title\n\nBody")),
+            Box::new(LivePanel::new(label())),
+            Box::new(LiveLabel::new("live")),
+            Box::new(KpiCard::new("Users", 12.0)),
+            Box::new(StatusBar::new()),
+            Box::new(AgentCard::new("agent", "model")),
+            Box::new(TabView::new()),
+            Box::new(Timeline::new()),
+            Box::new(TitleBar::new("App")),
+            Box::new(FileTree::new()),
+            Box::new(ControllablePanel::new(label())),
+            Box::new(Draggable::new(label())),
+            Box::new(Resizable::new(label(), Vec2::new(100.0, 80.0))),
+            Box::new(Tooltip::new(label(), "tip")),
+            Box::new(ContextMenuTrigger::new(label(), Vec::new())),
+            Box::new(ChatMessageWidget::new(&ChatMessage::user("hello"))),
+            Box::new(ChatView::new()),
+            Box::new(PromptInput::new()),
+            Box::new(crate::widget::Accessible::new(label(), "content")),
+            Box::new(crate::declarative::Keyed::new("key", label())),
+        ];
+        let size = Size::new(320.0, 240.0);
+        for mut widget in widgets {
+            let name = widget.type_name();
+            assert_eq!(widget.layout_with_constraints(BoxConstraints::tight(size)), size, "{} size", name);
+            assert_eq!(widget.get_size(), size, "{} keeps the size it reports", name);
+            widget.set_position(Offset::new(7.0, 9.0));
+            assert_eq!(widget.get_position(), Offset::new(7.0, 9.0), "{} position", name);
+        }
+    }
+}
\ No newline at end of file
@@ -5,12 +5,15 @@
 //! - LiveLabel: Label with reactive text
 //! - LiveKpi: KPI card with sparkline
 
+use std::fmt::Write;
 use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::reactive::{Reactive, ColorSource, Property};
 use crate::panel_style::{PanelPreset, PanelStyle};
 use crate::widget_id::WidgetId;
 use crate::widgets::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, Offset, Size, EdgeInsets};
+use crate::text::LabelCache;
 
 // =============================================================================
 // LIVE PANEL
@@ -148,7 +151,7 @@ impl Widget for LivePanel {
         
         let content_size = if let Some(content) = &mut self.content {
             let content_origin = origin + Vec2::splat(self.padding);
-            content.layout(content_origin, content_available)
+            content.layout_child(content_origin, content_available)
         } else {
             Vec2::ZERO
         };
@@ -161,6 +164,50 @@ impl Widget for LivePanel {
         
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let insets = self.content_insets();
+        let content_size = match &mut self.content {
+            Some(content) => content.layout_child_with_constraints(constraints.deflate(insets).loosen()),
+            None => Size::ZERO,
+        };
+        let natural = Size::new(content_size.width + insets.horizontal(), content_size.height + insets.vertical());
+        let size = if self.fill { constraints.biggest_or(natural) } else { constraints.constrain(natural) };
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        let inset = self.content_insets().top_left();
+        if let Some(content) = &mut self.content {
+            content.set_position(position + inset);
+        }
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        if let Some(content) = &self.content {
+            visitor(content.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        if let Some(content) = &mut self.content {
+            visitor(content.as_mut());
+        }
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        EdgeInsets::all(self.padding)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         if let Some(content) = &mut self.content {
@@ -279,15 +326,37 @@ impl LiveLabel {
             TextSource::Reactive(r) => r.get(),
         }
     }
+    
+    fn default_size(&self) -> Size {
+        Size::new(self.get_text().len() as f32 * (self.font_size * 0.6), self.font_size + 4.0)
+    }
 }
 
 impl Widget for LiveLabel {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        let text = self.get_text();
-        self.size = Vec2::new(text.len() as f32 * (self.font_size * 0.6), self.font_size + 4.0);
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
@@ -315,6 +384,8 @@ pub struct KpiCard {
     pub trend: Trend,
     pub sparkline_data: Vec<f32>,
     pub preset: PanelPreset,
+    /// Value text as last drawn
+    value_label: LabelCache<f32>,
 }
 
 /// Source of numeric value
@@ -343,6 +414,7 @@ impl KpiCard {
             trend: Trend::None,
             sparkline_data: Vec::new(),
             preset: PanelPreset::Data,
+            value_label: LabelCache::new(),
         }
     }
     
@@ -356,6 +428,7 @@ impl KpiCard {
             trend: Trend::None,
             sparkline_data: Vec::new(),
             preset: PanelPreset::Data,
+            value_label: LabelCache::new(),
         }
     }
     
@@ -387,6 +460,24 @@ impl Widget for KpiCard {
         self.position = origin;
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
@@ -406,8 +497,10 @@ impl Widget for KpiCard {
         
         // Value
         let value = self.get_value();
-        let value_str = format!("{:.1}", value);
-        renderer.draw_text(&value_str, self.position + Vec2::new(12.0, 30.0), 28.0, theme.text);
+        let value_text = self.value_label.get(value, |value, text| {
+            let _ = write!(text, "{:.1}", value);
+        });
+        renderer.draw_text(&value_text, self.position + Vec2::new(12.0, 30.0), 28.0, theme.text);
         
         // Trend indicator
         let trend_text = match self.trend {
@@ -1,13 +1,14 @@
 //! GlassUI Media Widgets
 //!
-//! Media display widgets: Image, Icon
+//! Media display widgets: Image, Icon, NineSlicePanel
 //!
 //! Note: Heavy image loading is typically done via the Resource async system
 //! in state.rs. This module provides the widgets to display loaded images.
 
+use std::cell::Cell;
 use glam::{Vec2, Vec4};
-use crate::renderer::GlassRenderer;
-use crate::layout::{BoxConstraints, Size, Offset};
+use crate::renderer::{GlassRenderer, ImageInstance, TextureId, check_rgba, release_texture};
+use crate::layout::{BoxConstraints, EdgeInsets, Rect, Size, Offset};
 use crate::widgets::core::{Widget, get_theme};
 
 // =============================================================================
@@ -41,7 +42,9 @@ pub enum ImageSource {
     Asset(String),
     /// URL for network image (requires async loading)
     Network(String),
-    /// Raw pixel data (RGBA8)
+    /// Texture already uploaded with `GlassRenderer::load_texture`
+    Texture(TextureId),
+    /// Raw pixel data (RGBA8), uploaded on first render
     Memory {
         data: Vec<u8>,
         width: u32,
@@ -80,7 +83,8 @@ impl Default for ImageSource {
 pub struct Image {
     pub position: Vec2,
     pub size: Vec2,
-    pub source: ImageSource,
+    /// What's drawn; change it with `set_source`
+    source: ImageSource,
     pub fit: BoxFit,
     pub tint: Option<Vec4>,
     pub corner_radius: f32,
@@ -94,14 +98,16 @@ pub struct Image {
     pub error: Option<String>,
     /// Whether image is currently loading
     pub loading: bool,
+    /// GPU copy of `ImageSource::Memory` pixels
+    uploaded: Cell<Option<TextureId>>,
 }
 
 impl Image {
     pub fn new(source: ImageSource) -> Self {
-        Self {
+        let mut image = Self {
             position: Vec2::ZERO,
             size: Vec2::new(100.0, 100.0), // Default placeholder size
-            source,
+            source: ImageSource::Placeholder,
             fit: BoxFit::Cover,
             tint: None,
             corner_radius: 0.0,
@@ -110,7 +116,31 @@ impl Image {
             native_size: None,
             error: None,
             loading: false,
+            uploaded: Cell::new(None),
+        };
+        image.set_source(source);
+        image
+    }
+    
+    pub fn source(&self) -> &ImageSource {
+        &self.source
+    }
+    
+    /// Show a different image: frees the texture uploaded for the old
+    /// `Memory` pixels and checks the new ones
+    pub fn set_source(&mut self, source: ImageSource) {
+        if let Some(id) = self.uploaded.take() {
+            release_texture(id);
         }
+        self.native_size = match &source {
+            ImageSource::Memory { width, height, .. } => Some((*width, *height)),
+            _ => None,
+        };
+        self.error = match &source {
+            ImageSource::Memory { data, width, height } => check_rgba(data, *width, *height).err().map(|e| e.to_string()),
+            _ => None,
+        };
+        self.source = source;
     }
     
     /// Create a placeholder image
@@ -118,6 +148,27 @@ impl Image {
         Self::new(ImageSource::Placeholder)
     }
     
+    /// Image showing an uploaded texture
+    pub fn from_texture(texture: TextureId, width: u32, height: u32) -> Self {
+        let mut image = Self::new(ImageSource::Texture(texture));
+        image.native_size = Some((width, height));
+        image
+    }
+    
+    /// Texture to draw, uploading `Memory` pixels the first time
+    fn texture(&self, renderer: &mut GlassRenderer) -> Option<TextureId> {
+        match &self.source {
+            ImageSource::Texture(id) => Some(*id),
+            ImageSource::Memory { data, width, height } if self.error.is_none() => {
+                if self.uploaded.get().is_none() {
+                    self.uploaded.set(renderer.load_texture(data, *width, *height).ok());
+                }
+                self.uploaded.get()
+            }
+            _ => None,
+        }
+    }
+    
     pub fn with_fit(mut self, fit: BoxFit) -> Self {
         self.fit = fit;
         self
@@ -213,6 +264,14 @@ impl Widget for Image {
         self.height.or_else(|| self.native_size.map(|(_, h)| h as f32))
     }
     
+    /// Frees the texture uploaded from `ImageSource::Memory`; textures
+    /// passed in with `ImageSource::Texture` belong to the caller
+    fn on_unmount(&mut self) {
+        if let Some(id) = self.uploaded.take() {
+            release_texture(id);
+        }
+    }
+    
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false // Images don't handle events by default
     }
@@ -224,6 +283,13 @@ impl Widget for Image {
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
         
+        if let Some(texture) = self.texture(renderer) {
+            let instance = ImageInstance::new(self.position, self.size, self.tint.unwrap_or(Vec4::ONE))
+                .with_radius(self.corner_radius);
+            renderer.draw_image_instance(texture, instance);
+            return;
+        }
+        
         match &self.source {
             ImageSource::Placeholder | ImageSource::Network(_) if self.loading => {
                 // Draw loading placeholder
@@ -281,9 +347,8 @@ impl Widget for Image {
                     );
                 }
             }
-            ImageSource::Memory { .. } => {
-                // Real implementation would upload to GPU texture
-                // For now, draw as colored rect with tint
+            ImageSource::Texture(_) | ImageSource::Memory { .. } => {
+                // Pixel data doesn't match its dimensions
                 renderer.draw_rounded_rect(
                     self.position,
                     self.size,
@@ -314,15 +379,17 @@ impl Widget for Image {
 // ICON WIDGET
 // =============================================================================
 
-/// Simple icon widget using text glyphs
+/// Simple icon widget using text glyphs or a bitmap
 ///
-/// Uses Unicode/emoji glyphs for now. Future: support icon fonts like
-/// Material Icons or FontAwesome.
+/// Uses Unicode/emoji glyphs, or a texture from `Icon::from_texture`.
+/// Future: support icon fonts like Material Icons or FontAwesome.
 pub struct Icon {
     pub position: Vec2,
     pub size: f32,
     pub glyph: String,
     pub color: Option<Vec4>,
+    /// Bitmap drawn instead of the glyph (tinted by `color`)
+    pub texture: Option<TextureId>,
 }
 
 impl Icon {
@@ -332,9 +399,17 @@ impl Icon {
             size: 24.0,
             glyph: glyph.to_string(),
             color: None,
+            texture: None,
         }
     }
     
+    /// Icon drawn from an uploaded texture
+    pub fn from_texture(texture: TextureId) -> Self {
+        let mut icon = Self::new("");
+        icon.texture = Some(texture);
+        icon
+    }
+    
     pub fn with_size(mut self, size: f32) -> Self {
         self.size = size;
         self
@@ -384,7 +459,188 @@ impl Widget for Icon {
     fn update(&mut self, _dt: f32) {}
     
     fn render(&self, renderer: &mut GlassRenderer) {
+        if let Some(texture) = self.texture {
+            renderer.draw_image(self.position, Vec2::splat(self.size), texture, self.color.unwrap_or(Vec4::ONE));
+            return;
+        }
         let color = self.color.unwrap_or_else(|| get_theme().text);
         renderer.draw_text(&self.glyph, self.position, self.size, color);
     }
 }
+
+// =============================================================================
+// NINE-SLICE PANEL
+// =============================================================================
+
+/// Panel drawn from a nine-slice texture, optionally wrapping a child
+///
+/// The corners of `texture` (`insets` texels wide) keep their size while
+/// the edges and center stretch, so themed chrome resizes cleanly. The
+/// child is laid out inside the insets plus `padding`.
+pub struct NineSlicePanel {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub texture: TextureId,
+    pub insets: EdgeInsets,
+    pub tint: Vec4,
+    /// Extra space between the border slices and the child
+    pub padding: EdgeInsets,
+    pub child: Option<Box<dyn Widget>>,
+    /// Fixed size (None = wrap the child, or fill the space without one)
+    pub fixed_size: Option<Vec2>,
+}
+
+impl NineSlicePanel {
+    pub fn new(texture: TextureId, insets: EdgeInsets) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            texture,
+            insets,
+            tint: Vec4::ONE,
+            padding: EdgeInsets::ZERO,
+            child: None,
+            fixed_size: None,
+        }
+    }
+    
+    pub fn with_child(mut self, child: Box<dyn Widget>) -> Self {
+        self.child = Some(child);
+        self
+    }
+    
+    pub fn with_tint(mut self, tint: Vec4) -> Self {
+        self.tint = tint;
+        self
+    }
+    
+    pub fn with_padding(mut self, padding: EdgeInsets) -> Self {
+        self.padding = padding;
+        self
+    }
+    
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.fixed_size = Some(Vec2::new(width, height));
+        self
+    }
+}
+
+impl Widget for NineSlicePanel {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let outer = self.fixed_size.unwrap_or(max_size).min(max_size);
+        let insets = self.content_insets();
+        let total = Vec2::new(insets.horizontal(), insets.vertical());
+        let fixed = self.fixed_size.is_some();
+        
+        self.size = match &mut self.child {
+            Some(child) => {
+                let child_size = child.layout_child(origin + Vec2::new(insets.left, insets.top), (outer - total).max(Vec2::ZERO));
+                if fixed { outer } else { child_size + total }
+            }
+            None => outer,
+        };
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let insets = self.content_insets();
+        let size = match (self.fixed_size, &mut self.child) {
+            (Some(fixed), child) => {
+                let size = constraints.constrain(Size::new(fixed.x, fixed.y));
+                if let Some(child) = child {
+                    child.layout_child_with_constraints(BoxConstraints::loose(size).deflate(insets));
+                }
+                size
+            }
+            (None, Some(child)) => {
+                let child_size = child.layout_child_with_constraints(constraints.deflate(insets).loosen());
+                constraints.constrain(Size::new(child_size.width + insets.horizontal(), child_size.height + insets.vertical()))
+            }
+            (None, None) => constraints.biggest_or(Size::ZERO),
+        };
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        let inset = self.content_insets().top_left();
+        if let Some(child) = &mut self.child {
+            child.set_position(position + inset);
+        }
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        EdgeInsets::only(
+            self.insets.top + self.padding.top,
+            self.insets.right + self.padding.right,
+            self.insets.bottom + self.padding.bottom,
+            self.insets.left + self.padding.left,
+        )
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        if let Some(child) = &self.child {
+            visitor(child.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        if let Some(child) = &mut self.child {
+            visitor(child.as_mut());
+        }
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.child.as_ref().is_some_and(|c| c.is_animating())
+    }
+    
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.child.as_mut().is_some_and(|c| c.handle_event(event, mouse_pos))
+    }
+    
+    fn update(&mut self, dt: f32) {
+        if let Some(child) = &mut self.child {
+            child.update(dt);
+        }
+    }
+    
+    fn render(&self, renderer: &mut GlassRenderer) {
+        renderer.draw_nine_slice_tinted(self.texture, Rect::new(self.position, self.size), self.insets, self.tint);
+        if let Some(child) = &self.child {
+            child.render(renderer);
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_source_rechecks_pixels() {
+        let mut image = Image::new(ImageSource::Memory { data: vec![0; 12], width: 2, height: 2 });
+        assert!(image.error.is_some());
+        
+        image.set_source(ImageSource::Memory { data: vec![0; 24], width: 3, height: 2 });
+        assert!(image.error.is_none());
+        assert_eq!(image.native_size, Some((3, 2)));
+        
+        image.set_source(ImageSource::Placeholder);
+        assert!(image.error.is_none() && image.native_size.is_none());
+        assert!(matches!(image.source(), ImageSource::Placeholder));
+    }
+}
@@ -7,7 +7,11 @@ mod layout;
 mod controls;
 mod premium;
 mod input;
+mod autocomplete;
+mod search;
+mod tags;
 mod containers;
+mod scroll;
 mod overlays;
 mod advanced;
 mod data;
@@ -17,37 +21,43 @@ mod richtext;
 mod video;
 
 // Re-export core types
-pub use core::{Theme, Widget, set_theme, get_theme, easing};
+pub use core::{Theme, Widget, WidgetState, UpdateState, Identified, update_parallel, count_widgets, mount, unmount, tree_generation, is_occluded, rounded_opaque_rect, set_theme, get_theme, render_faded, theme_generation, set_debug_layout, debug_layout, easing};
 
 // Re-export layout widgets
 pub use layout::{
-    Column, Row, Stack, Spacer, Align, Alignment,
-    Grid, Flex, FlexDirection, FlexJustify, FlexAlign,
+    Column, Row, Stack, StackOrder, Positioned, Spacer, Align, Alignment, Padding, AspectRatio, SizedBox, Length, DockPanel, Dock,
+    Grid, GridTrack, GridCell, Flex, FlexDirection, FlexJustify, FlexAlign,
 };
+pub(crate) use layout::{LinearLayout, place_children};
 
 // Re-export control widgets
 pub use controls::{
-    Button, Label, Slider, Checkbox, Panel,
+    Button, Label, Slider, Orientation, RangeSlider, RangeHandle, Knob, KnobDrag, Checkbox, CheckState, Panel,
 };
 
 // Re-export premium widgets
 pub use premium::{
-    ProgressBar, Toggle, RadioGroup, NumberInput,
+    ProgressBar, Toggle, RadioGroup, NumberInput, Rating,
 };
 
 // Re-export input widgets
 pub use input::{
-    TextInput, Dropdown, DatePicker, SimpleDate,
+    TextInput, Dropdown, DatePicker, SimpleDate, TimePicker, SimpleTime, DateTimePicker,
+    Validator, InputMask,
 };
+pub use autocomplete::{AutocompleteInput, Combobox, SuggestionProvider, SuggestionReply, filter_suggestions};
+pub use search::SearchInput;
+pub use tags::TagInput;
 
 // Re-export container widgets
 pub use containers::{
-    ScrollArea, TabBar,
+    ScrollArea, TabBar, CachedWidget, Opacity, Visible, PassThrough,
 };
+pub use scroll::ScrollBar;
 
 // Re-export overlay widgets
 pub use overlays::{
-    Tooltip, MenuItem, ContextMenu, ContextMenuTrigger, Modal,
+    Tooltip, TooltipContent, place_tooltip, MenuItem, ContextMenu, ContextMenuTrigger, Modal,
 };
 
 // Re-export advanced widgets
@@ -64,7 +74,7 @@ pub use data::{
 
 // Re-export media widgets
 pub use media::{
-    Image, ImageSource, BoxFit, Icon,
+    Image, ImageSource, BoxFit, Icon, NineSlicePanel,
 };
 
 // Re-export chart widgets
@@ -77,6 +87,7 @@ pub use charts::{
 pub use richtext::{
     RichText, RichTextEditor, TextSpan, SpanStyle,
     FontWeight, TextDecoration,
+    Markdown, MarkdownBlock, MarkdownBlockKind,
 };
 
 // Re-export video widgets
@@ -101,19 +112,19 @@ pub use panel_controls::{
 
 mod status;
 pub use status::{
-    StatusBar, StatusItem, 
-    Toast, ToastType, ToastContainer,
+    StatusBar, StatusItem, StatusSection, 
+    Toast, ToastType, ToastContainer, ToastAnchor,
     AgentCard,
 };
 
 mod command_palette;
 pub use command_palette::{
-    Command, CommandPalette,
+    Command, CommandPalette, FuzzyMatch, fuzzy_match,
 };
 
 mod timeline;
 pub use timeline::{
-    Timeline, TimelineEntry, TimelineEntryType,
+    Timeline, TimelineEntry, TimelineEntryType, TimelineGrouping, HistoryLoader,
 };
 
 mod gauges;
@@ -135,3 +146,15 @@ mod table;
 pub use table::{
     GridColumn, GridRow, CellValue, SortDirection, DataTable,
 };
+
+mod perf;
+pub use perf::{PerfOverlay, FrameStats};
+
+mod dialogs;
+pub use dialogs::DialogManager;
+
+mod titlebar;
+pub use titlebar::{TitleBar, WindowAction, resize_direction_at, borderless_window_builder};
+
+mod inspector;
+pub use inspector::{Inspector, WidgetSnapshot, widget_bounds, render_layout_debug, overflow_amount};
@@ -3,37 +3,101 @@
 //! Widgets that render on top of other content: Tooltip, ContextMenu, Modal
 
 use glam::{Vec2, Vec4};
-use winit::event::{ElementState, MouseButton};
+use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::keyboard::{Key, NamedKey};
 use crate::renderer::GlassRenderer;
-use super::core::{Widget, get_theme};
+use super::core::{Widget, get_theme, mount, unmount};
+use crate::layout::{BoxConstraints, Offset, Rect, Size};
 
 // =============================================================================
 // TOOLTIP
 // =============================================================================
 
+/// What a tooltip shows: optional title, body text and a shortcut hint
+///
+/// The body may span several lines separated by `\n`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TooltipContent {
+    pub title: Option<String>,
+    pub body: String,
+    pub shortcut: Option<String>,
+}
+
+impl TooltipContent {
+    pub fn new(body: &str) -> Self {
+        Self {
+            title: None,
+            body: body.to_string(),
+            shortcut: None,
+        }
+    }
+    
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+    
+    pub fn with_shortcut(mut self, shortcut: &str) -> Self {
+        self.shortcut = Some(shortcut.to_string());
+        self
+    }
+}
+
+/// Top-left corner for a tooltip of `size` shown for the cursor at `anchor`
+///
+/// Prefers below-right of the cursor, flips above it near the bottom edge,
+/// and slides left to stay inside `screen`.
+pub fn place_tooltip(anchor: Vec2, size: Vec2, screen: Vec2) -> Vec2 {
+    const OFFSET: Vec2 = Vec2::new(12.0, 16.0);
+    const MARGIN: f32 = 4.0;
+    
+    let mut pos = anchor + OFFSET;
+    if pos.x + size.x > screen.x - MARGIN {
+        pos.x = screen.x - MARGIN - size.x;
+    }
+    if pos.y + size.y > screen.y - MARGIN {
+        pos.y = anchor.y - MARGIN - size.y;
+    }
+    pos.max(Vec2::splat(MARGIN))
+}
+
 /// Wraps a child widget and shows a tooltip on hover
+///
+/// Hover comes from `EventDispatcher`, so a tooltip covered by a popup or
+/// another widget stays hidden.
+///
+/// The tooltip appears after `delay` seconds, fades in over `fade_in`
+/// seconds and is drawn by the renderer's tooltip pass on top of all
+/// other overlays.
 pub struct Tooltip {
     pub position: Vec2,
     pub size: Vec2,
     pub child: Box<dyn Widget>,
-    pub text: String,
+    pub content: TooltipContent,
     pub hovered: bool,
     pub hover_time: f32,
     pub mouse_pos: Vec2,
     pub delay: f32,
+    pub fade_in: f32,
 }
 
 impl Tooltip {
     pub fn new(child: Box<dyn Widget>, text: &str) -> Self {
+        Self::with_content(child, TooltipContent::new(text))
+    }
+    
+    /// Tooltip with a title and/or shortcut hint
+    pub fn with_content(child: Box<dyn Widget>, content: TooltipContent) -> Self {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
             child,
-            text: text.to_string(),
+            content,
             hovered: false,
             hover_time: 0.0,
             mouse_pos: Vec2::ZERO,
             delay: 0.5,
+            fade_in: 0.15,
         }
     }
     
@@ -41,24 +105,87 @@ impl Tooltip {
         self.delay = delay;
         self
     }
+    
+    pub fn with_fade_in(mut self, fade_in: f32) -> Self {
+        self.fade_in = fade_in;
+        self
+    }
+    
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.content.title = Some(title.to_string());
+        self
+    }
+    
+    pub fn with_shortcut(mut self, shortcut: &str) -> Self {
+        self.content.shortcut = Some(shortcut.to_string());
+        self
+    }
+    
+    /// Current tooltip opacity (0 while waiting for the delay)
+    pub fn opacity(&self) -> f32 {
+        if !self.hovered || self.hover_time < self.delay {
+            return 0.0;
+        }
+        if self.fade_in <= 0.0 {
+            return 1.0;
+        }
+        ((self.hover_time - self.delay) / self.fade_in).min(1.0)
+    }
 }
 
 impl Widget for Tooltip {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = self.child.layout(origin, max_size);
+        self.size = self.child.layout_child(origin, max_size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn positioned(&self) -> Option<&super::layout::Positioned> {
+        self.child.positioned()
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         self.mouse_pos = mouse_pos;
-        let handled = self.child.handle_event(event, mouse_pos);
-        
-        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
-        
-        self.hovered = inside;
-        handled
+        self.child.handle_event(event, mouse_pos)
+    }
+    
+    fn on_hover_enter(&mut self) {
+        self.hovered = true;
+    }
+    
+    fn on_hover_leave(&mut self) {
+        self.hovered = false;
+    }
+    
+    fn wants_hover(&self) -> bool {
+        true
     }
 
     fn update(&mut self, dt: f32) {
@@ -69,36 +196,17 @@ impl Widget for Tooltip {
             self.hover_time = 0.0;
         }
     }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating() || (self.hovered && self.opacity() < 1.0)
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         self.child.render(renderer);
         
-        // Render tooltip using overlay layer (on top of everything)
-        if self.hovered && self.hover_time > self.delay {
-            let theme = get_theme();
-            let padding = 8.0;
-            let text_width = self.text.len() as f32 * 8.0;
-            let tooltip_size = Vec2::new(text_width + padding * 2.0, 28.0);
-            let tooltip_pos = self.mouse_pos + Vec2::new(12.0, 12.0);
-            
-            // Background
-            renderer.draw_overlay_rect(
-                tooltip_pos,
-                tooltip_size,
-                Vec4::new(0.05, 0.05, 0.08, 0.95),
-                6.0
-            );
-            
-            // Border
-            renderer.draw_overlay_rect(
-                tooltip_pos - Vec2::splat(1.0),
-                tooltip_size + Vec2::splat(2.0),
-                Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.5),
-                7.0
-            );
-            
-            // Text
-            renderer.draw_overlay_text(&self.text, tooltip_pos + Vec2::new(padding, 6.0), 14.0, theme.text);
+        let opacity = self.opacity();
+        if opacity > 0.0 {
+            renderer.draw_rich_tooltip(&self.content, self.mouse_pos, opacity);
         }
     }
 }
@@ -108,10 +216,20 @@ impl Widget for Tooltip {
 // =============================================================================
 
 /// A single item in a context menu
+///
+/// Besides plain actions an item can be a separator, carry a leading icon,
+/// be checkable, be disabled, or open a nested submenu.
 pub struct MenuItem {
     pub label: String,
     pub shortcut: Option<String>,
     pub on_click: Option<Box<dyn FnMut()>>,
+    /// Leading icon glyph drawn before the label
+    pub icon: Option<String>,
+    /// `Some` for checkable items; toggled on activation
+    pub checked: Option<bool>,
+    pub enabled: bool,
+    pub separator: bool,
+    pub submenu: Option<Box<ContextMenu>>,
 }
 
 impl MenuItem {
@@ -120,6 +238,20 @@ impl MenuItem {
             label: label.to_string(),
             shortcut: None,
             on_click: None,
+            icon: None,
+            checked: None,
+            enabled: true,
+            separator: false,
+            submenu: None,
+        }
+    }
+    
+    /// Horizontal divider between groups of items
+    pub fn separator() -> Self {
+        Self {
+            separator: true,
+            enabled: false,
+            ..Self::new("")
         }
     }
     
@@ -132,21 +264,74 @@ impl MenuItem {
         self.on_click = Some(Box::new(callback));
         self
     }
+    
+    pub fn with_icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+    
+    /// Make the item checkable with an initial state
+    pub fn checkable(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+    
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+    
+    pub fn disabled(self) -> Self {
+        self.with_enabled(false)
+    }
+    
+    /// Nested items shown beside this one on hover or Right arrow
+    pub fn with_submenu(mut self, items: Vec<MenuItem>) -> Self {
+        self.submenu = Some(Box::new(ContextMenu::new(items)));
+        self
+    }
+    
+    /// Whether the item can be hovered and activated
+    pub fn is_selectable(&self) -> bool {
+        !self.separator && self.enabled
+    }
 }
 
 // =============================================================================
 // CONTEXT MENU
 // =============================================================================
 
+/// Outcome of a click or key press routed through a menu tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MenuResponse {
+    /// Input fell outside every open menu
+    Outside,
+    /// Input was consumed without activating anything
+    Handled,
+    /// An item ran its action; the whole tree should close
+    Activated,
+    /// The menu asked to be closed (Left/Escape in a submenu)
+    Close,
+}
+
 /// Popup context menu (right-click menu)
 pub struct ContextMenu {
     pub position: Vec2,
     pub items: Vec<MenuItem>,
     pub visible: bool,
     pub item_height: f32,
+    pub separator_height: f32,
     pub width: f32,
     pub hovered_index: Option<usize>,
     pub corner_radius: f32,
+    /// Seconds the pointer must rest on an item before its submenu opens
+    pub submenu_delay: f32,
+    /// Area the menu is kept inside; flips away from its edges
+    bounds: Vec2,
+    /// Size of that area, between `layout_with_constraints` and `set_position`
+    area: Vec2,
+    open_submenu: Option<usize>,
+    pending_submenu: Option<(usize, f32)>,
 }
 
 impl ContextMenu {
@@ -156,76 +341,260 @@ impl ContextMenu {
             items,
             visible: false,
             item_height: 32.0,
+            separator_height: 9.0,
             width: 180.0,
             hovered_index: None,
             corner_radius: 8.0,
+            submenu_delay: 0.25,
+            bounds: Vec2::ZERO,
+            area: Vec2::ZERO,
+            open_submenu: None,
+            pending_submenu: None,
         }
     }
     
+    /// Show the menu at `pos`, flipping left/up when it would overflow
     pub fn show(&mut self, pos: Vec2) {
-        self.position = pos;
+        let size = Vec2::new(self.width, self.total_height());
+        let mut position = pos;
+        if self.bounds.x > 0.0 && pos.x + size.x > self.bounds.x {
+            position.x = pos.x - size.x;
+        }
+        if self.bounds.y > 0.0 && pos.y + size.y > self.bounds.y {
+            position.y = pos.y - size.y;
+        }
+        self.position = position.max(Vec2::ZERO);
         self.visible = true;
+        self.hovered_index = None;
+        self.close_submenu();
     }
     
     pub fn hide(&mut self) {
         self.visible = false;
+        self.hovered_index = None;
+        self.close_submenu();
+    }
+    
+    /// Index of the item whose submenu is currently open
+    pub fn open_submenu_index(&self) -> Option<usize> {
+        self.open_submenu
+    }
+    
+    /// The open submenu, if any
+    pub fn submenu(&self) -> Option<&ContextMenu> {
+        self.open_submenu.and_then(|i| self.items[i].submenu.as_deref())
     }
     
     fn total_height(&self) -> f32 {
-        self.items.len() as f32 * self.item_height
+        self.items.iter().map(|item| self.row_height(item)).sum()
     }
-}
-
-impl Widget for ContextMenu {
-    fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
-        Vec2::new(self.width, self.total_height())
+    
+    fn row_height(&self, item: &MenuItem) -> f32 {
+        if item.separator { self.separator_height } else { self.item_height }
     }
-
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
-        if !self.visible {
-            return false;
+    
+    fn item_y(&self, index: usize) -> f32 {
+        self.position.y + self.items[..index].iter().map(|item| self.row_height(item)).sum::<f32>()
+    }
+    
+    fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.position.x && point.x <= self.position.x + self.width &&
+        point.y >= self.position.y && point.y <= self.position.y + self.total_height()
+    }
+    
+    /// Whether `point` lies over this menu or any open submenu
+    fn contains_tree(&self, point: Vec2) -> bool {
+        self.contains(point) || self.submenu().is_some_and(|sub| sub.contains_tree(point))
+    }
+    
+    fn item_at(&self, point: Vec2) -> Option<usize> {
+        if !self.contains(point) {
+            return None;
         }
+        let mut y = self.position.y;
+        for (i, item) in self.items.iter().enumerate() {
+            y += self.row_height(item);
+            if point.y < y {
+                return Some(i);
+            }
+        }
+        None
+    }
+    
+    fn open_submenu_at(&mut self, index: usize) {
+        if self.open_submenu == Some(index) {
+            return;
+        }
+        self.close_submenu();
+        let item_y = self.item_y(index);
+        let (x, width, bounds) = (self.position.x, self.width, self.bounds);
+        let Some(sub) = self.items[index].submenu.as_deref_mut() else { return };
+        sub.bounds = bounds;
         
-        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.width &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.total_height();
-        
-        if inside {
-            let relative_y = mouse_pos.y - self.position.y;
-            let index = (relative_y / self.item_height) as usize;
-            self.hovered_index = if index < self.items.len() { Some(index) } else { None };
-        } else {
-            self.hovered_index = None;
+        // Open to the right, or to the left when there is no room
+        let mut pos = Vec2::new(x + width - 4.0, item_y);
+        if bounds.x > 0.0 && pos.x + sub.width > bounds.x {
+            pos.x = x - sub.width + 4.0;
+        }
+        if bounds.y > 0.0 && pos.y + sub.total_height() > bounds.y {
+            pos.y = bounds.y - sub.total_height();
+        }
+        sub.position = pos.max(Vec2::ZERO);
+        sub.visible = true;
+        sub.hovered_index = None;
+        self.open_submenu = Some(index);
+    }
+    
+    fn close_submenu(&mut self) {
+        self.pending_submenu = None;
+        if let Some(index) = self.open_submenu.take() {
+            if let Some(sub) = self.items[index].submenu.as_deref_mut() {
+                sub.hide();
+            }
+        }
+    }
+    
+    fn submenu_mut(&mut self) -> Option<&mut ContextMenu> {
+        let index = self.open_submenu?;
+        self.items[index].submenu.as_deref_mut()
+    }
+    
+    fn hover(&mut self, point: Vec2) {
+        if let Some(sub) = self.submenu_mut() {
+            if sub.contains_tree(point) {
+                sub.hover(point);
+                return;
+            }
         }
         
-        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
-            if inside {
-                if let Some(index) = self.hovered_index {
-                    if let Some(callback) = &mut self.items[index].on_click {
-                        callback();
+        match self.item_at(point) {
+            Some(index) if self.items[index].is_selectable() => {
+                if self.hovered_index != Some(index) {
+                    self.hovered_index = Some(index);
+                    if self.items[index].submenu.is_none() {
+                        self.close_submenu();
+                    } else if self.open_submenu != Some(index) {
+                        self.pending_submenu = Some((index, 0.0));
                     }
-                    println!("Menu: '{}'", self.items[index].label);
-                    self.hide();
-                    return true;
                 }
-            } else {
-                self.hide();
-                return true;
+            }
+            Some(_) => {
+                self.hovered_index = None;
+                self.pending_submenu = None;
+            }
+            // Keep the parent row lit while the pointer travels to a submenu
+            None => {
+                if self.open_submenu.is_none() {
+                    self.hovered_index = None;
+                }
+                self.pending_submenu = None;
+            }
+        }
+    }
+    
+    fn click(&mut self, point: Vec2) -> MenuResponse {
+        if let Some(sub) = self.submenu_mut() {
+            if sub.contains_tree(point) {
+                return sub.click(point);
             }
         }
         
-        inside
+        match self.item_at(point) {
+            Some(index) if self.items[index].is_selectable() => self.activate(index),
+            Some(_) => MenuResponse::Handled,
+            None => MenuResponse::Outside,
+        }
     }
-
-    fn update(&mut self, _dt: f32) {}
-
-    fn render(&self, renderer: &mut GlassRenderer) {
-        if !self.visible {
+    
+    /// Run the item's action, or open its submenu
+    fn activate(&mut self, index: usize) -> MenuResponse {
+        let item = &mut self.items[index];
+        if !item.is_selectable() {
+            return MenuResponse::Handled;
+        }
+        if item.submenu.is_some() {
+            self.hovered_index = Some(index);
+            self.open_submenu_at(index);
+            return MenuResponse::Handled;
+        }
+        if let Some(checked) = &mut item.checked {
+            *checked = !*checked;
+        }
+        if let Some(callback) = &mut item.on_click {
+            callback();
+        }
+        MenuResponse::Activated
+    }
+    
+    /// Move the keyboard highlight to the next selectable item in `direction`
+    fn move_hover(&mut self, direction: isize) {
+        let len = self.items.len() as isize;
+        if len == 0 {
             return;
         }
+        let mut index = match self.hovered_index {
+            Some(i) => i as isize,
+            None if direction > 0 => -1,
+            None => len,
+        };
+        for _ in 0..len {
+            index = (index + direction).rem_euclid(len);
+            if self.items[index as usize].is_selectable() {
+                self.hovered_index = Some(index as usize);
+                self.pending_submenu = None;
+                return;
+            }
+        }
+    }
+    
+    fn handle_key(&mut self, key: &NamedKey) -> MenuResponse {
+        if let Some(sub) = self.submenu_mut() {
+            match sub.handle_key(key) {
+                MenuResponse::Close => {
+                    self.close_submenu();
+                    return MenuResponse::Handled;
+                }
+                response => return response,
+            }
+        }
         
+        match key {
+            NamedKey::ArrowDown => self.move_hover(1),
+            NamedKey::ArrowUp => self.move_hover(-1),
+            NamedKey::ArrowRight => {
+                if let Some(index) = self.hovered_index.filter(|&i| self.items[i].submenu.is_some()) {
+                    self.open_submenu_at(index);
+                    if let Some(sub) = self.submenu_mut() {
+                        sub.move_hover(1);
+                    }
+                }
+            }
+            NamedKey::Enter | NamedKey::Space => {
+                if let Some(index) = self.hovered_index {
+                    let response = self.activate(index);
+                    if let Some(sub) = self.submenu_mut() {
+                        sub.move_hover(1);
+                    }
+                    return response;
+                }
+            }
+            NamedKey::ArrowLeft | NamedKey::Escape => return MenuResponse::Close,
+            _ => {}
+        }
+        MenuResponse::Handled
+    }
+    
+    fn render_menu(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
         
-        // Background - use overlay layer
+        // Shadow and background - use overlay layer
+        renderer.draw_overlay_shadow(
+            self.position + Vec2::new(0.0, 4.0),
+            Vec2::new(self.width, self.total_height()),
+            self.corner_radius,
+            12.0,
+            Vec4::new(0.0, 0.0, 0.0, 0.4)
+        );
         renderer.draw_overlay_rect(
             self.position,
             Vec2::new(self.width, self.total_height()),
@@ -241,28 +610,146 @@ impl Widget for ContextMenu {
             self.corner_radius + 1.0
         );
         
+        // Reserve a leading column when any item has an icon or check mark
+        let leading = if self.items.iter().any(|item| item.icon.is_some() || item.checked.is_some()) { 22.0 } else { 0.0 };
+        
         // Items
+        let mut item_y = self.position.y;
         for (i, item) in self.items.iter().enumerate() {
-            let item_y = self.position.y + i as f32 * self.item_height;
+            let row_height = self.row_height(item);
+            
+            if item.separator {
+                renderer.draw_overlay_rect(
+                    Vec2::new(self.position.x + 8.0, item_y + row_height * 0.5),
+                    Vec2::new(self.width - 16.0, 1.0),
+                    Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.5),
+                    0.0
+                );
+                item_y += row_height;
+                continue;
+            }
             
             // Hover highlight
             if self.hovered_index == Some(i) {
                 renderer.draw_overlay_rect(
                     Vec2::new(self.position.x + 4.0, item_y + 2.0),
-                    Vec2::new(self.width - 8.0, self.item_height - 4.0),
+                    Vec2::new(self.width - 8.0, row_height - 4.0),
                     Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.3),
                     4.0
                 );
             }
             
+            let (text_color, secondary_color) = if item.enabled {
+                (theme.text, theme.text_secondary)
+            } else {
+                (theme.text.truncate().extend(0.35), theme.text_secondary.truncate().extend(0.3))
+            };
+            
+            // Check mark, falling back to the icon
+            let leading_text = match (&item.checked, &item.icon) {
+                (Some(true), _) => Some("✓"),
+                (_, Some(icon)) => Some(icon.as_str()),
+                _ => None,
+            };
+            if let Some(glyph) = leading_text {
+                renderer.draw_overlay_text(glyph, Vec2::new(self.position.x + 12.0, item_y + 8.0), 15.0, text_color);
+            }
+            
             // Label
-            renderer.draw_overlay_text(&item.label, Vec2::new(self.position.x + 12.0, item_y + 8.0), 15.0, theme.text);
+            renderer.draw_overlay_text(&item.label, Vec2::new(self.position.x + 12.0 + leading, item_y + 8.0), 15.0, text_color);
             
-            // Shortcut
-            if let Some(shortcut) = &item.shortcut {
+            // Submenu arrow or shortcut
+            if item.submenu.is_some() {
+                renderer.draw_overlay_text("›", Vec2::new(self.position.x + self.width - 20.0, item_y + 7.0), 15.0, secondary_color);
+            } else if let Some(shortcut) = &item.shortcut {
                 let shortcut_x = self.position.x + self.width - 12.0 - shortcut.len() as f32 * 7.0;
-                renderer.draw_overlay_text(shortcut, Vec2::new(shortcut_x, item_y + 8.0), 13.0, theme.text_secondary);
+                renderer.draw_overlay_text(shortcut, Vec2::new(shortcut_x, item_y + 8.0), 13.0, secondary_color);
             }
+            
+            item_y += row_height;
+        }
+        
+        if let Some(sub) = self.submenu() {
+            sub.render_menu(renderer);
+        }
+    }
+}
+
+impl Widget for ContextMenu {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.bounds = origin + max_size;
+        Vec2::new(self.width, self.total_height())
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.area = Vec2::new(constraints.max_width, constraints.max_height);
+        constraints.constrain(Size::new(self.width, self.total_height()))
+    }
+    
+    /// The menu opens wherever it is shown; only the bounds follow the layout
+    fn set_position(&mut self, position: Offset) {
+        self.bounds = Vec2::new(position.x, position.y) + self.area;
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if !self.visible {
+            return false;
+        }
+        
+        let winit::event::Event::WindowEvent { event, .. } = event else { return false };
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                self.hover(mouse_pos);
+                self.contains_tree(mouse_pos)
+            }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                match self.click(mouse_pos) {
+                    MenuResponse::Activated | MenuResponse::Outside => self.hide(),
+                    _ => {}
+                }
+                true
+            }
+            WindowEvent::KeyboardInput { event: key_event, .. } if key_event.state.is_pressed() => {
+                if let Key::Named(key) = &key_event.logical_key {
+                    match self.handle_key(key) {
+                        MenuResponse::Activated => self.hide(),
+                        MenuResponse::Close if *key == NamedKey::Escape => self.hide(),
+                        _ => {}
+                    }
+                }
+                true
+            }
+            _ => self.contains_tree(mouse_pos),
+        }
+    }
+    
+    fn hit_test_popup(&self, _point: Vec2) -> bool {
+        // An open menu takes every press; one outside it closes the menu
+        self.visible
+    }
+
+    fn update(&mut self, dt: f32) {
+        if let Some((index, elapsed)) = self.pending_submenu {
+            let elapsed = elapsed + dt;
+            if elapsed >= self.submenu_delay {
+                self.pending_submenu = None;
+                self.open_submenu_at(index);
+            } else {
+                self.pending_submenu = Some((index, elapsed));
+            }
+        }
+        if let Some(sub) = self.submenu_mut() {
+            sub.update(dt);
+        }
+    }
+    
+    fn is_animating(&self) -> bool {
+        self.pending_submenu.is_some() || self.submenu().is_some_and(|sub| sub.is_animating())
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        if self.visible {
+            self.render_menu(renderer);
         }
     }
 }
@@ -288,15 +775,64 @@ impl ContextMenuTrigger {
             menu: ContextMenu::new(items),
         }
     }
+    
+    /// Open the menu on a right-click over the child
+    fn handle_right_click(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        // Right-click detection
+        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
+                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
+        
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Right, .. }, .. } = event {
+            if inside {
+                self.menu.show(mouse_pos);
+                return true;
+            }
+        }
+        
+        false
+    }
 }
 
 impl Widget for ContextMenuTrigger {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = self.child.layout(origin, max_size);
-        self.menu.layout(Vec2::ZERO, max_size);
+        self.size = self.child.layout_child(origin, max_size);
+        self.menu.layout_child(Vec2::ZERO, origin + max_size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.menu.layout_child_with_constraints(constraints);
+        let size = self.child.layout_child_with_constraints(constraints);
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.child.set_position(position);
+        self.menu.set_position(position);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn positioned(&self) -> Option<&super::layout::Positioned> {
+        self.child.positioned()
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        visitor(self.child.as_mut());
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Menu gets priority if visible
@@ -309,24 +845,32 @@ impl Widget for ContextMenuTrigger {
             return true;
         }
         
-        // Right-click detection
-        let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
-        
-        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Right, .. }, .. } = event {
-            if inside {
-                self.menu.show(mouse_pos);
-                return true;
-            }
+        self.handle_right_click(event, mouse_pos)
+    }
+    
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        self.menu.hit_test_popup(point)
+    }
+    
+    fn capture_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.menu.visible && self.menu.handle_event(event, mouse_pos)
+    }
+    
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.menu.visible && self.menu.handle_event(event, mouse_pos) {
+            return true;
         }
-        
-        false
+        self.handle_right_click(event, mouse_pos)
     }
 
     fn update(&mut self, dt: f32) {
         self.child.update(dt);
         self.menu.update(dt);
     }
+    
+    fn is_animating(&self) -> bool {
+        self.child.is_animating() || self.menu.is_animating()
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         self.child.render(renderer);
@@ -393,11 +937,53 @@ impl Widget for Modal {
         if self.visible {
             let content_origin = self.modal_position() + Vec2::new(16.0, 48.0);
             let content_size = Vec2::new(self.width - 32.0, self.height - 64.0);
-            self.content.layout(content_origin, content_size);
+            self.content.layout_child(content_origin, content_size);
         }
         
         Vec2::ZERO // Modal doesn't take layout space
     }
+    
+    /// Centered in the space it is offered, which it doesn't take
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let screen = constraints.biggest_or(Size::new(self.screen_size.x, self.screen_size.y));
+        self.screen_size = Vec2::new(screen.width, screen.height);
+        if self.visible {
+            self.content.layout_child_with_constraints(BoxConstraints::loose(Size::new(self.width - 32.0, self.height - 64.0)));
+        }
+        constraints.smallest()
+    }
+    
+    fn set_position(&mut self, _position: Offset) {
+        if self.visible {
+            let content_origin = self.modal_position() + Vec2::new(16.0, 48.0);
+            self.content.set_position(Offset::new(content_origin.x, content_origin.y));
+        }
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        if self.visible {
+            visitor(self.content.as_ref());
+        }
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        if self.visible {
+            visitor(self.content.as_mut());
+        }
+    }
+    
+    /// Closed content is still mounted, it just isn't visited
+    fn on_mount(&mut self) {
+        if !self.visible {
+            mount(self.content.as_mut());
+        }
+    }
+    
+    fn on_unmount(&mut self) {
+        if !self.visible {
+            unmount(self.content.as_mut());
+        }
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         if !self.visible {
@@ -435,6 +1021,26 @@ impl Widget for Modal {
         
         true // Block all events when modal is open
     }
+    
+    fn hit_test_popup(&self, _point: Vec2) -> bool {
+        // The backdrop covers the whole screen
+        self.visible
+    }
+    
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if !self.visible {
+            return false;
+        }
+        
+        // Click on backdrop closes modal
+        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
+            if !Rect::new(self.modal_position(), Vec2::new(self.width, self.height)).contains(mouse_pos) {
+                self.hide();
+            }
+        }
+        
+        true // Content left it unhandled; nothing below the backdrop gets it
+    }
 
     fn update(&mut self, dt: f32) {
         if self.visible {
@@ -450,16 +1056,28 @@ impl Widget for Modal {
         let theme = get_theme();
         let modal_pos = self.modal_position();
         
+        // Drawn in the overlay layer rather than with draw_overlay_*, so the
+        // content widgets end up above the backdrop too
+        let layer = renderer.layer();
+        renderer.set_layer(GlassRenderer::OVERLAY_LAYER);
+        
         // Backdrop - full screen darkening
-        renderer.draw_overlay_rect(
+        renderer.draw_rounded_rect(
             Vec2::ZERO,
             self.screen_size,
             Vec4::new(0.0, 0.0, 0.0, self.backdrop_alpha),
             0.0
         );
         
-        // Modal background
-        renderer.draw_overlay_rect(
+        // Shadow and modal background
+        renderer.draw_shadow(
+            modal_pos + Vec2::new(0.0, 12.0),
+            Vec2::new(self.width, self.height),
+            self.corner_radius,
+            32.0,
+            Vec4::new(0.0, 0.0, 0.0, 0.5)
+        );
+        renderer.draw_rounded_rect(
             modal_pos,
             Vec2::new(self.width, self.height),
             Vec4::new(0.1, 0.1, 0.12, 0.98),
@@ -467,7 +1085,7 @@ impl Widget for Modal {
         );
         
         // Border glow
-        renderer.draw_overlay_rect(
+        renderer.draw_rounded_rect(
             modal_pos - Vec2::splat(1.0),
             Vec2::new(self.width + 2.0, self.height + 2.0),
             Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.3),
@@ -475,7 +1093,7 @@ impl Widget for Modal {
         );
         
         // Title bar
-        renderer.draw_overlay_rect(
+        renderer.draw_rounded_rect(
             modal_pos,
             Vec2::new(self.width, 40.0),
             Vec4::new(0.06, 0.06, 0.08, 0.95),
@@ -483,13 +1101,154 @@ impl Widget for Modal {
         );
         
         // Title text
-        renderer.draw_overlay_text(&self.title, modal_pos + Vec2::new(16.0, 12.0), 18.0, theme.text);
+        renderer.draw_text(&self.title, modal_pos + Vec2::new(16.0, 12.0), 18.0, theme.text);
         
         // Close button (X)
         let close_x = modal_pos.x + self.width - 32.0;
-        renderer.draw_overlay_text("✕", Vec2::new(close_x, modal_pos.y + 12.0), 16.0, theme.text_secondary);
+        renderer.draw_text("✕", Vec2::new(close_x, modal_pos.y + 12.0), 16.0, theme.text_secondary);
         
         // Content is rendered by the child widget (already laid out in correct position)
         self.content.render(renderer);
+        renderer.set_layer(layer);
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn menu() -> ContextMenu {
+        let mut menu = ContextMenu::new(vec![
+            MenuItem::new("Cut").with_icon("✂"),
+            MenuItem::separator(),
+            MenuItem::new("Paste").disabled(),
+            MenuItem::new("Wrap").checkable(false),
+            MenuItem::new("More").with_submenu(vec![
+                MenuItem::new("First"),
+                MenuItem::new("Second"),
+            ]),
+        ]);
+        menu.layout(Vec2::ZERO, Vec2::new(800.0, 600.0));
+        menu
+    }
+
+    fn row_center(menu: &ContextMenu, index: usize) -> Vec2 {
+        Vec2::new(menu.position.x + menu.width * 0.5, menu.item_y(index) + menu.row_height(&menu.items[index]) * 0.5)
+    }
+
+    #[test]
+    fn test_modal_centers_content_in_constraints() {
+        use crate::widgets::Label;
+
+        let mut modal = Modal::new("Confirm", Box::new(Label::new("Sure?")));
+        modal.visible = true;
+        assert_eq!(modal.layout_with_constraints(BoxConstraints::loose(Size::new(800.0, 600.0))), Size::ZERO);
+        modal.set_position(Offset::new(30.0, 40.0));
+        let content = modal.modal_position() + Vec2::new(16.0, 48.0);
+        assert_eq!(modal.content.get_position(), Offset::new(content.x, content.y));
+        assert_eq!(modal.modal_position(), (Vec2::new(800.0, 600.0) - Vec2::new(modal.width, modal.height)) / 2.0);
+    }
+
+    #[test]
+    fn test_tooltip_stays_inside_window() {
+        let screen = Vec2::new(800.0, 600.0);
+        let size = Vec2::new(120.0, 40.0);
+        assert_eq!(place_tooltip(Vec2::new(100.0, 100.0), size, screen), Vec2::new(112.0, 116.0));
+        
+        // Near the right edge it slides left, near the bottom it flips above
+        let pos = place_tooltip(Vec2::new(780.0, 590.0), size, screen);
+        assert_eq!(pos.x, 800.0 - 4.0 - 120.0);
+        assert!(pos.y + size.y < 590.0);
+    }
+
+    #[test]
+    fn test_tooltip_fades_in_after_delay() {
+        let mut tooltip = Tooltip::new(Box::new(crate::widgets::Label::new("x")), "Help")
+            .with_delay(0.5)
+            .with_fade_in(0.2);
+        tooltip.hovered = true;
+        tooltip.update(0.4);
+        assert_eq!(tooltip.opacity(), 0.0);
+        assert!(tooltip.is_animating());
+        tooltip.update(0.2);
+        assert!((tooltip.opacity() - 0.5).abs() < 1e-4);
+        tooltip.update(0.2);
+        assert_eq!(tooltip.opacity(), 1.0);
+        assert!(!tooltip.is_animating());
+        
+        tooltip.hovered = false;
+        tooltip.update(0.016);
+        assert_eq!(tooltip.opacity(), 0.0);
+    }
+
+    #[test]
+    fn test_keyboard_skips_separators_and_disabled() {
+        let mut menu = menu();
+        menu.show(Vec2::new(10.0, 10.0));
+        menu.move_hover(1);
+        assert_eq!(menu.hovered_index, Some(0));
+        menu.move_hover(1);
+        assert_eq!(menu.hovered_index, Some(3));
+        menu.move_hover(-1);
+        assert_eq!(menu.hovered_index, Some(0));
+        menu.move_hover(-1);
+        assert_eq!(menu.hovered_index, Some(4));
+    }
+
+    #[test]
+    fn test_submenu_opens_after_hover_delay() {
+        let mut menu = menu();
+        menu.show(Vec2::new(10.0, 10.0));
+        menu.hover(row_center(&menu, 4));
+        menu.update(0.1);
+        assert_eq!(menu.open_submenu_index(), None);
+        assert!(menu.is_animating());
+        menu.update(0.2);
+        assert_eq!(menu.open_submenu_index(), Some(4));
+        let sub = menu.submenu().unwrap();
+        assert!(sub.visible);
+        assert!(sub.position.x > menu.position.x);
+
+        // Left closes only the submenu
+        assert_eq!(menu.handle_key(&NamedKey::ArrowLeft), MenuResponse::Handled);
+        assert_eq!(menu.open_submenu_index(), None);
+    }
+
+    #[test]
+    fn test_submenu_item_activates_and_checkable_toggles() {
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        let mut menu = menu();
+        menu.items[4].submenu.as_mut().unwrap().items[1].on_click = Some(Box::new(move || counter.set(counter.get() + 1)));
+        menu.show(Vec2::new(10.0, 10.0));
+
+        assert_eq!(menu.click(row_center(&menu, 2)), MenuResponse::Handled);
+        assert_eq!(menu.click(row_center(&menu, 3)), MenuResponse::Activated);
+        assert_eq!(menu.items[3].checked, Some(true));
+
+        menu.hovered_index = Some(4);
+        menu.handle_key(&NamedKey::ArrowRight);
+        menu.handle_key(&NamedKey::ArrowDown);
+        assert_eq!(menu.handle_key(&NamedKey::Enter), MenuResponse::Activated);
+        assert_eq!(clicks.get(), 1);
+    }
+
+    #[test]
+    fn test_menu_flips_near_window_edges() {
+        let mut menu = menu();
+        let height = menu.total_height();
+        menu.show(Vec2::new(750.0, 590.0));
+        assert_eq!(menu.position, Vec2::new(750.0 - menu.width, 590.0 - height));
+
+        // Submenu opens to the left when there is no room on the right
+        menu.show(Vec2::new(560.0, 10.0));
+        menu.activate(4);
+        assert!(menu.submenu().unwrap().position.x < menu.position.x);
     }
 }
@@ -9,7 +9,8 @@
 use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
-use crate::widgets::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, Rect, Offset, Size, EdgeInsets};
+use crate::widgets::core::{Widget, get_theme, rounded_opaque_rect, format_color};
 use crate::panel_style::PanelPreset;
 
 // =============================================================================
@@ -165,9 +166,8 @@ pub struct ControllablePanel {
     restore_position: Vec2,
     restore_size: Vec2,
     
-    // Double-click detection
-    last_click_time: f32,
-    click_count: u8,
+    /// Space the last layout offered, which maximizing fills
+    available: Vec2,
     
     // Drag state
     drag_start: Option<Vec2>,
@@ -175,6 +175,9 @@ pub struct ControllablePanel {
     drag_start_size: Vec2,
 }
 
+/// Height of the control bar across the top of the panel
+const CONTROL_BAR_HEIGHT: f32 = 28.0;
+
 /// Available presets for cycling
 const PRESETS: [PanelPreset; 6] = [
     PanelPreset::Default,
@@ -210,8 +213,7 @@ impl ControllablePanel {
             maximized: false,
             restore_position: Vec2::ZERO,
             restore_size: Vec2::new(300.0, 200.0),
-            last_click_time: 0.0,
-            click_count: 0,
+            available: Vec2::ZERO,
             drag_start: None,
             drag_start_pos: Vec2::ZERO,
             drag_start_size: Vec2::ZERO,
@@ -241,8 +243,7 @@ impl ControllablePanel {
             maximized: false,
             restore_position: Vec2::ZERO,
             restore_size: Vec2::new(300.0, 200.0),
-            last_click_time: 0.0,
-            click_count: 0,
+            available: Vec2::ZERO,
             drag_start: None,
             drag_start_pos: Vec2::ZERO,
             drag_start_size: Vec2::ZERO,
@@ -312,23 +313,6 @@ impl ControllablePanel {
         self.update_button_positions();
     }
     
-    /// Check double-click and return true if detected
-    fn check_double_click(&mut self, current_time: f32) -> bool {
-        const DOUBLE_CLICK_TIME: f32 = 0.3;  // 300ms
-        
-        if current_time - self.last_click_time < DOUBLE_CLICK_TIME {
-            self.click_count += 1;
-            if self.click_count >= 2 {
-                self.click_count = 0;
-                return true;
-            }
-        } else {
-            self.click_count = 1;
-        }
-        self.last_click_time = current_time;
-        false
-    }
-    
     /// Update button positions based on panel position
     fn update_button_positions(&mut self) {
         let button_size = 20.0;
@@ -347,32 +331,9 @@ impl ControllablePanel {
         point.x >= self.position.x && point.x <= self.position.x + self.size.x &&
         point.y >= self.position.y && point.y <= self.position.y + self.size.y
     }
-}
-
-impl Widget for ControllablePanel {
-    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
-        // Use stored position if set, otherwise use origin
-        if self.position == Vec2::ZERO {
-            self.position = origin;
-        }
-        
-        // Constrain size
-        self.size = self.size.clamp(self.min_size, max_size);
-        
-        // Layout content
-        if let Some(content) = &mut self.content {
-            let content_origin = self.position + Vec2::splat(self.padding);
-            let content_max = self.size - Vec2::splat(self.padding * 2.0);
-            content.layout(content_origin, content_max);
-        }
-        
-        // Update button positions
-        self.update_button_positions();
-        
-        self.size
-    }
-
-    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+    
+    /// Selection, control buttons, move/resize drags and mode keys
+    fn handle_panel_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         let inside = self.contains(mouse_pos);
         
         // Update show_controls based on hover
@@ -518,6 +479,95 @@ impl Widget for ControllablePanel {
             _ => {}
         }
         
+        false
+    }
+}
+
+impl Widget for ControllablePanel {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        // Use stored position if set, otherwise use origin
+        if self.position == Vec2::ZERO {
+            self.position = origin;
+        }
+        
+        // Constrain size
+        self.available = max_size;
+        self.size = self.size.clamp(self.min_size, max_size);
+        
+        // Layout content
+        if let Some(content) = &mut self.content {
+            let content_origin = self.position + Vec2::splat(self.padding);
+            let content_max = self.size - Vec2::splat(self.padding * 2.0);
+            content.layout_child(content_origin, content_max);
+        }
+        
+        // Update button positions
+        self.update_button_positions();
+        
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.available = Vec2::new(constraints.max_width, constraints.max_height);
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height).clamp(self.min_size, self.available);
+        if let Some(content) = &mut self.content {
+            let content_max = self.size - Vec2::splat(self.padding * 2.0);
+            content.layout_child_with_constraints(BoxConstraints::loose(Size::new(content_max.x, content_max.y)));
+        }
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    /// A panel that has been dragged keeps its place
+    fn set_position(&mut self, position: Offset) {
+        if self.position == Vec2::ZERO {
+            self.position = Vec2::new(position.x, position.y);
+        }
+        if let Some(content) = &mut self.content {
+            let content_origin = self.position + Vec2::splat(self.padding);
+            content.set_position(Offset::new(content_origin.x, content_origin.y));
+        }
+        self.update_button_positions();
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a (dyn Widget + 'static))) {
+        if let Some(content) = &self.content {
+            visitor(content.as_ref());
+        }
+    }
+    
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut (dyn Widget + 'static))) {
+        if let Some(content) = &mut self.content {
+            visitor(content.as_mut());
+        }
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("mode", format!("{:?}", self.control_mode)));
+        props.push(("selected", self.selected.to_string()));
+        props.push(("maximized", self.maximized.to_string()));
+        props.push(("padding", format!("{:.0}", self.padding)));
+        props.push(("corner radius", format!("{:.0}", self.corner_radius)));
+        props.push(("color", format_color(self.color)));
+    }
+    
+    fn content_insets(&self) -> EdgeInsets {
+        EdgeInsets::all(self.padding)
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.handle_panel_event(event, mouse_pos) {
+            return true;
+        }
+        
         // Forward to content
         if let Some(content) = &mut self.content {
             if content.handle_event(event, mouse_pos) {
@@ -527,6 +577,25 @@ impl Widget for ControllablePanel {
         
         false
     }
+    
+    fn capture_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        // Presses anywhere on the panel select it before the content sees them
+        self.handle_panel_event(event, mouse_pos)
+    }
+    
+    fn handle_routed_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        self.handle_panel_event(event, mouse_pos)
+    }
+    
+    /// Double-clicking the control bar maximizes or restores the panel
+    fn handle_multi_click(&mut self, clicks: u32, mouse_pos: Vec2) -> bool {
+        let bar = Rect::new(self.position, Vec2::new(self.size.x, CONTROL_BAR_HEIGHT));
+        if clicks != 2 || !bar.contains(mouse_pos) {
+            return false;
+        }
+        self.toggle_maximize(self.available);
+        true
+    }
 
     fn update(&mut self, dt: f32) {
         // Update button animations for joyful feedback
@@ -538,6 +607,19 @@ impl Widget for ControllablePanel {
             content.update(dt);
         }
     }
+    
+    fn render_bounds(&self) -> Option<Rect> {
+        // Selection border extends 2px past the panel
+        let own = Rect::new(self.position, self.size).inset(-2.0);
+        match self.content.as_ref().and_then(|c| c.render_bounds()) {
+            Some(content) => Some(own.union(&content)),
+            None => Some(own),
+        }
+    }
+    
+    fn opaque_rect(&self) -> Option<Rect> {
+        Some(rounded_opaque_rect(self.position, self.size, self.corner_radius))
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
@@ -558,10 +640,9 @@ impl Widget for ControllablePanel {
         // Control bar (top)
         if self.show_controls {
             // Subtle control bar background
-            let bar_height = 28.0;
             renderer.draw_rounded_rect(
                 self.position,
-                Vec2::new(self.size.x, bar_height),
+                Vec2::new(self.size.x, CONTROL_BAR_HEIGHT),
                 Vec4::new(0.0, 0.0, 0.0, 0.3),
                 self.corner_radius,
             );
@@ -585,8 +666,12 @@ impl Widget for ControllablePanel {
             
             // Mode indicator
             if self.control_mode != PanelControlMode::None {
-                let mode_text = format!("[{}] {}", self.control_mode.key_hint(), self.control_mode.description());
-                renderer.draw_text(&mode_text, self.position + Vec2::new(8.0, 6.0), 12.0, theme.text_secondary);
+                renderer.draw_text_fmt(
+                    format_args!("[{}] {}", self.control_mode.key_hint(), self.control_mode.description()),
+                    self.position + Vec2::new(8.0, 6.0),
+                    12.0,
+                    theme.text_secondary,
+                );
             }
         }
         
@@ -604,7 +689,7 @@ impl Widget for ControllablePanel {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::widgets::Label;
+    use crate::widgets::{Label, is_occluded};
     
     #[test]
     fn test_panel_controls() {
@@ -629,4 +714,28 @@ mod tests {
         panel.cycle_color();
         assert_ne!(panel.color, initial_color);
     }
+    
+    #[test]
+    fn test_maximized_panel_occludes() {
+        let back = ControllablePanel::new_empty().at(100.0, 100.0).sized(200.0, 150.0);
+        let mut front = ControllablePanel::new_empty().at(50.0, 50.0).sized(300.0, 200.0);
+        assert!(!is_occluded(&back, [&front as &dyn Widget]));
+        
+        front.toggle_maximize(Vec2::new(1280.0, 720.0));
+        assert!(is_occluded(&back, [&front as &dyn Widget]));
+        assert!(!is_occluded(&front, [&back as &dyn Widget]));
+    }
+    
+    #[test]
+    fn test_bar_double_click_maximizes() {
+        let mut panel = ControllablePanel::new_empty().at(100.0, 100.0).sized(300.0, 200.0);
+        panel.layout(Vec2::ZERO, Vec2::new(1280.0, 720.0));
+        assert!(!panel.handle_multi_click(2, Vec2::new(200.0, 250.0)), "only the control bar");
+        assert!(panel.handle_multi_click(2, Vec2::new(200.0, 110.0)));
+        panel.layout(Vec2::ZERO, Vec2::new(1280.0, 720.0));
+        assert_eq!((panel.position, panel.size), (Vec2::ZERO, Vec2::new(1280.0, 720.0)));
+        
+        assert!(panel.handle_multi_click(2, Vec2::new(200.0, 10.0)));
+        assert_eq!(panel.position, Vec2::new(100.0, 100.0));
+    }
 }
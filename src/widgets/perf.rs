@@ -0,0 +1,505 @@
+//! GlassUI Performance Overlay
+//!
+//! Streaming chart of per-stage frame timings:
+//! - PerfOverlay - stacked bars per frame with a legend of averages
+//! - FrameStats - FPS, 1% lows, frame-time histogram and scene counts
+//!
+//! Feed them once per frame (`push(context.frame_timings())`,
+//! `record_frame(dt)`, `set_render_stats(context.frame_stats())`) and
+//! render them as top layers (e.g. via `GlassContext::render_layers`).
+
+use std::collections::VecDeque;
+use glam::{Vec2, Vec4};
+use crate::profiler::{FrameTimings, PerfStage, RenderStats};
+use crate::renderer::GlassRenderer;
+use crate::widget_id::WidgetId;
+use crate::widgets::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, Offset, Size};
+
+// =============================================================================
+// PERF OVERLAY
+// =============================================================================
+
+/// Toggleable overlay plotting CPU/GPU frame timings
+pub struct PerfOverlay {
+    pub id: WidgetId,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub visible: bool,
+    /// Number of frames kept in the chart
+    pub max_samples: usize,
+    /// Milliseconds at the top of the chart (16.6 = 60 FPS budget)
+    pub budget_ms: f32,
+    samples: VecDeque<FrameTimings>,
+}
+
+impl PerfOverlay {
+    pub fn new() -> Self {
+        Self {
+            id: WidgetId::new(),
+            position: Vec2::new(16.0, 16.0),
+            size: Vec2::new(320.0, 200.0),
+            visible: false,
+            max_samples: 120,
+            budget_ms: 16.6,
+            samples: VecDeque::new(),
+        }
+    }
+
+    pub fn at(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_budget(mut self, budget_ms: f32) -> Self {
+        self.budget_ms = budget_ms.max(0.1);
+        self
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Append one frame's timings, dropping the oldest beyond `max_samples`
+    pub fn push(&mut self, timings: FrameTimings) {
+        self.samples.push_back(timings);
+        while self.samples.len() > self.max_samples {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &FrameTimings> {
+        self.samples.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Average milliseconds for a stage over the recorded frames
+    pub fn average(&self, stage: PerfStage) -> Option<f32> {
+        let (sum, count) = self.samples.iter()
+            .filter_map(|s| s.get(stage))
+            .fold((0.0f32, 0usize), |(sum, count), ms| (sum + ms, count + 1));
+        (count > 0).then(|| sum / count as f32)
+    }
+
+    fn stage_color(stage: PerfStage) -> Vec4 {
+        match stage {
+            PerfStage::Update => Vec4::new(0.4, 0.7, 1.0, 0.9),
+            PerfStage::Layout => Vec4::new(0.5, 0.9, 0.5, 0.9),
+            PerfStage::Encode => Vec4::new(0.9, 0.7, 0.2, 0.9),
+            PerfStage::GpuScene => Vec4::new(0.7, 0.5, 1.0, 0.9),
+            PerfStage::GpuBlur => Vec4::new(1.0, 0.4, 0.6, 0.9),
+            PerfStage::GpuFinal => Vec4::new(0.3, 0.9, 0.9, 0.9),
+        }
+    }
+}
+
+impl Default for PerfOverlay {
+    fn default() -> Self { Self::new() }
+}
+
+impl Widget for PerfOverlay {
+    fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
+        // Positioned absolutely; takes no space in the parent layout
+        Vec2::ZERO
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        constraints.smallest()
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+
+    /// Layout reads and changes nothing
+    fn needs_layout(&self) -> bool {
+        false
+    }
+
+    fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
+        false
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn is_animating(&self) -> bool {
+        // Keep frames flowing so the chart streams
+        self.visible
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        if !self.visible {
+            return;
+        }
+        let theme = get_theme();
+        let padding = 10.0;
+        let legend_height = 3.0 * 16.0;
+
+        // Background
+        renderer.draw_overlay_rect(self.position, self.size, Vec4::new(0.04, 0.04, 0.08, 0.9), 8.0);
+
+        // Chart area
+        let chart_pos = self.position + Vec2::splat(padding);
+        let chart_size = Vec2::new(
+            self.size.x - padding * 2.0,
+            self.size.y - padding * 3.0 - legend_height,
+        );
+        renderer.draw_overlay_rect(chart_pos, chart_size, Vec4::new(1.0, 1.0, 1.0, 0.04), 4.0);
+
+        // Stacked bars, newest on the right
+        let bar_width = chart_size.x / self.max_samples.max(1) as f32;
+        let px_per_ms = chart_size.y / self.budget_ms;
+        let offset = self.max_samples.saturating_sub(self.samples.len());
+        for (i, sample) in self.samples.iter().enumerate() {
+            let x = chart_pos.x + (offset + i) as f32 * bar_width;
+            let mut y = chart_pos.y + chart_size.y;
+            for stage in PerfStage::ALL {
+                let Some(ms) = sample.get(stage) else { continue };
+                let h = (ms * px_per_ms).min(y - chart_pos.y);
+                if h <= 0.0 {
+                    continue;
+                }
+                y -= h;
+                renderer.draw_overlay_rect(
+                    Vec2::new(x, y),
+                    Vec2::new(bar_width.max(1.0), h),
+                    Self::stage_color(stage),
+                    0.0,
+                );
+            }
+        }
+
+        // Budget line at the top of the chart
+        renderer.draw_overlay_text_fmt(
+            format_args!("{:.1} ms", self.budget_ms),
+            chart_pos + Vec2::new(4.0, 2.0),
+            11.0,
+            theme.text_secondary,
+        );
+
+        // Legend (two columns of three stages)
+        let legend_pos = Vec2::new(chart_pos.x, chart_pos.y + chart_size.y + padding);
+        let column_width = chart_size.x / 2.0;
+        for (i, stage) in PerfStage::ALL.iter().enumerate() {
+            let pos = legend_pos + Vec2::new((i / 3) as f32 * column_width, (i % 3) as f32 * 16.0);
+            renderer.draw_overlay_rect(pos + Vec2::new(0.0, 3.0), Vec2::splat(8.0), Self::stage_color(*stage), 2.0);
+            let text_pos = pos + Vec2::new(14.0, 0.0);
+            match self.average(*stage) {
+                Some(ms) => renderer.draw_overlay_text_fmt(format_args!("{} {:.2} ms", stage.label(), ms), text_pos, 12.0, theme.text),
+                None => renderer.draw_overlay_text_fmt(format_args!("{} n/a", stage.label()), text_pos, 12.0, theme.text),
+            }
+        }
+    }
+}
+
+// =============================================================================
+// FRAME STATS
+// =============================================================================
+
+/// Toggleable FPS counter with a frame-time histogram
+pub struct FrameStats {
+    pub id: WidgetId,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub visible: bool,
+    /// Seconds of history kept for the averages and histogram
+    pub window_secs: f32,
+    /// Width of one histogram bucket in milliseconds
+    pub bucket_ms: f32,
+    /// Number of histogram buckets; the last one collects everything slower
+    pub buckets: usize,
+    frame_ms: VecDeque<f32>,
+    total_ms: f32,
+    widget_count: usize,
+    instance_count: usize,
+    layout_count: usize,
+    render_stats: Option<RenderStats>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self {
+            id: WidgetId::new(),
+            position: Vec2::new(16.0, 16.0),
+            size: Vec2::new(240.0, 166.0),
+            visible: false,
+            window_secs: 5.0,
+            bucket_ms: 2.0,
+            buckets: 20,
+            frame_ms: VecDeque::new(),
+            total_ms: 0.0,
+            widget_count: 0,
+            instance_count: 0,
+            layout_count: 0,
+            render_stats: None,
+        }
+    }
+
+    pub fn at(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_window(mut self, seconds: f32) -> Self {
+        self.window_secs = seconds.max(0.1);
+        self
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Record one frame's delta time (seconds), dropping history older than `window_secs`
+    pub fn record_frame(&mut self, dt: f32) {
+        let ms = (dt * 1000.0).max(0.0);
+        self.frame_ms.push_back(ms);
+        self.total_ms += ms;
+        while self.frame_ms.len() > 1 && self.total_ms - self.frame_ms[0] >= self.window_secs * 1000.0 {
+            if let Some(old) = self.frame_ms.pop_front() {
+                self.total_ms -= old;
+            }
+        }
+    }
+
+    /// Update the widget and glass instance counts shown in the overlay
+    pub fn set_counts(&mut self, widgets: usize, instances: usize) {
+        self.widget_count = widgets;
+        self.instance_count = instances;
+    }
+
+    /// Layouts run this frame (from `take_layout_count`); zero when every
+    /// subtree reused its cached size
+    pub fn set_layout_count(&mut self, layouts: usize) {
+        self.layout_count = layouts;
+    }
+
+    /// Show the renderer's per-kind counts and GPU time
+    /// (from `GlassRenderer::frame_stats`)
+    pub fn set_render_stats(&mut self, stats: RenderStats) {
+        self.render_stats = Some(stats);
+    }
+
+    pub fn clear(&mut self) {
+        self.frame_ms.clear();
+        self.total_ms = 0.0;
+    }
+
+    /// Average frames per second over the window
+    pub fn fps(&self) -> Option<f32> {
+        (self.total_ms > 0.0).then(|| self.frame_ms.len() as f32 * 1000.0 / self.total_ms)
+    }
+
+    /// FPS over the slowest 1% of frames (at least one frame)
+    pub fn one_percent_low(&self) -> Option<f32> {
+        if self.frame_ms.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f32> = self.frame_ms.iter().copied().collect();
+        sorted.sort_by(|a, b| b.total_cmp(a));
+        let count = sorted.len().div_ceil(100);
+        let avg_ms = sorted[..count].iter().sum::<f32>() / count as f32;
+        (avg_ms > 0.0).then(|| 1000.0 / avg_ms)
+    }
+
+    /// Most recent frame time in milliseconds
+    pub fn last_frame_ms(&self) -> Option<f32> {
+        self.frame_ms.back().copied()
+    }
+
+    /// Frame counts per `bucket_ms`-wide bucket
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.buckets.max(1)];
+        let last = counts.len() - 1;
+        for ms in &self.frame_ms {
+            let bucket = ((ms / self.bucket_ms.max(0.01)) as usize).min(last);
+            counts[bucket] += 1;
+        }
+        counts
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self { Self::new() }
+}
+
+impl Widget for FrameStats {
+    fn layout(&mut self, _origin: Vec2, _max_size: Vec2) -> Vec2 {
+        // Positioned absolutely; takes no space in the parent layout
+        Vec2::ZERO
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        constraints.smallest()
+    }
+
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+
+    /// Layout reads and changes nothing
+    fn needs_layout(&self) -> bool {
+        false
+    }
+
+    fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
+        false
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn is_animating(&self) -> bool {
+        // Keep frames flowing so the counters stay live
+        self.visible
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        if !self.visible {
+            return;
+        }
+        let theme = get_theme();
+        let padding = 10.0;
+        let line_height = 16.0;
+
+        renderer.draw_overlay_rect(self.position, self.size, Vec4::new(0.04, 0.04, 0.08, 0.9), 8.0);
+
+        // Counters
+        let text_pos = self.position + Vec2::splat(padding);
+        match (self.fps(), self.one_percent_low()) {
+            (Some(fps), Some(low)) => renderer.draw_overlay_text_fmt(
+                format_args!("{:.0} FPS  1% low {:.0}", fps, low), text_pos, 14.0, theme.text),
+            _ => renderer.draw_overlay_text("-- FPS", text_pos, 14.0, theme.text),
+        }
+        renderer.draw_overlay_text_fmt(
+            format_args!("{:.2} ms  {} widgets  {} layouts  {} instances",
+                self.last_frame_ms().unwrap_or(0.0), self.widget_count, self.layout_count, self.instance_count),
+            text_pos + Vec2::new(0.0, line_height + 2.0),
+            11.0,
+            theme.text_secondary,
+        );
+        let mut lines = 2.0;
+        if let Some(stats) = &self.render_stats {
+            let gpu = stats.timings.gpu_ms().map(|ms| format!("  GPU {:.2} ms", ms)).unwrap_or_default();
+            renderer.draw_overlay_text_fmt(
+                format_args!("{} shapes  {} text quads  {} glyphs ({:.0}% hit)  {} batches{}",
+                    stats.shape_instances + stats.mesh_triangles + stats.image_instances, stats.text_quads,
+                    stats.glyph_cache.cached_glyphs, stats.glyph_cache.hit_rate() * 100.0, stats.batches, gpu),
+                text_pos + Vec2::new(0.0, line_height * 2.0 + 2.0),
+                11.0,
+                theme.text_secondary,
+            );
+            lines = 3.0;
+        }
+
+        // Histogram of frame times, fastest bucket on the left
+        let chart_pos = self.position + Vec2::new(padding, padding + line_height * lines + 8.0);
+        let chart_size = Vec2::new(self.size.x - padding * 2.0, self.size.y - (chart_pos.y - self.position.y) - padding - 12.0);
+        renderer.draw_overlay_rect(chart_pos, chart_size, Vec4::new(1.0, 1.0, 1.0, 0.04), 4.0);
+        let histogram = self.histogram();
+        let max = histogram.iter().copied().max().unwrap_or(0).max(1);
+        let bar_width = chart_size.x / histogram.len() as f32;
+        let budget_bucket = (16.6 / self.bucket_ms.max(0.01)) as usize;
+        for (i, &count) in histogram.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let h = chart_size.y * count as f32 / max as f32;
+            let color = if i <= budget_bucket { theme.success } else { theme.warning };
+            renderer.draw_overlay_rect(
+                Vec2::new(chart_pos.x + i as f32 * bar_width, chart_pos.y + chart_size.y - h),
+                Vec2::new((bar_width - 1.0).max(1.0), h),
+                color,
+                0.0,
+            );
+        }
+        renderer.draw_overlay_text_fmt(
+            format_args!("0 - {:.0}+ ms over {:.0}s", self.bucket_ms * (histogram.len() - 1) as f32, self.window_secs),
+            Vec2::new(chart_pos.x, chart_pos.y + chart_size.y + 1.0),
+            10.0,
+            theme.text_secondary,
+        );
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_drops_oldest() {
+        let mut overlay = PerfOverlay::new();
+        overlay.max_samples = 3;
+        for i in 0..5 {
+            overlay.push(FrameTimings { update_ms: i as f32, ..Default::default() });
+        }
+        let updates: Vec<f32> = overlay.samples().map(|s| s.update_ms).collect();
+        assert_eq!(updates, vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_average() {
+        let mut overlay = PerfOverlay::new();
+        assert_eq!(overlay.average(PerfStage::Layout), None);
+
+        overlay.push(FrameTimings { layout_ms: 1.0, ..Default::default() });
+        overlay.push(FrameTimings { layout_ms: 3.0, gpu_blur_ms: Some(2.0), ..Default::default() });
+        assert_eq!(overlay.average(PerfStage::Layout), Some(2.0));
+        assert_eq!(overlay.average(PerfStage::GpuBlur), Some(2.0));
+        assert_eq!(overlay.average(PerfStage::GpuScene), None);
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut overlay = PerfOverlay::new();
+        assert!(!overlay.visible);
+        overlay.toggle();
+        assert!(overlay.visible);
+        assert!(overlay.is_animating());
+    }
+
+    #[test]
+    fn test_frame_stats_window() {
+        let mut stats = FrameStats::new().with_window(1.0);
+        assert_eq!(stats.fps(), None);
+        for _ in 0..120 {
+            stats.record_frame(1.0 / 60.0);
+        }
+        // Only about one second of history is kept
+        assert!((stats.fps().unwrap() - 60.0).abs() < 0.5);
+        assert!(stats.frame_ms.len() <= 61);
+    }
+
+    #[test]
+    fn test_frame_stats_lows_and_histogram() {
+        let mut stats = FrameStats::new();
+        for _ in 0..99 {
+            stats.record_frame(0.011);
+        }
+        stats.record_frame(0.050);
+        assert!((stats.one_percent_low().unwrap() - 20.0).abs() < 0.01);
+
+        let histogram = stats.histogram();
+        assert_eq!(histogram.len(), stats.buckets);
+        assert_eq!(histogram[5], 99);
+        assert_eq!(histogram[stats.buckets - 1], 1);
+    }
+}
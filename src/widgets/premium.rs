@@ -1,11 +1,23 @@
 //! GlassUI Premium Widgets
 //!
-//! High-quality widgets with advanced animations: ProgressBar, Toggle, RadioGroup, NumberInput
+//! High-quality widgets with advanced animations: ProgressBar, Toggle, RadioGroup, NumberInput, Rating
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
 use glam::{Vec2, Vec4};
 use winit::event::{ElementState, MouseButton};
-use crate::renderer::GlassRenderer;
-use super::core::{Widget, get_theme, easing};
+use winit::keyboard::ModifiersState;
+use crate::renderer::{GlassRenderer, Gradient};
+use super::input::{Validator, ERROR_LINE};
+use crate::clipboard::ClipboardAction;
+use crate::focus::{FocusId, Focusable};
+use super::core::{Widget, WidgetState, UpdateState, get_theme, render_faded, easing};
+use super::controls::snap_to_step;
+use crate::layout::{BoxConstraints, Offset, Rect, Size};
+use crate::path::PathMesh;
+use crate::text::LabelCache;
 
 // =============================================================================
 // PROGRESS BAR
@@ -15,6 +27,8 @@ use super::core::{Widget, get_theme, easing};
 pub struct ProgressBar {
     pub position: Vec2,
     pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
     pub value: f32,
     pub target_value: f32,
     pub animated_value: f32,
@@ -22,8 +36,12 @@ pub struct ProgressBar {
     pub indeterminate_phase: f32,
     pub show_percentage: bool,
     pub color: Option<Vec4>,
+    /// Fill for the bar instead of a flat `color`
+    pub gradient: Option<Gradient>,
     pub glow_intensity: f32,
     pub corner_radius: f32,
+    /// Percentage text as last drawn
+    percent_label: LabelCache<i32>,
 }
 
 impl ProgressBar {
@@ -32,6 +50,7 @@ impl ProgressBar {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            layout_intrinsic: None,
             value: v,
             target_value: v,
             animated_value: v,
@@ -39,8 +58,10 @@ impl ProgressBar {
             indeterminate_phase: 0.0,
             show_percentage: true,
             color: None,
+            gradient: None,
             glow_intensity: 0.0,
             corner_radius: 6.0,
+            percent_label: LabelCache::new(),
         }
     }
     
@@ -48,6 +69,7 @@ impl ProgressBar {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            layout_intrinsic: None,
             value: 0.0,
             target_value: 0.0,
             animated_value: 0.0,
@@ -55,8 +77,10 @@ impl ProgressBar {
             indeterminate_phase: 0.0,
             show_percentage: false,
             color: None,
+            gradient: None,
             glow_intensity: 0.0,
             corner_radius: 6.0,
+            percent_label: LabelCache::new(),
         }
     }
     
@@ -68,32 +92,66 @@ impl ProgressBar {
         self.color = Some(color);
         self
     }
+    
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+    
+    fn draw_bar(&self, renderer: &mut GlassRenderer, pos: Vec2, size: Vec2, color: Vec4) {
+        match &self.gradient {
+            Some(gradient) => renderer.draw_rect_gradient(pos, size, gradient, self.corner_radius - 2.0),
+            None => renderer.draw_rounded_rect(pos, size, color, self.corner_radius - 2.0),
+        }
+    }
+    
+    fn default_size(&self) -> Size {
+        Size::new(250.0, 24.0)
+    }
 }
 
 impl Widget for ProgressBar {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(250.0, 24.0);
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
     }
 
     fn update(&mut self, dt: f32) {
-        let diff = self.target_value - self.animated_value;
-        self.animated_value += diff * 8.0 * dt;
-        
-        if diff.abs() > 0.001 {
-            self.glow_intensity = (self.glow_intensity + dt * 3.0).min(1.0);
-        } else {
-            self.glow_intensity = (self.glow_intensity - dt * 2.0).max(0.0);
-        }
-        
-        if self.indeterminate {
-            self.indeterminate_phase = (self.indeterminate_phase + dt * 1.5) % 1.0;
-        }
+        self.update_state(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, _dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        jobs.push(self);
     }
 
     fn render(&self, renderer: &mut GlassRenderer) {
@@ -108,7 +166,9 @@ impl Widget for ProgressBar {
             self.corner_radius - 2.0
         );
         
-        let bar_color = self.color.unwrap_or(theme.primary);
+        let bar_color = self.color
+            .or_else(|| self.gradient.map(|g| g.start_color()))
+            .unwrap_or(theme.primary);
         
         if self.indeterminate {
             let bar_width = self.size.x * 0.3;
@@ -116,11 +176,11 @@ impl Widget for ProgressBar {
             let ease_t = easing::ease_in_out_quart((self.indeterminate_phase * 2.0 - 1.0).abs());
             let x_offset = ease_t * travel;
             
-            renderer.draw_rounded_rect(
+            self.draw_bar(
+                renderer,
                 self.position + Vec2::new(2.0 + x_offset, 2.0),
                 Vec2::new(bar_width, self.size.y - 4.0),
-                bar_color,
-                self.corner_radius - 2.0
+                bar_color
             );
         } else {
             let fill_width = (self.size.x - 4.0) * self.animated_value;
@@ -135,22 +195,42 @@ impl Widget for ProgressBar {
                     );
                 }
                 
-                renderer.draw_rounded_rect(
+                self.draw_bar(
+                    renderer,
                     self.position + Vec2::new(2.0, 2.0),
                     Vec2::new(fill_width, self.size.y - 4.0),
-                    bar_color,
-                    self.corner_radius - 2.0
+                    bar_color
                 );
             }
             
             if self.show_percentage {
-                let pct = format!("{}%", (self.animated_value * 100.0).round() as i32);
+                let pct = (self.animated_value * 100.0).round() as i32;
+                let pct = self.percent_label.get(pct, |pct, text| {
+                    let _ = write!(text, "{}%", pct);
+                });
                 renderer.draw_text(&pct, Vec2::new(self.position.x + self.size.x + 10.0, self.position.y + 2.0), 16.0, theme.text);
             }
         }
     }
 }
 
+impl UpdateState for ProgressBar {
+    fn update_state(&mut self, dt: f32) {
+        let diff = self.target_value - self.animated_value;
+        self.animated_value += diff * 8.0 * dt;
+        
+        if diff.abs() > 0.001 {
+            self.glow_intensity = (self.glow_intensity + dt * 3.0).min(1.0);
+        } else {
+            self.glow_intensity = (self.glow_intensity - dt * 2.0).max(0.0);
+        }
+        
+        if self.indeterminate {
+            self.indeterminate_phase = (self.indeterminate_phase + dt * 1.5) % 1.0;
+        }
+    }
+}
+
 // =============================================================================
 // TOGGLE
 // =============================================================================
@@ -159,12 +239,18 @@ impl Widget for ProgressBar {
 pub struct Toggle {
     pub position: Vec2,
     pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
     pub checked: bool,
     pub label: String,
     pub animated_t: f32,
     pub spring_velocity: f32,
     pub hovered: bool,
     pub pressed: bool,
+    /// Called with the new state when the switch is flipped
+    pub on_toggle: Option<Box<dyn FnMut(bool) + Send>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
 }
 
 impl Toggle {
@@ -172,28 +258,82 @@ impl Toggle {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            layout_intrinsic: None,
             checked,
             label: label.to_string(),
             animated_t: if checked { 1.0 } else { 0.0 },
             spring_velocity: 0.0,
             hovered: false,
             pressed: false,
+            on_toggle: None,
+            disabled: false,
+        }
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; drops hover and press
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hovered = false;
+            self.pressed = false;
         }
     }
     
+    pub fn on_toggle(mut self, callback: impl FnMut(bool) + Send + 'static) -> Self {
+        self.on_toggle = Some(Box::new(callback));
+        self
+    }
+    
     pub fn is_checked(&self) -> bool {
         self.checked
     }
+    
+    fn default_size(&self) -> Size {
+        Size::new(200.0, 28.0)
+    }
 }
 
 impl Widget for Toggle {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(200.0, 28.0);
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.disabled {
+            return false;
+        }
         let track_width = 50.0;
         let inside = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + track_width &&
                      mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
@@ -208,6 +348,9 @@ impl Widget for Toggle {
                 if self.pressed && inside {
                     self.checked = !self.checked;
                     self.spring_velocity = if self.checked { 8.0 } else { -8.0 };
+                    if let Some(callback) = &mut self.on_toggle {
+                        callback(self.checked);
+                    }
                 }
                 self.pressed = false;
             }
@@ -216,6 +359,63 @@ impl Widget for Toggle {
     }
 
     fn update(&mut self, dt: f32) {
+        self.update_state(dt);
+    }
+
+    fn split_update<'a>(&'a mut self, _dt: f32, jobs: &mut Vec<&'a mut dyn UpdateState>) {
+        jobs.push(self);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+        render_faded(renderer, self.disabled, None, |renderer| {
+            let track_width = 50.0;
+            let track_height = 28.0;
+            let knob_size = 22.0;
+            let padding = 3.0;
+            
+            let off_color = Vec4::new(0.15, 0.15, 0.18, 0.9);
+            let on_color = theme.primary;
+            let track_color = off_color.lerp(on_color, self.animated_t);
+            
+            // Track glow
+            if self.animated_t > 0.1 {
+                renderer.draw_rounded_rect(
+                    self.position - Vec2::splat(3.0),
+                    Vec2::new(track_width + 6.0, track_height + 6.0),
+                    Vec4::new(on_color.x, on_color.y, on_color.z, 0.2 * self.animated_t),
+                    17.0
+                );
+            }
+            
+            // Track
+            renderer.draw_rounded_rect(self.position, Vec2::new(track_width, track_height), track_color, 14.0);
+            
+            // Knob
+            let knob_travel = track_width - knob_size - padding * 2.0;
+            let knob_x = self.position.x + padding + knob_travel * self.animated_t;
+            let knob_y = self.position.y + padding;
+            
+            // Shadow
+            renderer.draw_rounded_rect(
+                Vec2::new(knob_x + 1.0, knob_y + 2.0),
+                Vec2::new(knob_size, knob_size),
+                Vec4::new(0.0, 0.0, 0.0, 0.3),
+                11.0
+            );
+            
+            // Knob
+            let knob_color = if self.pressed { Vec4::new(0.85, 0.85, 0.85, 1.0) } else { Vec4::new(0.98, 0.98, 0.98, 1.0) };
+            renderer.draw_rounded_rect(Vec2::new(knob_x, knob_y), Vec2::new(knob_size, knob_size), knob_color, 11.0);
+            
+            // Label
+            renderer.draw_text(&self.label, self.position + Vec2::new(track_width + 12.0, 4.0), 16.0, theme.text);
+        });
+    }
+}
+
+impl UpdateState for Toggle {
+    fn update_state(&mut self, dt: f32) {
         let target = if self.checked { 1.0 } else { 0.0 };
         let spring_k = 180.0;
         let damping = 12.0;
@@ -228,51 +428,6 @@ impl Widget for Toggle {
         self.animated_t += self.spring_velocity * dt;
         self.animated_t = self.animated_t.clamp(0.0, 1.0);
     }
-
-    fn render(&self, renderer: &mut GlassRenderer) {
-        let theme = get_theme();
-        let track_width = 50.0;
-        let track_height = 28.0;
-        let knob_size = 22.0;
-        let padding = 3.0;
-        
-        let off_color = Vec4::new(0.15, 0.15, 0.18, 0.9);
-        let on_color = theme.primary;
-        let track_color = off_color.lerp(on_color, self.animated_t);
-        
-        // Track glow
-        if self.animated_t > 0.1 {
-            renderer.draw_rounded_rect(
-                self.position - Vec2::splat(3.0),
-                Vec2::new(track_width + 6.0, track_height + 6.0),
-                Vec4::new(on_color.x, on_color.y, on_color.z, 0.2 * self.animated_t),
-                17.0
-            );
-        }
-        
-        // Track
-        renderer.draw_rounded_rect(self.position, Vec2::new(track_width, track_height), track_color, 14.0);
-        
-        // Knob
-        let knob_travel = track_width - knob_size - padding * 2.0;
-        let knob_x = self.position.x + padding + knob_travel * self.animated_t;
-        let knob_y = self.position.y + padding;
-        
-        // Shadow
-        renderer.draw_rounded_rect(
-            Vec2::new(knob_x + 1.0, knob_y + 2.0),
-            Vec2::new(knob_size, knob_size),
-            Vec4::new(0.0, 0.0, 0.0, 0.3),
-            11.0
-        );
-        
-        // Knob
-        let knob_color = if self.pressed { Vec4::new(0.85, 0.85, 0.85, 1.0) } else { Vec4::new(0.98, 0.98, 0.98, 1.0) };
-        renderer.draw_rounded_rect(Vec2::new(knob_x, knob_y), Vec2::new(knob_size, knob_size), knob_color, 11.0);
-        
-        // Label
-        renderer.draw_text(&self.label, self.position + Vec2::new(track_width + 12.0, 4.0), 16.0, theme.text);
-    }
 }
 
 // =============================================================================
@@ -287,6 +442,8 @@ pub struct RadioGroup {
     pub selected_index: usize,
     pub hovered_index: Option<usize>,
     pub item_height: f32,
+    /// Called with the index of the option clicked
+    pub on_select: Option<Box<dyn FnMut(usize)>>,
 }
 
 impl RadioGroup {
@@ -298,9 +455,15 @@ impl RadioGroup {
             selected_index: 0,
             hovered_index: None,
             item_height: 32.0,
+            on_select: None,
         }
     }
     
+    pub fn on_select(mut self, callback: impl FnMut(usize) + 'static) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+    
     pub fn with_selected(mut self, index: usize) -> Self {
         if index < self.options.len() {
             self.selected_index = index;
@@ -311,14 +474,37 @@ impl RadioGroup {
     pub fn selected(&self) -> Option<&str> {
         self.options.get(self.selected_index).map(|s| s.as_str())
     }
+    
+    fn default_size(&self) -> Size {
+        Size::new(220.0, self.options.len() as f32 * self.item_height)
+    }
 }
 
 impl Widget for RadioGroup {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(220.0, self.options.len() as f32 * self.item_height);
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         let inside_x = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + self.size.x;
@@ -338,6 +524,9 @@ impl Widget for RadioGroup {
         if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } = event {
             if let Some(index) = self.hovered_index {
                 self.selected_index = index;
+                if let Some(callback) = &mut self.on_select {
+                    callback(index);
+                }
                 return true;
             }
         }
@@ -395,39 +584,104 @@ impl Widget for RadioGroup {
 // =============================================================================
 
 /// Numeric input with increment/decrement buttons
+///
+/// Clicking the value starts typing a new one, committed (parsed, clamped
+/// and formatted to `precision`) with Enter or when focus leaves, and
+/// dropped with Escape. Dragging the value sideways or turning the wheel
+/// over the field steps it, as do Up/Down while focused.
+///
+/// While focused, Ctrl+C (and Ctrl+X, as a number can't be emptied) copies
+/// the value and Ctrl+V replaces it with a pasted number, clamped to range.
+/// Validators see the formatted value after each change.
 pub struct NumberInput {
     pub position: Vec2,
     pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
     pub value: f64,
     pub min: f64,
     pub max: f64,
     pub step: f64,
     pub precision: usize,
     pub focused: bool,
+    /// Identifies the field to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
     pub text_buffer: String,
     pub hovered_btn: Option<bool>,
     pub pressed_btn: Option<bool>,
     pub repeat_timer: f32,
+    /// Called with the new value after each step
+    pub on_change: Option<Box<dyn FnMut(f64)>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
+    /// Checked in order by `validate`; the first error is shown
+    pub validators: Vec<Validator>,
+    /// Message from the last `validate`, if it failed
+    pub error: Option<String>,
+    /// `text_buffer` is being typed into
+    pub editing: bool,
+    /// The next key typed replaces the whole buffer (drawn selected)
+    replace_on_type: bool,
+    /// Pointer x and value where a press on the value started, while held
+    drag_start: Option<(f32, f64)>,
+    /// The held press has moved far enough to scrub the value
+    scrubbing: bool,
+    /// Modifier keys held, from the last `ModifiersChanged`
+    modifiers: ModifiersState,
 }
 
+/// Pointer travel per step when dragging a NumberInput's value
+const SCRUB_PIXELS: f32 = 4.0;
+
 impl NumberInput {
     pub fn new(value: f64) -> Self {
         Self {
             position: Vec2::ZERO,
             size: Vec2::ZERO,
+            layout_intrinsic: None,
             value,
             min: f64::MIN,
             max: f64::MAX,
             step: 1.0,
             precision: 2,
             focused: false,
+            focus_id: FocusId::new(),
             text_buffer: format!("{:.2}", value),
             hovered_btn: None,
             pressed_btn: None,
             repeat_timer: 0.0,
+            on_change: None,
+            disabled: false,
+            validators: Vec::new(),
+            error: None,
+            editing: false,
+            replace_on_type: false,
+            drag_start: None,
+            scrubbing: false,
+            modifiers: ModifiersState::empty(),
         }
     }
     
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded; stops a held step button
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hovered_btn = None;
+            self.pressed_btn = None;
+            self.focused = false;
+        }
+    }
+    
+    pub fn on_change(mut self, callback: impl FnMut(f64) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
     pub fn with_range(mut self, min: f64, max: f64) -> Self {
         self.min = min;
         self.max = max;
@@ -440,49 +694,303 @@ impl NumberInput {
         self
     }
     
+    /// Decimal places the value is shown and committed with
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self.text_buffer = format!("{:.prec$}", self.value, prec = precision);
+        self
+    }
+    
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+    
+    /// Whether the value passes the validators, e.g. before a form is
+    /// submitted
+    pub fn is_valid(&self) -> bool {
+        self.validators.iter().all(|validator| validator.check(&self.text_buffer).is_ok())
+    }
+    
+    /// Check the value now and show the error, if any; returns whether it
+    /// is valid
+    pub fn validate(&mut self) -> bool {
+        self.error = self.validators.iter().find_map(|validator| validator.check(&self.text_buffer).err());
+        self.error.is_none()
+    }
+    
     fn increment(&mut self) {
-        self.value = (self.value + self.step).min(self.max);
-        self.text_buffer = format!("{:.prec$}", self.value, prec = self.precision);
+        self.step_to((self.value + self.step).min(self.max));
     }
     
     fn decrement(&mut self) {
-        self.value = (self.value - self.step).max(self.min);
+        self.step_to((self.value - self.step).max(self.min));
+    }
+    
+    /// Take the value from pasted `text`, clamped to the range; returns
+    /// whether it was a number
+    pub fn paste(&mut self, text: &str) -> bool {
+        match text.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => {
+                self.step_to(value.clamp(self.min, self.max));
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Height of the box itself, without the line kept for errors
+    fn field_size(&self) -> Vec2 {
+        let error_line = if self.validators.is_empty() { 0.0 } else { ERROR_LINE };
+        Vec2::new(self.size.x, (self.size.y - error_line).max(0.0))
+    }
+    
+    /// Start typing a new value, with the current one selected
+    pub fn begin_edit(&mut self) {
+        self.editing = true;
+        self.replace_on_type = true;
+    }
+    
+    /// Add typed `text` to the buffer, keeping only characters a number
+    /// can contain
+    pub fn type_text(&mut self, text: &str) {
+        if !self.editing {
+            self.begin_edit();
+        }
+        let accepted: String = text.chars().filter(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')).collect();
+        if accepted.is_empty() {
+            return;
+        }
+        if std::mem::take(&mut self.replace_on_type) {
+            self.text_buffer.clear();
+        }
+        self.text_buffer.push_str(&accepted);
+    }
+    
+    /// Remove the last typed character (everything, while it is selected)
+    pub fn backspace(&mut self) {
+        if std::mem::take(&mut self.replace_on_type) {
+            self.text_buffer.clear();
+        } else {
+            self.text_buffer.pop();
+        }
+    }
+    
+    /// Take the typed value, clamped and formatted; text that isn't a
+    /// number puts the old value back
+    pub fn commit_edit(&mut self) {
+        if !self.editing {
+            return;
+        }
+        self.editing = false;
+        self.replace_on_type = false;
+        let typed = std::mem::take(&mut self.text_buffer);
+        if !self.paste(&typed) {
+            self.step_to(self.value);
+        }
+    }
+    
+    /// Drop the typed text and show the value again
+    pub fn cancel_edit(&mut self) {
+        self.editing = false;
+        self.replace_on_type = false;
+        self.step_to(self.value);
+    }
+    
+    /// Move `steps` steps up (or down, if negative), within the range
+    fn step_by(&mut self, steps: f64) {
+        self.step_to((self.value + steps * self.step).clamp(self.min, self.max));
+    }
+    
+    fn step_to(&mut self, value: f64) {
+        let changed = value != self.value;
+        self.value = value;
         self.text_buffer = format!("{:.prec$}", self.value, prec = self.precision);
+        if !self.validators.is_empty() {
+            self.validate();
+        }
+        if changed {
+            if let Some(callback) = &mut self.on_change {
+                callback(value);
+            }
+        }
+    }
+    
+    fn default_size(&self) -> Size {
+        let error_line = if self.validators.is_empty() { 0.0 } else { ERROR_LINE };
+        Size::new(150.0, 32.0 + error_line)
+    }
+}
+
+impl Focusable for NumberInput {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn can_focus(&self) -> bool {
+        !self.disabled
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+        self.commit_edit();
     }
 }
 
 impl Widget for NumberInput {
     fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(150.0, 32.0);
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        if self.disabled {
+            return false;
+        }
+        match event {
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::ModifiersChanged(modifiers), .. } => {
+                self.modifiers = modifiers.state();
+                return false;
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::Ime(winit::event::Ime::Commit(text)), .. } if self.focused => {
+                self.type_text(text);
+                return true;
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::KeyboardInput { event: key_event, .. }, .. }
+                if self.focused && key_event.state.is_pressed() =>
+            {
+                use winit::keyboard::{Key, NamedKey};
+                match ClipboardAction::from_key(&key_event.logical_key, self.modifiers) {
+                    Some(ClipboardAction::Copy | ClipboardAction::Cut) => {
+                        let _ = crate::clipboard::copy_to_clipboard(&self.text_buffer);
+                        return true;
+                    }
+                    Some(ClipboardAction::Paste) => {
+                        if let Ok(pasted) = crate::clipboard::paste_from_clipboard() {
+                            self.editing = false;
+                            self.paste(&pasted);
+                        }
+                        return true;
+                    }
+                    None => {}
+                }
+                match &key_event.logical_key {
+                    Key::Named(NamedKey::Enter) if self.editing => self.commit_edit(),
+                    Key::Named(NamedKey::Escape) if self.editing => self.cancel_edit(),
+                    Key::Named(NamedKey::Backspace) if self.editing => self.backspace(),
+                    Key::Named(NamedKey::ArrowUp) | Key::Named(NamedKey::ArrowDown) => {
+                        self.commit_edit();
+                        self.step_by(if key_event.logical_key == Key::Named(NamedKey::ArrowUp) { 1.0 } else { -1.0 });
+                    }
+                    _ => match &key_event.text {
+                        Some(text) if !text.chars().any(|c| c.is_control()) => self.type_text(text),
+                        _ => return false,
+                    },
+                }
+                return true;
+            }
+            _ => {}
+        }
         let btn_width = 28.0;
-        let in_dec = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + btn_width &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
-        let in_inc = mouse_pos.x >= self.position.x + self.size.x - btn_width && mouse_pos.x <= self.position.x + self.size.x &&
-                     mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.size.y;
+        let field = self.field_size();
+        let in_field = mouse_pos.x >= self.position.x && mouse_pos.x <= self.position.x + field.x &&
+                       mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + field.y;
+        let in_dec = in_field && mouse_pos.x <= self.position.x + btn_width;
+        let in_inc = in_field && mouse_pos.x >= self.position.x + field.x - btn_width;
         
         self.hovered_btn = if in_inc { Some(true) } else if in_dec { Some(false) } else { None };
         
-        if let winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } = event {
-            if *state == ElementState::Pressed {
-                if in_inc {
-                    self.pressed_btn = Some(true);
-                    self.increment();
-                    self.repeat_timer = 0.0;
+        match event {
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, .. } => {
+                if *state == ElementState::Pressed {
+                    if in_inc || in_dec {
+                        self.commit_edit();
+                        self.pressed_btn = Some(in_inc);
+                        if in_inc { self.increment(); } else { self.decrement(); }
+                        self.repeat_timer = 0.0;
+                        return true;
+                    } else if in_field {
+                        // Typing or scrubbing is decided when the press ends
+                        self.focused = true;
+                        self.drag_start = Some((mouse_pos.x, self.value));
+                        self.scrubbing = false;
+                        return true;
+                    } else {
+                        self.commit_edit();
+                    }
+                } else {
+                    self.pressed_btn = None;
+                    if self.drag_start.take().is_some() {
+                        if !self.scrubbing {
+                            self.begin_edit();
+                        }
+                        self.scrubbing = false;
+                        return true;
+                    }
+                }
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::CursorMoved { .. }, .. } => {
+                if let Some((start_x, start_value)) = self.drag_start {
+                    let dx = mouse_pos.x - start_x;
+                    if self.scrubbing || dx.abs() > SCRUB_PIXELS {
+                        self.scrubbing = true;
+                        self.editing = false;
+                        let steps = (dx / SCRUB_PIXELS).trunc() as f64;
+                        self.step_to((start_value + steps * self.step).clamp(self.min, self.max));
+                    }
                     return true;
-                } else if in_dec {
-                    self.pressed_btn = Some(false);
-                    self.decrement();
-                    self.repeat_timer = 0.0;
+                }
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseWheel { delta, .. }, .. } if in_field => {
+                let lines = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(_, y) => *y,
+                    winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
+                };
+                if lines != 0.0 {
+                    self.commit_edit();
+                    self.step_by(lines.signum() as f64);
                     return true;
                 }
-            } else {
-                self.pressed_btn = None;
             }
+            _ => {}
         }
         false
     }
@@ -499,35 +1007,471 @@ impl Widget for NumberInput {
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
-        let btn_width = 28.0;
-        
-        // Background
-        renderer.draw_rounded_rect(self.position, self.size, Vec4::new(0.08, 0.08, 0.1, 0.9), 6.0);
+        render_faded(renderer, self.disabled, None, |renderer| {
+            let btn_width = 28.0;
+            let field = self.field_size();
+            
+            // Background
+            renderer.draw_rounded_rect(self.position, field, Vec4::new(0.08, 0.08, 0.1, 0.9), 6.0);
+            if self.error.is_some() || self.focused {
+                let accent = if self.error.is_some() { theme.error } else { theme.primary };
+                renderer.draw_rect_stroke(
+                    self.position - Vec2::splat(1.0),
+                    field + Vec2::splat(2.0),
+                    Vec4::new(accent.x, accent.y, accent.z, 0.6),
+                    1.5,
+                    7.0
+                );
+            }
+            if let Some(error) = &self.error {
+                renderer.draw_text(error, self.position + Vec2::new(4.0, field.y + 3.0), 12.0, theme.error);
+            }
+            
+            // Decrement button
+            let dec_color = match (self.pressed_btn, self.hovered_btn) {
+                (Some(false), _) => theme.pressed,
+                (_, Some(false)) => theme.hover,
+                _ => Vec4::new(0.15, 0.15, 0.18, 1.0),
+            };
+            renderer.draw_rounded_rect(self.position, Vec2::new(btn_width, field.y), dec_color, 6.0);
+            renderer.draw_text("−", self.position + Vec2::new(9.0, 6.0), 18.0, theme.text);
+            
+            // Increment button
+            let inc_color = match (self.pressed_btn, self.hovered_btn) {
+                (Some(true), _) => theme.pressed,
+                (_, Some(true)) => theme.hover,
+                _ => Vec4::new(0.15, 0.15, 0.18, 1.0),
+            };
+            renderer.draw_rounded_rect(
+                Vec2::new(self.position.x + field.x - btn_width, self.position.y),
+                Vec2::new(btn_width, field.y),
+                inc_color,
+                6.0
+            );
+            renderer.draw_text("+", Vec2::new(self.position.x + field.x - btn_width + 8.0, self.position.y + 6.0), 18.0, theme.text);
+            
+            // Value, selected or with a caret while typed
+            let text_pos = Vec2::new(self.position.x + btn_width + 8.0, self.position.y + 7.0);
+            let text_width = renderer.measure_text(&self.text_buffer, 16.0).width;
+            if self.editing && self.replace_on_type {
+                renderer.draw_rect(text_pos - Vec2::new(1.0, 1.0), Vec2::new(text_width + 2.0, 20.0), Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.35));
+            }
+            renderer.draw_text(&self.text_buffer, text_pos, 16.0, theme.text);
+            if self.editing && !self.replace_on_type {
+                renderer.draw_rect(text_pos + Vec2::new(text_width + 1.0, -1.0), Vec2::new(2.0, 20.0), theme.primary);
+            }
+        });
+    }
+}
+
+// =============================================================================
+// RATING
+// =============================================================================
+
+/// Row of stars showing a score out of `count`
+///
+/// Clicking a star rates up to it (or half of it, with half steps);
+/// clicking the current rating again clears it. Hovering previews the
+/// rating a click would give, and while focused Left/Right step it.
+/// In read-only mode the value is only shown, fractions included.
+pub struct Rating {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
+    pub value: f32,
+    /// Number of stars
+    pub count: usize,
+    /// Allow ratings like 3.5 as well as whole stars
+    pub half_steps: bool,
+    /// Only show the value
+    pub read_only: bool,
+    pub star_size: f32,
+    pub spacing: f32,
+    /// Fill of the rated stars
+    pub color: Vec4,
+    /// Rating a click at the hovered point would give
+    pub hover_value: Option<f32>,
+    pub focused: bool,
+    /// Identifies the rating to `EventDispatcher`'s focus tracking
+    pub focus_id: FocusId,
+    /// Called with the new rating
+    pub on_change: Option<Box<dyn FnMut(f32)>>,
+    /// Ignores input and is drawn faded (see `Theme::disabled_opacity`)
+    pub disabled: bool,
+}
+
+impl Rating {
+    pub fn new(count: usize) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            layout_intrinsic: None,
+            value: 0.0,
+            count: count.max(1),
+            half_steps: false,
+            read_only: false,
+            star_size: 20.0,
+            spacing: 4.0,
+            color: Vec4::new(1.0, 0.78, 0.2, 1.0),
+            hover_value: None,
+            focused: false,
+            focus_id: FocusId::new(),
+            on_change: None,
+            disabled: false,
+        }
+    }
+    
+    /// Show `value` as given, e.g. an average of 3.7; ratings picked by
+    /// the user land on whole (or half) stars
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value.clamp(0.0, self.count as f32);
+        self
+    }
+    
+    pub fn with_half_steps(mut self, half_steps: bool) -> Self {
+        self.half_steps = half_steps;
+        self
+    }
+    
+    /// Show the value without taking input
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        if read_only {
+            self.hover_value = None;
+            self.focused = false;
+        }
+        self
+    }
+    
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+    
+    /// Block input and draw faded
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.hover_value = None;
+        }
+    }
+    
+    pub fn with_star_size(mut self, size: f32) -> Self {
+        self.star_size = size;
+        self
+    }
+    
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+    
+    pub fn on_change(mut self, callback: impl FnMut(f32) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+    
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+    
+    /// `value` within 0..=count, on a whole or half star
+    fn snap(&self, value: f32) -> f32 {
+        snap_to_step(value, 0.0, self.count as f32, if self.half_steps { 0.5 } else { 1.0 })
+    }
+    
+    /// Set the rating from user input, reporting a change
+    fn set_from_input(&mut self, value: f32) {
+        let value = self.snap(value);
+        if value != self.value {
+            self.value = value;
+            if let Some(callback) = &mut self.on_change {
+                callback(value);
+            }
+        }
+    }
+    
+    /// Step the rating by `steps` half or whole stars
+    pub fn nudge(&mut self, steps: f32) {
+        let step = if self.half_steps { 0.5 } else { 1.0 };
+        self.set_from_input(self.value + steps * step);
+    }
+    
+    /// Rating a click at `x` gives: up to the star under it, or half of
+    /// it when half steps are on and `x` is on its left half
+    fn value_at(&self, x: f32) -> f32 {
+        let pitch = self.star_size + self.spacing;
+        let offset = (x - self.position.x).max(0.0);
+        let index = ((offset / pitch) as usize).min(self.count - 1);
+        let within = (offset - index as f32 * pitch) / self.star_size;
+        let half = self.half_steps && within < 0.5;
+        index as f32 + if half { 0.5 } else { 1.0 }
+    }
+    
+    fn default_size(&self) -> Size {
+        let count = self.count as f32;
+        Size::new(count * self.star_size + (count - 1.0) * self.spacing, self.star_size)
+    }
+}
+
+/// Five-pointed star filling a `size` square, point up
+fn star_path(size: f32) -> Vec<crate::PathCommand> {
+    use crate::PathCommand;
+    let center = size / 2.0;
+    let mut path: Vec<PathCommand> = (0..10).map(|i| {
+        let radius = if i % 2 == 0 { center } else { center * 0.42 };
+        let angle = -std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::PI / 5.0;
+        let (x, y) = (center + radius * angle.cos(), center * 1.08 + radius * angle.sin());
+        if i == 0 { PathCommand::MoveTo(x, y) } else { PathCommand::LineTo(x, y) }
+    }).collect();
+    path.push(PathCommand::Close);
+    path
+}
+
+thread_local! {
+    /// Tessellated stars by size (as `f32` bits), so drawing them each
+    /// frame doesn't redo the path
+    static STAR_MESHES: RefCell<HashMap<u32, Rc<PathMesh>>> = RefCell::new(HashMap::new());
+}
+
+/// The star mesh for `size`, tessellated on first use
+fn star_mesh(size: f32) -> Rc<PathMesh> {
+    STAR_MESHES.with(|meshes| {
+        meshes.borrow_mut()
+            .entry(size.to_bits())
+            .or_insert_with(|| Rc::new(crate::path::fill_path(&star_path(size))))
+            .clone()
+    })
+}
+
+/// Draw `count` stars `size` pixels wide from `origin`, filled with `color`
+/// up to `value` (a fraction fills part of a star); shared with DataTable's
+/// rating cells
+pub(super) fn draw_stars(renderer: &mut GlassRenderer, origin: Vec2, value: f32, count: usize, size: f32, spacing: f32, color: Vec4) {
+    let mesh = star_mesh(size);
+    for i in 0..count {
+        let star = origin + Vec2::new(i as f32 * (size + spacing), 0.0);
+        let fill = (value - i as f32).clamp(0.0, 1.0);
+        if fill < 1.0 {
+            renderer.draw_mesh(star, &mesh, Vec4::new(1.0, 1.0, 1.0, 0.18));
+        }
+        if fill >= 1.0 {
+            renderer.draw_mesh(star, &mesh, color);
+        } else if fill > 0.0 {
+            renderer.push_clip(Rect::new(star, Vec2::new(size * fill, size)));
+            renderer.draw_mesh(star, &mesh, color);
+            renderer.pop_clip();
+        }
+    }
+}
+
+impl Focusable for Rating {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn can_focus(&self) -> bool {
+        !self.read_only && !self.disabled
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+    }
+}
+
+impl Widget for Rating {
+    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        let size = self.default_size();
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(size);
+        self.size
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.default_size());
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(self.default_size());
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.default_size())
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        if self.read_only || self.disabled {
+            return false;
+        }
+        let inside = Rect::new(self.position, self.size).contains(mouse_pos);
+        self.hover_value = inside.then(|| self.value_at(mouse_pos.x));
         
-        // Decrement button
-        let dec_color = match (self.pressed_btn, self.hovered_btn) {
-            (Some(false), _) => theme.pressed,
-            (_, Some(false)) => theme.hover,
-            _ => Vec4::new(0.15, 0.15, 0.18, 1.0),
-        };
-        renderer.draw_rounded_rect(self.position, Vec2::new(btn_width, self.size.y), dec_color, 6.0);
-        renderer.draw_text("−", self.position + Vec2::new(9.0, 6.0), 18.0, theme.text);
+        match event {
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } if inside => {
+                let value = self.value_at(mouse_pos.x);
+                self.set_from_input(if value == self.value { 0.0 } else { value });
+                return true;
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. } if self.focused && key_event.state.is_pressed() => {
+                use winit::keyboard::{Key, NamedKey};
+                match key_event.logical_key {
+                    Key::Named(NamedKey::ArrowRight | NamedKey::ArrowUp) => self.nudge(1.0),
+                    Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowDown) => self.nudge(-1.0),
+                    Key::Named(NamedKey::Home) => self.set_from_input(0.0),
+                    Key::Named(NamedKey::End) => self.set_from_input(self.count as f32),
+                    _ => return false,
+                }
+                return true;
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
         
-        // Increment button
-        let inc_color = match (self.pressed_btn, self.hovered_btn) {
-            (Some(true), _) => theme.pressed,
-            (_, Some(true)) => theme.hover,
-            _ => Vec4::new(0.15, 0.15, 0.18, 1.0),
+        // A hovered preview is drawn a little lighter than the rating
+        let (value, color) = match self.hover_value {
+            Some(preview) => (preview, self.color * Vec4::new(1.0, 1.0, 1.0, 0.7)),
+            None => (self.value, self.color),
         };
-        renderer.draw_rounded_rect(
-            Vec2::new(self.position.x + self.size.x - btn_width, self.position.y),
-            Vec2::new(btn_width, self.size.y),
-            inc_color,
-            6.0
-        );
-        renderer.draw_text("+", Vec2::new(self.position.x + self.size.x - btn_width + 8.0, self.position.y + 6.0), 18.0, theme.text);
+        if self.focused {
+            renderer.draw_rect_stroke(
+                self.position - Vec2::splat(3.0),
+                self.size + Vec2::splat(6.0),
+                Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.6),
+                1.5,
+                4.0
+            );
+        }
+        render_faded(renderer, self.disabled, None, |renderer| {
+            draw_stars(renderer, self.position, value, self.count, self.star_size, self.spacing, color);
+        });
+    }
+    
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        props.push(("value", format!("{}/{}", self.value, self.count)));
+        props.push(("read_only", self.read_only.to_string()));
+    }
+    
+    fn saved_state(&self) -> Option<WidgetState> {
+        (!self.read_only).then_some(WidgetState::Number(self.value))
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::{RecordedButton, RecordedEvent};
+
+    #[test]
+    fn test_number_input_typing_and_scrubbing() {
+        let mut input = NumberInput::new(5.0).with_range(0.0, 10.0).with_precision(1);
+        input.layout(Vec2::ZERO, Vec2::splat(500.0));
+        assert_eq!(input.text_buffer, "5.0");
+
+        // A click on the value selects it; typing replaces it and the commit clamps
+        let mouse = |pressed| RecordedEvent::MouseInput { button: RecordedButton::Left, pressed }.to_event().unwrap();
+        assert!(input.handle_event(&mouse(true), Vec2::new(75.0, 16.0)));
+        assert!(input.handle_event(&mouse(false), Vec2::new(75.0, 16.0)));
+        assert!(input.editing);
+        input.type_text("12a.5");
+        assert_eq!(input.text_buffer, "12.5");
+        input.commit_edit();
+        assert_eq!((input.value, input.text_buffer.as_str(), input.editing), (10.0, "10.0", false));
+
+        // Text that isn't a number is dropped
+        input.begin_edit();
+        input.backspace();
+        input.type_text("-");
+        input.commit_edit();
+        assert_eq!(input.value, 10.0);
+
+        // Dragging left steps down once per few pixels, without starting an edit
+        let moved = RecordedEvent::CursorMoved { x: 55.0, y: 16.0 }.to_event().unwrap();
+        input.handle_event(&mouse(true), Vec2::new(75.0, 16.0));
+        assert!(input.handle_event(&moved, Vec2::new(55.0, 16.0)));
+        input.handle_event(&mouse(false), Vec2::new(55.0, 16.0));
+        assert_eq!((input.value, input.editing), (5.0, false));
+
+        let wheel = RecordedEvent::MouseWheel { dx: 0.0, dy: -1.0, lines: true }.to_event().unwrap();
+        assert!(input.handle_event(&wheel, Vec2::new(75.0, 16.0)));
+        assert_eq!(input.value, 4.0);
+    }
+
+    #[test]
+    fn test_rating_half_steps_hover_and_read_only() {
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = log.clone();
+        let mut rating = Rating::new(5).with_half_steps(true).with_value(3.3)
+            .on_change(move |v| sink.borrow_mut().push(v));
+        rating.nudge(1.0);
+        assert_eq!(rating.value, 4.0);
+        assert_eq!(rating.layout(Vec2::ZERO, Vec2::splat(500.0)), Vec2::new(116.0, 20.0));
+
+        // Stars are 24px apart: the left half of the second star is 1.5
+        let moved = RecordedEvent::CursorMoved { x: 28.0, y: 10.0 }.to_event().unwrap();
+        rating.handle_event(&moved, Vec2::new(28.0, 10.0));
+        assert_eq!(rating.hover_value, Some(1.5));
+        assert_eq!(rating.value, 4.0);
+        let click = RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }.to_event().unwrap();
+        assert!(rating.handle_event(&click, Vec2::new(28.0, 10.0)));
+        assert!(rating.handle_event(&click, Vec2::new(110.0, 10.0)));
+        assert_eq!(rating.value, 5.0);
+
+        // Clicking the current rating clears it
+        assert!(rating.handle_event(&click, Vec2::new(110.0, 10.0)));
+        assert_eq!(*log.borrow(), vec![4.0, 1.5, 5.0, 0.0]);
+
+        let mut score = Rating::new(5).with_value(3.7).read_only(true);
+        assert_eq!(score.value, 3.7);
+        assert!(!score.handle_event(&click, Vec2::new(10.0, 10.0)));
+        assert!(!score.can_focus());
         
-        // Value
-        renderer.draw_text(&self.text_buffer, Vec2::new(self.position.x + btn_width + 8.0, self.position.y + 7.0), 16.0, theme.text);
+        rating.set_disabled(true);
+        assert!(!rating.handle_event(&click, Vec2::new(28.0, 10.0)));
+        assert_eq!((rating.value, rating.hover_value), (0.0, None));
+        assert!(!rating.can_focus());
+    }
+    
+    #[test]
+    fn test_star_mesh_is_cached_per_size() {
+        let mesh = star_mesh(20.0);
+        assert!(!mesh.is_empty());
+        assert!(Rc::ptr_eq(&mesh, &star_mesh(20.0)));
+        assert!(!Rc::ptr_eq(&mesh, &star_mesh(14.0)));
     }
 }
@@ -4,11 +4,16 @@
 //! - `TextSpan` - Styled text segment
 //! - `RichText` - Compound styled text
 //! - `RichTextEditor` - Editable rich text widget
+//! - `Markdown` - CommonMark document viewer
 
 use glam::{Vec2, Vec4};
+use winit::keyboard::ModifiersState;
+use crate::clipboard::ClipboardAction;
 use crate::widgets::Widget;
 use crate::renderer::GlassRenderer;
-use crate::layout::{Size, Offset};
+use crate::layout::{BoxConstraints, Size, Offset};
+use crate::text::layout_text_width;
+use crate::focus::{FocusId, Focusable};
 
 // =============================================================================
 // TEXT STYLE (Rich)
@@ -85,6 +90,8 @@ pub struct TextSpan {
     pub text: String,
     pub style: SpanStyle,
     pub children: Vec<TextSpan>,
+    /// Target URL when the span is a link, see `with_link`
+    link: Option<String>,
 }
 
 impl TextSpan {
@@ -93,6 +100,7 @@ impl TextSpan {
             text: text.to_string(),
             style: SpanStyle::default(),
             children: Vec::new(),
+            link: None,
         }
     }
     
@@ -101,6 +109,7 @@ impl TextSpan {
             text: text.to_string(),
             style,
             children: Vec::new(),
+            link: None,
         }
     }
     
@@ -114,6 +123,16 @@ impl TextSpan {
         self
     }
     
+    pub fn with_link(mut self, url: &str) -> Self {
+        self.link = Some(url.to_string());
+        self
+    }
+    
+    /// Target URL when the span is a link
+    pub fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+    
     pub fn bold(text: &str) -> Self {
         Self::styled(text, SpanStyle::new().bold())
     }
@@ -152,6 +171,8 @@ pub struct RichText {
     size: Size,
     line_height: f32,
     max_width: f32,
+    /// Width the spans wrap at, from the last layout pass
+    wrap_width: f32,
 }
 
 impl RichText {
@@ -162,6 +183,7 @@ impl RichText {
             size: Size::ZERO,
             line_height: 1.4,
             max_width: f32::INFINITY,
+            wrap_width: f32::INFINITY,
         }
     }
     
@@ -259,32 +281,28 @@ impl RichText {
 impl Default for RichText { fn default() -> Self { Self::new() } }
 
 impl Widget for RichText {
-    fn layout(&mut self, _origin: Vec2, available: Vec2) -> Vec2 {
-        // Calculate size based on content
-        let mut total_width = 0.0f32;
-        let base_height = 20.0; // Approximate
-        
-        for span in &self.spans {
-            total_width += span.text.len() as f32 * span.style.font_size * 0.6;
-        }
-        
-        let width = total_width.min(available.x).min(self.max_width);
-        let lines = (total_width / width).ceil().max(1.0);
-        let height = lines * base_height * self.line_height;
-        
-        self.size = Size::new(width, height);
-        Vec2::new(width, height)
+    fn layout(&mut self, origin: Vec2, available: Vec2) -> Vec2 {
+        self.position = origin;
+        self.wrap_width = available.x.min(self.max_width);
+        let (_, size) = self.flow(self.wrap_width, layout_text_width);
+        self.size = size;
+        Vec2::new(size.width, size.height)
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.wrap_width = constraints.max_width.min(self.max_width);
+        let (_, size) = self.flow(self.wrap_width, layout_text_width);
+        self.size = constraints.constrain(size);
+        self.size
     }
     
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool { false }
     fn update(&mut self, _dt: f32) {}
     
     fn render(&self, renderer: &mut GlassRenderer) {
-        let mut x = self.position.x;
-        let mut y = self.position.y;
-        
-        for span in &self.spans {
-            self.render_span(renderer, span, &mut x, &mut y);
+        let (runs, _) = self.flow(self.wrap_width, |text, font_size| renderer.measure_text(text, font_size).width);
+        for run in &runs {
+            Self::render_run(renderer, run, self.position);
         }
     }
     
@@ -295,43 +313,116 @@ impl Widget for RichText {
     fn intrinsic_height(&self, _width: f32) -> Option<f32> { None }
 }
 
+/// Part of one span placed on one line by `RichText::flow`
+struct SpanRun<'a> {
+    text: &'a str,
+    style: &'a SpanStyle,
+    /// Top-left relative to the widget
+    offset: Vec2,
+    width: f32,
+}
+
 impl RichText {
-    fn render_span(&self, renderer: &mut GlassRenderer, span: &TextSpan, x: &mut f32, y: &mut f32) {
-        let char_width = span.style.font_size * 0.6;
-        let text_width = span.text.len() as f32 * char_width;
+    /// Place the spans (and their children, depth first) left to right,
+    /// wrapping between words at `max_width` and at newlines. Words wider
+    /// than a line overflow it. Returns the runs and their bounding size.
+    fn flow<'a>(&'a self, max_width: f32, measure: impl Fn(&str, f32) -> f32) -> (Vec<SpanRun<'a>>, Size) {
+        fn collect<'a>(spans: &'a [TextSpan], out: &mut Vec<&'a TextSpan>) {
+            for span in spans {
+                out.push(span);
+                collect(&span.children, out);
+            }
+        }
+        let mut spans = Vec::new();
+        collect(&self.spans, &mut spans);
+        
+        let mut runs: Vec<SpanRun<'a>> = Vec::new();
+        let mut x = 0.0f32;
+        let mut line_top = 0.0f32;
+        let mut line_first_run = 0;
+        let mut width = 0.0f32;
+        // Set at a wrap until the next word, so the spaces there are dropped
+        let mut wrapped = false;
+        let line_advance = |runs: &[SpanRun], first: usize| {
+            runs[first..].iter().map(|r| r.style.font_size).fold(0.0, f32::max) * self.line_height
+        };
+        
+        for span in spans {
+            let style = &span.style;
+            for (i, segment) in span.text.split('\n').enumerate() {
+                if i > 0 {
+                    let advance = line_advance(&runs, line_first_run).max(style.font_size * self.line_height);
+                    line_top += advance;
+                    line_first_run = runs.len();
+                    x = 0.0;
+                    wrapped = false;
+                }
+                // Each token is a word with the whitespace after it
+                let mut run_start: Option<(usize, f32)> = None;
+                let base = segment.as_ptr() as usize - span.text.as_ptr() as usize;
+                for token in segment.split_inclusive(char::is_whitespace) {
+                    let start = token.as_ptr() as usize - segment.as_ptr() as usize;
+                    let word = token.trim_end();
+                    let word_width = measure(word, style.font_size);
+                    if x > 0.0 && !word.is_empty() && x + word_width > max_width {
+                        if let Some((run_begin, run_x)) = run_start.take() {
+                            let text = span.text[base + run_begin..base + start].trim_end();
+                            runs.push(SpanRun { text, style, offset: Vec2::new(run_x, line_top), width: measure(text, style.font_size) });
+                        }
+                        line_top += line_advance(&runs, line_first_run).max(style.font_size * self.line_height);
+                        line_first_run = runs.len();
+                        x = 0.0;
+                        wrapped = true;
+                    }
+                    if wrapped && word.is_empty() {
+                        continue;
+                    }
+                    wrapped = false;
+                    if run_start.is_none() {
+                        run_start = Some((start, x));
+                    }
+                    width = width.max(x + word_width);
+                    x += measure(token, style.font_size);
+                }
+                if let Some((run_begin, run_x)) = run_start {
+                    let text = &span.text[base + run_begin..base + segment.len()];
+                    runs.push(SpanRun { text, style, offset: Vec2::new(run_x, line_top), width: measure(text, style.font_size) });
+                }
+            }
+        }
+        let last_line = line_advance(&runs, line_first_run);
+        (runs, Size::new(width, line_top + last_line))
+    }
+    
+    fn render_run(renderer: &mut GlassRenderer, run: &SpanRun, origin: Vec2) {
+        let style = run.style;
+        let pos = origin + run.offset;
         
         // Background highlight
-        if let Some(bg) = span.style.background {
-            renderer.draw_rect(Vec2::new(*x, *y), Vec2::new(text_width, span.style.font_size + 4.0), bg);
+        if let Some(bg) = style.background {
+            renderer.draw_rect(pos, Vec2::new(run.width, style.font_size + 4.0), bg);
         }
         
         // Text
-        renderer.draw_text(&span.text, Vec2::new(*x, *y), span.style.font_size, span.style.color);
+        renderer.draw_text(run.text, pos, style.font_size, style.color);
         
         // Underline
-        if span.style.decoration == TextDecoration::Underline {
+        if style.decoration == TextDecoration::Underline {
             renderer.draw_rect(
-                Vec2::new(*x, *y + span.style.font_size + 2.0),
-                Vec2::new(text_width, 1.0),
-                span.style.color
+                pos + Vec2::new(0.0, style.font_size + 2.0),
+                Vec2::new(run.width, 1.0),
+                style.color
             );
         }
         
         // Strikethrough
-        if span.style.decoration == TextDecoration::Strikethrough {
+        if style.decoration == TextDecoration::Strikethrough {
             renderer.draw_rect(
-                Vec2::new(*x, *y + span.style.font_size / 2.0),
-                Vec2::new(text_width, 1.0),
-                span.style.color
+                pos + Vec2::new(0.0, style.font_size / 2.0),
+                Vec2::new(run.width, 1.0),
+                style.color
             );
         }
-        
-        *x += text_width;
-        
-        // Render children
-        for child in &span.children {
-            self.render_span(renderer, child, x, y);
-        }
     }
 }
 
@@ -347,9 +438,14 @@ pub struct RichTextEditor {
     cursor_pos: usize,
     selection_start: Option<usize>,
     focused: bool,
+    focus_id: FocusId,
     cursor_blink: f32,
     /// Current style to apply to new text
     current_style: SpanStyle,
+    /// Modifier keys held, from the last `ModifiersChanged`
+    modifiers: ModifiersState,
+    /// Left button held after a press in the text, extending the selection
+    dragging: bool,
 }
 
 impl RichTextEditor {
@@ -361,8 +457,11 @@ impl RichTextEditor {
             cursor_pos: 0,
             selection_start: None,
             focused: false,
+            focus_id: FocusId::new(),
             cursor_blink: 0.0,
             current_style: SpanStyle::default(),
+            modifiers: ModifiersState::empty(),
+            dragging: false,
         }
     }
     
@@ -381,6 +480,79 @@ impl RichTextEditor {
         self.content.iter().map(|s| s.plain_text()).collect()
     }
     
+    /// Select the bytes in `range` of the edited text, leaving the cursor at its end
+    pub fn set_selection(&mut self, range: std::ops::Range<usize>) {
+        self.selection_start = Some(range.start);
+        self.cursor_pos = range.end;
+    }
+    
+    /// Selected byte range, if any
+    pub fn selection(&self) -> Option<std::ops::Range<usize>> {
+        self.selection_start.map(|start| start.min(self.cursor_pos)..start.max(self.cursor_pos))
+    }
+    
+    /// Text in the selection, across spans (empty without one)
+    pub fn selected_text(&self) -> String {
+        self.selection()
+            .and_then(|range| self.plain_text().get(range).map(str::to_string))
+            .unwrap_or_default()
+    }
+    
+    /// Remove bytes `range` of the plain text from whichever spans hold them
+    fn remove_range(&mut self, range: std::ops::Range<usize>) {
+        let mut start = 0;
+        for text in span_texts_mut(&mut self.content) {
+            let end = start + text.len();
+            let (from, to) = (range.start.clamp(start, end), range.end.clamp(start, end));
+            if from < to {
+                text.replace_range(from - start..to - start, "");
+            }
+            start = end;
+        }
+    }
+    
+    /// Remove the selected text, if any; returns whether there was some
+    fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection().filter(|range| !range.is_empty()) else {
+            return false;
+        };
+        if self.plain_text().get(range.clone()).is_some() {
+            self.remove_range(range.clone());
+            self.cursor_pos = range.start;
+        }
+        self.selection_start = None;
+        true
+    }
+    
+    /// Copy, cut or paste at the cursor; pasted text replaces the selection
+    fn apply_clipboard(&mut self, action: ClipboardAction) {
+        match action {
+            ClipboardAction::Copy | ClipboardAction::Cut => {
+                let selected = self.selected_text();
+                if !selected.is_empty() {
+                    let _ = crate::clipboard::copy_to_clipboard(&selected);
+                    if action == ClipboardAction::Cut {
+                        self.delete_selection();
+                    }
+                }
+            }
+            ClipboardAction::Paste => {
+                if let Ok(pasted) = crate::clipboard::paste_from_clipboard() {
+                    self.insert_text(&pasted);
+                }
+            }
+        }
+    }
+    
+    /// Insert `text` at the cursor in place of any selection, dropping
+    /// control characters other than newlines
+    fn insert_text(&mut self, text: &str) {
+        self.delete_selection();
+        for c in text.chars().filter(|c| !c.is_control() || *c == '\n') {
+            self.insert_char(c);
+        }
+    }
+    
     /// Set bold for current/future text
     pub fn toggle_bold(&mut self) {
         self.current_style.font_weight = match self.current_style.font_weight {
@@ -403,68 +575,189 @@ impl RichTextEditor {
     }
     
     fn insert_char(&mut self, c: char) {
-        // For now, simple append to first span
-        if let Some(span) = self.content.first_mut() {
-            span.text.push(c);
-            self.cursor_pos += 1;
+        self.delete_selection();
+        // The cursor is a byte offset into the plain text; at a boundary
+        // between spans the earlier one grows
+        self.cursor_pos = self.cursor_pos.min(self.plain_text().len());
+        let mut start = 0;
+        for text in span_texts_mut(&mut self.content) {
+            if self.cursor_pos <= start + text.len() {
+                text.insert(self.cursor_pos - start, c);
+                self.cursor_pos += c.len_utf8();
+                break;
+            }
+            start += text.len();
         }
+        self.selection_start = None;
     }
     
     fn delete_char(&mut self) {
-        if self.cursor_pos > 0 {
-            if let Some(span) = self.content.first_mut() {
-                if self.cursor_pos <= span.text.len() {
-                    span.text.remove(self.cursor_pos - 1);
-                    self.cursor_pos -= 1;
+        if self.delete_selection() {
+            return;
+        }
+        let text = self.plain_text();
+        if let Some(c) = text.get(..self.cursor_pos).and_then(|before| before.chars().next_back()) {
+            let at = self.cursor_pos - c.len_utf8();
+            self.remove_range(at..self.cursor_pos);
+            self.cursor_pos = at;
+            self.selection_start = None;
+        }
+    }
+    
+    /// Step the cursor one character left or right, extending the
+    /// selection when `extend` is set (Shift held)
+    fn move_cursor(&mut self, right: bool, extend: bool) {
+        let text = self.plain_text();
+        let at = self.cursor_pos.min(text.len());
+        let index = if right {
+            text[at..].chars().next().map_or(at, |c| at + c.len_utf8())
+        } else {
+            text[..at].chars().next_back().map_or(at, |c| at - c.len_utf8())
+        };
+        self.move_caret(index, extend);
+    }
+    
+    /// Move the cursor to `index`, extending the selection when `extend` is
+    /// set and dropping it otherwise
+    fn move_caret(&mut self, index: usize, extend: bool) {
+        if extend {
+            self.selection_start.get_or_insert(self.cursor_pos);
+        } else {
+            self.selection_start = None;
+        }
+        self.cursor_pos = index;
+    }
+    
+    /// A key press while focused, typing `text`; Shift extends the selection
+    fn handle_key(&mut self, key: &winit::keyboard::Key, text: Option<&str>) -> bool {
+        use winit::keyboard::{Key, NamedKey};
+        
+        if let Some(action) = ClipboardAction::from_key(key, self.modifiers) {
+            self.apply_clipboard(action);
+            return true;
+        }
+        
+        // Handle special keys
+        match key {
+            Key::Named(NamedKey::Backspace) => { self.delete_char(); return true; }
+            Key::Named(NamedKey::ArrowLeft) => { self.move_cursor(false, self.modifiers.shift_key()); return true; }
+            Key::Named(NamedKey::ArrowRight) => { self.move_cursor(true, self.modifiers.shift_key()); return true; }
+            Key::Named(NamedKey::Enter) => { self.insert_char('\n'); return true; }
+            _ => {}
+        }
+        
+        // Regular text input
+        if let Some(text) = text {
+            for c in text.chars() {
+                if !c.is_control() {
+                    self.insert_char(c);
                 }
             }
+            return true;
         }
+        false
+    }
+    
+    /// Byte index of the caret position nearest to the window point `x`,
+    /// measuring the spans the way `render` lays them out (the stops
+    /// `GlassRenderer::position_to_index` gives for each span)
+    fn index_at(&self, x: f32) -> usize {
+        let mut span_x = self.position.x + 12.0;
+        let mut start = 0;
+        for (i, span) in self.content.iter().enumerate() {
+            let measure = |run: &str| crate::text::layout_text_width(run, span.style.font_size);
+            let width = measure(&span.text);
+            if x < span_x + width || i + 1 == self.content.len() {
+                return start + crate::text::x_to_index(&span.text, x - span_x, measure);
+            }
+            span_x += width;
+            start += span.plain_text().len();
+        }
+        0
     }
 }
 
+/// Text of every span in `plain_text` order: each span's own, then its children's
+fn span_texts_mut(spans: &mut [TextSpan]) -> Vec<&mut String> {
+    let mut texts = Vec::new();
+    for span in spans {
+        texts.push(&mut span.text);
+        texts.extend(span_texts_mut(&mut span.children));
+    }
+    texts
+}
+
 impl Default for RichTextEditor { fn default() -> Self { Self::new() } }
 
+impl Focusable for RichTextEditor {
+    fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+    
+    fn on_focus(&mut self) {
+        self.focused = true;
+    }
+    
+    fn on_blur(&mut self) {
+        self.focused = false;
+    }
+}
+
 impl Widget for RichTextEditor {
     fn layout(&mut self, _origin: Vec2, _available: Vec2) -> Vec2 {
         Vec2::new(self.size.width, self.size.height)
     }
     
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.size = constraints.constrain(self.size);
+        self.size
+    }
+    
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+    
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+    
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         use winit::event::{Event, WindowEvent, ElementState, MouseButton};
         
         match event {
+            Event::WindowEvent { event: WindowEvent::ModifiersChanged(modifiers), .. } => {
+                self.modifiers = modifiers.state();
+                false
+            }
             Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
                 let in_bounds = mouse_pos.x >= self.position.x 
                     && mouse_pos.x <= self.position.x + self.size.width
                     && mouse_pos.y >= self.position.y 
                     && mouse_pos.y <= self.position.y + self.size.height;
                 self.focused = in_bounds;
+                if in_bounds {
+                    // Shift+click extends the selection, a plain press starts one
+                    let index = self.index_at(mouse_pos.x);
+                    self.move_caret(index, self.modifiers.shift_key());
+                    self.selection_start.get_or_insert(index);
+                    self.dragging = true;
+                }
                 in_bounds
             }
+            Event::WindowEvent { event: WindowEvent::CursorMoved { .. }, .. } if self.dragging => {
+                self.cursor_pos = self.index_at(mouse_pos.x);
+                true
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Released, button: MouseButton::Left, .. }, .. } if self.dragging => {
+                self.dragging = false;
+                true
+            }
+            Event::WindowEvent { event: WindowEvent::Ime(winit::event::Ime::Commit(text)), .. } if self.focused => {
+                text.chars().filter(|c| !c.is_control()).for_each(|c| self.insert_char(c));
+                true
+            }
             Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. } if self.focused => {
-                if key_event.state.is_pressed() {
-                    use winit::keyboard::{Key, NamedKey};
-                    
-                    // Handle special keys
-                    match &key_event.logical_key {
-                        Key::Named(NamedKey::Backspace) => { self.delete_char(); return true; }
-                        Key::Named(NamedKey::ArrowLeft) if self.cursor_pos > 0 => { self.cursor_pos -= 1; return true; }
-                        Key::Named(NamedKey::ArrowRight) => { self.cursor_pos += 1; return true; }
-                        Key::Named(NamedKey::Enter) => { self.insert_char('\n'); return true; }
-                        _ => {}
-                    }
-                    
-                    // Regular text input
-                    if let Some(text) = &key_event.text {
-                        for c in text.chars() {
-                            if !c.is_control() {
-                                self.insert_char(c);
-                            }
-                        }
-                        return true;
-                    }
-                }
-                false
+                key_event.state.is_pressed() && self.handle_key(&key_event.logical_key, key_event.text.as_deref())
             }
             _ => false,
         }
@@ -497,18 +790,35 @@ impl Widget for RichTextEditor {
         
         // Content
         let text_pos = self.position + Vec2::new(12.0, 12.0);
+        let selection = self.selection();
+        let mut cursor_drawn = !(self.focused && self.cursor_blink < 0.5);
         let mut x = text_pos.x;
-        
+        let mut start = 0;
         for span in &self.content {
-            renderer.draw_text(&span.text, Vec2::new(x, text_pos.y), span.style.font_size, span.style.color);
-            x += span.text.len() as f32 * span.style.font_size * 0.6;
-        }
-        
-        // Cursor
-        if self.focused && self.cursor_blink < 0.5 {
-            let plain = self.plain_text();
-            let cursor_x = text_pos.x + (self.cursor_pos.min(plain.len()) as f32) * 16.0 * 0.6;
-            renderer.draw_rect(Vec2::new(cursor_x, text_pos.y), Vec2::new(2.0, 18.0), theme_text);
+            let span_pos = Vec2::new(x, text_pos.y);
+            let end = start + span.text.len();
+            
+            // Selection highlight behind the part of it in this span
+            if let Some(selection) = &selection {
+                let (from, to) = (selection.start.clamp(start, end), selection.end.clamp(start, end));
+                if from < to {
+                    for rect in renderer.selection_rects(&span.text, span.style.font_size, from - start..to - start) {
+                        renderer.draw_rect(span_pos + rect.min, rect.size(), Vec4::new(0.3, 0.5, 0.9, 0.35));
+                    }
+                }
+            }
+            
+            renderer.draw_text(&span.text, span_pos, span.style.font_size, span.style.color);
+            
+            // Cursor, in the first span reaching it
+            if !cursor_drawn && self.cursor_pos <= end {
+                let cursor_x = x + renderer.index_to_position(&span.text, span.style.font_size, self.cursor_pos.max(start) - start);
+                renderer.draw_rect(Vec2::new(cursor_x, text_pos.y), Vec2::new(2.0, span.style.font_size * 1.2), theme_text);
+                cursor_drawn = true;
+            }
+            
+            x += renderer.measure_text(&span.text, span.style.font_size).width;
+            start += span.plain_text().len();
         }
         
         // Toolbar hint
@@ -523,3 +833,461 @@ impl Widget for RichTextEditor {
     fn intrinsic_width(&self, _height: f32) -> Option<f32> { Some(self.size.width) }
     fn intrinsic_height(&self, _width: f32) -> Option<f32> { Some(self.size.height) }
 }
+
+// =============================================================================
+// MARKDOWN
+// =============================================================================
+
+/// Colors used by `Markdown` for code and links
+const CODE_BACKGROUND: Vec4 = Vec4::new(0.2, 0.2, 0.25, 0.8);
+const CODE_COLOR: Vec4 = Vec4::new(0.85, 0.9, 0.8, 1.0);
+const LINK_COLOR: Vec4 = Vec4::new(0.45, 0.7, 1.0, 1.0);
+/// Indent per list level and padding around code blocks
+const LIST_INDENT: f32 = 20.0;
+const CODE_PADDING: f32 = 8.0;
+
+/// Kind of a top-level `Markdown` block
+#[derive(Clone, Debug, PartialEq)]
+pub enum MarkdownBlockKind {
+    Paragraph,
+    /// `#` to `######`
+    Heading(u8),
+    /// Item of a list nested `depth` lists deep, with its bullet or number
+    /// (empty for a continuation paragraph)
+    ListItem { depth: usize, marker: String },
+    CodeBlock,
+    Quote,
+    Rule,
+}
+
+/// Block of a `Markdown` document with its styled inline content
+#[derive(Clone, Debug)]
+pub struct MarkdownBlock {
+    pub kind: MarkdownBlockKind,
+    pub spans: Vec<TextSpan>,
+}
+
+impl MarkdownBlock {
+    /// Left indent of the block's text
+    fn indent(&self) -> f32 {
+        match &self.kind {
+            MarkdownBlockKind::ListItem { depth, .. } => (*depth + 1) as f32 * LIST_INDENT,
+            MarkdownBlockKind::CodeBlock => CODE_PADDING,
+            MarkdownBlockKind::Quote => 14.0,
+            _ => 0.0,
+        }
+    }
+    
+    /// Extra space above and below the text (code block padding)
+    fn padding(&self) -> f32 {
+        if self.kind == MarkdownBlockKind::CodeBlock { CODE_PADDING } else { 0.0 }
+    }
+}
+
+/// Inline formatting open while parsing
+#[derive(Default)]
+struct InlineState {
+    bold: bool,
+    italic: bool,
+    strikethrough: bool,
+    heading: Option<u8>,
+    code_block: bool,
+    link: Option<String>,
+}
+
+/// Read-only CommonMark document: headings, emphasis, lists, code and
+/// links, laid out as a column of word-wrapped blocks
+pub struct Markdown {
+    source: String,
+    blocks: Vec<MarkdownBlock>,
+    /// One `RichText` per block, with its y offset from the last layout
+    texts: Vec<(RichText, f32)>,
+    position: Vec2,
+    size: Size,
+    font_size: f32,
+    color: Vec4,
+}
+
+impl Markdown {
+    pub fn new(source: &str) -> Self {
+        let mut markdown = Self {
+            source: String::new(),
+            blocks: Vec::new(),
+            texts: Vec::new(),
+            position: Vec2::ZERO,
+            size: Size::ZERO,
+            font_size: 15.0,
+            color: SpanStyle::default().color,
+        };
+        markdown.set_source(source);
+        markdown
+    }
+    
+    /// Body text size; headings scale from it
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        let source = std::mem::take(&mut self.source);
+        self.set_source(&source);
+        self
+    }
+    
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        let source = std::mem::take(&mut self.source);
+        self.set_source(&source);
+        self
+    }
+    
+    /// Replace the document (e.g. as a streamed answer grows)
+    pub fn set_source(&mut self, source: &str) {
+        self.source = source.to_string();
+        self.blocks = self.parse(source);
+        self.texts = self.blocks.iter()
+            .map(|block| (RichText { spans: block.spans.clone(), ..RichText::new() }, 0.0))
+            .collect();
+    }
+    
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+    
+    pub fn blocks(&self) -> &[MarkdownBlock] {
+        &self.blocks
+    }
+    
+    /// (text, url) of every link, in document order
+    pub fn links(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.blocks.iter()
+            .flat_map(|block| &block.spans)
+            .filter_map(|span| span.link.as_deref().map(|url| (span.text.as_str(), url)))
+    }
+    
+    fn span_style(&self, state: &InlineState) -> SpanStyle {
+        let mut style = SpanStyle::new().color(self.color).size(self.font_size);
+        if let Some(level) = state.heading {
+            let scale = match level { 1 => 1.6, 2 => 1.4, 3 => 1.2, _ => 1.0 };
+            style = style.size(self.font_size * scale).bold();
+        }
+        if state.bold { style = style.bold(); }
+        if state.italic { style = style.italic(); }
+        if state.strikethrough { style = style.strikethrough(); }
+        if state.code_block { style = style.color(CODE_COLOR); }
+        if state.link.is_some() { style = style.color(LINK_COLOR).underline(); }
+        style
+    }
+    
+    fn parse(&self, source: &str) -> Vec<MarkdownBlock> {
+        use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+        
+        let mut blocks: Vec<MarkdownBlock> = Vec::new();
+        let mut state = InlineState::default();
+        // Next number of each open list (None = bullets)
+        let mut lists: Vec<Option<u64>> = Vec::new();
+        let mut quote_depth = 0;
+        // Set by a list item until its first paragraph starts
+        let mut item_open = false;
+        
+        let start_block = |blocks: &mut Vec<MarkdownBlock>, kind| blocks.push(MarkdownBlock { kind, spans: Vec::new() });
+        
+        for event in Parser::new_ext(source, Options::ENABLE_STRIKETHROUGH) {
+            match event {
+                Event::Start(Tag::Paragraph) => {
+                    if item_open {
+                        item_open = false;
+                    } else if !lists.is_empty() {
+                        start_block(&mut blocks, MarkdownBlockKind::ListItem { depth: lists.len() - 1, marker: String::new() });
+                    } else if quote_depth > 0 {
+                        start_block(&mut blocks, MarkdownBlockKind::Quote);
+                    } else {
+                        start_block(&mut blocks, MarkdownBlockKind::Paragraph);
+                    }
+                }
+                Event::Start(Tag::Heading { level, .. }) => {
+                    state.heading = Some(level as u8);
+                    start_block(&mut blocks, MarkdownBlockKind::Heading(level as u8));
+                }
+                Event::End(TagEnd::Heading(_)) => state.heading = None,
+                Event::Start(Tag::BlockQuote(_)) => quote_depth += 1,
+                Event::End(TagEnd::BlockQuote(_)) => quote_depth -= 1,
+                Event::Start(Tag::CodeBlock(_)) => {
+                    state.code_block = true;
+                    start_block(&mut blocks, MarkdownBlockKind::CodeBlock);
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    state.code_block = false;
+                    if let Some(last) = blocks.last_mut().and_then(|block| block.spans.last_mut()) {
+                        last.text.truncate(last.text.trim_end_matches('\n').len());
+                    }
+                }
+                Event::Start(Tag::List(first)) => {
+                    lists.push(first);
+                    item_open = false;
+                }
+                Event::End(TagEnd::List(_)) => { lists.pop(); }
+                Event::Start(Tag::Item) => {
+                    let marker = match lists.last_mut() {
+                        Some(Some(number)) => {
+                            *number += 1;
+                            format!("{}.", *number - 1)
+                        }
+                        _ => "•".to_string(),
+                    };
+                    start_block(&mut blocks, MarkdownBlockKind::ListItem { depth: lists.len().saturating_sub(1), marker });
+                    item_open = true;
+                }
+                Event::End(TagEnd::Item) => item_open = false,
+                Event::Start(Tag::Strong) => state.bold = true,
+                Event::End(TagEnd::Strong) => state.bold = false,
+                Event::Start(Tag::Emphasis) => state.italic = true,
+                Event::End(TagEnd::Emphasis) => state.italic = false,
+                Event::Start(Tag::Strikethrough) => state.strikethrough = true,
+                Event::End(TagEnd::Strikethrough) => state.strikethrough = false,
+                Event::Start(Tag::Link { dest_url, .. }) => state.link = Some(dest_url.to_string()),
+                Event::End(TagEnd::Link) => state.link = None,
+                Event::Text(text) => {
+                    let span = TextSpan::styled(&text, self.span_style(&state));
+                    push_span(&mut blocks, span, state.link.as_deref());
+                }
+                Event::Code(code) => {
+                    let style = self.span_style(&state).background(CODE_BACKGROUND).color(CODE_COLOR);
+                    push_span(&mut blocks, TextSpan::styled(&code, style), state.link.as_deref());
+                }
+                Event::SoftBreak => push_span(&mut blocks, TextSpan::styled(" ", self.span_style(&state)), None),
+                Event::HardBreak => push_span(&mut blocks, TextSpan::styled("\n", self.span_style(&state)), None),
+                Event::Rule => start_block(&mut blocks, MarkdownBlockKind::Rule),
+                _ => {}
+            }
+        }
+        blocks
+    }
+    
+    /// Height of each block's text at `width` (measured as in `RichText`
+    /// layout)
+    fn block_heights(&self, width: f32) -> impl Iterator<Item = f32> + '_ {
+        self.blocks.iter().zip(&self.texts).map(move |(block, (text, _))| {
+            if block.kind == MarkdownBlockKind::Rule {
+                return 1.0;
+            }
+            let text_width = (width - block.indent() - block.padding()).max(1.0);
+            text.flow(text_width, layout_text_width).1.height + block.padding() * 2.0
+        })
+    }
+    
+    /// Space between blocks
+    fn block_gap(&self) -> f32 {
+        self.font_size * 0.6
+    }
+    
+    /// Total height laid out at `width`
+    pub fn height_for_width(&self, width: f32) -> f32 {
+        let blocks: f32 = self.block_heights(width).sum();
+        blocks + self.block_gap() * self.blocks.len().saturating_sub(1) as f32
+    }
+}
+
+/// Append `span` (marked as a link to `link`) to the last block, starting a
+/// paragraph if there is none
+fn push_span(blocks: &mut Vec<MarkdownBlock>, mut span: TextSpan, link: Option<&str>) {
+    span.link = link.map(str::to_string);
+    if blocks.is_empty() {
+        blocks.push(MarkdownBlock { kind: MarkdownBlockKind::Paragraph, spans: Vec::new() });
+    }
+    if let Some(block) = blocks.last_mut() {
+        block.spans.push(span);
+    }
+}
+
+impl Widget for Markdown {
+    fn layout(&mut self, origin: Vec2, available: Vec2) -> Vec2 {
+        self.position = origin;
+        let heights: Vec<f32> = self.block_heights(available.x).collect();
+        let gap = self.block_gap();
+        let mut y = 0.0;
+        for ((block, (text, offset)), height) in self.blocks.iter().zip(&mut self.texts).zip(heights) {
+            *offset = y;
+            let inset = Vec2::new(block.indent(), block.padding());
+            let text_width = (available.x - block.indent() - block.padding()).max(1.0);
+            text.layout_child(origin + Vec2::new(0.0, y) + inset, Vec2::new(text_width, f32::INFINITY));
+            y += height + gap;
+        }
+        self.size = Size::new(available.x, (y - gap).max(0.0));
+        Vec2::new(self.size.width, self.size.height)
+    }
+    
+    /// Blocks are laid out in place and moved along by `set_position`
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.layout(self.position, Vec2::new(constraints.max_width, f32::INFINITY));
+        self.size = constraints.constrain(Size::new(size.x, size.y));
+        self.size
+    }
+    
+    fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool { false }
+    fn update(&mut self, _dt: f32) {}
+    
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let gap = self.block_gap();
+        let mut bottoms = self.texts.iter().skip(1).map(|(_, offset)| *offset - gap)
+            .chain(std::iter::once(self.size.height));
+        for (block, (text, offset)) in self.blocks.iter().zip(&self.texts) {
+            let top = self.position + Vec2::new(0.0, *offset);
+            let height = bottoms.next().unwrap_or(self.size.height) - offset;
+            match &block.kind {
+                MarkdownBlockKind::CodeBlock => {
+                    renderer.draw_rounded_rect(top, Vec2::new(self.size.width, height), CODE_BACKGROUND, 6.0);
+                }
+                MarkdownBlockKind::Quote => {
+                    renderer.draw_rect(top, Vec2::new(3.0, height), Vec4::new(self.color.x, self.color.y, self.color.z, 0.4));
+                }
+                MarkdownBlockKind::ListItem { marker, .. } if !marker.is_empty() => {
+                    let marker_pos = top + Vec2::new(block.indent() - LIST_INDENT * 0.8, 0.0);
+                    renderer.draw_text(marker, marker_pos, self.font_size, self.color);
+                }
+                MarkdownBlockKind::Rule => {
+                    renderer.draw_rect(top, Vec2::new(self.size.width, 1.0), Vec4::new(self.color.x, self.color.y, self.color.z, 0.3));
+                    continue;
+                }
+                _ => {}
+            }
+            text.render(renderer);
+        }
+    }
+    
+    fn set_position(&mut self, pos: Offset) {
+        let delta = Vec2::new(pos.x, pos.y) - self.position;
+        self.position += delta;
+        for (text, _) in &mut self.texts {
+            text.position += delta;
+        }
+    }
+    fn get_position(&self) -> Offset { Offset::new(self.position.x, self.position.y) }
+    fn get_size(&self) -> Size { self.size }
+    fn intrinsic_width(&self, _height: f32) -> Option<f32> { None }
+    fn intrinsic_height(&self, width: f32) -> Option<f32> { Some(self.height_for_width(width)) }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rich_text_flow_wraps_words_and_newlines() {
+        let text = RichText::new()
+            .with_text("hello world ")
+            .with_span(TextSpan::styled("foo\nbar", SpanStyle::new().size(20.0)));
+        let measure = |run: &str, _size: f32| run.chars().count() as f32 * 10.0;
+        let (runs, size) = text.flow(120.0, measure);
+        let placed: Vec<_> = runs.iter().map(|run| (run.text, run.offset.x)).collect();
+        assert_eq!(placed, [("hello world ", 0.0), ("foo", 0.0), ("bar", 0.0)]);
+        
+        // A wrapped line is as tall as its largest span
+        let line = 20.0 * text.line_height;
+        assert_eq!(runs.iter().map(|run| run.offset.y).collect::<Vec<_>>(), [0.0, line, line + line]);
+        assert_eq!(size, Size::new(110.0, line + line + line));
+        
+        // Unwrapped, only the newline breaks
+        let (runs, size) = text.flow(f32::INFINITY, measure);
+        assert_eq!(runs.iter().map(|run| run.text).collect::<Vec<_>>(), ["hello world ", "foo", "bar"]);
+        assert_eq!((runs[1].offset, size.width), (Vec2::new(120.0, 0.0), 150.0));
+    }
+
+    #[test]
+    fn test_markdown_blocks_and_inline_styles() {
+        let markdown = Markdown::new("# Title\n\nSome **bold** and [docs](https://example.com).\n\n- one\n- two\n  1. nested\n\n```\nlet x = 1;\n```\n\n> quoted\n\n---\n");
+        let kinds: Vec<_> = markdown.blocks().iter().map(|b| b.kind.clone()).collect();
+        assert_eq!(kinds, [
+            MarkdownBlockKind::Heading(1),
+            MarkdownBlockKind::Paragraph,
+            MarkdownBlockKind::ListItem { depth: 0, marker: "•".into() },
+            MarkdownBlockKind::ListItem { depth: 0, marker: "•".into() },
+            MarkdownBlockKind::ListItem { depth: 1, marker: "1.".into() },
+            MarkdownBlockKind::CodeBlock,
+            MarkdownBlockKind::Quote,
+            MarkdownBlockKind::Rule,
+        ]);
+        
+        let paragraph = &markdown.blocks()[1].spans;
+        assert_eq!(paragraph.iter().map(|s| s.text.as_str()).collect::<String>(), "Some bold and docs.");
+        assert_eq!(paragraph[1].style.font_weight, FontWeight::Bold);
+        assert_eq!(markdown.links().collect::<Vec<_>>(), [("docs", "https://example.com")]);
+        assert_eq!(paragraph[3].link(), Some("https://example.com"));
+        assert_eq!(TextSpan::new("x").with_link("a").link(), Some("a"));
+        assert_eq!(markdown.blocks()[5].spans[0].text, "let x = 1;");
+        assert!(markdown.blocks()[0].spans[0].style.font_size > markdown.blocks()[1].spans[0].style.font_size);
+        
+        // Taller when squeezed
+        assert!(markdown.height_for_width(80.0) > markdown.height_for_width(800.0));
+    }
+
+    #[test]
+    fn test_editor_pastes_over_selection() {
+        let mut editor = RichTextEditor::new().with_content("hello world");
+        editor.set_selection(6..11);
+        assert_eq!(editor.selected_text(), "world");
+        editor.insert_text("there\u{7}\nfriend");
+        assert_eq!(editor.plain_text(), "hello there\nfriend");
+        assert_eq!((editor.cursor_pos, editor.selected_text()), (18, String::new()));
+
+        editor.set_selection(0..6);
+        editor.delete_char();
+        assert_eq!(editor.plain_text(), "there\nfriend");
+        assert_eq!(editor.cursor_pos, 0);
+    }
+
+    #[test]
+    fn test_editor_selection_spans_styles() {
+        let mut editor = RichTextEditor::new();
+        let bold = SpanStyle { font_weight: FontWeight::Bold, ..SpanStyle::default() };
+        editor.content = vec![TextSpan::new("hello "), TextSpan::styled("bold", bold), TextSpan::new(" world")];
+        editor.set_selection(3..12);
+        assert_eq!(editor.selected_text(), "lo bold w");
+        
+        // Cutting takes the text out of all three spans, keeping their styles
+        editor.delete_selection();
+        assert_eq!(editor.plain_text(), "helorld");
+        assert_eq!(editor.content[1].text, "");
+        assert_eq!(editor.cursor_pos, 3);
+        
+        // Typing and backspace work past the first span
+        editor.cursor_pos = 7;
+        editor.insert_text("!");
+        assert_eq!(editor.content[2].text, "orld!");
+        editor.delete_char();
+        editor.delete_char();
+        assert_eq!(editor.plain_text(), "helorl");
+    }
+
+    #[test]
+    fn test_editor_selects_with_shift_arrows_and_drag() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        use winit::keyboard::{Key, ModifiersState, NamedKey};
+        let event = |recorded: RecordedEvent| recorded.to_event().unwrap();
+        let left = Key::Named(NamedKey::ArrowLeft);
+        let mouse = |pressed| event(RecordedEvent::MouseInput { button: RecordedButton::Left, pressed });
+        let mut editor = RichTextEditor::new().with_content("hello world");
+        
+        // Without a font, 16px text measures 8px per character past the 12px inset
+        assert!(editor.handle_event(&mouse(true), Vec2::new(12.0 + 8.0 * 6.0, 20.0)));
+        assert!(editor.handle_event(&event(RecordedEvent::CursorMoved { x: 0.0, y: 0.0 }), Vec2::new(12.0 + 8.0 * 11.0, 20.0)));
+        assert!(editor.handle_event(&mouse(false), Vec2::new(12.0 + 8.0 * 11.0, 20.0)));
+        assert_eq!(editor.selected_text(), "world");
+        
+        // Shift+Left shrinks it from the cursor end (winit key events can't
+        // be built outside winit, so keys go to `handle_key`)
+        editor.handle_event(&event(RecordedEvent::Modifiers { bits: ModifiersState::SHIFT.bits() }), Vec2::ZERO);
+        editor.handle_key(&left, None);
+        editor.handle_key(&left, None);
+        assert_eq!(editor.selected_text(), "wor");
+        
+        // Shift+click moves the cursor end, a plain arrow drops the selection
+        editor.handle_event(&mouse(true), Vec2::new(12.0 + 8.0 * 2.0, 20.0));
+        editor.handle_event(&mouse(false), Vec2::new(12.0 + 8.0 * 2.0, 20.0));
+        assert_eq!(editor.selected_text(), "llo ");
+        editor.handle_event(&event(RecordedEvent::Modifiers { bits: 0 }), Vec2::ZERO);
+        editor.handle_key(&left, None);
+        assert_eq!((editor.selected_text(), editor.cursor_pos), (String::new(), 1));
+    }
+}
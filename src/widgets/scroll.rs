@@ -0,0 +1,221 @@
+//! GlassUI Scrolling
+//!
+//! `ScrollBar` holds one axis of scroll state for ScrollArea, Table and
+//! ListView: wheel input eased toward a target, a draggable thumb, and
+//! paging by clicking the track on either side of it.
+
+use glam::Vec4;
+
+/// How quickly an eased scroll closes on its target (per second)
+const EASE_RATE: f32 = 18.0;
+
+/// Shortest thumb, so huge content stays grabbable
+const MIN_THUMB: f32 = 30.0;
+
+/// One axis of scrolling: the offset drawn, where it is easing to, and
+/// the thumb's drag state
+///
+/// Positions along the track are relative to its start; `viewport` is
+/// the visible length of content and `content` its full length.
+#[derive(Clone, Debug, Default)]
+pub struct ScrollBar {
+    offset: f32,
+    target: f32,
+    /// Pointer position along the track and offset when the thumb was grabbed
+    grab: Option<(f32, f32)>,
+    /// Pointer is over the thumb
+    pub hovered: bool,
+}
+
+impl ScrollBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offset drawn this frame
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    /// Offset being eased towards
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Jump to `offset` (clamped to `0..=max`) without easing
+    pub fn set(&mut self, offset: f32, max: f32) {
+        self.target = offset.clamp(0.0, max.max(0.0));
+        self.offset = self.target;
+    }
+
+    /// Ease to `offset` (clamped to `0..=max`)
+    pub fn scroll_to(&mut self, offset: f32, max: f32) {
+        self.target = offset.clamp(0.0, max.max(0.0));
+    }
+
+    /// Ease `delta` further than the current target, as wheel input does;
+    /// returns whether the target moved
+    pub fn scroll_by(&mut self, delta: f32, max: f32) -> bool {
+        let old = self.target;
+        self.scroll_to(self.target + delta, max);
+        (self.target - old).abs() > 0.1
+    }
+
+    /// Ease just far enough to show `start..start + length` of the content
+    /// (its start, if it is longer than the view)
+    pub fn reveal(&mut self, start: f32, length: f32, viewport: f32, max: f32) {
+        if start < self.target {
+            self.scroll_to(start, max);
+        } else if start + length > self.target + viewport {
+            self.scroll_to((start + length - viewport).min(start), max);
+        }
+    }
+
+    /// Keep the offsets within `0..=max` after the content or view resized
+    pub fn clamp(&mut self, max: f32) {
+        let max = max.max(0.0);
+        self.offset = self.offset.clamp(0.0, max);
+        self.target = self.target.clamp(0.0, max);
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.offset != self.target
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.offset += (self.target - self.offset) * (1.0 - (-EASE_RATE * dt).exp());
+        if (self.target - self.offset).abs() < 0.5 {
+            self.offset = self.target;
+        }
+    }
+
+    /// Start along the track and length of the thumb
+    pub fn thumb(&self, viewport: f32, content: f32, track: f32) -> (f32, f32) {
+        let length = (track * (viewport / content).min(1.0)).max(MIN_THUMB).min(track);
+        let max_scroll = (content - viewport).max(0.0);
+        let ratio = if max_scroll > 0.0 { self.offset / max_scroll } else { 0.0 };
+        (ratio * (track - length), length)
+    }
+
+    /// Whether `pos` along the track is on the thumb
+    pub fn thumb_contains(&self, pos: f32, viewport: f32, content: f32, track: f32) -> bool {
+        let (start, length) = self.thumb(viewport, content, track);
+        content > viewport && pos >= start && pos <= start + length
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    /// Press at `pos` along the track: grabs the thumb, or pages one view
+    /// towards the press. Returns whether the track took the press.
+    pub fn press(&mut self, pos: f32, viewport: f32, content: f32, track: f32) -> bool {
+        if content <= viewport || !(0.0..=track).contains(&pos) {
+            return false;
+        }
+        let max_scroll = content - viewport;
+        let (start, length) = self.thumb(viewport, content, track);
+        if pos < start {
+            self.scroll_to(self.target - viewport, max_scroll);
+        } else if pos > start + length {
+            self.scroll_to(self.target + viewport, max_scroll);
+        } else {
+            // Stop any easing so the thumb stays under the pointer
+            self.set(self.offset, max_scroll);
+            self.grab = Some((pos, self.offset));
+        }
+        true
+    }
+
+    /// Follow the pointer at `pos` while the thumb is grabbed; returns
+    /// whether it is
+    pub fn drag(&mut self, pos: f32, viewport: f32, content: f32, track: f32) -> bool {
+        let Some((grab_pos, grab_offset)) = self.grab else {
+            return false;
+        };
+        let (_, length) = self.thumb(viewport, content, track);
+        let range = track - length;
+        let max_scroll = (content - viewport).max(0.0);
+        if range > 0.0 {
+            self.set(grab_offset + (pos - grab_pos) / range * max_scroll, max_scroll);
+        }
+        true
+    }
+
+    pub fn release(&mut self) {
+        self.grab = None;
+    }
+
+    /// Pointer position along the track and offset when the thumb was
+    /// grabbed, while it is
+    pub fn grab(&self) -> Option<(f32, f32)> {
+        self.grab
+    }
+
+    /// Thumb color: `accent` while dragged, lighter while hovered
+    pub fn thumb_color(&self, accent: Vec4) -> Vec4 {
+        if self.is_dragging() {
+            accent
+        } else if self.hovered {
+            Vec4::new(0.6, 0.6, 0.6, 0.8)
+        } else {
+            Vec4::new(0.4, 0.4, 0.4, 0.6)
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wheel_eases_and_track_pages() {
+        let mut bar = ScrollBar::new();
+        // 100px view of 400px content on a 100px track: a 30px thumb
+        assert!(bar.scroll_by(120.0, 300.0));
+        assert_eq!(bar.offset(), 0.0);
+        bar.update(1.0 / 60.0);
+        assert!(bar.offset() > 0.0 && bar.offset() < 120.0);
+        bar.update(1.0);
+        assert_eq!(bar.offset(), 120.0);
+        assert!(!bar.is_animating());
+
+        // Below the thumb pages a view down; above pages back
+        assert!(bar.press(95.0, 100.0, 400.0, 100.0));
+        assert_eq!(bar.target(), 220.0);
+        assert!(bar.press(0.0, 100.0, 400.0, 100.0));
+        assert_eq!(bar.target(), 120.0);
+
+        // Grabbing the thumb drags content in proportion: 70px of track
+        // moves 300px of content
+        bar.set(0.0, 300.0);
+        assert!(bar.press(10.0, 100.0, 400.0, 100.0));
+        assert!(bar.drag(45.0, 100.0, 400.0, 100.0));
+        assert_eq!(bar.offset(), 150.0);
+        bar.release();
+        assert!(!bar.drag(90.0, 100.0, 400.0, 100.0));
+
+        bar.reveal(380.0, 20.0, 100.0, 300.0);
+        assert_eq!(bar.target(), 300.0);
+    }
+
+    #[test]
+    fn test_thumb_color_follows_hover_and_drag() {
+        let accent = Vec4::new(0.0, 0.5, 1.0, 1.0);
+        let mut bar = ScrollBar::new();
+        let idle = bar.thumb_color(accent);
+        bar.hovered = true;
+        let hovered = bar.thumb_color(accent);
+        assert!(hovered.w > idle.w);
+        assert!(bar.press(10.0, 100.0, 400.0, 100.0));
+        assert_eq!(bar.grab(), Some((10.0, 0.0)));
+        assert_eq!(bar.thumb_color(accent), accent);
+        bar.release();
+        assert_eq!(bar.grab(), None);
+        assert_eq!(bar.thumb_color(accent), hovered);
+    }
+}
@@ -0,0 +1,294 @@
+//! GlassUI Search Input
+//!
+//! `SearchInput` is a `TextInput` with a magnifier icon and a clear button
+//! that reports what was typed only once typing pauses, so filtering a
+//! `FileTree`, `DataTable` or `ListView` doesn't rerun on every keystroke.
+
+use glam::Vec2;
+use winit::event::{ElementState, MouseButton};
+use crate::renderer::GlassRenderer;
+use super::core::{Widget, WidgetState, get_theme, render_faded};
+use super::input::TextInput;
+use crate::layout::{BoxConstraints, EdgeInsets, Offset, Rect, Size};
+use crate::focus::Focusable;
+use crate::style::WidgetStyle;
+
+/// Width of the clear (×) button at the right of the field
+const CLEAR_WIDTH: f32 = 28.0;
+
+/// Default pause after the last edit before `on_search` runs, in seconds
+const DEFAULT_DEBOUNCE: f32 = 0.3;
+
+type SearchCallback = Box<dyn FnMut(String)>;
+
+// =============================================================================
+// SEARCH INPUT
+// =============================================================================
+
+/// Search field with a debounced `on_search`
+///
+/// Edits restart a countdown advanced in `update`; when it runs out the
+/// query is passed to `on_search`. Enter searches at once, and Escape or
+/// the × button clears the field (and the search) immediately.
+pub struct SearchInput {
+    pub input: TextInput,
+    /// Seconds to wait after the last edit before searching
+    pub debounce: f32,
+    /// Called with the query once typing pauses
+    pub on_search: Option<SearchCallback>,
+    pub clear_hovered: bool,
+    /// Time left before the pending search runs
+    pending: Option<f32>,
+    /// Query last passed to `on_search`, so unchanged text isn't re-searched
+    last_query: String,
+}
+
+impl SearchInput {
+    pub fn new(placeholder: &str) -> Self {
+        // Leave room for the magnifier on the left
+        let style = WidgetStyle::default().padding(EdgeInsets::only(8.0, CLEAR_WIDTH, 8.0, 34.0));
+        Self {
+            input: TextInput::new(placeholder).with_style(style),
+            debounce: DEFAULT_DEBOUNCE,
+            on_search: None,
+            clear_hovered: false,
+            pending: None,
+            last_query: String::new(),
+        }
+    }
+
+    /// Seconds to wait after the last edit (0 searches on every edit)
+    pub fn with_debounce(mut self, seconds: f32) -> Self {
+        self.debounce = seconds.max(0.0);
+        self
+    }
+
+    pub fn on_search(mut self, callback: impl FnMut(String) + 'static) -> Self {
+        self.on_search = Some(Box::new(callback));
+        self
+    }
+
+    pub fn get_text(&self) -> &str {
+        self.input.get_text()
+    }
+
+    /// A search is waiting for typing to pause
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Empty the field and search for nothing right away
+    pub fn clear(&mut self) {
+        if !self.input.text.is_empty() {
+            self.input.set_text("");
+            if let Some(callback) = &mut self.input.on_change {
+                callback("");
+            }
+        }
+        self.search_now();
+    }
+
+    /// Run the pending search (or search the current text) immediately
+    pub fn search_now(&mut self) {
+        self.pending = None;
+        if self.input.text == self.last_query {
+            return;
+        }
+        self.last_query = self.input.text.clone();
+        if let Some(callback) = &mut self.on_search {
+            callback(self.last_query.clone());
+        }
+    }
+
+    /// Restart the countdown after an edit
+    fn schedule(&mut self) {
+        if self.debounce <= 0.0 {
+            self.search_now();
+        } else {
+            self.pending = Some(self.debounce);
+        }
+    }
+
+    /// Advance the countdown, searching once it runs out
+    fn tick(&mut self, dt: f32) {
+        if let Some(remaining) = self.pending {
+            let remaining = remaining - dt;
+            if remaining <= 0.0 {
+                self.search_now();
+            } else {
+                self.pending = Some(remaining);
+            }
+        }
+    }
+
+    fn clear_button(&self) -> Rect {
+        Rect::new(
+            Vec2::new(self.input.position.x + self.input.size.x - CLEAR_WIDTH, self.input.position.y),
+            Vec2::new(CLEAR_WIDTH, self.input.size.y),
+        )
+    }
+
+    fn shows_clear(&self) -> bool {
+        !self.input.text.is_empty() && !self.input.disabled
+    }
+
+    /// Search keys: Enter and Escape; returns whether one was used
+    fn handle_search_key(&mut self, key: &winit::keyboard::Key) -> bool {
+        use winit::keyboard::{Key, NamedKey};
+        match key {
+            Key::Named(NamedKey::Enter) => self.search_now(),
+            Key::Named(NamedKey::Escape) if !self.input.text.is_empty() => self.clear(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Widget for SearchInput {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.input.layout_child(origin, max_size)
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.input.layout_child_with_constraints(constraints)
+    }
+
+    fn set_position(&mut self, position: Offset) {
+        self.input.set_position(position);
+    }
+
+    fn get_position(&self) -> Offset {
+        self.input.get_position()
+    }
+
+    fn get_size(&self) -> Size {
+        self.input.get_size()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.input.needs_layout()
+    }
+
+    fn is_animating(&self) -> bool {
+        // Keep updating until the pending search has run
+        self.pending.is_some()
+    }
+
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(&self.input)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(&mut self.input)
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        if self.input.disabled {
+            return false;
+        }
+
+        self.clear_hovered = self.shows_clear() && self.clear_button().contains(mouse_pos);
+        match event {
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. }
+                if self.clear_hovered =>
+            {
+                self.clear();
+                return true;
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. }
+                if self.input.focused && key_event.state.is_pressed() && self.handle_search_key(&key_event.logical_key) =>
+            {
+                return true;
+            }
+            _ => {}
+        }
+
+        let before = self.input.text.clone();
+        let handled = self.input.handle_event(event, mouse_pos);
+        if self.input.text != before {
+            self.schedule();
+        }
+        handled
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.input.update(dt);
+        self.tick(dt);
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.input.render(renderer);
+
+        let theme = get_theme();
+        render_faded(renderer, self.input.disabled, None, |renderer| {
+            renderer.draw_text("🔍", self.input.position + Vec2::new(10.0, 8.0), 16.0, theme.text_secondary);
+
+            if self.shows_clear() {
+                let button = self.clear_button();
+                let color = if self.clear_hovered { theme.text } else { theme.text_secondary };
+                renderer.draw_text("×", button.min + Vec2::new(8.0, 7.0), 18.0, color);
+            }
+        });
+    }
+
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        self.input.debug_properties(props);
+        props.push(("last_query", format!("{:?}", self.last_query)));
+    }
+
+    fn saved_state(&self) -> Option<WidgetState> {
+        self.input.saved_state()
+    }
+
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        self.input.text_origin()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_search_is_debounced_and_cleared() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        let click = RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }.to_event().unwrap();
+        let searches = Rc::new(RefCell::new(Vec::new()));
+        let log = searches.clone();
+        let mut search = SearchInput::new("Filter files")
+            .with_debounce(0.25)
+            .on_search(move |query| log.borrow_mut().push(query));
+        search.layout(Vec2::ZERO, Vec2::new(300.0, 300.0));
+
+        // Typing keeps restarting the countdown
+        search.input.set_text("ma");
+        search.schedule();
+        search.update(0.2);
+        search.input.set_text("main");
+        search.schedule();
+        search.update(0.2);
+        assert!(search.is_animating());
+        assert!(searches.borrow().is_empty());
+        search.update(0.1);
+        assert!(!search.is_pending());
+        assert_eq!(*searches.borrow(), ["main"]);
+
+        // Enter doesn't repeat an unchanged query
+        assert!(search.handle_search_key(&winit::keyboard::Key::Named(winit::keyboard::NamedKey::Enter)));
+        assert_eq!(searches.borrow().len(), 1);
+
+        // The × button clears without waiting
+        let button = search.clear_button().min + Vec2::splat(10.0);
+        assert!(search.handle_event(&click, button));
+        assert_eq!(search.get_text(), "");
+        assert_eq!(*searches.borrow(), ["main", ""]);
+        assert!(!search.handle_search_key(&winit::keyboard::Key::Named(winit::keyboard::NamedKey::Escape)));
+    }
+}
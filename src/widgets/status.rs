@@ -6,25 +6,70 @@
 //! - AgentCard for AI agent display
 
 use glam::{Vec2, Vec4};
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::rc::Rc;
 use std::time::Instant;
-use crate::renderer::GlassRenderer;
+use crate::renderer::{GlassRenderer, ImageInstance, TextureId};
 use crate::widget_id::WidgetId;
 use crate::widgets::core::{Widget, get_theme};
 use crate::panel_style::PanelPreset;
 use crate::ai::AgentState;
+use crate::layout::{BoxConstraints, Offset, Rect, Size};
+use crate::text::LabelCache;
+use crate::state::{State, Subscription};
+use crate::widgets::live::TextSource;
 
 // =============================================================================
 // STATUS ITEM
 // =============================================================================
 
+/// Which part of the status bar an item sits in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatusSection {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Live source feeding a status item's value
+///
+/// Changes land in `pending` from the source's subscriber and are applied
+/// on the next `StatusBar::update`, so nothing is cloned while idle.
+#[derive(Clone)]
+struct StatusBinding {
+    pending: Rc<RefCell<Option<String>>>,
+    _subscription: Option<Rc<Subscription>>,
+}
+
 /// Individual status item for the status bar
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct StatusItem {
     pub id: String,
     pub label: String,
     pub value: String,
     pub icon: Option<String>,
     pub color: Option<Vec4>,
+    pub section: StatusSection,
+    /// Higher priority items stay visible longest when the bar is too narrow
+    pub priority: i32,
+    pub tooltip: Option<String>,
+    pub on_click: Option<Rc<dyn Fn()>>,
+    binding: Option<StatusBinding>,
+}
+
+impl std::fmt::Debug for StatusItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusItem")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .field("value", &self.value)
+            .field("icon", &self.icon)
+            .field("section", &self.section)
+            .field("priority", &self.priority)
+            .finish_non_exhaustive()
+    }
 }
 
 impl StatusItem {
@@ -35,6 +80,11 @@ impl StatusItem {
             value: value.to_string(),
             icon: None,
             color: None,
+            section: StatusSection::Left,
+            priority: 0,
+            tooltip: None,
+            on_click: None,
+            binding: None,
         }
     }
     
@@ -48,8 +98,79 @@ impl StatusItem {
         self
     }
     
+    pub fn with_section(mut self, section: StatusSection) -> Self {
+        self.section = section;
+        self
+    }
+    
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+    
+    pub fn with_tooltip(mut self, tooltip: &str) -> Self {
+        self.tooltip = Some(tooltip.to_string());
+        self
+    }
+    
+    pub fn on_click<F: Fn() + 'static>(mut self, callback: F) -> Self {
+        self.on_click = Some(Rc::new(callback));
+        self
+    }
+    
+    /// Keep the value in sync with a `TextSource`
+    pub fn bind_text(mut self, source: TextSource) -> Self {
+        match source {
+            TextSource::Static(text) => {
+                self.value = text;
+                self.binding = None;
+            }
+            TextSource::Reactive(reactive) => {
+                self.value = reactive.get();
+                let pending = Rc::new(RefCell::new(None));
+                // Weak so a dropped item doesn't keep receiving values
+                let slot = Rc::downgrade(&pending);
+                reactive.subscribe(move |value: &String| {
+                    if let Some(slot) = slot.upgrade() {
+                        *slot.borrow_mut() = Some(value.clone());
+                    }
+                });
+                self.binding = Some(StatusBinding { pending, _subscription: None });
+            }
+        }
+        self
+    }
+    
+    /// Keep the value in sync with a `State<String>`
+    pub fn bind_state(mut self, state: &State<String>) -> Self {
+        self.value = state.get();
+        let pending = Rc::new(RefCell::new(None));
+        let slot = Rc::clone(&pending);
+        let subscription = state.subscribe(move |value: &String| {
+            *slot.borrow_mut() = Some(value.clone());
+        });
+        self.binding = Some(StatusBinding { pending, _subscription: Some(Rc::new(subscription)) });
+        self
+    }
+    
     pub fn set_value(&mut self, value: &str) {
-        self.value = value.to_string();
+        // Reuse the existing allocation; values are updated every frame
+        self.value.clear();
+        self.value.push_str(value);
+    }
+    
+    /// Apply a value pushed by the bound source since the last call
+    fn sync_binding(&mut self) {
+        let pending = self.binding.as_ref().and_then(|b| b.pending.borrow_mut().take());
+        if let Some(value) = pending {
+            self.value = value;
+        }
+    }
+    
+    /// Width the item takes in the bar
+    fn width(&self) -> f32 {
+        let icon = if self.icon.is_some() { 20.0 } else { 0.0 };
+        icon + self.label.chars().count() as f32 * 7.0 + 4.0 + self.value.chars().count() as f32 * 7.0
     }
 }
 
@@ -57,7 +178,15 @@ impl StatusItem {
 // STATUS BAR
 // =============================================================================
 
+const STATUS_BAR_HEIGHT: f32 = 28.0;
+const OVERFLOW_BUTTON_WIDTH: f32 = 28.0;
+const POPOVER_ROW_HEIGHT: f32 = 26.0;
+const TOOLTIP_DELAY: f32 = 0.5;
+
 /// Horizontal status bar with multiple status items
+///
+/// Items are placed in left, center and right sections. When they don't
+/// fit, the lowest priority ones move into a popover behind a "⋯" button.
 pub struct StatusBar {
     pub id: WidgetId,
     pub position: Vec2,
@@ -65,6 +194,14 @@ pub struct StatusBar {
     pub items: Vec<StatusItem>,
     pub background_color: Vec4,
     item_spacing: f32,
+    /// Laid out (item index, rect) pairs for visible items
+    slots: Vec<(usize, Rect)>,
+    /// Items that didn't fit, in bar order
+    overflow: Vec<usize>,
+    popover_open: bool,
+    hovered: Option<usize>,
+    hover_time: f32,
+    mouse_pos: Vec2,
 }
 
 impl StatusBar {
@@ -72,10 +209,16 @@ impl StatusBar {
         Self {
             id: WidgetId::new(),
             position: Vec2::ZERO,
-            size: Vec2::new(800.0, 28.0),
+            size: Vec2::new(800.0, STATUS_BAR_HEIGHT),
             items: Vec::new(),
             background_color: Vec4::new(0.05, 0.05, 0.08, 0.9),
             item_spacing: 24.0,
+            slots: Vec::new(),
+            overflow: Vec::new(),
+            popover_open: false,
+            hovered: None,
+            hover_time: 0.0,
+            mouse_pos: Vec2::ZERO,
         }
     }
     
@@ -84,13 +227,42 @@ impl StatusBar {
         self.items.push(item);
     }
     
+    /// Builder form of `add_item`
+    pub fn with_item(mut self, item: StatusItem) -> Self {
+        self.add_item(item);
+        self
+    }
+    
+    /// Remove a status item by ID
+    pub fn remove_item(&mut self, id: &str) -> Option<StatusItem> {
+        let index = self.items.iter().position(|i| i.id == id)?;
+        self.popover_open = false;
+        self.hovered = None;
+        Some(self.items.remove(index))
+    }
+    
+    /// Mutable access to a status item by ID
+    pub fn item_mut(&mut self, id: &str) -> Option<&mut StatusItem> {
+        self.items.iter_mut().find(|i| i.id == id)
+    }
+    
     /// Update a status item by ID
     pub fn update_item(&mut self, id: &str, value: &str) {
-        if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
+        if let Some(item) = self.item_mut(id) {
             item.set_value(value);
         }
     }
     
+    /// IDs of items currently moved into the overflow popover
+    pub fn overflowed(&self) -> impl Iterator<Item = &str> {
+        self.overflow.iter().map(|&i| self.items[i].id.as_str())
+    }
+    
+    /// Screen rect of a visible item
+    pub fn item_rect(&self, id: &str) -> Option<Rect> {
+        self.slots.iter().find(|(i, _)| self.items[*i].id == id).map(|(_, rect)| *rect)
+    }
+    
     /// Create a common status bar for dashboards
     pub fn dashboard_default() -> Self {
         let mut bar = Self::new();
@@ -101,6 +273,69 @@ impl StatusBar {
         bar.add_item(StatusItem::new("agents", "Agents", "0").with_icon("🤖"));
         bar
     }
+    
+    fn overflow_button_rect(&self) -> Rect {
+        Rect::new(
+            Vec2::new(self.position.x + self.size.x - OVERFLOW_BUTTON_WIDTH - 4.0, self.position.y),
+            Vec2::new(OVERFLOW_BUTTON_WIDTH, self.size.y),
+        )
+    }
+    
+    /// Popover listing overflowed items, opening above the bar
+    fn popover_rect(&self) -> Rect {
+        let width = self.overflow.iter().map(|&i| self.items[i].width()).fold(120.0, f32::max) + 24.0;
+        let height = self.overflow.len() as f32 * POPOVER_ROW_HEIGHT + 8.0;
+        let button = self.overflow_button_rect();
+        let x = (button.max.x - width).max(self.position.x);
+        let y = if self.position.y >= height { self.position.y - height - 4.0 } else { button.max.y + 4.0 };
+        Rect::new(Vec2::new(x, y), Vec2::new(width, height))
+    }
+    
+    fn popover_row_at(&self, point: Vec2) -> Option<usize> {
+        let rect = self.popover_rect();
+        if !rect.contains(point) {
+            return None;
+        }
+        let row = ((point.y - rect.min.y - 4.0) / POPOVER_ROW_HEIGHT).floor();
+        (row >= 0.0).then(|| self.overflow.get(row as usize).copied()).flatten()
+    }
+    
+    fn item_at(&self, point: Vec2) -> Option<usize> {
+        if self.popover_open {
+            if let Some(index) = self.popover_row_at(point) {
+                return Some(index);
+            }
+        }
+        self.slots.iter().find(|(_, rect)| rect.contains(point)).map(|(i, _)| *i)
+    }
+    
+    fn render_item(&self, renderer: &mut GlassRenderer, item: &StatusItem, origin: Vec2, hovered: bool) {
+        let theme = get_theme();
+        let mut x = origin.x;
+        
+        if hovered && item.on_click.is_some() {
+            renderer.draw_rounded_rect(
+                Vec2::new(x - 6.0, origin.y + 3.0),
+                Vec2::new(item.width() + 12.0, STATUS_BAR_HEIGHT - 6.0),
+                Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.15),
+                4.0
+            );
+        }
+        
+        // Icon
+        if let Some(icon) = &item.icon {
+            renderer.draw_text(icon, Vec2::new(x, origin.y + 5.0), 14.0, theme.text_secondary);
+            x += 20.0;
+        }
+        
+        // Label
+        renderer.draw_text(&item.label, Vec2::new(x, origin.y + 6.0), 12.0, theme.text_secondary);
+        x += item.label.chars().count() as f32 * 7.0 + 4.0;
+        
+        // Value
+        let value_color = item.color.unwrap_or(theme.text);
+        renderer.draw_text(&item.value, Vec2::new(x, origin.y + 6.0), 12.0, value_color);
+    }
 }
 
 impl Default for StatusBar {
@@ -112,15 +347,132 @@ impl Default for StatusBar {
 impl Widget for StatusBar {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
-        self.size = Vec2::new(max_size.x, 28.0);
+        self.size = Vec2::new(max_size.x, STATUS_BAR_HEIGHT);
+        
+        // Drop the lowest priority items (last added first) until the rest fit
+        let spacing = self.item_spacing;
+        let available = self.size.x - 24.0;
+        let mut visible = vec![true; self.items.len()];
+        let mut total = self.items.iter().map(|i| i.width() + spacing).sum::<f32>() - spacing;
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by_key(|&i| (self.items[i].priority, std::cmp::Reverse(i)));
+        for index in order {
+            if total <= available {
+                break;
+            }
+            if visible.iter().all(|v| *v) {
+                total += OVERFLOW_BUTTON_WIDTH + spacing;
+            }
+            visible[index] = false;
+            total -= self.items[index].width() + spacing;
+        }
+        self.overflow = (0..self.items.len()).filter(|&i| !visible[i]).collect();
+        if self.overflow.is_empty() {
+            self.popover_open = false;
+        }
+        
+        // Place each section
+        self.slots.clear();
+        let section_width = |section: StatusSection| -> f32 {
+            let widths: Vec<f32> = (0..self.items.len())
+                .filter(|&i| visible[i] && self.items[i].section == section)
+                .map(|i| self.items[i].width())
+                .collect();
+            widths.iter().sum::<f32>() + spacing * widths.len().saturating_sub(1) as f32
+        };
+        let right_end = if self.overflow.is_empty() {
+            self.position.x + self.size.x - 12.0
+        } else {
+            self.overflow_button_rect().min.x - spacing * 0.5
+        };
+        let starts = [
+            (StatusSection::Left, self.position.x + 12.0),
+            (StatusSection::Center, self.position.x + (self.size.x - section_width(StatusSection::Center)) * 0.5),
+            (StatusSection::Right, right_end - section_width(StatusSection::Right)),
+        ];
+        for (section, start) in starts {
+            let mut x = start;
+            for index in (0..self.items.len()).filter(|&i| visible[i] && self.items[i].section == section) {
+                let width = self.items[index].width();
+                self.slots.push((index, Rect::new(Vec2::new(x, self.position.y), Vec2::new(width, self.size.y))));
+                x += width + spacing;
+            }
+        }
+        
         self.size
     }
+    
+    /// Full width when bounded, otherwise the width it last had
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.biggest_or(Size::new(self.size.x, STATUS_BAR_HEIGHT));
+        let size = Size::new(size.width, constraints.constrain_height(STATUS_BAR_HEIGHT));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    /// Items are placed in absolute coordinates, so they are placed again
+    fn set_position(&mut self, position: Offset) {
+        let size = self.size;
+        self.layout(Vec2::new(position.x, position.y), size);
+        self.size = size;
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
-    fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
-        false
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        let winit::event::Event::WindowEvent { event, .. } = event else { return false };
+        let over_bar = Rect::new(self.position, self.size).contains(mouse_pos);
+        let over_popover = self.popover_open && self.popover_rect().contains(mouse_pos);
+        
+        match event {
+            winit::event::WindowEvent::CursorMoved { .. } => {
+                let hovered = self.item_at(mouse_pos);
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    self.hover_time = 0.0;
+                }
+                self.mouse_pos = mouse_pos;
+                over_bar || over_popover
+            }
+            winit::event::WindowEvent::MouseInput {
+                state: winit::event::ElementState::Pressed,
+                button: winit::event::MouseButton::Left,
+                ..
+            } => {
+                if !self.overflow.is_empty() && self.overflow_button_rect().contains(mouse_pos) {
+                    self.popover_open = !self.popover_open;
+                    return true;
+                }
+                let clicked = self.item_at(mouse_pos);
+                if let Some(callback) = clicked.and_then(|i| self.items[i].on_click.clone()) {
+                    callback();
+                }
+                let was_open = std::mem::take(&mut self.popover_open);
+                over_bar || over_popover || (was_open && clicked.is_some())
+            }
+            _ => false,
+        }
     }
 
-    fn update(&mut self, _dt: f32) {}
+    fn update(&mut self, dt: f32) {
+        for item in &mut self.items {
+            item.sync_binding();
+        }
+        if self.hovered.is_some() {
+            self.hover_time += dt;
+        }
+    }
+    
+    fn is_animating(&self) -> bool {
+        // Keep ticking until a pending tooltip shows
+        self.hovered.is_some_and(|i| self.items[i].tooltip.is_some()) && self.hover_time < TOOLTIP_DELAY
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
@@ -129,22 +481,44 @@ impl Widget for StatusBar {
         renderer.draw_rounded_rect(self.position, self.size, self.background_color, 4.0);
         
         // Items
-        let mut x = self.position.x + 12.0;
-        for item in &self.items {
-            // Icon
-            if let Some(icon) = &item.icon {
-                renderer.draw_text(icon, Vec2::new(x, self.position.y + 5.0), 14.0, theme.text_secondary);
-                x += 20.0;
-            }
-            
-            // Label
-            renderer.draw_text(&item.label, Vec2::new(x, self.position.y + 6.0), 12.0, theme.text_secondary);
-            x += item.label.len() as f32 * 7.0 + 4.0;
+        for (index, rect) in &self.slots {
+            self.render_item(renderer, &self.items[*index], rect.min, self.hovered == Some(*index));
+        }
+        
+        // Overflow button and popover
+        if !self.overflow.is_empty() {
+            let button = self.overflow_button_rect();
+            renderer.draw_text("⋯", button.min + Vec2::new(8.0, 5.0), 14.0, theme.text_secondary);
             
-            // Value
-            let value_color = item.color.unwrap_or(theme.text);
-            renderer.draw_text(&item.value, Vec2::new(x, self.position.y + 6.0), 12.0, value_color);
-            x += item.value.len() as f32 * 7.0 + self.item_spacing;
+            if self.popover_open {
+                let rect = self.popover_rect();
+                renderer.draw_overlay_rect(rect.min, rect.size(), Vec4::new(0.08, 0.08, 0.1, 0.97), 6.0);
+                for (row, &index) in self.overflow.iter().enumerate() {
+                    let item = &self.items[index];
+                    let origin = Vec2::new(rect.min.x + 12.0, rect.min.y + 4.0 + row as f32 * POPOVER_ROW_HEIGHT);
+                    if self.hovered == Some(index) {
+                        renderer.draw_overlay_rect(
+                            Vec2::new(rect.min.x + 4.0, origin.y),
+                            Vec2::new(rect.size().x - 8.0, POPOVER_ROW_HEIGHT),
+                            Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.2),
+                            4.0
+                        );
+                    }
+                    if let Some(icon) = &item.icon {
+                        renderer.draw_overlay_text(icon, origin + Vec2::new(0.0, 4.0), 14.0, theme.text_secondary);
+                    }
+                    renderer.draw_overlay_text(&item.label, origin + Vec2::new(20.0, 5.0), 12.0, theme.text_secondary);
+                    let value_x = 24.0 + item.label.chars().count() as f32 * 7.0;
+                    renderer.draw_overlay_text(&item.value, origin + Vec2::new(value_x, 5.0), 12.0, item.color.unwrap_or(theme.text));
+                }
+            }
+        }
+        
+        // Tooltip
+        if let Some(tooltip) = self.hovered.and_then(|i| self.items[i].tooltip.as_deref()) {
+            if self.hover_time >= TOOLTIP_DELAY {
+                renderer.draw_tooltip(tooltip, self.mouse_pos);
+            }
         }
     }
 }
@@ -195,6 +569,8 @@ pub struct Toast {
     // Animation
     pub slide_t: f32,
     pub fade_t: f32,
+    /// Animated stack slot, `None` until first placed
+    pub stack_pos: Option<f32>,
 }
 
 impl Toast {
@@ -210,6 +586,7 @@ impl Toast {
             dismissed: false,
             slide_t: 0.0,
             fade_t: 1.0,
+            stack_pos: None,
         }
     }
     
@@ -248,15 +625,49 @@ impl Toast {
 // TOAST CONTAINER
 // =============================================================================
 
+/// Screen corner or edge the toast stack is pinned to
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum ToastAnchor {
+    TopLeft,
+    TopCenter,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl ToastAnchor {
+    fn is_bottom(&self) -> bool {
+        matches!(self, ToastAnchor::BottomLeft | ToastAnchor::BottomCenter | ToastAnchor::BottomRight)
+    }
+    
+    /// Direction toasts slide in from
+    fn slide_direction(&self) -> Vec2 {
+        match self {
+            ToastAnchor::TopLeft | ToastAnchor::BottomLeft => Vec2::new(-1.0, 0.0),
+            ToastAnchor::TopRight | ToastAnchor::BottomRight => Vec2::new(1.0, 0.0),
+            ToastAnchor::TopCenter => Vec2::new(0.0, -1.0),
+            ToastAnchor::BottomCenter => Vec2::new(0.0, 1.0),
+        }
+    }
+}
+
 /// Container that manages and displays toast notifications
 pub struct ToastContainer {
     pub id: WidgetId,
+    /// Top-left of the first slot, recomputed from `anchor` on every layout
     pub position: Vec2,
     pub toasts: Vec<Toast>,
     pub max_visible: usize,
     pub toast_width: f32,
     pub toast_height: f32,
     pub spacing: f32,
+    pub anchor: ToastAnchor,
+    /// Distance from the anchored screen edges (x, y)
+    pub margin: Vec2,
+    /// Size of the area the stack is anchored in, from the last layout
+    area: Vec2,
 }
 
 impl ToastContainer {
@@ -269,6 +680,9 @@ impl ToastContainer {
             toast_width: 320.0,
             toast_height: 72.0,
             spacing: 8.0,
+            anchor: ToastAnchor::default(),
+            margin: Vec2::splat(16.0),
+            area: Vec2::ZERO,
         }
     }
     
@@ -302,11 +716,70 @@ impl ToastContainer {
         self.toasts.clear();
     }
     
+    /// Pin the stack to a screen corner or edge
+    pub fn with_anchor(mut self, anchor: ToastAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+    
+    /// Distance from the anchored edges
+    pub fn with_margin(mut self, margin: Vec2) -> Self {
+        self.margin = margin;
+        self
+    }
+    
+    pub fn with_max_visible(mut self, max_visible: usize) -> Self {
+        self.max_visible = max_visible.max(1);
+        self
+    }
+    
     /// Position at top-right of screen
+    /// 
+    /// `screen_width` only places the stack until the first layout, which
+    /// re-anchors it to the real window size.
     pub fn position_top_right(mut self, screen_width: f32, margin: f32) -> Self {
+        self.anchor = ToastAnchor::TopRight;
+        self.margin = Vec2::splat(margin);
         self.position = Vec2::new(screen_width - self.toast_width - margin, margin);
         self
     }
+    
+    /// Toasts beyond `max_visible` waiting for a free slot
+    pub fn overflow_count(&self) -> usize {
+        self.toasts.len().saturating_sub(self.max_visible)
+    }
+    
+    /// Screen position of slot `slot` (fractional while animating)
+    fn slot_position(&self, slot: f32) -> Vec2 {
+        let step = (self.toast_height + self.spacing) * slot;
+        if self.anchor.is_bottom() {
+            Vec2::new(self.position.x, self.position.y - step)
+        } else {
+            Vec2::new(self.position.x, self.position.y + step)
+        }
+    }
+    
+    /// Current rect of the `index`th toast
+    fn toast_rect(&self, index: usize) -> (Vec2, Vec2) {
+        let toast = &self.toasts[index];
+        let pos = self.slot_position(toast.stack_pos.unwrap_or(index as f32));
+        (pos, Vec2::new(self.toast_width, self.toast_height))
+    }
+    
+    /// Place the first slot against the anchored edges of `area` at `origin`
+    fn anchor_at(&mut self, origin: Vec2) {
+        let x = match self.anchor {
+            ToastAnchor::TopLeft | ToastAnchor::BottomLeft => origin.x + self.margin.x,
+            ToastAnchor::TopCenter | ToastAnchor::BottomCenter => origin.x + (self.area.x - self.toast_width) * 0.5,
+            ToastAnchor::TopRight | ToastAnchor::BottomRight => origin.x + self.area.x - self.toast_width - self.margin.x,
+        };
+        let y = if self.anchor.is_bottom() {
+            origin.y + self.area.y - self.toast_height - self.margin.y
+        } else {
+            origin.y + self.margin.y
+        };
+        self.position = Vec2::new(x, y);
+    }
 }
 
 impl Default for ToastContainer {
@@ -316,11 +789,25 @@ impl Default for ToastContainer {
 }
 
 impl Widget for ToastContainer {
-    fn layout(&mut self, origin: Vec2, _max_size: Vec2) -> Vec2 {
-        if self.position == Vec2::ZERO {
-            self.position = origin;
-        }
-        Vec2::new(self.toast_width, self.toasts.len() as f32 * (self.toast_height + self.spacing))
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        // Re-anchor on every layout so the stack follows window resizes
+        self.area = max_size;
+        self.anchor_at(origin);
+        Vec2::ZERO // Floats above the layout
+    }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.area = Vec2::new(constraints.max_width, constraints.max_height);
+        constraints.smallest()
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.anchor_at(Vec2::new(position.x, position.y));
+    }
+    
+    fn is_animating(&self) -> bool {
+        // Timers count down while any toast is showing
+        !self.toasts.is_empty()
     }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
@@ -332,16 +819,11 @@ impl Widget for ToastContainer {
                 ..
             }, .. 
         } = event {
-            for (i, toast) in self.toasts.iter_mut().enumerate() {
-                if i >= self.max_visible { break; }
-                
-                let y = self.position.y + i as f32 * (self.toast_height + self.spacing);
-                let toast_pos = Vec2::new(self.position.x, y);
-                let toast_size = Vec2::new(self.toast_width, self.toast_height);
-                
+            for i in 0..self.toasts.len().min(self.max_visible) {
+                let (toast_pos, toast_size) = self.toast_rect(i);
                 if mouse_pos.x >= toast_pos.x && mouse_pos.x <= toast_pos.x + toast_size.x &&
                    mouse_pos.y >= toast_pos.y && mouse_pos.y <= toast_pos.y + toast_size.y {
-                    toast.dismiss();
+                    self.toasts[i].dismiss();
                     return true;
                 }
             }
@@ -351,10 +833,20 @@ impl Widget for ToastContainer {
 
     fn update(&mut self, dt: f32) {
         // Update toast timers and animations
-        for toast in &mut self.toasts {
+        for (i, toast) in self.toasts.iter_mut().enumerate() {
+            // Queued toasts wait (and keep their full time) until they get a slot
+            if i >= self.max_visible {
+                continue;
+            }
+            
             // Slide in animation
             toast.slide_t = (toast.slide_t + dt * 8.0).min(1.0);
             
+            // Ease towards the slot, so the stack closes up when one expires
+            let slot = i as f32;
+            let current = toast.stack_pos.unwrap_or(slot);
+            toast.stack_pos = Some(current + (slot - current) * (dt * 12.0).min(1.0));
+            
             // Count down
             toast.time_remaining -= dt;
             
@@ -374,12 +866,10 @@ impl Widget for ToastContainer {
         for (i, toast) in self.toasts.iter().enumerate() {
             if i >= self.max_visible { break; }
             
-            let y = self.position.y + i as f32 * (self.toast_height + self.spacing);
-            
             // Slide animation offset
-            let slide_offset = (1.0 - toast.slide_t) * self.toast_width;
-            let toast_pos = Vec2::new(self.position.x + slide_offset, y);
-            let toast_size = Vec2::new(self.toast_width, self.toast_height);
+            let slide = self.anchor.slide_direction() * (1.0 - toast.slide_t);
+            let (pos, toast_size) = self.toast_rect(i);
+            let toast_pos = pos + slide * Vec2::new(self.toast_width, self.toast_height);
             
             // Background with fade
             let mut bg_color = toast.toast_type.color();
@@ -408,9 +898,18 @@ impl Widget for ToastContainer {
             let progress = toast.time_remaining / toast.duration;
             let bar_width = (toast_size.x - 24.0) * progress;
             let bar_pos = toast_pos + Vec2::new(12.0, toast_size.y - 6.0);
-            let mut bar_color = Vec4::new(1.0, 1.0, 1.0, 0.3 * toast.fade_t);
+            let bar_color = Vec4::new(1.0, 1.0, 1.0, 0.3 * toast.fade_t);
             renderer.draw_rounded_rect(bar_pos, Vec2::new(bar_width, 3.0), bar_color, 2.0);
         }
+        
+        // "+N more" pill in the slot after the last visible toast
+        let overflow = self.overflow_count();
+        if overflow > 0 {
+            let slot = self.slot_position(self.max_visible as f32);
+            let pill_pos = slot + Vec2::new(self.toast_width - 88.0, 0.0);
+            renderer.draw_rounded_rect(pill_pos, Vec2::new(88.0, 24.0), Vec4::new(0.1, 0.1, 0.14, 0.85), 12.0);
+            renderer.draw_text_fmt(format_args!("+{} more", overflow), pill_pos + Vec2::new(14.0, 5.0), 12.0, theme.text_secondary);
+        }
     }
 }
 
@@ -427,9 +926,14 @@ pub struct AgentCard {
     pub model: String,
     pub state: AgentState,
     pub message_count: usize,
+    /// Avatar picture (the robot glyph is shown without one)
+    pub avatar: Option<TextureId>,
     // Animation
     thinking_dots: u8,
     thinking_timer: f32,
+    /// State text (with thinking dots) and message count, as last drawn
+    state_label: LabelCache<(AgentState, u8)>,
+    count_label: LabelCache<usize>,
 }
 
 impl AgentCard {
@@ -442,8 +946,11 @@ impl AgentCard {
             model: model.to_string(),
             state: AgentState::Idle,
             message_count: 0,
+            avatar: None,
             thinking_dots: 0,
             thinking_timer: 0.0,
+            state_label: LabelCache::new(),
+            count_label: LabelCache::new(),
         }
     }
     
@@ -455,6 +962,11 @@ impl AgentCard {
         self.message_count = count;
     }
     
+    pub fn with_avatar(mut self, texture: TextureId) -> Self {
+        self.avatar = Some(texture);
+        self
+    }
+    
     fn state_color(&self) -> Vec4 {
         match self.state {
             AgentState::Idle => Vec4::new(0.5, 0.5, 0.5, 1.0),
@@ -479,6 +991,24 @@ impl Widget for AgentCard {
         self.position = origin;
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, _event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         false
@@ -513,8 +1043,12 @@ impl Widget for AgentCard {
         
         // Avatar circle
         let avatar_pos = self.position + Vec2::new(16.0, 16.0);
-        renderer.draw_rounded_rect(avatar_pos, Vec2::splat(40.0), theme.primary, 20.0);
-        renderer.draw_text("🤖", avatar_pos + Vec2::new(10.0, 8.0), 20.0, theme.text);
+        if let Some(avatar) = self.avatar {
+            renderer.draw_image_instance(avatar, ImageInstance::new(avatar_pos, Vec2::splat(40.0), Vec4::ONE).with_radius(20.0));
+        } else {
+            renderer.draw_rounded_rect(avatar_pos, Vec2::splat(40.0), theme.primary, 20.0);
+            renderer.draw_text("🤖", avatar_pos + Vec2::new(10.0, 8.0), 20.0, theme.text);
+        }
         
         // Name
         renderer.draw_text(&self.name, self.position + Vec2::new(70.0, 16.0), 16.0, theme.text);
@@ -523,16 +1057,17 @@ impl Widget for AgentCard {
         renderer.draw_text(&self.model, self.position + Vec2::new(70.0, 36.0), 12.0, theme.text_secondary);
         
         // State with animated dots
-        let mut state_text = self.state_text().to_string();
-        if self.state == AgentState::Thinking {
-            for _ in 0..self.thinking_dots {
-                state_text.push('.');
-            }
-        }
+        let dots = if self.state == AgentState::Thinking { self.thinking_dots } else { 0 };
+        let state_text = self.state_label.get((self.state, dots), |_, text| {
+            text.push_str(self.state_text());
+            text.extend(std::iter::repeat_n('.', dots as usize));
+        });
         renderer.draw_text(&state_text, self.position + Vec2::new(70.0, 56.0), 12.0, self.state_color());
         
         // Message count
-        let count_text = format!("{} msgs", self.message_count);
+        let count_text = self.count_label.get(self.message_count, |count, text| {
+            let _ = write!(text, "{} msgs", count);
+        });
         renderer.draw_text(&count_text, self.position + Vec2::new(self.size.x - 60.0, 76.0), 11.0, theme.text_secondary);
     }
 }
@@ -554,6 +1089,74 @@ mod tests {
         assert_eq!(item.value, "45%");
     }
     
+    fn click(bar: &mut StatusBar, pos: Vec2) -> bool {
+        bar.handle_event(&winit::event::Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: winit::event::WindowEvent::MouseInput {
+                device_id: unsafe { winit::event::DeviceId::dummy() },
+                state: winit::event::ElementState::Pressed,
+                button: winit::event::MouseButton::Left,
+            },
+        }, pos)
+    }
+    
+    #[test]
+    fn test_status_sections() {
+        let mut bar = StatusBar::new()
+            .with_item(StatusItem::new("mode", "Mode", "Edit"))
+            .with_item(StatusItem::new("file", "", "main.rs").with_section(StatusSection::Center))
+            .with_item(StatusItem::new("line", "Ln", "12").with_section(StatusSection::Right));
+        bar.layout(Vec2::new(0.0, 572.0), Vec2::new(800.0, 600.0));
+        
+        assert_eq!(bar.item_rect("mode").unwrap().min, Vec2::new(12.0, 572.0));
+        let center = bar.item_rect("file").unwrap();
+        assert!(((center.min.x + center.max.x) * 0.5 - 400.0).abs() < 0.01);
+        assert_eq!(bar.item_rect("line").unwrap().max.x, 788.0);
+    }
+    
+    #[test]
+    fn test_status_overflow_by_priority() {
+        let clicks = Rc::new(std::cell::Cell::new(0));
+        let counter = clicks.clone();
+        let mut bar = StatusBar::new()
+            .with_item(StatusItem::new("a", "Alpha", "1").with_priority(2))
+            .with_item(StatusItem::new("b", "Beta", "2").on_click(move || counter.set(counter.get() + 1)))
+            .with_item(StatusItem::new("c", "Gamma", "3").with_priority(1));
+        bar.layout(Vec2::ZERO, Vec2::new(800.0, 600.0));
+        assert_eq!(bar.overflowed().count(), 0);
+        
+        // Narrow: "b" has the lowest priority and moves into the popover
+        bar.layout(Vec2::ZERO, Vec2::new(200.0, 600.0));
+        assert_eq!(bar.overflowed().collect::<Vec<_>>(), vec!["b"]);
+        assert!(bar.item_rect("b").is_none());
+        
+        // Open the popover and click its row
+        let button = bar.overflow_button_rect();
+        assert!(click(&mut bar, (button.min + button.max) * 0.5));
+        assert!(bar.popover_open);
+        let popover = bar.popover_rect();
+        click(&mut bar, popover.min + Vec2::new(20.0, 4.0 + POPOVER_ROW_HEIGHT * 0.5));
+        assert_eq!(clicks.get(), 1);
+        assert!(!bar.popover_open);
+    }
+    
+    #[test]
+    fn test_status_item_bindings() {
+        let state = State::new("idle".to_string());
+        let reactive = crate::reactive::Reactive::new("v1".to_string());
+        let mut bar = StatusBar::new()
+            .with_item(StatusItem::new("state", "State", "").bind_state(&state))
+            .with_item(StatusItem::new("version", "Ver", "").bind_text(TextSource::Reactive(reactive.clone())));
+        assert_eq!(bar.items[0].value, "idle");
+        assert_eq!(bar.items[1].value, "v1");
+        
+        state.set("busy".to_string());
+        reactive.set("v2".to_string());
+        bar.update(0.016);
+        assert_eq!(bar.items[0].value, "busy");
+        assert_eq!(bar.items[1].value, "v2");
+    }
+    
     #[test]
     fn test_toast() {
         let mut container = ToastContainer::new();
@@ -563,6 +1166,56 @@ mod tests {
         assert_eq!(container.toasts.len(), 2);
     }
     
+    #[test]
+    fn test_toast_anchor_reflows_on_resize() {
+        let mut container = ToastContainer::new().with_anchor(ToastAnchor::BottomRight).with_margin(Vec2::new(10.0, 20.0));
+        container.layout(Vec2::ZERO, Vec2::new(800.0, 600.0));
+        assert_eq!(container.position, Vec2::new(800.0 - 320.0 - 10.0, 600.0 - 72.0 - 20.0));
+        
+        container.layout(Vec2::ZERO, Vec2::new(1200.0, 900.0));
+        assert_eq!(container.position, Vec2::new(1200.0 - 320.0 - 10.0, 900.0 - 72.0 - 20.0));
+        // Bottom anchors stack upwards
+        assert!(container.slot_position(1.0).y < container.position.y);
+    }
+    
+    #[test]
+    fn test_constraint_layout_places_items_and_toasts() {
+        let mut bar = StatusBar::new()
+            .with_item(StatusItem::new("mode", "Mode", "Edit"))
+            .with_item(StatusItem::new("line", "Ln", "12").with_section(StatusSection::Right));
+        let size = bar.layout_with_constraints(BoxConstraints::new(0.0, 800.0, 0.0, 600.0));
+        assert_eq!(size, Size::new(800.0, STATUS_BAR_HEIGHT));
+        bar.set_position(Offset::new(0.0, 572.0));
+        assert_eq!(bar.item_rect("mode").unwrap().min, Vec2::new(12.0, 572.0));
+        assert_eq!(bar.item_rect("line").unwrap().max.x, 788.0);
+        
+        // Toasts anchor in the area offered, wherever it is placed
+        let mut container = ToastContainer::new().with_anchor(ToastAnchor::BottomRight).with_margin(Vec2::new(10.0, 20.0));
+        assert_eq!(container.layout_with_constraints(BoxConstraints::loose(Size::new(800.0, 600.0))), Size::ZERO);
+        container.set_position(Offset::new(100.0, 50.0));
+        assert_eq!(container.position, Vec2::new(100.0 + 800.0 - 320.0 - 10.0, 50.0 + 600.0 - 72.0 - 20.0));
+    }
+    
+    #[test]
+    fn test_toast_overflow_and_stacking() {
+        let mut container = ToastContainer::new().with_max_visible(2);
+        container.push(Toast::info("A", "").with_duration(1.0));
+        container.push(Toast::info("B", "").with_duration(5.0));
+        container.push(Toast::info("C", "").with_duration(5.0));
+        assert_eq!(container.overflow_count(), 1);
+        
+        // Queued toasts don't count down
+        container.update(1.1);
+        assert_eq!(container.toasts.len(), 2);
+        assert_eq!(container.toasts[1].time_remaining, 5.0);
+        assert_eq!(container.overflow_count(), 0);
+        
+        // "B" moved from slot 1 to slot 0 and eases up rather than jumping
+        container.update(0.02);
+        let pos = container.toasts[0].stack_pos.unwrap();
+        assert!(pos > 0.0 && pos < 1.0);
+    }
+    
     #[test]
     fn test_agent_card() {
         let mut card = AgentCard::new("Assistant", "phi3");
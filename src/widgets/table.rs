@@ -6,10 +6,14 @@
 //! - Scrolling
 //! - Cell rendering
 
+use std::borrow::Cow;
 use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
 use crate::widgets::core::{Widget, get_theme};
+use crate::widgets::controls::{CheckState, draw_check_box};
+use crate::layout::{BoxConstraints, Offset, Size};
+use crate::text::TextOverflow;
 
 // =============================================================================
 // COLUMN
@@ -61,6 +65,8 @@ pub enum CellValue {
     Number(f64),
     Bool(bool),
     Badge(String, Vec4),
+    /// Score drawn as stars: the value and the number of stars
+    Rating(f32, usize),
 }
 
 impl CellValue {
@@ -72,12 +78,14 @@ impl CellValue {
         Self::Number(n)
     }
     
-    pub fn display(&self) -> String {
+    /// Display text; borrows where possible so rendering doesn't allocate
+    pub fn display(&self) -> Cow<'_, str> {
         match self {
-            CellValue::Text(s) => s.clone(),
-            CellValue::Number(n) => format!("{:.2}", n),
-            CellValue::Bool(b) => if *b { "✓".to_string() } else { "✗".to_string() },
-            CellValue::Badge(s, _) => s.clone(),
+            CellValue::Text(s) => Cow::Borrowed(s),
+            CellValue::Number(n) => Cow::Owned(format!("{:.2}", n)),
+            CellValue::Bool(b) => Cow::Borrowed(if *b { "✓" } else { "✗" }),
+            CellValue::Badge(s, _) => Cow::Borrowed(s),
+            CellValue::Rating(value, count) => Cow::Owned(format!("{:.1}/{}", value, count)),
         }
     }
 }
@@ -87,6 +95,8 @@ impl CellValue {
 pub struct GridRow {
     pub id: String,
     pub cells: Vec<CellValue>,
+    /// Checked in a multi-select table
+    pub selected: bool,
 }
 
 impl GridRow {
@@ -94,10 +104,22 @@ impl GridRow {
         Self {
             id: id.to_string(),
             cells,
+            selected: false,
         }
     }
+    
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
 }
 
+/// Width of the leading checkbox column of a multi-select table
+const CHECK_COLUMN_WIDTH: f32 = 36.0;
+
+/// Side of the checkboxes in that column
+const CHECK_BOX_SIZE: f32 = 16.0;
+
 // =============================================================================
 // DATA TABLE
 // =============================================================================
@@ -107,6 +129,8 @@ pub struct DataTable {
     pub id: WidgetId,
     pub position: Vec2,
     pub size: Vec2,
+    /// Intrinsic size used by the last layout pass
+    layout_intrinsic: Option<Size>,
     pub columns: Vec<GridColumn>,
     pub rows: Vec<GridRow>,
     pub selected_row: Option<usize>,
@@ -115,6 +139,11 @@ pub struct DataTable {
     pub header_height: f32,
     pub scroll_offset: f32,
     pub striped: bool,
+    /// Lead each row with a checkbox, and the header with a "select all"
+    /// box over them
+    pub multi_select: bool,
+    /// How header and cell text wider than its column is cut
+    pub overflow: TextOverflow,
     pub on_row_select: Option<Box<dyn FnMut(usize, &str)>>,
 }
 
@@ -124,6 +153,7 @@ impl DataTable {
             id: WidgetId::new(),
             position: Vec2::ZERO,
             size: Vec2::new(500.0, 300.0),
+            layout_intrinsic: None,
             columns: Vec::new(),
             rows: Vec::new(),
             selected_row: None,
@@ -132,6 +162,8 @@ impl DataTable {
             header_height: 36.0,
             scroll_offset: 0.0,
             striped: true,
+            multi_select: false,
+            overflow: TextOverflow::Ellipsis,
             on_row_select: None,
         }
     }
@@ -158,6 +190,59 @@ impl DataTable {
         self
     }
     
+    /// Set how text wider than its column is cut
+    pub fn with_overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+    
+    /// Show a checkbox column for selecting several rows
+    pub fn with_multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+    
+    /// State for the "select all" header box over the rows' `selected`
+    pub fn select_all_state(&self) -> CheckState {
+        CheckState::from_counts(self.rows.iter().filter(|row| row.selected).count(), self.rows.len())
+    }
+    
+    /// Mark every row selected or not, as a "select all" box click does
+    pub fn set_all_selected(&mut self, selected: bool) {
+        for row in &mut self.rows {
+            row.selected = selected;
+        }
+    }
+    
+    /// Ids of the checked rows
+    pub fn selected_ids(&self) -> impl Iterator<Item = &str> {
+        self.rows.iter().filter(|row| row.selected).map(|row| row.id.as_str())
+    }
+    
+    /// Width of the checkbox column, 0 unless `multi_select`
+    fn check_column_width(&self) -> f32 {
+        if self.multi_select { CHECK_COLUMN_WIDTH } else { 0.0 }
+    }
+    
+    /// Size that fits every column and row
+    fn content_size(&self) -> Size {
+        Size::new(
+            self.check_column_width() + self.columns.iter().map(|c| c.width).sum::<f32>(),
+            self.header_height + self.rows.len() as f32 * self.row_height,
+        )
+    }
+    
+    /// Checkbox within a header or row cell starting at `y`
+    fn check_box_position(&self, y: f32, height: f32) -> Vec2 {
+        Vec2::new(self.position.x + (CHECK_COLUMN_WIDTH - CHECK_BOX_SIZE) * 0.5, y + (height - CHECK_BOX_SIZE) * 0.5)
+    }
+    
+    /// Set callback for row clicks (row index and row id)
+    pub fn on_row_select(mut self, callback: impl FnMut(usize, &str) + 'static) -> Self {
+        self.on_row_select = Some(Box::new(callback));
+        self
+    }
+    
     /// Sort by column
     pub fn sort_by(&mut self, column_id: &str) {
         if let Some(col_idx) = self.columns.iter().position(|c| c.id == column_id) {
@@ -187,6 +272,9 @@ impl DataTable {
                     (Some(CellValue::Number(na)), Some(CellValue::Number(nb))) => {
                         na.partial_cmp(nb).unwrap_or(std::cmp::Ordering::Equal)
                     },
+                    (Some(CellValue::Rating(ra, _)), Some(CellValue::Rating(rb, _))) => {
+                        ra.partial_cmp(rb).unwrap_or(std::cmp::Ordering::Equal)
+                    },
                     (Some(a), Some(b)) => a.display().cmp(&b.display()),
                     _ => std::cmp::Ordering::Equal,
                 };
@@ -208,6 +296,15 @@ impl DataTable {
         if row < self.rows.len() { Some(row) } else { None }
     }
     
+    /// Indices of rows at least partly inside the visible area
+    pub fn visible_rows(&self) -> std::ops::Range<usize> {
+        let row_height = self.row_height.max(1.0);
+        let viewport = (self.size.y - self.header_height).max(0.0);
+        let first = (self.scroll_offset / row_height).floor().max(0.0) as usize;
+        let last = ((self.scroll_offset + viewport) / row_height).ceil().max(0.0) as usize;
+        first.min(self.rows.len())..last.min(self.rows.len())
+    }
+    
     /// Create sample data
     pub fn sample() -> Self {
         let mut table = Self::new();
@@ -251,8 +348,34 @@ impl Widget for DataTable {
     fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
         self.position = origin;
         self.size = max_size;
+        self.layout_intrinsic = Some(self.content_size());
         self.size
     }
+    
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        // Fills the space it is given; unbounded, it fits its columns and rows
+        let content = self.content_size();
+        let size = constraints.biggest_or(content);
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_intrinsic = Some(content);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    fn needs_layout(&self) -> bool {
+        self.layout_intrinsic != Some(self.content_size())
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Update hover
@@ -270,9 +393,16 @@ impl Widget for DataTable {
                 ..
             }, .. 
         } = event {
-            // Check header click (sort)
+            let in_check_column = mouse_pos.x >= self.position.x && mouse_pos.x < self.position.x + self.check_column_width();
+            
+            // Check header click (select all, or sort)
             if mouse_pos.y >= self.position.y && mouse_pos.y <= self.position.y + self.header_height {
-                let mut x = self.position.x;
+                if in_check_column {
+                    let checked = self.select_all_state().toggled() == CheckState::Checked;
+                    self.set_all_selected(checked);
+                    return true;
+                }
+                let mut x = self.position.x + self.check_column_width();
                 for col in &self.columns {
                     if mouse_pos.x >= x && mouse_pos.x <= x + col.width {
                         self.sort_by(&col.id.clone());
@@ -284,6 +414,10 @@ impl Widget for DataTable {
             
             // Check row click
             if let Some(row) = self.hovered_row {
+                if in_check_column {
+                    self.rows[row].selected = !self.rows[row].selected;
+                    return true;
+                }
                 self.selected_row = Some(row);
                 if let Some(callback) = &mut self.on_row_select {
                     callback(row, &self.rows[row].id);
@@ -325,10 +459,17 @@ impl Widget for DataTable {
             8.0
         );
         
-        let mut x = self.position.x;
+        if self.multi_select {
+            let position = self.check_box_position(self.position.y, self.header_height);
+            let state = self.select_all_state();
+            let t = if state == CheckState::Unchecked { 0.0 } else { 1.0 };
+            draw_check_box(renderer, position, Vec2::splat(CHECK_BOX_SIZE), state, t, false);
+        }
+        
+        let mut x = self.position.x + self.check_column_width();
         for col in &self.columns {
             // Header text
-            renderer.draw_text(&col.label, Vec2::new(x + 12.0, self.position.y + 10.0), 13.0, theme.text);
+            renderer.draw_text_overflow(&col.label, Vec2::new(x + 12.0, self.position.y + 10.0), 13.0, theme.text, col.width - 36.0, self.overflow);
             
             // Sort indicator
             if col.sortable {
@@ -347,14 +488,10 @@ impl Widget for DataTable {
         
         // Rows
         let content_y = self.position.y + self.header_height;
-        for (i, row) in self.rows.iter().enumerate() {
+        let visible = self.visible_rows();
+        for (i, row) in self.rows.iter().enumerate().skip(visible.start).take(visible.len()) {
             let row_y = content_y + i as f32 * self.row_height - self.scroll_offset;
             
-            // Skip if outside visible area
-            if row_y + self.row_height < content_y || row_y > self.position.y + self.size.y {
-                continue;
-            }
-            
             // Striped background
             if self.striped && i % 2 == 1 {
                 renderer.draw_rounded_rect(
@@ -366,7 +503,7 @@ impl Widget for DataTable {
             }
             
             // Selection
-            if self.selected_row == Some(i) {
+            if self.selected_row == Some(i) || row.selected {
                 renderer.draw_rounded_rect(
                     Vec2::new(self.position.x + 4.0, row_y),
                     Vec2::new(self.size.x - 8.0, self.row_height),
@@ -382,8 +519,14 @@ impl Widget for DataTable {
                 );
             }
             
+            if self.multi_select {
+                let position = self.check_box_position(row_y, self.row_height);
+                let state = CheckState::from_bool(row.selected);
+                draw_check_box(renderer, position, Vec2::splat(CHECK_BOX_SIZE), state, if row.selected { 1.0 } else { 0.0 }, false);
+            }
+            
             // Cells
-            let mut cell_x = self.position.x;
+            let mut cell_x = self.position.x + self.check_column_width();
             for (j, cell) in row.cells.iter().enumerate() {
                 let col_width = self.columns.get(j).map(|c| c.width).unwrap_or(100.0);
                 
@@ -404,8 +547,11 @@ impl Widget for DataTable {
                         let color = if *b { Vec4::new(0.3, 0.8, 0.4, 1.0) } else { Vec4::new(0.9, 0.3, 0.3, 1.0) };
                         renderer.draw_text(icon, Vec2::new(cell_x + 12.0, row_y + 8.0), 14.0, color);
                     },
+                    CellValue::Rating(value, count) => {
+                        super::premium::draw_stars(renderer, Vec2::new(cell_x + 12.0, row_y + 9.0), *value, *count, 14.0, 2.0, Vec4::new(1.0, 0.78, 0.2, 1.0));
+                    },
                     _ => {
-                        renderer.draw_text(&cell.display(), Vec2::new(cell_x + 12.0, row_y + 8.0), 12.0, theme.text_secondary);
+                        renderer.draw_text_overflow(&cell.display(), Vec2::new(cell_x + 12.0, row_y + 8.0), 12.0, theme.text_secondary, col_width - 24.0, self.overflow);
                     }
                 }
                 
@@ -440,4 +586,55 @@ mod tests {
             assert_eq!(*n, 62.0);  // Lowest first
         }
     }
+    
+    #[test]
+    fn test_visible_rows() {
+        let mut table = DataTable::new();
+        for i in 0..100 {
+            table.add_row(GridRow::new(&i.to_string(), vec![CellValue::number(i as f64)]));
+        }
+        table.size = Vec2::new(500.0, 36.0 + 32.0 * 5.0);
+        assert_eq!(table.visible_rows(), 0..5);
+        
+        table.scroll_offset = 48.0;
+        assert_eq!(table.visible_rows(), 1..7);
+        
+        table.scroll_offset = 32.0 * 98.0;
+        assert_eq!(table.visible_rows(), 98..100);
+    }
+    
+    #[test]
+    fn test_multi_select() {
+        let click = |table: &mut DataTable, pos: Vec2| {
+            let event = winit::event::Event::WindowEvent {
+                window_id: unsafe { winit::window::WindowId::dummy() },
+                event: winit::event::WindowEvent::MouseInput {
+                    device_id: unsafe { winit::event::DeviceId::dummy() },
+                    state: winit::event::ElementState::Pressed,
+                    button: winit::event::MouseButton::Left,
+                },
+            };
+            table.handle_event(&event, pos)
+        };
+        let mut table = DataTable::sample().with_multi_select(true);
+        table.layout(Vec2::ZERO, Vec2::new(600.0, 300.0));
+        assert_eq!(table.select_all_state(), CheckState::Unchecked);
+        
+        // A row's box checks it without moving the clicked-row selection
+        assert!(click(&mut table, Vec2::new(18.0, 36.0 + 32.0 + 16.0)));
+        assert_eq!(table.selected_ids().collect::<Vec<_>>(), vec!["2"]);
+        assert_eq!(table.selected_row, None);
+        assert_eq!(table.select_all_state(), CheckState::Indeterminate);
+        
+        // The mixed header box checks every row, then clears them
+        assert!(click(&mut table, Vec2::new(18.0, 18.0)));
+        assert_eq!(table.select_all_state(), CheckState::Checked);
+        assert!(click(&mut table, Vec2::new(18.0, 18.0)));
+        assert_eq!(table.selected_ids().count(), 0);
+        
+        // Columns start after the box column, so header clicks still sort
+        assert!(click(&mut table, Vec2::new(36.0 + 150.0 + 100.0 + 40.0, 18.0)));
+        assert_eq!(table.columns[2].sort_direction, SortDirection::Ascending);
+        assert_eq!(table.layout_with_constraints(BoxConstraints::loose(Size::new(f32::INFINITY, f32::INFINITY))).width, 36.0 + 450.0);
+    }
 }
@@ -10,6 +10,7 @@ use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
 use crate::widgets::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, Offset, Size};
 
 // =============================================================================
 // TAB
@@ -105,6 +106,18 @@ impl TabView {
         }
     }
     
+    /// Set callback for tab switches (tab index and tab id)
+    pub fn on_tab_change(mut self, callback: impl FnMut(usize, &str) + 'static) -> Self {
+        self.on_tab_change = Some(Box::new(callback));
+        self
+    }
+    
+    /// Set callback for tab close buttons (tab index and tab id)
+    pub fn on_tab_close(mut self, callback: impl FnMut(usize, &str) + 'static) -> Self {
+        self.on_tab_close = Some(Box::new(callback));
+        self
+    }
+    
     /// Add a tab
     pub fn add_tab(&mut self, tab: Tab) {
         self.tabs.push(tab);
@@ -182,6 +195,26 @@ impl Widget for TabView {
         self.update_indicator_target();
         self.size
     }
+    
+    /// Fills the space it is given; unbounded, it keeps its current size
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.biggest_or(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        self.update_indicator_target();
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         let tab_w = self.tab_width();
@@ -0,0 +1,429 @@
+//! GlassUI Tag Input
+//!
+//! `TagInput` turns what is typed into removable chips (labels on a task,
+//! filters on a timeline). It suggests from a fixed list through an
+//! `AutocompleteInput`, skips duplicates and can be bound to a
+//! `Reactive<Vec<String>>` shared with the rest of the app.
+
+use glam::{Vec2, Vec4};
+use winit::event::{ElementState, MouseButton};
+use crate::renderer::GlassRenderer;
+use super::core::{Widget, get_theme, render_faded};
+use super::autocomplete::AutocompleteInput;
+use crate::layout::{BoxConstraints, Offset, Rect, Size};
+use crate::focus::Focusable;
+use crate::reactive::Reactive;
+
+/// Height of a chip, and the gap between chips and below the field
+const CHIP_HEIGHT: f32 = 26.0;
+const CHIP_GAP: f32 = 6.0;
+
+/// Space at the right of a chip for its remove (×) button
+const CHIP_CLOSE_WIDTH: f32 = 20.0;
+
+/// Seconds an existing chip flashes when its tag is typed again
+const DUPLICATE_FLASH: f32 = 0.6;
+
+type TagsCallback = Box<dyn FnMut(&[String])>;
+
+// =============================================================================
+// TAG INPUT
+// =============================================================================
+
+/// Text field that collects tags as chips below itself
+///
+/// Enter (or a comma) adds the typed text as a tag, or the highlighted
+/// suggestion while the popup is open; Backspace in an empty field removes
+/// the last tag and a chip's × removes that one. Tags already present
+/// (ignoring case) aren't added again or suggested; the existing chip
+/// flashes instead.
+pub struct TagInput {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub input: AutocompleteInput,
+    pub tags: Vec<String>,
+    /// Chip whose × is under the mouse
+    pub hovered_close: Option<usize>,
+    /// Called with all tags after one is added or removed
+    pub on_change: Option<TagsCallback>,
+    /// Chip flashing as a duplicate, and the time left
+    flash: Option<(usize, f32)>,
+    /// Shared tags, and the version last read from or written to them
+    binding: Option<(Reactive<Vec<String>>, u64)>,
+    /// Field and chip size used by the last layout pass
+    layout_total: Option<Vec2>,
+}
+
+impl TagInput {
+    pub fn new(placeholder: &str) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            input: AutocompleteInput::new(placeholder),
+            tags: Vec::new(),
+            hovered_close: None,
+            on_change: None,
+            flash: None,
+            binding: None,
+            layout_total: None,
+        }
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.replace_tags(tags);
+        self
+    }
+
+    /// Suggest tags from a fixed list as the user types
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.input = self.input.with_suggestions(suggestions);
+        self
+    }
+
+    /// Keep the tags in sync with `tags`: changes made here are written to
+    /// it and changes made elsewhere are picked up on the next update
+    pub fn bind(mut self, tags: Reactive<Vec<String>>) -> Self {
+        self = self.with_tags(tags.get());
+        self.binding = Some((tags.clone(), tags.version()));
+        self
+    }
+
+    pub fn on_change(mut self, callback: impl FnMut(&[String]) + 'static) -> Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    /// Take `tags`, skipping empty ones and duplicates
+    fn replace_tags(&mut self, tags: Vec<String>) {
+        self.tags.clear();
+        self.flash = None;
+        for tag in tags {
+            if !tag.trim().is_empty() && self.find(&tag).is_none() {
+                self.tags.push(tag.trim().to_string());
+            }
+        }
+    }
+
+    /// Index of `tag` among the tags, ignoring case and surrounding space
+    fn find(&self, tag: &str) -> Option<usize> {
+        let tag = tag.trim().to_lowercase();
+        self.tags.iter().position(|existing| existing.to_lowercase() == tag)
+    }
+
+    /// Add `tag` (trimmed); returns false for an empty tag or a duplicate,
+    /// which flashes the chip already there
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return false;
+        }
+        if let Some(existing) = self.find(tag) {
+            self.flash = Some((existing, DUPLICATE_FLASH));
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        self.changed();
+        true
+    }
+
+    pub fn remove_tag(&mut self, index: usize) {
+        if index >= self.tags.len() {
+            return;
+        }
+        self.tags.remove(index);
+        self.flash = None;
+        self.hovered_close = None;
+        self.changed();
+    }
+
+    /// Report the tags to the callback and the binding
+    fn changed(&mut self) {
+        if let Some((tags, version)) = &mut self.binding {
+            tags.set(self.tags.clone());
+            *version = tags.version();
+        }
+        if let Some(callback) = &mut self.on_change {
+            callback(&self.tags);
+        }
+    }
+
+    /// Take the tags from the binding if they were changed elsewhere
+    fn sync_binding(&mut self) {
+        let Some((tags, version)) = &self.binding else {
+            return;
+        };
+        if tags.version() == *version {
+            return;
+        }
+        let (tags, version) = (tags.get(), tags.version());
+        self.replace_tags(tags);
+        if let Some((_, seen)) = &mut self.binding {
+            *seen = version;
+        }
+    }
+
+    /// Add the typed text, or suggestion `index`, and empty the field
+    fn commit(&mut self, suggestion: Option<usize>) {
+        let tag = match suggestion.and_then(|i| self.input.suggestions.get(i)) {
+            Some(suggestion) => suggestion.clone(),
+            None => self.input.get_text().to_string(),
+        };
+        self.add_tag(&tag);
+        self.input.input.set_text("");
+        self.input.close();
+    }
+
+    /// Drop suggestions that are already tags
+    fn filter_suggestions(&mut self) {
+        let tags: Vec<String> = self.tags.iter().map(|tag| tag.to_lowercase()).collect();
+        self.input.suggestions.retain(|suggestion| !tags.contains(&suggestion.to_lowercase()));
+        self.input.open &= !self.input.suggestions.is_empty();
+    }
+
+    /// Tag keys: Enter and Backspace; returns whether one was used
+    fn handle_tag_key(&mut self, key: &winit::keyboard::Key) -> bool {
+        use winit::keyboard::{Key, NamedKey};
+        match key {
+            Key::Named(NamedKey::Enter) => {
+                let highlighted = self.input.highlighted.filter(|_| self.input.open);
+                if highlighted.is_none() && self.input.get_text().trim().is_empty() {
+                    return false;
+                }
+                self.commit(highlighted);
+            }
+            Key::Named(NamedKey::Backspace) if self.input.get_text().is_empty() && !self.tags.is_empty() => {
+                self.remove_tag(self.tags.len() - 1);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Chips flowing left to right below the field, wrapping at its width
+    fn chip_rects(&self) -> Vec<Rect> {
+        let field = self.input.input.size;
+        let origin = self.input.input.position + Vec2::new(0.0, field.y + CHIP_GAP);
+        let mut cursor = Vec2::ZERO;
+        self.tags.iter().map(|tag| {
+            let width = crate::text::approximate_text_width(tag, 14.0) + 20.0 + CHIP_CLOSE_WIDTH;
+            if cursor.x > 0.0 && cursor.x + width > field.x {
+                cursor = Vec2::new(0.0, cursor.y + CHIP_HEIGHT + CHIP_GAP);
+            }
+            let rect = Rect::new(origin + cursor, Vec2::new(width, CHIP_HEIGHT));
+            cursor.x += width + CHIP_GAP;
+            rect
+        }).collect()
+    }
+
+    fn close_button(chip: &Rect) -> Rect {
+        Rect::new(Vec2::new(chip.max.x - CHIP_CLOSE_WIDTH - 2.0, chip.min.y), Vec2::new(CHIP_CLOSE_WIDTH, CHIP_HEIGHT))
+    }
+
+    /// Field plus the rows of chips below it
+    fn total_size(&self) -> Vec2 {
+        let field = self.input.input.size;
+        let bottom = self.chip_rects().iter().map(|chip| chip.max.y).fold(0.0, f32::max);
+        let height = if self.tags.is_empty() { field.y } else { bottom - self.input.input.position.y };
+        Vec2::new(field.x, height)
+    }
+}
+
+impl Widget for TagInput {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        self.input.layout_child(origin, max_size);
+        self.size = self.total_size();
+        self.layout_total = Some(self.size);
+        self.size
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        self.input.layout_child_with_constraints(constraints.loosen());
+        let total = self.total_size();
+        let size = constraints.constrain(Size::new(total.x, total.y));
+        self.size = Vec2::new(size.width, size.height);
+        self.layout_total = Some(total);
+        size
+    }
+
+    /// Chips wrapping onto another row (or off one) change the height
+    fn needs_layout(&self) -> bool {
+        self.input.needs_layout() || self.layout_total != Some(self.total_size())
+    }
+
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+        self.input.set_position(position);
+    }
+
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+
+    fn is_animating(&self) -> bool {
+        self.flash.is_some() || self.input.is_animating()
+    }
+
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        self.input.as_focusable()
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        self.input.as_focusable_mut()
+    }
+
+    fn hit_test_popup(&self, point: Vec2) -> bool {
+        self.input.hit_test_popup(point)
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
+        use winit::event::{Event, WindowEvent};
+        if self.input.input.disabled {
+            return false;
+        }
+
+        self.hovered_close = self.chip_rects().iter().position(|chip| Self::close_button(chip).contains(mouse_pos));
+        match event {
+            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                if let Some(index) = self.hovered_close {
+                    self.remove_tag(index);
+                    return true;
+                }
+                if let Some(index) = self.input.item_at(mouse_pos) {
+                    self.commit(Some(index));
+                    return true;
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event: key_event, .. }, .. }
+                if self.input.input.focused && key_event.state.is_pressed() && self.handle_tag_key(&key_event.logical_key) =>
+            {
+                return true;
+            }
+            _ => {}
+        }
+
+        let handled = self.input.handle_event(event, mouse_pos);
+
+        // A typed (or pasted) comma separates tags
+        if self.input.get_text().contains(',') {
+            let text = self.input.get_text().to_string();
+            let (done, rest) = text.rsplit_once(',').unwrap_or_default();
+            for tag in done.split(',') {
+                self.add_tag(tag);
+            }
+            self.input.input.set_text(rest.trim_start());
+            self.input.refresh();
+        }
+        self.filter_suggestions();
+        handled
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.sync_binding();
+        self.input.update(dt);
+        self.filter_suggestions();
+        if let Some((index, remaining)) = self.flash {
+            self.flash = (remaining > dt).then_some((index, remaining - dt));
+        }
+    }
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        self.input.render(renderer);
+
+        let theme = get_theme();
+        render_faded(renderer, self.input.input.disabled, None, |renderer| {
+            for (i, (tag, chip)) in self.tags.iter().zip(self.chip_rects()).enumerate() {
+                // A duplicate's chip fades back from a brighter fill
+                let flash = match self.flash {
+                    Some((index, remaining)) if index == i => remaining / DUPLICATE_FLASH,
+                    _ => 0.0,
+                };
+                let fill = Vec4::new(theme.primary.x, theme.primary.y, theme.primary.z, 0.25 + 0.45 * flash);
+                renderer.draw_rounded_rect(chip.min, chip.size(), fill, CHIP_HEIGHT / 2.0);
+                renderer.draw_text(tag, chip.min + Vec2::new(10.0, 5.0), 14.0, theme.text);
+
+                let close = Self::close_button(&chip);
+                let color = if self.hovered_close == Some(i) { theme.text } else { theme.text_secondary };
+                renderer.draw_text("×", close.min + Vec2::new(5.0, 3.0), 16.0, color);
+            }
+        });
+    }
+
+    fn debug_properties(&self, props: &mut Vec<(&'static str, String)>) {
+        self.input.debug_properties(props);
+        props.push(("tags", self.tags.join(", ")));
+    }
+
+    fn text_origin(&self) -> Option<(Vec2, f32)> {
+        self.input.text_origin()
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::keyboard::{Key, NamedKey};
+
+    #[test]
+    fn test_tags_are_added_deduplicated_and_bound() {
+        let shared = Reactive::new(vec!["urgent".to_string()]);
+        let mut tags = TagInput::new("Labels")
+            .with_suggestions(["Urgent", "Backend", "Bug"].map(String::from).to_vec())
+            .bind(shared.clone());
+        tags.input.input.focused = true;
+        assert_eq!(tags.tags, ["urgent"]);
+
+        // Existing tags aren't suggested
+        tags.input.input.set_text("u");
+        tags.input.refresh();
+        tags.filter_suggestions();
+        assert_eq!(tags.input.suggestions, ["Bug"]);
+
+        tags.input.highlight_next(true);
+        assert!(tags.handle_tag_key(&Key::Named(NamedKey::Enter)));
+        assert_eq!(shared.get(), ["urgent", "Bug"]);
+        assert_eq!(tags.input.get_text(), "");
+
+        // Duplicates flash the existing chip instead
+        tags.input.input.set_text(" URGENT ");
+        assert!(tags.handle_tag_key(&Key::Named(NamedKey::Enter)));
+        assert_eq!(tags.tags, ["urgent", "Bug"]);
+        assert_eq!(tags.flash.map(|(i, _)| i), Some(0));
+
+        // Backspace in the empty field removes the last tag
+        assert!(tags.handle_tag_key(&Key::Named(NamedKey::Backspace)));
+        assert_eq!(shared.get(), ["urgent"]);
+
+        // Changes made elsewhere come back on update
+        shared.set(vec!["ops".to_string(), "infra".to_string(), "OPS".to_string()]);
+        tags.update(0.016);
+        assert_eq!(tags.tags, ["ops", "infra"]);
+    }
+
+    #[test]
+    fn test_chips_wrap_and_remove_on_click() {
+        use crate::recorder::{RecordedButton, RecordedEvent};
+        let click = RecordedEvent::MouseInput { button: RecordedButton::Left, pressed: true }.to_event().unwrap();
+        let mut tags = TagInput::new("Labels").with_tags(["alpha", "beta", "gamma", "delta"].map(String::from).to_vec());
+        tags.layout(Vec2::ZERO, Vec2::new(200.0, 400.0));
+        tags.input.input.size.x = 200.0;
+
+        // Each chip is 7px per character plus 40px of padding and ×
+        let chips = tags.chip_rects();
+        assert_eq!(chips[0].min.x, 0.0);
+        assert_eq!(chips[1].min.x, chips[0].max.x + CHIP_GAP);
+        assert_eq!(chips[2].min, Vec2::new(0.0, chips[0].max.y + CHIP_GAP));
+
+        let close = TagInput::close_button(&chips[1]).min + Vec2::splat(5.0);
+        assert!(tags.handle_event(&click, close));
+        assert_eq!(tags.tags, ["alpha", "gamma", "delta"]);
+    }
+}
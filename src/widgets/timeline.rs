@@ -2,13 +2,16 @@
 //!
 //! Activity timeline for showing task history and events:
 //! - Vertical timeline with entries
-//! - Time-based grouping
-//! - Visual connections
+//! - Day/hour group headers
+//! - Lazy-loaded history and live appends
+//! - Filtering by entry type
 
 use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
-use crate::widgets::core::{Widget, get_theme};
+use crate::widgets::core::{Widget, get_theme, easing};
+use crate::widgets::input::SimpleDate;
+use crate::layout::{BoxConstraints, Offset, Size};
 
 // =============================================================================
 // TIMELINE ENTRY
@@ -54,6 +57,8 @@ pub struct TimelineEntry {
     pub title: String,
     pub description: String,
     pub time: String,  // e.g., "2m ago", "10:45"
+    /// Unix seconds, used for group headers
+    pub timestamp: Option<u64>,
     pub completed: bool,
 }
 
@@ -66,6 +71,7 @@ impl TimelineEntry {
             title: title.to_string(),
             description: String::new(),
             time: time.to_string(),
+            timestamp: None,
             completed: false,
         }
     }
@@ -75,6 +81,11 @@ impl TimelineEntry {
         self
     }
     
+    pub fn with_timestamp(mut self, unix_seconds: u64) -> Self {
+        self.timestamp = Some(unix_seconds);
+        self
+    }
+    
     pub fn completed(mut self) -> Self {
         self.completed = true;
         self
@@ -105,7 +116,35 @@ impl TimelineEntry {
 // TIMELINE
 // =============================================================================
 
+/// How entries with timestamps are grouped under headers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimelineGrouping {
+    #[default]
+    None,
+    Day,
+    Hour,
+}
+
+/// Laid out line of the timeline
+#[derive(Clone, Debug, PartialEq)]
+enum TimelineRow {
+    Header(String),
+    Entry(usize),
+}
+
+const HEADER_HEIGHT: f32 = 26.0;
+const CONTENT_TOP: f32 = 40.0;
+const SLIDE_IN_DURATION: f32 = 0.3;
+
+/// Fetches entries older than the given one (oldest first); empty when done
+pub type HistoryLoader = Box<dyn FnMut(Option<&TimelineEntry>) -> Vec<TimelineEntry>>;
+
 /// Vertical timeline widget
+///
+/// Entries run oldest at the top to newest at the bottom when used with
+/// `append_live` and a history loader: scrolling near the top asks the
+/// loader for older entries, and live appends slide in and keep the view
+/// pinned to the bottom if it was already there.
 pub struct Timeline {
     pub id: WidgetId,
     pub position: Vec2,
@@ -113,8 +152,19 @@ pub struct Timeline {
     pub entries: Vec<TimelineEntry>,
     pub scroll_offset: f32,
     pub max_scroll: f32,
+    pub grouping: TimelineGrouping,
+    /// Distance from the top (px) at which older entries are requested
+    pub load_threshold: f32,
     entry_height: f32,
     line_x: f32,  // X position of the vertical line
+    rows: Vec<TimelineRow>,
+    filter: Option<Vec<TimelineEntryType>>,
+    history_loader: Option<HistoryLoader>,
+    history_exhausted: bool,
+    /// Entry ids still sliding in, with animation progress (0..1)
+    appearing: Vec<(u64, f32)>,
+    /// Fixed "now" for relative day labels (system time when unset)
+    now: Option<u64>,
 }
 
 impl Timeline {
@@ -126,33 +176,200 @@ impl Timeline {
             entries: Vec::new(),
             scroll_offset: 0.0,
             max_scroll: 0.0,
+            grouping: TimelineGrouping::None,
+            load_threshold: 48.0,
             entry_height: 64.0,
             line_x: 24.0,
+            rows: Vec::new(),
+            filter: None,
+            history_loader: None,
+            history_exhausted: false,
+            appearing: Vec::new(),
+            now: None,
         }
     }
     
+    pub fn with_grouping(mut self, grouping: TimelineGrouping) -> Self {
+        self.grouping = grouping;
+        self.rebuild_rows();
+        self
+    }
+    
+    /// Load older entries on demand when scrolled near the top
+    pub fn with_history_loader<F>(mut self, loader: F) -> Self
+    where
+        F: FnMut(Option<&TimelineEntry>) -> Vec<TimelineEntry> + 'static,
+    {
+        self.history_loader = Some(Box::new(loader));
+        self.history_exhausted = false;
+        self
+    }
+    
+    /// Use a fixed unix time for "Today"/"Yesterday" headers
+    pub fn set_now(&mut self, unix_seconds: u64) {
+        self.now = Some(unix_seconds);
+        self.rebuild_rows();
+    }
+    
     /// Add an entry to the timeline
     pub fn add_entry(&mut self, entry: TimelineEntry) {
         self.entries.push(entry);
-        self.update_scroll_limits();
+        self.rebuild_rows();
     }
     
     /// Add entry at the beginning (newest first)
     pub fn prepend_entry(&mut self, entry: TimelineEntry) {
         self.entries.insert(0, entry);
-        self.update_scroll_limits();
+        self.rebuild_rows();
+    }
+    
+    /// Append a new entry at the bottom with a slide-in animation
+    /// 
+    /// Keeps following the newest entry when the view was scrolled to the
+    /// bottom.
+    pub fn append_live(&mut self, entry: TimelineEntry) {
+        let at_bottom = self.scroll_offset >= self.max_scroll - 1.0;
+        self.appearing.push((entry.id, 0.0));
+        self.entries.push(entry);
+        self.rebuild_rows();
+        if at_bottom {
+            self.scroll_offset = self.max_scroll;
+        }
     }
     
     /// Clear all entries
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.appearing.clear();
+        self.history_exhausted = false;
         self.scroll_offset = 0.0;
-        self.max_scroll = 0.0;
+        self.rebuild_rows();
+    }
+    
+    /// Only show entries of the given types
+    pub fn set_filter(&mut self, types: &[TimelineEntryType]) {
+        self.filter = Some(types.to_vec());
+        self.rebuild_rows();
+    }
+    
+    /// Show entries of every type
+    pub fn clear_filter(&mut self) {
+        self.filter = None;
+        self.rebuild_rows();
+    }
+    
+    /// Entries passing the current filter, in display order
+    pub fn visible_entries(&self) -> impl Iterator<Item = &TimelineEntry> {
+        self.rows.iter().filter_map(|row| match row {
+            TimelineRow::Entry(i) => Some(&self.entries[*i]),
+            TimelineRow::Header(_) => None,
+        })
+    }
+    
+    /// Group header labels, in display order
+    pub fn headers(&self) -> impl Iterator<Item = &str> {
+        self.rows.iter().filter_map(|row| match row {
+            TimelineRow::Header(label) => Some(label.as_str()),
+            TimelineRow::Entry(_) => None,
+        })
+    }
+    
+    /// Whether the history loader has reported there is nothing older
+    pub fn history_exhausted(&self) -> bool {
+        self.history_exhausted
+    }
+    
+    fn passes_filter(&self, entry: &TimelineEntry) -> bool {
+        self.filter.as_ref().is_none_or(|types| types.contains(&entry.entry_type))
+    }
+    
+    fn current_time(&self) -> u64 {
+        self.now.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        })
+    }
+    
+    /// Header label for the group containing `timestamp`
+    fn group_label(&self, timestamp: u64) -> String {
+        let today = self.current_time() / 86400;
+        let day = timestamp / 86400;
+        let day_label = match today.checked_sub(day) {
+            Some(0) => "Today".to_string(),
+            Some(1) => "Yesterday".to_string(),
+            _ => SimpleDate::from_unix_days(day).format(),
+        };
+        match self.grouping {
+            TimelineGrouping::Hour => format!("{} {:02}:00", day_label, timestamp % 86400 / 3600),
+            _ => day_label,
+        }
+    }
+    
+    fn group_key(&self, timestamp: u64) -> u64 {
+        match self.grouping {
+            TimelineGrouping::Hour => timestamp / 3600,
+            _ => timestamp / 86400,
+        }
+    }
+    
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::with_capacity(self.entries.len());
+        let mut last_group = None;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if !self.passes_filter(entry) {
+                continue;
+            }
+            if self.grouping != TimelineGrouping::None {
+                if let Some(timestamp) = entry.timestamp {
+                    let key = self.group_key(timestamp);
+                    if last_group != Some(key) {
+                        rows.push(TimelineRow::Header(self.group_label(timestamp)));
+                        last_group = Some(key);
+                    }
+                }
+            }
+            rows.push(TimelineRow::Entry(i));
+        }
+        self.rows = rows;
+        self.update_scroll_limits();
+    }
+    
+    fn row_height(&self, row: &TimelineRow) -> f32 {
+        match row {
+            TimelineRow::Header(_) => HEADER_HEIGHT,
+            TimelineRow::Entry(_) => self.entry_height,
+        }
+    }
+    
+    fn content_height(&self) -> f32 {
+        self.rows.iter().map(|row| self.row_height(row)).sum()
     }
     
     fn update_scroll_limits(&mut self) {
-        let content_height = self.entries.len() as f32 * self.entry_height;
-        self.max_scroll = (content_height - self.size.y + 16.0).max(0.0);
+        self.max_scroll = (self.content_height() - self.size.y + 16.0 + CONTENT_TOP).max(0.0);
+        self.scroll_offset = self.scroll_offset.clamp(0.0, self.max_scroll);
+    }
+    
+    /// Ask the loader for older entries once scrolled near the top
+    fn load_history(&mut self) {
+        if self.history_exhausted || self.scroll_offset > self.load_threshold {
+            return;
+        }
+        let Some(loader) = &mut self.history_loader else { return };
+        let older = loader(self.entries.first());
+        if older.is_empty() {
+            self.history_exhausted = true;
+            return;
+        }
+        
+        // Keep the same entries under the viewport after inserting above
+        let before = self.content_height();
+        self.entries.splice(0..0, older);
+        self.rebuild_rows();
+        self.scroll_offset += self.content_height() - before;
+        self.update_scroll_limits();
     }
     
     /// Create a sample timeline for demo
@@ -181,6 +398,26 @@ impl Widget for Timeline {
         self.update_scroll_limits();
         self.size
     }
+    
+    /// Fills the space it is given; unbounded, it keeps its current size
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.biggest_or(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        self.update_scroll_limits();
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, _mouse_pos: Vec2) -> bool {
         // Handle scroll wheel
@@ -198,7 +435,17 @@ impl Widget for Timeline {
         false
     }
 
-    fn update(&mut self, _dt: f32) {}
+    fn update(&mut self, dt: f32) {
+        for (_, progress) in &mut self.appearing {
+            *progress += dt / SLIDE_IN_DURATION;
+        }
+        self.appearing.retain(|(_, progress)| *progress < 1.0);
+        self.load_history();
+    }
+    
+    fn is_animating(&self) -> bool {
+        !self.appearing.is_empty()
+    }
 
     fn render(&self, renderer: &mut GlassRenderer) {
         let theme = get_theme();
@@ -225,24 +472,49 @@ impl Widget for Timeline {
             1.0
         );
         
-        // Entries
-        let content_start = self.position.y + 40.0;
-        for (i, entry) in self.entries.iter().enumerate() {
-            let y = content_start + i as f32 * self.entry_height - self.scroll_offset;
+        // Rows
+        let mut y = self.position.y + CONTENT_TOP - self.scroll_offset;
+        for row in &self.rows {
+            let row_height = self.row_height(row);
+            let row_y = y;
+            y += row_height;
             
             // Clip check
-            if y + self.entry_height < self.position.y + 36.0 || y > self.position.y + self.size.y {
+            if row_y + row_height < self.position.y + 36.0 || row_y > self.position.y + self.size.y {
                 continue;
             }
             
+            let entry = match row {
+                TimelineRow::Header(label) => {
+                    let pill_width = label.chars().count() as f32 * 6.5 + 16.0;
+                    renderer.draw_rounded_rect(
+                        Vec2::new(self.position.x + 40.0, row_y + 3.0),
+                        Vec2::new(pill_width, HEADER_HEIGHT - 8.0),
+                        Vec4::new(0.12, 0.12, 0.16, 0.9),
+                        (HEADER_HEIGHT - 8.0) * 0.5
+                    );
+                    renderer.draw_text(label, Vec2::new(self.position.x + 48.0, row_y + 6.0), 11.0, theme.text_secondary);
+                    continue;
+                }
+                TimelineRow::Entry(i) => &self.entries[*i],
+            };
+            
+            // Slide in from the right while fading in
+            let appear = self.appearing.iter()
+                .find(|(id, _)| *id == entry.id)
+                .map_or(1.0, |(_, progress)| easing::ease_out_cubic(*progress));
+            let slide = (1.0 - appear) * 40.0;
+            let fade = |color: Vec4| Vec4::new(color.x, color.y, color.z, color.w * appear);
+            let y = row_y;
+            
             // Dot on the line
             let dot_color = entry.entry_type.color();
             let dot_pos = Vec2::new(line_x - 5.0, y + 8.0);
-            renderer.draw_rounded_rect(dot_pos, Vec2::splat(10.0), dot_color, 5.0);
+            renderer.draw_rounded_rect(dot_pos, Vec2::splat(10.0), fade(dot_color), 5.0);
             
             // Icon
-            let icon_pos = Vec2::new(self.position.x + 40.0, y + 4.0);
-            renderer.draw_text(entry.entry_type.icon(), icon_pos, 14.0, dot_color);
+            let icon_pos = Vec2::new(self.position.x + 40.0 + slide, y + 4.0);
+            renderer.draw_text(entry.entry_type.icon(), icon_pos, 14.0, fade(dot_color));
             
             // Title
             let title_color = if entry.completed {
@@ -250,27 +522,28 @@ impl Widget for Timeline {
             } else {
                 theme.text
             };
-            renderer.draw_text(&entry.title, Vec2::new(self.position.x + 60.0, y + 4.0), 13.0, title_color);
+            let text_x = self.position.x + 60.0 + slide;
+            renderer.draw_text(&entry.title, Vec2::new(text_x, y + 4.0), 13.0, fade(title_color));
             
             // Time
-            let time_x = self.position.x + self.size.x - entry.time.len() as f32 * 6.0 - 12.0;
-            renderer.draw_text(&entry.time, Vec2::new(time_x, y + 4.0), 11.0, theme.text_secondary);
+            let time_x = self.position.x + self.size.x - entry.time.len() as f32 * 6.0 - 12.0 + slide;
+            renderer.draw_text(&entry.time, Vec2::new(time_x, y + 4.0), 11.0, fade(theme.text_secondary));
             
             // Description
             if !entry.description.is_empty() {
                 renderer.draw_text(
                     &entry.description, 
-                    Vec2::new(self.position.x + 60.0, y + 24.0), 
+                    Vec2::new(text_x, y + 24.0), 
                     11.0, 
-                    theme.text_secondary
+                    fade(theme.text_secondary)
                 );
             }
             
             // Completed strikethrough effect (just dimmer)
             if entry.completed {
-                let line_pos = Vec2::new(self.position.x + 60.0, y + 12.0);
+                let line_pos = Vec2::new(text_x, y + 12.0);
                 let line_width = entry.title.len() as f32 * 7.0;
-                renderer.draw_rounded_rect(line_pos, Vec2::new(line_width, 1.0), theme.text_secondary, 0.0);
+                renderer.draw_rounded_rect(line_pos, Vec2::new(line_width, 1.0), fade(theme.text_secondary), 0.0);
             }
         }
     }
@@ -294,6 +567,84 @@ mod tests {
         assert_eq!(timeline.entries[0].title, "Start");
     }
     
+    // 2024-03-05 14:30 UTC
+    const NOW: u64 = 1_709_649_000;
+    
+    #[test]
+    fn test_day_and_hour_headers() {
+        let mut timeline = Timeline::new().with_grouping(TimelineGrouping::Day);
+        timeline.set_now(NOW);
+        timeline.add_entry(TimelineEntry::event("Old", "").with_timestamp(NOW - 3 * 86400));
+        timeline.add_entry(TimelineEntry::event("Yesterday", "").with_timestamp(NOW - 86400));
+        timeline.add_entry(TimelineEntry::task("Morning", "").with_timestamp(NOW - 5 * 3600));
+        timeline.add_entry(TimelineEntry::task("Now", "").with_timestamp(NOW));
+        assert_eq!(timeline.headers().collect::<Vec<_>>(), vec!["2024-03-02", "Yesterday", "Today"]);
+        
+        timeline.grouping = TimelineGrouping::Hour;
+        timeline.rebuild_rows();
+        assert_eq!(timeline.headers().last(), Some("Today 14:00"));
+        assert_eq!(timeline.headers().count(), 4);
+    }
+    
+    #[test]
+    fn test_filter_by_type() {
+        let mut timeline = Timeline::sample();
+        timeline.set_filter(&[TimelineEntryType::Alert, TimelineEntryType::Milestone]);
+        let titles: Vec<_> = timeline.visible_entries().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["Version 2.0", "High CPU usage"]);
+        
+        timeline.clear_filter();
+        assert_eq!(timeline.visible_entries().count(), 5);
+    }
+    
+    #[test]
+    fn test_history_loads_when_scrolled_to_top() {
+        let mut pages = vec![
+            vec![TimelineEntry::event("Older 1", ""), TimelineEntry::event("Older 2", "")],
+        ];
+        let mut timeline = Timeline::new().with_history_loader(move |_oldest| pages.pop().unwrap_or_default());
+        for i in 0..10 {
+            timeline.add_entry(TimelineEntry::message(&format!("Message {}", i), ""));
+        }
+        timeline.layout(Vec2::ZERO, Vec2::new(300.0, 400.0));
+        timeline.scroll_offset = timeline.max_scroll;
+        timeline.update(0.016);
+        assert_eq!(timeline.entries.len(), 10);
+        
+        // Near the top: older entries go above and the view stays put
+        timeline.scroll_offset = 10.0;
+        timeline.update(0.016);
+        assert_eq!(timeline.entries[0].title, "Older 1");
+        assert_eq!(timeline.scroll_offset, 10.0 + 2.0 * 64.0);
+        
+        // Loader returned nothing: stop asking
+        timeline.scroll_offset = 0.0;
+        timeline.update(0.016);
+        assert!(timeline.history_exhausted());
+        assert_eq!(timeline.entries.len(), 12);
+    }
+    
+    #[test]
+    fn test_live_append_follows_bottom_and_animates() {
+        let mut timeline = Timeline::new();
+        for i in 0..10 {
+            timeline.add_entry(TimelineEntry::message(&format!("Message {}", i), ""));
+        }
+        timeline.layout(Vec2::ZERO, Vec2::new(300.0, 400.0));
+        timeline.scroll_offset = timeline.max_scroll;
+        
+        timeline.append_live(TimelineEntry::event("New", ""));
+        assert_eq!(timeline.scroll_offset, timeline.max_scroll);
+        assert!(timeline.is_animating());
+        timeline.update(SLIDE_IN_DURATION + 0.01);
+        assert!(!timeline.is_animating());
+        
+        // Not at the bottom: the view isn't moved
+        timeline.scroll_offset = 50.0;
+        timeline.append_live(TimelineEntry::event("Newer", ""));
+        assert_eq!(timeline.scroll_offset, 50.0);
+    }
+    
     #[test]
     fn test_entry_types() {
         let entry = TimelineEntry::milestone("Release", "today").completed();
@@ -0,0 +1,445 @@
+//! GlassUI Window Chrome
+//!
+//! Custom title bar for borderless windows:
+//! - TitleBar - draggable title region with minimize/maximize/close buttons
+//! - Edge hit-zones that hand resizing back to the OS
+//! - `borderless_window_builder` for an undecorated, translucent window
+//!
+//! The title bar only records `WindowAction`s; call `TitleBar::apply` with
+//! the winit window after routing each event to carry them out.
+
+use glam::{Vec2, Vec4};
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+use winit::window::{CursorIcon, ResizeDirection, Window, WindowBuilder};
+use crate::layout::{BoxConstraints, Offset, Rect, Size};
+use crate::renderer::GlassRenderer;
+use crate::widget_id::WidgetId;
+use crate::widgets::core::{Widget, get_theme};
+
+const BUTTON_WIDTH: f32 = 46.0;
+
+// =============================================================================
+// WINDOW ACTIONS
+// =============================================================================
+
+/// Something the title bar wants the window to do
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowAction {
+    /// Start an OS window move (title region pressed)
+    Drag,
+    /// Start an OS resize from an edge or corner
+    Resize(ResizeDirection),
+    Minimize,
+    ToggleMaximize,
+    Close,
+    /// Show the system window menu at a position (right-click on the bar)
+    ShowMenu(Vec2),
+}
+
+/// Window edge or corner under `point`, if within `border` px of it
+pub fn resize_direction_at(point: Vec2, window_size: Vec2, border: f32) -> Option<ResizeDirection> {
+    if point.x < 0.0 || point.y < 0.0 || point.x > window_size.x || point.y > window_size.y {
+        return None;
+    }
+    let west = point.x < border;
+    let east = point.x > window_size.x - border;
+    let north = point.y < border;
+    let south = point.y > window_size.y - border;
+    match (north, south, west, east) {
+        (true, _, true, _) => Some(ResizeDirection::NorthWest),
+        (true, _, _, true) => Some(ResizeDirection::NorthEast),
+        (_, true, true, _) => Some(ResizeDirection::SouthWest),
+        (_, true, _, true) => Some(ResizeDirection::SouthEast),
+        (true, ..) => Some(ResizeDirection::North),
+        (_, true, ..) => Some(ResizeDirection::South),
+        (_, _, true, _) => Some(ResizeDirection::West),
+        (_, _, _, true) => Some(ResizeDirection::East),
+        _ => None,
+    }
+}
+
+/// Window builder for a borderless, translucent window with system blur
+///
+/// Pair with a `TitleBar` and `GlassRenderer::set_background_opacity` so the
+/// whole app reads as one glass surface. System blur is honoured where winit
+/// supports it (macOS, KDE Wayland); elsewhere the window is just translucent.
+pub fn borderless_window_builder(title: &str) -> WindowBuilder {
+    WindowBuilder::new()
+        .with_title(title)
+        .with_decorations(false)
+        .with_transparent(true)
+        .with_blur(true)
+}
+
+// =============================================================================
+// TITLE BAR
+// =============================================================================
+
+/// Caption button on the right of the title bar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TitleButton {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+impl TitleButton {
+    fn action(&self) -> WindowAction {
+        match self {
+            TitleButton::Minimize => WindowAction::Minimize,
+            TitleButton::Maximize => WindowAction::ToggleMaximize,
+            TitleButton::Close => WindowAction::Close,
+        }
+    }
+}
+
+/// Title bar for borderless windows
+///
+/// Lay it out with the window size (it takes the full width and `height`)
+/// and give it events before the rest of the UI: it claims presses on the
+/// title region, its buttons and the window edges.
+pub struct TitleBar {
+    pub id: WidgetId,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub title: String,
+    pub icon: Option<String>,
+    pub height: f32,
+    /// Width of the edge zones that resize the window
+    pub resize_border: f32,
+    pub show_minimize: bool,
+    pub show_maximize: bool,
+    pub maximized: bool,
+    window_size: Vec2,
+    hovered_button: Option<TitleButton>,
+    pressed_button: Option<TitleButton>,
+    hovered_edge: Option<ResizeDirection>,
+    applied_cursor: Option<CursorIcon>,
+    actions: Vec<WindowAction>,
+}
+
+impl TitleBar {
+    pub fn new(title: &str) -> Self {
+        Self {
+            id: WidgetId::new(),
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+            title: title.to_string(),
+            icon: None,
+            height: 36.0,
+            resize_border: 6.0,
+            show_minimize: true,
+            show_maximize: true,
+            maximized: false,
+            window_size: Vec2::ZERO,
+            hovered_button: None,
+            pressed_button: None,
+            hovered_edge: None,
+            applied_cursor: None,
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn with_icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn with_resize_border(mut self, border: f32) -> Self {
+        self.resize_border = border;
+        self
+    }
+
+    /// Hide the minimize and maximize buttons (e.g. for tool windows)
+    pub fn close_only(mut self) -> Self {
+        self.show_minimize = false;
+        self.show_maximize = false;
+        self
+    }
+
+    /// Take the actions recorded since the last call
+    pub fn take_actions(&mut self) -> Vec<WindowAction> {
+        std::mem::take(&mut self.actions)
+    }
+
+    /// Cursor to show for the current pointer position
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.hovered_edge.map_or(CursorIcon::Default, CursorIcon::from)
+    }
+
+    /// Carry out pending actions on `window`
+    ///
+    /// Returns true when the close button was pressed; closing is left to
+    /// the app (usually by exiting the event loop).
+    pub fn apply(&mut self, window: &Window) -> bool {
+        let mut close = false;
+        for action in self.take_actions() {
+            // Drag/resize fail on platforms without support; nothing to do then
+            match action {
+                WindowAction::Drag => { let _ = window.drag_window(); }
+                WindowAction::Resize(direction) => { let _ = window.drag_resize_window(direction); }
+                WindowAction::Minimize => window.set_minimized(true),
+                WindowAction::ToggleMaximize => window.set_maximized(!window.is_maximized()),
+                WindowAction::Close => close = true,
+                WindowAction::ShowMenu(pos) => window.show_window_menu(winit::dpi::LogicalPosition::new(pos.x, pos.y)),
+            }
+        }
+        self.maximized = window.is_maximized();
+
+        let cursor = self.cursor_icon();
+        if self.applied_cursor != Some(cursor) {
+            window.set_cursor_icon(cursor);
+            self.applied_cursor = Some(cursor);
+        }
+        close
+    }
+
+    fn buttons(&self) -> impl Iterator<Item = TitleButton> {
+        [
+            self.show_minimize.then_some(TitleButton::Minimize),
+            self.show_maximize.then_some(TitleButton::Maximize),
+            Some(TitleButton::Close),
+        ].into_iter().flatten()
+    }
+
+    fn button_rect(&self, button: TitleButton) -> Rect {
+        let index_from_right = self.buttons().collect::<Vec<_>>().iter().rev().position(|b| *b == button).unwrap_or(0);
+        let x = self.position.x + self.size.x - (index_from_right + 1) as f32 * BUTTON_WIDTH;
+        Rect::new(Vec2::new(x, self.position.y), Vec2::new(BUTTON_WIDTH, self.size.y))
+    }
+
+    fn button_at(&self, point: Vec2) -> Option<TitleButton> {
+        self.buttons().find(|b| self.button_rect(*b).contains(point))
+    }
+
+    fn edge_at(&self, point: Vec2) -> Option<ResizeDirection> {
+        if self.maximized || self.resize_border <= 0.0 {
+            return None;
+        }
+        resize_direction_at(point, self.window_size, self.resize_border)
+    }
+}
+
+impl Widget for TitleBar {
+    fn layout(&mut self, origin: Vec2, max_size: Vec2) -> Vec2 {
+        self.position = origin;
+        self.window_size = origin + max_size;
+        self.size = Vec2::new(max_size.x, self.height);
+        self.size
+    }
+
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        // The window edges stay relative to where the bar sits
+        self.window_size = self.position + Vec2::new(constraints.max_width, constraints.max_height);
+        let size = Size::new(constraints.biggest_or(Size::new(self.size.x, self.height)).width, constraints.constrain_height(self.height));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+
+    fn set_position(&mut self, position: Offset) {
+        let position = Vec2::new(position.x, position.y);
+        self.window_size += position - self.position;
+        self.position = position;
+    }
+
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+
+    fn handle_event(&mut self, event: &Event<()>, mouse_pos: Vec2) -> bool {
+        let Event::WindowEvent { event, .. } = event else { return false };
+        let over_bar = Rect::new(self.position, self.size).contains(mouse_pos);
+
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                self.hovered_edge = self.edge_at(mouse_pos);
+                self.hovered_button = if self.hovered_edge.is_none() { self.button_at(mouse_pos) } else { None };
+                false
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.hovered_edge = None;
+                self.hovered_button = None;
+                false
+            }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                if let Some(direction) = self.edge_at(mouse_pos) {
+                    self.actions.push(WindowAction::Resize(direction));
+                    return true;
+                }
+                if let Some(button) = self.button_at(mouse_pos) {
+                    self.pressed_button = Some(button);
+                    return true;
+                }
+                if !over_bar {
+                    return false;
+                }
+                self.actions.push(WindowAction::Drag);
+                true
+            }
+            WindowEvent::MouseInput { state: ElementState::Released, button: MouseButton::Left, .. } => {
+                // Buttons fire on release over the button that was pressed
+                match self.pressed_button.take() {
+                    Some(button) => {
+                        if self.button_at(mouse_pos) == Some(button) {
+                            self.actions.push(button.action());
+                        }
+                        true
+                    }
+                    None => false,
+                }
+            }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Right, .. } if over_bar => {
+                self.actions.push(WindowAction::ShowMenu(mouse_pos));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Double-clicking the title maximizes or restores the window
+    fn handle_multi_click(&mut self, clicks: u32, mouse_pos: Vec2) -> bool {
+        let on_title = Rect::new(self.position, self.size).contains(mouse_pos)
+            && self.edge_at(mouse_pos).is_none()
+            && self.button_at(mouse_pos).is_none();
+        if clicks != 2 || !on_title || !self.show_maximize {
+            return false;
+        }
+        // The press before this started a drag; maximize instead
+        if self.actions.last() == Some(&WindowAction::Drag) {
+            self.actions.pop();
+        }
+        self.actions.push(WindowAction::ToggleMaximize);
+        true
+    }
+
+    fn update(&mut self, _dt: f32) {}
+
+    fn render(&self, renderer: &mut GlassRenderer) {
+        let theme = get_theme();
+
+        // Bar with a hairline separating it from the content
+        renderer.draw_rect(self.position, self.size, Vec4::new(0.05, 0.05, 0.08, 0.6));
+        renderer.draw_rect(
+            Vec2::new(self.position.x, self.position.y + self.size.y - 1.0),
+            Vec2::new(self.size.x, 1.0),
+            Vec4::new(theme.border.x, theme.border.y, theme.border.z, 0.3)
+        );
+
+        // Icon and title
+        let text_y = self.position.y + (self.size.y - 14.0) * 0.5;
+        let mut x = self.position.x + 12.0;
+        if let Some(icon) = &self.icon {
+            renderer.draw_text(icon, Vec2::new(x, text_y - 1.0), 16.0, theme.primary);
+            x += 24.0;
+        }
+        renderer.draw_text(&self.title, Vec2::new(x, text_y), 14.0, theme.text);
+
+        // Caption buttons
+        for button in self.buttons() {
+            let rect = self.button_rect(button);
+            let hovered = self.hovered_button == Some(button);
+            let pressed = self.pressed_button == Some(button);
+            if hovered || pressed {
+                let color = match button {
+                    TitleButton::Close => Vec4::new(0.85, 0.2, 0.2, if pressed { 0.7 } else { 0.9 }),
+                    _ => Vec4::new(1.0, 1.0, 1.0, if pressed { 0.16 } else { 0.1 }),
+                };
+                renderer.draw_rect(rect.min, rect.size(), color);
+            }
+            let glyph = match button {
+                TitleButton::Minimize => "─",
+                TitleButton::Maximize if self.maximized => "❐",
+                TitleButton::Maximize => "□",
+                TitleButton::Close => "✕",
+            };
+            renderer.draw_text(glyph, Vec2::new(rect.min.x + 17.0, text_y), 14.0, theme.text);
+        }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mouse(bar: &mut TitleBar, state: ElementState, pos: Vec2) -> bool {
+        bar.handle_event(&Event::WindowEvent {
+            window_id: unsafe { winit::window::WindowId::dummy() },
+            event: WindowEvent::MouseInput {
+                device_id: unsafe { winit::event::DeviceId::dummy() },
+                state,
+                button: MouseButton::Left,
+            },
+        }, pos)
+    }
+
+    fn title_bar() -> TitleBar {
+        let mut bar = TitleBar::new("App");
+        bar.layout(Vec2::ZERO, Vec2::new(800.0, 600.0));
+        bar
+    }
+
+    #[test]
+    fn test_resize_zones() {
+        let size = Vec2::new(800.0, 600.0);
+        assert_eq!(resize_direction_at(Vec2::new(2.0, 2.0), size, 6.0), Some(ResizeDirection::NorthWest));
+        assert_eq!(resize_direction_at(Vec2::new(798.0, 598.0), size, 6.0), Some(ResizeDirection::SouthEast));
+        assert_eq!(resize_direction_at(Vec2::new(400.0, 599.0), size, 6.0), Some(ResizeDirection::South));
+        assert_eq!(resize_direction_at(Vec2::new(1.0, 300.0), size, 6.0), Some(ResizeDirection::West));
+        assert_eq!(resize_direction_at(Vec2::new(400.0, 300.0), size, 6.0), None);
+
+        // Maximized windows can't be resized from the edges
+        let mut bar = title_bar();
+        assert!(mouse(&mut bar, ElementState::Pressed, Vec2::new(400.0, 2.0)));
+        assert_eq!(bar.take_actions(), vec![WindowAction::Resize(ResizeDirection::North)]);
+        bar.maximized = true;
+        mouse(&mut bar, ElementState::Pressed, Vec2::new(400.0, 2.0));
+        assert_eq!(bar.take_actions(), vec![WindowAction::Drag]);
+    }
+
+    #[test]
+    fn test_title_drag_and_double_click() {
+        let mut bar = title_bar();
+        assert!(mouse(&mut bar, ElementState::Pressed, Vec2::new(300.0, 18.0)));
+        mouse(&mut bar, ElementState::Pressed, Vec2::new(300.0, 18.0));
+        assert!(bar.handle_multi_click(2, Vec2::new(300.0, 18.0)));
+        mouse(&mut bar, ElementState::Pressed, Vec2::new(300.0, 18.0));
+        assert!(!bar.handle_multi_click(2, bar.button_rect(TitleButton::Close).min + Vec2::splat(4.0)));
+        assert_eq!(bar.take_actions(), vec![WindowAction::Drag, WindowAction::ToggleMaximize, WindowAction::Drag]);
+
+        // Presses below the bar are left to the content
+        assert!(!mouse(&mut bar, ElementState::Pressed, Vec2::new(300.0, 200.0)));
+    }
+
+    #[test]
+    fn test_caption_buttons_fire_on_release() {
+        let mut bar = title_bar();
+        let close = bar.button_rect(TitleButton::Close);
+        let minimize = bar.button_rect(TitleButton::Minimize);
+        assert_eq!(close.max.x, 800.0);
+        assert!(minimize.max.x <= bar.button_rect(TitleButton::Maximize).min.x);
+
+        let center = (close.min + close.max) * 0.5;
+        mouse(&mut bar, ElementState::Pressed, center);
+        assert!(bar.take_actions().is_empty());
+        mouse(&mut bar, ElementState::Released, center);
+        assert_eq!(bar.take_actions(), vec![WindowAction::Close]);
+
+        // Releasing elsewhere cancels
+        mouse(&mut bar, ElementState::Pressed, (minimize.min + minimize.max) * 0.5);
+        mouse(&mut bar, ElementState::Released, Vec2::new(300.0, 18.0));
+        assert!(bar.take_actions().is_empty());
+    }
+}
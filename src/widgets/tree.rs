@@ -9,6 +9,9 @@ use glam::{Vec2, Vec4};
 use crate::renderer::GlassRenderer;
 use crate::widget_id::WidgetId;
 use crate::widgets::core::{Widget, get_theme};
+use crate::layout::{BoxConstraints, Offset, Size};
+
+type NodeCallback = Box<dyn FnMut(&str)>;
 
 // =============================================================================
 // TREE NODE
@@ -86,7 +89,9 @@ pub struct FileTree {
     pub indent_width: f32,
     pub row_height: f32,
     pub scroll_offset: f32,
-    pub on_select: Option<Box<dyn FnMut(&str)>>,
+    pub on_select: Option<NodeCallback>,
+    /// Called with a node's id when it is double-clicked (open, rename...)
+    pub on_activate: Option<NodeCallback>,
 }
 
 impl FileTree {
@@ -102,9 +107,22 @@ impl FileTree {
             row_height: 28.0,
             scroll_offset: 0.0,
             on_select: None,
+            on_activate: None,
         }
     }
     
+    /// Set callback for node clicks (node id)
+    pub fn on_select(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+    
+    /// Set callback for node double-clicks (node id)
+    pub fn on_activate(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.on_activate = Some(Box::new(callback));
+        self
+    }
+    
     /// Add a root node
     pub fn add_node(&mut self, node: FileNode) {
         self.nodes.push(node);
@@ -201,6 +219,43 @@ impl Widget for FileTree {
         self.size = max_size;
         self.size
     }
+    
+    /// Fills the space it is given; unbounded, it keeps its current size
+    fn layout_with_constraints(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.biggest_or(Size::new(self.size.x, self.size.y));
+        self.size = Vec2::new(size.width, size.height);
+        size
+    }
+    
+    fn set_position(&mut self, position: Offset) {
+        self.position = Vec2::new(position.x, position.y);
+    }
+    
+    fn get_position(&self) -> Offset {
+        Offset::new(self.position.x, self.position.y)
+    }
+    
+    fn get_size(&self) -> Size {
+        Size::new(self.size.x, self.size.y)
+    }
+    
+    /// Only the first pass matters: the size follows the space it is given
+    fn needs_layout(&self) -> bool {
+        self.size == Vec2::ZERO
+    }
+
+    fn handle_multi_click(&mut self, clicks: u32, mouse_pos: Vec2) -> bool {
+        if clicks != 2 || !self.hit_test(mouse_pos) {
+            return false;
+        }
+        match (self.node_at_y(mouse_pos.y), &mut self.on_activate) {
+            (Some((id, _)), Some(callback)) => {
+                callback(&id);
+                true
+            }
+            _ => false,
+        }
+    }
 
     fn handle_event(&mut self, event: &winit::event::Event<()>, mouse_pos: Vec2) -> bool {
         // Update hover